@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use rusty_money::iso::Currency;
 use thiserror::Error;
 
 use crate::{
@@ -11,6 +12,8 @@ use crate::{
         carts::{CartsService, PgCartsService},
         products::{PgProductsService, ProductsService},
         promotions::service::{PgPromotionsService, PromotionsService},
+        quotes::{PgQuotesService, QuotesService},
+        stacks::{PgStacksService, StacksService},
     },
 };
 
@@ -25,18 +28,23 @@ pub struct AppContext {
     pub carts: Arc<dyn CartsService>,
     pub products: Arc<dyn ProductsService>,
     pub promotions: Arc<dyn PromotionsService>,
+    pub quotes: Arc<dyn QuotesService>,
+    pub stacks: Arc<dyn StacksService>,
     pub auth: Arc<dyn AuthService>,
 }
 
 impl AppContext {
     /// Build application context from a database URL.
     ///
+    /// Carts and quotes are priced in `currency`.
+    ///
     /// # Errors
     ///
     /// Returns an error when establishing a database connection fails.
     pub async fn from_database_url(
         url: &str,
         openbao: OpenBaoClient,
+        currency: &'static Currency,
     ) -> Result<Self, AppInitError> {
         let pool = database::connect(url)
             .await
@@ -49,9 +57,11 @@ impl AppContext {
         let db = Db::new(pool.clone());
 
         Ok(Self {
-            carts: Arc::new(PgCartsService::new(db.clone())),
+            carts: Arc::new(PgCartsService::new(db.clone(), currency)),
             products: Arc::new(PgProductsService::new(db.clone())),
-            promotions: Arc::new(PgPromotionsService::new(db)),
+            promotions: Arc::new(PgPromotionsService::new(db.clone())),
+            quotes: Arc::new(PgQuotesService::new(db.clone(), currency)),
+            stacks: Arc::new(PgStacksService::new(db, currency)),
             auth: Arc::new(PgAuthService::new(pool, openbao)),
         })
     }
@@ -6,6 +6,8 @@ use sqlx::{
 };
 use thiserror::Error;
 
+use crate::domain::pricing::PricingError;
+
 #[derive(Debug, Error)]
 pub enum CartsServiceError {
     #[error("cart already exists")]
@@ -25,6 +27,15 @@ pub enum CartsServiceError {
 
     #[error("storage error")]
     Sql(#[source] Error),
+
+    #[error("pricing error")]
+    Pricing(#[source] PricingError),
+}
+
+impl From<PricingError> for CartsServiceError {
+    fn from(error: PricingError) -> Self {
+        Self::Pricing(error)
+    }
 }
 
 impl From<Error> for CartsServiceError {
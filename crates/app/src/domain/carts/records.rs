@@ -2,7 +2,10 @@
 
 use jiff::Timestamp;
 
-use crate::{domain::products::records::ProductUuid, uuids::TypedUuid};
+use crate::{
+    domain::{pricing::data::Redemption, products::records::ProductUuid},
+    uuids::TypedUuid,
+};
 
 /// Cart UUID
 pub type CartUuid = TypedUuid<CartRecord>;
@@ -28,6 +31,7 @@ pub struct CartItemRecord {
     pub uuid: CartItemUuid,
     pub price: u64,
     pub product_uuid: ProductUuid,
+    pub redemptions: Vec<Redemption>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
     pub deleted_at: Option<Timestamp>,
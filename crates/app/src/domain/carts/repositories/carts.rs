@@ -10,6 +10,7 @@ use crate::domain::carts::records::{CartRecord, CartUuid};
 const GET_CART_SQL: &str = include_str!("../sql/get_cart.sql");
 const CREATE_CART_SQL: &str = include_str!("../sql/create_cart.sql");
 const DELETE_CART_SQL: &str = include_str!("../sql/delete_cart.sql");
+const UPDATE_CART_TOTALS_SQL: &str = include_str!("../sql/update_cart_totals.sql");
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct PgCartsRepository;
@@ -85,6 +86,32 @@ impl PgCartsRepository {
 
         Ok(rows_affected)
     }
+
+    #[tracing::instrument(
+        name = "carts.repository.update_cart_totals",
+        skip(self, tx),
+        fields(cart_uuid = %cart, subtotal, total),
+        err
+    )]
+    pub(crate) async fn update_cart_totals(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        cart: CartUuid,
+        subtotal: u64,
+        total: u64,
+    ) -> Result<u64, sqlx::Error> {
+        let rows_affected = query(UPDATE_CART_TOTALS_SQL)
+            .bind(cart.into_uuid())
+            .bind(try_amount_to_i64(subtotal, "subtotal")?)
+            .bind(try_amount_to_i64(total, "total")?)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        debug!(cart_uuid = %cart, subtotal, total, rows_affected, "updated cart totals");
+
+        Ok(rows_affected)
+    }
 }
 
 impl<'r> FromRow<'r, PgRow> for CartRecord {
@@ -116,3 +143,10 @@ pub(super) fn try_get_amount(row: &PgRow, col: &str) -> Result<u64, sqlx::Error>
         source: Box::new(e),
     })
 }
+
+fn try_amount_to_i64(amount: u64, col: &str) -> Result<i64, sqlx::Error> {
+    i64::try_from(amount).map_err(|e| sqlx::Error::ColumnDecode {
+        index: col.to_string(),
+        source: Box::new(e),
+    })
+}
@@ -118,6 +118,7 @@ impl<'r> FromRow<'r, PgRow> for CartItemRecord {
             uuid: CartItemUuid::from_uuid(row.try_get("uuid")?),
             price,
             product_uuid: ProductUuid::from_uuid(row.try_get("product_uuid")?),
+            redemptions: Vec::new(),
             created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
             updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
             deleted_at: row
@@ -3,8 +3,11 @@
 use async_trait::async_trait;
 use jiff::Timestamp;
 use mockall::automock;
+use rusty_money::iso::Currency;
 use tracing::{info, warn};
 
+use sqlx::{Postgres, Transaction};
+
 use crate::{
     database::Db,
     domain::{
@@ -14,6 +17,14 @@ use crate::{
             records::{CartItemRecord, CartItemUuid, CartRecord, CartUuid},
             repositories::{PgCartItemsRepository, PgCartsRepository},
         },
+        pricing::{
+            data::{PricingItem, PricingResult},
+            price_items,
+        },
+        products::records::ProductUuid,
+        promotions::PgPromotionsRepository,
+        stacks::PgStacksRepository,
+        tags::PgTagsRepository,
         tenants::records::TenantUuid,
     },
 };
@@ -21,18 +32,123 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct PgCartsService {
     db: Db,
+    currency: &'static Currency,
     carts: PgCartsRepository,
     items: PgCartItemsRepository,
+    promotions: PgPromotionsRepository,
+    stacks: PgStacksRepository,
+    tags: PgTagsRepository,
 }
 
 impl PgCartsService {
     #[must_use]
-    pub fn new(db: Db) -> Self {
+    pub fn new(db: Db, currency: &'static Currency) -> Self {
         Self {
             db,
+            currency,
             carts: PgCartsRepository::new(),
             items: PgCartItemsRepository::new(),
+            promotions: PgPromotionsRepository::new(),
+            stacks: PgStacksRepository::new(),
+            tags: PgTagsRepository::new(),
+        }
+    }
+
+    /// Price the cart's items against the promotions active at `point_in_time`,
+    /// layered by the tenant's stack at that time when it has one.
+    ///
+    /// Returns the cart items with their redemptions attached, alongside the
+    /// pricing result they were taken from.
+    #[tracing::instrument(
+        name = "carts.service.price_cart",
+        skip(self, tx),
+        fields(cart_uuid = %cart, point_in_time = %point_in_time),
+        err
+    )]
+    async fn price_cart(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        cart: CartUuid,
+        point_in_time: Timestamp,
+    ) -> Result<(Vec<CartItemRecord>, PricingResult), CartsServiceError> {
+        let mut items = self.items.get_cart_items(tx, cart, point_in_time).await?;
+
+        let mut product_uuids: Vec<ProductUuid> =
+            items.iter().map(|item| item.product_uuid).collect();
+
+        product_uuids.sort_unstable();
+        product_uuids.dedup();
+
+        let product_tags = self
+            .tags
+            .list_taggables_tag_names(tx, &product_uuids)
+            .await?;
+
+        let promotions = self
+            .promotions
+            .list_active_promotions(tx, point_in_time)
+            .await?;
+
+        let stack = self.stacks.find_active_stack(tx, point_in_time).await?;
+
+        let pricing_items: Vec<PricingItem> = items
+            .iter()
+            .map(|item| PricingItem {
+                price: item.price,
+                tags: product_tags
+                    .get(&item.product_uuid.into_uuid())
+                    .cloned()
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let mut pricing = price_items(
+            &pricing_items,
+            &promotions,
+            stack.as_ref().map(|stack| &stack.graph),
+            self.currency,
+        )?;
+
+        for (item, redemptions) in items.iter_mut().zip(&mut pricing.redemptions) {
+            item.redemptions.extend(redemptions.drain(..));
+        }
+
+        Ok((items, pricing))
+    }
+
+    /// Re-price the cart as of now and persist its subtotal and total.
+    #[tracing::instrument(
+        name = "carts.service.reprice_cart",
+        skip(self, tx),
+        fields(cart_uuid = %cart),
+        err
+    )]
+    async fn reprice_cart(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        cart: CartUuid,
+    ) -> Result<(), CartsServiceError> {
+        let (_, pricing) = self.price_cart(tx, cart, Timestamp::now()).await?;
+
+        let rows_affected = self
+            .carts
+            .update_cart_totals(tx, cart, pricing.subtotal, pricing.total)
+            .await?;
+
+        if rows_affected == 0 {
+            warn!("cart did not exist for repricing");
+
+            return Err(CartsServiceError::NotFound);
         }
+
+        info!(
+            cart_uuid = %cart,
+            subtotal = pricing.subtotal,
+            total = pricing.total,
+            "repriced cart"
+        );
+
+        Ok(())
     }
 }
 
@@ -56,16 +172,15 @@ impl CartsService for PgCartsService {
     ) -> Result<CartRecord, CartsServiceError> {
         let mut tx = self.db.begin_tenant_transaction(tenant).await?;
 
-        let items = self
-            .items
-            .get_cart_items(&mut tx, cart, point_in_time)
-            .await?;
+        let (items, pricing) = self.price_cart(&mut tx, cart, point_in_time).await?;
 
         let mut cart = self.carts.get_cart(&mut tx, cart, point_in_time).await?;
 
         tx.commit().await?;
 
         let item_count = items.len();
+        cart.subtotal = pricing.subtotal;
+        cart.total = pricing.total;
         cart.items.extend(items);
         info!(cart_uuid = %cart.uuid, item_count, "fetched cart");
 
@@ -142,6 +257,8 @@ impl CartsService for PgCartsService {
 
         let item = self.items.create_cart_item(&mut tx, cart, item).await?;
 
+        self.reprice_cart(&mut tx, cart).await?;
+
         tx.commit().await?;
 
         info!(
@@ -179,6 +296,8 @@ impl CartsService for PgCartsService {
             return Err(CartsServiceError::NotFound);
         }
 
+        self.reprice_cart(&mut tx, cart).await?;
+
         tx.commit().await?;
 
         info!(cart_uuid = %cart, item_uuid = %item, rows_affected, "removed cart item");
@@ -190,7 +309,7 @@ impl CartsService for PgCartsService {
 #[automock]
 #[async_trait]
 pub trait CartsService: Send + Sync {
-    /// Retrieve a single cart.
+    /// Retrieve a single cart, priced against the promotions active at the given point in time.
     async fn get_cart(
         &self,
         tenant: TenantUuid,
@@ -236,10 +355,21 @@ mod tests {
     use testresult::TestResult;
 
     use crate::{
-        domain::{carts::data::NewCart, products::records::ProductUuid},
+        domain::{
+            carts::data::NewCart,
+            products::records::ProductUuid,
+            promotions::records::PromotionUuid,
+            stacks::{
+                data::{LayerOutput, StackGraph, StackLayer},
+                records::StackUuid,
+            },
+        },
         test::{
             TestContext,
-            helpers::{add_item, create_cart, create_product, get_cart, remove_item},
+            helpers::{
+                add_item, create_cart, create_direct_discount_promotion, create_product,
+                create_stack, get_cart, remove_item,
+            },
         },
     };
 
@@ -371,6 +501,186 @@ mod tests {
             Ok(())
         }
 
+        #[tokio::test]
+        async fn get_cart_returns_item_redemptions() -> TestResult {
+            let ctx = TestContext::new().await;
+            let cart_uuid = CartUuid::new();
+            let promotion_uuid = PromotionUuid::new();
+
+            let product = create_product(
+                &ctx,
+                ctx.tenant_uuid,
+                ProductUuid::new(),
+                10_00,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            create_direct_discount_promotion(
+                &ctx,
+                ctx.tenant_uuid,
+                promotion_uuid,
+                25,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            create_cart(&ctx, ctx.tenant_uuid, cart_uuid).await?;
+
+            add_item(
+                &ctx,
+                ctx.tenant_uuid,
+                cart_uuid,
+                product.uuid,
+                CartItemUuid::new(),
+            )
+            .await?;
+
+            let cart = get_cart(&ctx, ctx.tenant_uuid, cart_uuid, Timestamp::now()).await?;
+
+            assert_eq!(cart.subtotal, 10_00);
+            assert_eq!(cart.total, 7_50);
+
+            let Some(item) = cart.items.first() else {
+                panic!("expected item, got None");
+            };
+
+            let Some(redemption) = item.redemptions.first() else {
+                panic!("expected redemption, got None");
+            };
+
+            assert_eq!(redemption.promotion_uuid, promotion_uuid);
+            assert_eq!(redemption.original_price, 10_00);
+            assert_eq!(redemption.final_price, 7_50);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn get_cart_stacks_discounts_across_layers() -> TestResult {
+            let ctx = TestContext::new().await;
+            let cart_uuid = CartUuid::new();
+            let first = PromotionUuid::new();
+            let second = PromotionUuid::new();
+
+            let product = create_product(
+                &ctx,
+                ctx.tenant_uuid,
+                ProductUuid::new(),
+                10_00,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            for promotion in [first, second] {
+                create_direct_discount_promotion(
+                    &ctx,
+                    ctx.tenant_uuid,
+                    promotion,
+                    50,
+                    smallvec!["sale".to_string()],
+                )
+                .await?;
+            }
+
+            create_cart(&ctx, ctx.tenant_uuid, cart_uuid).await?;
+
+            add_item(
+                &ctx,
+                ctx.tenant_uuid,
+                cart_uuid,
+                product.uuid,
+                CartItemUuid::new(),
+            )
+            .await?;
+
+            let before_stack = Timestamp::now();
+
+            create_stack(
+                &ctx,
+                ctx.tenant_uuid,
+                StackUuid::new(),
+                StackGraph {
+                    root: "first".to_string(),
+                    layers: vec![
+                        StackLayer {
+                            name: "first".to_string(),
+                            promotions: vec![first],
+                            output: LayerOutput::PassThrough {
+                                next: Some("second".to_string()),
+                            },
+                        },
+                        StackLayer {
+                            name: "second".to_string(),
+                            promotions: vec![second],
+                            output: LayerOutput::PassThrough { next: None },
+                        },
+                    ],
+                },
+            )
+            .await?;
+
+            let unstacked = get_cart(&ctx, ctx.tenant_uuid, cart_uuid, before_stack).await?;
+
+            assert_eq!(unstacked.total, 5_00);
+
+            let stacked = get_cart(&ctx, ctx.tenant_uuid, cart_uuid, Timestamp::now()).await?;
+
+            assert_eq!(stacked.total, 2_50);
+
+            let Some(item) = stacked.items.first() else {
+                panic!("expected item, got None");
+            };
+
+            assert_eq!(item.redemptions.len(), 2);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn get_cart_ignores_promotions_created_after_point_in_time() -> TestResult {
+            let ctx = TestContext::new().await;
+            let cart_uuid = CartUuid::new();
+
+            let product = create_product(
+                &ctx,
+                ctx.tenant_uuid,
+                ProductUuid::new(),
+                10_00,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            create_cart(&ctx, ctx.tenant_uuid, cart_uuid).await?;
+
+            add_item(
+                &ctx,
+                ctx.tenant_uuid,
+                cart_uuid,
+                product.uuid,
+                CartItemUuid::new(),
+            )
+            .await?;
+
+            let before_promotion = Timestamp::now();
+
+            create_direct_discount_promotion(
+                &ctx,
+                ctx.tenant_uuid,
+                PromotionUuid::new(),
+                25,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            let cart = get_cart(&ctx, ctx.tenant_uuid, cart_uuid, before_promotion).await?;
+
+            assert_eq!(cart.total, 10_00);
+            assert!(cart.items.iter().all(|item| item.redemptions.is_empty()));
+
+            Ok(())
+        }
+
         #[tokio::test]
         async fn get_cart_unknown_uuid_returns_not_found() {
             let ctx = TestContext::new().await;
@@ -456,6 +766,51 @@ mod tests {
             Ok(())
         }
 
+        #[tokio::test]
+        async fn adding_item_persists_discounted_totals() -> TestResult {
+            let ctx = TestContext::new().await;
+
+            let product = create_product(
+                &ctx,
+                ctx.tenant_uuid,
+                ProductUuid::new(),
+                10_00,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            create_direct_discount_promotion(
+                &ctx,
+                ctx.tenant_uuid,
+                PromotionUuid::new(),
+                50,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            let cart = create_cart(&ctx, ctx.tenant_uuid, CartUuid::new()).await?;
+
+            add_item(
+                &ctx,
+                ctx.tenant_uuid,
+                cart.uuid,
+                product.uuid,
+                CartItemUuid::new(),
+            )
+            .await?;
+
+            let (subtotal, total): (i64, i64) =
+                sqlx::query_as("SELECT subtotal, total FROM carts WHERE uuid = $1")
+                    .bind(cart.uuid.into_uuid())
+                    .fetch_one(ctx.db.pool())
+                    .await?;
+
+            assert_eq!(subtotal, 10_00);
+            assert_eq!(total, 5_00);
+
+            Ok(())
+        }
+
         #[tokio::test]
         async fn adding_same_product_twice_creates_two_distinct_items() -> TestResult {
             let ctx = TestContext::new().await;
@@ -573,6 +928,8 @@ mod tests {
             let cart = get_cart(&ctx, ctx.tenant_uuid, cart.uuid, Timestamp::now()).await?;
 
             assert!(cart.items.is_empty());
+            assert_eq!(cart.subtotal, 0);
+            assert_eq!(cart.total, 0);
 
             Ok(())
         }
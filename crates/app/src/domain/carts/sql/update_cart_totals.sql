@@ -0,0 +1,6 @@
+UPDATE carts
+SET
+    subtotal = $2,
+    total = $3
+WHERE uuid = $1
+  AND deleted_at IS NULL
@@ -1,7 +1,10 @@
 //! Lattice Domain Concerns
 
 pub mod carts;
+pub mod pricing;
 pub mod products;
 pub mod promotions;
+pub mod quotes;
+pub mod stacks;
 pub mod tags;
 pub mod tenants;
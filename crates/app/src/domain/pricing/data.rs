@@ -0,0 +1,31 @@
+//! Pricing Data
+
+use smallvec::SmallVec;
+
+use crate::domain::promotions::records::PromotionUuid;
+
+/// Pricing Item Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricingItem {
+    pub price: u64,
+    pub tags: SmallVec<[String; 3]>,
+}
+
+/// Pricing Result Data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PricingResult {
+    pub subtotal: u64,
+    pub total: u64,
+
+    /// Redemptions per priced item, in the same order as the items were given.
+    pub redemptions: Vec<SmallVec<[Redemption; 3]>>,
+}
+
+/// Promotion Redemption Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redemption {
+    pub promotion_uuid: PromotionUuid,
+    pub redemption_idx: usize,
+    pub original_price: u64,
+    pub final_price: u64,
+}
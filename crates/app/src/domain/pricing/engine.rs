@@ -0,0 +1,743 @@
+//! Pricing Engine
+//!
+//! Prices a set of items against promotion details using the lattice promotion graph.
+
+use lattice::{
+    graph::{OutputMode, PromotionGraph, PromotionGraphBuilder},
+    items::{Item, groups::ItemGroup},
+    products::ProductKey,
+    promotions::{Promotion, PromotionKey},
+};
+use rustc_hash::FxHashMap;
+use rusty_money::iso::{self, Currency};
+use slotmap::SlotMap;
+use smallvec::SmallVec;
+use tracing::debug;
+
+use crate::domain::{
+    pricing::{
+        PricingError,
+        data::{PricingItem, PricingResult, Redemption},
+        promotions::{core_promotion, money, tag_collection},
+    },
+    promotions::records::{PromotionDetailsRecord, PromotionUuid},
+    stacks::data::{LayerOutput, StackGraph},
+};
+
+/// Currency used when none is configured.
+pub const DEFAULT_CURRENCY: &Currency = iso::GBP;
+
+/// Price the given items against the given promotions.
+///
+/// With a stack, only the promotions placed in its layers apply and discounts
+/// stack across layers. Without one, all promotions compete in a single layer.
+///
+/// Prices, promotion amounts and the result are all minor units of
+/// `currency`.
+///
+/// # Errors
+///
+/// Returns an error when an amount cannot be represented by the engine or
+/// when the promotion graph fails to build or evaluate.
+#[tracing::instrument(
+    name = "pricing.price_items",
+    skip(items, promotions, stack),
+    fields(
+        item_count = items.len(),
+        promotion_count = promotions.len(),
+        has_stack = stack.is_some()
+    ),
+    err
+)]
+pub(crate) fn price_items(
+    items: &[PricingItem],
+    promotions: &[PromotionDetailsRecord],
+    stack: Option<&StackGraph>,
+    currency: &'static Currency,
+) -> Result<PricingResult, PricingError> {
+    let subtotal = items
+        .iter()
+        .try_fold(0_u64, |subtotal, item| subtotal.checked_add(item.price))
+        .ok_or(PricingError::AmountOutOfRange)?;
+
+    if items.is_empty() {
+        return Ok(PricingResult::default());
+    }
+
+    let mut promotion_uuids: SlotMap<PromotionKey, PromotionUuid> = SlotMap::with_key();
+
+    let graph = promotion_graph(promotions, stack, currency, &mut promotion_uuids)?;
+
+    let mut product_keys: SlotMap<ProductKey, ()> = SlotMap::with_key();
+    let mut core_items = SmallVec::with_capacity(items.len());
+
+    for item in items {
+        core_items.push(Item::with_tags(
+            product_keys.insert(()),
+            money(item.price, currency)?,
+            tag_collection(&item.tags),
+        ));
+    }
+
+    let result = graph.evaluate(&ItemGroup::new(core_items, currency))?;
+
+    let total =
+        u64::try_from(result.total.to_minor_units()).map_err(|_| PricingError::AmountOutOfRange)?;
+
+    let mut redemptions = vec![SmallVec::new(); items.len()];
+
+    for (item_idx, item_redemptions) in result.item_redemptions {
+        let Some(slot) = redemptions.get_mut(item_idx) else {
+            continue;
+        };
+
+        for redemption in item_redemptions {
+            slot.push(Redemption {
+                promotion_uuid: promotion_uuids
+                    .get(redemption.promotion_key)
+                    .copied()
+                    .ok_or(PricingError::UnknownPromotion)?,
+                redemption_idx: redemption.redemption_idx,
+                original_price: u64::try_from(redemption.original_price.to_minor_units())
+                    .map_err(|_| PricingError::AmountOutOfRange)?,
+                final_price: u64::try_from(redemption.final_price.to_minor_units())
+                    .map_err(|_| PricingError::AmountOutOfRange)?,
+            });
+        }
+    }
+
+    debug!(subtotal, total, "priced items");
+
+    Ok(PricingResult {
+        subtotal,
+        total,
+        redemptions,
+    })
+}
+
+/// Check that a stack builds into a valid promotion graph.
+///
+/// Promotion amounts are read in `currency`, as they are when pricing.
+///
+/// # Errors
+///
+/// Returns an error when a layer is named twice, a layer refers to an unknown
+/// layer, or the graph breaks a [`lattice::graph::GraphError`] rule.
+pub(crate) fn validate_stack(
+    stack: &StackGraph,
+    promotions: &[PromotionDetailsRecord],
+    currency: &'static Currency,
+) -> Result<(), PricingError> {
+    let mut promotion_uuids = SlotMap::with_key();
+
+    promotion_graph(promotions, Some(stack), currency, &mut promotion_uuids).map(drop)
+}
+
+/// Build the promotion graph, recording which promotion each key stands for.
+///
+/// A promotion keeps one key wherever it appears, so placing it twice along a
+/// path is rejected by the graph builder.
+fn promotion_graph(
+    promotions: &[PromotionDetailsRecord],
+    stack: Option<&StackGraph>,
+    currency: &'static Currency,
+    promotion_uuids: &mut SlotMap<PromotionKey, PromotionUuid>,
+) -> Result<PromotionGraph<'static>, PricingError> {
+    let mut core_promotions = Vec::with_capacity(promotions.len());
+
+    for record in promotions {
+        let key = promotion_uuids.insert(record.uuid);
+
+        core_promotions.push((record.uuid, core_promotion(key, &record.details, currency)?));
+    }
+
+    let Some(stack) = stack else {
+        return Ok(PromotionGraph::single_layer(
+            core_promotions.into_iter().map(|(_, promotion)| promotion),
+        )?);
+    };
+
+    let core_promotions: FxHashMap<PromotionUuid, Promotion<'static>> =
+        core_promotions.into_iter().collect();
+
+    let mut builder = PromotionGraphBuilder::new();
+    let mut nodes = FxHashMap::default();
+
+    for layer in &stack.layers {
+        let output_mode = match layer.output {
+            LayerOutput::PassThrough { .. } => OutputMode::PassThrough,
+            LayerOutput::Split { .. } => OutputMode::Split,
+        };
+
+        // Promotions that are not active at pricing time drop out of their layer.
+        let layer_promotions = layer
+            .promotions
+            .iter()
+            .filter_map(|uuid| core_promotions.get(uuid).cloned());
+
+        let node = builder.add_layer(layer.name.clone(), layer_promotions, output_mode)?;
+
+        if nodes.insert(layer.name.as_str(), node).is_some() {
+            return Err(PricingError::DuplicateLayer(layer.name.clone()));
+        }
+    }
+
+    let node = |name: &str| {
+        nodes
+            .get(name)
+            .copied()
+            .ok_or_else(|| PricingError::UnknownLayer(name.to_string()))
+    };
+
+    builder.set_root(node(&stack.root)?);
+
+    for layer in &stack.layers {
+        let from = node(&layer.name)?;
+
+        match &layer.output {
+            LayerOutput::PassThrough { next: Some(next) } => {
+                builder.connect_pass_through(from, node(next)?)?;
+            }
+            LayerOutput::Split {
+                participating: Some(participating),
+                non_participating: Some(non_participating),
+            } => {
+                builder.connect_split(from, node(participating)?, node(non_participating)?)?;
+            }
+            LayerOutput::Split {
+                participating: Some(participating),
+                non_participating: None,
+            } => {
+                builder.connect_split_participating_only(from, node(participating)?)?;
+            }
+            LayerOutput::Split {
+                participating: None,
+                non_participating: Some(non_participating),
+            } => {
+                builder.connect_split_non_participating_only(from, node(non_participating)?)?;
+            }
+            // A split without successors is rejected when the graph is built.
+            LayerOutput::PassThrough { next: None }
+            | LayerOutput::Split {
+                participating: None,
+                non_participating: None,
+            } => {}
+        }
+    }
+
+    Ok(PromotionGraph::from_builder(builder)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::domain::{
+        promotions::{
+            data::{
+                PromotionDetails,
+                budgets::Budgets,
+                discounts::{MixAndMatchDiscount, SimpleDiscount, ThresholdDiscount},
+                qualification::{
+                    Qualification, QualificationContext, QualificationOp, QualificationRule,
+                },
+                slots::{MixAndMatchSlot, MixAndMatchSlotDetails},
+                tiers::{ThresholdTier, ThresholdTierDetails, TierThreshold},
+            },
+            records::{
+                DirectDiscountDetailUuid, MixAndMatchDetailUuid, MixAndMatchSlotUuid,
+                PositionalDiscountDetailUuid, ThresholdTierUuid, TierQualificationUuid,
+                TieredThresholdDetailUuid,
+            },
+        },
+        stacks::data::StackLayer,
+    };
+
+    use super::*;
+
+    fn item(price: u64, tags: &[&str]) -> PricingItem {
+        PricingItem {
+            price,
+            tags: tags.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn direct_discount(
+        discount: SimpleDiscount,
+        budgets: Budgets,
+        qualification: Option<Qualification>,
+    ) -> PromotionDetailsRecord {
+        PromotionDetailsRecord {
+            uuid: PromotionUuid::new(),
+            details: PromotionDetails::DirectDiscount {
+                uuid: DirectDiscountDetailUuid::new(),
+                budgets,
+                discount,
+                qualification,
+            },
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    fn unlimited() -> Budgets {
+        Budgets {
+            redemptions: None,
+            monetary: None,
+        }
+    }
+
+    #[test]
+    fn empty_items_price_to_zero() -> TestResult {
+        let result = price_items(&[], &[], None, DEFAULT_CURRENCY)?;
+
+        assert_eq!(result, PricingResult::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn items_without_promotions_are_full_price() -> TestResult {
+        let result = price_items(
+            &[item(10_00, &[]), item(5_00, &[])],
+            &[],
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 15_00);
+        assert_eq!(result.total, 15_00);
+        assert!(result.redemptions.iter().all(SmallVec::is_empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn qualifying_items_receive_direct_discount() -> TestResult {
+        let promotion = direct_discount(
+            SimpleDiscount::PercentageOff { percentage: 50 },
+            unlimited(),
+            Some(Qualification {
+                context: QualificationContext::Primary,
+                op: QualificationOp::And,
+                rules: vec![QualificationRule::HasAny {
+                    tags: smallvec!["sale".to_string()],
+                }],
+            }),
+        );
+
+        let result = price_items(
+            &[item(10_00, &["sale"]), item(4_00, &["full-price"])],
+            std::slice::from_ref(&promotion),
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 14_00);
+        assert_eq!(result.total, 9_00);
+
+        let [discounted, full_price] = result.redemptions.as_slice() else {
+            panic!("expected two items, got {:?}", result.redemptions);
+        };
+
+        assert_eq!(
+            discounted.as_slice(),
+            &[Redemption {
+                promotion_uuid: promotion.uuid,
+                redemption_idx: 0,
+                original_price: 10_00,
+                final_price: 5_00,
+            }]
+        );
+        assert!(full_price.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn redemption_budget_limits_discounted_items() -> TestResult {
+        let promotion = direct_discount(
+            SimpleDiscount::FixedAmountOff { amount: 1_00 },
+            Budgets {
+                redemptions: Some(1),
+                monetary: None,
+            },
+            None,
+        );
+
+        let result = price_items(
+            &[item(3_00, &[]), item(3_00, &[])],
+            &[promotion],
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 6_00);
+        assert_eq!(result.total, 5_00);
+
+        Ok(())
+    }
+
+    #[test]
+    fn positional_discount_frees_cheapest_item_in_bundle() -> TestResult {
+        let promotion = PromotionDetailsRecord {
+            uuid: PromotionUuid::new(),
+            details: PromotionDetails::PositionalDiscount {
+                uuid: PositionalDiscountDetailUuid::new(),
+                budgets: unlimited(),
+                size: 3,
+                positions: smallvec![2],
+                discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                qualification: None,
+            },
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        };
+
+        let result = price_items(
+            &[item(3_00, &[]), item(2_00, &[]), item(1_00, &[])],
+            &[promotion],
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 6_00);
+        assert_eq!(result.total, 5_00);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mix_and_match_prices_complete_bundle_at_fixed_total() -> TestResult {
+        let slot = |tag: &str| MixAndMatchSlotDetails {
+            uuid: MixAndMatchSlotUuid::new(),
+            slot: MixAndMatchSlot {
+                qualification: Some(Qualification {
+                    context: QualificationContext::Primary,
+                    op: QualificationOp::And,
+                    rules: vec![QualificationRule::HasAny {
+                        tags: smallvec![tag.to_string()],
+                    }],
+                }),
+                min: 1,
+                max: Some(1),
+            },
+        };
+
+        let promotion = PromotionDetailsRecord {
+            uuid: PromotionUuid::new(),
+            details: PromotionDetails::MixAndMatch {
+                uuid: MixAndMatchDetailUuid::new(),
+                budgets: unlimited(),
+                slots: vec![slot("main"), slot("drink")],
+                discount: MixAndMatchDiscount::FixedTotal { amount: 5_00 },
+            },
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        };
+
+        let result = price_items(
+            &[
+                item(4_00, &["main"]),
+                item(2_00, &["drink"]),
+                item(1_00, &["snack"]),
+            ],
+            &[promotion],
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 7_00);
+        assert_eq!(result.total, 6_00);
+        assert!(result.redemptions[2].is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiered_threshold_applies_highest_reached_tier() -> TestResult {
+        let spend = |amount: u64| TierThreshold {
+            monetary: Some(amount),
+            items: None,
+        };
+
+        let tier = |lower: u64, upper: Option<u64>, percentage: u16| ThresholdTierDetails {
+            uuid: ThresholdTierUuid::new(),
+            contribution_uuid: TierQualificationUuid::new(),
+            discount_uuid: TierQualificationUuid::new(),
+            tier: ThresholdTier {
+                lower_threshold: spend(lower),
+                upper_threshold: upper.map(spend),
+                contribution_qualification: None,
+                discount_qualification: None,
+                discount: ThresholdDiscount::PercentEachItem { percentage },
+            },
+        };
+
+        let promotion = PromotionDetailsRecord {
+            uuid: PromotionUuid::new(),
+            details: PromotionDetails::TieredThreshold {
+                uuid: TieredThresholdDetailUuid::new(),
+                budgets: unlimited(),
+                tiers: vec![tier(5_000, Some(10_000), 10), tier(10_000, None, 20)],
+            },
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        };
+
+        let below = price_items(
+            &[item(4_000, &[])],
+            std::slice::from_ref(&promotion),
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(below.total, 4_000);
+
+        let lower_tier = price_items(
+            &[item(3_000, &[]), item(3_000, &[])],
+            std::slice::from_ref(&promotion),
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(lower_tier.total, 5_400);
+
+        let upper_tier = price_items(
+            &[item(6_000, &[]), item(5_000, &[])],
+            &[promotion],
+            None,
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(upper_tier.subtotal, 11_000);
+        assert_eq!(upper_tier.total, 8_800);
+
+        Ok(())
+    }
+
+    fn sale_only() -> Option<Qualification> {
+        Some(Qualification {
+            context: QualificationContext::Primary,
+            op: QualificationOp::And,
+            rules: vec![QualificationRule::HasAny {
+                tags: smallvec!["sale".to_string()],
+            }],
+        })
+    }
+
+    fn stack_layer(name: &str, promotion: PromotionUuid, output: LayerOutput) -> StackLayer {
+        StackLayer {
+            name: name.to_string(),
+            promotions: vec![promotion],
+            output,
+        }
+    }
+
+    #[test]
+    fn stacked_layers_apply_discounts_in_sequence() -> TestResult {
+        let half_off = direct_discount(
+            SimpleDiscount::PercentageOff { percentage: 50 },
+            unlimited(),
+            None,
+        );
+
+        let pound_off = direct_discount(
+            SimpleDiscount::FixedAmountOff { amount: 1_00 },
+            unlimited(),
+            None,
+        );
+
+        let stack = StackGraph {
+            root: "first".to_string(),
+            layers: vec![
+                stack_layer(
+                    "first",
+                    half_off.uuid,
+                    LayerOutput::PassThrough {
+                        next: Some("second".to_string()),
+                    },
+                ),
+                stack_layer(
+                    "second",
+                    pound_off.uuid,
+                    LayerOutput::PassThrough { next: None },
+                ),
+            ],
+        };
+
+        let promotions = [half_off, pound_off];
+
+        let competing = price_items(&[item(10_00, &[])], &promotions, None, DEFAULT_CURRENCY)?;
+
+        assert_eq!(competing.total, 5_00);
+
+        let stacked = price_items(
+            &[item(10_00, &[])],
+            &promotions,
+            Some(&stack),
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(stacked.total, 4_00);
+
+        let [redemptions] = stacked.redemptions.as_slice() else {
+            panic!("expected one item, got {:?}", stacked.redemptions);
+        };
+
+        assert_eq!(
+            redemptions
+                .iter()
+                .map(|redemption| redemption.promotion_uuid)
+                .collect::<Vec<_>>(),
+            promotions
+                .iter()
+                .map(|promotion| promotion.uuid)
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_layer_routes_only_participating_items_onwards() -> TestResult {
+        let half_off_sale = direct_discount(
+            SimpleDiscount::PercentageOff { percentage: 50 },
+            unlimited(),
+            sale_only(),
+        );
+
+        let pound_off = direct_discount(
+            SimpleDiscount::FixedAmountOff { amount: 1_00 },
+            unlimited(),
+            None,
+        );
+
+        let stack = StackGraph {
+            root: "sale".to_string(),
+            layers: vec![
+                stack_layer(
+                    "sale",
+                    half_off_sale.uuid,
+                    LayerOutput::Split {
+                        participating: Some("loyalty".to_string()),
+                        non_participating: None,
+                    },
+                ),
+                stack_layer(
+                    "loyalty",
+                    pound_off.uuid,
+                    LayerOutput::PassThrough { next: None },
+                ),
+            ],
+        };
+
+        let result = price_items(
+            &[item(10_00, &["sale"]), item(4_00, &[])],
+            &[half_off_sale, pound_off],
+            Some(&stack),
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.subtotal, 14_00);
+        assert_eq!(result.total, 8_00);
+        assert!(result.redemptions[1].is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stack_promotions_outside_layers_do_not_apply() -> TestResult {
+        let layered = direct_discount(
+            SimpleDiscount::FixedAmountOff { amount: 1_00 },
+            unlimited(),
+            None,
+        );
+
+        let unlayered = direct_discount(
+            SimpleDiscount::PercentageOff { percentage: 50 },
+            unlimited(),
+            None,
+        );
+
+        let stack = StackGraph {
+            root: "default".to_string(),
+            layers: vec![stack_layer(
+                "default",
+                layered.uuid,
+                LayerOutput::PassThrough { next: None },
+            )],
+        };
+
+        let result = price_items(
+            &[item(10_00, &[])],
+            &[layered, unlayered],
+            Some(&stack),
+            DEFAULT_CURRENCY,
+        )?;
+
+        assert_eq!(result.total, 9_00);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stack_with_duplicate_layer_name_is_invalid() {
+        let promotion = direct_discount(
+            SimpleDiscount::PercentageOff { percentage: 10 },
+            unlimited(),
+            None,
+        );
+
+        let stack = StackGraph {
+            root: "default".to_string(),
+            layers: vec![
+                stack_layer(
+                    "default",
+                    promotion.uuid,
+                    LayerOutput::PassThrough { next: None },
+                ),
+                StackLayer {
+                    name: "default".to_string(),
+                    promotions: Vec::new(),
+                    output: LayerOutput::PassThrough { next: None },
+                },
+            ],
+        };
+
+        let result = validate_stack(&stack, &[promotion], DEFAULT_CURRENCY);
+
+        assert!(
+            matches!(result, Err(PricingError::DuplicateLayer(ref name)) if name == "default"),
+            "expected DuplicateLayer, got {result:?}"
+        );
+    }
+
+    #[test]
+    fn stack_with_split_without_successors_is_invalid() {
+        let stack = StackGraph {
+            root: "default".to_string(),
+            layers: vec![StackLayer {
+                name: "default".to_string(),
+                promotions: Vec::new(),
+                output: LayerOutput::Split {
+                    participating: None,
+                    non_participating: None,
+                },
+            }],
+        };
+
+        let result = validate_stack(&stack, &[], DEFAULT_CURRENCY);
+
+        assert!(
+            matches!(result, Err(PricingError::Graph(_))),
+            "expected graph error, got {result:?}"
+        );
+    }
+}
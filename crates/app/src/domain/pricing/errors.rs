@@ -0,0 +1,28 @@
+//! Pricing errors.
+
+use lattice::graph::GraphError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PricingError {
+    #[error("amount is out of range for the pricing engine")]
+    AmountOutOfRange,
+
+    #[error("redemption references an unknown promotion")]
+    UnknownPromotion,
+
+    #[error("layer `{0}` is defined more than once")]
+    DuplicateLayer(String),
+
+    #[error("layer `{0}` is not defined")]
+    UnknownLayer(String),
+
+    #[error("promotion graph error")]
+    Graph(#[source] GraphError),
+}
+
+impl From<GraphError> for PricingError {
+    fn from(error: GraphError) -> Self {
+        Self::Graph(error)
+    }
+}
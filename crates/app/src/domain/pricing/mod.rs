@@ -0,0 +1,10 @@
+//! Pricing
+
+pub mod data;
+mod engine;
+mod errors;
+mod promotions;
+
+pub use engine::DEFAULT_CURRENCY;
+pub(crate) use engine::{price_items, validate_stack};
+pub use errors::PricingError;
@@ -0,0 +1,280 @@
+//! Pricing Promotions
+//!
+//! Conversions from stored promotion details into lattice promotions.
+
+use decimal_percentage::Percentage;
+use lattice::{
+    discounts::SimpleDiscount as CoreSimpleDiscount,
+    promotions::{
+        Promotion, PromotionKey, PromotionSlotKey,
+        budget::PromotionBudget,
+        promotion,
+        qualification::{
+            BoolOp, Qualification as CoreQualification, QualificationRule as CoreQualificationRule,
+        },
+        types::{
+            DirectDiscountPromotion, MixAndMatchDiscount as CoreMixAndMatchDiscount,
+            MixAndMatchPromotion, MixAndMatchSlot as CoreMixAndMatchSlot,
+            PositionalDiscountPromotion, ThresholdDiscount as CoreThresholdDiscount,
+            ThresholdTier as CoreThresholdTier, TierThreshold as CoreTierThreshold,
+            TieredThresholdPromotion,
+        },
+    },
+    tags::string::StringTagCollection,
+};
+use rusty_money::{Money, iso::Currency};
+use slotmap::SlotMap;
+
+use crate::domain::{
+    pricing::PricingError,
+    promotions::data::{
+        PromotionDetails,
+        budgets::Budgets,
+        discounts::{MixAndMatchDiscount, SimpleDiscount, ThresholdDiscount},
+        qualification::{Qualification, QualificationOp, QualificationRule},
+        slots::MixAndMatchSlotDetails,
+        tiers::{ThresholdTierDetails, TierThreshold},
+    },
+};
+
+/// Builds a lattice promotion for the given promotion details.
+pub(super) fn core_promotion(
+    key: PromotionKey,
+    details: &PromotionDetails,
+    currency: &'static Currency,
+) -> Result<Promotion<'static>, PricingError> {
+    match details {
+        PromotionDetails::DirectDiscount {
+            budgets,
+            discount,
+            qualification,
+            ..
+        } => Ok(promotion(DirectDiscountPromotion::new(
+            key,
+            core_qualification(qualification.as_ref()),
+            core_discount(discount, currency)?,
+            core_budget(budgets, currency)?,
+        ))),
+        PromotionDetails::PositionalDiscount {
+            budgets,
+            size,
+            positions,
+            discount,
+            qualification,
+            ..
+        } => Ok(promotion(PositionalDiscountPromotion::new(
+            key,
+            core_qualification(qualification.as_ref()),
+            *size,
+            positions.clone(),
+            core_discount(discount, currency)?,
+            core_budget(budgets, currency)?,
+        ))),
+        PromotionDetails::MixAndMatch {
+            budgets,
+            slots,
+            discount,
+            ..
+        } => Ok(promotion(MixAndMatchPromotion::new(
+            key,
+            core_slots(slots),
+            core_mix_and_match_discount(discount, currency)?,
+            core_budget(budgets, currency)?,
+        ))),
+        PromotionDetails::TieredThreshold { budgets, tiers, .. } => {
+            Ok(promotion(TieredThresholdPromotion::new(
+                key,
+                core_tiers(tiers, currency)?,
+                core_budget(budgets, currency)?,
+            )))
+        }
+    }
+}
+
+fn core_slots(slots: &[MixAndMatchSlotDetails]) -> Vec<CoreMixAndMatchSlot> {
+    let mut slot_keys: SlotMap<PromotionSlotKey, ()> = SlotMap::with_key();
+
+    slots
+        .iter()
+        .map(|details| {
+            CoreMixAndMatchSlot::new(
+                slot_keys.insert(()),
+                core_qualification(details.slot.qualification.as_ref()),
+                details.slot.min as usize,
+                details.slot.max.map(|max| max as usize),
+            )
+        })
+        .collect()
+}
+
+fn core_tiers(
+    tiers: &[ThresholdTierDetails],
+    currency: &'static Currency,
+) -> Result<Vec<CoreThresholdTier<'static, StringTagCollection>>, PricingError> {
+    tiers
+        .iter()
+        .map(|details| {
+            Ok(CoreThresholdTier::new(
+                core_threshold(&details.tier.lower_threshold, currency)?,
+                details
+                    .tier
+                    .upper_threshold
+                    .as_ref()
+                    .map(|threshold| core_threshold(threshold, currency))
+                    .transpose()?,
+                core_qualification(details.tier.contribution_qualification.as_ref()),
+                core_qualification(details.tier.discount_qualification.as_ref()),
+                core_threshold_discount(&details.tier.discount, currency)?,
+            ))
+        })
+        .collect()
+}
+
+fn core_threshold(
+    threshold: &TierThreshold,
+    currency: &'static Currency,
+) -> Result<CoreTierThreshold<'static>, PricingError> {
+    Ok(CoreTierThreshold::new(
+        threshold
+            .monetary
+            .map(|monetary| money(monetary, currency))
+            .transpose()?,
+        threshold.items,
+    ))
+}
+
+/// A missing qualification matches every item.
+fn core_qualification(qualification: Option<&Qualification>) -> CoreQualification {
+    qualification.map_or_else(CoreQualification::match_all, |qualification| {
+        CoreQualification::new(
+            match qualification.op {
+                QualificationOp::And => BoolOp::And,
+                QualificationOp::Or => BoolOp::Or,
+            },
+            qualification.rules.iter().map(core_rule).collect(),
+        )
+    })
+}
+
+fn core_rule(rule: &QualificationRule) -> CoreQualificationRule {
+    match rule {
+        QualificationRule::HasAll { tags } => CoreQualificationRule::HasAll {
+            tags: tag_collection(tags),
+        },
+        QualificationRule::HasAny { tags } => CoreQualificationRule::HasAny {
+            tags: tag_collection(tags),
+        },
+        QualificationRule::HasNone { tags } => CoreQualificationRule::HasNone {
+            tags: tag_collection(tags),
+        },
+        QualificationRule::Group { qualification } => {
+            CoreQualificationRule::Group(Box::new(core_qualification(Some(qualification))))
+        }
+    }
+}
+
+pub(super) fn tag_collection(tags: &[String]) -> StringTagCollection {
+    StringTagCollection::new(tags.iter().cloned().collect())
+}
+
+fn core_discount(
+    discount: &SimpleDiscount,
+    currency: &'static Currency,
+) -> Result<CoreSimpleDiscount<'static>, PricingError> {
+    match discount {
+        SimpleDiscount::PercentageOff { percentage } => Ok(CoreSimpleDiscount::PercentageOff(
+            percentage_fraction(*percentage),
+        )),
+        SimpleDiscount::FixedAmountOff { amount } => {
+            Ok(CoreSimpleDiscount::AmountOff(money(*amount, currency)?))
+        }
+    }
+}
+
+fn core_mix_and_match_discount(
+    discount: &MixAndMatchDiscount,
+    currency: &'static Currency,
+) -> Result<CoreMixAndMatchDiscount<'static>, PricingError> {
+    Ok(match discount {
+        MixAndMatchDiscount::PercentAllItems { percentage } => {
+            CoreMixAndMatchDiscount::PercentAllItems(percentage_fraction(*percentage))
+        }
+        MixAndMatchDiscount::AmountOffEachItem { amount } => {
+            CoreMixAndMatchDiscount::AmountOffEachItem(money(*amount, currency)?)
+        }
+        MixAndMatchDiscount::FixedPriceEachItem { amount } => {
+            CoreMixAndMatchDiscount::FixedPriceEachItem(money(*amount, currency)?)
+        }
+        MixAndMatchDiscount::AmountOffTotal { amount } => {
+            CoreMixAndMatchDiscount::AmountOffTotal(money(*amount, currency)?)
+        }
+        MixAndMatchDiscount::FixedTotal { amount } => {
+            CoreMixAndMatchDiscount::FixedTotal(money(*amount, currency)?)
+        }
+        MixAndMatchDiscount::PercentCheapest { percentage } => {
+            CoreMixAndMatchDiscount::PercentCheapest(percentage_fraction(*percentage))
+        }
+        MixAndMatchDiscount::FixedCheapest { amount } => {
+            CoreMixAndMatchDiscount::FixedCheapest(money(*amount, currency)?)
+        }
+    })
+}
+
+fn core_threshold_discount(
+    discount: &ThresholdDiscount,
+    currency: &'static Currency,
+) -> Result<CoreThresholdDiscount<'static>, PricingError> {
+    Ok(match discount {
+        ThresholdDiscount::PercentEachItem { percentage } => {
+            CoreThresholdDiscount::PercentEachItem(percentage_fraction(*percentage))
+        }
+        ThresholdDiscount::AmountOffEachItem { amount } => {
+            CoreThresholdDiscount::AmountOffEachItem(money(*amount, currency)?)
+        }
+        ThresholdDiscount::FixedPriceEachItem { amount } => {
+            CoreThresholdDiscount::FixedPriceEachItem(money(*amount, currency)?)
+        }
+        ThresholdDiscount::AmountOffTotal { amount } => {
+            CoreThresholdDiscount::AmountOffTotal(money(*amount, currency)?)
+        }
+        ThresholdDiscount::FixedTotal { amount } => {
+            CoreThresholdDiscount::FixedTotal(money(*amount, currency)?)
+        }
+        ThresholdDiscount::PercentCheapest { percentage } => {
+            CoreThresholdDiscount::PercentCheapest(percentage_fraction(*percentage))
+        }
+        ThresholdDiscount::FixedCheapest { amount } => {
+            CoreThresholdDiscount::FixedCheapest(money(*amount, currency)?)
+        }
+    })
+}
+
+/// Stored percentages are whole numbers, the engine works in fractions.
+fn percentage_fraction(percentage: u16) -> Percentage {
+    Percentage::from(f64::from(percentage) / 100.0)
+}
+
+fn core_budget(
+    budgets: &Budgets,
+    currency: &'static Currency,
+) -> Result<PromotionBudget<'static>, PricingError> {
+    Ok(PromotionBudget {
+        redemption_limit: budgets
+            .redemptions
+            .map(|limit| u32::try_from(limit).map_err(|_| PricingError::AmountOutOfRange))
+            .transpose()?,
+        monetary_limit: budgets
+            .monetary
+            .map(|limit| money(limit, currency))
+            .transpose()?,
+    })
+}
+
+pub(super) fn money(
+    minor_units: u64,
+    currency: &'static Currency,
+) -> Result<Money<'static, Currency>, PricingError> {
+    i64::try_from(minor_units)
+        .map(|minor_units| Money::from_minor(minor_units, currency))
+        .map_err(|_| PricingError::AmountOutOfRange)
+}
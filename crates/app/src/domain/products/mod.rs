@@ -7,4 +7,5 @@ mod repository;
 mod service;
 
 pub use errors::ProductsServiceError;
+pub(crate) use repository::PgProductsRepository;
 pub use service::*;
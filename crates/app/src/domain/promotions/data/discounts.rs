@@ -16,3 +16,57 @@ impl SimpleDiscount {
         }
     }
 }
+
+/// Mix and Match Discount Data
+#[derive(Debug, Clone, PartialEq)]
+pub enum MixAndMatchDiscount {
+    PercentAllItems { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl MixAndMatchDiscount {
+    #[must_use]
+    pub const fn to_str(&self) -> &'static str {
+        match self {
+            Self::PercentAllItems { .. } => "percent_all_items",
+            Self::AmountOffEachItem { .. } => "amount_off_each_item",
+            Self::FixedPriceEachItem { .. } => "fixed_price_each_item",
+            Self::AmountOffTotal { .. } => "amount_off_total",
+            Self::FixedTotal { .. } => "fixed_total",
+            Self::PercentCheapest { .. } => "percent_cheapest",
+            Self::FixedCheapest { .. } => "fixed_cheapest",
+        }
+    }
+}
+
+/// Threshold Discount Data
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThresholdDiscount {
+    PercentEachItem { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl ThresholdDiscount {
+    #[must_use]
+    pub const fn to_str(&self) -> &'static str {
+        match self {
+            Self::PercentEachItem { .. } => "percent_each_item",
+            Self::AmountOffEachItem { .. } => "amount_off_each_item",
+            Self::FixedPriceEachItem { .. } => "fixed_price_each_item",
+            Self::AmountOffTotal { .. } => "amount_off_total",
+            Self::FixedTotal { .. } => "fixed_total",
+            Self::PercentCheapest { .. } => "percent_cheapest",
+            Self::FixedCheapest { .. } => "fixed_cheapest",
+        }
+    }
+}
@@ -1,13 +1,27 @@
 //! Promotions Data
 
+use smallvec::{SmallVec, smallvec};
+use uuid::Uuid;
+
 use crate::domain::promotions::{
-    data::{budgets::Budgets, discounts::SimpleDiscount, qualification::Qualification},
-    records::PromotionUuid,
+    data::{
+        budgets::Budgets,
+        discounts::{MixAndMatchDiscount, SimpleDiscount},
+        qualification::Qualification,
+        slots::{MixAndMatchSlot, MixAndMatchSlotDetails},
+        tiers::{ThresholdTier, ThresholdTierDetails},
+    },
+    records::{
+        DirectDiscountDetailUuid, MixAndMatchDetailUuid, PositionalDiscountDetailUuid,
+        PromotionDetailUuid, PromotionUuid, TieredThresholdDetailUuid,
+    },
 };
 
 pub mod budgets;
 pub mod discounts;
 pub mod qualification;
+pub mod slots;
+pub mod tiers;
 
 /// New Promotion Data
 #[derive(Debug, Clone, PartialEq)]
@@ -18,6 +32,25 @@ pub enum NewPromotion {
         discount: SimpleDiscount,
         qualification: Option<Qualification>,
     },
+    PositionalDiscount {
+        uuid: PromotionUuid,
+        budgets: Budgets,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscount,
+        qualification: Option<Qualification>,
+    },
+    MixAndMatch {
+        uuid: PromotionUuid,
+        budgets: Budgets,
+        slots: Vec<MixAndMatchSlot>,
+        discount: MixAndMatchDiscount,
+    },
+    TieredThreshold {
+        uuid: PromotionUuid,
+        budgets: Budgets,
+        tiers: Vec<ThresholdTier>,
+    },
 }
 
 impl NewPromotion {
@@ -25,19 +58,55 @@ impl NewPromotion {
     pub const fn type_as_str(&self) -> &'static str {
         match self {
             Self::DirectDiscount { .. } => "direct",
+            Self::PositionalDiscount { .. } => "positional",
+            Self::MixAndMatch { .. } => "mix_and_match",
+            Self::TieredThreshold { .. } => "tiered_threshold",
         }
     }
 
     #[must_use]
     pub fn uuid(&self) -> PromotionUuid {
         match self {
-            Self::DirectDiscount { uuid, .. } => *uuid,
+            Self::DirectDiscount { uuid, .. }
+            | Self::PositionalDiscount { uuid, .. }
+            | Self::MixAndMatch { uuid, .. }
+            | Self::TieredThreshold { uuid, .. } => *uuid,
+        }
+    }
+
+    /// The first detail version shares the promotion's UUID.
+    #[must_use]
+    pub fn detail_uuid(&self) -> PromotionDetailUuid {
+        match self {
+            Self::DirectDiscount { uuid, .. } => PromotionDetailUuid::DirectDiscount(
+                DirectDiscountDetailUuid::from_uuid(uuid.into_uuid()),
+            ),
+            Self::PositionalDiscount { uuid, .. } => PromotionDetailUuid::PositionalDiscount(
+                PositionalDiscountDetailUuid::from_uuid(uuid.into_uuid()),
+            ),
+            Self::MixAndMatch { uuid, .. } => {
+                PromotionDetailUuid::MixAndMatch(MixAndMatchDetailUuid::from_uuid(uuid.into_uuid()))
+            }
+            Self::TieredThreshold { uuid, .. } => PromotionDetailUuid::TieredThreshold(
+                TieredThresholdDetailUuid::from_uuid(uuid.into_uuid()),
+            ),
         }
     }
 
     pub fn take_qualification(&mut self) -> Option<Qualification> {
         match self {
-            Self::DirectDiscount { qualification, .. } => qualification.take(),
+            Self::DirectDiscount { qualification, .. }
+            | Self::PositionalDiscount { qualification, .. } => qualification.take(),
+            Self::MixAndMatch { .. } | Self::TieredThreshold { .. } => None,
+        }
+    }
+
+    /// Take the qualifications nested in slots or tiers, in storage order.
+    pub fn take_nested_qualifications(&mut self) -> Vec<Option<Qualification>> {
+        match self {
+            Self::DirectDiscount { .. } | Self::PositionalDiscount { .. } => Vec::new(),
+            Self::MixAndMatch { slots, .. } => take_slot_qualifications(slots),
+            Self::TieredThreshold { tiers, .. } => take_tier_qualifications(tiers),
         }
     }
 }
@@ -50,6 +119,22 @@ pub enum PromotionUpdate {
         discount: SimpleDiscount,
         qualification: Option<Qualification>,
     },
+    PositionalDiscount {
+        budgets: Budgets,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscount,
+        qualification: Option<Qualification>,
+    },
+    MixAndMatch {
+        budgets: Budgets,
+        slots: Vec<MixAndMatchSlot>,
+        discount: MixAndMatchDiscount,
+    },
+    TieredThreshold {
+        budgets: Budgets,
+        tiers: Vec<ThresholdTier>,
+    },
 }
 
 impl PromotionUpdate {
@@ -57,12 +142,135 @@ impl PromotionUpdate {
     pub const fn type_as_str(&self) -> &'static str {
         match self {
             Self::DirectDiscount { .. } => "direct",
+            Self::PositionalDiscount { .. } => "positional",
+            Self::MixAndMatch { .. } => "mix_and_match",
+            Self::TieredThreshold { .. } => "tiered_threshold",
         }
     }
 
     pub fn take_qualification(&mut self) -> Option<Qualification> {
         match self {
-            Self::DirectDiscount { qualification, .. } => qualification.take(),
+            Self::DirectDiscount { qualification, .. }
+            | Self::PositionalDiscount { qualification, .. } => qualification.take(),
+            Self::MixAndMatch { .. } | Self::TieredThreshold { .. } => None,
+        }
+    }
+
+    /// Take the qualifications nested in slots or tiers, in storage order.
+    pub fn take_nested_qualifications(&mut self) -> Vec<Option<Qualification>> {
+        match self {
+            Self::DirectDiscount { .. } | Self::PositionalDiscount { .. } => Vec::new(),
+            Self::MixAndMatch { slots, .. } => take_slot_qualifications(slots),
+            Self::TieredThreshold { tiers, .. } => take_tier_qualifications(tiers),
+        }
+    }
+}
+
+/// Promotion Details Data
+///
+/// The type-specific detail version of a promotion, as loaded from storage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromotionDetails {
+    DirectDiscount {
+        uuid: DirectDiscountDetailUuid,
+        budgets: Budgets,
+        discount: SimpleDiscount,
+        qualification: Option<Qualification>,
+    },
+    PositionalDiscount {
+        uuid: PositionalDiscountDetailUuid,
+        budgets: Budgets,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscount,
+        qualification: Option<Qualification>,
+    },
+    MixAndMatch {
+        uuid: MixAndMatchDetailUuid,
+        budgets: Budgets,
+        slots: Vec<MixAndMatchSlotDetails>,
+        discount: MixAndMatchDiscount,
+    },
+    TieredThreshold {
+        uuid: TieredThresholdDetailUuid,
+        budgets: Budgets,
+        tiers: Vec<ThresholdTierDetails>,
+    },
+}
+
+impl PromotionDetails {
+    #[must_use]
+    pub const fn type_as_str(&self) -> &'static str {
+        match self {
+            Self::DirectDiscount { .. } => "direct",
+            Self::PositionalDiscount { .. } => "positional",
+            Self::MixAndMatch { .. } => "mix_and_match",
+            Self::TieredThreshold { .. } => "tiered_threshold",
+        }
+    }
+
+    #[must_use]
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            Self::DirectDiscount { uuid, .. } => uuid.into_uuid(),
+            Self::PositionalDiscount { uuid, .. } => uuid.into_uuid(),
+            Self::MixAndMatch { uuid, .. } => uuid.into_uuid(),
+            Self::TieredThreshold { uuid, .. } => uuid.into_uuid(),
+        }
+    }
+
+    /// Mutable access to each qualification, keyed by the UUID it is stored against.
+    pub fn qualifications_mut(&mut self) -> SmallVec<[(Uuid, &mut Option<Qualification>); 3]> {
+        match self {
+            Self::DirectDiscount {
+                uuid,
+                qualification,
+                ..
+            } => smallvec![(uuid.into_uuid(), qualification)],
+            Self::PositionalDiscount {
+                uuid,
+                qualification,
+                ..
+            } => smallvec![(uuid.into_uuid(), qualification)],
+            Self::MixAndMatch { slots, .. } => slots
+                .iter_mut()
+                .map(|slot| (slot.uuid.into_uuid(), &mut slot.slot.qualification))
+                .collect(),
+            Self::TieredThreshold { tiers, .. } => tiers
+                .iter_mut()
+                .flat_map(|tier| {
+                    [
+                        (
+                            tier.contribution_uuid.into_uuid(),
+                            &mut tier.tier.contribution_qualification,
+                        ),
+                        (
+                            tier.discount_uuid.into_uuid(),
+                            &mut tier.tier.discount_qualification,
+                        ),
+                    ]
+                })
+                .collect(),
         }
     }
 }
+
+fn take_slot_qualifications(slots: &mut [MixAndMatchSlot]) -> Vec<Option<Qualification>> {
+    slots
+        .iter_mut()
+        .map(|slot| slot.qualification.take())
+        .collect()
+}
+
+/// Each tier contributes its contribution then its discount qualification.
+fn take_tier_qualifications(tiers: &mut [ThresholdTier]) -> Vec<Option<Qualification>> {
+    tiers
+        .iter_mut()
+        .flat_map(|tier| {
+            [
+                tier.contribution_qualification.take(),
+                tier.discount_qualification.take(),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,22 @@
+//! Promotion Slots Data
+
+use crate::domain::promotions::{data::qualification::Qualification, records::MixAndMatchSlotUuid};
+
+/// Mix and Match Slot Data
+///
+/// A slot without a qualification accepts any item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixAndMatchSlot {
+    pub qualification: Option<Qualification>,
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+/// Mix and Match Slot Details Data
+///
+/// A stored slot, belonging to a single mix and match detail version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixAndMatchSlotDetails {
+    pub uuid: MixAndMatchSlotUuid,
+    pub slot: MixAndMatchSlot,
+}
@@ -0,0 +1,41 @@
+//! Promotion Tiers Data
+
+use crate::domain::promotions::{
+    data::{discounts::ThresholdDiscount, qualification::Qualification},
+    records::{ThresholdTierUuid, TierQualificationUuid},
+};
+
+/// Tier Threshold Data
+///
+/// A threshold can require spend, item count, or both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierThreshold {
+    pub monetary: Option<u64>,
+    pub items: Option<u32>,
+}
+
+/// Threshold Tier Data
+///
+/// Items matching the contribution qualification count towards the tier's
+/// thresholds; items matching the discount qualification receive its
+/// discount. A missing qualification matches every item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdTier {
+    pub lower_threshold: TierThreshold,
+    pub upper_threshold: Option<TierThreshold>,
+    pub contribution_qualification: Option<Qualification>,
+    pub discount_qualification: Option<Qualification>,
+    pub discount: ThresholdDiscount,
+}
+
+/// Threshold Tier Details Data
+///
+/// A stored tier, belonging to a single tiered threshold detail version. Its
+/// qualifications are stored against the contribution and discount UUIDs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdTierDetails {
+    pub uuid: ThresholdTierUuid,
+    pub contribution_uuid: TierQualificationUuid,
+    pub discount_uuid: TierQualificationUuid,
+    pub tier: ThresholdTier,
+}
@@ -7,3 +7,4 @@ mod repositories;
 pub mod service;
 
 pub use errors::PromotionsServiceError;
+pub(crate) use repositories::promotions::PgPromotionsRepository;
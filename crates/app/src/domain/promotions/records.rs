@@ -1,8 +1,14 @@
 //! Promotions Records
 
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use jiff::Timestamp;
+use uuid::Uuid;
 
-use crate::{domain::tags::Taggable, uuids::TypedUuid};
+use crate::{
+    domain::{promotions::data::PromotionDetails, tags::Taggable},
+    uuids::TypedUuid,
+};
 
 /// Promotion UUID
 pub type PromotionUuid = TypedUuid<PromotionRecord>;
@@ -16,6 +22,18 @@ pub struct PromotionRecord {
     pub deleted_at: Option<Timestamp>,
 }
 
+/// Promotion Details Record
+///
+/// A promotion together with the detail version valid at a point in time.
+#[derive(Debug, Clone)]
+pub struct PromotionDetailsRecord {
+    pub uuid: PromotionUuid,
+    pub details: PromotionDetails,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub deleted_at: Option<Timestamp>,
+}
+
 /// Direct Discount Promotion Detail Record
 #[derive(Debug, Clone)]
 pub struct DirectDiscountPromotionDetailRecord {}
@@ -23,6 +41,77 @@ pub struct DirectDiscountPromotionDetailRecord {}
 /// Direct Discount Promotion Detail UUID
 pub type DirectDiscountDetailUuid = TypedUuid<DirectDiscountPromotionDetailRecord>;
 
+/// Positional Discount Promotion Detail Record
+#[derive(Debug, Clone)]
+pub struct PositionalDiscountPromotionDetailRecord {}
+
+/// Positional Discount Promotion Detail UUID
+pub type PositionalDiscountDetailUuid = TypedUuid<PositionalDiscountPromotionDetailRecord>;
+
+/// Mix and Match Promotion Detail Record
+#[derive(Debug, Clone)]
+pub struct MixAndMatchPromotionDetailRecord {}
+
+/// Mix and Match Promotion Detail UUID
+pub type MixAndMatchDetailUuid = TypedUuid<MixAndMatchPromotionDetailRecord>;
+
+/// Mix and Match Slot Record
+#[derive(Debug, Clone)]
+pub struct MixAndMatchSlotRecord {}
+
+/// Mix and Match Slot UUID
+pub type MixAndMatchSlotUuid = TypedUuid<MixAndMatchSlotRecord>;
+
+/// Tiered Threshold Promotion Detail Record
+#[derive(Debug, Clone)]
+pub struct TieredThresholdPromotionDetailRecord {}
+
+/// Tiered Threshold Promotion Detail UUID
+pub type TieredThresholdDetailUuid = TypedUuid<TieredThresholdPromotionDetailRecord>;
+
+/// Threshold Tier Record
+#[derive(Debug, Clone)]
+pub struct ThresholdTierRecord {}
+
+/// Threshold Tier UUID
+pub type ThresholdTierUuid = TypedUuid<ThresholdTierRecord>;
+
+/// Tier Qualification Record
+#[derive(Debug, Clone)]
+pub struct TierQualificationRecord {}
+
+/// Tier Qualification UUID
+pub type TierQualificationUuid = TypedUuid<TierQualificationRecord>;
+
+/// Promotion Detail UUID
+///
+/// The UUID of a type-specific promotion detail version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromotionDetailUuid {
+    DirectDiscount(DirectDiscountDetailUuid),
+    PositionalDiscount(PositionalDiscountDetailUuid),
+    MixAndMatch(MixAndMatchDetailUuid),
+    TieredThreshold(TieredThresholdDetailUuid),
+}
+
+impl PromotionDetailUuid {
+    #[must_use]
+    pub const fn into_uuid(self) -> Uuid {
+        match self {
+            Self::DirectDiscount(uuid) => uuid.into_uuid(),
+            Self::PositionalDiscount(uuid) => uuid.into_uuid(),
+            Self::MixAndMatch(uuid) => uuid.into_uuid(),
+            Self::TieredThreshold(uuid) => uuid.into_uuid(),
+        }
+    }
+}
+
+impl Display for PromotionDetailUuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.into_uuid(), f)
+    }
+}
+
 /// Qualification UUID
 pub type QualificationUuid = TypedUuid<QualificationRecord>;
 
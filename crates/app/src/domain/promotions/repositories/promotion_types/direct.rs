@@ -1,13 +1,18 @@
 //! Direct Discount Promotions
 
-use sqlx::{Postgres, Transaction, query, query_scalar};
+use jiff::Timestamp;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{FromRow, Postgres, Row, Transaction, postgres::PgRow, query, query_as, query_scalar};
 use tracing::debug;
 use uuid::Uuid;
 
 use crate::domain::promotions::{
-    data::{budgets::Budgets, discounts::SimpleDiscount},
-    records::{DirectDiscountDetailUuid, PromotionUuid},
-    repositories::promotions::{budget_numeric_sql_values, discount_numeric_sql_values},
+    data::{PromotionDetails, budgets::Budgets, discounts::SimpleDiscount},
+    records::{DirectDiscountDetailUuid, PromotionDetailsRecord, PromotionUuid},
+    repositories::promotions::{
+        budget_numeric_sql_values, budgets_from_sql_values, discount_from_sql_values,
+        discount_numeric_sql_values,
+    },
 };
 
 const CREATE_DIRECT_DISCOUNT_PROMOTION_DETAIL_SQL: &str =
@@ -16,6 +21,11 @@ const CREATE_DIRECT_DISCOUNT_PROMOTION_DETAIL_SQL: &str =
 const UPDATE_DIRECT_DISCOUNT_PROMOTION_DETAIL_SQL: &str =
     include_str!("../../sql/direct/update_direct_discount_promotion_detail.sql");
 
+const LIST_DIRECT_DISCOUNT_PROMOTION_DETAILS_SQL: &str =
+    include_str!("../../sql/direct/list_direct_discount_promotion_details.sql");
+
+struct DirectDiscountPromotionRow(PromotionDetailsRecord);
+
 #[tracing::instrument(
     name = "promotions.direct_repository.insert_direct_discount_promotion",
     skip(tx, budgets, discount),
@@ -100,3 +110,60 @@ pub(crate) async fn update_direct_discount_promotion(
 
     Ok(DirectDiscountDetailUuid::from_uuid(returned_uuid))
 }
+
+#[tracing::instrument(
+    name = "promotions.direct_repository.list_direct_discount_promotions",
+    skip(tx),
+    fields(point_in_time = %point_in_time),
+    err
+)]
+pub(crate) async fn list_direct_discount_promotions(
+    tx: &mut Transaction<'_, Postgres>,
+    point_in_time: Timestamp,
+    promotion: Option<PromotionUuid>,
+) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+    let rows = query_as::<Postgres, DirectDiscountPromotionRow>(
+        LIST_DIRECT_DISCOUNT_PROMOTION_DETAILS_SQL,
+    )
+    .bind(SqlxTimestamp::from(point_in_time))
+    .bind(promotion.map(PromotionUuid::into_uuid))
+    .fetch_all(&mut **tx)
+    .await?;
+
+    debug!(
+        promotion_count = rows.len(),
+        "queried direct discount promotion details"
+    );
+
+    Ok(rows.into_iter().map(|row| row.0).collect())
+}
+
+impl<'r> FromRow<'r, PgRow> for DirectDiscountPromotionRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let budgets = budgets_from_sql_values(
+            row.try_get("redemption_budget")?,
+            row.try_get("monetary_budget")?,
+        )?;
+
+        let discount = discount_from_sql_values(
+            row.try_get("discount_kind")?,
+            row.try_get("discount_percentage")?,
+            row.try_get("discount_amount")?,
+        )?;
+
+        Ok(Self(PromotionDetailsRecord {
+            uuid: PromotionUuid::from_uuid(row.try_get("uuid")?),
+            details: PromotionDetails::DirectDiscount {
+                uuid: DirectDiscountDetailUuid::from_uuid(row.try_get("detail_uuid")?),
+                budgets,
+                discount,
+                qualification: None,
+            },
+            created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
+            updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
+            deleted_at: row
+                .try_get::<Option<SqlxTimestamp>, _>("deleted_at")?
+                .map(SqlxTimestamp::to_jiff),
+        }))
+    }
+}
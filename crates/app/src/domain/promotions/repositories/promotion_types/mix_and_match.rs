@@ -0,0 +1,364 @@
+//! Mix and Match Promotions
+
+use jiff::Timestamp;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use rustc_hash::FxHashMap;
+use sqlx::{FromRow, Postgres, Row, Transaction, postgres::PgRow, query, query_as, query_scalar};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::domain::promotions::{
+    data::{
+        PromotionDetails,
+        budgets::Budgets,
+        discounts::MixAndMatchDiscount,
+        slots::{MixAndMatchSlot, MixAndMatchSlotDetails},
+    },
+    records::{MixAndMatchDetailUuid, MixAndMatchSlotUuid, PromotionDetailsRecord, PromotionUuid},
+    repositories::promotions::{
+        budget_numeric_sql_values, budgets_from_sql_values, try_i64_from_u64, try_u32_from_i64,
+        try_u64_from_i64,
+    },
+};
+
+const COLUMN_DISCOUNT_KIND: &str = "discount_kind";
+const COLUMN_DISCOUNT_PERCENTAGE: &str = "discount_percentage";
+const COLUMN_DISCOUNT_AMOUNT: &str = "discount_amount";
+const COLUMN_POSITION: &str = "position";
+const COLUMN_MIN_ITEMS: &str = "min_items";
+const COLUMN_MAX_ITEMS: &str = "max_items";
+
+const CREATE_MIX_AND_MATCH_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/mix_and_match/create_mix_and_match_promotion_detail.sql");
+
+const UPDATE_MIX_AND_MATCH_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/mix_and_match/update_mix_and_match_promotion_detail.sql");
+
+const LIST_MIX_AND_MATCH_PROMOTION_DETAILS_SQL: &str =
+    include_str!("../../sql/mix_and_match/list_mix_and_match_promotion_details.sql");
+
+const CREATE_MIX_AND_MATCH_SLOT_SQL: &str =
+    include_str!("../../sql/mix_and_match/create_mix_and_match_slot.sql");
+
+const LIST_MIX_AND_MATCH_SLOTS_SQL: &str =
+    include_str!("../../sql/mix_and_match/list_mix_and_match_slots.sql");
+
+struct MixAndMatchPromotionRow(PromotionDetailsRecord);
+
+#[tracing::instrument(
+    name = "promotions.mix_and_match_repository.insert_mix_and_match_promotion",
+    skip(tx, budgets, slots, discount),
+    fields(
+        promotion_uuid = %uuid,
+        slot_count = slots.len(),
+        discount_type = %discount.to_str(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn insert_mix_and_match_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    slots: &[MixAndMatchSlot],
+    discount: &MixAndMatchDiscount,
+) -> Result<(), sqlx::Error> {
+    let detail_uuid = MixAndMatchDetailUuid::from_uuid(uuid.into_uuid());
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+    let (discount_percentage, discount_amount) = discount_numeric_sql_values(discount)?;
+
+    query(CREATE_MIX_AND_MATCH_PROMOTION_DETAIL_SQL)
+        .bind(detail_uuid.into_uuid())
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .bind(discount.to_str())
+        .bind(discount_percentage)
+        .bind(discount_amount)
+        .execute(&mut **tx)
+        .await?;
+
+    insert_mix_and_match_slots(tx, detail_uuid, slots).await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        slot_count = slots.len(),
+        discount_type = %discount.to_str(),
+        "inserted mix and match promotion detail"
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "promotions.mix_and_match_repository.update_mix_and_match_promotion",
+    skip(tx, budgets, slots, discount),
+    fields(
+        promotion_uuid = %uuid,
+        slot_count = slots.len(),
+        discount_type = %discount.to_str(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn update_mix_and_match_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    slots: &[MixAndMatchSlot],
+    discount: &MixAndMatchDiscount,
+) -> Result<MixAndMatchDetailUuid, sqlx::Error> {
+    let new_detail_uuid = MixAndMatchDetailUuid::new();
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+    let (discount_percentage, discount_amount) = discount_numeric_sql_values(discount)?;
+
+    let returned_uuid: Uuid = query_scalar(UPDATE_MIX_AND_MATCH_PROMOTION_DETAIL_SQL)
+        .bind(uuid.into_uuid())
+        .bind(new_detail_uuid.into_uuid())
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .bind(discount.to_str())
+        .bind(discount_percentage)
+        .bind(discount_amount)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let detail_uuid = MixAndMatchDetailUuid::from_uuid(returned_uuid);
+
+    insert_mix_and_match_slots(tx, detail_uuid, slots).await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        detail_uuid = %detail_uuid,
+        "updated mix and match promotion detail"
+    );
+
+    Ok(detail_uuid)
+}
+
+#[tracing::instrument(
+    name = "promotions.mix_and_match_repository.list_mix_and_match_promotions",
+    skip(tx),
+    fields(point_in_time = %point_in_time),
+    err
+)]
+pub(crate) async fn list_mix_and_match_promotions(
+    tx: &mut Transaction<'_, Postgres>,
+    point_in_time: Timestamp,
+    promotion: Option<PromotionUuid>,
+) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+    let mut promotions: Vec<PromotionDetailsRecord> =
+        query_as::<Postgres, MixAndMatchPromotionRow>(LIST_MIX_AND_MATCH_PROMOTION_DETAILS_SQL)
+            .bind(SqlxTimestamp::from(point_in_time))
+            .bind(promotion.map(PromotionUuid::into_uuid))
+            .fetch_all(&mut **tx)
+            .await?
+            .into_iter()
+            .map(|row| row.0)
+            .collect();
+
+    let detail_uuids: Vec<Uuid> = promotions
+        .iter()
+        .map(|promotion| promotion.details.uuid())
+        .collect();
+
+    let mut slots = list_mix_and_match_slots(tx, &detail_uuids).await?;
+
+    for promotion in &mut promotions {
+        if let PromotionDetails::MixAndMatch {
+            uuid,
+            slots: promotion_slots,
+            ..
+        } = &mut promotion.details
+        {
+            *promotion_slots = slots.remove(uuid).unwrap_or_default();
+        }
+    }
+
+    debug!(
+        promotion_count = promotions.len(),
+        "queried mix and match promotion details"
+    );
+
+    Ok(promotions)
+}
+
+/// List the slot UUIDs of a mix and match detail version, in slot order.
+#[tracing::instrument(
+    name = "promotions.mix_and_match_repository.list_mix_and_match_slot_uuids",
+    skip(tx),
+    fields(detail_uuid = %detail_uuid),
+    err
+)]
+pub(crate) async fn list_mix_and_match_slot_uuids(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuid: MixAndMatchDetailUuid,
+) -> Result<Vec<MixAndMatchSlotUuid>, sqlx::Error> {
+    let slots = list_mix_and_match_slots(tx, &[detail_uuid.into_uuid()]).await?;
+
+    Ok(slots
+        .into_values()
+        .flatten()
+        .map(|slot| slot.uuid)
+        .collect())
+}
+
+async fn insert_mix_and_match_slots(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuid: MixAndMatchDetailUuid,
+    slots: &[MixAndMatchSlot],
+) -> Result<(), sqlx::Error> {
+    for (position, slot) in slots.iter().enumerate() {
+        let position = i32::try_from(position).map_err(|e| sqlx::Error::ColumnDecode {
+            index: COLUMN_POSITION.to_string(),
+            source: Box::new(e),
+        })?;
+
+        query(CREATE_MIX_AND_MATCH_SLOT_SQL)
+            .bind(MixAndMatchSlotUuid::new().into_uuid())
+            .bind(detail_uuid.into_uuid())
+            .bind(position)
+            .bind(i64::from(slot.min))
+            .bind(slot.max.map(i64::from))
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    debug!(
+        detail_uuid = %detail_uuid,
+        slot_count = slots.len(),
+        "inserted mix and match slots"
+    );
+
+    Ok(())
+}
+
+async fn list_mix_and_match_slots(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuids: &[Uuid],
+) -> Result<FxHashMap<MixAndMatchDetailUuid, Vec<MixAndMatchSlotDetails>>, sqlx::Error> {
+    let rows: Vec<(Uuid, Uuid, i64, Option<i64>)> = query_as(LIST_MIX_AND_MATCH_SLOTS_SQL)
+        .bind(detail_uuids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    let mut slots: FxHashMap<MixAndMatchDetailUuid, Vec<MixAndMatchSlotDetails>> =
+        FxHashMap::default();
+
+    for (uuid, detail_uuid, min_items, max_items) in rows {
+        slots
+            .entry(MixAndMatchDetailUuid::from_uuid(detail_uuid))
+            .or_default()
+            .push(MixAndMatchSlotDetails {
+                uuid: MixAndMatchSlotUuid::from_uuid(uuid),
+                slot: MixAndMatchSlot {
+                    qualification: None,
+                    min: try_u32_from_i64(min_items, COLUMN_MIN_ITEMS)?,
+                    max: max_items
+                        .map(|max| try_u32_from_i64(max, COLUMN_MAX_ITEMS))
+                        .transpose()?,
+                },
+            });
+    }
+
+    Ok(slots)
+}
+
+fn discount_numeric_sql_values(
+    discount: &MixAndMatchDiscount,
+) -> Result<(Option<i64>, Option<i64>), sqlx::Error> {
+    match discount {
+        MixAndMatchDiscount::PercentAllItems { percentage }
+        | MixAndMatchDiscount::PercentCheapest { percentage } => {
+            Ok((Some(i64::from(*percentage)), None))
+        }
+        MixAndMatchDiscount::AmountOffEachItem { amount }
+        | MixAndMatchDiscount::FixedPriceEachItem { amount }
+        | MixAndMatchDiscount::AmountOffTotal { amount }
+        | MixAndMatchDiscount::FixedTotal { amount }
+        | MixAndMatchDiscount::FixedCheapest { amount } => Ok((
+            None,
+            Some(try_i64_from_u64(*amount, COLUMN_DISCOUNT_AMOUNT)?),
+        )),
+    }
+}
+
+fn discount_from_sql_values(
+    kind: &str,
+    discount_percentage: Option<i64>,
+    discount_amount: Option<i64>,
+) -> Result<MixAndMatchDiscount, sqlx::Error> {
+    let percentage = || {
+        discount_percentage
+            .ok_or_else(|| unexpected_discount(kind))
+            .and_then(|percentage| {
+                u16::try_from(percentage).map_err(|e| sqlx::Error::ColumnDecode {
+                    index: COLUMN_DISCOUNT_PERCENTAGE.to_string(),
+                    source: Box::new(e),
+                })
+            })
+    };
+
+    let amount = || {
+        discount_amount
+            .ok_or_else(|| unexpected_discount(kind))
+            .and_then(|amount| try_u64_from_i64(amount, COLUMN_DISCOUNT_AMOUNT))
+    };
+
+    match kind {
+        "percent_all_items" => Ok(MixAndMatchDiscount::PercentAllItems {
+            percentage: percentage()?,
+        }),
+        "amount_off_each_item" => Ok(MixAndMatchDiscount::AmountOffEachItem { amount: amount()? }),
+        "fixed_price_each_item" => {
+            Ok(MixAndMatchDiscount::FixedPriceEachItem { amount: amount()? })
+        }
+        "amount_off_total" => Ok(MixAndMatchDiscount::AmountOffTotal { amount: amount()? }),
+        "fixed_total" => Ok(MixAndMatchDiscount::FixedTotal { amount: amount()? }),
+        "percent_cheapest" => Ok(MixAndMatchDiscount::PercentCheapest {
+            percentage: percentage()?,
+        }),
+        "fixed_cheapest" => Ok(MixAndMatchDiscount::FixedCheapest { amount: amount()? }),
+        _ => Err(unexpected_discount(kind)),
+    }
+}
+
+fn unexpected_discount(kind: &str) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: COLUMN_DISCOUNT_KIND.to_string(),
+        source: format!("unexpected discount kind `{kind}` for stored values").into(),
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for MixAndMatchPromotionRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let budgets = budgets_from_sql_values(
+            row.try_get("redemption_budget")?,
+            row.try_get("monetary_budget")?,
+        )?;
+
+        let discount = discount_from_sql_values(
+            row.try_get(COLUMN_DISCOUNT_KIND)?,
+            row.try_get(COLUMN_DISCOUNT_PERCENTAGE)?,
+            row.try_get(COLUMN_DISCOUNT_AMOUNT)?,
+        )?;
+
+        Ok(Self(PromotionDetailsRecord {
+            uuid: PromotionUuid::from_uuid(row.try_get("uuid")?),
+            details: PromotionDetails::MixAndMatch {
+                uuid: MixAndMatchDetailUuid::from_uuid(row.try_get("detail_uuid")?),
+                budgets,
+                slots: Vec::new(),
+                discount,
+            },
+            created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
+            updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
+            deleted_at: row
+                .try_get::<Option<SqlxTimestamp>, _>("deleted_at")?
+                .map(SqlxTimestamp::to_jiff),
+        }))
+    }
+}
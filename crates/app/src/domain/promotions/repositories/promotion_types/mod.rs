@@ -1,3 +1,6 @@
 //! Promotions Repository Promotion Types
 
 pub(super) mod direct;
+pub(super) mod mix_and_match;
+pub(super) mod positional;
+pub(super) mod tiered_threshold;
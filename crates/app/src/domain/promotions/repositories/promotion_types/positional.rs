@@ -0,0 +1,206 @@
+//! Positional Discount Promotions
+
+use jiff::Timestamp;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use smallvec::SmallVec;
+use sqlx::{FromRow, Postgres, Row, Transaction, postgres::PgRow, query, query_as, query_scalar};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::domain::promotions::{
+    data::{PromotionDetails, budgets::Budgets, discounts::SimpleDiscount},
+    records::{PositionalDiscountDetailUuid, PromotionDetailsRecord, PromotionUuid},
+    repositories::promotions::{
+        budget_numeric_sql_values, budgets_from_sql_values, discount_from_sql_values,
+        discount_numeric_sql_values,
+    },
+};
+
+const COLUMN_SIZE: &str = "size";
+const COLUMN_POSITIONS: &str = "positions";
+
+const CREATE_POSITIONAL_DISCOUNT_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/positional/create_positional_discount_promotion_detail.sql");
+
+const UPDATE_POSITIONAL_DISCOUNT_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/positional/update_positional_discount_promotion_detail.sql");
+
+const LIST_POSITIONAL_DISCOUNT_PROMOTION_DETAILS_SQL: &str =
+    include_str!("../../sql/positional/list_positional_discount_promotion_details.sql");
+
+struct PositionalDiscountPromotionRow(PromotionDetailsRecord);
+
+#[tracing::instrument(
+    name = "promotions.positional_repository.insert_positional_discount_promotion",
+    skip(tx, budgets, positions, discount),
+    fields(
+        promotion_uuid = %uuid,
+        size,
+        position_count = positions.len(),
+        discount_type = %discount.to_str(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn insert_positional_discount_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    size: u16,
+    positions: &[u16],
+    discount: &SimpleDiscount,
+) -> Result<(), sqlx::Error> {
+    let db_uuid = uuid.into_uuid();
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+    let (discount_percentage, discount_amount) = discount_numeric_sql_values(discount)?;
+
+    query(CREATE_POSITIONAL_DISCOUNT_PROMOTION_DETAIL_SQL)
+        .bind(db_uuid)
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .bind(i32::from(size))
+        .bind(positions_sql_values(positions))
+        .bind(discount.to_str())
+        .bind(discount_percentage)
+        .bind(discount_amount)
+        .execute(&mut **tx)
+        .await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        size,
+        position_count = positions.len(),
+        discount_type = %discount.to_str(),
+        "inserted positional discount promotion detail"
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "promotions.positional_repository.update_positional_discount_promotion",
+    skip(tx, budgets, positions, discount),
+    fields(
+        promotion_uuid = %uuid,
+        size,
+        position_count = positions.len(),
+        discount_type = %discount.to_str(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn update_positional_discount_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    size: u16,
+    positions: &[u16],
+    discount: &SimpleDiscount,
+) -> Result<PositionalDiscountDetailUuid, sqlx::Error> {
+    let new_detail_uuid = PositionalDiscountDetailUuid::new();
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+    let (discount_percentage, discount_amount) = discount_numeric_sql_values(discount)?;
+
+    let returned_uuid: Uuid = query_scalar(UPDATE_POSITIONAL_DISCOUNT_PROMOTION_DETAIL_SQL)
+        .bind(uuid.into_uuid())
+        .bind(new_detail_uuid.into_uuid())
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .bind(i32::from(size))
+        .bind(positions_sql_values(positions))
+        .bind(discount.to_str())
+        .bind(discount_percentage)
+        .bind(discount_amount)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        detail_uuid = %returned_uuid,
+        "updated positional discount promotion detail"
+    );
+
+    Ok(PositionalDiscountDetailUuid::from_uuid(returned_uuid))
+}
+
+#[tracing::instrument(
+    name = "promotions.positional_repository.list_positional_discount_promotions",
+    skip(tx),
+    fields(point_in_time = %point_in_time),
+    err
+)]
+pub(crate) async fn list_positional_discount_promotions(
+    tx: &mut Transaction<'_, Postgres>,
+    point_in_time: Timestamp,
+    promotion: Option<PromotionUuid>,
+) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+    let rows = query_as::<Postgres, PositionalDiscountPromotionRow>(
+        LIST_POSITIONAL_DISCOUNT_PROMOTION_DETAILS_SQL,
+    )
+    .bind(SqlxTimestamp::from(point_in_time))
+    .bind(promotion.map(PromotionUuid::into_uuid))
+    .fetch_all(&mut **tx)
+    .await?;
+
+    debug!(
+        promotion_count = rows.len(),
+        "queried positional discount promotion details"
+    );
+
+    Ok(rows.into_iter().map(|row| row.0).collect())
+}
+
+fn positions_sql_values(positions: &[u16]) -> Vec<i32> {
+    positions.iter().copied().map(i32::from).collect()
+}
+
+fn try_u16_from_i32(value: i32, column: &'static str) -> Result<u16, sqlx::Error> {
+    u16::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
+
+impl<'r> FromRow<'r, PgRow> for PositionalDiscountPromotionRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let budgets = budgets_from_sql_values(
+            row.try_get("redemption_budget")?,
+            row.try_get("monetary_budget")?,
+        )?;
+
+        let discount = discount_from_sql_values(
+            row.try_get("discount_kind")?,
+            row.try_get("discount_percentage")?,
+            row.try_get("discount_amount")?,
+        )?;
+
+        let size = try_u16_from_i32(row.try_get(COLUMN_SIZE)?, COLUMN_SIZE)?;
+
+        let positions = row
+            .try_get::<Vec<i32>, _>(COLUMN_POSITIONS)?
+            .into_iter()
+            .map(|position| try_u16_from_i32(position, COLUMN_POSITIONS))
+            .collect::<Result<SmallVec<[u16; 5]>, _>>()?;
+
+        Ok(Self(PromotionDetailsRecord {
+            uuid: PromotionUuid::from_uuid(row.try_get("uuid")?),
+            details: PromotionDetails::PositionalDiscount {
+                uuid: PositionalDiscountDetailUuid::from_uuid(row.try_get("detail_uuid")?),
+                budgets,
+                size,
+                positions,
+                discount,
+                qualification: None,
+            },
+            created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
+            updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
+            deleted_at: row
+                .try_get::<Option<SqlxTimestamp>, _>("deleted_at")?
+                .map(SqlxTimestamp::to_jiff),
+        }))
+    }
+}
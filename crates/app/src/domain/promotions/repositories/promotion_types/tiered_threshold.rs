@@ -0,0 +1,445 @@
+//! Tiered Threshold Promotions
+
+use jiff::Timestamp;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use rustc_hash::FxHashMap;
+use sqlx::{FromRow, Postgres, Row, Transaction, postgres::PgRow, query, query_as, query_scalar};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::domain::promotions::{
+    data::{
+        PromotionDetails,
+        budgets::Budgets,
+        discounts::ThresholdDiscount,
+        tiers::{ThresholdTier, ThresholdTierDetails, TierThreshold},
+    },
+    records::{
+        PromotionDetailsRecord, PromotionUuid, ThresholdTierUuid, TierQualificationUuid,
+        TieredThresholdDetailUuid,
+    },
+    repositories::promotions::{
+        budget_numeric_sql_values, budgets_from_sql_values, try_i64_from_u64, try_u32_from_i64,
+        try_u64_from_i64,
+    },
+};
+
+const COLUMN_DISCOUNT_KIND: &str = "discount_kind";
+const COLUMN_DISCOUNT_PERCENTAGE: &str = "discount_percentage";
+const COLUMN_DISCOUNT_AMOUNT: &str = "discount_amount";
+const COLUMN_POSITION: &str = "position";
+const COLUMN_LOWER_MONETARY_THRESHOLD: &str = "lower_monetary_threshold";
+const COLUMN_LOWER_ITEM_COUNT_THRESHOLD: &str = "lower_item_count_threshold";
+const COLUMN_UPPER_MONETARY_THRESHOLD: &str = "upper_monetary_threshold";
+const COLUMN_UPPER_ITEM_COUNT_THRESHOLD: &str = "upper_item_count_threshold";
+
+const CREATE_TIERED_THRESHOLD_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/tiered_threshold/create_tiered_threshold_promotion_detail.sql");
+
+const UPDATE_TIERED_THRESHOLD_PROMOTION_DETAIL_SQL: &str =
+    include_str!("../../sql/tiered_threshold/update_tiered_threshold_promotion_detail.sql");
+
+const LIST_TIERED_THRESHOLD_PROMOTION_DETAILS_SQL: &str =
+    include_str!("../../sql/tiered_threshold/list_tiered_threshold_promotion_details.sql");
+
+const CREATE_THRESHOLD_TIER_SQL: &str =
+    include_str!("../../sql/tiered_threshold/create_threshold_tier.sql");
+
+const LIST_THRESHOLD_TIERS_SQL: &str =
+    include_str!("../../sql/tiered_threshold/list_threshold_tiers.sql");
+
+struct TieredThresholdPromotionRow(PromotionDetailsRecord);
+
+struct ThresholdTierRow {
+    detail_uuid: TieredThresholdDetailUuid,
+    tier: ThresholdTierDetails,
+}
+
+#[tracing::instrument(
+    name = "promotions.tiered_threshold_repository.insert_tiered_threshold_promotion",
+    skip(tx, budgets, tiers),
+    fields(
+        promotion_uuid = %uuid,
+        tier_count = tiers.len(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn insert_tiered_threshold_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    tiers: &[ThresholdTier],
+) -> Result<(), sqlx::Error> {
+    let detail_uuid = TieredThresholdDetailUuid::from_uuid(uuid.into_uuid());
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+
+    query(CREATE_TIERED_THRESHOLD_PROMOTION_DETAIL_SQL)
+        .bind(detail_uuid.into_uuid())
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .execute(&mut **tx)
+        .await?;
+
+    insert_threshold_tiers(tx, detail_uuid, tiers).await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        tier_count = tiers.len(),
+        "inserted tiered threshold promotion detail"
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "promotions.tiered_threshold_repository.update_tiered_threshold_promotion",
+    skip(tx, budgets, tiers),
+    fields(
+        promotion_uuid = %uuid,
+        tier_count = tiers.len(),
+        has_redemption_budget = budgets.redemptions.is_some(),
+        has_monetary_budget = budgets.monetary.is_some()
+    ),
+    err
+)]
+pub(crate) async fn update_tiered_threshold_promotion(
+    tx: &mut Transaction<'_, Postgres>,
+    uuid: PromotionUuid,
+    budgets: &Budgets,
+    tiers: &[ThresholdTier],
+) -> Result<TieredThresholdDetailUuid, sqlx::Error> {
+    let new_detail_uuid = TieredThresholdDetailUuid::new();
+
+    let (redemption_budget, monetary_budget) = budget_numeric_sql_values(budgets)?;
+
+    let returned_uuid: Uuid = query_scalar(UPDATE_TIERED_THRESHOLD_PROMOTION_DETAIL_SQL)
+        .bind(uuid.into_uuid())
+        .bind(new_detail_uuid.into_uuid())
+        .bind(redemption_budget)
+        .bind(monetary_budget)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let detail_uuid = TieredThresholdDetailUuid::from_uuid(returned_uuid);
+
+    insert_threshold_tiers(tx, detail_uuid, tiers).await?;
+
+    debug!(
+        promotion_uuid = %uuid,
+        detail_uuid = %detail_uuid,
+        "updated tiered threshold promotion detail"
+    );
+
+    Ok(detail_uuid)
+}
+
+#[tracing::instrument(
+    name = "promotions.tiered_threshold_repository.list_tiered_threshold_promotions",
+    skip(tx),
+    fields(point_in_time = %point_in_time),
+    err
+)]
+pub(crate) async fn list_tiered_threshold_promotions(
+    tx: &mut Transaction<'_, Postgres>,
+    point_in_time: Timestamp,
+    promotion: Option<PromotionUuid>,
+) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+    let mut promotions: Vec<PromotionDetailsRecord> =
+        query_as::<Postgres, TieredThresholdPromotionRow>(
+            LIST_TIERED_THRESHOLD_PROMOTION_DETAILS_SQL,
+        )
+        .bind(SqlxTimestamp::from(point_in_time))
+        .bind(promotion.map(PromotionUuid::into_uuid))
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|row| row.0)
+        .collect();
+
+    let detail_uuids: Vec<Uuid> = promotions
+        .iter()
+        .map(|promotion| promotion.details.uuid())
+        .collect();
+
+    let mut tiers = list_threshold_tiers(tx, &detail_uuids).await?;
+
+    for promotion in &mut promotions {
+        if let PromotionDetails::TieredThreshold {
+            uuid,
+            tiers: promotion_tiers,
+            ..
+        } = &mut promotion.details
+        {
+            *promotion_tiers = tiers.remove(uuid).unwrap_or_default();
+        }
+    }
+
+    debug!(
+        promotion_count = promotions.len(),
+        "queried tiered threshold promotion details"
+    );
+
+    Ok(promotions)
+}
+
+/// List the qualification UUIDs of a tiered threshold detail version: the
+/// contribution then discount UUID of each tier, in tier order.
+#[tracing::instrument(
+    name = "promotions.tiered_threshold_repository.list_tier_qualification_uuids",
+    skip(tx),
+    fields(detail_uuid = %detail_uuid),
+    err
+)]
+pub(crate) async fn list_tier_qualification_uuids(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuid: TieredThresholdDetailUuid,
+) -> Result<Vec<TierQualificationUuid>, sqlx::Error> {
+    let tiers = list_threshold_tiers(tx, &[detail_uuid.into_uuid()]).await?;
+
+    Ok(tiers
+        .into_values()
+        .flatten()
+        .flat_map(|tier| [tier.contribution_uuid, tier.discount_uuid])
+        .collect())
+}
+
+async fn insert_threshold_tiers(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuid: TieredThresholdDetailUuid,
+    tiers: &[ThresholdTier],
+) -> Result<(), sqlx::Error> {
+    for (position, tier) in tiers.iter().enumerate() {
+        let position = i32::try_from(position).map_err(|e| sqlx::Error::ColumnDecode {
+            index: COLUMN_POSITION.to_string(),
+            source: Box::new(e),
+        })?;
+
+        let (lower_monetary, lower_items) =
+            threshold_sql_values(Some(&tier.lower_threshold), COLUMN_LOWER_MONETARY_THRESHOLD)?;
+
+        let (upper_monetary, upper_items) = threshold_sql_values(
+            tier.upper_threshold.as_ref(),
+            COLUMN_UPPER_MONETARY_THRESHOLD,
+        )?;
+
+        let (discount_percentage, discount_amount) = discount_numeric_sql_values(&tier.discount)?;
+
+        query(CREATE_THRESHOLD_TIER_SQL)
+            .bind(ThresholdTierUuid::new().into_uuid())
+            .bind(detail_uuid.into_uuid())
+            .bind(position)
+            .bind(TierQualificationUuid::new().into_uuid())
+            .bind(TierQualificationUuid::new().into_uuid())
+            .bind(lower_monetary)
+            .bind(lower_items)
+            .bind(upper_monetary)
+            .bind(upper_items)
+            .bind(tier.discount.to_str())
+            .bind(discount_percentage)
+            .bind(discount_amount)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    debug!(
+        detail_uuid = %detail_uuid,
+        tier_count = tiers.len(),
+        "inserted threshold tiers"
+    );
+
+    Ok(())
+}
+
+async fn list_threshold_tiers(
+    tx: &mut Transaction<'_, Postgres>,
+    detail_uuids: &[Uuid],
+) -> Result<FxHashMap<TieredThresholdDetailUuid, Vec<ThresholdTierDetails>>, sqlx::Error> {
+    let rows = query_as::<Postgres, ThresholdTierRow>(LIST_THRESHOLD_TIERS_SQL)
+        .bind(detail_uuids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    let mut tiers: FxHashMap<TieredThresholdDetailUuid, Vec<ThresholdTierDetails>> =
+        FxHashMap::default();
+
+    for row in rows {
+        tiers.entry(row.detail_uuid).or_default().push(row.tier);
+    }
+
+    Ok(tiers)
+}
+
+fn threshold_sql_values(
+    threshold: Option<&TierThreshold>,
+    monetary_column: &'static str,
+) -> Result<(Option<i64>, Option<i64>), sqlx::Error> {
+    let Some(threshold) = threshold else {
+        return Ok((None, None));
+    };
+
+    Ok((
+        threshold
+            .monetary
+            .map(|monetary| try_i64_from_u64(monetary, monetary_column))
+            .transpose()?,
+        threshold.items.map(i64::from),
+    ))
+}
+
+fn threshold_from_sql_values(
+    monetary: Option<i64>,
+    items: Option<i64>,
+    monetary_column: &'static str,
+    items_column: &'static str,
+) -> Result<TierThreshold, sqlx::Error> {
+    Ok(TierThreshold {
+        monetary: monetary
+            .map(|monetary| try_u64_from_i64(monetary, monetary_column))
+            .transpose()?,
+        items: items
+            .map(|items| try_u32_from_i64(items, items_column))
+            .transpose()?,
+    })
+}
+
+fn discount_numeric_sql_values(
+    discount: &ThresholdDiscount,
+) -> Result<(Option<i64>, Option<i64>), sqlx::Error> {
+    match discount {
+        ThresholdDiscount::PercentEachItem { percentage }
+        | ThresholdDiscount::PercentCheapest { percentage } => {
+            Ok((Some(i64::from(*percentage)), None))
+        }
+        ThresholdDiscount::AmountOffEachItem { amount }
+        | ThresholdDiscount::FixedPriceEachItem { amount }
+        | ThresholdDiscount::AmountOffTotal { amount }
+        | ThresholdDiscount::FixedTotal { amount }
+        | ThresholdDiscount::FixedCheapest { amount } => Ok((
+            None,
+            Some(try_i64_from_u64(*amount, COLUMN_DISCOUNT_AMOUNT)?),
+        )),
+    }
+}
+
+fn discount_from_sql_values(
+    kind: &str,
+    discount_percentage: Option<i64>,
+    discount_amount: Option<i64>,
+) -> Result<ThresholdDiscount, sqlx::Error> {
+    let percentage = || {
+        discount_percentage
+            .ok_or_else(|| unexpected_discount(kind))
+            .and_then(|percentage| {
+                u16::try_from(percentage).map_err(|e| sqlx::Error::ColumnDecode {
+                    index: COLUMN_DISCOUNT_PERCENTAGE.to_string(),
+                    source: Box::new(e),
+                })
+            })
+    };
+
+    let amount = || {
+        discount_amount
+            .ok_or_else(|| unexpected_discount(kind))
+            .and_then(|amount| try_u64_from_i64(amount, COLUMN_DISCOUNT_AMOUNT))
+    };
+
+    match kind {
+        "percent_each_item" => Ok(ThresholdDiscount::PercentEachItem {
+            percentage: percentage()?,
+        }),
+        "amount_off_each_item" => Ok(ThresholdDiscount::AmountOffEachItem { amount: amount()? }),
+        "fixed_price_each_item" => Ok(ThresholdDiscount::FixedPriceEachItem { amount: amount()? }),
+        "amount_off_total" => Ok(ThresholdDiscount::AmountOffTotal { amount: amount()? }),
+        "fixed_total" => Ok(ThresholdDiscount::FixedTotal { amount: amount()? }),
+        "percent_cheapest" => Ok(ThresholdDiscount::PercentCheapest {
+            percentage: percentage()?,
+        }),
+        "fixed_cheapest" => Ok(ThresholdDiscount::FixedCheapest { amount: amount()? }),
+        _ => Err(unexpected_discount(kind)),
+    }
+}
+
+fn unexpected_discount(kind: &str) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: COLUMN_DISCOUNT_KIND.to_string(),
+        source: format!("unexpected discount kind `{kind}` for stored values").into(),
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for TieredThresholdPromotionRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let budgets = budgets_from_sql_values(
+            row.try_get("redemption_budget")?,
+            row.try_get("monetary_budget")?,
+        )?;
+
+        Ok(Self(PromotionDetailsRecord {
+            uuid: PromotionUuid::from_uuid(row.try_get("uuid")?),
+            details: PromotionDetails::TieredThreshold {
+                uuid: TieredThresholdDetailUuid::from_uuid(row.try_get("detail_uuid")?),
+                budgets,
+                tiers: Vec::new(),
+            },
+            created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
+            updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
+            deleted_at: row
+                .try_get::<Option<SqlxTimestamp>, _>("deleted_at")?
+                .map(SqlxTimestamp::to_jiff),
+        }))
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for ThresholdTierRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let lower_threshold = threshold_from_sql_values(
+            row.try_get(COLUMN_LOWER_MONETARY_THRESHOLD)?,
+            row.try_get(COLUMN_LOWER_ITEM_COUNT_THRESHOLD)?,
+            COLUMN_LOWER_MONETARY_THRESHOLD,
+            COLUMN_LOWER_ITEM_COUNT_THRESHOLD,
+        )?;
+
+        let upper_monetary: Option<i64> = row.try_get(COLUMN_UPPER_MONETARY_THRESHOLD)?;
+        let upper_items: Option<i64> = row.try_get(COLUMN_UPPER_ITEM_COUNT_THRESHOLD)?;
+
+        // An upper threshold without any requirement is stored as no upper threshold.
+        let upper_threshold = if upper_monetary.is_none() && upper_items.is_none() {
+            None
+        } else {
+            Some(threshold_from_sql_values(
+                upper_monetary,
+                upper_items,
+                COLUMN_UPPER_MONETARY_THRESHOLD,
+                COLUMN_UPPER_ITEM_COUNT_THRESHOLD,
+            )?)
+        };
+
+        let discount = discount_from_sql_values(
+            row.try_get(COLUMN_DISCOUNT_KIND)?,
+            row.try_get(COLUMN_DISCOUNT_PERCENTAGE)?,
+            row.try_get(COLUMN_DISCOUNT_AMOUNT)?,
+        )?;
+
+        Ok(Self {
+            detail_uuid: TieredThresholdDetailUuid::from_uuid(
+                row.try_get("tiered_threshold_promotion_uuid")?,
+            ),
+            tier: ThresholdTierDetails {
+                uuid: ThresholdTierUuid::from_uuid(row.try_get("uuid")?),
+                contribution_uuid: TierQualificationUuid::from_uuid(
+                    row.try_get("contribution_uuid")?,
+                ),
+                discount_uuid: TierQualificationUuid::from_uuid(row.try_get("discount_uuid")?),
+                tier: ThresholdTier {
+                    lower_threshold,
+                    upper_threshold,
+                    contribution_qualification: None,
+                    discount_qualification: None,
+                    discount,
+                },
+            },
+        })
+    }
+}
@@ -1,23 +1,45 @@
 //! Promotions Repository
 
+use jiff::Timestamp;
 use jiff_sqlx::Timestamp as SqlxTimestamp;
-use sqlx::{Postgres, Transaction, query_as};
+use sqlx::{Postgres, Transaction, query, query_as};
 use tracing::debug;
 use uuid::Uuid;
 
 use crate::domain::promotions::{
     data::{NewPromotion, PromotionUpdate, budgets::Budgets, discounts::SimpleDiscount},
-    records::{DirectDiscountDetailUuid, PromotionRecord, PromotionUuid},
-    repositories::promotion_types::direct::{
-        insert_direct_discount_promotion, update_direct_discount_promotion,
+    records::{PromotionDetailUuid, PromotionDetailsRecord, PromotionRecord, PromotionUuid},
+    repositories::{
+        promotion_types::{
+            direct::{
+                insert_direct_discount_promotion, list_direct_discount_promotions,
+                update_direct_discount_promotion,
+            },
+            mix_and_match::{
+                insert_mix_and_match_promotion, list_mix_and_match_promotions,
+                list_mix_and_match_slot_uuids, update_mix_and_match_promotion,
+            },
+            positional::{
+                insert_positional_discount_promotion, list_positional_discount_promotions,
+                update_positional_discount_promotion,
+            },
+            tiered_threshold::{
+                insert_tiered_threshold_promotion, list_tier_qualification_uuids,
+                list_tiered_threshold_promotions, update_tiered_threshold_promotion,
+            },
+        },
+        qualifications::list_qualifications,
     },
 };
 
+const COLUMN_DISCOUNT_KIND: &str = "discount_kind";
+const COLUMN_DISCOUNT_PERCENTAGE: &str = "discount_percentage";
 const COLUMN_DISCOUNT_AMOUNT: &str = "discount_amount";
 const COLUMN_REDEMPTION_BUDGET: &str = "redemption_budget";
 const COLUMN_MONETARY_BUDGET: &str = "monetary_budget";
 
 const CREATE_PROMOTION_SQL: &str = include_str!("../sql/create_promotion.sql");
+const DELETE_PROMOTION_SQL: &str = include_str!("../sql/delete_promotion.sql");
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct PgPromotionsRepository;
@@ -48,6 +70,8 @@ impl PgPromotionsRepository {
         tracing::Span::current().record("promotion_uuid", tracing::field::display(promotion_uuid));
         tracing::Span::current().record("promotion_type", tracing::field::display(promotion_type));
 
+        let record = insert_promotion_record(tx, &promotion_uuid, &promotion).await?;
+
         match &promotion {
             NewPromotion::DirectDiscount {
                 uuid,
@@ -55,15 +79,41 @@ impl PgPromotionsRepository {
                 discount,
                 ..
             } => {
-                let record = insert_promotion_record(tx, uuid, &promotion).await?;
-
                 insert_direct_discount_promotion(tx, *uuid, budgets, discount).await?;
-
-                debug!(promotion_uuid = %record.uuid, promotion_type, "created promotion");
-
-                Ok(record)
+            }
+            NewPromotion::PositionalDiscount {
+                uuid,
+                budgets,
+                size,
+                positions,
+                discount,
+                ..
+            } => {
+                insert_positional_discount_promotion(
+                    tx, *uuid, budgets, *size, positions, discount,
+                )
+                .await?;
+            }
+            NewPromotion::MixAndMatch {
+                uuid,
+                budgets,
+                slots,
+                discount,
+            } => {
+                insert_mix_and_match_promotion(tx, *uuid, budgets, slots, discount).await?;
+            }
+            NewPromotion::TieredThreshold {
+                uuid,
+                budgets,
+                tiers,
+            } => {
+                insert_tiered_threshold_promotion(tx, *uuid, budgets, tiers).await?;
             }
         }
+
+        debug!(promotion_uuid = %record.uuid, promotion_type, "created promotion");
+
+        Ok(record)
     }
 
     #[tracing::instrument(
@@ -80,29 +130,181 @@ impl PgPromotionsRepository {
         tx: &mut Transaction<'_, Postgres>,
         uuid: PromotionUuid,
         promotion: PromotionUpdate,
-    ) -> Result<DirectDiscountDetailUuid, sqlx::Error> {
+    ) -> Result<PromotionDetailUuid, sqlx::Error> {
         let promotion_type = promotion.type_as_str();
 
         tracing::Span::current().record("promotion_type", tracing::field::display(promotion_type));
 
-        match &promotion {
+        let detail_uuid = match &promotion {
             PromotionUpdate::DirectDiscount {
                 budgets, discount, ..
-            } => {
-                let detail_uuid =
-                    update_direct_discount_promotion(tx, uuid, budgets, discount).await?;
+            } => PromotionDetailUuid::DirectDiscount(
+                update_direct_discount_promotion(tx, uuid, budgets, discount).await?,
+            ),
+            PromotionUpdate::PositionalDiscount {
+                budgets,
+                size,
+                positions,
+                discount,
+                ..
+            } => PromotionDetailUuid::PositionalDiscount(
+                update_positional_discount_promotion(tx, uuid, budgets, *size, positions, discount)
+                    .await?,
+            ),
+            PromotionUpdate::MixAndMatch {
+                budgets,
+                slots,
+                discount,
+            } => PromotionDetailUuid::MixAndMatch(
+                update_mix_and_match_promotion(tx, uuid, budgets, slots, discount).await?,
+            ),
+            PromotionUpdate::TieredThreshold { budgets, tiers } => {
+                PromotionDetailUuid::TieredThreshold(
+                    update_tiered_threshold_promotion(tx, uuid, budgets, tiers).await?,
+                )
+            }
+        };
 
-                debug!(
-                    promotion_uuid = %uuid,
-                    detail_uuid = %detail_uuid,
-                    promotion_type,
-                    "updated promotion"
-                );
+        debug!(
+            promotion_uuid = %uuid,
+            detail_uuid = %detail_uuid,
+            promotion_type,
+            "updated promotion"
+        );
+
+        Ok(detail_uuid)
+    }
 
-                Ok(detail_uuid)
+    #[tracing::instrument(
+        name = "promotions.repository.list_active_promotions",
+        skip(self, tx),
+        fields(point_in_time = %point_in_time),
+        err
+    )]
+    pub(crate) async fn list_active_promotions(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        point_in_time: Timestamp,
+    ) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+        let promotions = list_promotions(tx, point_in_time, None).await?;
+
+        debug!(
+            promotion_count = promotions.len(),
+            "queried active promotions"
+        );
+
+        Ok(promotions)
+    }
+
+    /// Get a promotion as it was defined at `point_in_time`.
+    #[tracing::instrument(
+        name = "promotions.repository.get_promotion",
+        skip(self, tx),
+        fields(promotion_uuid = %promotion, point_in_time = %point_in_time),
+        err
+    )]
+    pub(crate) async fn get_promotion(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        promotion: PromotionUuid,
+        point_in_time: Timestamp,
+    ) -> Result<PromotionDetailsRecord, sqlx::Error> {
+        let promotion = list_promotions(tx, point_in_time, Some(promotion))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        debug!(promotion_uuid = %promotion.uuid, "queried promotion");
+
+        Ok(promotion)
+    }
+
+    #[tracing::instrument(
+        name = "promotions.repository.delete_promotion",
+        skip(self, tx),
+        fields(promotion_uuid = %promotion),
+        err
+    )]
+    pub(crate) async fn delete_promotion(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        promotion: PromotionUuid,
+    ) -> Result<u64, sqlx::Error> {
+        let rows_affected = query(DELETE_PROMOTION_SQL)
+            .bind(promotion.into_uuid())
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        debug!(rows_affected, "deleted promotion rows");
+
+        Ok(rows_affected)
+    }
+
+    /// List the UUIDs that a detail version's nested qualifications are stored
+    /// against, in the order of [`NewPromotion::take_nested_qualifications`].
+    #[tracing::instrument(
+        name = "promotions.repository.list_nested_qualification_uuids",
+        skip(self, tx),
+        fields(detail_uuid = %detail_uuid),
+        err
+    )]
+    pub(crate) async fn list_nested_qualification_uuids(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        detail_uuid: PromotionDetailUuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let uuids = match detail_uuid {
+            PromotionDetailUuid::DirectDiscount(_) | PromotionDetailUuid::PositionalDiscount(_) => {
+                Vec::new()
             }
+            PromotionDetailUuid::MixAndMatch(uuid) => list_mix_and_match_slot_uuids(tx, uuid)
+                .await?
+                .into_iter()
+                .map(|uuid| uuid.into_uuid())
+                .collect(),
+            PromotionDetailUuid::TieredThreshold(uuid) => list_tier_qualification_uuids(tx, uuid)
+                .await?
+                .into_iter()
+                .map(|uuid| uuid.into_uuid())
+                .collect(),
+        };
+
+        Ok(uuids)
+    }
+}
+
+/// List the promotions live at `point_in_time`, optionally only the given one,
+/// with their qualifications attached.
+async fn list_promotions(
+    tx: &mut Transaction<'_, Postgres>,
+    point_in_time: Timestamp,
+    promotion: Option<PromotionUuid>,
+) -> Result<Vec<PromotionDetailsRecord>, sqlx::Error> {
+    let mut promotions = list_direct_discount_promotions(tx, point_in_time, promotion).await?;
+
+    promotions.extend(list_positional_discount_promotions(tx, point_in_time, promotion).await?);
+    promotions.extend(list_mix_and_match_promotions(tx, point_in_time, promotion).await?);
+    promotions.extend(list_tiered_threshold_promotions(tx, point_in_time, promotion).await?);
+
+    promotions.sort_by_key(|promotion| (promotion.created_at, promotion.uuid));
+
+    let promotionable_uuids: Vec<Uuid> = promotions
+        .iter_mut()
+        .flat_map(|promotion| promotion.details.qualifications_mut())
+        .map(|(uuid, _)| uuid)
+        .collect();
+
+    let mut qualifications = list_qualifications(tx, &promotionable_uuids).await?;
+
+    for promotion in &mut promotions {
+        for (uuid, qualification) in promotion.details.qualifications_mut() {
+            *qualification = qualifications.remove(&uuid);
         }
     }
+
+    Ok(promotions)
 }
 
 #[tracing::instrument(
@@ -170,9 +372,59 @@ pub(super) fn discount_numeric_sql_values(
     }
 }
 
+pub(super) fn budgets_from_sql_values(
+    redemption_budget: Option<i64>,
+    monetary_budget: Option<i64>,
+) -> Result<Budgets, sqlx::Error> {
+    Ok(Budgets {
+        redemptions: redemption_budget
+            .map(|v| try_u64_from_i64(v, COLUMN_REDEMPTION_BUDGET))
+            .transpose()?,
+        monetary: monetary_budget
+            .map(|v| try_u64_from_i64(v, COLUMN_MONETARY_BUDGET))
+            .transpose()?,
+    })
+}
+
+pub(super) fn discount_from_sql_values(
+    kind: &str,
+    discount_percentage: Option<i64>,
+    discount_amount: Option<i64>,
+) -> Result<SimpleDiscount, sqlx::Error> {
+    match (kind, discount_percentage, discount_amount) {
+        ("percentage_off", Some(percentage), None) => Ok(SimpleDiscount::PercentageOff {
+            percentage: u16::try_from(percentage).map_err(|e| sqlx::Error::ColumnDecode {
+                index: COLUMN_DISCOUNT_PERCENTAGE.to_string(),
+                source: Box::new(e),
+            })?,
+        }),
+        ("amount_off", None, Some(amount)) => Ok(SimpleDiscount::FixedAmountOff {
+            amount: try_u64_from_i64(amount, COLUMN_DISCOUNT_AMOUNT)?,
+        }),
+        _ => Err(sqlx::Error::ColumnDecode {
+            index: COLUMN_DISCOUNT_KIND.to_string(),
+            source: format!("unexpected discount kind `{kind}` for stored values").into(),
+        }),
+    }
+}
+
+pub(super) fn try_u64_from_i64(value: i64, column: &'static str) -> Result<u64, sqlx::Error> {
+    u64::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
+
 pub(super) fn try_i64_from_u64(value: u64, column: &'static str) -> Result<i64, sqlx::Error> {
     i64::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
         index: column.to_string(),
         source: Box::new(e),
     })
 }
+
+pub(super) fn try_u32_from_i64(value: i64, column: &'static str) -> Result<u32, sqlx::Error> {
+    u32::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
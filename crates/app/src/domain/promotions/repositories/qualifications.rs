@@ -1,16 +1,23 @@
 //! Qualifications Repository
 
+use std::collections::hash_map::Entry;
+
+use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
-use sqlx::{Postgres, Transaction, query};
+use sqlx::{Postgres, Transaction, query, query_as};
 use tracing::debug;
+use uuid::Uuid;
 
 use crate::domain::promotions::{
-    data::qualification::{Qualification, QualificationRule},
-    records::{DirectDiscountDetailUuid, PromotionUuid, QualificationRuleUuid, QualificationUuid},
+    data::qualification::{
+        Qualification, QualificationContext, QualificationOp, QualificationRule,
+    },
+    records::{PromotionUuid, QualificationRuleUuid, QualificationUuid},
 };
 
 const CREATE_QUALIFICATION_SQL: &str = include_str!("../sql/create_qualification.sql");
 const CREATE_QUALIFICATION_RULE_SQL: &str = include_str!("../sql/create_qualification_rule.sql");
+const LIST_QUALIFICATIONS_SQL: &str = include_str!("../sql/list_qualifications.sql");
 
 type RuleTag = (QualificationRuleUuid, SmallVec<[String; 3]>);
 type RuleTags = SmallVec<[RuleTag; 5]>;
@@ -39,7 +46,7 @@ impl PgQualificationsRepository {
         &self,
         tx: &mut Transaction<'_, Postgres>,
         promotion_uuid: PromotionUuid,
-        promotionable_uuid: DirectDiscountDetailUuid,
+        promotionable_uuid: Uuid,
         promotionable_type: &'static str,
         qualification: &Qualification,
     ) -> Result<RuleTags, sqlx::Error> {
@@ -82,7 +89,7 @@ impl PgQualificationsRepository {
 async fn insert_qualification(
     tx: &mut Transaction<'_, Postgres>,
     promotion_uuid: PromotionUuid,
-    promotionable_uuid: DirectDiscountDetailUuid,
+    promotionable_uuid: Uuid,
     promotionable_type: &'static str,
     qualification: &Qualification,
     parent_uuid: Option<QualificationUuid>,
@@ -94,7 +101,7 @@ async fn insert_qualification(
     query(CREATE_QUALIFICATION_SQL)
         .bind(qualification_uuid.into_uuid())
         .bind(promotion_uuid.into_uuid())
-        .bind(promotionable_uuid.into_uuid())
+        .bind(promotionable_uuid)
         .bind(qualification.context.as_str())
         .bind(qualification.op.as_str())
         .bind(parent_uuid.map(QualificationUuid::into_uuid))
@@ -201,3 +208,152 @@ async fn insert_qualification_rule(
 
     Ok(())
 }
+
+type QualificationRow = (
+    Uuid,
+    Uuid,
+    Option<Uuid>,
+    String,
+    String,
+    Option<String>,
+    Vec<String>,
+);
+
+/// A qualification node loaded from storage, before its children are attached.
+struct LoadedQualification {
+    promotionable_uuid: Uuid,
+    parent_uuid: Option<Uuid>,
+    qualification: Qualification,
+    children: SmallVec<[Uuid; 2]>,
+}
+
+/// Loads the root qualification of each given promotionable, keyed by promotionable UUID.
+#[tracing::instrument(
+    name = "promotions.qualifications_repository.list_qualifications",
+    skip(tx, promotionable_uuids),
+    fields(promotionable_count = promotionable_uuids.len()),
+    err
+)]
+pub(super) async fn list_qualifications(
+    tx: &mut Transaction<'_, Postgres>,
+    promotionable_uuids: &[Uuid],
+) -> Result<FxHashMap<Uuid, Qualification>, sqlx::Error> {
+    if promotionable_uuids.is_empty() {
+        return Ok(FxHashMap::default());
+    }
+
+    let rows: Vec<QualificationRow> = query_as(LIST_QUALIFICATIONS_SQL)
+        .bind(promotionable_uuids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    let row_count = rows.len();
+
+    let mut loaded: FxHashMap<Uuid, LoadedQualification> = FxHashMap::default();
+    let mut order: Vec<Uuid> = Vec::new();
+
+    for (uuid, promotionable_uuid, parent_uuid, context, op, rule_kind, rule_tags) in rows {
+        let node = match loaded.entry(uuid) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                order.push(uuid);
+
+                entry.insert(LoadedQualification {
+                    promotionable_uuid,
+                    parent_uuid,
+                    qualification: Qualification {
+                        context: context_from_str(&context)?,
+                        op: op_from_str(&op)?,
+                        rules: Vec::new(),
+                    },
+                    children: SmallVec::new(),
+                })
+            }
+        };
+
+        if let Some(kind) = rule_kind {
+            let tags = rule_tags.into_iter().collect();
+
+            node.qualification.rules.push(match kind.as_str() {
+                "has_all" => QualificationRule::HasAll { tags },
+                "has_any" => QualificationRule::HasAny { tags },
+                "has_none" => QualificationRule::HasNone { tags },
+                other => return Err(decode_error("rule_kind", other)),
+            });
+        }
+    }
+
+    for uuid in &order {
+        if let Some(parent_uuid) = loaded.get(uuid).and_then(|node| node.parent_uuid)
+            && let Some(parent) = loaded.get_mut(&parent_uuid)
+        {
+            parent.children.push(*uuid);
+        }
+    }
+
+    let mut roots = FxHashMap::default();
+
+    for uuid in order {
+        let is_root = loaded
+            .get(&uuid)
+            .is_some_and(|node| node.parent_uuid.is_none());
+
+        if is_root && let Some((promotionable_uuid, qualification)) = assemble(&mut loaded, uuid) {
+            roots.insert(promotionable_uuid, qualification);
+        }
+    }
+
+    debug!(
+        row_count,
+        qualification_count = roots.len(),
+        "queried qualifications"
+    );
+
+    Ok(roots)
+}
+
+/// Removes a qualification and its descendants from `loaded`, nesting children as group rules.
+fn assemble(
+    loaded: &mut FxHashMap<Uuid, LoadedQualification>,
+    uuid: Uuid,
+) -> Option<(Uuid, Qualification)> {
+    let LoadedQualification {
+        promotionable_uuid,
+        mut qualification,
+        children,
+        ..
+    } = loaded.remove(&uuid)?;
+
+    for child in children {
+        if let Some((_, nested)) = assemble(loaded, child) {
+            qualification.rules.push(QualificationRule::Group {
+                qualification: nested,
+            });
+        }
+    }
+
+    Some((promotionable_uuid, qualification))
+}
+
+fn context_from_str(value: &str) -> Result<QualificationContext, sqlx::Error> {
+    match value {
+        "primary" => Ok(QualificationContext::Primary),
+        "group" => Ok(QualificationContext::Group),
+        other => Err(decode_error("context", other)),
+    }
+}
+
+fn op_from_str(value: &str) -> Result<QualificationOp, sqlx::Error> {
+    match value {
+        "and" => Ok(QualificationOp::And),
+        "or" => Ok(QualificationOp::Or),
+        other => Err(decode_error("op", other)),
+    }
+}
+
+fn decode_error(column: &str, value: &str) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: format!("unexpected value `{value}`").into(),
+    }
+}
@@ -1,16 +1,21 @@
 //! Promotions Service
 
 use async_trait::async_trait;
+use jiff::Timestamp;
 use mockall::automock;
-use tracing::{Span, info};
+use sqlx::{Postgres, Transaction};
+use tracing::{Span, info, warn};
+use uuid::Uuid;
 
 use crate::{
     database::Db,
     domain::{
         promotions::{
             PromotionsServiceError,
-            data::{NewPromotion, PromotionUpdate},
-            records::{DirectDiscountDetailUuid, PromotionRecord, PromotionUuid},
+            data::{NewPromotion, PromotionUpdate, qualification::Qualification},
+            records::{
+                PromotionDetailUuid, PromotionDetailsRecord, PromotionRecord, PromotionUuid,
+            },
             repositories::{
                 promotions::PgPromotionsRepository, qualifications::PgQualificationsRepository,
             },
@@ -40,8 +45,124 @@ impl PgPromotionsService {
     }
 }
 
+impl PgPromotionsService {
+    /// Store the qualifications of a promotion detail version and tag their rules.
+    ///
+    /// The promotion-level qualification is stored against the detail version,
+    /// each nested slot or tier qualification against its owner. Returns the
+    /// number of tagged rules.
+    async fn create_qualifications(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        promotion_uuid: PromotionUuid,
+        detail_uuid: PromotionDetailUuid,
+        promotionable_type: &'static str,
+        qualification: Option<Qualification>,
+        nested_qualifications: Vec<Option<Qualification>>,
+    ) -> Result<usize, PromotionsServiceError> {
+        let mut owned: Vec<(Uuid, Qualification)> = qualification
+            .map(|qualification| (detail_uuid.into_uuid(), qualification))
+            .into_iter()
+            .collect();
+
+        if !nested_qualifications.is_empty() {
+            let nested_uuids = self
+                .promotions
+                .list_nested_qualification_uuids(tx, detail_uuid)
+                .await?;
+
+            owned.extend(
+                nested_uuids
+                    .into_iter()
+                    .zip(nested_qualifications)
+                    .filter_map(|(uuid, qualification)| {
+                        qualification.map(|qualification| (uuid, qualification))
+                    }),
+            );
+        }
+
+        let mut rule_tag_count = 0;
+
+        for (promotionable_uuid, qualification) in owned {
+            let rule_tags = self
+                .qualifications
+                .create_qualifications(
+                    tx,
+                    promotion_uuid,
+                    promotionable_uuid,
+                    promotionable_type,
+                    &qualification,
+                )
+                .await?;
+
+            rule_tag_count += rule_tags.len();
+
+            let taggables = self.tags.resolve_taggable_tags(tx, &rule_tags).await?;
+
+            self.tags.create_taggables(tx, &taggables).await?;
+        }
+
+        Ok(rule_tag_count)
+    }
+}
+
 #[async_trait]
 impl PromotionsService for PgPromotionsService {
+    #[tracing::instrument(
+        name = "promotions.service.list_promotions",
+        skip(self),
+        fields(tenant_uuid = %tenant, point_in_time = %point_in_time),
+        err
+    )]
+    async fn list_promotions(
+        &self,
+        tenant: TenantUuid,
+        point_in_time: Timestamp,
+    ) -> Result<Vec<PromotionDetailsRecord>, PromotionsServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        let promotions = self
+            .promotions
+            .list_active_promotions(&mut tx, point_in_time)
+            .await?;
+
+        tx.commit().await?;
+
+        info!(promotion_count = promotions.len(), "listed promotions");
+
+        Ok(promotions)
+    }
+
+    #[tracing::instrument(
+        name = "promotions.service.get_promotion",
+        skip(self),
+        fields(
+            tenant_uuid = %tenant,
+            promotion_uuid = %promotion,
+            point_in_time = %point_in_time
+        ),
+        err
+    )]
+    async fn get_promotion(
+        &self,
+        tenant: TenantUuid,
+        promotion: PromotionUuid,
+        point_in_time: Timestamp,
+    ) -> Result<PromotionDetailsRecord, PromotionsServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        let promotion = self
+            .promotions
+            .get_promotion(&mut tx, promotion, point_in_time)
+            .await?;
+
+        tx.commit().await?;
+
+        info!(promotion_uuid = %promotion.uuid, "fetched promotion");
+
+        Ok(promotion)
+    }
+
     #[tracing::instrument(
         name = "promotions.service.create_promotion",
         skip(self, promotion),
@@ -81,30 +202,23 @@ impl PromotionsService for PgPromotionsService {
             tracing::field::display(qualification.is_some()),
         );
 
-        let detail_uuid = DirectDiscountDetailUuid::from_uuid(promotion_uuid.into_uuid());
+        let nested_qualifications = promotion.take_nested_qualifications();
+        let detail_uuid = promotion.detail_uuid();
 
         let record = self.promotions.create_promotion(&mut tx, promotion).await?;
 
-        if let Some(qual) = qualification {
-            let rule_tags = self
-                .qualifications
-                .create_qualifications(
-                    &mut tx,
-                    promotion_uuid,
-                    detail_uuid,
-                    promotionable_type,
-                    &qual,
-                )
-                .await?;
-
-            let rule_tag_count = rule_tags.len();
-
-            span.record("rule_tag_count", tracing::field::display(rule_tag_count));
-
-            let taggables = self.tags.resolve_taggable_tags(&mut tx, &rule_tags).await?;
+        let rule_tag_count = self
+            .create_qualifications(
+                &mut tx,
+                promotion_uuid,
+                detail_uuid,
+                promotionable_type,
+                qualification,
+                nested_qualifications,
+            )
+            .await?;
 
-            self.tags.create_taggables(&mut tx, &taggables).await?;
-        }
+        span.record("rule_tag_count", tracing::field::display(rule_tag_count));
 
         tx.commit().await?;
 
@@ -138,6 +252,7 @@ impl PromotionsService for PgPromotionsService {
 
         let promotionable_type = update.type_as_str();
         let qualification = update.take_qualification();
+        let nested_qualifications = update.take_nested_qualifications();
 
         let span = Span::current();
 
@@ -158,24 +273,50 @@ impl PromotionsService for PgPromotionsService {
 
         span.record("detail_uuid", tracing::field::display(detail_uuid));
 
-        if let Some(qual) = qualification {
-            let rule_tags = self
-                .qualifications
-                .create_qualifications(&mut tx, uuid, detail_uuid, promotionable_type, &qual)
-                .await?;
+        let rule_tag_count = self
+            .create_qualifications(
+                &mut tx,
+                uuid,
+                detail_uuid,
+                promotionable_type,
+                qualification,
+                nested_qualifications,
+            )
+            .await?;
+
+        span.record("rule_tag_count", tracing::field::display(rule_tag_count));
+
+        tx.commit().await?;
+
+        info!(promotion_uuid = %uuid, detail_uuid = %detail_uuid, "updated promotion");
+
+        Ok(())
+    }
 
-            let rule_tag_count = rule_tags.len();
+    #[tracing::instrument(
+        name = "promotions.service.delete_promotion",
+        skip(self),
+        fields(tenant_uuid = %tenant, promotion_uuid = %promotion),
+        err
+    )]
+    async fn delete_promotion(
+        &self,
+        tenant: TenantUuid,
+        promotion: PromotionUuid,
+    ) -> Result<(), PromotionsServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
 
-            span.record("rule_tag_count", tracing::field::display(rule_tag_count));
+        let rows_affected = self.promotions.delete_promotion(&mut tx, promotion).await?;
 
-            let taggables = self.tags.resolve_taggable_tags(&mut tx, &rule_tags).await?;
+        if rows_affected == 0 {
+            warn!("promotion did not exist for deletion");
 
-            self.tags.create_taggables(&mut tx, &taggables).await?;
+            return Err(PromotionsServiceError::NotFound);
         }
 
         tx.commit().await?;
 
-        info!(promotion_uuid = %uuid, detail_uuid = %detail_uuid, "updated promotion");
+        info!(rows_affected, "deleted promotion");
 
         Ok(())
     }
@@ -184,6 +325,21 @@ impl PromotionsService for PgPromotionsService {
 #[automock]
 #[async_trait]
 pub trait PromotionsService: Send + Sync {
+    /// Retrieve the promotions live at `point_in_time`.
+    async fn list_promotions(
+        &self,
+        tenant: TenantUuid,
+        point_in_time: Timestamp,
+    ) -> Result<Vec<PromotionDetailsRecord>, PromotionsServiceError>;
+
+    /// Retrieve a promotion as it was defined at `point_in_time`.
+    async fn get_promotion(
+        &self,
+        tenant: TenantUuid,
+        promotion: PromotionUuid,
+        point_in_time: Timestamp,
+    ) -> Result<PromotionDetailsRecord, PromotionsServiceError>;
+
     async fn create_promotion(
         &self,
         tenant: TenantUuid,
@@ -196,6 +352,13 @@ pub trait PromotionsService: Send + Sync {
         uuid: PromotionUuid,
         update: PromotionUpdate,
     ) -> Result<(), PromotionsServiceError>;
+
+    /// Soft-delete a promotion; it stays readable at earlier points in time.
+    async fn delete_promotion(
+        &self,
+        tenant: TenantUuid,
+        promotion: PromotionUuid,
+    ) -> Result<(), PromotionsServiceError>;
 }
 
 #[cfg(test)]
@@ -209,14 +372,16 @@ mod tests {
     use crate::{
         domain::promotions::{
             data::{
-                NewPromotion, PromotionUpdate,
+                NewPromotion, PromotionDetails, PromotionUpdate,
                 budgets::Budgets,
-                discounts::SimpleDiscount,
+                discounts::{MixAndMatchDiscount, SimpleDiscount, ThresholdDiscount},
                 qualification::{
                     Qualification, QualificationContext, QualificationOp, QualificationRule,
                 },
+                slots::MixAndMatchSlot,
+                tiers::{ThresholdTier, TierThreshold},
             },
-            records::PromotionUuid,
+            records::{PromotionDetailsRecord, PromotionUuid},
         },
         test::TestContext,
     };
@@ -761,4 +926,790 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn create_positional_promotion_inserts_rows_as_expected() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let promotion = ctx
+            .promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::PositionalDiscount {
+                    uuid: PromotionUuid::new(),
+                    budgets: Budgets {
+                        redemptions: Some(3),
+                        monetary: None,
+                    },
+                    size: 3,
+                    positions: smallvec![2],
+                    discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                    qualification: Some(Qualification {
+                        context: QualificationContext::Primary,
+                        op: QualificationOp::And,
+                        rules: vec![QualificationRule::HasAny {
+                            tags: smallvec!["3-for-2".to_string()],
+                        }],
+                    }),
+                },
+            )
+            .await?;
+
+        let detail: (i32, Vec<i32>, Option<i64>, String, Option<i64>) = sqlx::query_as(
+            "SELECT
+               size,
+               positions,
+               redemption_budget,
+               discount_kind::text,
+               discount_percentage
+             FROM positional_discount_promotions
+             WHERE promotion_uuid = $1
+               AND upper_inf(valid_period)",
+        )
+        .bind(promotion.uuid.into_uuid())
+        .fetch_one(ctx.db.pool())
+        .await?;
+
+        assert_eq!(
+            detail,
+            (3, vec![2], Some(3), "percentage_off".to_string(), Some(100))
+        );
+
+        let qualifications: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT promotionable_uuid, promotionable_type::text
+             FROM qualifications
+             WHERE promotion_uuid = $1",
+        )
+        .bind(promotion.uuid.into_uuid())
+        .fetch_all(ctx.db.pool())
+        .await?;
+
+        assert_eq!(
+            qualifications,
+            vec![(promotion.uuid.into_uuid(), "positional".to_string())]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_positional_promotion_position_outside_bundle_returns_invalid_data() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::PositionalDiscount {
+                    uuid: PromotionUuid::new(),
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    size: 2,
+                    positions: smallvec![2],
+                    discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                    qualification: None,
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::InvalidData)),
+            "expected InvalidData, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_positional_promotion_creates_new_detail_row() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::PositionalDiscount {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    size: 2,
+                    positions: smallvec![1],
+                    discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                    qualification: None,
+                },
+            )
+            .await?;
+
+        ctx.promotions
+            .update_promotion(
+                ctx.tenant_uuid,
+                uuid,
+                PromotionUpdate::PositionalDiscount {
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    size: 2,
+                    positions: smallvec![1],
+                    discount: SimpleDiscount::PercentageOff { percentage: 50 },
+                    qualification: None,
+                },
+            )
+            .await?;
+
+        let rows: Vec<(bool, Option<i64>)> = sqlx::query_as(
+            "SELECT upper_inf(valid_period), discount_percentage
+             FROM positional_discount_promotions
+             WHERE promotion_uuid = $1
+             ORDER BY created_at",
+        )
+        .bind(uuid.into_uuid())
+        .fetch_all(ctx.db.pool())
+        .await?;
+
+        assert_eq!(rows, vec![(false, Some(100)), (true, Some(50))]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_promotion_with_different_type_returns_not_found() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::DirectDiscount {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    discount: SimpleDiscount::PercentageOff { percentage: 20 },
+                    qualification: None,
+                },
+            )
+            .await?;
+
+        let result = ctx
+            .promotions
+            .update_promotion(
+                ctx.tenant_uuid,
+                uuid,
+                PromotionUpdate::PositionalDiscount {
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    size: 2,
+                    positions: smallvec![1],
+                    discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                    qualification: None,
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::NotFound)),
+            "expected NotFound, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    fn tagged_slot(tag: &str, min: u32, max: Option<u32>) -> MixAndMatchSlot {
+        MixAndMatchSlot {
+            qualification: Some(Qualification {
+                context: QualificationContext::Primary,
+                op: QualificationOp::And,
+                rules: vec![QualificationRule::HasAny {
+                    tags: smallvec![tag.to_string()],
+                }],
+            }),
+            min,
+            max,
+        }
+    }
+
+    async fn active_promotions(ctx: &TestContext) -> TestResult<Vec<PromotionDetailsRecord>> {
+        let mut tx = ctx.db.begin_test_transaction().await;
+
+        let promotions = PgPromotionsRepository::new()
+            .list_active_promotions(&mut tx, Timestamp::now())
+            .await?;
+
+        Ok(promotions)
+    }
+
+    #[tokio::test]
+    async fn create_mix_and_match_promotion_round_trips_slots() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        let slots = vec![
+            tagged_slot("main", 1, Some(1)),
+            tagged_slot("drink", 1, Some(1)),
+            MixAndMatchSlot {
+                qualification: None,
+                min: 0,
+                max: None,
+            },
+        ];
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::MixAndMatch {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: Some(10),
+                        monetary: None,
+                    },
+                    slots: slots.clone(),
+                    discount: MixAndMatchDiscount::FixedTotal { amount: 5_00 },
+                },
+            )
+            .await?;
+
+        let promotions = active_promotions(&ctx).await?;
+
+        let [promotion] = promotions.as_slice() else {
+            panic!("expected one promotion, got {promotions:?}");
+        };
+
+        let PromotionDetails::MixAndMatch {
+            budgets,
+            slots: stored_slots,
+            discount,
+            ..
+        } = &promotion.details
+        else {
+            panic!(
+                "expected mix and match details, got {:?}",
+                promotion.details
+            );
+        };
+
+        assert_eq!(promotion.uuid, uuid);
+        assert_eq!(
+            *budgets,
+            Budgets {
+                redemptions: Some(10),
+                monetary: None,
+            }
+        );
+        assert_eq!(*discount, MixAndMatchDiscount::FixedTotal { amount: 5_00 });
+        assert_eq!(
+            stored_slots
+                .iter()
+                .map(|details| details.slot.clone())
+                .collect::<Vec<_>>(),
+            slots
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_mix_and_match_promotion_max_below_min_returns_invalid_data() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::MixAndMatch {
+                    uuid: PromotionUuid::new(),
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    slots: vec![tagged_slot("main", 2, Some(1))],
+                    discount: MixAndMatchDiscount::PercentCheapest { percentage: 100 },
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::InvalidData)),
+            "expected InvalidData, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_mix_and_match_promotion_versions_slots() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::MixAndMatch {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    slots: vec![
+                        tagged_slot("main", 1, Some(1)),
+                        tagged_slot("drink", 1, None),
+                    ],
+                    discount: MixAndMatchDiscount::PercentAllItems { percentage: 20 },
+                },
+            )
+            .await?;
+
+        ctx.promotions
+            .update_promotion(
+                ctx.tenant_uuid,
+                uuid,
+                PromotionUpdate::MixAndMatch {
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    slots: vec![tagged_slot("snack", 3, Some(3))],
+                    discount: MixAndMatchDiscount::FixedCheapest { amount: 0 },
+                },
+            )
+            .await?;
+
+        let promotions = active_promotions(&ctx).await?;
+
+        let [promotion] = promotions.as_slice() else {
+            panic!("expected one promotion, got {promotions:?}");
+        };
+
+        let PromotionDetails::MixAndMatch {
+            slots, discount, ..
+        } = &promotion.details
+        else {
+            panic!(
+                "expected mix and match details, got {:?}",
+                promotion.details
+            );
+        };
+
+        assert_eq!(*discount, MixAndMatchDiscount::FixedCheapest { amount: 0 });
+        assert_eq!(
+            slots
+                .iter()
+                .map(|details| details.slot.clone())
+                .collect::<Vec<_>>(),
+            vec![tagged_slot("snack", 3, Some(3))]
+        );
+
+        let slot_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM mix_and_match_slots")
+            .fetch_one(ctx.db.pool())
+            .await?;
+
+        assert_eq!(slot_count, 3, "previous version's slots should be kept");
+
+        Ok(())
+    }
+
+    fn spend_tier(
+        lower: u64,
+        upper: Option<u64>,
+        tag: Option<&str>,
+        percentage: u16,
+    ) -> ThresholdTier {
+        let spend = |amount: u64| TierThreshold {
+            monetary: Some(amount),
+            items: None,
+        };
+
+        ThresholdTier {
+            lower_threshold: spend(lower),
+            upper_threshold: upper.map(spend),
+            contribution_qualification: None,
+            discount_qualification: tag.map(|tag| Qualification {
+                context: QualificationContext::Primary,
+                op: QualificationOp::And,
+                rules: vec![QualificationRule::HasAny {
+                    tags: smallvec![tag.to_string()],
+                }],
+            }),
+            discount: ThresholdDiscount::PercentEachItem { percentage },
+        }
+    }
+
+    #[tokio::test]
+    async fn create_tiered_threshold_promotion_round_trips_tiers() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        let tiers = vec![
+            spend_tier(5_000, Some(10_000), Some("sale"), 10),
+            ThresholdTier {
+                lower_threshold: TierThreshold {
+                    monetary: Some(10_000),
+                    items: Some(3),
+                },
+                upper_threshold: None,
+                contribution_qualification: Some(Qualification {
+                    context: QualificationContext::Primary,
+                    op: QualificationOp::And,
+                    rules: vec![QualificationRule::HasNone {
+                        tags: smallvec!["gift-card".to_string()],
+                    }],
+                }),
+                discount_qualification: None,
+                discount: ThresholdDiscount::AmountOffTotal { amount: 2_500 },
+            },
+        ];
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::TieredThreshold {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: Some(100_000),
+                    },
+                    tiers: tiers.clone(),
+                },
+            )
+            .await?;
+
+        let promotions = active_promotions(&ctx).await?;
+
+        let [promotion] = promotions.as_slice() else {
+            panic!("expected one promotion, got {promotions:?}");
+        };
+
+        let PromotionDetails::TieredThreshold {
+            budgets,
+            tiers: stored_tiers,
+            ..
+        } = &promotion.details
+        else {
+            panic!(
+                "expected tiered threshold details, got {:?}",
+                promotion.details
+            );
+        };
+
+        assert_eq!(promotion.uuid, uuid);
+        assert_eq!(
+            *budgets,
+            Budgets {
+                redemptions: None,
+                monetary: Some(100_000),
+            }
+        );
+        assert_eq!(
+            stored_tiers
+                .iter()
+                .map(|details| details.tier.clone())
+                .collect::<Vec<_>>(),
+            tiers
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_tiered_threshold_promotion_upper_below_lower_returns_invalid_data() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::TieredThreshold {
+                    uuid: PromotionUuid::new(),
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    tiers: vec![spend_tier(10_000, Some(5_000), None, 10)],
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::InvalidData)),
+            "expected InvalidData, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_tiered_threshold_promotion_versions_tiers() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(
+                ctx.tenant_uuid,
+                NewPromotion::TieredThreshold {
+                    uuid,
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    tiers: vec![
+                        spend_tier(5_000, Some(10_000), None, 10),
+                        spend_tier(10_000, None, None, 20),
+                    ],
+                },
+            )
+            .await?;
+
+        let updated_tiers = vec![spend_tier(7_500, None, Some("sale"), 15)];
+
+        ctx.promotions
+            .update_promotion(
+                ctx.tenant_uuid,
+                uuid,
+                PromotionUpdate::TieredThreshold {
+                    budgets: Budgets {
+                        redemptions: Some(100),
+                        monetary: None,
+                    },
+                    tiers: updated_tiers.clone(),
+                },
+            )
+            .await?;
+
+        let promotions = active_promotions(&ctx).await?;
+
+        let [promotion] = promotions.as_slice() else {
+            panic!("expected one promotion, got {promotions:?}");
+        };
+
+        let PromotionDetails::TieredThreshold { budgets, tiers, .. } = &promotion.details else {
+            panic!(
+                "expected tiered threshold details, got {:?}",
+                promotion.details
+            );
+        };
+
+        assert_eq!(budgets.redemptions, Some(100));
+        assert_eq!(
+            tiers
+                .iter()
+                .map(|details| details.tier.clone())
+                .collect::<Vec<_>>(),
+            updated_tiers
+        );
+
+        let tier_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM threshold_tiers")
+            .fetch_one(ctx.db.pool())
+            .await?;
+
+        assert_eq!(tier_count, 3, "previous version's tiers should be kept");
+
+        Ok(())
+    }
+
+    fn direct_percentage_off(uuid: PromotionUuid, percentage: u16) -> NewPromotion {
+        NewPromotion::DirectDiscount {
+            uuid,
+            budgets: Budgets {
+                redemptions: None,
+                monetary: None,
+            },
+            discount: SimpleDiscount::PercentageOff { percentage },
+            qualification: None,
+        }
+    }
+
+    fn percentage_of(promotion: &PromotionDetailsRecord) -> Option<u16> {
+        match &promotion.details {
+            PromotionDetails::DirectDiscount {
+                discount: SimpleDiscount::PercentageOff { percentage },
+                ..
+            } => Some(*percentage),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn list_promotions_returns_live_promotions() -> TestResult {
+        let ctx = TestContext::new().await;
+        let first = PromotionUuid::new();
+        let second = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(first, 10))
+            .await?;
+
+        let between = Timestamp::now();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(second, 20))
+            .await?;
+
+        let now = ctx
+            .promotions
+            .list_promotions(ctx.tenant_uuid, Timestamp::now())
+            .await?;
+
+        let earlier = ctx
+            .promotions
+            .list_promotions(ctx.tenant_uuid, between)
+            .await?;
+
+        assert_eq!(
+            now.iter()
+                .map(|promotion| promotion.uuid)
+                .collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        assert_eq!(
+            earlier
+                .iter()
+                .map(|promotion| promotion.uuid)
+                .collect::<Vec<_>>(),
+            vec![first]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_promotion_returns_version_live_at_point_in_time() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(uuid, 10))
+            .await?;
+
+        let before_update = Timestamp::now();
+
+        ctx.promotions
+            .update_promotion(
+                ctx.tenant_uuid,
+                uuid,
+                PromotionUpdate::DirectDiscount {
+                    budgets: Budgets {
+                        redemptions: None,
+                        monetary: None,
+                    },
+                    discount: SimpleDiscount::PercentageOff { percentage: 20 },
+                    qualification: None,
+                },
+            )
+            .await?;
+
+        let current = ctx
+            .promotions
+            .get_promotion(ctx.tenant_uuid, uuid, Timestamp::now())
+            .await?;
+
+        let previous = ctx
+            .promotions
+            .get_promotion(ctx.tenant_uuid, uuid, before_update)
+            .await?;
+
+        assert_eq!(current.uuid, uuid);
+        assert_eq!(percentage_of(&current), Some(20));
+        assert_eq!(percentage_of(&previous), Some(10));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_promotion_unknown_uuid_returns_not_found() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .promotions
+            .get_promotion(ctx.tenant_uuid, PromotionUuid::new(), Timestamp::now())
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::NotFound)),
+            "expected NotFound, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_promotion_not_visible_to_other_tenant() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(uuid, 10))
+            .await?;
+
+        let tenant_b = ctx.create_tenant("Tenant B").await;
+
+        let result = ctx
+            .promotions
+            .get_promotion(tenant_b, uuid, Timestamp::now())
+            .await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::NotFound)),
+            "expected NotFound for cross-tenant access, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_promotion_keeps_it_readable_in_the_past() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(uuid, 10))
+            .await?;
+
+        let before_delete = Timestamp::now();
+
+        ctx.promotions
+            .delete_promotion(ctx.tenant_uuid, uuid)
+            .await?;
+
+        let current = ctx
+            .promotions
+            .get_promotion(ctx.tenant_uuid, uuid, Timestamp::now())
+            .await;
+
+        assert!(
+            matches!(current, Err(PromotionsServiceError::NotFound)),
+            "expected NotFound, got {current:?}"
+        );
+
+        let previous = ctx
+            .promotions
+            .get_promotion(ctx.tenant_uuid, uuid, before_delete)
+            .await?;
+
+        assert_eq!(previous.uuid, uuid);
+        assert!(active_promotions(&ctx).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_promotion_twice_returns_not_found() -> TestResult {
+        let ctx = TestContext::new().await;
+        let uuid = PromotionUuid::new();
+
+        ctx.promotions
+            .create_promotion(ctx.tenant_uuid, direct_percentage_off(uuid, 10))
+            .await?;
+
+        ctx.promotions
+            .delete_promotion(ctx.tenant_uuid, uuid)
+            .await?;
+
+        let result = ctx.promotions.delete_promotion(ctx.tenant_uuid, uuid).await;
+
+        assert!(
+            matches!(result, Err(PromotionsServiceError::NotFound)),
+            "expected NotFound, got {result:?}"
+        );
+
+        Ok(())
+    }
 }
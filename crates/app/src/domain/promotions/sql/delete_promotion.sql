@@ -0,0 +1,6 @@
+UPDATE promotions
+SET
+  deleted_at = now()
+WHERE
+  uuid = $1
+  AND deleted_at IS NULL
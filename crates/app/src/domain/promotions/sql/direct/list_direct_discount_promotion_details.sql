@@ -0,0 +1,29 @@
+SELECT
+  promotions.uuid,
+  direct_discount_promotions.uuid AS detail_uuid,
+  direct_discount_promotions.redemption_budget,
+  direct_discount_promotions.monetary_budget,
+  direct_discount_promotions.discount_kind::TEXT AS discount_kind,
+  direct_discount_promotions.discount_percentage,
+  direct_discount_promotions.discount_amount,
+  promotions.created_at,
+  promotions.updated_at,
+  promotions.deleted_at
+FROM
+  promotions
+  INNER JOIN direct_discount_promotions ON direct_discount_promotions.promotion_uuid = promotions.uuid
+WHERE
+  promotions.promotionable_type = 'direct'
+  AND direct_discount_promotions.valid_period @> $1::TIMESTAMPTZ
+  AND promotions.created_at <= $1::TIMESTAMPTZ
+  AND (
+    promotions.deleted_at IS NULL
+    OR promotions.deleted_at > $1::TIMESTAMPTZ
+  )
+  AND (
+    $2::UUID IS NULL
+    OR promotions.uuid = $2
+  )
+ORDER BY
+  promotions.created_at,
+  promotions.uuid
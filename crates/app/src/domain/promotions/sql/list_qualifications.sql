@@ -0,0 +1,25 @@
+SELECT
+  qualifications.uuid,
+  qualifications.promotionable_uuid,
+  qualifications.parent_qualification_uuid,
+  qualifications.context::TEXT AS context,
+  qualifications.op::TEXT AS op,
+  qualification_rules.kind::TEXT AS rule_kind,
+  COALESCE(
+    array_agg(tags.name ORDER BY tags.name) FILTER (WHERE tags.name IS NOT NULL),
+    '{}'
+  ) AS rule_tags
+FROM
+  qualifications
+  LEFT JOIN qualification_rules ON qualification_rules.qualification_uuid = qualifications.uuid
+  LEFT JOIN taggables ON taggables.taggable_type = 'qualification_rule'
+  AND taggables.taggable_uuid = qualification_rules.uuid
+  LEFT JOIN tags ON tags.uuid = taggables.tag_uuid
+WHERE
+  qualifications.promotionable_uuid = ANY($1::UUID[])
+GROUP BY
+  qualifications.uuid,
+  qualification_rules.uuid
+ORDER BY
+  qualifications.uuid,
+  qualification_rules.uuid
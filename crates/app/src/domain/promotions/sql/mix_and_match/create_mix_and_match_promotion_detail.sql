@@ -0,0 +1,12 @@
+INSERT INTO
+  mix_and_match_promotions (
+    uuid,
+    promotion_uuid,
+    redemption_budget,
+    monetary_budget,
+    discount_kind,
+    discount_percentage,
+    discount_amount
+  )
+VALUES
+  ($1, $1, $2, $3, $4::mix_and_match_discount_kind, $5, $6)
@@ -0,0 +1,10 @@
+INSERT INTO
+  mix_and_match_slots (
+    uuid,
+    mix_and_match_promotion_uuid,
+    position,
+    min_items,
+    max_items
+  )
+VALUES
+  ($1, $2, $3, $4, $5)
@@ -0,0 +1,29 @@
+SELECT
+  promotions.uuid,
+  mix_and_match_promotions.uuid AS detail_uuid,
+  mix_and_match_promotions.redemption_budget,
+  mix_and_match_promotions.monetary_budget,
+  mix_and_match_promotions.discount_kind::TEXT AS discount_kind,
+  mix_and_match_promotions.discount_percentage,
+  mix_and_match_promotions.discount_amount,
+  promotions.created_at,
+  promotions.updated_at,
+  promotions.deleted_at
+FROM
+  promotions
+  INNER JOIN mix_and_match_promotions ON mix_and_match_promotions.promotion_uuid = promotions.uuid
+WHERE
+  promotions.promotionable_type = 'mix_and_match'
+  AND mix_and_match_promotions.valid_period @> $1::TIMESTAMPTZ
+  AND promotions.created_at <= $1::TIMESTAMPTZ
+  AND (
+    promotions.deleted_at IS NULL
+    OR promotions.deleted_at > $1::TIMESTAMPTZ
+  )
+  AND (
+    $2::UUID IS NULL
+    OR promotions.uuid = $2
+  )
+ORDER BY
+  promotions.created_at,
+  promotions.uuid
@@ -0,0 +1,12 @@
+SELECT
+  uuid,
+  mix_and_match_promotion_uuid,
+  min_items,
+  max_items
+FROM
+  mix_and_match_slots
+WHERE
+  mix_and_match_promotion_uuid = ANY ($1)
+ORDER BY
+  mix_and_match_promotion_uuid,
+  position
@@ -0,0 +1,14 @@
+INSERT INTO
+  positional_discount_promotions (
+    uuid,
+    promotion_uuid,
+    redemption_budget,
+    monetary_budget,
+    size,
+    positions,
+    discount_kind,
+    discount_percentage,
+    discount_amount
+  )
+VALUES
+  ($1, $1, $2, $3, $4, $5, $6::simple_discount_kind, $7, $8)
@@ -0,0 +1,31 @@
+SELECT
+  promotions.uuid,
+  positional_discount_promotions.uuid AS detail_uuid,
+  positional_discount_promotions.redemption_budget,
+  positional_discount_promotions.monetary_budget,
+  positional_discount_promotions.size,
+  positional_discount_promotions.positions,
+  positional_discount_promotions.discount_kind::TEXT AS discount_kind,
+  positional_discount_promotions.discount_percentage,
+  positional_discount_promotions.discount_amount,
+  promotions.created_at,
+  promotions.updated_at,
+  promotions.deleted_at
+FROM
+  promotions
+  INNER JOIN positional_discount_promotions ON positional_discount_promotions.promotion_uuid = promotions.uuid
+WHERE
+  promotions.promotionable_type = 'positional'
+  AND positional_discount_promotions.valid_period @> $1::TIMESTAMPTZ
+  AND promotions.created_at <= $1::TIMESTAMPTZ
+  AND (
+    promotions.deleted_at IS NULL
+    OR promotions.deleted_at > $1::TIMESTAMPTZ
+  )
+  AND (
+    $2::UUID IS NULL
+    OR promotions.uuid = $2
+  )
+ORDER BY
+  promotions.created_at,
+  promotions.uuid
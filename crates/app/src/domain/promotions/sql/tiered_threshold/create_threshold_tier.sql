@@ -0,0 +1,30 @@
+INSERT INTO
+  threshold_tiers (
+    uuid,
+    tiered_threshold_promotion_uuid,
+    position,
+    contribution_uuid,
+    discount_uuid,
+    lower_monetary_threshold,
+    lower_item_count_threshold,
+    upper_monetary_threshold,
+    upper_item_count_threshold,
+    discount_kind,
+    discount_percentage,
+    discount_amount
+  )
+VALUES
+  (
+    $1,
+    $2,
+    $3,
+    $4,
+    $5,
+    $6,
+    $7,
+    $8,
+    $9,
+    $10::threshold_discount_kind,
+    $11,
+    $12
+  )
@@ -0,0 +1,9 @@
+INSERT INTO
+  tiered_threshold_promotions (
+    uuid,
+    promotion_uuid,
+    redemption_budget,
+    monetary_budget
+  )
+VALUES
+  ($1, $1, $2, $3)
@@ -0,0 +1,19 @@
+SELECT
+  uuid,
+  tiered_threshold_promotion_uuid,
+  contribution_uuid,
+  discount_uuid,
+  lower_monetary_threshold,
+  lower_item_count_threshold,
+  upper_monetary_threshold,
+  upper_item_count_threshold,
+  discount_kind::TEXT AS discount_kind,
+  discount_percentage,
+  discount_amount
+FROM
+  threshold_tiers
+WHERE
+  tiered_threshold_promotion_uuid = ANY ($1)
+ORDER BY
+  tiered_threshold_promotion_uuid,
+  position
@@ -0,0 +1,26 @@
+SELECT
+  promotions.uuid,
+  tiered_threshold_promotions.uuid AS detail_uuid,
+  tiered_threshold_promotions.redemption_budget,
+  tiered_threshold_promotions.monetary_budget,
+  promotions.created_at,
+  promotions.updated_at,
+  promotions.deleted_at
+FROM
+  promotions
+  INNER JOIN tiered_threshold_promotions ON tiered_threshold_promotions.promotion_uuid = promotions.uuid
+WHERE
+  promotions.promotionable_type = 'tiered_threshold'
+  AND tiered_threshold_promotions.valid_period @> $1::TIMESTAMPTZ
+  AND promotions.created_at <= $1::TIMESTAMPTZ
+  AND (
+    promotions.deleted_at IS NULL
+    OR promotions.deleted_at > $1::TIMESTAMPTZ
+  )
+  AND (
+    $2::UUID IS NULL
+    OR promotions.uuid = $2
+  )
+ORDER BY
+  promotions.created_at,
+  promotions.uuid
@@ -0,0 +1,24 @@
+//! Quote Data
+
+use smallvec::SmallVec;
+
+use crate::domain::products::records::ProductUuid;
+
+/// New Quote Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewQuote {
+    pub items: Vec<NewQuoteItem>,
+}
+
+/// New Quote Item Data
+#[derive(Debug, Clone, PartialEq)]
+pub enum NewQuoteItem {
+    /// A catalogue product, priced and tagged as it was at the quote's point in time.
+    Product { product_uuid: ProductUuid },
+
+    /// An item that is not in the catalogue.
+    Inline {
+        price: u64,
+        tags: SmallVec<[String; 3]>,
+    },
+}
@@ -0,0 +1,35 @@
+//! Quotes service errors.
+
+use sqlx::Error;
+use thiserror::Error;
+
+use crate::domain::pricing::PricingError;
+
+#[derive(Debug, Error)]
+pub enum QuotesServiceError {
+    #[error("related resource not found")]
+    InvalidReference,
+
+    #[error("storage error")]
+    Sql(#[source] Error),
+
+    #[error("pricing error")]
+    Pricing(#[source] PricingError),
+}
+
+impl From<PricingError> for QuotesServiceError {
+    fn from(error: PricingError) -> Self {
+        Self::Pricing(error)
+    }
+}
+
+impl From<Error> for QuotesServiceError {
+    fn from(error: Error) -> Self {
+        // Quotes only read, so a missing row is always a product the request referenced.
+        if matches!(error, Error::RowNotFound) {
+            return Self::InvalidReference;
+        }
+
+        Self::Sql(error)
+    }
+}
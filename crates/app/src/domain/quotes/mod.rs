@@ -0,0 +1,12 @@
+//! Quotes
+//!
+//! A quote prices an ad-hoc basket against the tenant's promotions without
+//! persisting anything.
+
+pub mod data;
+mod errors;
+pub mod records;
+mod service;
+
+pub use errors::QuotesServiceError;
+pub use service::*;
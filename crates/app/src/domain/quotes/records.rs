@@ -0,0 +1,24 @@
+//! Quote Records
+
+use smallvec::SmallVec;
+
+use crate::domain::{pricing::data::Redemption, products::records::ProductUuid};
+
+/// Quote Record
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteRecord {
+    pub subtotal: u64,
+    pub total: u64,
+
+    /// The quoted items, in the same order as they were given.
+    pub items: Vec<QuoteItemRecord>,
+}
+
+/// Quote Item Record
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteItemRecord {
+    pub product_uuid: Option<ProductUuid>,
+    pub price: u64,
+    pub tags: SmallVec<[String; 3]>,
+    pub redemptions: Vec<Redemption>,
+}
@@ -0,0 +1,382 @@
+//! Quotes service.
+
+use async_trait::async_trait;
+use jiff::Timestamp;
+use mockall::automock;
+use rustc_hash::FxHashMap;
+use rusty_money::iso::Currency;
+use tracing::info;
+
+use crate::{
+    database::Db,
+    domain::{
+        pricing::{data::PricingItem, price_items},
+        products::{PgProductsRepository, records::ProductUuid},
+        promotions::PgPromotionsRepository,
+        quotes::{
+            data::{NewQuote, NewQuoteItem},
+            errors::QuotesServiceError,
+            records::{QuoteItemRecord, QuoteRecord},
+        },
+        stacks::PgStacksRepository,
+        tags::PgTagsRepository,
+        tenants::records::TenantUuid,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct PgQuotesService {
+    db: Db,
+    currency: &'static Currency,
+    products: PgProductsRepository,
+    promotions: PgPromotionsRepository,
+    stacks: PgStacksRepository,
+    tags: PgTagsRepository,
+}
+
+impl PgQuotesService {
+    #[must_use]
+    pub fn new(db: Db, currency: &'static Currency) -> Self {
+        Self {
+            db,
+            currency,
+            products: PgProductsRepository::new(),
+            promotions: PgPromotionsRepository::new(),
+            stacks: PgStacksRepository::new(),
+            tags: PgTagsRepository::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl QuotesService for PgQuotesService {
+    #[tracing::instrument(
+        name = "quotes.service.create_quote",
+        skip(self, quote),
+        fields(
+            tenant_uuid = %tenant,
+            item_count = quote.items.len(),
+            point_in_time = %point_in_time
+        ),
+        err
+    )]
+    async fn create_quote(
+        &self,
+        tenant: TenantUuid,
+        quote: NewQuote,
+        point_in_time: Timestamp,
+    ) -> Result<QuoteRecord, QuotesServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        let mut product_uuids: Vec<ProductUuid> = quote
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                NewQuoteItem::Product { product_uuid } => Some(*product_uuid),
+                NewQuoteItem::Inline { .. } => None,
+            })
+            .collect();
+
+        product_uuids.sort_unstable();
+        product_uuids.dedup();
+
+        let mut product_prices = FxHashMap::default();
+
+        for product in &product_uuids {
+            let record = self
+                .products
+                .get_product(&mut tx, *product, point_in_time)
+                .await?;
+
+            product_prices.insert(*product, record.price);
+        }
+
+        let product_tags = self
+            .tags
+            .list_taggables_tag_names(&mut tx, &product_uuids)
+            .await?;
+
+        let promotions = self
+            .promotions
+            .list_active_promotions(&mut tx, point_in_time)
+            .await?;
+
+        let stack = self
+            .stacks
+            .find_active_stack(&mut tx, point_in_time)
+            .await?;
+
+        tx.commit().await?;
+
+        let mut items: Vec<QuoteItemRecord> = quote
+            .items
+            .into_iter()
+            .map(|item| match item {
+                NewQuoteItem::Product { product_uuid } => QuoteItemRecord {
+                    product_uuid: Some(product_uuid),
+                    price: product_prices.get(&product_uuid).copied().unwrap_or(0),
+                    tags: product_tags
+                        .get(&product_uuid.into_uuid())
+                        .cloned()
+                        .unwrap_or_default(),
+                    redemptions: Vec::new(),
+                },
+                NewQuoteItem::Inline { price, tags } => QuoteItemRecord {
+                    product_uuid: None,
+                    price,
+                    tags,
+                    redemptions: Vec::new(),
+                },
+            })
+            .collect();
+
+        let pricing_items: Vec<PricingItem> = items
+            .iter()
+            .map(|item| PricingItem {
+                price: item.price,
+                tags: item.tags.clone(),
+            })
+            .collect();
+
+        let mut pricing = price_items(
+            &pricing_items,
+            &promotions,
+            stack.as_ref().map(|stack| &stack.graph),
+            self.currency,
+        )?;
+
+        for (item, redemptions) in items.iter_mut().zip(&mut pricing.redemptions) {
+            item.redemptions.extend(redemptions.drain(..));
+        }
+
+        info!(
+            item_count = items.len(),
+            subtotal = pricing.subtotal,
+            total = pricing.total,
+            "quoted items"
+        );
+
+        Ok(QuoteRecord {
+            subtotal: pricing.subtotal,
+            total: pricing.total,
+            items,
+        })
+    }
+}
+
+#[automock]
+#[async_trait]
+pub trait QuotesService: Send + Sync {
+    /// Price the given items against the promotions and stack active at the given point in time.
+    async fn create_quote(
+        &self,
+        tenant: TenantUuid,
+        quote: NewQuote,
+        point_in_time: Timestamp,
+    ) -> Result<QuoteRecord, QuotesServiceError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        domain::promotions::records::PromotionUuid,
+        test::{
+            TestContext,
+            helpers::{create_direct_discount_promotion, create_product},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn create_quote_prices_products_against_active_promotions() -> TestResult {
+        let ctx = TestContext::new().await;
+        let promotion_uuid = PromotionUuid::new();
+
+        let product = create_product(
+            &ctx,
+            ctx.tenant_uuid,
+            ProductUuid::new(),
+            10_00,
+            smallvec!["sale".to_string()],
+        )
+        .await?;
+
+        create_direct_discount_promotion(
+            &ctx,
+            ctx.tenant_uuid,
+            promotion_uuid,
+            25,
+            smallvec!["sale".to_string()],
+        )
+        .await?;
+
+        let quote = ctx
+            .quotes
+            .create_quote(
+                ctx.tenant_uuid,
+                NewQuote {
+                    items: vec![NewQuoteItem::Product {
+                        product_uuid: product.uuid,
+                    }],
+                },
+                Timestamp::now(),
+            )
+            .await?;
+
+        assert_eq!(quote.subtotal, 10_00);
+        assert_eq!(quote.total, 7_50);
+
+        let Some(item) = quote.items.first() else {
+            panic!("expected item, got None");
+        };
+
+        assert_eq!(item.product_uuid, Some(product.uuid));
+        assert_eq!(item.tags.as_slice(), ["sale".to_string()]);
+
+        let Some(redemption) = item.redemptions.first() else {
+            panic!("expected redemption, got None");
+        };
+
+        assert_eq!(redemption.promotion_uuid, promotion_uuid);
+        assert_eq!(redemption.final_price, 7_50);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_quote_prices_inline_items() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        create_direct_discount_promotion(
+            &ctx,
+            ctx.tenant_uuid,
+            PromotionUuid::new(),
+            50,
+            smallvec!["sale".to_string()],
+        )
+        .await?;
+
+        let quote = ctx
+            .quotes
+            .create_quote(
+                ctx.tenant_uuid,
+                NewQuote {
+                    items: vec![
+                        NewQuoteItem::Inline {
+                            price: 4_00,
+                            tags: smallvec!["sale".to_string()],
+                        },
+                        NewQuoteItem::Inline {
+                            price: 6_00,
+                            tags: smallvec![],
+                        },
+                    ],
+                },
+                Timestamp::now(),
+            )
+            .await?;
+
+        assert_eq!(quote.subtotal, 10_00);
+        assert_eq!(quote.total, 8_00);
+
+        assert_eq!(
+            quote
+                .items
+                .iter()
+                .map(|item| item.redemptions.len())
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_quote_ignores_promotions_created_after_point_in_time() -> TestResult {
+        let ctx = TestContext::new().await;
+        let before_promotion = Timestamp::now();
+
+        create_direct_discount_promotion(
+            &ctx,
+            ctx.tenant_uuid,
+            PromotionUuid::new(),
+            25,
+            smallvec!["sale".to_string()],
+        )
+        .await?;
+
+        let quote = ctx
+            .quotes
+            .create_quote(
+                ctx.tenant_uuid,
+                NewQuote {
+                    items: vec![NewQuoteItem::Inline {
+                        price: 10_00,
+                        tags: smallvec!["sale".to_string()],
+                    }],
+                },
+                before_promotion,
+            )
+            .await?;
+
+        assert_eq!(quote.total, 10_00);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_quote_unknown_product_returns_invalid_reference() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .quotes
+            .create_quote(
+                ctx.tenant_uuid,
+                NewQuote {
+                    items: vec![NewQuoteItem::Product {
+                        product_uuid: ProductUuid::new(),
+                    }],
+                },
+                Timestamp::now(),
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(QuotesServiceError::InvalidReference)),
+            "expected InvalidReference, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_quote_does_not_see_other_tenants_products() -> TestResult {
+        let ctx = TestContext::new().await;
+        let other_tenant = ctx.create_tenant("Other").await;
+
+        let product =
+            create_product(&ctx, other_tenant, ProductUuid::new(), 10_00, smallvec![]).await?;
+
+        let result = ctx
+            .quotes
+            .create_quote(
+                ctx.tenant_uuid,
+                NewQuote {
+                    items: vec![NewQuoteItem::Product {
+                        product_uuid: product.uuid,
+                    }],
+                },
+                Timestamp::now(),
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(QuotesServiceError::InvalidReference)),
+            "expected InvalidReference, got {result:?}"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! Stacks Data
+
+use crate::domain::{promotions::records::PromotionUuid, stacks::records::StackUuid};
+
+/// New Stack Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewStack {
+    pub uuid: StackUuid,
+    pub graph: StackGraph,
+}
+
+/// Stack Update Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackUpdate {
+    pub graph: StackGraph,
+}
+
+/// Stack Graph Data
+///
+/// Items enter the stack at the root layer and flow between layers by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackGraph {
+    pub root: String,
+    pub layers: Vec<StackLayer>,
+}
+
+/// Stack Layer Data
+///
+/// The promotions in a layer compete with each other; discounts stack across
+/// layers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackLayer {
+    pub name: String,
+    pub promotions: Vec<PromotionUuid>,
+    pub output: LayerOutput,
+}
+
+/// Layer Output Data
+///
+/// How items leave a layer. A missing target ends evaluation for those items.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerOutput {
+    /// All items flow to the next layer.
+    PassThrough { next: Option<String> },
+
+    /// Items that took part in a promotion in this or an earlier layer flow
+    /// to `participating`, the rest to `non_participating`.
+    Split {
+        participating: Option<String>,
+        non_participating: Option<String>,
+    },
+}
+
+impl LayerOutput {
+    #[must_use]
+    pub const fn to_str(&self) -> &'static str {
+        match self {
+            Self::PassThrough { .. } => "pass_through",
+            Self::Split { .. } => "split",
+        }
+    }
+}
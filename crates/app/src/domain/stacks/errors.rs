@@ -0,0 +1,55 @@
+//! Stacks service errors.
+
+use sqlx::{
+    Error,
+    error::{DatabaseError, ErrorKind},
+};
+use thiserror::Error;
+
+use crate::domain::pricing::PricingError;
+
+#[derive(Debug, Error)]
+pub enum StacksServiceError {
+    #[error("stack already exists")]
+    AlreadyExists,
+
+    #[error("stack not found")]
+    NotFound,
+
+    #[error("related resource not found")]
+    InvalidReference,
+
+    #[error("missing required data")]
+    MissingRequiredData,
+
+    #[error("invalid data")]
+    InvalidData,
+
+    #[error("invalid stack graph")]
+    InvalidGraph(#[source] PricingError),
+
+    #[error("storage error")]
+    Sql(#[source] Error),
+}
+
+impl From<PricingError> for StacksServiceError {
+    fn from(error: PricingError) -> Self {
+        Self::InvalidGraph(error)
+    }
+}
+
+impl From<Error> for StacksServiceError {
+    fn from(error: Error) -> Self {
+        if matches!(error, Error::RowNotFound) {
+            return Self::NotFound;
+        }
+
+        match error.as_database_error().map(DatabaseError::kind) {
+            Some(ErrorKind::UniqueViolation) => Self::AlreadyExists,
+            Some(ErrorKind::ForeignKeyViolation) => Self::InvalidReference,
+            Some(ErrorKind::NotNullViolation) => Self::MissingRequiredData,
+            Some(ErrorKind::CheckViolation) => Self::InvalidData,
+            Some(ErrorKind::Other | _) | None => Self::Sql(error),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Stacks
+//!
+//! A stack arranges a tenant's promotions into layers, so that discounts from
+//! one layer can stack on top of another's.
+
+pub mod data;
+mod errors;
+pub mod records;
+mod repository;
+mod service;
+
+pub use errors::StacksServiceError;
+pub(crate) use repository::PgStacksRepository;
+pub use service::*;
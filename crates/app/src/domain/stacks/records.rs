@@ -0,0 +1,43 @@
+//! Stack Records
+
+use jiff::Timestamp;
+
+use crate::{domain::stacks::data::StackGraph, uuids::TypedUuid};
+
+/// Stack UUID
+pub type StackUuid = TypedUuid<StackRecord>;
+
+/// Stack Record
+#[derive(Debug, Clone)]
+pub struct StackRecord {
+    pub uuid: StackUuid,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub deleted_at: Option<Timestamp>,
+}
+
+/// Stack Version Record
+pub struct StackVersionRecord;
+
+/// Stack Version UUID
+pub type StackVersionUuid = TypedUuid<StackVersionRecord>;
+
+/// Stack Layer Record
+pub struct StackLayerRecord;
+
+/// Stack Layer UUID
+pub type StackLayerUuid = TypedUuid<StackLayerRecord>;
+
+/// Stack Details Record
+///
+/// A stack together with the version of its layer graph valid at the
+/// requested point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackDetailsRecord {
+    pub uuid: StackUuid,
+    pub version_uuid: StackVersionUuid,
+    pub graph: StackGraph,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub deleted_at: Option<Timestamp>,
+}
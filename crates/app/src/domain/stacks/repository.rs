@@ -0,0 +1,300 @@
+//! Stacks Repository
+
+use jiff::Timestamp;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{FromRow, Postgres, Row, Transaction, postgres::PgRow, query, query_as, query_scalar};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::domain::{
+    promotions::records::PromotionUuid,
+    stacks::{
+        data::{LayerOutput, StackGraph, StackLayer},
+        records::{StackDetailsRecord, StackLayerUuid, StackRecord, StackUuid, StackVersionUuid},
+    },
+};
+
+const COLUMN_OUTPUT_MODE: &str = "output_mode";
+const COLUMN_POSITION: &str = "position";
+
+const CREATE_STACK_SQL: &str = include_str!("sql/create_stack.sql");
+const CREATE_STACK_VERSION_SQL: &str = include_str!("sql/create_stack_version.sql");
+const UPDATE_STACK_VERSION_SQL: &str = include_str!("sql/update_stack_version.sql");
+const CREATE_STACK_LAYER_SQL: &str = include_str!("sql/create_stack_layer.sql");
+const CREATE_STACK_LAYER_PROMOTIONS_SQL: &str =
+    include_str!("sql/create_stack_layer_promotions.sql");
+const GET_STACK_SQL: &str = include_str!("sql/get_stack.sql");
+const FIND_ACTIVE_STACK_SQL: &str = include_str!("sql/find_active_stack.sql");
+const LIST_STACK_LAYERS_SQL: &str = include_str!("sql/list_stack_layers.sql");
+
+struct StackVersionRow {
+    uuid: StackUuid,
+    version_uuid: StackVersionUuid,
+    root: String,
+    created_at: Timestamp,
+    updated_at: Timestamp,
+    deleted_at: Option<Timestamp>,
+}
+
+struct StackLayerRow(StackLayer);
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PgStacksRepository;
+
+impl PgStacksRepository {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    #[tracing::instrument(
+        name = "stacks.repository.create_stack",
+        level = "debug",
+        skip(self, tx, graph),
+        fields(stack_uuid = %stack, layer_count = graph.layers.len()),
+        err
+    )]
+    pub(crate) async fn create_stack(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        stack: StackUuid,
+        graph: &StackGraph,
+    ) -> Result<StackRecord, sqlx::Error> {
+        let (created_uuid, created_at, updated_at, deleted_at): (
+            Uuid,
+            SqlxTimestamp,
+            SqlxTimestamp,
+            Option<SqlxTimestamp>,
+        ) = query_as(CREATE_STACK_SQL)
+            .bind(stack.into_uuid())
+            .fetch_one(&mut **tx)
+            .await?;
+
+        // The first version shares the stack's UUID.
+        query(CREATE_STACK_VERSION_SQL)
+            .bind(created_uuid)
+            .bind(&graph.root)
+            .execute(&mut **tx)
+            .await?;
+
+        insert_stack_layers(tx, StackVersionUuid::from_uuid(created_uuid), &graph.layers).await?;
+
+        debug!(stack_uuid = %stack, "inserted stack");
+
+        Ok(StackRecord {
+            uuid: StackUuid::from_uuid(created_uuid),
+            created_at: created_at.to_jiff(),
+            updated_at: updated_at.to_jiff(),
+            deleted_at: deleted_at.map(SqlxTimestamp::to_jiff),
+        })
+    }
+
+    #[tracing::instrument(
+        name = "stacks.repository.update_stack",
+        level = "debug",
+        skip(self, tx, graph),
+        fields(stack_uuid = %stack, layer_count = graph.layers.len()),
+        err
+    )]
+    pub(crate) async fn update_stack(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        stack: StackUuid,
+        graph: &StackGraph,
+    ) -> Result<StackVersionUuid, sqlx::Error> {
+        let returned_uuid: Uuid = query_scalar(UPDATE_STACK_VERSION_SQL)
+            .bind(stack.into_uuid())
+            .bind(StackVersionUuid::new().into_uuid())
+            .bind(&graph.root)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        let version_uuid = StackVersionUuid::from_uuid(returned_uuid);
+
+        insert_stack_layers(tx, version_uuid, &graph.layers).await?;
+
+        debug!(stack_uuid = %stack, version_uuid = %version_uuid, "updated stack");
+
+        Ok(version_uuid)
+    }
+
+    #[tracing::instrument(
+        name = "stacks.repository.get_stack",
+        level = "debug",
+        skip(self, tx),
+        fields(stack_uuid = %stack, point_in_time = %point_in_time),
+        err
+    )]
+    pub(crate) async fn get_stack(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        stack: StackUuid,
+        point_in_time: Timestamp,
+    ) -> Result<StackDetailsRecord, sqlx::Error> {
+        let row = query_as::<Postgres, StackVersionRow>(GET_STACK_SQL)
+            .bind(stack.into_uuid())
+            .bind(SqlxTimestamp::from(point_in_time))
+            .fetch_one(&mut **tx)
+            .await?;
+
+        stack_details(tx, row).await
+    }
+
+    /// Find the tenant's stack as it was at `point_in_time`, if it had one.
+    #[tracing::instrument(
+        name = "stacks.repository.find_active_stack",
+        level = "debug",
+        skip(self, tx),
+        fields(point_in_time = %point_in_time),
+        err
+    )]
+    pub(crate) async fn find_active_stack(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        point_in_time: Timestamp,
+    ) -> Result<Option<StackDetailsRecord>, sqlx::Error> {
+        let row = query_as::<Postgres, StackVersionRow>(FIND_ACTIVE_STACK_SQL)
+            .bind(SqlxTimestamp::from(point_in_time))
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(stack_details(tx, row).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+async fn insert_stack_layers(
+    tx: &mut Transaction<'_, Postgres>,
+    version_uuid: StackVersionUuid,
+    layers: &[StackLayer],
+) -> Result<(), sqlx::Error> {
+    for (position, layer) in layers.iter().enumerate() {
+        let position = i32::try_from(position).map_err(|e| sqlx::Error::ColumnDecode {
+            index: COLUMN_POSITION.to_string(),
+            source: Box::new(e),
+        })?;
+
+        let (next, participating, non_participating) = match &layer.output {
+            LayerOutput::PassThrough { next } => (next.as_deref(), None, None),
+            LayerOutput::Split {
+                participating,
+                non_participating,
+            } => (None, participating.as_deref(), non_participating.as_deref()),
+        };
+
+        let layer_uuid = StackLayerUuid::new();
+
+        query(CREATE_STACK_LAYER_SQL)
+            .bind(layer_uuid.into_uuid())
+            .bind(version_uuid.into_uuid())
+            .bind(position)
+            .bind(&layer.name)
+            .bind(layer.output.to_str())
+            .bind(next)
+            .bind(participating)
+            .bind(non_participating)
+            .execute(&mut **tx)
+            .await?;
+
+        let promotion_uuids: Vec<Uuid> = layer
+            .promotions
+            .iter()
+            .map(|promotion| promotion.into_uuid())
+            .collect();
+
+        query(CREATE_STACK_LAYER_PROMOTIONS_SQL)
+            .bind(layer_uuid.into_uuid())
+            .bind(promotion_uuids)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    debug!(
+        version_uuid = %version_uuid,
+        layer_count = layers.len(),
+        "inserted stack layers"
+    );
+
+    Ok(())
+}
+
+async fn stack_details(
+    tx: &mut Transaction<'_, Postgres>,
+    row: StackVersionRow,
+) -> Result<StackDetailsRecord, sqlx::Error> {
+    let layers = query_as::<Postgres, StackLayerRow>(LIST_STACK_LAYERS_SQL)
+        .bind(row.version_uuid.into_uuid())
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|row| row.0)
+        .collect::<Vec<_>>();
+
+    debug!(
+        stack_uuid = %row.uuid,
+        version_uuid = %row.version_uuid,
+        layer_count = layers.len(),
+        "queried stack"
+    );
+
+    Ok(StackDetailsRecord {
+        uuid: row.uuid,
+        version_uuid: row.version_uuid,
+        graph: StackGraph {
+            root: row.root,
+            layers,
+        },
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+        deleted_at: row.deleted_at,
+    })
+}
+
+impl<'r> FromRow<'r, PgRow> for StackVersionRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            uuid: StackUuid::from_uuid(row.try_get("uuid")?),
+            version_uuid: StackVersionUuid::from_uuid(row.try_get("version_uuid")?),
+            root: row.try_get("root_layer")?,
+            created_at: row.try_get::<SqlxTimestamp, _>("created_at")?.to_jiff(),
+            updated_at: row.try_get::<SqlxTimestamp, _>("updated_at")?.to_jiff(),
+            deleted_at: row
+                .try_get::<Option<SqlxTimestamp>, _>("deleted_at")?
+                .map(SqlxTimestamp::to_jiff),
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for StackLayerRow {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let output_mode: &str = row.try_get(COLUMN_OUTPUT_MODE)?;
+
+        let output = match output_mode {
+            "pass_through" => LayerOutput::PassThrough {
+                next: row.try_get("next_layer")?,
+            },
+            "split" => LayerOutput::Split {
+                participating: row.try_get("participating_layer")?,
+                non_participating: row.try_get("non_participating_layer")?,
+            },
+            _ => {
+                return Err(sqlx::Error::ColumnDecode {
+                    index: COLUMN_OUTPUT_MODE.to_string(),
+                    source: format!("unexpected output mode `{output_mode}`").into(),
+                });
+            }
+        };
+
+        Ok(Self(StackLayer {
+            name: row.try_get("name")?,
+            promotions: row
+                .try_get::<Vec<Uuid>, _>("promotion_uuids")?
+                .into_iter()
+                .map(PromotionUuid::from_uuid)
+                .collect(),
+            output,
+        }))
+    }
+}
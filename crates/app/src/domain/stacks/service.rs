@@ -0,0 +1,545 @@
+//! Stacks Service
+
+use async_trait::async_trait;
+use jiff::Timestamp;
+use mockall::automock;
+use rustc_hash::FxHashSet;
+use rusty_money::iso::Currency;
+use sqlx::{Postgres, Transaction};
+use tracing::{info, warn};
+
+use crate::{
+    database::Db,
+    domain::{
+        pricing::validate_stack,
+        promotions::{PgPromotionsRepository, records::PromotionUuid},
+        stacks::{
+            PgStacksRepository, StacksServiceError,
+            data::{NewStack, StackGraph, StackUpdate},
+            records::{StackDetailsRecord, StackRecord, StackUuid},
+        },
+        tenants::records::TenantUuid,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct PgStacksService {
+    db: Db,
+    currency: &'static Currency,
+    stacks: PgStacksRepository,
+    promotions: PgPromotionsRepository,
+}
+
+impl PgStacksService {
+    #[must_use]
+    pub fn new(db: Db, currency: &'static Currency) -> Self {
+        Self {
+            db,
+            currency,
+            stacks: PgStacksRepository::new(),
+            promotions: PgPromotionsRepository::new(),
+        }
+    }
+
+    /// Check that every promotion in the stack is active and that the stack
+    /// builds into a valid promotion graph.
+    async fn validate_graph(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        graph: &StackGraph,
+    ) -> Result<(), StacksServiceError> {
+        let promotions = self
+            .promotions
+            .list_active_promotions(tx, Timestamp::now())
+            .await?;
+
+        let active: FxHashSet<PromotionUuid> =
+            promotions.iter().map(|promotion| promotion.uuid).collect();
+
+        if let Some(unknown) = graph
+            .layers
+            .iter()
+            .flat_map(|layer| &layer.promotions)
+            .find(|uuid| !active.contains(uuid))
+        {
+            warn!(promotion_uuid = %unknown, "stack references an unknown promotion");
+
+            return Err(StacksServiceError::InvalidReference);
+        }
+
+        validate_stack(graph, &promotions, self.currency)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StacksService for PgStacksService {
+    #[tracing::instrument(
+        name = "stacks.service.get_stack",
+        skip(self),
+        fields(
+            tenant_uuid = %tenant,
+            stack_uuid = %stack,
+            point_in_time = %point_in_time
+        ),
+        err
+    )]
+    async fn get_stack(
+        &self,
+        tenant: TenantUuid,
+        stack: StackUuid,
+        point_in_time: Timestamp,
+    ) -> Result<StackDetailsRecord, StacksServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        let stack = self.stacks.get_stack(&mut tx, stack, point_in_time).await?;
+
+        tx.commit().await?;
+
+        info!(stack_uuid = %stack.uuid, "fetched stack");
+
+        Ok(stack)
+    }
+
+    #[tracing::instrument(
+        name = "stacks.service.create_stack",
+        skip(self, stack),
+        fields(
+            tenant_uuid = %tenant,
+            stack_uuid = %stack.uuid,
+            layer_count = stack.graph.layers.len()
+        ),
+        err
+    )]
+    async fn create_stack(
+        &self,
+        tenant: TenantUuid,
+        stack: NewStack,
+    ) -> Result<StackRecord, StacksServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        self.validate_graph(&mut tx, &stack.graph).await?;
+
+        let record = self
+            .stacks
+            .create_stack(&mut tx, stack.uuid, &stack.graph)
+            .await?;
+
+        tx.commit().await?;
+
+        info!(stack_uuid = %record.uuid, "created stack");
+
+        Ok(record)
+    }
+
+    #[tracing::instrument(
+        name = "stacks.service.update_stack",
+        skip(self, update),
+        fields(
+            tenant_uuid = %tenant,
+            stack_uuid = %uuid,
+            layer_count = update.graph.layers.len()
+        ),
+        err
+    )]
+    async fn update_stack(
+        &self,
+        tenant: TenantUuid,
+        uuid: StackUuid,
+        update: StackUpdate,
+    ) -> Result<(), StacksServiceError> {
+        let mut tx = self.db.begin_tenant_transaction(tenant).await?;
+
+        self.validate_graph(&mut tx, &update.graph).await?;
+
+        let version_uuid = self
+            .stacks
+            .update_stack(&mut tx, uuid, &update.graph)
+            .await?;
+
+        tx.commit().await?;
+
+        info!(stack_uuid = %uuid, version_uuid = %version_uuid, "updated stack");
+
+        Ok(())
+    }
+}
+
+#[automock]
+#[async_trait]
+pub trait StacksService: Send + Sync {
+    /// Retrieve a stack as it was at `point_in_time`.
+    async fn get_stack(
+        &self,
+        tenant: TenantUuid,
+        stack: StackUuid,
+        point_in_time: Timestamp,
+    ) -> Result<StackDetailsRecord, StacksServiceError>;
+
+    /// Create the tenant's stack. A tenant has at most one stack.
+    async fn create_stack(
+        &self,
+        tenant: TenantUuid,
+        stack: NewStack,
+    ) -> Result<StackRecord, StacksServiceError>;
+
+    /// Replace the stack's layers with a new version.
+    async fn update_stack(
+        &self,
+        tenant: TenantUuid,
+        uuid: StackUuid,
+        update: StackUpdate,
+    ) -> Result<(), StacksServiceError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        domain::{
+            pricing::PricingError,
+            stacks::data::{LayerOutput, StackLayer},
+        },
+        test::{TestContext, helpers::create_direct_discount_promotion},
+    };
+
+    use super::*;
+
+    fn layer(name: &str, promotions: &[PromotionUuid], output: LayerOutput) -> StackLayer {
+        StackLayer {
+            name: name.to_string(),
+            promotions: promotions.to_vec(),
+            output,
+        }
+    }
+
+    fn pass_through(next: Option<&str>) -> LayerOutput {
+        LayerOutput::PassThrough {
+            next: next.map(ToString::to_string),
+        }
+    }
+
+    async fn create_promotions(ctx: &TestContext, count: usize) -> TestResult<Vec<PromotionUuid>> {
+        let mut uuids = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let uuid = PromotionUuid::new();
+
+            create_direct_discount_promotion(
+                ctx,
+                ctx.tenant_uuid,
+                uuid,
+                10,
+                smallvec!["sale".to_string()],
+            )
+            .await?;
+
+            uuids.push(uuid);
+        }
+
+        Ok(uuids)
+    }
+
+    async fn create_stack(
+        ctx: &TestContext,
+        graph: StackGraph,
+    ) -> Result<StackRecord, StacksServiceError> {
+        ctx.stacks
+            .create_stack(
+                ctx.tenant_uuid,
+                NewStack {
+                    uuid: StackUuid::new(),
+                    graph,
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn create_stack_round_trips_layers() -> TestResult {
+        let ctx = TestContext::new().await;
+        let promotions = create_promotions(&ctx, 3).await?;
+
+        let graph = StackGraph {
+            root: "members".to_string(),
+            layers: vec![
+                layer(
+                    "members",
+                    &promotions[..1],
+                    LayerOutput::Split {
+                        participating: Some("loyalty".to_string()),
+                        non_participating: None,
+                    },
+                ),
+                layer("loyalty", &promotions[1..], pass_through(None)),
+            ],
+        };
+
+        let stack = create_stack(&ctx, graph.clone()).await?;
+
+        let details = ctx
+            .stacks
+            .get_stack(ctx.tenant_uuid, stack.uuid, Timestamp::now())
+            .await?;
+
+        assert_eq!(details.uuid, stack.uuid);
+        assert_eq!(details.version_uuid.into_uuid(), stack.uuid.into_uuid());
+        assert_eq!(details.graph, graph);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_second_stack_returns_already_exists() -> TestResult {
+        let ctx = TestContext::new().await;
+        let promotions = create_promotions(&ctx, 1).await?;
+
+        let graph = StackGraph {
+            root: "default".to_string(),
+            layers: vec![layer("default", &promotions, pass_through(None))],
+        };
+
+        create_stack(&ctx, graph.clone()).await?;
+
+        let result = create_stack(&ctx, graph).await;
+
+        assert!(
+            matches!(result, Err(StacksServiceError::AlreadyExists)),
+            "expected AlreadyExists, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_stack_with_cycle_returns_invalid_graph() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let result = create_stack(
+            &ctx,
+            StackGraph {
+                root: "first".to_string(),
+                layers: vec![
+                    layer("first", &[], pass_through(Some("second"))),
+                    layer("second", &[], pass_through(Some("first"))),
+                ],
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StacksServiceError::InvalidGraph(PricingError::Graph(_)))
+            ),
+            "expected InvalidGraph, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_stack_with_promotion_twice_in_path_returns_invalid_graph() -> TestResult {
+        let ctx = TestContext::new().await;
+        let promotions = create_promotions(&ctx, 1).await?;
+
+        let result = create_stack(
+            &ctx,
+            StackGraph {
+                root: "first".to_string(),
+                layers: vec![
+                    layer("first", &promotions, pass_through(Some("second"))),
+                    layer("second", &promotions, pass_through(None)),
+                ],
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StacksServiceError::InvalidGraph(PricingError::Graph(_)))
+            ),
+            "expected InvalidGraph, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_stack_with_unreachable_layer_returns_invalid_graph() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let result = create_stack(
+            &ctx,
+            StackGraph {
+                root: "first".to_string(),
+                layers: vec![
+                    layer("first", &[], pass_through(None)),
+                    layer("orphan", &[], pass_through(None)),
+                ],
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StacksServiceError::InvalidGraph(PricingError::Graph(_)))
+            ),
+            "expected InvalidGraph, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_stack_with_unknown_layer_returns_invalid_graph() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let result = create_stack(
+            &ctx,
+            StackGraph {
+                root: "first".to_string(),
+                layers: vec![layer("first", &[], pass_through(Some("missing")))],
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StacksServiceError::InvalidGraph(PricingError::UnknownLayer(ref name)))
+                    if name == "missing"
+            ),
+            "expected InvalidGraph, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_stack_with_unknown_promotion_returns_invalid_reference() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let result = create_stack(
+            &ctx,
+            StackGraph {
+                root: "first".to_string(),
+                layers: vec![layer("first", &[PromotionUuid::new()], pass_through(None))],
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(StacksServiceError::InvalidReference)),
+            "expected InvalidReference, got {result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_stack_creates_new_version() -> TestResult {
+        let ctx = TestContext::new().await;
+        let promotions = create_promotions(&ctx, 2).await?;
+
+        let original = StackGraph {
+            root: "default".to_string(),
+            layers: vec![layer("default", &promotions, pass_through(None))],
+        };
+
+        let stack = create_stack(&ctx, original.clone()).await?;
+
+        let before_update = Timestamp::now();
+
+        let updated = StackGraph {
+            root: "first".to_string(),
+            layers: vec![
+                layer("first", &promotions[..1], pass_through(Some("second"))),
+                layer("second", &promotions[1..], pass_through(None)),
+            ],
+        };
+
+        ctx.stacks
+            .update_stack(
+                ctx.tenant_uuid,
+                stack.uuid,
+                StackUpdate {
+                    graph: updated.clone(),
+                },
+            )
+            .await?;
+
+        let current = ctx
+            .stacks
+            .get_stack(ctx.tenant_uuid, stack.uuid, Timestamp::now())
+            .await?;
+
+        let previous = ctx
+            .stacks
+            .get_stack(ctx.tenant_uuid, stack.uuid, before_update)
+            .await?;
+
+        assert_eq!(current.graph, updated);
+        assert_ne!(current.version_uuid, previous.version_uuid);
+        assert_eq!(previous.graph, original);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_stack_not_found_returns_not_found() {
+        let ctx = TestContext::new().await;
+
+        let result = ctx
+            .stacks
+            .update_stack(
+                ctx.tenant_uuid,
+                StackUuid::new(),
+                StackUpdate {
+                    graph: StackGraph {
+                        root: "default".to_string(),
+                        layers: vec![layer("default", &[], pass_through(None))],
+                    },
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(StacksServiceError::NotFound)),
+            "expected NotFound, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn stack_not_visible_to_other_tenant() -> TestResult {
+        let ctx = TestContext::new().await;
+
+        let stack = create_stack(
+            &ctx,
+            StackGraph {
+                root: "default".to_string(),
+                layers: vec![layer("default", &[], pass_through(None))],
+            },
+        )
+        .await?;
+
+        let tenant_b = ctx.create_tenant("Tenant B").await;
+
+        let result = ctx
+            .stacks
+            .get_stack(tenant_b, stack.uuid, Timestamp::now())
+            .await;
+
+        assert!(
+            matches!(result, Err(StacksServiceError::NotFound)),
+            "expected NotFound for cross-tenant access, got {result:?}"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+INSERT INTO
+  stacks (uuid)
+VALUES
+  ($1)
+RETURNING
+  uuid,
+  created_at,
+  updated_at,
+  deleted_at
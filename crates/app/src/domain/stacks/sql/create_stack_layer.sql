@@ -0,0 +1,22 @@
+INSERT INTO
+  stack_layers (
+    uuid,
+    stack_version_uuid,
+    position,
+    name,
+    output_mode,
+    next_layer,
+    participating_layer,
+    non_participating_layer
+  )
+VALUES
+  (
+    $1,
+    $2,
+    $3,
+    $4,
+    $5::stack_layer_output_mode,
+    $6,
+    $7,
+    $8
+  )
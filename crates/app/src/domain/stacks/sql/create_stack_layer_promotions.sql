@@ -0,0 +1,8 @@
+INSERT INTO
+  stack_layer_promotions (stack_layer_uuid, promotion_uuid, position)
+SELECT
+  $1,
+  layer_promotions.promotion_uuid,
+  layer_promotions.ordinality - 1
+FROM
+  UNNEST($2::UUID[]) WITH ORDINALITY AS layer_promotions (promotion_uuid, ordinality)
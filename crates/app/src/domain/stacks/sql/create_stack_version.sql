@@ -0,0 +1,4 @@
+INSERT INTO
+  stack_versions (uuid, stack_uuid, root_layer)
+VALUES
+  ($1, $1, $2)
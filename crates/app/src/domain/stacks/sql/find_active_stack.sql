@@ -0,0 +1,21 @@
+SELECT
+  stacks.uuid,
+  stack_versions.uuid AS version_uuid,
+  stack_versions.root_layer,
+  stacks.created_at,
+  stacks.updated_at,
+  stacks.deleted_at
+FROM
+  stacks
+  INNER JOIN stack_versions ON stack_versions.stack_uuid = stacks.uuid
+WHERE
+  stack_versions.valid_period @> $1::TIMESTAMPTZ
+  AND stacks.created_at <= $1::TIMESTAMPTZ
+  AND (
+    stacks.deleted_at IS NULL
+    OR stacks.deleted_at > $1::TIMESTAMPTZ
+  )
+ORDER BY
+  stacks.created_at DESC
+LIMIT
+  1
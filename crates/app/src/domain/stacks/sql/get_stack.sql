@@ -0,0 +1,18 @@
+SELECT
+  stacks.uuid,
+  stack_versions.uuid AS version_uuid,
+  stack_versions.root_layer,
+  stacks.created_at,
+  stacks.updated_at,
+  stacks.deleted_at
+FROM
+  stacks
+  INNER JOIN stack_versions ON stack_versions.stack_uuid = stacks.uuid
+WHERE
+  stacks.uuid = $1
+  AND stack_versions.valid_period @> $2::TIMESTAMPTZ
+  AND stacks.created_at <= $2::TIMESTAMPTZ
+  AND (
+    stacks.deleted_at IS NULL
+    OR stacks.deleted_at > $2::TIMESTAMPTZ
+  )
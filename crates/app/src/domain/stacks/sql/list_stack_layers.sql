@@ -0,0 +1,22 @@
+SELECT
+  stack_layers.name,
+  stack_layers.output_mode::TEXT AS output_mode,
+  stack_layers.next_layer,
+  stack_layers.participating_layer,
+  stack_layers.non_participating_layer,
+  ARRAY(
+    SELECT
+      stack_layer_promotions.promotion_uuid
+    FROM
+      stack_layer_promotions
+    WHERE
+      stack_layer_promotions.stack_layer_uuid = stack_layers.uuid
+    ORDER BY
+      stack_layer_promotions.position
+  ) AS promotion_uuids
+FROM
+  stack_layers
+WHERE
+  stack_layers.stack_version_uuid = $1
+ORDER BY
+  stack_layers.position
@@ -0,0 +1,41 @@
+WITH
+  target_stack AS (
+    SELECT
+      uuid
+    FROM
+      stacks
+    WHERE
+      uuid = $1
+      AND deleted_at IS NULL
+  ),
+  closed_current_version AS (
+    UPDATE stack_versions
+    SET
+      valid_period = tstzrange (lower(valid_period), NOW(), '[)')
+    WHERE
+      stack_uuid = (
+        SELECT
+          uuid
+        FROM
+          target_stack
+      )
+      AND upper_inf(valid_period)
+    RETURNING
+      stack_uuid
+  ),
+  inserted_version AS (
+    INSERT INTO
+      stack_versions (uuid, stack_uuid, root_layer)
+    SELECT
+      $2,
+      closed_current_version.stack_uuid,
+      $3
+    FROM
+      closed_current_version
+    RETURNING
+      uuid
+  )
+SELECT
+  uuid
+FROM
+  inserted_version
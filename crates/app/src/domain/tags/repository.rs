@@ -11,6 +11,7 @@ use super::{Taggable, records::TagUuid};
 const SYNC_TAGS_SQL: &str = include_str!("sql/sync_tags.sql");
 const CREATE_TAGGABLES_SQL: &str = include_str!("sql/create_taggables.sql");
 const DELETE_TAGGABLES_SQL: &str = include_str!("sql/delete_taggables.sql");
+const LIST_TAGGABLES_TAG_NAMES_SQL: &str = include_str!("sql/list_taggables_tag_names.sql");
 
 #[cfg(test)]
 const LIST_TAGGABLE_TAG_NAMES_SQL: &str = include_str!("sql/list_taggable_tag_names.sql");
@@ -221,6 +222,55 @@ impl PgTagsRepository {
         Ok(resolved)
     }
 
+    #[tracing::instrument(
+        name = "tags.repository.list_taggables_tag_names",
+        skip(self, tx, taggable_uuids),
+        fields(
+            taggable_type = tracing::field::Empty,
+            taggable_count = tracing::field::Empty
+        ),
+        err
+    )]
+    pub(crate) async fn list_taggables_tag_names<T>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        taggable_uuids: &[T],
+    ) -> Result<FxHashMap<Uuid, SmallVec<[String; 3]>>, sqlx::Error>
+    where
+        T: Taggable + Copy + Into<Uuid>,
+    {
+        let taggable_count = taggable_uuids.len();
+
+        tracing::Span::current().record("taggable_type", T::type_as_str());
+        tracing::Span::current().record("taggable_count", taggable_count);
+
+        if taggable_uuids.is_empty() {
+            return Ok(FxHashMap::default());
+        }
+
+        let uuids: Vec<Uuid> = taggable_uuids.iter().copied().map(Into::into).collect();
+
+        let rows: Vec<(Uuid, Vec<String>)> = query_as(LIST_TAGGABLES_TAG_NAMES_SQL)
+            .bind(T::type_as_str())
+            .bind(&uuids)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        let tag_names: FxHashMap<Uuid, SmallVec<[String; 3]>> = rows
+            .into_iter()
+            .map(|(uuid, names)| (uuid, names.into_iter().collect()))
+            .collect();
+
+        debug!(
+            taggable_type = T::type_as_str(),
+            taggable_count,
+            tagged_count = tag_names.len(),
+            "listed taggables tag names"
+        );
+
+        Ok(tag_names)
+    }
+
     #[cfg(test)]
     pub(crate) async fn list_taggable_tag_names<T>(
         &self,
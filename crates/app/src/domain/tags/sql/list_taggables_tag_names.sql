@@ -0,0 +1,11 @@
+SELECT
+  tg.taggable_uuid,
+  array_agg(t.name ORDER BY t.name) AS names
+FROM
+  taggables tg
+  JOIN tags t ON t.uuid = tg.tag_uuid
+WHERE
+  tg.taggable_type = $1::taggable_type
+  AND tg.taggable_uuid = ANY($2::uuid[])
+GROUP BY
+  tg.taggable_uuid
@@ -6,8 +6,11 @@ use crate::{
     database::Db,
     domain::{
         carts::PgCartsService,
+        pricing::DEFAULT_CURRENCY,
         products::PgProductsService,
         promotions::service::PgPromotionsService,
+        quotes::PgQuotesService,
+        stacks::PgStacksService,
         tenants::{PgTenantsService, TenantsService, data::NewTenant, records::TenantUuid},
     },
 };
@@ -23,7 +26,9 @@ pub struct TestContext {
     pub tenant_uuid: TenantUuid,
     pub products: PgProductsService,
     pub promotions: PgPromotionsService,
+    pub quotes: PgQuotesService,
     pub carts: PgCartsService,
+    pub stacks: PgStacksService,
 }
 
 impl TestContext {
@@ -48,7 +53,9 @@ impl TestContext {
         Self {
             products: PgProductsService::new(db.clone()),
             promotions: PgPromotionsService::new(db.clone()),
-            carts: PgCartsService::new(db),
+            quotes: PgQuotesService::new(db.clone(), DEFAULT_CURRENCY),
+            carts: PgCartsService::new(db.clone(), DEFAULT_CURRENCY),
+            stacks: PgStacksService::new(db, DEFAULT_CURRENCY),
             tenant_uuid,
             db: test_db,
         }
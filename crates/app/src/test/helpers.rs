@@ -15,6 +15,24 @@ use crate::{
             data::NewProduct,
             records::{ProductRecord, ProductUuid},
         },
+        promotions::{
+            PromotionsServiceError,
+            data::{
+                NewPromotion,
+                budgets::Budgets,
+                discounts::SimpleDiscount,
+                qualification::{
+                    Qualification, QualificationContext, QualificationOp, QualificationRule,
+                },
+            },
+            records::{PromotionRecord, PromotionUuid},
+            service::PromotionsService,
+        },
+        stacks::{
+            StacksService, StacksServiceError,
+            data::{NewStack, StackGraph},
+            records::{StackRecord, StackUuid},
+        },
         tenants::records::TenantUuid,
     },
     test::TestContext,
@@ -83,3 +101,41 @@ pub(crate) async fn create_product(
         )
         .await
 }
+
+pub(crate) async fn create_direct_discount_promotion(
+    ctx: &TestContext,
+    tenant: TenantUuid,
+    promotion: PromotionUuid,
+    percentage: u16,
+    tags: SmallVec<[String; 3]>,
+) -> Result<PromotionRecord, PromotionsServiceError> {
+    ctx.promotions
+        .create_promotion(
+            tenant,
+            NewPromotion::DirectDiscount {
+                uuid: promotion,
+                budgets: Budgets {
+                    redemptions: None,
+                    monetary: None,
+                },
+                discount: SimpleDiscount::PercentageOff { percentage },
+                qualification: Some(Qualification {
+                    context: QualificationContext::Primary,
+                    op: QualificationOp::And,
+                    rules: vec![QualificationRule::HasAny { tags }],
+                }),
+            },
+        )
+        .await
+}
+
+pub(crate) async fn create_stack(
+    ctx: &TestContext,
+    tenant: TenantUuid,
+    stack: StackUuid,
+    graph: StackGraph,
+) -> Result<StackRecord, StacksServiceError> {
+    ctx.stacks
+        .create_stack(tenant, NewStack { uuid: stack, graph })
+        .await
+}
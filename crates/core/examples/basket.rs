@@ -4,7 +4,9 @@
 //!
 //! Use `-f` to load a fixture set by name
 //! Use `-n` to limit the number of items
-//! Use `-o` to specify the filename of a typst formatted output file in `target/ilp-formulations`
+//! Use `-o` to specify the filename of an output file in `target/ilp-formulations`: a `.lp`
+//! or `.mps` name writes one CPLEX-LP or MPS file per layer, a `.json` name writes a trace
+//! of the formulation and solved values, anything else a typst document
 
 use std::{fs::create_dir_all, io, io::Write, path::PathBuf, time::Instant};
 
@@ -13,8 +15,15 @@ use clap::Parser;
 use humanize_duration::{Truncate, prelude::DurationExt};
 
 use lattice::{
-    fixtures::Fixture, items::groups::ItemGroup, receipt::Receipt,
-    solvers::ilp::renderers::typst::MultiLayerRenderer, utils::ExampleBasketArgs,
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    receipt::Receipt,
+    solvers::ilp::renderers::{
+        json::TraceObserver,
+        lp::{LpFormat, LpRenderer},
+        typst::MultiLayerRenderer,
+    },
+    utils::ExampleBasketArgs,
 };
 
 /// Processed Basket Receipt Example
@@ -33,25 +42,69 @@ pub fn main() -> Result<()> {
 
         create_dir_all(&output_dir)?;
 
-        let mut renderer = MultiLayerRenderer::new_with_metadata(
-            output_dir.join(out),
-            &item_group,
-            fixture.product_meta_map(),
-            fixture.promotion_meta_map(),
-        );
-
-        let result = fixture
-            .graph()?
-            .evaluate_with_observer(&item_group, Some(&mut renderer))?;
-
-        renderer.write()?;
-
-        println!(
-            "\nILP formulation written to: {}",
-            renderer.output_path().display()
-        );
-
-        result
+        let output_path = output_dir.join(out);
+        let extension = output_path.extension().and_then(|ext| ext.to_str());
+        let format = match extension {
+            Some("lp") => Some(LpFormat::CplexLp),
+            Some("mps") => Some(LpFormat::Mps),
+            _ => None,
+        };
+
+        if extension == Some("json") {
+            let mut observer = TraceObserver::new_with_metadata(
+                &item_group,
+                fixture.product_meta_map(),
+                fixture.promotion_meta_map(),
+            );
+
+            let result = fixture
+                .graph()?
+                .evaluate_with_observer(&item_group, Some(&mut observer))?;
+
+            observer.write(&output_path)?;
+
+            println!("\nILP trace written to: {}", output_path.display());
+
+            result
+        } else if let Some(format) = format {
+            let mut renderer = LpRenderer::new_with_metadata(
+                output_path,
+                format,
+                &item_group,
+                fixture.product_meta_map(),
+                fixture.promotion_meta_map(),
+            );
+
+            let result = fixture
+                .graph()?
+                .evaluate_with_observer(&item_group, Some(&mut renderer))?;
+
+            for path in renderer.write()? {
+                println!("\nILP formulation written to: {}", path.display());
+            }
+
+            result
+        } else {
+            let mut renderer = MultiLayerRenderer::new_with_metadata(
+                output_path,
+                &item_group,
+                fixture.product_meta_map(),
+                fixture.promotion_meta_map(),
+            );
+
+            let result = fixture
+                .graph()?
+                .evaluate_with_observer(&item_group, Some(&mut renderer))?;
+
+            renderer.write()?;
+
+            println!(
+                "\nILP formulation written to: {}",
+                renderer.output_path().display()
+            );
+
+            result
+        }
     } else {
         fixture.graph()?.evaluate(&item_group)?
     };
@@ -40,6 +40,8 @@ impl<'a, T: TagCollection> Basket<'a, T> {
 
     /// Create a new basket with the given items.
     ///
+    /// Items with a quantity are expanded into one item per unit.
+    ///
     /// # Errors
     ///
     /// Returns a `BasketError` if there was a currency mismatch error.
@@ -63,6 +65,12 @@ impl<'a, T: TagCollection> Basket<'a, T> {
             }
         })?;
 
+        let items = if items.iter().all(|item| item.quantity() == 1) {
+            items
+        } else {
+            items.into_iter().flat_map(Item::into_units).collect()
+        };
+
         Ok(Basket { items, currency })
     }
 
@@ -184,6 +192,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn with_items_expands_quantities_into_units() -> TestResult {
+        let items = [
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(50, GBP),
+                StringTagCollection::empty(),
+                4,
+            ),
+            Item::new(ProductKey::default(), Money::from_minor(200, GBP)),
+        ];
+
+        let basket = Basket::<'_, StringTagCollection>::with_items(items, GBP)?;
+
+        assert_eq!(basket.len(), 5);
+        assert_eq!(basket.subtotal()?, Money::from_minor(400, GBP));
+
+        Ok(())
+    }
+
     #[test]
     fn subtotal_with_no_items() -> TestResult {
         let basket = Basket::<'_, StringTagCollection>::new(GBP);
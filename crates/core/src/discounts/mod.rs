@@ -11,6 +11,8 @@ use rust_decimal::{
 use rusty_money::{Money, MoneyError, iso::Currency};
 use thiserror::Error;
 
+use crate::items::measure::{Measure, MeasureError};
+
 /// Errors specific to discount calculations.
 #[derive(Debug, Error)]
 pub enum DiscountError {
@@ -25,6 +27,10 @@ pub enum DiscountError {
     /// Wrapped money arithmetic or currency mismatch error.
     #[error(transparent)]
     Money(#[from] MoneyError),
+
+    /// Scaling a per-measure amount overflowed.
+    #[error(transparent)]
+    Measure(#[from] MeasureError),
 }
 
 /// Discount configuration for promotions without complex requirements.
@@ -38,6 +44,12 @@ pub enum SimpleDiscount<'a> {
 
     /// Subtract a fixed amount from item price (e.g., "£2 off")
     AmountOff(Money<'a, Currency>),
+
+    /// Subtract a fixed amount per kilogram or litre from the price of
+    /// variable-measure items (e.g., "£1 off per kg").
+    ///
+    /// Items sold by count have no measure and are not discounted.
+    AmountOffPerMeasure(Money<'a, Currency>),
 }
 
 /// Calculate the discount amount in minor units based on a percentage and a minor unit amount.
@@ -60,6 +72,23 @@ pub fn percent_of_minor(percent: &Percentage, minor: i64) -> Result<i64, Discoun
         .ok_or(DiscountError::PercentConversion)
 }
 
+/// Calculate the amount taken off an item with the given measure by a per-measure discount.
+///
+/// Items without a measure are sold by count, so no amount is taken off.
+///
+/// # Errors
+///
+/// Returns an error if the scaled amount does not fit in minor units (`DiscountError::Measure`).
+pub fn amount_off_per_measure_minor(
+    amount_minor: i64,
+    measure: Option<Measure>,
+) -> Result<i64, DiscountError> {
+    match measure {
+        Some(measure) => Ok(measure.scale_minor(amount_minor)?),
+        None => Ok(0),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -97,6 +126,17 @@ mod tests {
         assert!(matches!(result, Err(DiscountError::PercentConversion)));
     }
 
+    #[test]
+    fn amount_off_per_measure_scales_to_measure() -> TestResult {
+        assert_eq!(
+            amount_off_per_measure_minor(100, Some(Measure::grams(1500)))?,
+            150
+        );
+        assert_eq!(amount_off_per_measure_minor(100, None)?, 0);
+
+        Ok(())
+    }
+
     #[test]
     fn percent_of_minor_calculates_correctly() -> TestResult {
         let percent = Percentage::from(0.25);
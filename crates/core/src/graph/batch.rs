@@ -0,0 +1,403 @@
+//! Batch Evaluation
+//!
+//! Replays many baskets through one graph, such as last month's orders against a
+//! proposed set of promotions. Promotions qualify items by their tags, so the
+//! layers an item may qualify for are matched once per distinct set of tags and
+//! reused for every later basket; layers that none of a basket's items qualify
+//! for are not solved. Each basket's result is handed on as soon as it is
+//! priced, and totals are kept per promotion, without building a
+//! [`Receipt`](crate::receipt::Receipt) for any basket.
+
+use petgraph::visit::NodeIndexable;
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
+
+use crate::{
+    graph::{
+        PromotionGraph, error::GraphError, evaluation::LayerSolver, result::LayeredSolverResult,
+    },
+    items::{Item, groups::ItemGroup},
+    promotions::PromotionKey,
+    solvers::ilp::ILPSolverConfig,
+    tags::string::StringTagCollection,
+};
+
+/// Number of baskets priced together before their results are handed on.
+const CHUNK_SIZE: usize = 256;
+
+/// Whether an item may qualify for each layer, by node index.
+type LayerMatches = SmallVec<[bool; 8]>;
+
+/// An evaluation of many baskets with the same graph.
+///
+/// Layer matches are kept between calls to [`evaluate()`](Self::evaluate), so
+/// later batches reuse the ones earlier batches worked out.
+#[derive(Debug)]
+pub struct BatchEvaluator<'g, 'a> {
+    graph: &'g PromotionGraph<'a>,
+    config: ILPSolverConfig,
+
+    /// Layers each distinct set of item tags may qualify for
+    layer_matches: FxHashMap<StringTagCollection, LayerMatches>,
+}
+
+/// One basket's outcome in a batch.
+#[derive(Debug, Clone)]
+pub struct BasketSummary<'b> {
+    /// Position of the basket in the batch
+    pub basket_idx: usize,
+
+    /// Basket total before any promotions, in minor units
+    pub subtotal_minor: i64,
+
+    /// The basket's evaluation result
+    pub result: LayeredSolverResult<'b>,
+}
+
+impl BasketSummary<'_> {
+    /// How much the promotions took off the basket, in minor units.
+    pub fn savings_minor(&self) -> i64 {
+        self.subtotal_minor - self.result.total.to_minor_units()
+    }
+}
+
+/// Totals across every basket in a batch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchTotals {
+    /// Number of baskets evaluated
+    pub baskets: usize,
+
+    /// Number of baskets with a layer that was not solved to optimality
+    pub non_optimal_baskets: usize,
+
+    /// Sum of the basket totals before any promotions, in minor units
+    pub subtotal_minor: i64,
+
+    /// Sum of the basket totals, in minor units
+    pub total_minor: i64,
+
+    /// Totals for each promotion that was redeemed at least once
+    pub promotions: FxHashMap<PromotionKey, PromotionTotals>,
+}
+
+impl BatchTotals {
+    /// How much the promotions took off every basket, in minor units.
+    pub fn savings_minor(&self) -> i64 {
+        self.subtotal_minor - self.total_minor
+    }
+
+    /// Add a basket's outcome to the totals.
+    fn add(&mut self, summary: &BasketSummary<'_>) {
+        self.baskets += 1;
+        self.subtotal_minor += summary.subtotal_minor;
+        self.total_minor += summary.result.total.to_minor_units();
+
+        if !summary.result.optimal {
+            self.non_optimal_baskets += 1;
+        }
+
+        let mut redemptions: SmallVec<[(PromotionKey, usize); 16]> = SmallVec::new();
+
+        for redemption in summary.result.item_redemptions.values().flatten() {
+            let totals = self.promotions.entry(redemption.promotion_key).or_default();
+
+            totals.items += 1;
+            totals.discount_minor += redemption.original_price.to_minor_units()
+                - redemption.final_price.to_minor_units();
+
+            redemptions.push((redemption.promotion_key, redemption.redemption_idx));
+        }
+
+        redemptions.sort_unstable();
+        redemptions.dedup();
+
+        for (position, &(promotion_key, _)) in redemptions.iter().enumerate() {
+            let totals = self.promotions.entry(promotion_key).or_default();
+
+            totals.redemptions += 1;
+
+            let first_in_basket = position == 0
+                || redemptions
+                    .get(position - 1)
+                    .is_none_or(|(previous, _)| *previous != promotion_key);
+
+            if first_in_basket {
+                totals.baskets += 1;
+            }
+        }
+    }
+}
+
+/// A promotion's totals across every basket in a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PromotionTotals {
+    /// How much the promotion took off item prices, in minor units
+    pub discount_minor: i64,
+
+    /// Number of times the promotion was redeemed; a bundle of items counts once
+    pub redemptions: usize,
+
+    /// Number of items the promotion was applied to
+    pub items: usize,
+
+    /// Number of baskets the promotion was redeemed in
+    pub baskets: usize,
+}
+
+impl<'g, 'a> BatchEvaluator<'g, 'a> {
+    /// Start a batch evaluation solving every layer with `config`.
+    pub(super) fn new(graph: &'g PromotionGraph<'a>, config: ILPSolverConfig) -> Self {
+        Self {
+            graph,
+            config,
+            layer_matches: FxHashMap::default(),
+        }
+    }
+
+    /// Evaluate each item group, passing every basket's summary to `sink` in the
+    /// order the item groups were given.
+    ///
+    /// Baskets are priced in chunks, in parallel when the `parallel` feature is
+    /// enabled. A configured time limit applies to each basket. Every basket must
+    /// be in the same currency as the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if a basket is in a different currency to the
+    /// first or if a basket's evaluation fails. Baskets before it have already
+    /// been passed to `sink`.
+    pub fn evaluate<'b>(
+        &mut self,
+        item_groups: impl IntoIterator<Item = ItemGroup<'b>>,
+        mut sink: impl FnMut(BasketSummary<'b>),
+    ) -> Result<BatchTotals, GraphError> {
+        let mut totals = BatchTotals::default();
+        let mut chunk: Vec<(ItemGroup<'b>, LayerMatches)> = Vec::with_capacity(CHUNK_SIZE);
+        let mut batch_currency = None;
+        let mut first_idx = 0;
+
+        for (basket_idx, item_group) in item_groups.into_iter().enumerate() {
+            let currency = item_group.currency();
+            let batch_currency = *batch_currency.get_or_insert(currency);
+
+            if currency != batch_currency {
+                self.flush(first_idx, &mut chunk, &mut totals, &mut sink)?;
+
+                return Err(GraphError::BatchCurrencyMismatch {
+                    basket_idx,
+                    currency: currency.iso_alpha_code,
+                    batch_currency: batch_currency.iso_alpha_code,
+                });
+            }
+
+            let eligible_layers = self.eligible_layers(&item_group);
+
+            chunk.push((item_group, eligible_layers));
+
+            if chunk.len() == CHUNK_SIZE {
+                self.flush(first_idx, &mut chunk, &mut totals, &mut sink)?;
+                first_idx = basket_idx + 1;
+            }
+        }
+
+        self.flush(first_idx, &mut chunk, &mut totals, &mut sink)?;
+
+        Ok(totals)
+    }
+
+    /// Price the baskets in `chunk`, then pass them on in order.
+    fn flush<'b>(
+        &self,
+        first_idx: usize,
+        chunk: &mut Vec<(ItemGroup<'b>, LayerMatches)>,
+        totals: &mut BatchTotals,
+        sink: &mut impl FnMut(BasketSummary<'b>),
+    ) -> Result<(), GraphError> {
+        let results = self.evaluate_chunk(first_idx, chunk);
+
+        chunk.clear();
+
+        for result in results {
+            let summary = result?;
+
+            totals.add(&summary);
+            sink(summary);
+        }
+
+        Ok(())
+    }
+
+    /// Price each basket in `chunk`.
+    #[cfg(feature = "parallel")]
+    fn evaluate_chunk<'b>(
+        &self,
+        first_idx: usize,
+        chunk: &[(ItemGroup<'b>, LayerMatches)],
+    ) -> Vec<Result<BasketSummary<'b>, GraphError>> {
+        use rayon::prelude::*;
+
+        chunk
+            .par_iter()
+            .enumerate()
+            .map(|(position, (item_group, eligible_layers))| {
+                self.evaluate_basket(first_idx + position, item_group, eligible_layers)
+            })
+            .collect()
+    }
+
+    /// Price each basket in `chunk`.
+    #[cfg(not(feature = "parallel"))]
+    fn evaluate_chunk<'b>(
+        &self,
+        first_idx: usize,
+        chunk: &[(ItemGroup<'b>, LayerMatches)],
+    ) -> Vec<Result<BasketSummary<'b>, GraphError>> {
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(position, (item_group, eligible_layers))| {
+                self.evaluate_basket(first_idx + position, item_group, eligible_layers)
+            })
+            .collect()
+    }
+
+    /// Price one basket, solving only the layers its items may qualify for.
+    fn evaluate_basket<'b>(
+        &self,
+        basket_idx: usize,
+        item_group: &ItemGroup<'b>,
+        eligible_layers: &[bool],
+    ) -> Result<BasketSummary<'b>, GraphError> {
+        let result = self
+            .graph
+            .evaluate_with_solver(
+                item_group,
+                &mut LayerSolver::with_eligible_layers(&self.config, eligible_layers),
+                None,
+            )
+            .map_err(|source| GraphError::Basket {
+                basket_idx,
+                source: Box::new(source),
+            })?;
+
+        Ok(BasketSummary {
+            basket_idx,
+            subtotal_minor: item_group
+                .iter()
+                .map(|item| item.price().to_minor_units())
+                .sum(),
+            result,
+        })
+    }
+
+    /// Layers any of the basket's items may qualify for, by node index.
+    fn eligible_layers(&mut self, item_group: &ItemGroup<'_>) -> LayerMatches {
+        let mut eligible_layers: LayerMatches =
+            SmallVec::from_elem(false, self.graph.graph.node_bound());
+
+        for item in item_group.iter() {
+            if !self.layer_matches.contains_key(item.tags()) {
+                self.layer_matches
+                    .insert(item.tags().clone(), match_layers(self.graph, item));
+            }
+
+            let Some(matches) = self.layer_matches.get(item.tags()) else {
+                continue;
+            };
+
+            for (eligible, &matched) in eligible_layers.iter_mut().zip(matches) {
+                *eligible |= matched;
+            }
+        }
+
+        eligible_layers
+    }
+}
+
+/// Layers with a promotion `item` may qualify for, by node index.
+fn match_layers(graph: &PromotionGraph<'_>, item: &Item<'_>) -> LayerMatches {
+    let mut matches: LayerMatches = SmallVec::from_elem(false, graph.graph.node_bound());
+
+    for node_idx in graph.graph.node_indices() {
+        let Some(node) = graph.graph.node_weight(node_idx) else {
+            continue;
+        };
+
+        if let Some(matched) = matches.get_mut(node_idx.index()) {
+            *matched = node
+                .promotions
+                .iter()
+                .any(|promotion| promotion.is_item_eligible(item));
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::{Money, iso::GBP};
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        graph::{OutputMode, PromotionGraphBuilder},
+        products::ProductKey,
+        promotions::{
+            budget::PromotionBudget, promotion, qualification::Qualification,
+            types::DirectDiscountPromotion,
+        },
+    };
+
+    use super::*;
+
+    fn item<'a>(tags: &[&str]) -> Item<'a> {
+        Item::with_tags(
+            ProductKey::default(),
+            Money::from_minor(100, GBP),
+            StringTagCollection::from_strs(tags),
+        )
+    }
+
+    #[test]
+    fn layers_are_matched_once_per_tag_set() -> TestResult {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut builder = PromotionGraphBuilder::new();
+
+        let mut direct = |tag: &str| {
+            promotion(DirectDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&[tag])),
+                SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+                PromotionBudget::unlimited(),
+            ))
+        };
+
+        let first = builder.add_layer("a", [direct("a")], OutputMode::PassThrough)?;
+        let second = builder.add_layer("b", [direct("b")], OutputMode::PassThrough)?;
+
+        builder.set_root(first);
+        builder.connect_pass_through(first, second)?;
+
+        let graph = PromotionGraph::from_builder(builder)?;
+        let mut batch = graph.batch();
+
+        let only_a = ItemGroup::new(smallvec![item(&["a"]), item(&["a"]), item(&["c"])], GBP);
+        let eligible = batch.eligible_layers(&only_a);
+
+        assert_eq!(eligible.get(first.index()), Some(&true));
+        assert_eq!(eligible.get(second.index()), Some(&false));
+        assert_eq!(batch.layer_matches.len(), 2);
+
+        let with_b = ItemGroup::new(smallvec![item(&["a"]), item(&["b"])], GBP);
+        let eligible = batch.eligible_layers(&with_b);
+
+        assert_eq!(eligible.get(second.index()), Some(&true));
+        assert_eq!(batch.layer_matches.len(), 3);
+
+        Ok(())
+    }
+}
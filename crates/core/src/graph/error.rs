@@ -64,4 +64,29 @@ pub enum GraphError {
     /// Money arithmetic error during evaluation.
     #[error(transparent)]
     Money(#[from] MoneyError),
+
+    /// A basket in a batch is in a different currency to the batch.
+    #[error(
+        "basket {basket_idx} has currency {currency}, but the batch has currency {batch_currency}"
+    )]
+    BatchCurrencyMismatch {
+        /// Position of the basket in the batch
+        basket_idx: usize,
+
+        /// Currency of the basket
+        currency: &'static str,
+
+        /// Currency of the first basket in the batch
+        batch_currency: &'static str,
+    },
+
+    /// Evaluating a basket in a batch failed.
+    #[error("basket {basket_idx} failed: {source}")]
+    Basket {
+        /// Position of the basket in the batch
+        basket_idx: usize,
+
+        /// The underlying evaluation error
+        source: Box<GraphError>,
+    },
 }
@@ -1,8 +1,11 @@
 //! DFS graph evaluation engine.
 
+use std::time::Instant;
+
 use petgraph::graph::NodeIndex;
 use petgraph::stable_graph::StableDiGraph;
 use petgraph::visit::EdgeRef;
+use rustc_hash::FxHashMap;
 use rusty_money::{Money, iso::Currency};
 use smallvec::SmallVec;
 
@@ -11,16 +14,34 @@ use crate::{
         edge::LayerEdge,
         error::GraphError,
         node::{LayerNode, OutputMode},
+        result::LayeredSolverResult,
     },
     items::{Item, groups::ItemGroup},
     promotions::redemptions::PromotionRedemption,
-    solvers::{
-        Solver,
-        ilp::{ILPSolver, observer::ILPObserver},
+    solvers::ilp::{
+        ILPSolver, ILPSolverConfig,
+        components::ComponentCache,
+        observer::{ILPObserver, NoopObserver},
     },
 };
 
-type TrackedItems<'b> = SmallVec<[TrackedItem<'b>; 8]>;
+pub(super) type TrackedItems<'b> = SmallVec<[TrackedItem<'b>; 8]>;
+
+/// Layer results kept between evaluations, by layer node.
+pub(super) type LayerCache<'b> = FxHashMap<NodeIndex, CachedLayer<'b>>;
+
+/// A layer's input items and the redemptions its solve produced for them.
+#[derive(Debug)]
+pub(super) struct CachedLayer<'b> {
+    /// Items the layer was solved for, in order, at their input prices
+    input: SmallVec<[Item<'b>; 10]>,
+
+    /// Redemptions the layer produced, indexed by position in `input`
+    redemptions: SmallVec<[PromotionRedemption<'b>; 10]>,
+
+    /// The layer's components, for reuse when only some of them change
+    components: ComponentCache<'b>,
+}
 
 /// An item flowing through the graph, carrying provenance information.
 #[derive(Debug, Clone)]
@@ -35,6 +56,190 @@ pub(super) struct TrackedItem<'b> {
     pub redemptions: SmallVec<[PromotionRedemption<'b>; 3]>,
 }
 
+/// Solver settings shared by every layer of a single graph evaluation.
+#[derive(Debug)]
+pub(super) struct LayerSolver<'c, 'b> {
+    /// Configuration each layer is solved with
+    config: &'c ILPSolverConfig,
+
+    /// When the evaluation's time budget runs out, if it has one
+    deadline: Option<Instant>,
+
+    /// Whether every layer solved so far was solved to optimality
+    optimal: bool,
+
+    /// Optimal layer results from earlier evaluations, reused for layers whose
+    /// input is unchanged
+    cache: Option<&'c mut LayerCache<'b>>,
+
+    /// Number of layers solved so far
+    solved_layers: usize,
+
+    /// Number of layers whose cached result was reused so far
+    reused_layers: usize,
+
+    /// Number of components of solved layers whose cached result was reused so far
+    reused_components: usize,
+
+    /// Whether any item of the evaluation may qualify for each layer, by node
+    /// index; layers that no item qualifies for are not solved
+    eligible_layers: Option<&'c [bool]>,
+}
+
+impl<'c, 'b> LayerSolver<'c, 'b> {
+    /// Start an evaluation; the configured time limit covers all of its layers.
+    pub(super) fn new(config: &'c ILPSolverConfig) -> Self {
+        Self {
+            config,
+            deadline: config.time_limit().map(|limit| Instant::now() + limit),
+            optimal: true,
+            cache: None,
+            solved_layers: 0,
+            reused_layers: 0,
+            reused_components: 0,
+            eligible_layers: None,
+        }
+    }
+
+    /// Start an evaluation that only solves the layers marked in `eligible_layers`.
+    pub(super) fn with_eligible_layers(
+        config: &'c ILPSolverConfig,
+        eligible_layers: &'c [bool],
+    ) -> Self {
+        Self {
+            eligible_layers: Some(eligible_layers),
+            ..Self::new(config)
+        }
+    }
+
+    /// Start an evaluation that reuses, and records, layer results in `cache`.
+    pub(super) fn with_cache(config: &'c ILPSolverConfig, cache: &'c mut LayerCache<'b>) -> Self {
+        Self {
+            cache: Some(cache),
+            ..Self::new(config)
+        }
+    }
+
+    /// Whether every layer solved so far was solved to optimality.
+    pub(super) fn optimal(&self) -> bool {
+        self.optimal
+    }
+
+    /// Number of layers solved so far.
+    pub(super) fn solved_layers(&self) -> usize {
+        self.solved_layers
+    }
+
+    /// Number of layers whose cached result was reused so far.
+    pub(super) fn reused_layers(&self) -> usize {
+        self.reused_layers
+    }
+
+    /// Number of components of solved layers whose cached result was reused so far.
+    pub(super) fn reused_components(&self) -> usize {
+        self.reused_components
+    }
+
+    /// A solver for another branch of the same evaluation.
+    ///
+    /// It shares the configuration and time budget, but not the cache or counts.
+    #[cfg(feature = "parallel")]
+    pub(super) fn fork(&self) -> LayerSolver<'c, 'b> {
+        Self {
+            config: self.config,
+            deadline: self.deadline,
+            optimal: true,
+            cache: None,
+            solved_layers: 0,
+            reused_layers: 0,
+            reused_components: 0,
+            eligible_layers: self.eligible_layers,
+        }
+    }
+
+    /// Fold a forked solver's outcome back into this one.
+    #[cfg(feature = "parallel")]
+    pub(super) fn join(&mut self, other: &LayerSolver<'_, 'b>) {
+        self.optimal &= other.optimal;
+        self.solved_layers += other.solved_layers;
+        self.reused_layers += other.reused_layers;
+        self.reused_components += other.reused_components;
+    }
+
+    /// Whether any item may qualify for the layer's promotions.
+    pub(super) fn may_apply(&self, node_idx: NodeIndex) -> bool {
+        self.eligible_layers.is_none_or(|eligible_layers| {
+            eligible_layers
+                .get(node_idx.index())
+                .copied()
+                .unwrap_or(false)
+        })
+    }
+
+    /// Cached redemptions for the layer, if it was last solved for the same input.
+    fn cached(
+        &self,
+        node_idx: NodeIndex,
+        input: &ItemGroup<'b>,
+    ) -> Option<SmallVec<[PromotionRedemption<'b>; 10]>> {
+        let cached = self.cache.as_deref()?.get(&node_idx)?;
+
+        cached
+            .input
+            .iter()
+            .eq(input.iter())
+            .then(|| cached.redemptions.clone())
+    }
+
+    /// The layer's cached components, taken out to be reused and replaced, if
+    /// results are being cached.
+    fn take_components(&mut self, node_idx: NodeIndex) -> Option<ComponentCache<'b>> {
+        let cache = self.cache.as_deref_mut()?;
+
+        Some(
+            cache
+                .get_mut(&node_idx)
+                .map(|cached| std::mem::take(&mut cached.components))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Remember the layer's redemptions and components for `input`, if results
+    /// are being cached.
+    fn store(
+        &mut self,
+        node_idx: NodeIndex,
+        input: &ItemGroup<'b>,
+        redemptions: &[PromotionRedemption<'b>],
+        components: ComponentCache<'b>,
+    ) {
+        if let Some(cache) = self.cache.as_deref_mut() {
+            cache.insert(
+                node_idx,
+                CachedLayer {
+                    input: (0..input.len())
+                        .filter_map(|idx| input.get_item(idx).ok())
+                        .cloned()
+                        .collect(),
+                    redemptions: redemptions.iter().cloned().collect(),
+                    components,
+                },
+            );
+        }
+    }
+
+    /// Configuration for the next layer, limited to the time left in the budget.
+    fn layer_config(&self) -> ILPSolverConfig {
+        match self.deadline {
+            Some(deadline) => self
+                .config
+                .clone()
+                .with_time_limit(deadline.saturating_duration_since(Instant::now())),
+            None => self.config.clone(),
+        }
+    }
+}
+
 /// Evaluate a single node in the promotion graph.
 ///
 /// Solves the ILP for the node's promotions, then routes items to successors
@@ -49,6 +254,7 @@ pub fn evaluate_node<'b>(
     tracked_items: TrackedItems<'b>,
     currency: &'b Currency,
     next_redemption_idx: &mut usize,
+    solver: &mut LayerSolver<'_, 'b>,
     mut observer: Option<&mut dyn ILPObserver>,
 ) -> Result<TrackedItems<'b>, GraphError> {
     if tracked_items.is_empty() {
@@ -59,20 +265,60 @@ pub fn evaluate_node<'b>(
         return Ok(tracked_items);
     };
 
-    // If this layer has no promotions, skip the solve and just route items through.
-    // This avoids pointless ILP solver invocations for pure routing layers.
-    if node.promotions.is_empty() {
+    // If this layer has no promotions, or none any item qualifies for, skip the
+    // solve and just route items through. This avoids pointless ILP solver
+    // invocations for pure routing layers.
+    if node.promotions.is_empty() || !solver.may_apply(node_idx) {
         return route_to_successors(
             graph,
             node_idx,
-            node.output_mode,
             tracked_items,
             currency,
             next_redemption_idx,
+            solver,
             observer,
         );
     }
 
+    let updated_items = solve_node(
+        node_idx,
+        node,
+        tracked_items,
+        currency,
+        next_redemption_idx,
+        solver,
+        observer.as_deref_mut(),
+    )?;
+
+    // Route items to successors based on output mode
+    route_to_successors(
+        graph,
+        node_idx,
+        updated_items,
+        currency,
+        next_redemption_idx,
+        solver,
+        observer,
+    )
+}
+
+/// Solve a layer's promotions and apply its redemptions to the tracked items.
+///
+/// Redemption indexes are numbered from `next_redemption_idx`, which is advanced
+/// past the ones this layer used.
+///
+/// # Errors
+///
+/// Returns a [`GraphError`] if the solver fails.
+pub(super) fn solve_node<'b>(
+    node_idx: NodeIndex,
+    node: &LayerNode<'_>,
+    tracked_items: TrackedItems<'b>,
+    currency: &'b Currency,
+    next_redemption_idx: &mut usize,
+    solver: &mut LayerSolver<'_, 'b>,
+    mut observer: Option<&mut dyn ILPObserver>,
+) -> Result<TrackedItems<'b>, GraphError> {
     // Build a temporary ItemGroup from the tracked items' current prices
     let temp_items: SmallVec<[Item<'b, _>; 10]> =
         tracked_items.iter().map(|ti| ti.item.clone()).collect();
@@ -85,10 +331,10 @@ pub fn evaluate_node<'b>(
     }
 
     // Solve the ILP for this layer.
-    let redemptions = solve_layer(node, &temp_group, observer.as_deref_mut())?;
+    let redemptions = solve_layer(node_idx, node, &temp_group, solver, observer.as_deref_mut())?;
 
     // Notify observer of layer completion
-    if let Some(obs) = observer.as_deref_mut() {
+    if let Some(obs) = observer {
         obs.on_layer_end();
     }
 
@@ -111,11 +357,9 @@ pub fn evaluate_node<'b>(
         };
 
         // Update item price to the discounted price
-        tracked.item = Item::with_tags(
-            tracked.item.product(),
-            Money::from_minor(final_price_minor, currency),
-            tracked.item.tags().clone(),
-        );
+        tracked.item = tracked
+            .item
+            .with_price(Money::from_minor(final_price_minor, currency));
 
         // Record the redemption with remapped indices
         tracked.redemptions.push(PromotionRedemption {
@@ -134,33 +378,67 @@ pub fn evaluate_node<'b>(
         *next_redemption_idx = redemption_idx_offset.saturating_add(max).saturating_add(1);
     }
 
-    // Route items to successors based on output mode
-    route_to_successors(
-        graph,
-        node_idx,
-        node.output_mode,
-        updated_items,
-        currency,
-        next_redemption_idx,
-        observer,
-    )
+    Ok(updated_items)
 }
 
-/// Solve the ILP for a layer.
+/// Solve the ILP for a layer, or reuse its cached result if its input is unchanged.
+///
+/// When the input has changed, the layer's components that have not are reused.
 fn solve_layer<'b>(
+    node_idx: NodeIndex,
     node: &LayerNode<'_>,
     temp_group: &ItemGroup<'b>,
+    solver: &mut LayerSolver<'_, 'b>,
     observer: Option<&mut dyn ILPObserver>,
 ) -> Result<SmallVec<[PromotionRedemption<'b>; 10]>, GraphError> {
-    let result = match observer {
-        Some(obs) => ILPSolver::solve_with_observer(&node.promotions, temp_group, obs),
-        None => ILPSolver::solve(&node.promotions, temp_group),
+    if let Some(redemptions) = solver.cached(node_idx, temp_group) {
+        solver.reused_layers += 1;
+
+        return Ok(redemptions);
+    }
+
+    let mut noop = NoopObserver;
+    let observer = match observer {
+        Some(obs) => obs,
+        None => &mut noop,
+    };
+
+    let config = solver.layer_config();
+    let mut components = solver.take_components(node_idx);
+
+    let result = match components.as_mut() {
+        Some(components) => ILPSolver::solve_reusing_components(
+            &config,
+            &node.promotions,
+            temp_group,
+            observer,
+            components,
+        ),
+        None => ILPSolver::solve_with_config(&config, &node.promotions, temp_group, observer),
     }
     .map_err(|source| GraphError::Solver {
         layer_key: node.key,
         source,
     })?;
 
+    solver.optimal &= result.optimal;
+    solver.solved_layers += 1;
+
+    if let Some(components) = components {
+        solver.reused_components += components.reused();
+
+        // Only proven optima are worth reusing; a time-limited result may
+        // improve with another attempt.
+        if result.optimal {
+            solver.store(
+                node_idx,
+                temp_group,
+                &result.promotion_redemptions,
+                components,
+            );
+        }
+    }
+
     Ok(result.promotion_redemptions)
 }
 
@@ -168,56 +446,36 @@ fn solve_layer<'b>(
 fn route_to_successors<'b>(
     graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
     node_idx: NodeIndex,
-    output_mode: OutputMode,
     updated_items: TrackedItems<'b>,
     currency: &'b Currency,
     next_redemption_idx: &mut usize,
+    solver: &mut LayerSolver<'_, 'b>,
     mut observer: Option<&mut dyn ILPObserver>,
 ) -> Result<TrackedItems<'b>, GraphError> {
-    let edges: SmallVec<[(NodeIndex, LayerEdge); 2]> = graph
-        .edges(node_idx)
-        .map(|e| (e.target(), *e.weight()))
-        .collect();
+    let Some(output_mode) = graph.node_weight(node_idx).map(|node| node.output_mode) else {
+        return Ok(updated_items);
+    };
 
-    match output_mode {
-        OutputMode::PassThrough => {
-            let successor = edges.iter().find(|(_, w)| *w == LayerEdge::All);
+    let edges = successor_edges(graph, node_idx);
 
-            match successor {
-                Some((target, _)) => evaluate_node(
-                    graph,
-                    *target,
-                    updated_items,
-                    currency,
-                    next_redemption_idx,
-                    observer.as_deref_mut(),
-                ),
-                None => Ok(updated_items),
-            }
-        }
+    match output_mode {
+        OutputMode::PassThrough => match successor(&edges, LayerEdge::All) {
+            Some(target) => evaluate_node(
+                graph,
+                target,
+                updated_items,
+                currency,
+                next_redemption_idx,
+                solver,
+                observer.as_deref_mut(),
+            ),
+            None => Ok(updated_items),
+        },
         OutputMode::Split => {
-            let mut promoted_items: TrackedItems<'b> = TrackedItems::new();
-            let mut unpromoted_items: TrackedItems<'b> = TrackedItems::new();
-
-            for item in updated_items {
-                let was_discounted = !item.redemptions.is_empty();
-
-                if was_discounted {
-                    promoted_items.push(item);
-                } else {
-                    unpromoted_items.push(item);
-                }
-            }
+            let (promoted_items, unpromoted_items) = split_items(updated_items);
 
-            let promoted_target = edges
-                .iter()
-                .find(|(_, w)| *w == LayerEdge::Participating)
-                .map(|(t, _)| *t);
-
-            let unpromoted_target = edges
-                .iter()
-                .find(|(_, w)| *w == LayerEdge::NonParticipating)
-                .map(|(t, _)| *t);
+            let promoted_target = successor(&edges, LayerEdge::Participating);
+            let unpromoted_target = successor(&edges, LayerEdge::NonParticipating);
 
             let mut final_items: TrackedItems<'b> = TrackedItems::new();
 
@@ -230,6 +488,7 @@ fn route_to_successors<'b>(
                     promoted_items,
                     currency,
                     next_redemption_idx,
+                    solver,
                     observer.as_deref_mut(),
                 )?;
                 final_items.extend(result_items);
@@ -246,6 +505,7 @@ fn route_to_successors<'b>(
                     unpromoted_items,
                     currency,
                     next_redemption_idx,
+                    solver,
                     observer,
                 )?;
                 final_items.extend(result_items);
@@ -258,6 +518,96 @@ fn route_to_successors<'b>(
     }
 }
 
+/// Start tracking every item of `item_group` at its current price.
+///
+/// # Errors
+///
+/// Returns a [`GraphError`] if an item cannot be read from the group.
+pub(super) fn track_items<'b>(item_group: &ItemGroup<'b>) -> Result<TrackedItems<'b>, GraphError> {
+    let mut tracked_items: TrackedItems<'b> = SmallVec::with_capacity(item_group.len());
+
+    for idx in 0..item_group.len() {
+        let item = item_group.get_item(idx)?;
+        tracked_items.push(TrackedItem {
+            original_basket_idx: idx,
+            item: item.clone(),
+            redemptions: SmallVec::new(),
+        });
+    }
+
+    Ok(tracked_items)
+}
+
+/// Build the evaluation result from the items that left the graph.
+///
+/// # Errors
+///
+/// Returns a [`GraphError`] if the total cannot be summed.
+pub(super) fn into_result<'b>(
+    final_items: &[TrackedItem<'b>],
+    currency: &'b Currency,
+    optimal: bool,
+) -> Result<LayeredSolverResult<'b>, GraphError> {
+    let mut total = Money::from_minor(0, currency);
+
+    let mut item_redemptions: FxHashMap<usize, SmallVec<[PromotionRedemption<'b>; 3]>> =
+        FxHashMap::default();
+
+    let mut full_price_items: SmallVec<[usize; 10]> = SmallVec::new();
+
+    for tracked in final_items {
+        total = total.add(*tracked.item.price())?;
+
+        if tracked.redemptions.is_empty() {
+            full_price_items.push(tracked.original_basket_idx);
+        } else {
+            item_redemptions.insert(tracked.original_basket_idx, tracked.redemptions.clone());
+        }
+    }
+
+    Ok(LayeredSolverResult {
+        total,
+        item_redemptions,
+        full_price_items,
+        optimal,
+    })
+}
+
+/// A node's outgoing edges, as `(target, weight)` pairs.
+pub(super) fn successor_edges(
+    graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
+    node_idx: NodeIndex,
+) -> SmallVec<[(NodeIndex, LayerEdge); 2]> {
+    graph
+        .edges(node_idx)
+        .map(|e| (e.target(), *e.weight()))
+        .collect()
+}
+
+/// Target of the first edge with the given weight.
+pub(super) fn successor(edges: &[(NodeIndex, LayerEdge)], weight: LayerEdge) -> Option<NodeIndex> {
+    edges.iter().find(|(_, w)| *w == weight).map(|(t, _)| *t)
+}
+
+/// Separate items that have been discounted so far from those that have not,
+/// keeping their order.
+pub(super) fn split_items<'b>(items: TrackedItems<'b>) -> (TrackedItems<'b>, TrackedItems<'b>) {
+    let mut promoted_items: TrackedItems<'b> = TrackedItems::new();
+    let mut unpromoted_items: TrackedItems<'b> = TrackedItems::new();
+
+    for item in items {
+        let was_discounted = !item.redemptions.is_empty();
+
+        if was_discounted {
+            promoted_items.push(item);
+        } else {
+            unpromoted_items.push(item);
+        }
+    }
+
+    (promoted_items, unpromoted_items)
+}
+
 #[cfg(test)]
 mod tests {
     use good_lp::{Expression, Variable};
@@ -353,6 +703,7 @@ mod tests {
             items,
             GBP,
             &mut next_redemption_idx,
+            &mut LayerSolver::new(&ILPSolverConfig::default()),
             None,
         )
         .expect("evaluation should succeed");
@@ -381,6 +732,7 @@ mod tests {
             SmallVec::from_vec(vec![tracked_item(100)]),
             GBP,
             &mut next_redemption_idx,
+            &mut LayerSolver::new(&ILPSolverConfig::default()),
             Some(&mut observer),
         )
         .expect("evaluation should succeed");
@@ -408,6 +760,7 @@ mod tests {
             SmallVec::from_vec(vec![tracked_item(9_007_199_254_740_993)]),
             GBP,
             &mut next_redemption_idx,
+            &mut LayerSolver::new(&ILPSolverConfig::default()),
             None,
         )
         .expect_err("expected solver error");
@@ -436,10 +789,10 @@ mod tests {
         let result = route_to_successors(
             &graph,
             node,
-            OutputMode::PassThrough,
             SmallVec::from_vec(vec![tracked_item(100)]),
             GBP,
             &mut next_redemption_idx,
+            &mut LayerSolver::new(&ILPSolverConfig::default()),
             None,
         )
         .expect("routing should succeed");
@@ -472,10 +825,10 @@ mod tests {
         let result = route_to_successors(
             &graph,
             node,
-            OutputMode::Split,
             SmallVec::from_vec(vec![discounted, tracked_item(200)]),
             GBP,
             &mut next_redemption_idx,
+            &mut LayerSolver::new(&ILPSolverConfig::default()),
             None,
         )
         .expect("routing should succeed");
@@ -6,34 +6,44 @@
 //! across layers.
 
 use petgraph::{graph::NodeIndex, stable_graph::StableDiGraph};
-use rustc_hash::FxHashMap;
-use rusty_money::Money;
-use smallvec::SmallVec;
 
 use self::{
     edge::LayerEdge,
-    evaluation::{TrackedItem, evaluate_node},
+    evaluation::{LayerSolver, evaluate_node, into_result, track_items},
     node::LayerNode,
 };
 use crate::{
     items::groups::ItemGroup,
-    promotions::{Promotion, redemptions::PromotionRedemption},
-    solvers::ilp::ILPObserver,
+    products::{Product, ProductKey},
+    promotions::{Promotion, explanations::PromotionExplanation},
+    solvers::{
+        ilp::{ILPObserver, ILPSolverConfig},
+        verifier::VerificationError,
+    },
 };
 
+pub mod batch;
 pub mod builder;
 pub mod error;
 pub mod result;
+pub mod session;
+pub mod upsell;
 
 pub(crate) mod edge;
 pub(crate) mod node;
 
+pub use batch::{BasketSummary, BatchEvaluator, BatchTotals, PromotionTotals};
 pub use builder::PromotionGraphBuilder;
 pub use error::GraphError;
 pub use node::{OutputMode, PromotionLayerKey};
 pub use result::LayeredSolverResult;
+pub use session::{EvaluationSession, RedemptionChange, SessionUpdate};
+pub use upsell::Upsell;
 
 mod evaluation;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod verification;
 
 /// A validated promotion graph ready for evaluation.
 ///
@@ -90,13 +100,14 @@ impl<'a> PromotionGraph<'a> {
         &self,
         item_group: &ItemGroup<'b>,
     ) -> Result<LayeredSolverResult<'b>, GraphError> {
-        self.evaluate_with_observer(item_group, None)
+        self.evaluate_with_config(&ILPSolverConfig::default(), item_group, None)
     }
 
     /// Evaluate the promotion graph with an observer.
     ///
     /// Same as [`evaluate()`](Self::evaluate), but passes an observer through to capture
-    /// ILP formulations from all layers.
+    /// ILP formulations from all layers. Each layer is solved as a single
+    /// formulation rather than being decomposed into independent components.
     ///
     /// # Errors
     ///
@@ -107,69 +118,233 @@ impl<'a> PromotionGraph<'a> {
         item_group: &ItemGroup<'b>,
         observer: Option<&mut dyn ILPObserver>,
     ) -> Result<LayeredSolverResult<'b>, GraphError> {
-        let currency = item_group.currency();
+        self.evaluate_with_config(
+            &ILPSolverConfig::default().with_decomposition(false),
+            item_group,
+            observer,
+        )
+    }
 
-        // Create initial tracked items from the item group.
-        let mut tracked_items: SmallVec<[TrackedItem<'b>; 8]> =
-            SmallVec::with_capacity(item_group.len());
-
-        for idx in 0..item_group.len() {
-            let item = item_group.get_item(idx)?;
-            tracked_items.push(TrackedItem {
-                original_basket_idx: idx,
-                item: item.clone(),
-                redemptions: SmallVec::new(),
-            });
-        }
+    /// Evaluate the promotion graph with a solver configuration and an observer.
+    ///
+    /// Every layer is solved with `config`. A configured time limit is a budget for
+    /// the whole evaluation: each layer may use whatever time the layers before it
+    /// left. If any layer runs out of time, the result is marked as not
+    /// [`optimal`](LayeredSolverResult::optimal).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if any layer's solver fails or if item group
+    /// construction fails.
+    pub fn evaluate_with_config<'b>(
+        &self,
+        config: &ILPSolverConfig,
+        item_group: &ItemGroup<'b>,
+        observer: Option<&mut dyn ILPObserver>,
+    ) -> Result<LayeredSolverResult<'b>, GraphError> {
+        self.evaluate_with_solver(item_group, &mut LayerSolver::new(config), observer)
+    }
+
+    /// Evaluate the promotion graph, running independent branches in parallel.
+    ///
+    /// The two branches below a [`Split`](OutputMode::Split) layer see disjoint
+    /// items, so they are evaluated at the same time on the rayon thread pool. The
+    /// result is identical to [`evaluate()`](Self::evaluate), including every
+    /// redemption's `redemption_idx`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if any layer's solver fails or if item group
+    /// construction fails.
+    #[cfg(feature = "parallel")]
+    pub fn evaluate_parallel<'b>(
+        &self,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<LayeredSolverResult<'b>, GraphError> {
+        self.evaluate_parallel_with_config(&ILPSolverConfig::default(), item_group)
+    }
+
+    /// Evaluate the promotion graph in parallel with a solver configuration.
+    ///
+    /// Like [`evaluate_with_config()`](Self::evaluate_with_config), a configured
+    /// time limit is a budget for the whole evaluation, but branches evaluated at
+    /// the same time share it rather than taking turns. Results only match
+    /// sequential evaluation when every layer is solved to optimality.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if any layer's solver fails or if item group
+    /// construction fails.
+    #[cfg(feature = "parallel")]
+    pub fn evaluate_parallel_with_config<'b>(
+        &self,
+        config: &ILPSolverConfig,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<LayeredSolverResult<'b>, GraphError> {
+        let currency = item_group.currency();
+        let tracked_items = track_items(item_group)?;
 
+        let mut solver = LayerSolver::new(config);
         let mut next_redemption_idx: usize = 0;
 
-        // Evaluate the graph starting from the root
-        let final_items = evaluate_node(
+        let final_items = parallel::evaluate_node(
             &self.graph,
             self.root,
             tracked_items,
             currency,
             &mut next_redemption_idx,
-            observer,
+            &mut solver,
         )?;
 
-        // Build the result from final tracked items
-        let mut total = Money::from_minor(0, currency);
+        into_result(&final_items, currency, solver.optimal())
+    }
+
+    /// Explain each promotion in the graph, in layer order, for an evaluated basket.
+    ///
+    /// `result` is the result of evaluating `item_group` with this graph. Each
+    /// explanation says whether the promotion applied and how close the basket is to
+    /// its requirements, measured against every item at its undiscounted price.
+    pub fn explain(
+        &self,
+        item_group: &ItemGroup<'_>,
+        result: &LayeredSolverResult<'_>,
+    ) -> Vec<PromotionExplanation> {
+        self.graph
+            .node_indices()
+            .filter_map(|node_idx| self.graph.node_weight(node_idx))
+            .flat_map(|node| node.promotions.iter())
+            .map(|promotion| {
+                let promotion_key = promotion.key();
+
+                PromotionExplanation {
+                    promotion_key,
+                    applied: result.item_redemptions.values().any(|redemptions| {
+                        redemptions
+                            .iter()
+                            .any(|redemption| redemption.promotion_key == promotion_key)
+                    }),
+                    progress: promotion.progress(item_group),
+                }
+            })
+            .collect()
+    }
 
-        let mut item_redemptions: FxHashMap<usize, SmallVec<[PromotionRedemption<'b>; 3]>> =
-            FxHashMap::default();
+    /// Check an evaluated result against the rules of this graph's promotions.
+    ///
+    /// `result` is replayed from the root: each layer's redemptions are checked at
+    /// the prices items entered the layer with, and items are routed as they were
+    /// during evaluation. See [`crate::solvers::verifier`] for what is checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`VerificationError`] describing the first violation found.
+    pub fn verify(
+        &self,
+        item_group: &ItemGroup<'_>,
+        result: &LayeredSolverResult<'_>,
+    ) -> Result<(), VerificationError> {
+        verification::verify(&self.graph, self.root, item_group, result)
+    }
 
-        let mut full_price_items: SmallVec<[usize; 10]> = SmallVec::new();
+    /// Start an incremental evaluation session for `item_group`.
+    ///
+    /// See [`EvaluationSession`] for how items are added and removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if evaluating the initial item group fails.
+    pub fn session<'g, 'b>(
+        &'g self,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<EvaluationSession<'g, 'a, 'b>, GraphError> {
+        self.session_with_config(ILPSolverConfig::default(), item_group)
+    }
 
-        for tracked in &final_items {
-            total = total.add(*tracked.item.price())?;
+    /// Start an incremental evaluation session solving every layer with `config`.
+    ///
+    /// A configured time limit applies to each evaluation of the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if evaluating the initial item group fails.
+    pub fn session_with_config<'g, 'b>(
+        &'g self,
+        config: ILPSolverConfig,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<EvaluationSession<'g, 'a, 'b>, GraphError> {
+        EvaluationSession::new(self, config, item_group)
+    }
 
-            if tracked.redemptions.is_empty() {
-                full_price_items.push(tracked.original_basket_idx);
-            } else {
-                item_redemptions.insert(tracked.original_basket_idx, tracked.redemptions.clone());
-            }
-        }
+    /// Start a batch evaluation, for pricing many baskets with this graph.
+    ///
+    /// See [`BatchEvaluator`] for how baskets are priced and summarised.
+    pub fn batch(&self) -> BatchEvaluator<'_, 'a> {
+        self.batch_with_config(ILPSolverConfig::default())
+    }
 
-        Ok(LayeredSolverResult {
-            total,
-            item_redemptions,
-            full_price_items,
-        })
+    /// Start a batch evaluation solving every layer with `config`.
+    ///
+    /// A configured time limit applies to each basket of the batch.
+    pub fn batch_with_config(&self, config: ILPSolverConfig) -> BatchEvaluator<'_, 'a> {
+        BatchEvaluator::new(self, config)
+    }
+
+    /// Rank candidate products by how much adding one unit of each to `item_group`
+    /// would save against its shelf price.
+    ///
+    /// Each candidate is priced on its own with the basket, so the suggestions are
+    /// alternatives rather than a combined recommendation. Candidates with the
+    /// most savings come first; candidates with no savings are included last.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if a candidate's price is in a different currency
+    /// to the item group or if an evaluation fails.
+    pub fn upsells<'p, 'b: 'p>(
+        &self,
+        item_group: &ItemGroup<'b>,
+        candidates: impl IntoIterator<Item = (ProductKey, &'p Product<'b>)>,
+    ) -> Result<Vec<Upsell>, GraphError> {
+        upsell::rank(self, &ILPSolverConfig::default(), item_group, candidates)
+    }
+
+    /// Evaluate the graph with a prepared layer solver.
+    fn evaluate_with_solver<'b>(
+        &self,
+        item_group: &ItemGroup<'b>,
+        solver: &mut LayerSolver<'_, 'b>,
+        observer: Option<&mut dyn ILPObserver>,
+    ) -> Result<LayeredSolverResult<'b>, GraphError> {
+        let currency = item_group.currency();
+        let tracked_items = track_items(item_group)?;
+
+        let mut next_redemption_idx: usize = 0;
+
+        // Evaluate the graph starting from the root
+        let final_items = evaluate_node(
+            &self.graph,
+            self.root,
+            tracked_items,
+            currency,
+            &mut next_redemption_idx,
+            solver,
+            observer,
+        )?;
+
+        into_result(&final_items, currency, solver.optimal())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use decimal_percentage::Percentage;
-    use rusty_money::iso::GBP;
-    use smallvec::smallvec;
+    use rusty_money::{Money, iso::GBP};
+    use smallvec::{SmallVec, smallvec};
     use testresult::TestResult;
 
     use crate::{
         discounts::SimpleDiscount,
-        items::{Item, groups::ItemGroup},
+        items::{Item, groups::ItemGroup, measure::Measure},
         products::ProductKey,
         promotions::{
             Promotion, PromotionKey, budget::PromotionBudget, promotion,
@@ -273,6 +448,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn measure_discounts_apply_below_a_discounting_layer() -> TestResult {
+        // 1.2kg of apples at £2.00/kg = £2.40
+        let apples = Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(200, GBP),
+            StringTagCollection::from_strs(&["produce"]),
+            Measure::grams(1200),
+        )?;
+
+        let item_group = ItemGroup::new(smallvec![apples], GBP);
+
+        let mut keys = slotmap::SlotMap::<PromotionKey, ()>::with_key();
+
+        let produce_promo = make_promo(keys.insert(()), &["produce"], 0.10);
+        let per_kg_promo = promotion(DirectDiscountPromotion::new(
+            keys.insert(()),
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            SimpleDiscount::AmountOffPerMeasure(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let mut builder = PromotionGraphBuilder::new();
+
+        let layer1 = builder.add_layer("Produce", [produce_promo], OutputMode::PassThrough)?;
+        let layer2 = builder.add_layer("Per Kilo", [per_kg_promo], OutputMode::PassThrough)?;
+
+        builder.set_root(layer1);
+        builder.connect_pass_through(layer1, layer2)?;
+
+        let graph = PromotionGraph::from_builder(builder)?;
+        let result = graph.evaluate(&item_group)?;
+
+        // Layer 1: 10% off 240 -> 216
+        // Layer 2: £1/kg off 1.2kg -> 216 - 120 = 96
+        assert_eq!(result.total.to_minor_units(), 96);
+        assert_eq!(result.item_redemptions.get(&0).map(SmallVec::len), Some(2));
+
+        graph.verify(&item_group, &result)?;
+
+        Ok(())
+    }
+
     #[test]
     fn split_routing_separates_promoted_and_unpromoted() -> TestResult {
         let items = tagged_items();
@@ -490,4 +708,52 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn exhausted_time_budget_marks_result_as_not_optimal() -> TestResult {
+        let items = tagged_items();
+        let item_group = ItemGroup::new(items, GBP);
+
+        let mut keys = slotmap::SlotMap::<PromotionKey, ()>::with_key();
+        let k1 = keys.insert(());
+        let k2 = keys.insert(());
+
+        let mut builder = PromotionGraphBuilder::new();
+        let layer1 = builder.add_layer(
+            "Food",
+            [make_promo(k1, &["food"], 0.20)],
+            OutputMode::PassThrough,
+        )?;
+        let layer2 =
+            builder.add_layer("All", [make_promo(k2, &[], 0.10)], OutputMode::PassThrough)?;
+
+        builder.set_root(layer1);
+        builder.connect_pass_through(layer1, layer2)?;
+
+        let graph = PromotionGraph::from_builder(builder)?;
+
+        // Reserving the whole budget for the fallback leaves no time to search.
+        let time_limit = std::time::Duration::from_secs(60);
+        let config = ILPSolverConfig::new()
+            .with_time_limit(time_limit)
+            .with_fallback_reserve(time_limit);
+        let limited = graph.evaluate_with_config(&config, &item_group, None)?;
+
+        assert!(!limited.optimal, "no layer had time to reach an optimum");
+
+        let unlimited = graph.evaluate(&item_group)?;
+
+        assert!(unlimited.optimal);
+
+        let full_price: i64 = item_group
+            .iter()
+            .map(|item| item.price().to_minor_units())
+            .sum();
+
+        // Each layer has a single promotion, which the greedy fallback solves exactly.
+        assert!(limited.total.to_minor_units() < full_price);
+        assert_eq!(limited.total, unlimited.total);
+
+        Ok(())
+    }
 }
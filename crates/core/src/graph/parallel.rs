@@ -0,0 +1,185 @@
+//! Parallel graph evaluation.
+//!
+//! The two branches below a split layer receive disjoint items, so they can be
+//! evaluated at the same time. Each branch numbers its redemptions from the same
+//! starting index; the non-participating branch's numbers are then shifted past
+//! the participating branch's, which is the numbering sequential evaluation gives.
+
+use petgraph::{graph::NodeIndex, stable_graph::StableDiGraph};
+use rusty_money::iso::Currency;
+
+use crate::graph::{
+    edge::LayerEdge,
+    error::GraphError,
+    evaluation::{LayerSolver, TrackedItems, solve_node, split_items, successor, successor_edges},
+    node::{LayerNode, OutputMode},
+};
+
+/// Evaluate a node and its successors, running the branches of split layers in
+/// parallel.
+///
+/// Produces the same items, in the same order and with the same redemption
+/// indexes, as [`evaluate_node`](super::evaluation::evaluate_node).
+///
+/// # Errors
+///
+/// Returns a [`GraphError`] if the solver fails or if item group construction fails.
+pub(super) fn evaluate_node<'b>(
+    graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
+    node_idx: NodeIndex,
+    tracked_items: TrackedItems<'b>,
+    currency: &'b Currency,
+    next_redemption_idx: &mut usize,
+    solver: &mut LayerSolver<'_, 'b>,
+) -> Result<TrackedItems<'b>, GraphError> {
+    if tracked_items.is_empty() {
+        return Ok(TrackedItems::new());
+    }
+
+    let Some(node) = graph.node_weight(node_idx) else {
+        return Ok(tracked_items);
+    };
+
+    let updated_items = if node.promotions.is_empty() || !solver.may_apply(node_idx) {
+        tracked_items
+    } else {
+        solve_node(
+            node_idx,
+            node,
+            tracked_items,
+            currency,
+            next_redemption_idx,
+            solver,
+            None,
+        )?
+    };
+
+    let edges = successor_edges(graph, node_idx);
+
+    match node.output_mode {
+        OutputMode::PassThrough => match successor(&edges, LayerEdge::All) {
+            Some(target) => evaluate_node(
+                graph,
+                target,
+                updated_items,
+                currency,
+                next_redemption_idx,
+                solver,
+            ),
+            None => Ok(updated_items),
+        },
+        OutputMode::Split => {
+            let (promoted_items, unpromoted_items) = split_items(updated_items);
+
+            let promoted_target =
+                successor(&edges, LayerEdge::Participating).filter(|_| !promoted_items.is_empty());
+            let unpromoted_target = successor(&edges, LayerEdge::NonParticipating)
+                .filter(|_| !unpromoted_items.is_empty());
+
+            let (promoted_items, unpromoted_items) = match (promoted_target, unpromoted_target) {
+                (Some(promoted_target), Some(unpromoted_target)) => evaluate_branches(
+                    graph,
+                    (promoted_target, promoted_items),
+                    (unpromoted_target, unpromoted_items),
+                    currency,
+                    next_redemption_idx,
+                    solver,
+                )?,
+                (Some(target), None) => (
+                    evaluate_node(
+                        graph,
+                        target,
+                        promoted_items,
+                        currency,
+                        next_redemption_idx,
+                        solver,
+                    )?,
+                    unpromoted_items,
+                ),
+                (None, Some(target)) => (
+                    promoted_items,
+                    evaluate_node(
+                        graph,
+                        target,
+                        unpromoted_items,
+                        currency,
+                        next_redemption_idx,
+                        solver,
+                    )?,
+                ),
+                (None, None) => (promoted_items, unpromoted_items),
+            };
+
+            let mut final_items = promoted_items;
+            final_items.extend(unpromoted_items);
+
+            Ok(final_items)
+        }
+    }
+}
+
+/// Evaluate both branches of a split layer in parallel.
+///
+/// Returns the participating branch's items, then the non-participating
+/// branch's. If both branches fail, the participating branch's error is returned,
+/// as sequential evaluation would.
+fn evaluate_branches<'b>(
+    graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
+    (promoted_target, promoted_items): (NodeIndex, TrackedItems<'b>),
+    (unpromoted_target, unpromoted_items): (NodeIndex, TrackedItems<'b>),
+    currency: &'b Currency,
+    next_redemption_idx: &mut usize,
+    solver: &mut LayerSolver<'_, 'b>,
+) -> Result<(TrackedItems<'b>, TrackedItems<'b>), GraphError> {
+    let start = *next_redemption_idx;
+
+    let mut promoted_solver = solver.fork();
+    let mut unpromoted_solver = solver.fork();
+    let mut promoted_next = start;
+    let mut unpromoted_next = start;
+
+    let (promoted, unpromoted) = rayon::join(
+        || {
+            evaluate_node(
+                graph,
+                promoted_target,
+                promoted_items,
+                currency,
+                &mut promoted_next,
+                &mut promoted_solver,
+            )
+        },
+        || {
+            evaluate_node(
+                graph,
+                unpromoted_target,
+                unpromoted_items,
+                currency,
+                &mut unpromoted_next,
+                &mut unpromoted_solver,
+            )
+        },
+    );
+
+    solver.join(&promoted_solver);
+    solver.join(&unpromoted_solver);
+
+    let promoted = promoted?;
+    let mut unpromoted = unpromoted?;
+
+    // Redemptions from before the split keep their indexes; the rest follow on
+    // from the participating branch's.
+    let shift = promoted_next.saturating_sub(start);
+
+    for redemption in unpromoted
+        .iter_mut()
+        .flat_map(|item| item.redemptions.iter_mut())
+        .filter(|redemption| redemption.redemption_idx >= start)
+    {
+        redemption.redemption_idx = redemption.redemption_idx.saturating_add(shift);
+    }
+
+    *next_redemption_idx = unpromoted_next.saturating_add(shift);
+
+    Ok((promoted, unpromoted))
+}
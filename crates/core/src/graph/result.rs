@@ -21,4 +21,10 @@ pub struct LayeredSolverResult<'a> {
 
     /// Original basket indices of items that received no promotion in any layer
     pub full_price_items: SmallVec<[usize; 10]>,
+
+    /// Whether every layer was solved to proven optimality.
+    ///
+    /// `false` when a time limit cut at least one layer's search short, so callers
+    /// can log or retry baskets that were priced with a best-effort solution.
+    pub optimal: bool,
 }
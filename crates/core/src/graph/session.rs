@@ -0,0 +1,447 @@
+//! Incremental Evaluation
+//!
+//! Carts usually change one item at a time. An [`EvaluationSession`] keeps the
+//! basket's items and each layer's last optimal solution between evaluations, so
+//! adding or removing an item only re-solves the layers whose input changed. Each
+//! change reports which items' redemptions changed, such as an item being taken
+//! from one promotion by another.
+
+use rusty_money::iso::Currency;
+use smallvec::SmallVec;
+
+use crate::{
+    graph::{
+        PromotionGraph,
+        error::GraphError,
+        evaluation::{LayerCache, LayerSolver},
+        result::LayeredSolverResult,
+    },
+    items::{Item, groups::ItemGroup, groups::ItemGroupError},
+    promotions::redemptions::PromotionRedemption,
+    solvers::ilp::ILPSolverConfig,
+};
+
+/// An incremental evaluation of a promotion graph over a changing basket.
+///
+/// The `good_lp` backends do not accept a starting solution, so instead of
+/// seeding the solver, the session reuses the previous optimal solution of every
+/// layer whose input items and prices are unchanged. Layers downstream of a
+/// change, or that the changed item flows through, are solved again, but only
+/// in part: a layer is split into components of items that share promotions, as
+/// the solver does for any basket, and each component whose promotions and
+/// items are unchanged keeps its previous solution. Components are only found
+/// when [decomposition](ILPSolverConfig::with_decomposition) is enabled.
+///
+/// Items are single units indexed in the order they were added, as in
+/// [`ItemGroup`]; removing an item shifts the indexes of the items after it.
+#[derive(Debug)]
+pub struct EvaluationSession<'g, 'a, 'b> {
+    graph: &'g PromotionGraph<'a>,
+    config: ILPSolverConfig,
+    currency: &'b Currency,
+    items: SmallVec<[Item<'b>; 10]>,
+    layers: LayerCache<'b>,
+    result: LayeredSolverResult<'b>,
+}
+
+impl<'g, 'a, 'b> EvaluationSession<'g, 'a, 'b> {
+    /// Start a session by evaluating `item_group`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if the evaluation fails.
+    pub(super) fn new(
+        graph: &'g PromotionGraph<'a>,
+        config: ILPSolverConfig,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<Self, GraphError> {
+        let items = (0..item_group.len())
+            .map(|idx| item_group.get_item(idx).cloned())
+            .collect::<Result<SmallVec<[Item<'b>; 10]>, _>>()?;
+
+        let mut layers = LayerCache::default();
+        let result = graph.evaluate_with_solver(
+            item_group,
+            &mut LayerSolver::with_cache(&config, &mut layers),
+            None,
+        )?;
+
+        Ok(Self {
+            graph,
+            config,
+            currency: item_group.currency(),
+            items,
+            layers,
+            result,
+        })
+    }
+
+    /// The result of the latest evaluation.
+    pub fn result(&self) -> &LayeredSolverResult<'b> {
+        &self.result
+    }
+
+    /// The items currently in the session, by index.
+    pub fn items(&self) -> &[Item<'b>] {
+        &self.items
+    }
+
+    /// Add an item and re-price the basket.
+    ///
+    /// An item with a quantity is added as one item per unit, at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if the item's currency differs from the session's
+    /// currency or if the evaluation fails.
+    pub fn add_item(&mut self, item: Item<'b>) -> Result<SessionUpdate<'b>, GraphError> {
+        let item_currency = item.price().currency();
+
+        if item_currency != self.currency {
+            return Err(ItemGroupError::CurrencyMismatch(
+                self.items.len(),
+                item_currency.iso_alpha_code,
+                self.currency.iso_alpha_code,
+            )
+            .into());
+        }
+
+        let previous_len = self.items.len();
+
+        self.items.extend(item.into_units());
+
+        self.reevaluate(|idx| (idx < previous_len).then_some(idx))
+    }
+
+    /// Remove the item at `item_idx` and re-price the basket.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphError`] if there is no item at `item_idx` or if the
+    /// evaluation fails.
+    pub fn remove_item(&mut self, item_idx: usize) -> Result<SessionUpdate<'b>, GraphError> {
+        if item_idx >= self.items.len() {
+            return Err(ItemGroupError::ItemNotFound(item_idx).into());
+        }
+
+        self.items.remove(item_idx);
+
+        self.reevaluate(|idx| {
+            Some(if idx < item_idx {
+                idx
+            } else {
+                idx.saturating_add(1)
+            })
+        })
+    }
+
+    /// Evaluate the current items, reusing unchanged layers, and compare the
+    /// redemptions with the previous result.
+    ///
+    /// `previous_idx` maps an item's current index to its index in the previous
+    /// result, or `None` for a newly added item.
+    fn reevaluate(
+        &mut self,
+        previous_idx: impl Fn(usize) -> Option<usize>,
+    ) -> Result<SessionUpdate<'b>, GraphError> {
+        let item_group = ItemGroup::new(self.items.clone(), self.currency);
+        let mut solver = LayerSolver::with_cache(&self.config, &mut self.layers);
+
+        let result = self
+            .graph
+            .evaluate_with_solver(&item_group, &mut solver, None)?;
+
+        let solved_layers = solver.solved_layers();
+        let reused_layers = solver.reused_layers();
+        let reused_components = solver.reused_components();
+
+        let mut changes = SmallVec::new();
+
+        for item_idx in 0..self.items.len() {
+            let before = previous_idx(item_idx)
+                .and_then(|idx| self.result.item_redemptions.get(&idx))
+                .cloned()
+                .unwrap_or_default();
+            let after = result
+                .item_redemptions
+                .get(&item_idx)
+                .cloned()
+                .unwrap_or_default();
+
+            if !same_redemptions(&before, &after) {
+                changes.push(RedemptionChange {
+                    item_idx,
+                    before,
+                    after,
+                });
+            }
+        }
+
+        self.result = result;
+
+        Ok(SessionUpdate {
+            changes,
+            solved_layers,
+            reused_layers,
+            reused_components,
+        })
+    }
+}
+
+/// What changed when a session re-priced its basket.
+#[derive(Debug, Clone)]
+pub struct SessionUpdate<'b> {
+    /// Items whose redemptions changed, by current item index
+    pub changes: SmallVec<[RedemptionChange<'b>; 4]>,
+
+    /// Number of layers that were solved again
+    pub solved_layers: usize,
+
+    /// Number of layers whose previous solution was reused unchanged
+    pub reused_layers: usize,
+
+    /// Number of components of re-solved layers whose previous solution was reused
+    pub reused_components: usize,
+}
+
+/// A change to one item's redemptions between two evaluations.
+#[derive(Debug, Clone)]
+pub struct RedemptionChange<'b> {
+    /// Current index of the item
+    pub item_idx: usize,
+
+    /// Redemptions before the change, empty for a newly added item
+    pub before: SmallVec<[PromotionRedemption<'b>; 3]>,
+
+    /// Redemptions after the change
+    pub after: SmallVec<[PromotionRedemption<'b>; 3]>,
+}
+
+impl RedemptionChange<'_> {
+    /// Whether another promotion took the item from a promotion it was in.
+    pub fn is_stolen(&self) -> bool {
+        let lost = self.before.iter().any(|before| {
+            !self
+                .after
+                .iter()
+                .any(|after| after.promotion_key == before.promotion_key)
+        });
+        let gained = self.after.iter().any(|after| {
+            !self
+                .before
+                .iter()
+                .any(|before| before.promotion_key == after.promotion_key)
+        });
+
+        lost && gained
+    }
+}
+
+/// Whether two redemption lists apply the same promotions at the same prices.
+///
+/// Redemption indexes are renumbered on every evaluation, so they are ignored.
+fn same_redemptions(before: &[PromotionRedemption<'_>], after: &[PromotionRedemption<'_>]) -> bool {
+    before.len() == after.len()
+        && before.iter().zip(after).all(|(before, after)| {
+            before.promotion_key == after.promotion_key
+                && before.final_price.to_minor_units() == after.final_price.to_minor_units()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::{Money, iso::GBP, iso::USD};
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        graph::{OutputMode, PromotionGraphBuilder},
+        products::ProductKey,
+        promotions::{
+            Promotion, PromotionKey, budget::PromotionBudget, promotion,
+            qualification::Qualification, types::DirectDiscountPromotion,
+        },
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    fn tagged_item<'a>(price: i64, tags: &[&str]) -> Item<'a> {
+        Item::with_tags(
+            ProductKey::default(),
+            Money::from_minor(price, GBP),
+            StringTagCollection::from_strs(tags),
+        )
+    }
+
+    fn percent_off(key: PromotionKey, tags: &[&str], pct: f64) -> Promotion<'static> {
+        promotion(DirectDiscountPromotion::new(
+            key,
+            Qualification::match_any(StringTagCollection::from_strs(tags)),
+            SimpleDiscount::PercentageOff(Percentage::from(pct)),
+            PromotionBudget::unlimited(),
+        ))
+    }
+
+    /// Food is discounted in the root layer, then discounted food and everything
+    /// else each pass through their own layer.
+    fn split_graph() -> Result<PromotionGraph<'static>, GraphError> {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        let mut builder = PromotionGraphBuilder::new();
+        let root = builder.add_layer(
+            "Food",
+            [percent_off(keys.insert(()), &["food"], 0.10)],
+            OutputMode::Split,
+        )?;
+        let promoted = builder.add_layer(
+            "Loyalty",
+            [percent_off(keys.insert(()), &["food"], 0.05)],
+            OutputMode::PassThrough,
+        )?;
+        let unpromoted = builder.add_layer(
+            "Drinks",
+            [percent_off(keys.insert(()), &["drink"], 0.20)],
+            OutputMode::PassThrough,
+        )?;
+
+        builder.set_root(root);
+        builder.connect_split(root, promoted, unpromoted)?;
+
+        PromotionGraph::from_builder(builder)
+    }
+
+    #[test]
+    fn session_matches_full_evaluation_after_each_change() -> TestResult {
+        let graph = split_graph()?;
+        let item_group = ItemGroup::new(smallvec![tagged_item(1000, &["food"])], GBP);
+
+        let mut session = graph.session(&item_group)?;
+
+        session.add_item(tagged_item(500, &["drink"]))?;
+        session.add_item(tagged_item(300, &["food"]))?;
+        session.remove_item(0)?;
+
+        let expected = graph.evaluate(&ItemGroup::new(session.items().into(), GBP))?;
+
+        assert_eq!(
+            session.result().total.to_minor_units(),
+            expected.total.to_minor_units()
+        );
+        assert_eq!(session.result().full_price_items, expected.full_price_items);
+        assert_eq!(session.items().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn adding_an_item_reuses_layers_with_unchanged_input() -> TestResult {
+        let graph = split_graph()?;
+        let item_group = ItemGroup::new(
+            smallvec![tagged_item(1000, &["food"]), tagged_item(500, &["drink"])],
+            GBP,
+        );
+
+        let mut session = graph.session(&item_group)?;
+
+        // The drink changes the root layer's input and reaches the drinks layer,
+        // but the loyalty layer still only sees the discounted food.
+        let update = session.add_item(tagged_item(200, &["drink"]))?;
+
+        assert_eq!(update.solved_layers, 2);
+        assert_eq!(update.reused_layers, 1);
+
+        let changed: Vec<usize> = update.changes.iter().map(|c| c.item_idx).collect();
+
+        assert_eq!(changed, vec![2]);
+        assert!(update.changes.iter().all(|change| !change.is_stolen()));
+        assert_eq!(session.result().total.to_minor_units(), 855 + 400 + 160);
+
+        Ok(())
+    }
+
+    #[test]
+    fn changed_layers_reuse_their_unchanged_components() -> TestResult {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        let mut builder = PromotionGraphBuilder::new();
+        let layer = builder.add_layer(
+            "Everything",
+            [
+                percent_off(keys.insert(()), &["food"], 0.10),
+                percent_off(keys.insert(()), &["drink"], 0.20),
+            ],
+            OutputMode::PassThrough,
+        )?;
+
+        builder.set_root(layer);
+
+        let graph = PromotionGraph::from_builder(builder)?;
+        let item_group = ItemGroup::new(
+            smallvec![tagged_item(1000, &["food"]), tagged_item(500, &["drink"])],
+            GBP,
+        );
+
+        let mut session = graph.session(&item_group)?;
+
+        // The drink changes the layer's input, but the food is solved apart from
+        // the drinks and keeps its previous solution.
+        let update = session.add_item(tagged_item(200, &["drink"]))?;
+
+        assert_eq!(update.solved_layers, 1);
+        assert_eq!(update.reused_components, 1);
+        assert_eq!(session.result().total.to_minor_units(), 900 + 400 + 160);
+
+        // Removing the food shifts the drinks' indexes, but not their component.
+        let update = session.remove_item(0)?;
+
+        assert_eq!(update.reused_components, 1);
+
+        let update = session.add_item(tagged_item(300, &["food"]))?;
+
+        assert_eq!(update.reused_components, 1);
+        assert_eq!(session.result().total.to_minor_units(), 400 + 160 + 270);
+
+        let expected = graph.evaluate(&ItemGroup::new(session.items().into(), GBP))?;
+
+        assert_eq!(
+            session.result().total.to_minor_units(),
+            expected.total.to_minor_units()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_changes_return_errors() -> TestResult {
+        let graph = split_graph()?;
+        let item_group = ItemGroup::new(smallvec![tagged_item(1000, &["food"])], GBP);
+
+        let mut session = graph.session(&item_group)?;
+
+        let mismatch = session.add_item(Item::new(
+            ProductKey::default(),
+            Money::from_minor(100, USD),
+        ));
+        let missing = session.remove_item(5);
+
+        assert!(matches!(
+            mismatch,
+            Err(GraphError::ItemGroup(ItemGroupError::CurrencyMismatch(
+                1,
+                _,
+                _
+            )))
+        ));
+        assert!(matches!(
+            missing,
+            Err(GraphError::ItemGroup(ItemGroupError::ItemNotFound(5)))
+        ));
+        assert_eq!(session.items().len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+//! Upsell Suggestions
+//!
+//! Prices a basket once, then again with each candidate product added, to find
+//! which additions cost less than their shelf price: "add X to save £Y". Each
+//! candidate is evaluated on its own, so suggestions do not combine.
+
+use smallvec::SmallVec;
+
+use crate::{
+    graph::{
+        PromotionGraph,
+        error::GraphError,
+        evaluation::{LayerCache, LayerSolver},
+    },
+    items::{Item, groups::ItemGroup, groups::ItemGroupError},
+    products::{Product, ProductKey},
+    solvers::ilp::ILPSolverConfig,
+};
+
+/// The effect on a basket's total of adding one unit of a product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Upsell {
+    /// Key of the candidate product
+    pub product_key: ProductKey,
+
+    /// Shelf price of the product, in minor units
+    pub price_minor: i64,
+
+    /// How much the basket total rises when the product is added, in minor units
+    pub marginal_minor: i64,
+
+    /// How much less the product adds than its shelf price (`price - marginal`),
+    /// in minor units
+    pub savings_minor: i64,
+}
+
+impl Upsell {
+    /// Whether adding the product costs less than its shelf price.
+    pub fn saves(&self) -> bool {
+        self.savings_minor > 0
+    }
+}
+
+/// Evaluate `item_group` with and without each candidate, ranked by savings.
+///
+/// Layers whose input is the same for several candidates are only solved once.
+///
+/// # Errors
+///
+/// Returns a [`GraphError`] if a candidate's price is in a different currency to
+/// the item group or if an evaluation fails.
+pub(super) fn rank<'p, 'b: 'p>(
+    graph: &PromotionGraph<'_>,
+    config: &ILPSolverConfig,
+    item_group: &ItemGroup<'b>,
+    candidates: impl IntoIterator<Item = (ProductKey, &'p Product<'b>)>,
+) -> Result<Vec<Upsell>, GraphError> {
+    let currency = item_group.currency();
+    let items = (0..item_group.len())
+        .map(|idx| item_group.get_item(idx).cloned())
+        .collect::<Result<SmallVec<[Item<'b>; 10]>, _>>()?;
+
+    let mut layers = LayerCache::default();
+    let base_total = graph
+        .evaluate_with_solver(
+            item_group,
+            &mut LayerSolver::with_cache(config, &mut layers),
+            None,
+        )?
+        .total
+        .to_minor_units();
+
+    let mut upsells = Vec::new();
+
+    for (product_key, product) in candidates {
+        let product_currency = product.price.currency();
+
+        if product_currency != currency {
+            return Err(ItemGroupError::CurrencyMismatch(
+                items.len(),
+                product_currency.iso_alpha_code,
+                currency.iso_alpha_code,
+            )
+            .into());
+        }
+
+        let mut projected_items = items.clone();
+
+        projected_items.push(Item::with_tags(
+            product_key,
+            product.price,
+            product.tags.clone(),
+        ));
+
+        let projected_total = graph
+            .evaluate_with_solver(
+                &ItemGroup::new(projected_items, currency),
+                &mut LayerSolver::with_cache(config, &mut layers),
+                None,
+            )?
+            .total
+            .to_minor_units();
+
+        let price_minor = product.price.to_minor_units();
+        let marginal_minor = projected_total.saturating_sub(base_total);
+
+        upsells.push(Upsell {
+            product_key,
+            price_minor,
+            marginal_minor,
+            savings_minor: price_minor.saturating_sub(marginal_minor),
+        });
+    }
+
+    // Stable, so candidates with equal savings and cost keep their given order.
+    upsells.sort_by(|a, b| {
+        b.savings_minor
+            .cmp(&a.savings_minor)
+            .then(a.marginal_minor.cmp(&b.marginal_minor))
+    });
+
+    Ok(upsells)
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::{Money, iso::GBP, iso::USD};
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        promotions::{
+            PromotionKey,
+            budget::PromotionBudget,
+            promotion,
+            qualification::Qualification,
+            types::{DirectDiscountPromotion, PositionalDiscountPromotion},
+        },
+        tags::{collection::TagCollection, string::StringTagCollection},
+    };
+
+    use super::*;
+
+    fn product<'a>(name: &str, price: i64, tags: &[&str]) -> Product<'a> {
+        Product {
+            name: name.to_string(),
+            tags: StringTagCollection::from_strs(tags),
+            price: Money::from_minor(price, GBP),
+        }
+    }
+
+    fn item_for<'a>(key: ProductKey, product: &Product<'a>) -> Item<'a> {
+        Item::with_tags(key, product.price, product.tags.clone())
+    }
+
+    #[test]
+    fn candidates_are_ranked_by_savings() -> TestResult {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut products = SlotMap::<ProductKey, Product<'_>>::with_key();
+
+        let snack = products.insert(product("Crisps", 200, &["snack"]));
+        let fruit = products.insert(product("Apple", 100, &["fruit"]));
+        let drink = products.insert(product("Juice", 300, &["drink"]));
+
+        // Buy one snack, get a second free, and 10% off fruit.
+        let graph = PromotionGraph::single_layer([
+            promotion(PositionalDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["snack"])),
+                2,
+                smallvec![1],
+                SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+                PromotionBudget::unlimited(),
+            )),
+            promotion(DirectDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["fruit"])),
+                SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+                PromotionBudget::unlimited(),
+            )),
+        ])?;
+
+        let snack_product = products.get(snack).ok_or("missing snack")?;
+        let item_group = ItemGroup::new(smallvec![item_for(snack, snack_product)], GBP);
+
+        let upsells = graph.upsells(&item_group, products.iter())?;
+
+        let ranked: Vec<(ProductKey, i64, i64)> = upsells
+            .iter()
+            .map(|upsell| {
+                (
+                    upsell.product_key,
+                    upsell.marginal_minor,
+                    upsell.savings_minor,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            ranked,
+            vec![(snack, 0, 200), (fruit, 90, 10), (drink, 300, 0)]
+        );
+        assert!(upsells.iter().take(2).all(Upsell::saves));
+        assert!(upsells.last().is_some_and(|upsell| !upsell.saves()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn candidate_in_another_currency_is_rejected() -> TestResult {
+        let graph = PromotionGraph::single_layer([])?;
+        let item_group = ItemGroup::new(SmallVec::new(), GBP);
+
+        let mut products = SlotMap::<ProductKey, Product<'_>>::with_key();
+
+        products.insert(Product {
+            name: "Imported".to_string(),
+            tags: StringTagCollection::empty(),
+            price: Money::from_minor(100, USD),
+        });
+
+        let result = graph.upsells(&item_group, products.iter());
+
+        assert!(matches!(
+            result,
+            Err(GraphError::ItemGroup(ItemGroupError::CurrencyMismatch(
+                0,
+                _,
+                _
+            )))
+        ));
+
+        Ok(())
+    }
+}
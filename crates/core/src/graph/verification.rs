@@ -0,0 +1,237 @@
+//! Layered result verification.
+//!
+//! Replays a [`LayeredSolverResult`] through the graph: items are routed the
+//! same way as during evaluation, and each layer's redemptions are checked
+//! against its promotions at the prices the items entered the layer with.
+
+use petgraph::{graph::NodeIndex, stable_graph::StableDiGraph, visit::EdgeRef};
+use rusty_money::{Money, iso::Currency};
+use smallvec::SmallVec;
+
+use crate::{
+    graph::{
+        edge::LayerEdge,
+        node::{LayerNode, OutputMode},
+        result::LayeredSolverResult,
+    },
+    items::{Item, groups::ItemGroup},
+    promotions::redemptions::PromotionRedemption,
+    solvers::verifier::{VerificationError, verify_redemptions},
+};
+
+type ReplayItems<'b> = SmallVec<[ReplayItem<'b>; 8]>;
+
+/// An item being replayed through the graph.
+#[derive(Debug, Clone)]
+struct ReplayItem<'b> {
+    /// Index of this item in the original basket/item group
+    original_basket_idx: usize,
+
+    /// The item at its price entering the next layer
+    item: Item<'b>,
+
+    /// Number of the item's redemptions replayed so far
+    replayed: usize,
+}
+
+/// Verify a layered result against the graph it was evaluated with.
+///
+/// # Errors
+///
+/// Returns a [`VerificationError`] describing the first violation found.
+pub(super) fn verify<'b>(
+    graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
+    root: NodeIndex,
+    item_group: &ItemGroup<'b>,
+    result: &LayeredSolverResult<'_>,
+) -> Result<(), VerificationError> {
+    let items: ReplayItems<'b> = (0..item_group.len())
+        .filter_map(|idx| item_group.get_item(idx).ok().map(|item| (idx, item)))
+        .map(|(original_basket_idx, item)| ReplayItem {
+            original_basket_idx,
+            item: item.clone(),
+            replayed: 0,
+        })
+        .collect();
+
+    if let Some(&item_idx) = result
+        .item_redemptions
+        .keys()
+        .find(|&&item_idx| item_idx >= item_group.len())
+    {
+        return Err(VerificationError::UnknownItem { item_idx });
+    }
+
+    let final_items = replay_node(graph, root, items, item_group.currency(), result)?;
+
+    let mut expected_total = 0_i64;
+
+    for replayed in &final_items {
+        let item_idx = replayed.original_basket_idx;
+        let redemptions = result.item_redemptions.get(&item_idx);
+        let redemption_count = redemptions.map_or(0, SmallVec::len);
+
+        // Every redemption must belong to a layer the item passed through.
+        if replayed.replayed != redemption_count {
+            return Err(VerificationError::RedemptionChainBroken { item_idx });
+        }
+
+        if result.full_price_items.contains(&item_idx) != (redemption_count == 0) {
+            return Err(VerificationError::AffectedItemsMismatch { item_idx });
+        }
+
+        expected_total += replayed.item.price().to_minor_units();
+    }
+
+    if let Some(&item_idx) = result
+        .full_price_items
+        .iter()
+        .find(|&&item_idx| item_idx >= item_group.len())
+    {
+        return Err(VerificationError::AffectedItemsMismatch { item_idx });
+    }
+
+    let actual_total = result.total.to_minor_units();
+
+    if actual_total != expected_total {
+        return Err(VerificationError::TotalMismatch {
+            expected: expected_total,
+            actual: actual_total,
+        });
+    }
+
+    Ok(())
+}
+
+/// Replay a layer, then route its items to the layer's successors.
+fn replay_node<'b>(
+    graph: &StableDiGraph<LayerNode<'_>, LayerEdge>,
+    node_idx: NodeIndex,
+    items: ReplayItems<'b>,
+    currency: &'b Currency,
+    result: &LayeredSolverResult<'_>,
+) -> Result<ReplayItems<'b>, VerificationError> {
+    if items.is_empty() {
+        return Ok(items);
+    }
+
+    let Some(node) = graph.node_weight(node_idx) else {
+        return Ok(items);
+    };
+
+    let mut items = items;
+
+    if !node.promotions.is_empty() {
+        replay_layer(node, &mut items, currency, result)?;
+    }
+
+    let edges: SmallVec<[(NodeIndex, LayerEdge); 2]> = graph
+        .edges(node_idx)
+        .map(|edge| (edge.target(), *edge.weight()))
+        .collect();
+
+    let successor = |edge: LayerEdge| {
+        edges
+            .iter()
+            .find(|(_, weight)| *weight == edge)
+            .map(|(target, _)| *target)
+    };
+
+    match node.output_mode {
+        OutputMode::PassThrough => match successor(LayerEdge::All) {
+            Some(target) => replay_node(graph, target, items, currency, result),
+            None => Ok(items),
+        },
+        OutputMode::Split => {
+            let (participating, non_participating): (ReplayItems<'b>, ReplayItems<'b>) =
+                items.into_iter().partition(|item| item.replayed > 0);
+
+            let mut final_items = ReplayItems::new();
+
+            for (edge, routed) in [
+                (LayerEdge::Participating, participating),
+                (LayerEdge::NonParticipating, non_participating),
+            ] {
+                match successor(edge) {
+                    Some(target) => {
+                        final_items.extend(replay_node(graph, target, routed, currency, result)?);
+                    }
+                    None => final_items.extend(routed),
+                }
+            }
+
+            Ok(final_items)
+        }
+    }
+}
+
+/// Check the redemptions a layer made and apply their final prices.
+///
+/// An item's next redemption belongs to this layer if it is for one of the
+/// layer's promotions.
+fn replay_layer<'b>(
+    node: &LayerNode<'_>,
+    items: &mut ReplayItems<'b>,
+    currency: &'b Currency,
+    result: &LayeredSolverResult<'_>,
+) -> Result<(), VerificationError> {
+    let layer_group = ItemGroup::new(
+        items.iter().map(|replayed| replayed.item.clone()).collect(),
+        currency,
+    );
+
+    let mut redemptions: SmallVec<[PromotionRedemption<'_>; 10]> = SmallVec::new();
+
+    for (local_idx, replayed) in items.iter().enumerate() {
+        let Some(redemption) = result
+            .item_redemptions
+            .get(&replayed.original_basket_idx)
+            .and_then(|redemptions| redemptions.get(replayed.replayed))
+        else {
+            continue;
+        };
+
+        if !node
+            .promotions
+            .iter()
+            .any(|promotion| promotion.key() == redemption.promotion_key)
+        {
+            continue;
+        }
+
+        if redemption.item_idx != replayed.original_basket_idx {
+            return Err(VerificationError::RedemptionChainBroken {
+                item_idx: replayed.original_basket_idx,
+            });
+        }
+
+        redemptions.push(PromotionRedemption {
+            item_idx: local_idx,
+            ..redemption.clone()
+        });
+    }
+
+    // Report items by their index in the basket rather than in the layer.
+    verify_redemptions(&node.promotions, &layer_group, &redemptions).map_err(|err| {
+        err.map_item_idx(|local_idx| {
+            items
+                .get(local_idx)
+                .map_or(local_idx, |replayed| replayed.original_basket_idx)
+        })
+    })?;
+
+    for redemption in &redemptions {
+        let Some(replayed) = items.get_mut(redemption.item_idx) else {
+            continue;
+        };
+
+        // Later layers see the discounted price, as during evaluation.
+        replayed.item = replayed.item.with_price(Money::from_minor(
+            redemption.final_price.to_minor_units(),
+            currency,
+        ));
+        replayed.replayed += 1;
+    }
+
+    Ok(())
+}
@@ -31,7 +31,15 @@ pub struct ItemGroup<'a, T: TagCollection = StringTagCollection> {
 
 impl<'a, T: TagCollection> ItemGroup<'a, T> {
     /// Create a new item group with items and currency.
+    ///
+    /// Items with a quantity are expanded into one item per unit.
     pub fn new(items: SmallVec<[Item<'a, T>; 10]>, currency: &'a Currency) -> Self {
+        let items = if items.iter().all(|item| item.quantity() == 1) {
+            items
+        } else {
+            items.into_iter().flat_map(Item::into_units).collect()
+        };
+
         ItemGroup { items, currency }
     }
 
@@ -67,6 +75,35 @@ impl<'a, T: TagCollection> ItemGroup<'a, T> {
     }
 }
 
+impl<'a, T: TagCollection + Clone + PartialEq> ItemGroup<'a, T> {
+    /// Compress runs of identical adjacent units into line items with a quantity.
+    ///
+    /// Only units for which `compressible` holds are merged; the rest stay one
+    /// line per unit. Line order follows unit order, so the units of each line
+    /// directly follow the units of the lines before it.
+    pub(crate) fn compress(&self, compressible: impl Fn(&Item<'a, T>) -> bool) -> Self {
+        let mut lines: SmallVec<[Item<'a, T>; 10]> = SmallVec::new();
+
+        for item in &self.items {
+            if let Some(line) = lines.last_mut()
+                && line.is_same_unit(item)
+                && compressible(item)
+            {
+                line.quantity = line.quantity.saturating_add(1);
+
+                continue;
+            }
+
+            lines.push(item.clone());
+        }
+
+        ItemGroup {
+            items: lines,
+            currency: self.currency,
+        }
+    }
+}
+
 impl<'a> From<&'a Basket<'a>> for ItemGroup<'a> {
     fn from(basket: &'a Basket<'a>) -> Self {
         ItemGroup {
@@ -79,7 +116,7 @@ impl<'a> From<&'a Basket<'a>> for ItemGroup<'a> {
 #[cfg(test)]
 mod tests {
     use rusty_money::{Money, iso::GBP};
-    use smallvec::SmallVec;
+    use smallvec::{SmallVec, smallvec};
     use testresult::TestResult;
 
     use crate::{basket::Basket, items::Item, products::ProductKey};
@@ -115,6 +152,75 @@ mod tests {
         assert!(matches!(err, Some(ItemGroupError::ItemNotFound(99))));
     }
 
+    #[test]
+    fn new_expands_quantities_into_units() {
+        let items: SmallVec<[Item<'_>; 10]> = smallvec![
+            Item::new(ProductKey::default(), Money::from_minor(100, GBP)),
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(50, GBP),
+                StringTagCollection::empty(),
+                3,
+            ),
+        ];
+
+        let group = ItemGroup::new(items, GBP);
+
+        let prices: Vec<i64> = group
+            .iter()
+            .map(|item| item.price().to_minor_units())
+            .collect();
+
+        assert_eq!(prices, vec![100, 50, 50, 50]);
+        assert!(group.iter().all(|item| item.quantity() == 1));
+    }
+
+    #[test]
+    fn compress_merges_adjacent_identical_units() {
+        let items: SmallVec<[Item<'_>; 10]> = smallvec![
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(50, GBP),
+                StringTagCollection::empty(),
+                3,
+            ),
+            Item::new(ProductKey::default(), Money::from_minor(100, GBP)),
+            Item::new(ProductKey::default(), Money::from_minor(50, GBP)),
+        ];
+
+        let group = ItemGroup::new(items, GBP);
+        let lines = group.compress(|_| true);
+
+        let quantities: Vec<u32> = lines.iter().map(Item::quantity).collect();
+
+        assert_eq!(quantities, vec![3, 1, 1]);
+    }
+
+    #[test]
+    fn compress_keeps_units_it_may_not_merge_apart() {
+        let items: SmallVec<[Item<'_>; 10]> = smallvec![
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(50, GBP),
+                StringTagCollection::empty(),
+                3,
+            ),
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::empty(),
+                2,
+            ),
+        ];
+
+        let group = ItemGroup::new(items, GBP);
+        let lines = group.compress(|item| item.price().to_minor_units() == 50);
+
+        let quantities: Vec<u32> = lines.iter().map(Item::quantity).collect();
+
+        assert_eq!(quantities, vec![3, 1, 1]);
+    }
+
     #[test]
     fn from_basket_clones_items_and_currency() -> TestResult {
         let basket = Basket::with_items(test_items(), GBP)?;
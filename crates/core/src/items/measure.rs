@@ -0,0 +1,141 @@
+//! Measures
+//!
+//! Variable-measure items (loose produce, deli counters, draught drinks) are sold
+//! by weight or volume rather than by count. A [`Measure`] records how much of the
+//! product the item contains, in whole grams or millilitres.
+
+use rust_decimal::{
+    Decimal, RoundingStrategy,
+    prelude::{FromPrimitive, ToPrimitive},
+};
+use thiserror::Error;
+
+/// Number of base units (grams or millilitres) in one standard unit (kilogram or litre).
+const BASE_UNITS_PER_STANDARD_UNIT: u32 = 1000;
+
+/// Errors specific to measure calculations.
+#[derive(Debug, Error)]
+pub enum MeasureError {
+    /// Scaling a per-kilogram or per-litre amount overflowed.
+    #[error("measured amount overflowed")]
+    Overflow,
+}
+
+/// The unit a [`Measure`] is expressed in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MeasureUnit {
+    /// Weight, in grams. Per-measure prices are per kilogram.
+    Gram,
+
+    /// Volume, in millilitres. Per-measure prices are per litre.
+    Millilitre,
+}
+
+/// A weight or volume of product.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Measure {
+    amount: u32,
+    unit: MeasureUnit,
+}
+
+impl Measure {
+    /// Create a weight of the given number of grams.
+    #[must_use]
+    pub const fn grams(grams: u32) -> Self {
+        Self {
+            amount: grams,
+            unit: MeasureUnit::Gram,
+        }
+    }
+
+    /// Create a weight of the given number of kilograms.
+    #[must_use]
+    pub const fn kilograms(kilograms: u32) -> Self {
+        Self::grams(kilograms.saturating_mul(BASE_UNITS_PER_STANDARD_UNIT))
+    }
+
+    /// Create a volume of the given number of millilitres.
+    #[must_use]
+    pub const fn millilitres(millilitres: u32) -> Self {
+        Self {
+            amount: millilitres,
+            unit: MeasureUnit::Millilitre,
+        }
+    }
+
+    /// Create a volume of the given number of litres.
+    #[must_use]
+    pub const fn litres(litres: u32) -> Self {
+        Self::millilitres(litres.saturating_mul(BASE_UNITS_PER_STANDARD_UNIT))
+    }
+
+    /// Return the amount in grams or millilitres.
+    #[must_use]
+    pub const fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    /// Return the unit the amount is expressed in.
+    #[must_use]
+    pub const fn unit(&self) -> MeasureUnit {
+        self.unit
+    }
+
+    /// Scale an amount given per kilogram or litre to this measure, rounding half away from zero.
+    ///
+    /// For example, a £3.00/kg price over 250g scales to 75p.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasureError::Overflow`] if the scaled amount does not fit in minor units.
+    pub fn scale_minor(&self, per_standard_unit_minor: i64) -> Result<i64, MeasureError> {
+        let per_standard_unit =
+            Decimal::from_i64(per_standard_unit_minor).ok_or(MeasureError::Overflow)?;
+
+        per_standard_unit
+            .checked_mul(Decimal::from(self.amount))
+            .and_then(|scaled| scaled.checked_div(Decimal::from(BASE_UNITS_PER_STANDARD_UNIT)))
+            .ok_or(MeasureError::Overflow)?
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_i64()
+            .ok_or(MeasureError::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use testresult::TestResult;
+
+    use super::*;
+
+    #[test]
+    fn standard_units_convert_to_base_units() {
+        assert_eq!(Measure::kilograms(2), Measure::grams(2000));
+        assert_eq!(Measure::litres(3), Measure::millilitres(3000));
+        assert_eq!(Measure::litres(1).unit(), MeasureUnit::Millilitre);
+    }
+
+    #[test]
+    fn scale_minor_prices_fractional_weights() -> TestResult {
+        assert_eq!(Measure::grams(250).scale_minor(300)?, 75);
+        assert_eq!(Measure::grams(1500).scale_minor(100)?, 150);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_minor_rounds_half_away_from_zero() -> TestResult {
+        // 333g at £1.99/kg is 66.267p; 50g at 10p/kg is exactly half a penny.
+        assert_eq!(Measure::grams(333).scale_minor(199)?, 66);
+        assert_eq!(Measure::grams(50).scale_minor(10)?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_minor_overflow_returns_error() {
+        let result = Measure::kilograms(1000).scale_minor(i64::MAX);
+
+        assert!(matches!(result, Err(MeasureError::Overflow)));
+    }
+}
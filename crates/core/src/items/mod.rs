@@ -3,18 +3,29 @@
 use rusty_money::{Money, iso::Currency};
 
 use crate::{
+    items::measure::{Measure, MeasureError},
     products::ProductKey,
     tags::{collection::TagCollection, string::StringTagCollection},
 };
 
 pub mod groups;
+pub mod measure;
 
 /// An unprocessed item with a price and tags.
+///
+/// An item may stand for several identical units of a product. Baskets and item
+/// groups expand such items into one item per unit, so item indexes always refer
+/// to single units.
+///
+/// Items sold by weight or volume carry a [`Measure`] instead; their price is the
+/// unit price scaled to the measured amount.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Item<'a, T: TagCollection = StringTagCollection> {
     product: ProductKey,
     price: Money<'a, Currency>,
     tags: T,
+    quantity: u32,
+    measure: Option<Measure>,
 }
 
 impl<'a, T: TagCollection> Item<'a, T> {
@@ -26,10 +37,54 @@ impl<'a, T: TagCollection> Item<'a, T> {
 
     /// Creates a new item with the given price and tags.
     pub fn with_tags(product: ProductKey, price: Money<'a, Currency>, tags: T) -> Self {
+        Self::with_quantity(product, price, tags, 1)
+    }
+
+    /// Creates a line item of `quantity` identical units, each at the given unit price.
+    pub fn with_quantity(
+        product: ProductKey,
+        price: Money<'a, Currency>,
+        tags: T,
+        quantity: u32,
+    ) -> Self {
         Self {
             product,
             price,
             tags,
+            quantity,
+            measure: None,
+        }
+    }
+
+    /// Creates a variable-measure item priced from a unit price per kilogram or litre.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MeasureError`] if the scaled price does not fit in minor units.
+    pub fn with_measure(
+        product: ProductKey,
+        unit_price: Money<'a, Currency>,
+        tags: T,
+        measure: Measure,
+    ) -> Result<Self, MeasureError> {
+        let price_minor = measure.scale_minor(unit_price.to_minor_units())?;
+
+        Ok(Self {
+            product,
+            price: Money::from_minor(price_minor, unit_price.currency()),
+            tags,
+            quantity: 1,
+            measure: Some(measure),
+        })
+    }
+
+    /// Returns a copy of the item at a different price, keeping its quantity,
+    /// measure and tags.
+    #[must_use]
+    pub fn with_price(&self, price: Money<'a, Currency>) -> Self {
+        Self {
+            price,
+            ..self.clone()
         }
     }
 
@@ -43,6 +98,16 @@ impl<'a, T: TagCollection> Item<'a, T> {
         &self.price
     }
 
+    /// Returns the number of units the item stands for
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    /// Returns the weight or volume of a variable-measure item
+    pub fn measure(&self) -> Option<Measure> {
+        self.measure
+    }
+
     /// Returns the tags for the item.
     pub fn tags(&self) -> &T {
         &self.tags
@@ -52,6 +117,28 @@ impl<'a, T: TagCollection> Item<'a, T> {
     pub fn tags_mut(&mut self) -> &mut T {
         &mut self.tags
     }
+
+    /// Returns true if both items are interchangeable units of the same product.
+    pub(crate) fn is_same_unit(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.product == other.product
+            && self.price == other.price
+            && self.measure == other.measure
+            && self.tags == other.tags
+    }
+
+    /// Split the item into one single-unit item per unit of its quantity.
+    pub(crate) fn into_units(self) -> impl Iterator<Item = Self> {
+        let quantity = self.quantity;
+        let unit = Self {
+            quantity: 1,
+            ..self
+        };
+
+        std::iter::repeat_n(unit, usize::try_from(quantity).unwrap_or(usize::MAX))
+    }
 }
 
 /// Returns the cheapest item in a list of items
@@ -64,6 +151,7 @@ pub fn cheapest_item<'a, T: TagCollection>(items: &'a [Item<'a, T>]) -> Option<&
 #[cfg(test)]
 mod tests {
     use rusty_money::iso::GBP;
+    use testresult::TestResult;
 
     use super::*;
 
@@ -93,6 +181,70 @@ mod tests {
         assert!(item.tags().contains("sale"));
     }
 
+    #[test]
+    fn with_quantity_records_quantity() {
+        let item: Item<'_> = Item::with_quantity(
+            ProductKey::default(),
+            Money::from_minor(50, GBP),
+            StringTagCollection::from_strs(&["can"]),
+            40,
+        );
+
+        assert_eq!(item.quantity(), 40);
+        assert_eq!(item.price(), &Money::from_minor(50, GBP));
+    }
+
+    #[test]
+    fn with_price_keeps_quantity_and_measure() -> TestResult {
+        let item: Item<'_> = Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(200, GBP),
+            StringTagCollection::from_strs(&["produce"]),
+            Measure::grams(1200),
+        )?;
+
+        let repriced = item.with_price(Money::from_minor(100, GBP));
+
+        assert_eq!(repriced.price(), &Money::from_minor(100, GBP));
+        assert_eq!(repriced.measure(), Some(Measure::grams(1200)));
+        assert_eq!(repriced.quantity(), item.quantity());
+        assert_eq!(repriced.tags(), item.tags());
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_units_splits_quantity_into_single_units() {
+        let item: Item<'_> = Item::with_quantity(
+            ProductKey::default(),
+            Money::from_minor(50, GBP),
+            StringTagCollection::from_strs(&["can"]),
+            3,
+        );
+
+        let units: Vec<_> = item.clone().into_units().collect();
+
+        assert_eq!(units.len(), 3);
+        assert!(units.iter().all(|unit| unit.quantity() == 1));
+        assert!(units.iter().all(|unit| unit.is_same_unit(&item)));
+    }
+
+    #[test]
+    fn with_measure_prices_item_from_unit_price() -> TestResult {
+        let item: Item<'_> = Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(300, GBP),
+            StringTagCollection::from_strs(&["produce"]),
+            Measure::grams(250),
+        )?;
+
+        assert_eq!(item.price(), &Money::from_minor(75, GBP));
+        assert_eq!(item.measure(), Some(Measure::grams(250)));
+        assert_eq!(item.quantity(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn item_product_accessor_returns_key() {
         let key = ProductKey::default();
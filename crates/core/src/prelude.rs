@@ -5,7 +5,10 @@
 pub use crate::{
     basket::{Basket, BasketError},
     discounts::{DiscountError, SimpleDiscount},
-    graph::{GraphError, LayeredSolverResult, OutputMode, PromotionGraph, PromotionGraphBuilder},
+    graph::{
+        EvaluationSession, GraphError, LayeredSolverResult, OutputMode, PromotionGraph,
+        PromotionGraphBuilder, Upsell,
+    },
     items::{
         Item,
         groups::{ItemGroup, ItemGroupError},
@@ -24,10 +27,18 @@ pub use crate::{
     receipt::{Receipt, ReceiptError},
     solvers::{
         Solver, SolverError, SolverResult,
+        brute_force::BruteForceSolver,
+        greedy::{GreedySolver, OptimalityGap},
         ilp::{
-            ILPObserver, ILPSolver, NoopObserver,
-            renderers::typst::{MultiLayerRenderer, TypstRenderError, TypstRenderer},
+            ILPBackend, ILPObserver, ILPSolver, ILPSolverConfig, NoopObserver, RetailerGoal,
+            RetailerObjective, TieBreak,
+            renderers::{
+                json::{FormulationTrace, TraceError, TraceEvent, TraceObserver, TraceTerm},
+                lp::{LpFormat, LpRenderError, LpRenderer},
+                typst::{MultiLayerRenderer, TypstRenderError, TypstRenderer},
+            },
         },
+        verifier::{VerificationError, verify_result},
     },
     tags::{collection::TagCollection, string::StringTagCollection},
 };
@@ -54,6 +54,15 @@ impl<'a> PromotionBudget<'a> {
     pub const fn has_constraints(&self) -> bool {
         self.redemption_limit.is_some() || self.monetary_limit.is_some()
     }
+
+    /// Check if this budget allows no redemptions at all
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.redemption_limit == Some(0)
+            || self
+                .monetary_limit
+                .is_some_and(|limit| limit.to_minor_units() <= 0)
+    }
 }
 
 #[cfg(test)]
@@ -98,4 +107,16 @@ mod tests {
         assert_eq!(budget.redemption_limit, Some(5));
         assert_eq!(budget.monetary_limit, Some(limit));
     }
+
+    #[test]
+    fn test_exhausted_budgets() {
+        let limit = Money::from_minor(1000, iso::GBP);
+        let spent = Money::from_minor(0, iso::GBP);
+
+        assert!(!PromotionBudget::unlimited().is_exhausted());
+        assert!(!PromotionBudget::with_both_limits(5, limit).is_exhausted());
+        assert!(PromotionBudget::with_redemption_limit(0).is_exhausted());
+        assert!(PromotionBudget::with_monetary_limit(spent).is_exhausted());
+        assert!(PromotionBudget::with_both_limits(5, spent).is_exhausted());
+    }
 }
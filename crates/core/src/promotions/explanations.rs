@@ -0,0 +1,106 @@
+//! Promotion Explanations
+//!
+//! Answers the question "how do I get this promotion?" for a basket: whether each
+//! promotion applied and, if not, the closest requirement the basket is missing,
+//! such as an unfilled mix-and-match slot or the spend still needed to reach a
+//! tier. Tiered promotions also report how far away their next tier is.
+
+use crate::{
+    items::measure::Measure,
+    promotions::{PromotionKey, PromotionSlotKey},
+};
+
+/// Whether a promotion applied to a basket, and how close it is to applying.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromotionExplanation {
+    /// Key of the promotion being explained
+    pub promotion_key: PromotionKey,
+
+    /// Whether the promotion was redeemed on at least one item
+    pub applied: bool,
+
+    /// How close the basket is to the promotion's requirements
+    pub progress: PromotionProgress,
+}
+
+impl PromotionExplanation {
+    /// Whether the basket meets every requirement, yet the promotion did not apply.
+    ///
+    /// This happens when a better promotion claimed the items, or the promotion's
+    /// monetary budget does not cover any redemption the basket allows.
+    pub fn is_outcompeted(&self) -> bool {
+        !self.applied && self.progress.missing.is_none()
+    }
+}
+
+/// How close a basket is to meeting a promotion's requirements.
+///
+/// Measured against every item in the basket at its undiscounted price.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromotionProgress {
+    /// The closest requirement the basket does not meet yet, if any
+    pub missing: Option<MissingRequirement>,
+
+    /// For tiered promotions, what is still needed for the next tier above the
+    /// highest one the basket reaches
+    pub next_tier: Option<TierShortfall>,
+
+    /// For positional promotions the basket already forms a bundle for, the
+    /// further qualifying items needed to form another, unless the budget
+    /// allows no more bundles
+    pub next_bundle: Option<u32>,
+}
+
+/// A requirement a basket does not meet yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingRequirement {
+    /// More qualifying items are needed (the number of items).
+    QualifyingItems(u32),
+
+    /// A mix-and-match slot has too few qualifying items.
+    SlotItems {
+        /// Key of the unfilled slot
+        slot: PromotionSlotKey,
+
+        /// Number of items still needed to fill it
+        items: u32,
+    },
+
+    /// No tier's threshold is reached; this is what the lowest tier still needs.
+    Tier(TierShortfall),
+
+    /// The promotion's budget allows no redemptions, such as a redemption or
+    /// monetary limit that is already used up.
+    BudgetExhausted,
+}
+
+/// What a basket still needs to reach a tier of a tiered promotion.
+///
+/// Only requirements that are not met yet are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierShortfall {
+    /// Position of the tier in the promotion's tiers
+    pub tier_idx: usize,
+
+    /// Further spend on contributing items, in minor units
+    pub spend_minor: Option<i64>,
+
+    /// Further contributing items
+    pub items: Option<u32>,
+
+    /// Further weight or volume of contributing items
+    pub measure: Option<Measure>,
+
+    /// Whether the basket also needs an item the tier discounts
+    pub discounted_item: bool,
+}
+
+impl TierShortfall {
+    /// Whether the tier is reached, with nothing left to add.
+    pub fn is_reached(&self) -> bool {
+        self.spend_minor.is_none()
+            && self.items.is_none()
+            && self.measure.is_none()
+            && !self.discounted_item
+    }
+}
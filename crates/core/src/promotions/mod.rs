@@ -7,6 +7,7 @@ use slotmap::{SecondaryMap, new_key_type};
 use crate::{graph::PromotionLayerKey, solvers::ilp::ILPPromotion};
 
 pub mod budget;
+pub mod explanations;
 pub mod prelude;
 pub mod qualification;
 pub mod redemptions;
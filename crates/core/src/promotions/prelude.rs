@@ -5,3 +5,5 @@
 pub use crate::solvers::ilp::{
     ILPPromotion, ILPPromotionVars, ILPState, PromotionVars, i64_to_f64_exact,
 };
+
+pub use crate::promotions::explanations::{MissingRequirement, PromotionProgress, TierShortfall};
@@ -3,7 +3,7 @@
 //! A direct percentage discount, amount discount, or amount override on all qualifying items
 
 use crate::{
-    discounts::{DiscountError, SimpleDiscount, percent_of_minor},
+    discounts::{DiscountError, SimpleDiscount, amount_off_per_measure_minor, percent_of_minor},
     items::Item,
     promotions::{PromotionKey, budget::PromotionBudget, qualification::Qualification},
     tags::{collection::TagCollection, string::StringTagCollection},
@@ -83,6 +83,15 @@ impl<'a, T: TagCollection> DirectDiscountPromotion<'a, T> {
                 // Subtract amount from price
                 item.price().sub(*amount)?.to_minor_units()
             }
+            SimpleDiscount::AmountOffPerMeasure(amount) => {
+                // Subtract the amount scaled to the item's weight or volume
+                let amount_off_minor =
+                    amount_off_per_measure_minor(amount.to_minor_units(), item.measure())?;
+
+                item.price()
+                    .to_minor_units()
+                    .saturating_sub(amount_off_minor)
+            }
         };
 
         Ok(Money::from_minor(
@@ -99,7 +108,11 @@ mod tests {
     use slotmap::SlotMap;
     use testresult::TestResult;
 
-    use crate::{items::Item, products::ProductKey, promotions::qualification::Qualification};
+    use crate::{
+        items::{Item, measure::Measure},
+        products::ProductKey,
+        promotions::qualification::Qualification,
+    };
 
     use super::*;
 
@@ -170,6 +183,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn calculate_discounted_price_amount_off_per_measure() -> TestResult {
+        let promo = DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::<StringTagCollection>::match_all(),
+            SimpleDiscount::AmountOffPerMeasure(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        );
+
+        // 1.5kg at £4.00/kg with £1 off per kg
+        let weighed = Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(400, GBP),
+            StringTagCollection::empty(),
+            Measure::grams(1500),
+        )?;
+
+        assert_eq!(
+            promo.calculate_discounted_price(&weighed)?,
+            Money::from_minor(450, GBP)
+        );
+
+        // Items sold by count have no measure to discount
+        let counted = Item::new(ProductKey::default(), Money::from_minor(100, GBP));
+
+        assert_eq!(
+            promo.calculate_discounted_price(&counted)?,
+            Money::from_minor(100, GBP)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn calculate_discounted_price_clamps_percentage_to_zero() -> TestResult {
         let promo = DirectDiscountPromotion::new(
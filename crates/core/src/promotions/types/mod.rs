@@ -9,3 +9,19 @@ pub use direct_discount::*;
 pub use mix_and_match::*;
 pub use positional_discount::*;
 pub use tiered_threshold::*;
+
+/// One of the built-in promotion types.
+#[derive(Debug, Clone, Copy)]
+pub enum PromotionType<'p> {
+    /// A [`DirectDiscountPromotion`].
+    DirectDiscount(&'p DirectDiscountPromotion<'p>),
+
+    /// A [`MixAndMatchPromotion`].
+    MixAndMatch(&'p MixAndMatchPromotion<'p>),
+
+    /// A [`PositionalDiscountPromotion`].
+    PositionalDiscount(&'p PositionalDiscountPromotion<'p>),
+
+    /// A [`TieredThresholdPromotion`].
+    TieredThreshold(&'p TieredThresholdPromotion<'p>),
+}
@@ -11,7 +11,7 @@ use rusty_money::{Money, iso::Currency};
 
 use crate::{
     discounts::{DiscountError, percent_of_minor},
-    items::Item,
+    items::{Item, measure::Measure},
     promotions::{PromotionKey, budget::PromotionBudget, qualification::Qualification},
     tags::{collection::TagCollection, string::StringTagCollection},
 };
@@ -47,11 +47,17 @@ pub enum ThresholdDiscount<'a> {
 
 /// Threshold requirements for a tier.
 ///
-/// A threshold can require spend, item count, or both.
+/// A threshold can require spend, item count, or both. It can also require a
+/// weight or volume of variable-measure items (e.g., "buy 2kg").
 #[derive(Debug, Clone)]
+#[expect(
+    clippy::struct_field_names,
+    reason = "Each field is a distinct kind of threshold"
+)]
 pub struct TierThreshold<'a> {
     monetary_threshold: Option<Money<'a, Currency>>,
     item_count_threshold: Option<u32>,
+    measure_threshold: Option<Measure>,
 }
 
 impl<'a> TierThreshold<'a> {
@@ -64,6 +70,7 @@ impl<'a> TierThreshold<'a> {
         Self {
             monetary_threshold,
             item_count_threshold,
+            measure_threshold: None,
         }
     }
 
@@ -73,6 +80,7 @@ impl<'a> TierThreshold<'a> {
         Self {
             monetary_threshold: Some(monetary),
             item_count_threshold: None,
+            measure_threshold: None,
         }
     }
 
@@ -82,6 +90,7 @@ impl<'a> TierThreshold<'a> {
         Self {
             monetary_threshold: None,
             item_count_threshold: Some(item_count),
+            measure_threshold: None,
         }
     }
 
@@ -91,6 +100,20 @@ impl<'a> TierThreshold<'a> {
         Self {
             monetary_threshold: Some(monetary),
             item_count_threshold: Some(item_count),
+            measure_threshold: None,
+        }
+    }
+
+    /// Create a threshold with a measure requirement only.
+    ///
+    /// Only items measured in the same unit contribute; items sold by count and
+    /// items measured in another unit count as zero.
+    #[must_use]
+    pub const fn with_measure_threshold(measure: Measure) -> Self {
+        Self {
+            monetary_threshold: None,
+            item_count_threshold: None,
+            measure_threshold: Some(measure),
         }
     }
 
@@ -105,6 +128,12 @@ impl<'a> TierThreshold<'a> {
     pub const fn item_count_threshold(&self) -> Option<u32> {
         self.item_count_threshold
     }
+
+    /// Return the optional measure threshold.
+    #[must_use]
+    pub const fn measure_threshold(&self) -> Option<Measure> {
+        self.measure_threshold
+    }
 }
 
 /// A single threshold tier within a tiered threshold promotion.
@@ -383,6 +412,13 @@ mod tests {
             Some(5000)
         );
         assert_eq!(both.item_count_threshold(), Some(3));
+        assert_eq!(both.measure_threshold(), None);
+
+        let measure_only = TierThreshold::with_measure_threshold(Measure::kilograms(2));
+
+        assert_eq!(measure_only.monetary_threshold(), None);
+        assert_eq!(measure_only.item_count_threshold(), None);
+        assert_eq!(measure_only.measure_threshold(), Some(Measure::grams(2000)));
     }
 
     #[test]
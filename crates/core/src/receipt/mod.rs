@@ -837,6 +837,7 @@ mod tests {
             unaffected_items: smallvec![1],
             total: Money::from_minor(500, GBP), // 75 + 200 + 225
             promotion_redemptions: promotion_apps,
+            optimal: true,
         };
 
         let receipt = Receipt::from_solver_result(&basket, solver_result)?;
@@ -864,6 +865,7 @@ mod tests {
             unaffected_items: smallvec![0, 1],
             total: Money::from_minor(300, GBP),
             promotion_redemptions: smallvec![],
+            optimal: true,
         };
 
         let receipt = Receipt::from_solver_result(&basket, solver_result)?;
@@ -899,6 +901,7 @@ mod tests {
             unaffected_items: smallvec![],
             total: Money::from_minor(50, GBP),
             promotion_redemptions: promotion_apps,
+            optimal: true,
         };
 
         let receipt = Receipt::from_solver_result(&basket, solver_result)?;
@@ -1173,6 +1176,7 @@ mod tests {
             unaffected_items: smallvec![],
             total: Money::from_minor(50, GBP),
             promotion_redemptions: smallvec![redemption.clone(), redemption],
+            optimal: true,
         };
 
         let _ = Receipt::from_solver_result(&basket, solver_result).expect("receipt should build");
@@ -1336,6 +1340,7 @@ mod tests {
             total: Money::from_minor(470, GBP),
             item_redemptions,
             full_price_items: smallvec![1],
+            optimal: true,
         };
 
         let receipt = Receipt::from_layered_result(&basket, layered_result)?;
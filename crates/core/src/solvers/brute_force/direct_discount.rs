@@ -0,0 +1,37 @@
+//! Direct Discount Candidates
+//!
+//! Every item is redeemed on its own, so there is only one candidate.
+
+use smallvec::{SmallVec, smallvec};
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::types::DirectDiscountPromotion,
+    solvers::{
+        SolverError,
+        brute_force::{Bundle, Candidate, candidate},
+        verifier::simple_discounted_minor,
+    },
+};
+
+/// List the ways a direct discount can redeem `items`.
+pub(crate) fn candidates<'b>(
+    promotion: &DirectDiscountPromotion<'_>,
+    item_group: &ItemGroup<'b>,
+    items: &[usize],
+) -> Result<Vec<Candidate<'b>>, SolverError> {
+    let mut bundles: SmallVec<[Bundle; 10]> = SmallVec::new();
+
+    for &item_idx in items {
+        let item = item_group.get_item(item_idx)?;
+        let final_minor = simple_discounted_minor(
+            promotion.discount(),
+            item.price().to_minor_units(),
+            item.measure(),
+        )?;
+
+        bundles.push(smallvec![(item_idx, final_minor)]);
+    }
+
+    Ok(vec![candidate(promotion.key(), item_group, &bundles)?])
+}
@@ -0,0 +1,156 @@
+//! Mix-and-Match Candidates
+//!
+//! A promotion with fixed arity can split the items into any number of full
+//! bundles, so every such split is a candidate. Otherwise all of the items form
+//! one bundle. Slot fills are left to the verifier.
+
+use smallvec::SmallVec;
+
+use crate::{
+    discounts::percent_of_minor,
+    items::groups::ItemGroup,
+    promotions::types::{MixAndMatchDiscount, MixAndMatchPromotion},
+    solvers::{
+        SolverError,
+        brute_force::{
+            Bundle, Candidate, allocate_total, candidate, cheapest_discounted, original_prices,
+        },
+    },
+};
+
+/// List the ways a mix-and-match promotion can redeem `items`.
+pub(crate) fn candidates<'b>(
+    promotion: &MixAndMatchPromotion<'_>,
+    item_group: &ItemGroup<'b>,
+    items: &[usize],
+) -> Result<Vec<Candidate<'b>>, SolverError> {
+    let splits = if promotion.has_fixed_arity() {
+        let size = promotion.bundle_size();
+
+        if size == 0 || !items.len().is_multiple_of(size) {
+            return Ok(Vec::new());
+        }
+
+        let mut splits = Vec::new();
+
+        split_into_bundles(items, size, &mut SmallVec::new(), &mut splits);
+
+        splits
+    } else {
+        vec![SmallVec::from_elem(items.iter().copied().collect(), 1)]
+    };
+
+    let mut candidates = Vec::with_capacity(splits.len());
+
+    for split in splits {
+        let mut bundles: SmallVec<[Bundle; 10]> = SmallVec::new();
+
+        for bundle in &split {
+            let originals = original_prices(item_group, bundle)?;
+            let finals = bundle_prices(promotion.discount(), &originals)?;
+
+            bundles.push(bundle.iter().copied().zip(finals).collect());
+        }
+
+        candidates.push(candidate(promotion.key(), item_group, &bundles)?);
+    }
+
+    Ok(candidates)
+}
+
+/// Indexes of the items in one bundle.
+type ItemSet = SmallVec<[usize; 10]>;
+
+/// Collect every way of splitting `items` into bundles of `size`.
+///
+/// Each bundle starts with the first item not yet taken, so each split is
+/// collected once.
+fn split_into_bundles(
+    items: &[usize],
+    size: usize,
+    taken: &mut SmallVec<[ItemSet; 10]>,
+    splits: &mut Vec<SmallVec<[ItemSet; 10]>>,
+) {
+    let Some((&first, rest)) = items.split_first() else {
+        splits.push(taken.clone());
+        return;
+    };
+
+    let mut others = ItemSet::new();
+
+    choose(rest, size.saturating_sub(1), &mut others, &mut |chosen| {
+        let mut bundle = ItemSet::new();
+
+        bundle.push(first);
+        bundle.extend(chosen.iter().copied());
+
+        let remaining: ItemSet = rest
+            .iter()
+            .copied()
+            .filter(|item_idx| !chosen.contains(item_idx))
+            .collect();
+
+        taken.push(bundle);
+        split_into_bundles(&remaining, size, taken, splits);
+        taken.pop();
+    });
+}
+
+/// Call `visit` with every choice of `count` items from `items`.
+fn choose(items: &[usize], count: usize, chosen: &mut ItemSet, visit: &mut impl FnMut(&[usize])) {
+    if chosen.len() == count {
+        visit(chosen);
+        return;
+    }
+
+    for (position, &item_idx) in items.iter().enumerate() {
+        chosen.push(item_idx);
+        choose(
+            items.get(position + 1..).unwrap_or_default(),
+            count,
+            chosen,
+            visit,
+        );
+        chosen.pop();
+    }
+}
+
+/// Final prices of one bundle's items, given their original prices.
+fn bundle_prices(
+    discount: &MixAndMatchDiscount<'_>,
+    originals: &[i64],
+) -> Result<SmallVec<[i64; 10]>, SolverError> {
+    let prices = match discount {
+        MixAndMatchDiscount::PercentAllItems(pct) => originals
+            .iter()
+            .map(|&original| Ok(original.saturating_sub(percent_of_minor(pct, original)?)))
+            .collect::<Result<_, SolverError>>()?,
+        MixAndMatchDiscount::AmountOffEachItem(amount) => originals
+            .iter()
+            .map(|&original| original.saturating_sub(amount.to_minor_units()).max(0))
+            .collect(),
+        MixAndMatchDiscount::FixedPriceEachItem(amount) => originals
+            .iter()
+            .map(|_| amount.to_minor_units().max(0))
+            .collect(),
+        MixAndMatchDiscount::AmountOffTotal(amount) => {
+            let original: i64 = originals.iter().sum();
+
+            allocate_total(
+                original.saturating_sub(amount.to_minor_units()).max(0),
+                originals,
+            )
+        }
+        MixAndMatchDiscount::FixedTotal(amount) => {
+            allocate_total(amount.to_minor_units().max(0), originals)
+        }
+        MixAndMatchDiscount::PercentCheapest(pct) => cheapest_discounted(originals, |original| {
+            Ok(original.saturating_sub(percent_of_minor(pct, original)?))
+        })?,
+        MixAndMatchDiscount::FixedCheapest(amount) => {
+            cheapest_discounted(originals, |_original| Ok(amount.to_minor_units().max(0)))?
+        }
+    };
+
+    Ok(prices)
+}
@@ -0,0 +1,383 @@
+//! Brute-Force Solver
+//!
+//! An exhaustive reference solver for small baskets. It tries every way of
+//! sharing the items between the promotions. For each promotion and set of
+//! items, it lists every way the promotion's rules let it redeem exactly those
+//! items, and the cheapest one that passes the [verifier](super::verifier) is
+//! kept.
+//!
+//! None of the ILP formulation is used, so the two solvers can be compared to
+//! test each other. The search grows exponentially with the number of items.
+
+use rustc_hash::FxHashMap;
+use rusty_money::Money;
+use smallvec::{SmallVec, smallvec};
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::{Promotion, PromotionKey, redemptions::PromotionRedemption, types::PromotionType},
+    solvers::{Solver, SolverError, SolverResult, ilp::ILPPromotion, verifier::verify_promotion},
+};
+
+pub(crate) mod direct_discount;
+pub(crate) mod mix_and_match;
+pub(crate) mod positional_discount;
+pub(crate) mod tiered_threshold;
+
+/// One way for a promotion to redeem a set of items.
+///
+/// Redemption indexes group the items into bundles and start from zero.
+pub type Candidate<'b> = SmallVec<[PromotionRedemption<'b>; 10]>;
+
+/// Items redeemed together, with their final prices in minor units.
+pub(crate) type Bundle = SmallVec<[(usize, i64); 10]>;
+
+/// Solver that finds the optimum by trying every assignment of items to promotions.
+///
+/// Only the built-in promotion types, found through
+/// [`ILPPromotion::promotion_type`], are ever applied.
+#[derive(Debug)]
+pub struct BruteForceSolver;
+
+impl BruteForceSolver {
+    /// Largest item group the solver accepts.
+    pub const MAX_ITEMS: usize = 10;
+}
+
+impl Solver for BruteForceSolver {
+    fn solve<'b>(
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        if item_group.len() > Self::MAX_ITEMS {
+            return Err(SolverError::TooManyItems {
+                items: item_group.len(),
+                limit: Self::MAX_ITEMS,
+            });
+        }
+
+        let mut search = Search {
+            promotions,
+            item_group,
+            applicable: promotions
+                .iter()
+                .map(|promotion| promotion.is_applicable(item_group))
+                .collect(),
+            cheapest: FxHashMap::default(),
+            best: None,
+        };
+
+        let mut claims: SmallVec<[u32; 5]> = smallvec![0; promotions.len()];
+
+        search.assign(0, &mut claims)?;
+
+        search.into_result()
+    }
+}
+
+/// State of the exhaustive search.
+struct Search<'p, 'a, 'g, 'b> {
+    promotions: &'p [Promotion<'a>],
+    item_group: &'g ItemGroup<'b>,
+
+    /// Whether each promotion might apply at all
+    applicable: SmallVec<[bool; 5]>,
+
+    /// Cheapest valid redemption of each promotion and set of items (as a bit mask),
+    /// or `None` if the promotion cannot redeem exactly those items
+    cheapest: FxHashMap<(usize, u32), Option<(i64, Candidate<'b>)>>,
+
+    /// Cheapest total found so far, with the items each promotion claims
+    best: Option<(i64, SmallVec<[u32; 5]>)>,
+}
+
+impl<'b> Search<'_, '_, '_, 'b> {
+    /// Try every claim for the items from `item_idx` on.
+    fn assign(&mut self, item_idx: usize, claims: &mut [u32]) -> Result<(), SolverError> {
+        if item_idx == self.item_group.len() {
+            return self.evaluate(claims);
+        }
+
+        // Left at full price.
+        self.assign(item_idx + 1, claims)?;
+
+        let item = self.item_group.get_item(item_idx)?;
+        let bit = 1_u32 << item_idx;
+
+        for (promotion_idx, promotion) in self.promotions.iter().enumerate() {
+            if !self.applicable.get(promotion_idx).copied().unwrap_or(false)
+                || !promotion.is_item_eligible(item)
+            {
+                continue;
+            }
+
+            let Some(claim) = claims.get_mut(promotion_idx) else {
+                continue;
+            };
+
+            *claim |= bit;
+            self.assign(item_idx + 1, claims)?;
+
+            if let Some(claim) = claims.get_mut(promotion_idx) {
+                *claim &= !bit;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Price a complete assignment, keeping it if it is the cheapest so far.
+    fn evaluate(&mut self, claims: &[u32]) -> Result<(), SolverError> {
+        let mut total = 0_i64;
+        let mut claimed = 0_u32;
+
+        for (promotion_idx, &claim) in claims.iter().enumerate() {
+            if claim == 0 {
+                continue;
+            }
+
+            let Some(cost) = self.cost(promotion_idx, claim)? else {
+                return Ok(());
+            };
+
+            total += cost;
+            claimed |= claim;
+        }
+
+        for (item_idx, item) in self.item_group.iter().enumerate() {
+            if claimed & (1 << item_idx) == 0 {
+                total += item.price().to_minor_units();
+            }
+        }
+
+        if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+            self.best = Some((total, claims.iter().copied().collect()));
+        }
+
+        Ok(())
+    }
+
+    /// Total of the cheapest valid redemption of exactly the items in `claim`.
+    fn cost(&mut self, promotion_idx: usize, claim: u32) -> Result<Option<i64>, SolverError> {
+        if let Some(cheapest) = self.cheapest.get(&(promotion_idx, claim)) {
+            return Ok(cheapest.as_ref().map(|(total, _)| *total));
+        }
+
+        let Some(promotion) = self.promotions.get(promotion_idx) else {
+            return Ok(None);
+        };
+
+        let items: SmallVec<[usize; 10]> = (0..self.item_group.len())
+            .filter(|item_idx| claim & (1 << item_idx) != 0)
+            .collect();
+
+        let mut cheapest: Option<(i64, Candidate<'b>)> = None;
+
+        for candidate in candidates(promotion, self.item_group, &items)? {
+            if verify_promotion(promotion, self.item_group, &candidate).is_err() {
+                continue;
+            }
+
+            let total: i64 = candidate
+                .iter()
+                .map(|redemption| redemption.final_price.to_minor_units())
+                .sum();
+
+            if cheapest.as_ref().is_none_or(|(best, _)| total < *best) {
+                cheapest = Some((total, candidate));
+            }
+        }
+
+        let total = cheapest.as_ref().map(|(total, _)| *total);
+
+        self.cheapest.insert((promotion_idx, claim), cheapest);
+
+        Ok(total)
+    }
+
+    /// Build the result for the cheapest assignment.
+    fn into_result(self) -> Result<SolverResult<'b>, SolverError> {
+        let currency = self.item_group.currency();
+        let claims = self.best.map(|(_, claims)| claims).unwrap_or_default();
+
+        let mut total = Money::from_minor(0, currency);
+        let mut affected_items = SmallVec::new();
+        let mut unaffected_items = SmallVec::new();
+        let mut promotion_redemptions = SmallVec::new();
+        let mut next_redemption_idx = 0;
+
+        for (promotion_idx, &claim) in claims.iter().enumerate() {
+            let Some(Some((_, candidate))) = self.cheapest.get(&(promotion_idx, claim)) else {
+                continue;
+            };
+
+            let offset = next_redemption_idx;
+
+            for redemption in candidate {
+                let redemption_idx = redemption.redemption_idx + offset;
+
+                next_redemption_idx = next_redemption_idx.max(redemption_idx + 1);
+                total = total.add(redemption.final_price)?;
+                affected_items.push(redemption.item_idx);
+
+                promotion_redemptions.push(PromotionRedemption {
+                    redemption_idx,
+                    ..redemption.clone()
+                });
+            }
+        }
+
+        for (item_idx, item) in self.item_group.iter().enumerate() {
+            if affected_items.contains(&item_idx) {
+                continue;
+            }
+
+            total = total.add(Money::from_minor(item.price().to_minor_units(), currency))?;
+            unaffected_items.push(item_idx);
+        }
+
+        Ok(SolverResult {
+            affected_items,
+            unaffected_items,
+            total,
+            promotion_redemptions,
+            optimal: true,
+        })
+    }
+}
+
+/// List every way `promotion` could redeem exactly the items in `items`.
+///
+/// Each candidate holds one redemption per item, grouped into bundles by
+/// redemption index from zero. Candidates may break the promotion's rules, such
+/// as its budget, since [`verify_promotion`] filters them. Custom
+/// promotions list nothing.
+fn candidates<'b>(
+    promotion: &Promotion<'_>,
+    item_group: &ItemGroup<'b>,
+    items: &[usize],
+) -> Result<Vec<Candidate<'b>>, SolverError> {
+    match promotion.promotion_type() {
+        Some(PromotionType::DirectDiscount(promotion)) => {
+            direct_discount::candidates(promotion, item_group, items)
+        }
+        Some(PromotionType::MixAndMatch(promotion)) => {
+            mix_and_match::candidates(promotion, item_group, items)
+        }
+        Some(PromotionType::PositionalDiscount(promotion)) => {
+            positional_discount::candidates(promotion, item_group, items)
+        }
+        Some(PromotionType::TieredThreshold(promotion)) => {
+            tiered_threshold::candidates(promotion, item_group, items)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Build a candidate from bundles of `(item index, final price)` pairs.
+pub(crate) fn candidate<'b>(
+    promotion_key: PromotionKey,
+    item_group: &ItemGroup<'b>,
+    bundles: &[Bundle],
+) -> Result<Candidate<'b>, SolverError> {
+    let currency = item_group.currency();
+    let mut redemptions = Candidate::new();
+
+    for (redemption_idx, bundle) in bundles.iter().enumerate() {
+        for &(item_idx, final_minor) in bundle {
+            let item = item_group.get_item(item_idx)?;
+
+            redemptions.push(PromotionRedemption {
+                promotion_key,
+                item_idx,
+                redemption_idx,
+                original_price: *item.price(),
+                final_price: Money::from_minor(final_minor, currency),
+            });
+        }
+    }
+
+    Ok(redemptions)
+}
+
+/// Original prices of `items`, in minor units.
+pub(crate) fn original_prices(
+    item_group: &ItemGroup<'_>,
+    items: &[usize],
+) -> Result<SmallVec<[i64; 10]>, SolverError> {
+    items
+        .iter()
+        .map(|&item_idx| Ok(item_group.get_item(item_idx)?.price().to_minor_units()))
+        .collect()
+}
+
+/// Share `total` between items in proportion to their original prices.
+///
+/// Each share is rounded half up, and the last item takes what is left.
+pub(crate) fn allocate_total(total: i64, originals: &[i64]) -> SmallVec<[i64; 10]> {
+    let denom: i64 = originals.iter().sum();
+    let mut remaining = total;
+    let mut shares = SmallVec::new();
+
+    for (position, &original) in originals.iter().enumerate() {
+        let share = if position + 1 == originals.len() {
+            remaining
+        } else if denom == 0 {
+            0
+        } else {
+            let share = (i128::from(total) * i128::from(original) + i128::from(denom / 2))
+                / i128::from(denom);
+
+            i64::try_from(share).unwrap_or(0)
+        };
+
+        remaining -= share;
+        shares.push(share);
+    }
+
+    shares
+}
+
+/// Final prices when only the cheapest item is discounted, to `target`.
+pub(crate) fn cheapest_discounted(
+    originals: &[i64],
+    target: impl Fn(i64) -> Result<i64, SolverError>,
+) -> Result<SmallVec<[i64; 10]>, SolverError> {
+    let cheapest = originals
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, original)| *original)
+        .map(|(position, _)| position);
+
+    originals
+        .iter()
+        .enumerate()
+        .map(|(position, &original)| {
+            if Some(position) == cheapest {
+                target(original)
+            } else {
+                Ok(original)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_total_gives_remainder_to_last_item() {
+        assert_eq!(allocate_total(100, &[1, 1, 1]).as_slice(), &[33, 33, 34]);
+        assert_eq!(allocate_total(0, &[0, 0]).as_slice(), &[0, 0]);
+    }
+
+    #[test]
+    fn cheapest_discounted_only_discounts_one_item() -> Result<(), SolverError> {
+        let prices = cheapest_discounted(&[300, 100, 100], |_| Ok(0))?;
+
+        assert_eq!(prices.as_slice(), &[300, 0, 100]);
+
+        Ok(())
+    }
+}
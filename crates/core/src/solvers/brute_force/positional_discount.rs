@@ -0,0 +1,68 @@
+//! Positional Discount Candidates
+//!
+//! Ordered by price, most expensive first, the items are cut into bundles of the
+//! promotion's size. Only the items at the promotion's positions in each bundle
+//! are discounted.
+
+use smallvec::SmallVec;
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::types::PositionalDiscountPromotion,
+    solvers::{
+        SolverError,
+        brute_force::{Bundle, Candidate, candidate},
+        verifier::simple_discounted_minor,
+    },
+};
+
+/// List the ways a positional discount can redeem `items`.
+pub(crate) fn candidates<'b>(
+    promotion: &PositionalDiscountPromotion<'_>,
+    item_group: &ItemGroup<'b>,
+    items: &[usize],
+) -> Result<Vec<Candidate<'b>>, SolverError> {
+    let size = usize::from(promotion.size());
+
+    if size == 0 || !items.len().is_multiple_of(size) {
+        return Ok(Vec::new());
+    }
+
+    let mut ordered: SmallVec<[(usize, i64); 10]> = SmallVec::new();
+
+    for &item_idx in items {
+        ordered.push((
+            item_idx,
+            item_group.get_item(item_idx)?.price().to_minor_units(),
+        ));
+    }
+
+    ordered.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut bundles: SmallVec<[Bundle; 10]> = SmallVec::new();
+
+    for chunk in ordered.chunks(size) {
+        let mut bundle = SmallVec::new();
+
+        for (position, &(item_idx, original_minor)) in chunk.iter().enumerate() {
+            let discounted = u16::try_from(position)
+                .is_ok_and(|position| promotion.positions().contains(&position));
+
+            let final_minor = if discounted {
+                simple_discounted_minor(
+                    promotion.discount(),
+                    original_minor,
+                    item_group.get_item(item_idx)?.measure(),
+                )?
+            } else {
+                original_minor
+            };
+
+            bundle.push((item_idx, final_minor));
+        }
+
+        bundles.push(bundle);
+    }
+
+    Ok(vec![candidate(promotion.key(), item_group, &bundles)?])
+}
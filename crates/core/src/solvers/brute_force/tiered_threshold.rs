@@ -0,0 +1,111 @@
+//! Tiered Threshold Candidates
+//!
+//! All of the items form one redemption, by any tier they all qualify for. Items
+//! that only contribute keep their price. Thresholds are left to the verifier.
+
+use smallvec::SmallVec;
+
+use crate::{
+    discounts::percent_of_minor,
+    items::groups::ItemGroup,
+    promotions::types::{ThresholdDiscount, ThresholdTier, TieredThresholdPromotion},
+    solvers::{
+        SolverError,
+        brute_force::{
+            Bundle, Candidate, allocate_total, candidate, cheapest_discounted, original_prices,
+        },
+    },
+};
+
+/// List the ways a tiered threshold promotion can redeem `items`.
+pub(crate) fn candidates<'b>(
+    promotion: &TieredThresholdPromotion<'_>,
+    item_group: &ItemGroup<'b>,
+    items: &[usize],
+) -> Result<Vec<Candidate<'b>>, SolverError> {
+    let mut candidates = Vec::new();
+
+    'tiers: for tier in promotion.tiers() {
+        let mut bundle = Bundle::new();
+        let mut discounted: SmallVec<[usize; 10]> = SmallVec::new();
+
+        for &item_idx in items {
+            let item = item_group.get_item(item_idx)?;
+            let contributes = tier.contribution_qualification().matches(item.tags());
+            let discountable = tier.discount_qualification().matches(item.tags());
+
+            if !contributes && !discountable {
+                continue 'tiers;
+            }
+
+            if discountable {
+                discounted.push(bundle.len());
+            }
+
+            bundle.push((item_idx, item.price().to_minor_units()));
+        }
+
+        let discounted_items: SmallVec<[usize; 10]> = discounted
+            .iter()
+            .filter_map(|&position| bundle.get(position).map(|&(item_idx, _)| item_idx))
+            .collect();
+
+        let finals = discounted_prices(tier, item_group, &discounted_items)?;
+
+        for (&position, final_minor) in discounted.iter().zip(finals) {
+            if let Some((_, price)) = bundle.get_mut(position) {
+                *price = final_minor;
+            }
+        }
+
+        candidates.push(candidate(promotion.key(), item_group, &[bundle])?);
+    }
+
+    Ok(candidates)
+}
+
+/// Final prices of the items a tier discounts.
+fn discounted_prices(
+    tier: &ThresholdTier<'_>,
+    item_group: &ItemGroup<'_>,
+    items: &[usize],
+) -> Result<SmallVec<[i64; 10]>, SolverError> {
+    let originals = original_prices(item_group, items)?;
+
+    let prices = match tier.discount() {
+        ThresholdDiscount::PercentEachItem(_)
+        | ThresholdDiscount::AmountOffEachItem(_)
+        | ThresholdDiscount::FixedPriceEachItem(_) => items
+            .iter()
+            .map(|&item_idx| {
+                let item = item_group.get_item(item_idx)?;
+
+                Ok(
+                    TieredThresholdPromotion::calculate_discounted_price(tier, item)?
+                        .to_minor_units(),
+                )
+            })
+            .collect::<Result<_, SolverError>>()?,
+        ThresholdDiscount::AmountOffTotal(amount) => {
+            let original: i64 = originals.iter().sum();
+
+            allocate_total(
+                original.saturating_sub(amount.to_minor_units()).max(0),
+                &originals,
+            )
+        }
+        ThresholdDiscount::FixedTotal(amount) => {
+            allocate_total(amount.to_minor_units().max(0), &originals)
+        }
+        ThresholdDiscount::PercentCheapest(pct) => cheapest_discounted(&originals, |original| {
+            Ok(original
+                .saturating_sub(percent_of_minor(pct, original)?)
+                .max(0))
+        })?,
+        ThresholdDiscount::FixedCheapest(amount) => {
+            cheapest_discounted(&originals, |_original| Ok(amount.to_minor_units().max(0)))?
+        }
+    };
+
+    Ok(prices)
+}
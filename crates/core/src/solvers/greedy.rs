@@ -0,0 +1,641 @@
+//! Greedy Solver
+//!
+//! A heuristic that applies promotions one at a time instead of choosing between
+//! all of them at once. Each step solves a single promotion's own ILP formulation
+//! over the items still available, so promotion rules are exactly those used by
+//! [`ILPSolver`], but the combined result is not guaranteed to be optimal.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use rusty_money::Money;
+use smallvec::SmallVec;
+
+use crate::{
+    items::{Item, groups::ItemGroup},
+    promotions::{Promotion, redemptions::PromotionRedemption},
+    solvers::{
+        Solver, SolverError, SolverResult,
+        ilp::{ILPPromotion, ILPSolver, ILPSolverConfig, NoopObserver},
+    },
+};
+
+/// Solver that applies promotions greedily, in descending order of saving.
+///
+/// Starting from a full-price basket, it repeatedly applies whichever promotion
+/// saves the most on the items not yet claimed. It then improves the result by
+/// swaps: each applied promotion is re-solved together with one competing
+/// promotion over the items they could share, and the pair's joint assignment is
+/// kept when it saves more.
+///
+/// Results are only marked [`optimal`](SolverResult::optimal) when at most one
+/// promotion applies to the basket and its sub-problem was solved in full.
+#[derive(Debug)]
+pub struct GreedySolver;
+
+impl GreedySolver {
+    /// Solve greedily, handing each promotion's sub-problem to the configured backend.
+    ///
+    /// A time limit in `config` bounds the whole search: each sub-problem may only
+    /// use the time left, and once it runs out the promotions applied so far are
+    /// kept and every other item stays at full price.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if any sub-problem fails to solve.
+    pub fn solve_with_config<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        let promotion_refs: SmallVec<[&dyn ILPPromotion; 5]> =
+            promotions.iter().map(AsRef::as_ref).collect();
+
+        let deadline = config.time_limit().map(|limit| Instant::now() + limit);
+
+        solve_greedy(config, &promotion_refs, item_group, true, deadline)
+    }
+
+    /// Solve both greedily and with [`ILPSolver`] to measure the heuristic's gap.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if either solver fails.
+    pub fn solve_with_gap<'b>(
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+    ) -> Result<OptimalityGap<'b>, SolverError> {
+        Ok(OptimalityGap {
+            greedy: Self::solve(promotions, item_group)?,
+            optimal: ILPSolver::solve(promotions, item_group)?,
+        })
+    }
+}
+
+impl Solver for GreedySolver {
+    fn solve<'b>(
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        Self::solve_with_config(&ILPSolverConfig::default(), promotions, item_group)
+    }
+}
+
+/// A greedy result alongside the optimal result for the same basket.
+#[derive(Debug, Clone)]
+pub struct OptimalityGap<'a> {
+    /// Result from [`GreedySolver`]
+    pub greedy: SolverResult<'a>,
+
+    /// Result from [`ILPSolver`]
+    pub optimal: SolverResult<'a>,
+}
+
+impl OptimalityGap<'_> {
+    /// How much more the greedy total is than the optimal total, in minor units.
+    #[must_use]
+    pub fn gap_minor(&self) -> i64 {
+        self.greedy
+            .total
+            .to_minor_units()
+            .saturating_sub(self.optimal.total.to_minor_units())
+    }
+}
+
+/// Promotions applied together to a set of items.
+#[derive(Debug)]
+struct Commitment<'b> {
+    /// Positions of the promotions in the promotion list
+    promotions: SmallVec<[usize; 2]>,
+
+    /// Redemptions, indexed against the full item group
+    redemptions: SmallVec<[PromotionRedemption<'b>; 10]>,
+
+    /// Saving against full price, in minor units
+    saving: i64,
+}
+
+impl Commitment<'_> {
+    fn claims_any(&self, claimed: &[bool]) -> bool {
+        self.redemptions
+            .iter()
+            .any(|redemption| claimed.get(redemption.item_idx).copied().unwrap_or(false))
+    }
+
+    fn claim(&self, claimed: &mut [bool], value: bool) {
+        for redemption in &self.redemptions {
+            if let Some(flag) = claimed.get_mut(redemption.item_idx) {
+                *flag = value;
+            }
+        }
+    }
+}
+
+/// Greedy search shared by [`GreedySolver`] and the ILP solver's time-limit fallback.
+///
+/// With `improve` unset only the construction phase runs. No sub-problem starts
+/// after `deadline`, and those running when it passes are cut short.
+///
+/// # Errors
+///
+/// Returns [`SolverError`] if any sub-problem fails to solve.
+pub(crate) fn solve_greedy<'b>(
+    config: &ILPSolverConfig,
+    promotions: &[&dyn ILPPromotion],
+    item_group: &ItemGroup<'b>,
+    improve: bool,
+    deadline: Option<Instant>,
+) -> Result<SolverResult<'b>, SolverError> {
+    let search = GreedySearch {
+        config: ILPSolverConfig::new()
+            .with_backend(config.backend())
+            .with_fallback_reserve(Duration::ZERO),
+        deadline,
+        interrupted: Cell::new(false),
+        promotions,
+        item_group,
+    };
+
+    let applicable: SmallVec<[usize; 5]> = promotions
+        .iter()
+        .enumerate()
+        .filter(|(_, promotion)| promotion.is_applicable(item_group))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    let mut claimed: SmallVec<[bool; 10]> = SmallVec::from_elem(false, item_group.len());
+    let mut commitments: Vec<Commitment<'b>> = Vec::new();
+
+    loop {
+        search.construct(&applicable, &mut claimed, &mut commitments)?;
+
+        if !improve || !search.swap(&applicable, &mut claimed, &mut commitments)? {
+            break;
+        }
+    }
+
+    let optimal = applicable.len() <= 1 && !search.interrupted.get();
+
+    search.build_result(&commitments, &claimed, optimal)
+}
+
+struct GreedySearch<'s, 'a, 'b> {
+    /// Configuration for each sub-problem, without a time limit. A sub-problem
+    /// cut short keeps no reserve, as this search is itself the fallback.
+    config: ILPSolverConfig,
+
+    /// When the search stops with the promotions applied so far
+    deadline: Option<Instant>,
+
+    /// Whether the deadline skipped a sub-problem or cut one short
+    interrupted: Cell<bool>,
+
+    /// All promotions being solved
+    promotions: &'s [&'a dyn ILPPromotion],
+
+    /// The full item group
+    item_group: &'s ItemGroup<'b>,
+}
+
+impl<'b> GreedySearch<'_, '_, 'b> {
+    /// Apply the best remaining promotion to unclaimed items until none saves anything.
+    ///
+    /// A promotion's best application only changes when another promotion claims
+    /// one of its items, so the others are reused from the previous round. Once
+    /// the deadline passes, promotions without a valid application are dropped.
+    fn construct(
+        &self,
+        applicable: &[usize],
+        claimed: &mut [bool],
+        commitments: &mut Vec<Commitment<'b>>,
+    ) -> Result<(), SolverError> {
+        let mut candidates: Vec<(usize, Commitment<'b>)> = Vec::new();
+
+        for &idx in applicable {
+            if commitments
+                .iter()
+                .any(|commitment| commitment.promotions.contains(&idx))
+            {
+                continue;
+            }
+
+            let Some(candidate) = self.solve_subset(&[idx], claimed)? else {
+                break;
+            };
+
+            candidates.push((idx, candidate));
+        }
+
+        loop {
+            let best = candidates
+                .iter()
+                .enumerate()
+                .map(|(pos, (_, candidate))| (pos, candidate.saving))
+                .filter(|&(_, saving)| saving > 0)
+                .fold(
+                    None,
+                    |best: Option<(usize, i64)>, (pos, saving)| match best {
+                        Some((_, best_saving)) if best_saving >= saving => best,
+                        _ => Some((pos, saving)),
+                    },
+                );
+
+            let Some((pos, _)) = best else {
+                return Ok(());
+            };
+
+            let (_, commitment) = candidates.remove(pos);
+
+            commitment.claim(claimed, true);
+
+            let mut still_valid = Vec::with_capacity(candidates.len());
+
+            for (idx, candidate) in candidates {
+                if !candidate.claims_any(claimed) {
+                    still_valid.push((idx, candidate));
+                } else if let Some(candidate) = self.solve_subset(&[idx], claimed)? {
+                    still_valid.push((idx, candidate));
+                }
+            }
+
+            candidates = still_valid;
+            commitments.push(commitment);
+        }
+    }
+
+    /// Find one swap that increases the total saving and apply it.
+    ///
+    /// Returns whether a swap was made.
+    fn swap(
+        &self,
+        applicable: &[usize],
+        claimed: &mut [bool],
+        commitments: &mut Vec<Commitment<'b>>,
+    ) -> Result<bool, SolverError> {
+        for current in 0..commitments.len() {
+            for &idx in applicable {
+                let Some(commitment) = commitments.get(current) else {
+                    continue;
+                };
+
+                // Releasing this commitment's items can only help a promotion that
+                // could use some of them.
+                if commitment.promotions.contains(&idx)
+                    || !self.qualifies_for_any(idx, commitment)?
+                {
+                    continue;
+                }
+
+                let rival = commitments
+                    .iter()
+                    .position(|commitment| commitment.promotions.contains(&idx));
+
+                let mut released: SmallVec<[usize; 2]> = SmallVec::from_elem(current, 1);
+                released.extend(rival);
+
+                let mut available = SmallVec::<[bool; 10]>::from(&*claimed);
+                let mut group: SmallVec<[usize; 4]> = SmallVec::new();
+                let mut saving: i64 = 0;
+
+                for &pos in &released {
+                    if let Some(commitment) = commitments.get(pos) {
+                        commitment.claim(&mut available, false);
+                        group.extend(commitment.promotions.iter().copied());
+                        saving = saving.saturating_add(commitment.saving);
+                    }
+                }
+
+                if rival.is_none() {
+                    group.push(idx);
+                }
+
+                let Some(candidate) = self.solve_subset(&group, &available)? else {
+                    return Ok(false);
+                };
+
+                if candidate.saving <= saving {
+                    continue;
+                }
+
+                released.sort_unstable();
+
+                for &pos in released.iter().rev() {
+                    commitments.remove(pos).claim(claimed, false);
+                }
+
+                candidate.claim(claimed, true);
+                commitments.push(candidate);
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Whether the promotion at `promotion_position` applies to any of the
+    /// commitment's items.
+    fn qualifies_for_any(
+        &self,
+        promotion_position: usize,
+        commitment: &Commitment<'b>,
+    ) -> Result<bool, SolverError> {
+        let Some(promotion) = self.promotions.get(promotion_position) else {
+            return Ok(false);
+        };
+
+        let mut items: SmallVec<[Item<'b>; 10]> = SmallVec::new();
+
+        for redemption in &commitment.redemptions {
+            items.push(self.item_group.get_item(redemption.item_idx)?.clone());
+        }
+
+        Ok(promotion.is_applicable(&ItemGroup::new(items, self.item_group.currency())))
+    }
+
+    /// Solve the given promotions together over the unclaimed items.
+    ///
+    /// Returns `None` without solving once the deadline has passed.
+    fn solve_subset(
+        &self,
+        promotion_positions: &[usize],
+        claimed: &[bool],
+    ) -> Result<Option<Commitment<'b>>, SolverError> {
+        let limited;
+        let config = match self.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                if remaining.is_zero() {
+                    self.interrupted.set(true);
+
+                    return Ok(None);
+                }
+
+                limited = self.config.clone().with_time_limit(remaining);
+                &limited
+            }
+            None => &self.config,
+        };
+
+        let mut item_positions: SmallVec<[usize; 10]> = SmallVec::new();
+        let mut items: SmallVec<[Item<'b>; 10]> = SmallVec::new();
+        let mut full_price_minor: i64 = 0;
+
+        for idx in 0..self.item_group.len() {
+            if claimed.get(idx).copied().unwrap_or(true) {
+                continue;
+            }
+
+            let item = self.item_group.get_item(idx)?;
+
+            full_price_minor = full_price_minor.saturating_add(item.price().to_minor_units());
+            item_positions.push(idx);
+            items.push(item.clone());
+        }
+
+        let promotions: SmallVec<[&dyn ILPPromotion; 4]> = promotion_positions
+            .iter()
+            .filter_map(|&pos| self.promotions.get(pos).copied())
+            .collect();
+
+        let sub_group = ItemGroup::new(items, self.item_group.currency());
+        let result = ILPSolver::solve_internal(config, &promotions, &sub_group, &mut NoopObserver)?;
+
+        if !result.optimal {
+            self.interrupted.set(true);
+        }
+
+        let mut redemptions = result.promotion_redemptions;
+
+        for redemption in &mut redemptions {
+            redemption.item_idx = item_positions.get(redemption.item_idx).copied().ok_or(
+                SolverError::InvariantViolation {
+                    message: "greedy sub-problem returned an unknown item",
+                },
+            )?;
+        }
+
+        Ok(Some(Commitment {
+            promotions: promotion_positions.iter().copied().collect(),
+            redemptions,
+            saving: full_price_minor.saturating_sub(result.total.to_minor_units()),
+        }))
+    }
+
+    /// Combine the commitments into a single result.
+    fn build_result(
+        &self,
+        commitments: &[Commitment<'b>],
+        claimed: &[bool],
+        optimal: bool,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        let currency = self.item_group.currency();
+        let mut total = Money::from_minor(0, currency);
+        let mut affected_items: SmallVec<[usize; 10]> = SmallVec::new();
+        let mut unaffected_items: SmallVec<[usize; 10]> = SmallVec::new();
+        let mut promotion_redemptions: SmallVec<[PromotionRedemption<'b>; 10]> = SmallVec::new();
+        let mut next_redemption_idx: usize = 0;
+
+        // Redemption indexes are only unique within a sub-problem, so offset each
+        // commitment's indexes past those already used.
+        for commitment in commitments {
+            let offset = next_redemption_idx;
+
+            for redemption in &commitment.redemptions {
+                let redemption_idx = redemption.redemption_idx.saturating_add(offset);
+
+                next_redemption_idx = next_redemption_idx.max(redemption_idx.saturating_add(1));
+                total = total.add(redemption.final_price)?;
+                affected_items.push(redemption.item_idx);
+
+                promotion_redemptions.push(PromotionRedemption {
+                    redemption_idx,
+                    ..redemption.clone()
+                });
+            }
+        }
+
+        for (idx, item) in self.item_group.iter().enumerate() {
+            if claimed.get(idx).copied().unwrap_or(false) {
+                continue;
+            }
+
+            total = total.add(Money::from_minor(item.price().to_minor_units(), currency))?;
+            unaffected_items.push(idx);
+        }
+
+        Ok(SolverResult {
+            affected_items,
+            unaffected_items,
+            total,
+            promotion_redemptions,
+            optimal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use decimal_percentage::Percentage;
+    use rusty_money::iso::GBP;
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        products::ProductKey,
+        promotions::{
+            PromotionKey,
+            budget::PromotionBudget,
+            promotion,
+            qualification::Qualification,
+            types::{DirectDiscountPromotion, PositionalDiscountPromotion},
+        },
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    fn tagged_item(price_minor: i64, tag: &str) -> Item<'static> {
+        Item::with_tags(
+            ProductKey::default(),
+            Money::from_minor(price_minor, GBP),
+            StringTagCollection::from_strs(&[tag]),
+        )
+    }
+
+    fn percent_off(key: PromotionKey, tag: &str, pct: f64) -> Promotion<'static> {
+        promotion(DirectDiscountPromotion::new(
+            key,
+            Qualification::match_any(StringTagCollection::from_strs(&[tag])),
+            SimpleDiscount::PercentageOff(Percentage::from(pct)),
+            PromotionBudget::unlimited(),
+        ))
+    }
+
+    /// Any two of the tagged items, with the cheaper one discounted by `pct`.
+    fn pair_deal(key: PromotionKey, tags: &[&str], pct: f64) -> Promotion<'static> {
+        promotion(PositionalDiscountPromotion::new(
+            key,
+            Qualification::match_any(StringTagCollection::from_strs(tags)),
+            2,
+            smallvec![1],
+            SimpleDiscount::PercentageOff(Percentage::from(pct)),
+            PromotionBudget::unlimited(),
+        ))
+    }
+
+    #[test]
+    fn single_promotion_matches_ilp_and_is_optimal() -> TestResult {
+        let item_group = ItemGroup::new(
+            smallvec![
+                tagged_item(100, "a"),
+                tagged_item(200, "a"),
+                tagged_item(300, "b")
+            ],
+            GBP,
+        );
+
+        let promotions = [percent_off(PromotionKey::default(), "a", 0.5)];
+
+        let greedy = GreedySolver::solve(&promotions, &item_group)?;
+        let optimal = ILPSolver::solve(&promotions, &item_group)?;
+
+        assert_eq!(greedy.total, optimal.total);
+        assert_eq!(greedy.total.to_minor_units(), 450);
+        assert_eq!(greedy.unaffected_items.as_slice(), &[2]);
+        assert!(greedy.optimal);
+
+        Ok(())
+    }
+
+    #[test]
+    fn swaps_recover_items_claimed_by_the_largest_saving() -> TestResult {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        // The pair deal saves most on its own by taking both £1 items, but then the
+        // half-price offer on `a` has nothing left. Letting the half-price offer take
+        // `a` and the pair deal take `b` and `c` saves more overall.
+        let item_group = ItemGroup::new(
+            smallvec![
+                tagged_item(100, "a"),
+                tagged_item(100, "b"),
+                tagged_item(60, "c")
+            ],
+            GBP,
+        );
+
+        let promotions = [
+            pair_deal(keys.insert(()), &["a", "b", "c"], 1.0),
+            percent_off(keys.insert(()), "a", 0.5),
+        ];
+
+        let config = ILPSolverConfig::default();
+        let refs: SmallVec<[&dyn ILPPromotion; 5]> = promotions.iter().map(AsRef::as_ref).collect();
+
+        let constructed = solve_greedy(&config, &refs, &item_group, false, None)?;
+        let improved = GreedySolver::solve(&promotions, &item_group)?;
+
+        assert_eq!(constructed.total.to_minor_units(), 160);
+        assert_eq!(improved.total.to_minor_units(), 150);
+        assert!(!improved.optimal);
+
+        let mut redemption_idxs: Vec<usize> = improved
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| redemption.redemption_idx)
+            .collect();
+
+        redemption_idxs.sort_unstable();
+        redemption_idxs.dedup();
+
+        assert_eq!(redemption_idxs.len(), 2, "one bundle per promotion");
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_limit_leaves_untried_promotions_unapplied() -> TestResult {
+        let item_group = ItemGroup::new(smallvec![tagged_item(100, "a")], GBP);
+        let promotions = [percent_off(PromotionKey::default(), "a", 0.5)];
+
+        let config = ILPSolverConfig::new().with_time_limit(Duration::ZERO);
+        let result = GreedySolver::solve_with_config(&config, &promotions, &item_group)?;
+
+        assert_eq!(result.total.to_minor_units(), 100);
+        assert_eq!(result.unaffected_items.as_slice(), &[0]);
+        assert!(!result.optimal, "a promotion was never tried");
+
+        Ok(())
+    }
+
+    #[test]
+    fn solve_with_gap_reports_distance_from_optimal() -> TestResult {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        // Greedy takes the 90p pair deal first; half price on each item separately
+        // saves £1, and no single swap gets there.
+        let item_group =
+            ItemGroup::new(smallvec![tagged_item(100, "a"), tagged_item(100, "b")], GBP);
+
+        let promotions = [
+            pair_deal(keys.insert(()), &["a", "b"], 0.9),
+            percent_off(keys.insert(()), "a", 0.5),
+            percent_off(keys.insert(()), "b", 0.5),
+        ];
+
+        let gap = GreedySolver::solve_with_gap(&promotions, &item_group)?;
+
+        assert_eq!(gap.greedy.total.to_minor_units(), 110);
+        assert_eq!(gap.optimal.total.to_minor_units(), 100);
+        assert_eq!(gap.gap_minor(), 10);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,485 @@
+//! Alternative Solutions
+//!
+//! Enumerates the best few distinct redemption assignments for a basket, so the
+//! chosen arrangement can be compared with the runners-up. After each solve a
+//! no-good cut forbids the assignment just found, and the model is solved again
+//! for the best remaining one.
+//!
+//! An assignment is the option each item is bought through: full price, or one
+//! of the promotions. Identical units are interchangeable, so an assignment is
+//! only told apart by how many units of each kind take each option, and a cut
+//! requires at least one of those counts to fall. Arrangements that differ only
+//! in which identical units take an option, or in how the same items are grouped
+//! into bundles of the same promotions, count as one assignment.
+
+use good_lp::{
+    Expression, ProblemVariables, ResolutionError, Solution, SolverModel, Variable, WithTimeLimit,
+    solvers::Solver as MILPSolver, variable,
+};
+use rusty_money::Money;
+use smallvec::SmallVec;
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::Promotion,
+    solvers::{
+        SolverError, SolverResult,
+        ilp::{
+            BuiltILPFormulation, FeasibilityCheck, ILPBackend, ILPPromotion, ILPSolver,
+            ILPSolverConfig, NoopObserver, SolveOutcome, apply_exclusivity_constraints,
+            apply_recorded_constraints, best_known_result, build_ilp_formulation,
+            build_solver_result, deadlines, ensure_presence_vars_len, greedy_fallback, has_passed,
+            promotions::PromotionInstances, remaining_seconds, solve_model,
+        },
+    },
+};
+
+/// The option each item was bought through: the position of the promotion it
+/// participates in, or `None` at full price.
+type Assignment = SmallVec<[Option<usize>; 10]>;
+
+impl ILPSolver {
+    /// Solve for up to `k` distinct redemption assignments, cheapest first.
+    ///
+    /// The first result has the optimal total, though its redemptions may differ
+    /// from [`solve_with_config`](Self::solve_with_config) among equally cheap
+    /// arrangements, as promotions' tie-break preferences are not applied. Fewer
+    /// than `k` results are returned when the basket has fewer distinct
+    /// assignments.
+    ///
+    /// Identical units are not told apart, so swapping them between promotions
+    /// does not give a new assignment. The basket is solved as a whole, ignoring
+    /// [decomposition](ILPSolverConfig::with_decomposition).
+    ///
+    /// A configured time limit is a budget for the whole enumeration. If it runs
+    /// out, the results found so far are returned, including the best known
+    /// solution of the interrupted solve marked as not
+    /// [`optimal`](SolverResult::optimal).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the solver encounters an error.
+    pub fn solve_alternatives<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+        k: usize,
+    ) -> Result<Vec<SolverResult<'b>>, SolverError> {
+        let promotion_refs: SmallVec<[&dyn ILPPromotion; 5]> =
+            promotions.iter().map(AsRef::as_ref).collect();
+
+        match config.backend() {
+            #[cfg(feature = "solver-microlp")]
+            ILPBackend::Microlp => solve_alternatives_using(
+                good_lp::solvers::microlp::microlp,
+                config,
+                &promotion_refs,
+                item_group,
+                k,
+            ),
+            #[cfg(feature = "solver-lpsolve")]
+            ILPBackend::LpSolve => solve_alternatives_using(
+                good_lp::solvers::lpsolve::lp_solve,
+                config,
+                &promotion_refs,
+                item_group,
+                k,
+            ),
+        }
+    }
+}
+
+/// Enumerate assignments with the given backend, cutting off each one found.
+fn solve_alternatives_using<'b, B>(
+    backend: B,
+    config: &ILPSolverConfig,
+    promotions: &[&dyn ILPPromotion],
+    item_group: &ItemGroup<'b>,
+    k: usize,
+) -> Result<Vec<SolverResult<'b>>, SolverError>
+where
+    B: MILPSolver + Copy,
+    B::Model: SolverModel<Error = ResolutionError> + WithTimeLimit,
+{
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+
+    // An empty basket has exactly one assignment.
+    if item_group.is_empty() {
+        return Ok(vec![SolverResult {
+            affected_items: SmallVec::new(),
+            unaffected_items: SmallVec::new(),
+            total: Money::from_minor(0, item_group.currency()),
+            promotion_redemptions: SmallVec::new(),
+            optimal: true,
+        }]);
+    }
+
+    let classes = unit_classes(item_group);
+    let (search_deadline, deadline) = deadlines(config);
+    let mut results = Vec::new();
+    let mut cuts: Vec<Assignment> = Vec::new();
+
+    while results.len() < k {
+        if has_passed(search_deadline) {
+            if results.is_empty() {
+                results.push(greedy_fallback(config, promotions, item_group, deadline)?);
+            }
+
+            break;
+        }
+
+        let mut observer = NoopObserver;
+        let BuiltILPFormulation {
+            mut pb,
+            cost,
+            item_presence,
+            constraints,
+            promotion_instances,
+        } = build_ilp_formulation(promotions, item_group, &mut observer)?;
+
+        let mut cut_constraints = Vec::new();
+
+        for cut in &cuts {
+            cut_constraints.extend(assignment_cut(
+                cut,
+                &classes,
+                &promotion_instances,
+                &item_presence,
+                &mut pb,
+            )?);
+        }
+
+        let mut feasibility = deadline.map(|_| FeasibilityCheck::new(&pb, &constraints));
+        let mut model = pb.minimise(cost).using(backend);
+
+        if let Some(search_deadline) = search_deadline {
+            model = model.with_time_limit(remaining_seconds(search_deadline));
+        }
+
+        ensure_presence_vars_len(item_presence.len(), item_group.len())?;
+
+        model = apply_exclusivity_constraints(
+            model,
+            &promotion_instances,
+            item_group,
+            &item_presence,
+            &mut observer,
+            feasibility.as_mut(),
+        )?;
+        model = apply_recorded_constraints(model, constraints);
+
+        for (expr, bound) in cut_constraints {
+            if let Some(feasibility) = feasibility.as_mut() {
+                feasibility.require_leq(expr.clone(), bound);
+            }
+
+            model = model.with(expr.leq(bound));
+        }
+
+        let solution = match solve_model(model, search_deadline) {
+            Ok(SolveOutcome::Finished(solution)) => solution,
+            Ok(SolveOutcome::TimedOut(solution)) => {
+                if let Some(result) = best_known_result(
+                    feasibility.as_ref(),
+                    &promotion_instances,
+                    solution.as_ref(),
+                    item_group,
+                    &item_presence,
+                )? {
+                    results.push(result);
+                } else if results.is_empty() {
+                    results.push(greedy_fallback(config, promotions, item_group, deadline)?);
+                }
+
+                break;
+            }
+            // Every assignment has been found.
+            Err(ResolutionError::Infeasible) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        cuts.push(solved_assignment(
+            &promotion_instances,
+            &solution,
+            item_presence.len(),
+        ));
+        results.push(build_solver_result(
+            &promotion_instances,
+            &solution,
+            item_group,
+            &item_presence,
+        )?);
+    }
+
+    Ok(results)
+}
+
+/// Read which option each item was bought through.
+fn solved_assignment(
+    promotion_instances: &PromotionInstances<'_>,
+    solution: &dyn Solution,
+    item_count: usize,
+) -> Assignment {
+    (0..item_count)
+        .map(|item_idx| {
+            promotion_instances
+                .iter()
+                .position(|instance| instance.is_item_participating(solution, item_idx))
+        })
+        .collect()
+}
+
+/// The first item each item is identical to, so identical units share a class.
+fn unit_classes(item_group: &ItemGroup<'_>) -> SmallVec<[usize; 10]> {
+    item_group
+        .iter()
+        .enumerate()
+        .map(|(item_idx, item)| {
+            item_group
+                .iter()
+                .position(|other| other == item)
+                .unwrap_or(item_idx)
+        })
+        .collect()
+}
+
+/// No-good cut, as constraints `lhs <= rhs`, forbidding `assignment` and every
+/// assignment that takes each option with as many units of each class.
+///
+/// Each class has a fixed number of units, so an assignment is new exactly when
+/// some class takes one of its options fewer times. A binary per class and option
+/// picks a count that must fall, and at least one is picked.
+fn assignment_cut(
+    assignment: &Assignment,
+    classes: &[usize],
+    promotion_instances: &PromotionInstances<'_>,
+    item_presence: &[Variable],
+    pb: &mut ProblemVariables,
+) -> Result<Vec<(Expression, f64)>, SolverError> {
+    let mut counts: Vec<((usize, Option<usize>), usize)> = Vec::new();
+
+    for (&class, &option) in classes.iter().zip(assignment) {
+        match counts.iter_mut().find(|(key, _)| *key == (class, option)) {
+            Some((_, count)) => *count += 1,
+            None => counts.push(((class, option), 1)),
+        }
+    }
+
+    let mut constraints = Vec::with_capacity(counts.len() + 1);
+    let mut any_fall = Expression::default();
+
+    for ((class, option), count) in counts {
+        let class_size = classes.iter().filter(|&&other| other == class).count();
+        let size = count_coefficient(class_size)?;
+        let falls = pb.add(variable().binary());
+
+        let mut expr = Expression::default();
+
+        for item_idx in (0..classes.len()).filter(|&idx| classes.get(idx) == Some(&class)) {
+            expr = option_term(expr, option, item_idx, promotion_instances, item_presence);
+        }
+
+        // count <= solved count - 1 when it falls, otherwise at most the class size.
+        constraints.push((expr + size * falls, count_coefficient(count)? - 1.0 + size));
+        any_fall -= falls;
+    }
+
+    constraints.push((any_fall, -1.0));
+
+    Ok(constraints)
+}
+
+/// Add the variable for item `item_idx` taking `option` to `expr`.
+fn option_term(
+    expr: Expression,
+    option: Option<usize>,
+    item_idx: usize,
+    promotion_instances: &PromotionInstances<'_>,
+    item_presence: &[Variable],
+) -> Expression {
+    match option.and_then(|pos| promotion_instances.iter().nth(pos)) {
+        Some(instance) => instance.add_item_presence_term(expr, item_idx),
+        None => match item_presence.get(item_idx) {
+            Some(&z_i) => expr + z_i,
+            None => expr,
+        },
+    }
+}
+
+/// A count of items as a constraint coefficient.
+fn count_coefficient(count: usize) -> Result<f64, SolverError> {
+    u32::try_from(count)
+        .map(f64::from)
+        .map_err(|_err| SolverError::InvariantViolation {
+            message: "item count does not fit a constraint bound",
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use decimal_percentage::Percentage;
+    use rusty_money::iso::GBP;
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        items::Item,
+        products::ProductKey,
+        promotions::{
+            PromotionKey,
+            budget::PromotionBudget,
+            promotion,
+            qualification::Qualification,
+            types::{DirectDiscountPromotion, PositionalDiscountPromotion},
+        },
+        solvers::{Solver, ilp::ILPSolver},
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    fn tagged_item<'a>(price: i64, tags: &[&str]) -> Item<'a> {
+        Item::with_tags(
+            ProductKey::default(),
+            Money::from_minor(price, GBP),
+            StringTagCollection::from_strs(tags),
+        )
+    }
+
+    /// 3-for-2 on snacks and 10% off anything tagged "sale".
+    fn promotions() -> Vec<Promotion<'static>> {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        vec![
+            promotion(PositionalDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["snack"])),
+                3,
+                smallvec![2],
+                SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+                PromotionBudget::unlimited(),
+            )),
+            promotion(DirectDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["sale"])),
+                SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+                PromotionBudget::unlimited(),
+            )),
+        ]
+    }
+
+    #[test]
+    fn alternatives_are_distinct_and_cheapest_first() -> TestResult {
+        let promotions = promotions();
+        let item_group = ItemGroup::new(
+            smallvec![
+                tagged_item(300, &["snack", "sale"]),
+                tagged_item(300, &["snack"]),
+                tagged_item(300, &["snack"]),
+            ],
+            GBP,
+        );
+
+        let alternatives = ILPSolver::solve_alternatives(
+            &ILPSolverConfig::default(),
+            &promotions,
+            &item_group,
+            3,
+        )?;
+
+        let totals: Vec<i64> = alternatives
+            .iter()
+            .map(|result| result.total.to_minor_units())
+            .collect();
+
+        // 3-for-2 on all three, then 10% off the sale snack alone, then nothing.
+        assert_eq!(totals, vec![600, 870, 900]);
+        assert_eq!(
+            totals.first().copied(),
+            Some(
+                ILPSolver::solve(&promotions, &item_group)?
+                    .total
+                    .to_minor_units()
+            )
+        );
+        assert!(alternatives.iter().all(|result| result.optimal));
+
+        Ok(())
+    }
+
+    #[test]
+    fn identical_units_are_counted_not_told_apart() -> TestResult {
+        let promotions = promotions();
+        let item_group = ItemGroup::new(
+            smallvec![
+                tagged_item(300, &["snack", "sale"]),
+                tagged_item(300, &["snack", "sale"]),
+                tagged_item(300, &["snack", "sale"]),
+            ],
+            GBP,
+        );
+
+        let alternatives = ILPSolver::solve_alternatives(
+            &ILPSolverConfig::default(),
+            &promotions,
+            &item_group,
+            10,
+        )?;
+
+        let totals: Vec<i64> = alternatives
+            .iter()
+            .map(|result| result.total.to_minor_units())
+            .collect();
+
+        // 3-for-2 on all three, then 10% off three, two or one of them, then
+        // nothing. Which units take the 10% off does not matter.
+        assert_eq!(totals, vec![600, 810, 840, 870, 900]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn enumeration_stops_when_assignments_run_out() -> TestResult {
+        let promotions = promotions();
+        let item_group = ItemGroup::new(smallvec![tagged_item(500, &["sale"])], GBP);
+
+        let alternatives = ILPSolver::solve_alternatives(
+            &ILPSolverConfig::default().with_time_limit(Duration::from_secs(10)),
+            &promotions,
+            &item_group,
+            5,
+        )?;
+
+        let totals: Vec<i64> = alternatives
+            .iter()
+            .map(|result| result.total.to_minor_units())
+            .collect();
+
+        assert_eq!(totals, vec![450, 500]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_alternatives_requested_returns_none() -> TestResult {
+        let promotions = promotions();
+        let item_group = ItemGroup::new(smallvec![tagged_item(500, &["sale"])], GBP);
+
+        let alternatives = ILPSolver::solve_alternatives(
+            &ILPSolverConfig::default(),
+            &promotions,
+            &item_group,
+            0,
+        )?;
+
+        assert!(alternatives.is_empty());
+
+        Ok(())
+    }
+}
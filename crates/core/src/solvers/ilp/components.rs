@@ -0,0 +1,454 @@
+//! Basket Decomposition
+//!
+//! Items only compete with each other through promotions they are both eligible
+//! for. Linking every item to the promotions it is eligible for splits a basket
+//! into connected components that share no promotion, so each component can be
+//! solved on its own and the results merged. The size of each model then
+//! follows the largest component rather than the whole basket.
+
+use rusty_money::Money;
+use smallvec::SmallVec;
+
+use crate::{
+    items::{Item, groups::ItemGroup},
+    promotions::{PromotionKey, redemptions::PromotionRedemption},
+    solvers::{SolverError, SolverResult, ilp::promotions::ILPPromotion},
+};
+
+/// Items connected through shared promotions, with the promotions linking them.
+#[derive(Debug)]
+pub(crate) struct Component<'p> {
+    /// Positions of the component's items in the original item group, ascending.
+    pub(crate) item_positions: SmallVec<[usize; 10]>,
+
+    /// Promotions eligible for at least one of the component's items.
+    pub(crate) promotions: SmallVec<[&'p dyn ILPPromotion; 5]>,
+}
+
+impl Component<'_> {
+    /// Build the component's own item group, in original item order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SolverError`] if a position is not in `item_group`.
+    pub(crate) fn item_group<'b>(
+        &self,
+        item_group: &ItemGroup<'b>,
+    ) -> Result<ItemGroup<'b>, SolverError> {
+        let items = self
+            .item_positions
+            .iter()
+            .map(|&idx| item_group.get_item(idx).cloned())
+            .collect::<Result<SmallVec<[Item<'b>; 10]>, _>>()?;
+
+        Ok(ItemGroup::new(items, item_group.currency()))
+    }
+
+    /// Map an index in the component's item group back to the original item group.
+    fn original_idx(&self, idx: usize) -> Result<usize, SolverError> {
+        self.item_positions
+            .get(idx)
+            .copied()
+            .ok_or(SolverError::InvariantViolation {
+                message: "component result refers to an item outside the component",
+            })
+    }
+}
+
+/// Optimal component results from an earlier solve of the same promotions.
+///
+/// A component is reused when it has the same promotions, by key, and the same
+/// items at the same prices, in order. Its result only refers to items by their
+/// index within the component, so it still holds wherever the component's items
+/// now sit in the item group.
+#[derive(Debug, Default)]
+pub(crate) struct ComponentCache<'b> {
+    solved: SmallVec<[SolvedComponent<'b>; 4]>,
+
+    /// Number of components reused by the latest solve
+    reused: usize,
+}
+
+/// A component's promotions and items, and the optimal result solved for them.
+#[derive(Debug)]
+struct SolvedComponent<'b> {
+    promotions: SmallVec<[PromotionKey; 5]>,
+    items: SmallVec<[Item<'b>; 10]>,
+    result: SolverResult<'b>,
+}
+
+impl<'b> ComponentCache<'b> {
+    /// Number of components reused by the latest solve.
+    pub(crate) fn reused(&self) -> usize {
+        self.reused
+    }
+
+    /// The cached result for `component`, whose own item group is `component_group`.
+    pub(crate) fn get(
+        &self,
+        component: &Component<'_>,
+        component_group: &ItemGroup<'b>,
+    ) -> Option<SolverResult<'b>> {
+        self.solved
+            .iter()
+            .find(|solved| {
+                solved
+                    .promotions
+                    .iter()
+                    .copied()
+                    .eq(component.promotions.iter().map(|promotion| promotion.key()))
+                    && solved.items.iter().eq(component_group.iter())
+            })
+            .map(|solved| solved.result.clone())
+    }
+
+    /// Replace the cache with the components of the latest solve, keeping only
+    /// optimal results, and record how many of them were reused.
+    pub(crate) fn replace(
+        &mut self,
+        components: impl IntoIterator<
+            Item = (SmallVec<[PromotionKey; 5]>, ItemGroup<'b>, SolverResult<'b>),
+        >,
+        reused: usize,
+    ) {
+        self.solved = components
+            .into_iter()
+            .filter(|(_, _, result)| result.optimal)
+            .map(|(promotions, component_group, result)| SolvedComponent {
+                promotions,
+                items: (0..component_group.len())
+                    .filter_map(|idx| component_group.get_item(idx).ok())
+                    .cloned()
+                    .collect(),
+                result,
+            })
+            .collect();
+        self.reused = reused;
+    }
+}
+
+/// A basket split into independent components.
+#[derive(Debug)]
+pub(crate) struct Decomposition<'p> {
+    /// Components that at least one promotion may apply to.
+    pub(crate) components: SmallVec<[Component<'p>; 4]>,
+
+    /// Positions of items no promotion is eligible for, ascending.
+    pub(crate) full_price_items: SmallVec<[usize; 10]>,
+}
+
+impl<'p> Decomposition<'p> {
+    /// Partition `item_group` by the promotions its items are eligible for.
+    ///
+    /// Promotions that are not applicable to the whole item group are dropped, as
+    /// they cannot apply to any part of it either.
+    pub(crate) fn new(promotions: &[&'p dyn ILPPromotion], item_group: &ItemGroup<'_>) -> Self {
+        let mut parents: SmallVec<[usize; 10]> = (0..item_group.len()).collect();
+        let mut eligible: SmallVec<[bool; 10]> = SmallVec::from_elem(false, item_group.len());
+
+        // Each applicable promotion is linked to the first item it is eligible for,
+        // with every other eligible item joined to that one.
+        let mut linked: SmallVec<[(&'p dyn ILPPromotion, usize); 5]> = SmallVec::new();
+
+        for &promotion in promotions {
+            if !promotion.is_applicable(item_group) {
+                continue;
+            }
+
+            let mut first = None;
+
+            for (idx, item) in item_group.iter().enumerate() {
+                if !promotion.is_item_eligible(item) {
+                    continue;
+                }
+
+                if let Some(flag) = eligible.get_mut(idx) {
+                    *flag = true;
+                }
+
+                match first {
+                    Some(first) => union(&mut parents, first, idx),
+                    None => first = Some(idx),
+                }
+            }
+
+            if let Some(first) = first {
+                linked.push((promotion, first));
+            }
+        }
+
+        let mut components: SmallVec<[Component<'p>; 4]> = SmallVec::new();
+        let mut component_roots: SmallVec<[usize; 4]> = SmallVec::new();
+        let mut full_price_items = SmallVec::new();
+
+        for idx in 0..item_group.len() {
+            if !eligible.get(idx).copied().unwrap_or(false) {
+                full_price_items.push(idx);
+
+                continue;
+            }
+
+            let root = find(&mut parents, idx);
+
+            if let Some(component) = component_roots
+                .iter()
+                .position(|&r| r == root)
+                .and_then(|pos| components.get_mut(pos))
+            {
+                component.item_positions.push(idx);
+            } else {
+                component_roots.push(root);
+                components.push(Component {
+                    item_positions: SmallVec::from_elem(idx, 1),
+                    promotions: SmallVec::new(),
+                });
+            }
+        }
+
+        for (promotion, first) in linked {
+            let root = find(&mut parents, first);
+
+            if let Some(component) = component_roots
+                .iter()
+                .position(|&r| r == root)
+                .and_then(|pos| components.get_mut(pos))
+            {
+                component.promotions.push(promotion);
+            }
+        }
+
+        Self {
+            components,
+            full_price_items,
+        }
+    }
+
+    /// Combine each component's result, in component order, into a result for
+    /// the whole item group.
+    ///
+    /// Item indexes are mapped back to the original item group, and redemption
+    /// indexes are offset so they stay unique across components. The result is
+    /// only optimal if every component's result is.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SolverError`] if a component result refers to an item outside
+    /// its component, or if the totals cannot be added.
+    pub(crate) fn merge<'b>(
+        &self,
+        item_group: &ItemGroup<'b>,
+        results: impl IntoIterator<Item = SolverResult<'b>>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        let currency = item_group.currency();
+        let mut merged = SolverResult {
+            affected_items: SmallVec::new(),
+            unaffected_items: SmallVec::new(),
+            total: Money::from_minor(0, currency),
+            promotion_redemptions: SmallVec::new(),
+            optimal: true,
+        };
+        let mut next_redemption_idx: usize = 0;
+
+        for (component, result) in self.components.iter().zip(results) {
+            let offset = next_redemption_idx;
+
+            for redemption in result.promotion_redemptions {
+                let redemption_idx = redemption.redemption_idx.saturating_add(offset);
+
+                next_redemption_idx = next_redemption_idx.max(redemption_idx.saturating_add(1));
+
+                merged.promotion_redemptions.push(PromotionRedemption {
+                    item_idx: component.original_idx(redemption.item_idx)?,
+                    redemption_idx,
+                    ..redemption
+                });
+            }
+
+            for idx in result.affected_items {
+                merged.affected_items.push(component.original_idx(idx)?);
+            }
+
+            for idx in result.unaffected_items {
+                merged.unaffected_items.push(component.original_idx(idx)?);
+            }
+
+            merged.total = merged.total.add(result.total)?;
+            merged.optimal &= result.optimal;
+        }
+
+        for &idx in &self.full_price_items {
+            let item = item_group.get_item(idx)?;
+
+            merged.total = merged
+                .total
+                .add(Money::from_minor(item.price().to_minor_units(), currency))?;
+            merged.unaffected_items.push(idx);
+        }
+
+        merged.unaffected_items.sort_unstable();
+
+        Ok(merged)
+    }
+}
+
+/// Find the representative of `idx`'s set, halving paths along the way.
+fn find(parents: &mut [usize], mut idx: usize) -> usize {
+    while let Some(&parent) = parents.get(idx)
+        && parent != idx
+    {
+        let grandparent = parents.get(parent).copied().unwrap_or(parent);
+
+        if let Some(slot) = parents.get_mut(idx) {
+            *slot = grandparent;
+        }
+
+        idx = grandparent;
+    }
+
+    idx
+}
+
+/// Merge the sets containing `a` and `b`, keeping the lower root.
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let a = find(parents, a);
+    let b = find(parents, b);
+
+    if let Some(slot) = parents.get_mut(a.max(b)) {
+        *slot = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::iso::GBP;
+    use smallvec::SmallVec;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        items::Item,
+        products::ProductKey,
+        promotions::{
+            PromotionKey, budget::PromotionBudget, qualification::Qualification,
+            types::DirectDiscountPromotion,
+        },
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    fn tagged_item<'a>(price: i64, tags: &[&str]) -> Item<'a> {
+        Item::with_tags(
+            ProductKey::default(),
+            Money::from_minor(price, GBP),
+            StringTagCollection::from_strs(tags),
+        )
+    }
+
+    fn percent_off(tags: &[&str]) -> DirectDiscountPromotion<'static> {
+        DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(tags)),
+            SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+            PromotionBudget::unlimited(),
+        )
+    }
+
+    #[test]
+    fn items_sharing_a_promotion_form_one_component() {
+        let items: SmallVec<[Item<'_>; 10]> = [
+            tagged_item(100, &["a"]),
+            tagged_item(200, &["b"]),
+            tagged_item(300, &["a", "c"]),
+            tagged_item(400, &["d"]),
+            tagged_item(500, &["c"]),
+        ]
+        .into_iter()
+        .collect();
+        let item_group = ItemGroup::new(items, GBP);
+
+        let promo_a = percent_off(&["a"]);
+        let promo_b = percent_off(&["b"]);
+        let promo_c = percent_off(&["c"]);
+        let promo_e = percent_off(&["e"]);
+        let promotions: [&dyn ILPPromotion; 4] = [&promo_a, &promo_b, &promo_c, &promo_e];
+
+        let decomposition = Decomposition::new(&promotions, &item_group);
+
+        let positions: Vec<Vec<usize>> = decomposition
+            .components
+            .iter()
+            .map(|component| component.item_positions.to_vec())
+            .collect();
+        let promotion_counts: Vec<usize> = decomposition
+            .components
+            .iter()
+            .map(|component| component.promotions.len())
+            .collect();
+
+        assert_eq!(positions, vec![vec![0, 2, 4], vec![1]]);
+        assert_eq!(promotion_counts, vec![2, 1]);
+        assert_eq!(decomposition.full_price_items.as_slice(), &[3]);
+    }
+
+    #[test]
+    fn merge_maps_items_back_and_offsets_redemptions() -> TestResult {
+        let items: SmallVec<[Item<'_>; 10]> = [
+            tagged_item(100, &["a"]),
+            tagged_item(200, &["b"]),
+            tagged_item(300, &["a"]),
+            tagged_item(400, &[]),
+        ]
+        .into_iter()
+        .collect();
+        let item_group = ItemGroup::new(items, GBP);
+
+        let promo_a = percent_off(&["a"]);
+        let promo_b = percent_off(&["b"]);
+        let promotions: [&dyn ILPPromotion; 2] = [&promo_a, &promo_b];
+
+        let decomposition = Decomposition::new(&promotions, &item_group);
+
+        let redemption = |item_idx, redemption_idx, price| PromotionRedemption {
+            promotion_key: PromotionKey::default(),
+            item_idx,
+            redemption_idx,
+            original_price: Money::from_minor(price, GBP),
+            final_price: Money::from_minor(price, GBP),
+        };
+
+        let results = [
+            SolverResult {
+                affected_items: SmallVec::from_slice(&[1]),
+                unaffected_items: SmallVec::from_slice(&[0]),
+                total: Money::from_minor(400, GBP),
+                promotion_redemptions: SmallVec::from_elem(redemption(1, 0, 300), 1),
+                optimal: true,
+            },
+            SolverResult {
+                affected_items: SmallVec::from_slice(&[0]),
+                unaffected_items: SmallVec::new(),
+                total: Money::from_minor(200, GBP),
+                promotion_redemptions: SmallVec::from_elem(redemption(0, 0, 200), 1),
+                optimal: false,
+            },
+        ];
+
+        let merged = decomposition.merge(&item_group, results)?;
+
+        let redemptions: Vec<(usize, usize)> = merged
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| (redemption.item_idx, redemption.redemption_idx))
+            .collect();
+
+        assert_eq!(merged.total.to_minor_units(), 1000);
+        assert_eq!(merged.affected_items.as_slice(), &[2, 1]);
+        assert_eq!(merged.unaffected_items.as_slice(), &[0, 3]);
+        assert_eq!(redemptions, vec![(2, 0), (1, 1)]);
+        assert!(!merged.optimal);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! ILP Solver Configuration
+//!
+//! The ILP formulation does not depend on a particular MILP backend. Each
+//! `good_lp` backend the crate is built with is enabled by a `solver-*` cargo
+//! feature and chosen at runtime through [`ILPSolverConfig`], which also carries
+//! an optional time budget for each solve, whether baskets are decomposed
+//! into independent components before solving, which retailer goals to pursue
+//! among the cheapest solutions, and how remaining ties are broken.
+
+use std::time::Duration;
+
+use super::{RetailerObjective, TieBreak};
+
+#[cfg(not(any(feature = "solver-microlp", feature = "solver-lpsolve")))]
+compile_error!("at least one MILP backend feature must be enabled, such as `solver-microlp`");
+
+/// A MILP backend the ILP solver can hand its formulation to.
+///
+/// Only backends enabled through cargo features are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ILPBackend {
+    /// The bundled pure-Rust `microlp` solver (`solver-microlp` feature).
+    #[cfg(feature = "solver-microlp")]
+    Microlp,
+
+    /// The `lp_solve` C library, built from bundled sources (`solver-lpsolve`
+    /// feature).
+    ///
+    /// `lp_solve` only enforces time limits in whole seconds, so a time-limited
+    /// solve may run up to a second past its budget. It also writes a report of
+    /// each solve to standard output, which `good_lp` gives no way to turn off.
+    #[cfg(feature = "solver-lpsolve")]
+    LpSolve,
+}
+
+impl ILPBackend {
+    /// Every backend enabled in this build, in order of preference.
+    pub const ENABLED: &'static [ILPBackend] = &[
+        #[cfg(feature = "solver-microlp")]
+        ILPBackend::Microlp,
+        #[cfg(feature = "solver-lpsolve")]
+        ILPBackend::LpSolve,
+    ];
+
+    /// Human-readable name of the backend.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "solver-microlp")]
+            ILPBackend::Microlp => "microlp",
+            #[cfg(feature = "solver-lpsolve")]
+            ILPBackend::LpSolve => "lp_solve",
+        }
+    }
+}
+
+impl Default for ILPBackend {
+    /// The most preferred enabled backend.
+    fn default() -> Self {
+        DEFAULT_BACKEND
+    }
+}
+
+#[cfg(feature = "solver-microlp")]
+const DEFAULT_BACKEND: ILPBackend = ILPBackend::Microlp;
+
+#[cfg(all(feature = "solver-lpsolve", not(feature = "solver-microlp")))]
+const DEFAULT_BACKEND: ILPBackend = ILPBackend::LpSolve;
+
+/// Fraction of the time limit kept back for the greedy fallback by default, as
+/// its denominator.
+const DEFAULT_FALLBACK_SHARE: u32 = 5;
+
+/// Runtime configuration for [`super::ILPSolver`].
+#[derive(Debug, Clone)]
+pub struct ILPSolverConfig {
+    backend: ILPBackend,
+    time_limit: Option<Duration>,
+    fallback_reserve: Option<Duration>,
+    decomposition: bool,
+    tie_break: TieBreak,
+    retailer_objective: Option<RetailerObjective>,
+}
+
+impl Default for ILPSolverConfig {
+    fn default() -> Self {
+        Self {
+            backend: ILPBackend::default(),
+            time_limit: None,
+            fallback_reserve: None,
+            decomposition: true,
+            tie_break: TieBreak::default(),
+            retailer_objective: None,
+        }
+    }
+}
+
+impl ILPSolverConfig {
+    /// Create a configuration using the default backend.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given backend.
+    #[must_use]
+    pub fn with_backend(mut self, backend: ILPBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Return the configured backend.
+    pub fn backend(&self) -> ILPBackend {
+        self.backend
+    }
+
+    /// Stop searching once `time_limit` has elapsed.
+    ///
+    /// When the limit is hit the solver returns the best feasible solution found so
+    /// far and marks the result as not optimal. If the search found none, promotions
+    /// are applied greedily in the [reserved](Self::with_fallback_reserve) part of
+    /// the limit, and any items left when it runs out stay at full price.
+    ///
+    /// The limit is wall-clock time for the whole solve. It is checked before each
+    /// formulation is built and before each solver pass, and handed to the backend
+    /// for the search itself. Backends only check it between steps of their own
+    /// search, so a solve can overrun by as long as one such step takes. There is
+    /// no separate iteration or node budget, as not every backend can enforce one.
+    #[must_use]
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Return the configured time limit, if any.
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Keep `reserve` of the time limit back for the greedy fallback.
+    ///
+    /// The search stops this long before the limit, so a search that finds no
+    /// solution still leaves the fallback time to apply promotions. Defaults to a
+    /// fifth of the time limit, and is never more than the limit itself.
+    #[must_use]
+    pub fn with_fallback_reserve(mut self, reserve: Duration) -> Self {
+        self.fallback_reserve = Some(reserve);
+        self
+    }
+
+    /// Return how much of the time limit is kept back for the greedy fallback.
+    ///
+    /// Zero when there is no time limit.
+    pub fn fallback_reserve(&self) -> Duration {
+        let Some(time_limit) = self.time_limit else {
+            return Duration::ZERO;
+        };
+
+        self.fallback_reserve
+            .unwrap_or(time_limit / DEFAULT_FALLBACK_SHARE)
+            .min(time_limit)
+    }
+
+    /// Enable or disable splitting the basket into independent components.
+    ///
+    /// Items that share no eligible promotion cannot affect each other's prices,
+    /// so each component is solved as its own, smaller model and the results are
+    /// merged. Enabled by default.
+    ///
+    /// Observers see one model per component, each numbering its own variables
+    /// and items from zero, so observer-based entry points such as
+    /// [`super::ILPSolver::solve_with_observer`] disable decomposition.
+    #[must_use]
+    pub fn with_decomposition(mut self, decomposition: bool) -> Self {
+        self.decomposition = decomposition;
+        self
+    }
+
+    /// Return whether the basket is decomposed into independent components.
+    pub fn decomposition(&self) -> bool {
+        self.decomposition
+    }
+
+    /// Choose between solutions with the same basket total using `tie_break`.
+    ///
+    /// An explicit policy also solves promotions in key order, so the result does
+    /// not depend on the order they are listed in.
+    #[must_use]
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Return the tie-break policy.
+    pub fn tie_break(&self) -> &TieBreak {
+        &self.tie_break
+    }
+
+    /// Pursue the retailer's goals among solutions with the same basket total.
+    ///
+    /// The goals are applied before the tie-break policy, and never change the
+    /// total the customer pays.
+    #[must_use]
+    pub fn with_retailer_objective(mut self, retailer_objective: RetailerObjective) -> Self {
+        self.retailer_objective = Some(retailer_objective);
+        self
+    }
+
+    /// Return the retailer objective, if any.
+    pub fn retailer_objective(&self) -> Option<&RetailerObjective> {
+        self.retailer_objective.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::ilp::RetailerGoal;
+
+    use super::*;
+
+    #[test]
+    fn default_backend_is_enabled() {
+        assert!(
+            ILPBackend::ENABLED.contains(&ILPBackend::default()),
+            "default backend must be one of the enabled backends"
+        );
+    }
+
+    #[test]
+    fn with_backend_sets_backend() {
+        for &backend in ILPBackend::ENABLED {
+            let config = ILPSolverConfig::new().with_backend(backend);
+
+            assert_eq!(config.backend(), backend);
+        }
+    }
+
+    #[test]
+    fn time_limit_is_unset_by_default() {
+        assert_eq!(ILPSolverConfig::new().time_limit(), None);
+
+        let config = ILPSolverConfig::new().with_time_limit(Duration::from_millis(50));
+
+        assert_eq!(config.time_limit(), Some(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn fallback_reserve_is_a_share_of_the_time_limit() {
+        assert_eq!(ILPSolverConfig::new().fallback_reserve(), Duration::ZERO);
+
+        let config = ILPSolverConfig::new().with_time_limit(Duration::from_millis(50));
+
+        assert_eq!(config.fallback_reserve(), Duration::from_millis(10));
+
+        let config = config.with_fallback_reserve(Duration::from_secs(1));
+
+        assert_eq!(config.fallback_reserve(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn tie_break_defaults_to_promotion_defined() {
+        assert_eq!(
+            ILPSolverConfig::new().tie_break(),
+            &TieBreak::PromotionDefined
+        );
+
+        let config = ILPSolverConfig::new().with_tie_break(TieBreak::FewestRedemptions);
+
+        assert_eq!(config.tie_break(), &TieBreak::FewestRedemptions);
+    }
+
+    #[test]
+    fn retailer_objective_is_unset_by_default() {
+        assert!(ILPSolverConfig::new().retailer_objective().is_none());
+
+        let config =
+            ILPSolverConfig::new().with_retailer_objective(RetailerObjective::lexicographic([
+                RetailerGoal::MaximiseMargin,
+            ]));
+
+        assert!(
+            config
+                .retailer_objective()
+                .is_some_and(|objective| !objective.is_weighted())
+        );
+    }
+
+    #[test]
+    fn decomposition_is_enabled_by_default() {
+        assert!(ILPSolverConfig::new().decomposition());
+        assert!(
+            !ILPSolverConfig::new()
+                .with_decomposition(false)
+                .decomposition()
+        );
+    }
+}
@@ -1,36 +1,52 @@
 //! ILP Solver
 
+use std::time::Instant;
+
 use good_lp::{
-    Expression, IntoAffineExpression, ProblemVariables, Solution, SolverModel, Variable,
-    solvers::microlp::microlp as default_solver, variable,
+    Expression, ProblemVariables, ResolutionError, Solution, SolutionStatus, SolverModel, Variable,
+    VariableDefinition, WithTimeLimit, solvers::Solver as MILPSolver, variable,
 };
+use num_traits::ToPrimitive;
 use rusty_money::{Money, iso::Currency};
 use smallvec::{SmallVec, smallvec};
 
 use crate::{
-    items::groups::ItemGroup,
+    items::{Item, groups::ItemGroup},
     promotions::{Promotion, redemptions::PromotionRedemption},
     solvers::{
         Solver, SolverError, SolverResult,
+        greedy::solve_greedy,
         ilp::{
+            components::{ComponentCache, Decomposition},
             promotions::PromotionInstances,
             state::{ConstraintRelation, ILPConstraint},
         },
     },
 };
 
+mod alternatives;
+pub(crate) mod components;
+pub mod config;
+pub mod objectives;
 pub mod observer;
 pub(crate) mod promotions;
 pub mod renderers;
 pub(crate) mod state;
+pub mod tie_break;
 
+pub use config::{ILPBackend, ILPSolverConfig};
+pub use objectives::{RetailerGoal, RetailerObjective};
 pub use observer::{ILPObserver, NoopObserver};
 pub use promotions::{ILPPromotion, ILPPromotionVars, PromotionVars, i64_to_f64_exact};
 pub use state::ILPState;
+pub use tie_break::TieBreak;
 
 /// Binary threshold for determining truthiness
 pub const BINARY_THRESHOLD: f64 = 0.5;
 
+/// Tolerance when checking a time-limited solution against the formulation.
+const FEASIBILITY_TOLERANCE: f64 = 1e-6;
+
 type ItemIndexList = SmallVec<[usize; 10]>;
 type ItemUsageFlags = SmallVec<[bool; 10]>;
 type AppliedPromotionState<'a> = (ItemIndexList, ItemUsageFlags, Money<'a, Currency>);
@@ -95,19 +111,197 @@ impl ILPSolver {
         promotions: &[Promotion<'_>],
         item_group: &ItemGroup<'b>,
         observer: &mut dyn ILPObserver,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        // Decomposed components would each number their variables and items from
+        // zero, so give the observer a single formulation for the whole basket.
+        Self::solve_with_config(
+            &ILPSolverConfig::default().with_decomposition(false),
+            promotions,
+            item_group,
+            observer,
+        )
+    }
+
+    /// Solve with a runtime configuration and an observer.
+    ///
+    /// The configuration selects which enabled MILP backend solves the formulation
+    /// and how long it may search. If the time limit is hit, the result is the best
+    /// feasible solution found so far, or a greedy one built in the time
+    /// [reserved](ILPSolverConfig::with_fallback_reserve) for it when there is none,
+    /// with [`SolverResult::optimal`] set to `false`.
+    ///
+    /// With [decomposition](ILPSolverConfig::with_decomposition) enabled, the
+    /// observer receives one formulation per independent component.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the solver encounters an error.
+    pub fn solve_with_config<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        let promotion_refs: SmallVec<[&dyn ILPPromotion; 5]> =
+            promotions.iter().map(AsRef::as_ref).collect();
+
+        Self::solve_internal(config, &promotion_refs, item_group, observer)
+    }
+
+    /// Internal solve implementation that splits the item group into independent
+    /// components, if enabled, and solves each with the configured backend.
+    pub(crate) fn solve_internal<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[&dyn ILPPromotion],
+        item_group: &ItemGroup<'b>,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        Self::solve_components(config, promotions, item_group, observer, None)
+    }
+
+    /// Solve like [`Self::solve_with_config`], reusing the result of any component
+    /// in `cache` that is unchanged, and leave this solve's components in `cache`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the solver encounters an error.
+    pub(crate) fn solve_reusing_components<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+        observer: &mut dyn ILPObserver,
+        cache: &mut ComponentCache<'b>,
     ) -> Result<SolverResult<'b>, SolverError> {
         let promotion_refs: SmallVec<[&dyn ILPPromotion; 5]> =
             promotions.iter().map(AsRef::as_ref).collect();
 
-        Self::solve_internal(&promotion_refs, item_group, observer)
+        Self::solve_components(config, &promotion_refs, item_group, observer, Some(cache))
+    }
+
+    /// Split the item group into components, if enabled, and solve each one that
+    /// `cache` has no result for.
+    fn solve_components<'b>(
+        config: &ILPSolverConfig,
+        promotions: &[&dyn ILPPromotion],
+        item_group: &ItemGroup<'b>,
+        observer: &mut dyn ILPObserver,
+        cache: Option<&mut ComponentCache<'b>>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        // An explicit tie-break policy must not depend on the order promotions
+        // were listed in, so build the model from them in key order.
+        let mut ordered: SmallVec<[&dyn ILPPromotion; 5]> = SmallVec::from_slice(promotions);
+
+        if config.tie_break().is_explicit() {
+            ordered.sort_by_key(|promotion| promotion.key());
+        }
+
+        let promotions = ordered.as_slice();
+
+        if !config.decomposition() {
+            return Self::solve_with_backend(config, promotions, item_group, observer);
+        }
+
+        let decomposition = Decomposition::new(promotions, item_group);
+
+        // A single component covering every item gains nothing from splitting,
+        // unless its result may be reused.
+        if let [component] = decomposition.components.as_slice()
+            && decomposition.full_price_items.is_empty()
+            && cache.is_none()
+        {
+            return Self::solve_with_backend(config, &component.promotions, item_group, observer);
+        }
+
+        // The time limit is a budget for the whole item group, so each component
+        // may use whatever the components before it left.
+        let deadline = config.time_limit().map(|limit| Instant::now() + limit);
+        let mut results = SmallVec::<[SolverResult<'b>; 4]>::new();
+        let mut solved = SmallVec::<[_; 4]>::new();
+        let mut reused = 0;
+
+        for component in &decomposition.components {
+            let component_group = component.item_group(item_group)?;
+
+            let result = if let Some(result) = cache
+                .as_deref()
+                .and_then(|cache| cache.get(component, &component_group))
+            {
+                reused += 1;
+
+                result
+            } else {
+                let component_config = match deadline {
+                    Some(deadline) => config
+                        .clone()
+                        .with_time_limit(deadline.saturating_duration_since(Instant::now())),
+                    None => config.clone(),
+                };
+
+                Self::solve_with_backend(
+                    &component_config,
+                    &component.promotions,
+                    &component_group,
+                    observer,
+                )?
+            };
+
+            if cache.is_some() {
+                let keys = component.promotions.iter().map(|promotion| promotion.key());
+
+                solved.push((keys.collect(), component_group, result.clone()));
+            }
+
+            results.push(result);
+        }
+
+        if let Some(cache) = cache {
+            cache.replace(solved, reused);
+        }
+
+        decomposition.merge(item_group, results)
     }
 
-    /// Internal solve implementation that supports an observer.
-    fn solve_internal<'b>(
+    /// Solve the whole item group as one model with the configured backend.
+    fn solve_with_backend<'b>(
+        config: &ILPSolverConfig,
         promotions: &[&dyn ILPPromotion],
         item_group: &ItemGroup<'b>,
         observer: &mut dyn ILPObserver,
     ) -> Result<SolverResult<'b>, SolverError> {
+        match config.backend() {
+            #[cfg(feature = "solver-microlp")]
+            ILPBackend::Microlp => Self::solve_using(
+                good_lp::solvers::microlp::microlp,
+                config,
+                promotions,
+                item_group,
+                observer,
+            ),
+            #[cfg(feature = "solver-lpsolve")]
+            ILPBackend::LpSolve => Self::solve_using(
+                good_lp::solvers::lpsolve::lp_solve,
+                config,
+                promotions,
+                item_group,
+                observer,
+            ),
+        }
+    }
+
+    /// Build the formulation and solve it with the given backend.
+    fn solve_using<'b, B>(
+        backend: B,
+        config: &ILPSolverConfig,
+        promotions: &[&dyn ILPPromotion],
+        item_group: &ItemGroup<'b>,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<SolverResult<'b>, SolverError>
+    where
+        B: MILPSolver + Copy,
+        B::Model: SolverModel<Error = ResolutionError> + WithTimeLimit,
+    {
+        let (search_deadline, deadline) = deadlines(config);
+
         // Return early if the item group is empty
         if item_group.is_empty() {
             return Ok(SolverResult {
@@ -115,9 +309,23 @@ impl ILPSolver {
                 unaffected_items: SmallVec::with_capacity(0),
                 total: Money::from_minor(0, item_group.currency()),
                 promotion_redemptions: SmallVec::with_capacity(0),
+                optimal: true,
             });
         }
 
+        // Building the formulation can take a while on its own, so don't start
+        // one the time limit leaves no room to solve.
+        if has_passed(search_deadline) {
+            return greedy_fallback(config, promotions, item_group, deadline);
+        }
+
+        // Solve over lines of identical units rather than single units, where
+        // every applicable promotion that could claim them can model integer
+        // counts. Other units keep their own binary variables.
+        let units = item_group;
+        let compressed = compress_lines(promotions, item_group);
+        let item_group = compressed.as_ref().unwrap_or(item_group);
+
         let BuiltILPFormulation {
             pb,
             cost,
@@ -126,20 +334,27 @@ impl ILPSolver {
             promotion_instances,
         } = build_ilp_formulation(promotions, item_group, observer)?;
 
-        // Promotions may optionally contribute a secondary tie-break objective.
-        // We check whether any non-zero linear terms were emitted so we can skip
-        // the second pass entirely when no tie-break is configured.
-        let secondary_objective =
-            promotion_instances.add_secondary_objective_terms(Expression::default(), item_group)?;
-        let has_secondary_objective_terms =
-            IntoAffineExpression::linear_coefficients(&secondary_objective)
-                .next()
-                .is_some();
+        // Retailer goals, promotions and the configured tie-break policy may
+        // contribute secondary objectives. Those without any non-zero terms are
+        // left out, so the later passes are skipped entirely when none apply.
+        let has_secondary_objectives =
+            !objectives::secondary_objectives(config, &promotion_instances, item_group)?.is_empty();
+
+        // A time-limited solve may stop on a point that is not a valid assignment,
+        // so keep what is needed to check it against the formulation afterwards.
+        let mut feasibility = deadline.map(|_| FeasibilityCheck::new(&pb, &constraints));
 
         // Keep a clone of the exact first-pass objective so we can evaluate the
         // solved optimum value before consuming `cost` in the model builder.
         let primary_cost = cost.clone();
-        let mut model = pb.minimise(cost).using(default_solver);
+
+        // Variables in creation order, to report their solved values.
+        let variables: Vec<Variable> = pb.iter_variables_with_def().map(|(var, _)| var).collect();
+        let mut model = pb.minimise(cost).using(backend);
+
+        if let Some(search_deadline) = search_deadline {
+            model = model.with_time_limit(remaining_seconds(search_deadline));
+        }
 
         ensure_presence_vars_len(item_presence.len(), item_group.len())?;
 
@@ -151,23 +366,50 @@ impl ILPSolver {
         //
         // Example: If "20% off" and "Buy-one-get-one" both target the same item,
         // the solver must choose one or neither, never both.
-        for (item_idx, z_i) in item_presence.iter().copied().enumerate() {
-            let constraint_expr =
-                promotion_instances.add_item_presence_term(Expression::from(z_i), item_idx);
-
-            // Notify observer before adding constraint
-            observer.on_exclusivity_constraint(item_idx, &constraint_expr);
-
-            model = model.with(constraint_expr.eq(1));
-        }
+        //
+        // For a line of identical units the same holds for every unit, so the
+        // line's counts must add up to its quantity.
+        model = apply_exclusivity_constraints(
+            model,
+            &promotion_instances,
+            item_group,
+            &item_presence,
+            observer,
+            feasibility.as_mut(),
+        )?;
 
         // Add all recorded promotion constraints.
         model = apply_recorded_constraints(model, constraints);
 
+        if has_passed(search_deadline) {
+            return greedy_fallback(config, promotions, units, deadline);
+        }
+
         // Pass 1: optimize the real business objective (total final basket value).
-        let primary_solution = model.solve()?;
+        let primary_solution = match solve_model(model, search_deadline)? {
+            SolveOutcome::Finished(solution) => solution,
+            SolveOutcome::TimedOut(solution) => {
+                let best_known = best_known_result(
+                    feasibility.as_ref(),
+                    &promotion_instances,
+                    solution.as_ref(),
+                    item_group,
+                    &item_presence,
+                )?;
+
+                let (Some(result), Some(solution)) = (best_known, solution) else {
+                    return greedy_fallback(config, promotions, units, deadline);
+                };
+
+                report_variable_values(observer, &variables, &solution);
+
+                return Ok(result);
+            }
+        };
+
+        if !has_secondary_objectives {
+            report_variable_values(observer, &variables, &primary_solution);
 
-        if !has_secondary_objective_terms {
             return build_solver_result(
                 &promotion_instances,
                 &primary_solution,
@@ -187,51 +429,160 @@ impl ILPSolver {
             SolverError::MinorUnitsNotRepresentable(primary_optimal_value),
         )?;
 
-        // Pass 2 uses an identical formulation but with:
-        // 1) a fixed equality `primary_cost == optimum_from_pass_1`
-        // 2) a secondary objective that only breaks ties among pass-1 optima.
-        //
-        // We rebuild instead of mutating the solved model in-place to keep the
-        // construction path identical and avoid backend-specific model mutation
-        // assumptions.
-        let mut secondary_observer = NoopObserver;
-        let BuiltILPFormulation {
-            pb,
-            cost,
-            item_presence,
-            constraints,
-            promotion_instances,
-        } = build_ilp_formulation(promotions, item_group, &mut secondary_observer)?;
-
-        let secondary_objective =
-            promotion_instances.add_secondary_objective_terms(Expression::default(), item_group)?;
-        let mut secondary_model = pb.minimise(secondary_objective).using(default_solver);
-
-        ensure_presence_vars_len(item_presence.len(), item_group.len())?;
-
-        for (item_idx, z_i) in item_presence.iter().copied().enumerate() {
-            let constraint_expr =
-                promotion_instances.add_item_presence_term(Expression::from(z_i), item_idx);
+        if let Some((result, values)) = Self::solve_secondary_passes(
+            backend,
+            config,
+            deadline,
+            promotions,
+            item_group,
+            primary_optimal_f64,
+        )? {
+            // Every pass builds the same formulation, so its variables line up
+            // with the ones the observer saw.
+            for (&var, value) in variables.iter().zip(values) {
+                observer.on_variable_value(var, value);
+            }
 
-            secondary_observer.on_exclusivity_constraint(item_idx, &constraint_expr);
-            secondary_model = secondary_model.with(constraint_expr.eq(1));
+            return Ok(result);
         }
 
-        secondary_model = apply_recorded_constraints(secondary_model, constraints);
-        // Lexicographic guardrail: force pass 2 to stay on the primary optimum face.
-        secondary_model = secondary_model.with(cost.eq(primary_optimal_f64));
-
-        // Pass 2: choose a deterministic/cheaper branch profile among equal-cost
-        // pass-1 solutions.
-        let secondary_solution = secondary_model.solve()?;
+        // The first secondary pass ran out of time, but it only chooses between
+        // equally cheap solutions, so the pass-1 optimum is still the lowest total.
+        report_variable_values(observer, &variables, &primary_solution);
 
         build_solver_result(
             &promotion_instances,
-            &secondary_solution,
+            &primary_solution,
             item_group,
             &item_presence,
         )
     }
+
+    /// Later passes: among solutions with the pass-1 optimal cost, minimise each
+    /// secondary objective in turn, keeping earlier ones at their optimum.
+    ///
+    /// Returns the result of the last completed pass, with the value of each
+    /// variable in creation order, or `None` if the time limit stopped the first
+    /// one.
+    fn solve_secondary_passes<'b, B>(
+        backend: B,
+        config: &ILPSolverConfig,
+        deadline: Option<Instant>,
+        promotions: &[&dyn ILPPromotion],
+        item_group: &ItemGroup<'b>,
+        primary_optimal_f64: f64,
+    ) -> Result<Option<(SolverResult<'b>, Vec<f64>)>, SolverError>
+    where
+        B: MILPSolver + Copy,
+        B::Model: SolverModel<Error = ResolutionError> + WithTimeLimit,
+    {
+        let mut fixed_values: SmallVec<[f64; 2]> = SmallVec::new();
+        let mut result = None;
+
+        loop {
+            // A pass only breaks ties left by the ones before it, so once time
+            // runs out the last completed pass stands.
+            if has_passed(deadline) {
+                return Ok(result);
+            }
+
+            // Each pass uses an identical formulation but with:
+            // 1) a fixed equality `primary_cost == optimum_from_pass_1`
+            // 2) earlier secondary objectives fixed at their optimum
+            // 3) the next secondary objective, which only breaks ties among the
+            //    solutions left by the passes before it.
+            //
+            // We rebuild instead of mutating the solved model in-place to keep the
+            // construction path identical and avoid backend-specific model mutation
+            // assumptions.
+            let mut secondary_observer = NoopObserver;
+            let BuiltILPFormulation {
+                pb,
+                cost,
+                item_presence,
+                constraints,
+                promotion_instances,
+            } = build_ilp_formulation(promotions, item_group, &mut secondary_observer)?;
+
+            let mut stages =
+                objectives::secondary_objectives(config, &promotion_instances, item_group)?
+                    .into_iter();
+            let earlier_stages: SmallVec<[Expression; 2]> =
+                stages.by_ref().take(fixed_values.len()).collect();
+
+            let Some(objective) = stages.next() else {
+                return Ok(result);
+            };
+
+            let objective_value = objective.clone();
+            let variables: Vec<Variable> =
+                pb.iter_variables_with_def().map(|(var, _)| var).collect();
+            let mut secondary_model = pb.minimise(objective).using(backend);
+
+            if let Some(deadline) = deadline {
+                secondary_model = secondary_model.with_time_limit(remaining_seconds(deadline));
+            }
+
+            ensure_presence_vars_len(item_presence.len(), item_group.len())?;
+
+            secondary_model = apply_exclusivity_constraints(
+                secondary_model,
+                &promotion_instances,
+                item_group,
+                &item_presence,
+                &mut secondary_observer,
+                None,
+            )?;
+
+            secondary_model = apply_recorded_constraints(secondary_model, constraints);
+            // Lexicographic guardrail: force every pass to stay on the primary
+            // optimum face, and on the optimum of each earlier pass.
+            secondary_model = secondary_model.with(cost.eq(primary_optimal_f64));
+
+            for (earlier, &value) in earlier_stages.into_iter().zip(&fixed_values) {
+                secondary_model = secondary_model.with(earlier.eq(value));
+            }
+
+            if has_passed(deadline) {
+                return Ok(result);
+            }
+
+            let SolveOutcome::Finished(secondary_solution) =
+                solve_model(secondary_model, deadline)?
+            else {
+                return Ok(result);
+            };
+
+            result = Some((
+                build_solver_result(
+                    &promotion_instances,
+                    &secondary_solution,
+                    item_group,
+                    &item_presence,
+                )?,
+                variables
+                    .iter()
+                    .map(|&var| secondary_solution.value(var))
+                    .collect(),
+            ));
+
+            if stages.len() == 0 {
+                return Ok(result);
+            }
+
+            // Only retailer goals come before another pass, and their coefficients
+            // are integral, so their optimum can be fixed exactly.
+            let optimum = objective_value_to_integral_minor_units(
+                secondary_solution.eval(&objective_value),
+                "secondary objective value is non-integral",
+            )?;
+
+            fixed_values.push(
+                i64_to_f64_exact(optimum)
+                    .ok_or(SolverError::MinorUnitsNotRepresentable(optimum))?,
+            );
+        }
+    }
 }
 
 impl Solver for ILPSolver {
@@ -239,12 +590,281 @@ impl Solver for ILPSolver {
         promotions: &[Promotion<'_>],
         item_group: &ItemGroup<'b>,
     ) -> Result<SolverResult<'b>, SolverError> {
-        let mut observer = NoopObserver;
+        Self::solve_with_config(
+            &ILPSolverConfig::default(),
+            promotions,
+            item_group,
+            &mut NoopObserver,
+        )
+    }
+}
 
-        let promotion_refs: SmallVec<[&dyn ILPPromotion; 5]> =
-            promotions.iter().map(AsRef::as_ref).collect();
+/// Compress runs of identical units into lines, where no applicable promotion
+/// that needs one entry per unit is eligible for them.
+///
+/// Returns `None` when no units can be merged.
+fn compress_lines<'b>(
+    promotions: &[&dyn ILPPromotion],
+    item_group: &ItemGroup<'b>,
+) -> Option<ItemGroup<'b>> {
+    let per_unit: SmallVec<[&dyn ILPPromotion; 5]> = promotions
+        .iter()
+        .copied()
+        .filter(|promotion| !promotion.supports_quantities() && promotion.is_applicable(item_group))
+        .collect();
+
+    let compressed = item_group.compress(|item| {
+        !per_unit
+            .iter()
+            .any(|promotion| promotion.is_item_eligible(item))
+    });
+
+    (compressed.len() < item_group.len()).then_some(compressed)
+}
 
-        Self::solve_internal(&promotion_refs, item_group, &mut observer)
+/// Quantity of the item group line at `item_idx`, as a constraint bound.
+fn line_quantity(item_group: &ItemGroup<'_>, item_idx: usize) -> Result<f64, SolverError> {
+    Ok(f64::from(item_group.get_item(item_idx)?.quantity()))
+}
+
+/// Read a solved integer count variable, tolerating floating-point noise.
+pub(crate) fn solved_count<S: Solution + ?Sized>(solution: &S, var: Variable) -> u32 {
+    solution.value(var).round().to_u32().unwrap_or(0)
+}
+
+/// Decision variable for an item: binary for a single unit, or a count of
+/// units for a line of identical ones.
+pub(crate) fn unit_count_variable(item: &Item<'_>) -> VariableDefinition {
+    match item.quantity() {
+        1 => variable().binary(),
+        quantity => variable().integer().min(0).max(quantity),
+    }
+}
+
+/// Unit index of the first unit of each line in `item_group`.
+///
+/// Lines are runs of identical units in unit order, so the first unit of a line
+/// directly follows the units of the lines before it.
+fn line_unit_starts(item_group: &ItemGroup<'_>) -> (SmallVec<[usize; 10]>, usize) {
+    let mut starts = SmallVec::with_capacity(item_group.len());
+    let mut unit_count: usize = 0;
+
+    for item in item_group.iter() {
+        starts.push(unit_count);
+        unit_count =
+            unit_count.saturating_add(usize::try_from(item.quantity()).unwrap_or(usize::MAX));
+    }
+
+    (starts, unit_count)
+}
+
+/// Deadlines for a solve starting now, as `(search, solve)`.
+///
+/// The search must stop early enough to leave the configured fallback reserve,
+/// while the solve as a whole, fallback included, must end by the time limit.
+fn deadlines(config: &ILPSolverConfig) -> (Option<Instant>, Option<Instant>) {
+    let Some(limit) = config.time_limit() else {
+        return (None, None);
+    };
+
+    let started = Instant::now();
+    let search_limit = limit.saturating_sub(config.fallback_reserve());
+
+    (Some(started + search_limit), Some(started + limit))
+}
+
+/// Whether `deadline` is set and has passed.
+fn has_passed(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// How a backend's search ended.
+enum SolveOutcome<S> {
+    /// The search ran to completion.
+    Finished(S),
+
+    /// The time limit stopped the search, leaving the backend's last point if it
+    /// had one.
+    TimedOut(Option<S>),
+}
+
+/// Solve `model`, telling a completed search from one stopped by the time limit.
+///
+/// Backends report time limits differently. microlp returns its last point with a
+/// [`SolutionStatus::TimeLimit`] status, while `lp_solve` returns an error when it has
+/// no solution and an optimal status when it has one. A solve that returns after
+/// the deadline is therefore treated as stopped by it, whatever its status.
+fn solve_model<M>(
+    model: M,
+    deadline: Option<Instant>,
+) -> Result<SolveOutcome<M::Solution>, ResolutionError>
+where
+    M: SolverModel<Error = ResolutionError>,
+{
+    match model.solve() {
+        Ok(solution)
+            if matches!(solution.status(), SolutionStatus::TimeLimit) || has_passed(deadline) =>
+        {
+            Ok(SolveOutcome::TimedOut(Some(solution)))
+        }
+        Ok(solution) => Ok(SolveOutcome::Finished(solution)),
+        Err(ResolutionError::Other("Timeout")) if deadline.is_some() => {
+            Ok(SolveOutcome::TimedOut(None))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Seconds left before `deadline`, or zero once it has passed.
+fn remaining_seconds(deadline: Instant) -> f64 {
+    deadline
+        .saturating_duration_since(Instant::now())
+        .as_secs_f64()
+}
+
+/// Constraints and integrality a time-limited solution must satisfy.
+///
+/// A backend stopped by its time limit returns its best integral solution when it
+/// found one, but otherwise may hand back the last relaxation it visited.
+struct FeasibilityCheck {
+    /// Variables that must take integral values.
+    integer_vars: Vec<Variable>,
+
+    /// Every linear constraint in the model.
+    constraints: Vec<ILPConstraint>,
+}
+
+impl FeasibilityCheck {
+    fn new(pb: &ProblemVariables, constraints: &[ILPConstraint]) -> Self {
+        let integer_vars = pb
+            .iter_variables_with_def()
+            .filter(|(_, definition)| definition.is_integer())
+            .map(|(var, _)| var)
+            .collect();
+
+        Self {
+            integer_vars,
+            constraints: constraints.to_vec(),
+        }
+    }
+
+    fn require_eq(&mut self, lhs: Expression, rhs: f64) {
+        self.constraints.push(ILPConstraint {
+            lhs,
+            relation: ConstraintRelation::Eq,
+            rhs,
+        });
+    }
+
+    fn require_leq(&mut self, lhs: Expression, rhs: f64) {
+        self.constraints.push(ILPConstraint {
+            lhs,
+            relation: ConstraintRelation::Leq,
+            rhs,
+        });
+    }
+
+    fn is_satisfied_by<S: Solution>(&self, solution: &S) -> bool {
+        let integral = self.integer_vars.iter().all(|&var| {
+            let value = solution.value(var);
+
+            (value - value.round()).abs() <= FEASIBILITY_TOLERANCE
+        });
+
+        integral
+            && self.constraints.iter().all(|constraint| {
+                let lhs = solution.eval(&constraint.lhs);
+
+                match constraint.relation {
+                    ConstraintRelation::Eq => (lhs - constraint.rhs).abs() <= FEASIBILITY_TOLERANCE,
+                    ConstraintRelation::Leq => lhs <= constraint.rhs + FEASIBILITY_TOLERANCE,
+                    ConstraintRelation::Geq => lhs >= constraint.rhs - FEASIBILITY_TOLERANCE,
+                }
+            })
+    }
+}
+
+/// Result of a pass-1 solve stopped by its time limit.
+///
+/// Returns `None` if the backend had no solution or it is not a valid assignment.
+/// A valid solution is marked as not optimal.
+fn best_known_result<'b, S: Solution>(
+    feasibility: Option<&FeasibilityCheck>,
+    promotion_instances: &PromotionInstances<'_>,
+    solution: Option<&S>,
+    item_group: &ItemGroup<'b>,
+    item_presence: &[Variable],
+) -> Result<Option<SolverResult<'b>>, SolverError> {
+    let Some(solution) = solution.filter(|solution| {
+        feasibility.is_some_and(|feasibility| feasibility.is_satisfied_by(*solution))
+    }) else {
+        return Ok(None);
+    };
+
+    let mut result = build_solver_result(promotion_instances, solution, item_group, item_presence)?;
+
+    result.optimal = false;
+
+    Ok(Some(result))
+}
+
+/// Greedy result used when a time limit stops the search before any valid
+/// assignment is found.
+///
+/// The search stops early enough to leave the fallback its reserve of the time
+/// limit. Only the greedy construction phase runs, and only until `deadline`, so
+/// the fallback never extends the time limit. Items the greedy search had no time
+/// to place stay at full price.
+fn greedy_fallback<'b>(
+    config: &ILPSolverConfig,
+    promotions: &[&dyn ILPPromotion],
+    item_group: &ItemGroup<'b>,
+    deadline: Option<Instant>,
+) -> Result<SolverResult<'b>, SolverError> {
+    let mut result = solve_greedy(config, promotions, item_group, false, deadline)?;
+
+    result.optimal = false;
+
+    Ok(result)
+}
+
+/// Require every line to be bought exactly as many times as its quantity, either
+/// at full price or through promotions.
+fn apply_exclusivity_constraints<S: SolverModel>(
+    mut model: S,
+    promotion_instances: &PromotionInstances<'_>,
+    item_group: &ItemGroup<'_>,
+    item_presence: &[Variable],
+    observer: &mut dyn ILPObserver,
+    mut feasibility: Option<&mut FeasibilityCheck>,
+) -> Result<S, SolverError> {
+    for (item_idx, z_i) in item_presence.iter().copied().enumerate() {
+        let constraint_expr =
+            promotion_instances.add_item_presence_term(Expression::from(z_i), item_idx);
+
+        // Notify observer before adding constraint
+        observer.on_exclusivity_constraint(item_idx, &constraint_expr);
+
+        let quantity = line_quantity(item_group, item_idx)?;
+
+        if let Some(feasibility) = feasibility.as_deref_mut() {
+            feasibility.require_eq(constraint_expr.clone(), quantity);
+        }
+
+        model = model.with(constraint_expr.eq(quantity));
+    }
+
+    Ok(model)
+}
+
+/// Report the value each variable takes in the solution a result is read from.
+fn report_variable_values(
+    observer: &mut dyn ILPObserver,
+    variables: &[Variable],
+    solution: &dyn Solution,
+) {
+    for &var in variables {
+        observer.on_variable_value(var, solution.value(var));
     }
 }
 
@@ -274,6 +894,8 @@ fn build_ilp_formulation<'a>(
     // 1. Presence variables: each item at full price (baseline option)
     // 2. Promotion variables: each item with each applicable promotion (discount options)
     // 3. Constraints: ensure each item is purchased exactly once (baseline full price OR one promotion discount applied)
+    observer.on_item_group(item_group);
+
     let mut state = ILPState::with_presence_variables_and_observer(item_group, observer)?;
 
     // Set up all possible promotion choices for the solver to consider.
@@ -284,6 +906,15 @@ fn build_ilp_formulation<'a>(
 
     let (pb, cost, item_presence, constraints) = state.into_parts_with_constraints();
 
+    for (var, definition) in pb.iter_variables_with_def() {
+        observer.on_variable_bounds(
+            var,
+            definition.get_min(),
+            definition.get_max(),
+            definition.is_integer(),
+        );
+    }
+
     Ok(BuiltILPFormulation {
         pb,
         cost,
@@ -338,7 +969,9 @@ fn build_solver_result<'a, 'b, S: Solution>(
 ) -> Result<SolverResult<'b>, SolverError> {
     // Translate the solver's decisions back into business terms: which items got
     // discounted, by which promotions, and what their final prices are.
-    let mut used_items: ItemUsageFlags = smallvec![false; item_group.len()];
+    let (line_starts, unit_count) = line_unit_starts(item_group);
+    let mut line_offsets: SmallVec<[usize; 10]> = smallvec![0; line_starts.len()];
+    let mut used_items: ItemUsageFlags = smallvec![false; unit_count];
     let mut total = Money::from_minor(0, item_group.currency());
     let mut promotion_redemptions: SmallVec<[PromotionRedemption<'b>; 10]> = SmallVec::new();
     let mut next_redemption_idx: usize = 0;
@@ -346,11 +979,24 @@ fn build_solver_result<'a, 'b, S: Solution>(
 
     // Extract which items each promotion selected and their discounted prices
     for instance in promotion_instances.iter() {
-        let apps =
+        let mut apps =
             instance.calculate_item_redemptions(solution, item_group, &mut next_redemption_idx)?;
 
+        // Promotions report redemptions per line; hand each one the next unit of its line.
+        for app in &mut apps {
+            let (Some(start), Some(offset)) = (
+                line_starts.get(app.item_idx),
+                line_offsets.get_mut(app.item_idx),
+            ) else {
+                continue;
+            };
+
+            app.item_idx = start.saturating_add(*offset);
+            *offset = offset.saturating_add(1);
+        }
+
         let (applied_items, updated_used_items, updated_total) =
-            apply_promotion_redemptions(item_group.len(), used_items, total, &apps)?;
+            apply_promotion_redemptions(unit_count, used_items, total, &apps)?;
 
         affected_items.extend(applied_items);
         used_items = updated_used_items;
@@ -369,6 +1015,7 @@ fn build_solver_result<'a, 'b, S: Solution>(
         unaffected_items,
         total,
         promotion_redemptions,
+        optimal: true,
     })
 }
 
@@ -405,7 +1052,11 @@ fn build_presence_variables_and_objective<O: ILPObserver + ?Sized>(
     // variables later, they'll offer alternative (discounted) costs. The solver will
     // compare full-price vs. discounted options and choose what minimizes the total.
     for (item_idx, item) in item_group.iter().enumerate() {
-        let var = pb.add(variable().binary());
+        // A line of identical units counts how many of them are bought at full price.
+        let var = match item.quantity() {
+            1 => pb.add(variable().binary()),
+            quantity => pb.add(variable().integer().min(0).max(quantity)),
+        };
         let minor_units = item.price().to_minor_units();
 
         // `good_lp` stores coefficients as `f64`. Only integers with absolute value <= 2^53
@@ -441,16 +1092,30 @@ fn collect_full_price_items<'b>(
     let mut used_items = used_items;
     let mut total = total;
 
+    let (line_starts, _) = line_unit_starts(item_group);
+
     // Any item that wasn't claimed by a promotion is treated as an unaffected
     // full-price item and contributes its full price to the total.
-    for (item_idx, (var, item)) in z.iter().copied().zip(item_group.iter()).enumerate() {
-        let Some(used) = used_items.get_mut(item_idx) else {
-            continue;
-        };
+    for ((var, item), start) in z.iter().copied().zip(item_group.iter()).zip(line_starts) {
+        // `var` counts the line's full-price units (a binary for single units); the
+        // solver returns floats, so round to tolerate tiny numerical noise.
+        let mut full_price_units = solved_count(solution, var);
+
+        for offset in 0..usize::try_from(item.quantity()).unwrap_or(usize::MAX) {
+            if full_price_units == 0 {
+                break;
+            }
+
+            let item_idx = start.saturating_add(offset);
+
+            let Some(used) = used_items.get_mut(item_idx) else {
+                continue;
+            };
+
+            if *used {
+                continue;
+            }
 
-        // `var` is a binary decision variable; the solver return floats, so treat values
-        // greater than 0.5 as "selected" (i.e. 1) to tolerate tiny numerical noise.
-        if solution.value(var) > BINARY_THRESHOLD && !*used {
             // Add the item to the list of unaffected items.
             unaffected_items.push(item_idx);
 
@@ -462,6 +1127,7 @@ fn collect_full_price_items<'b>(
 
             // Mark the item as used.
             *used = true;
+            full_price_units -= 1;
         }
     }
 
@@ -521,11 +1187,12 @@ fn apply_promotion_redemptions<'b>(
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, time::Duration};
 
     use decimal_percentage::Percentage;
     use rustc_hash::FxHashMap;
     use rusty_money::iso::GBP;
+    use slotmap::SlotMap;
     use smallvec::{SmallVec, smallvec};
     use testresult::TestResult;
 
@@ -534,11 +1201,26 @@ mod tests {
         items::{Item, groups::ItemGroup},
         products::ProductKey,
         promotions::{
-            PromotionKey, budget::PromotionBudget, promotion, qualification::Qualification,
-            redemptions::PromotionRedemption, types::DirectDiscountPromotion,
+            PromotionKey, PromotionSlotKey,
+            budget::PromotionBudget,
+            promotion,
+            qualification::Qualification,
+            redemptions::PromotionRedemption,
+            types::{
+                DirectDiscountPromotion, MixAndMatchDiscount, MixAndMatchPromotion,
+                PositionalDiscountPromotion, ThresholdDiscount, ThresholdTier, TierThreshold,
+                TieredThresholdPromotion,
+            },
+        },
+        solvers::{
+            ilp::promotions::{
+                ILPPromotion, ILPPromotionVars, PromotionVars,
+                test_support::{CountingObserver, SelectAllSolution},
+            },
+            verifier::verify_result,
         },
-        solvers::ilp::promotions::{ILPPromotion, ILPPromotionVars, PromotionVars},
         tags::string::StringTagCollection,
+        utils::slot,
     };
 
     use super::*;
@@ -675,6 +1357,23 @@ mod tests {
         }
     }
 
+    /// Solve with every unit kept apart, by adding a promotion that needs one
+    /// entry per unit but is never worth taking.
+    fn solve_per_unit<'b>(
+        promotions: &[Promotion<'_>],
+        item_group: &ItemGroup<'b>,
+    ) -> Result<SolverResult<'b>, SolverError> {
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut per_unit = promotions.to_vec();
+
+        per_unit.push(promotion(TestCustomPromotion {
+            key: keys.insert(()),
+            final_minor: 1_000_000,
+        }));
+
+        ILPSolver::solve(&per_unit, item_group)
+    }
+
     impl ILPPromotion for TestCustomPromotion {
         fn key(&self) -> PromotionKey {
             self.key
@@ -1297,4 +1996,549 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn solver_models_identical_units_as_integer_counts() -> TestResult {
+        let item_group = item_group_from_items([Item::with_quantity(
+            ProductKey::default(),
+            Money::from_minor(100, GBP),
+            StringTagCollection::from_strs(&["can"]),
+            40,
+        )]);
+
+        let promotions = [promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["can"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+            PromotionBudget {
+                redemption_limit: Some(25),
+                monetary_limit: None,
+            },
+        ))];
+
+        let mut observer = CountingObserver::default();
+        let result = ILPSolver::solve_with_observer(&promotions, &item_group, &mut observer)?;
+
+        assert_eq!(
+            observer.promotion_variables, 1,
+            "expected one variable per line"
+        );
+        assert_eq!(result.total.to_minor_units(), 25 * 90 + 15 * 100);
+        assert_eq!(result.promotion_redemptions.len(), 25);
+        assert_eq!(result.affected_items.len(), 25);
+        assert_eq!(result.unaffected_items.len(), 15);
+
+        let mut units: Vec<usize> = result
+            .affected_items
+            .iter()
+            .chain(result.unaffected_items.iter())
+            .copied()
+            .collect();
+
+        units.sort_unstable();
+
+        assert_eq!(units, (0..40).collect::<Vec<_>>());
+
+        let mut redemption_indices: Vec<usize> = result
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| redemption.redemption_idx)
+            .collect();
+
+        redemption_indices.sort_unstable();
+        redemption_indices.dedup();
+
+        assert_eq!(redemption_indices.len(), 25);
+
+        Ok(())
+    }
+
+    #[test]
+    fn solver_walks_positional_bundles_across_a_line() -> TestResult {
+        let can = |price, quantity| {
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&["can"]),
+                quantity,
+            )
+        };
+
+        let item_group = item_group_from_items([can(250, 1), can(100, 7)]);
+
+        let promotions = [promotion(PositionalDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["can"])),
+            3,
+            SmallVec::from_vec(vec![2u16]),
+            SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+            PromotionBudget::unlimited(),
+        ))];
+
+        let promotion_refs: Vec<&dyn ILPPromotion> = promotions.iter().map(AsRef::as_ref).collect();
+        let lines = compress_lines(&promotion_refs, &item_group).ok_or("Expected lines")?;
+        let quantities: Vec<u32> = lines.iter().map(Item::quantity).collect();
+
+        assert_eq!(quantities, vec![1, 7]);
+
+        let result = ILPSolver::solve(&promotions, &item_group)?;
+        let unit_result = solve_per_unit(&promotions, &item_group)?;
+
+        verify_result(&promotions, &item_group, &result)?;
+
+        // Two 3-for-2 bundles, each giving away a can at 100
+        assert_eq!(result.total.to_minor_units(), 750);
+        assert_eq!(result.total, unit_result.total);
+        assert_eq!(result.promotion_redemptions.len(), 6);
+
+        let mut bundles: Vec<usize> = result
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| redemption.redemption_idx)
+            .collect();
+
+        bundles.dedup();
+
+        assert_eq!(bundles.len(), 2, "expected the units in two bundles");
+
+        let free_units = result
+            .promotion_redemptions
+            .iter()
+            .filter(|redemption| redemption.final_price.is_zero())
+            .count();
+
+        assert_eq!(free_units, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn solver_forms_mix_and_match_bundles_across_lines() -> TestResult {
+        let tagged = |price, tag, quantity| {
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&[tag]),
+                quantity,
+            )
+        };
+
+        let item_group = item_group_from_items([
+            tagged(300, "sandwich", 3),
+            tagged(150, "drink", 2),
+            tagged(120, "drink", 1),
+            tagged(80, "snack", 4),
+        ]);
+
+        let discounts = [
+            MixAndMatchDiscount::PercentAllItems(Percentage::from(0.25)),
+            MixAndMatchDiscount::AmountOffEachItem(Money::from_minor(40, GBP)),
+            MixAndMatchDiscount::FixedPriceEachItem(Money::from_minor(100, GBP)),
+            MixAndMatchDiscount::AmountOffTotal(Money::from_minor(150, GBP)),
+            MixAndMatchDiscount::PercentCheapest(Percentage::from(1.0)),
+            MixAndMatchDiscount::FixedCheapest(Money::from_minor(10, GBP)),
+            MixAndMatchDiscount::FixedTotal(Money::from_minor(400, GBP)),
+        ];
+
+        // A fixed-arity meal deal, and a variable-arity one taking any number of snacks.
+        let arities = [Some(1), None];
+
+        for discount in discounts {
+            for snack_max in arities {
+                let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+                let promotions = [promotion(MixAndMatchPromotion::new(
+                    PromotionKey::default(),
+                    vec![
+                        slot(
+                            &mut slot_keys,
+                            StringTagCollection::from_strs(&["sandwich"]),
+                            1,
+                            Some(1),
+                        ),
+                        slot(
+                            &mut slot_keys,
+                            StringTagCollection::from_strs(&["drink"]),
+                            1,
+                            Some(1),
+                        ),
+                        slot(
+                            &mut slot_keys,
+                            StringTagCollection::from_strs(&["snack"]),
+                            1,
+                            snack_max,
+                        ),
+                    ],
+                    discount.clone(),
+                    PromotionBudget::unlimited(),
+                ))];
+
+                let promotion_refs: Vec<&dyn ILPPromotion> =
+                    promotions.iter().map(AsRef::as_ref).collect();
+                let lines = compress_lines(&promotion_refs, &item_group).ok_or("Expected lines")?;
+
+                assert_eq!(lines.len(), 4, "{discount:?}");
+
+                let result = ILPSolver::solve(&promotions, &item_group)?;
+                let unit_result = solve_per_unit(&promotions, &item_group)?;
+
+                verify_result(&promotions, &item_group, &result)?;
+
+                assert_eq!(result.total, unit_result.total, "{discount:?}");
+
+                let bundles = |result: &SolverResult<'_>| {
+                    let mut bundles: Vec<usize> = result
+                        .promotion_redemptions
+                        .iter()
+                        .map(|redemption| redemption.redemption_idx)
+                        .collect();
+
+                    bundles.dedup();
+                    bundles.len()
+                };
+
+                assert_eq!(bundles(&result), bundles(&unit_result), "{discount:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn solver_applies_threshold_tiers_across_lines() -> TestResult {
+        let tagged = |price, tags: &[&str], quantity| {
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(tags),
+                quantity,
+            )
+        };
+
+        let item_group = item_group_from_items([
+            tagged(900, &["wine"], 2),
+            tagged(400, &["wine", "cheese"], 3),
+            tagged(150, &["cheese"], 2),
+        ]);
+
+        let discounts = [
+            ThresholdDiscount::PercentEachItem(Percentage::from(0.2)),
+            ThresholdDiscount::AmountOffEachItem(Money::from_minor(50, GBP)),
+            ThresholdDiscount::FixedPriceEachItem(Money::from_minor(100, GBP)),
+            ThresholdDiscount::AmountOffTotal(Money::from_minor(250, GBP)),
+            ThresholdDiscount::FixedTotal(Money::from_minor(1_000, GBP)),
+            ThresholdDiscount::PercentCheapest(Percentage::from(1.0)),
+            ThresholdDiscount::FixedCheapest(Money::from_minor(10, GBP)),
+        ];
+
+        // Spend and count thresholds that only whole lines' worth of units reach.
+        let thresholds = [
+            TierThreshold::with_monetary_threshold(Money::from_minor(2_500, GBP)),
+            TierThreshold::with_item_count_threshold(4),
+        ];
+
+        for discount in discounts {
+            for threshold in &thresholds {
+                let promotions = [promotion(TieredThresholdPromotion::new(
+                    PromotionKey::default(),
+                    vec![ThresholdTier::new(
+                        threshold.clone(),
+                        None,
+                        Qualification::match_any(StringTagCollection::from_strs(&["wine"])),
+                        Qualification::match_any(StringTagCollection::from_strs(&["cheese"])),
+                        discount.clone(),
+                    )],
+                    PromotionBudget::unlimited(),
+                ))];
+
+                let promotion_refs: Vec<&dyn ILPPromotion> =
+                    promotions.iter().map(AsRef::as_ref).collect();
+                let lines = compress_lines(&promotion_refs, &item_group).ok_or("Expected lines")?;
+
+                assert_eq!(lines.len(), 3, "{discount:?}");
+
+                let result = ILPSolver::solve(&promotions, &item_group)?;
+                let unit_result = solve_per_unit(&promotions, &item_group)?;
+
+                verify_result(&promotions, &item_group, &result)?;
+
+                assert!(!result.promotion_redemptions.is_empty(), "{discount:?}");
+                assert_eq!(result.total, unit_result.total, "{discount:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn solver_compresses_lines_every_built_in_promotion_can_claim() -> TestResult {
+        let tagged = |price, tag, quantity| {
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&[tag]),
+                quantity,
+            )
+        };
+
+        let item_group = item_group_from_items([
+            tagged(100, "can", 200),
+            tagged(50, "crisps", 6),
+            tagged(300, "sandwich", 2),
+            tagged(150, "drink", 2),
+        ]);
+
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let promotions = [
+            promotion(DirectDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["can"])),
+                SimpleDiscount::PercentageOff(Percentage::from(0.1)),
+                PromotionBudget {
+                    redemption_limit: Some(150),
+                    monetary_limit: None,
+                },
+            )),
+            promotion(PositionalDiscountPromotion::new(
+                keys.insert(()),
+                Qualification::match_any(StringTagCollection::from_strs(&["crisps"])),
+                3,
+                smallvec![2],
+                SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+                PromotionBudget::unlimited(),
+            )),
+            promotion(MixAndMatchPromotion::new(
+                keys.insert(()),
+                vec![
+                    slot(
+                        &mut slot_keys,
+                        StringTagCollection::from_strs(&["sandwich"]),
+                        1,
+                        Some(1),
+                    ),
+                    slot(
+                        &mut slot_keys,
+                        StringTagCollection::from_strs(&["drink"]),
+                        1,
+                        Some(1),
+                    ),
+                ],
+                MixAndMatchDiscount::FixedTotal(Money::from_minor(350, GBP)),
+                PromotionBudget::unlimited(),
+            )),
+        ];
+
+        let promotion_refs: Vec<&dyn ILPPromotion> = promotions.iter().map(AsRef::as_ref).collect();
+
+        // Every built-in promotion models counts, so each product stays one line.
+        let lines = compress_lines(&promotion_refs, &item_group).ok_or("Expected lines")?;
+        let quantities: Vec<u32> = lines.iter().map(Item::quantity).collect();
+
+        assert_eq!(quantities, [200, 6, 2, 2]);
+
+        let result = ILPSolver::solve(&promotions, &item_group)?;
+
+        // 150 cans at 90 and 50 at 100, two 3-for-2s on crisps, two meal deals:
+        // 13500 + 5000 + 200 + 700
+        assert_eq!(result.total.to_minor_units(), 19_400);
+        assert_eq!(result.promotion_redemptions.len(), 150 + 6 + 4);
+        assert_eq!(
+            result.affected_items.len() + result.unaffected_items.len(),
+            200 + 6 + 2 + 2
+        );
+        assert_eq!(
+            result.total,
+            solve_per_unit(&promotions, &item_group)?.total,
+        );
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct HalfSolution;
+
+    impl Solution for HalfSolution {
+        fn status(&self) -> SolutionStatus {
+            SolutionStatus::TimeLimit
+        }
+
+        fn value(&self, _variable: Variable) -> f64 {
+            0.5
+        }
+    }
+
+    #[test]
+    fn feasibility_check_rejects_fractional_and_infeasible_solutions() {
+        let mut pb = ProblemVariables::new();
+        let x = pb.add(variable().binary());
+        let y = pb.add(variable().binary());
+
+        let mut check = FeasibilityCheck::new(&pb, &[]);
+
+        check.require_eq(x + y, 2.0);
+
+        assert!(check.is_satisfied_by(&SelectAllSolution));
+        assert!(!check.is_satisfied_by(&HalfSolution), "0.5 is not integral");
+
+        check.require_eq(Expression::from(x), 0.0);
+
+        assert!(!check.is_satisfied_by(&SelectAllSolution));
+    }
+
+    #[test]
+    fn zero_time_limit_without_a_solution_leaves_items_at_full_price() -> TestResult {
+        let item_group = item_group_from_items(test_items());
+        let promotions = [promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_all(),
+            SimpleDiscount::PercentageOff(Percentage::from(0.5)),
+            PromotionBudget::unlimited(),
+        ))];
+
+        // The greedy fallback has no time left for its own sub-problems.
+        let config = ILPSolverConfig::new().with_time_limit(Duration::ZERO);
+        let result =
+            ILPSolver::solve_with_config(&config, &promotions, &item_group, &mut NoopObserver)?;
+
+        assert!(!result.optimal, "a fallback result is never proven optimal");
+        assert_eq!(result.total.to_minor_units(), 600);
+        assert!(result.promotion_redemptions.is_empty());
+        assert_eq!(result.unaffected_items.len(), 3);
+
+        let unlimited = ILPSolver::solve(&promotions, &item_group)?;
+
+        assert!(unlimited.optimal);
+        assert_eq!(unlimited.total.to_minor_units(), 300);
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_stops_the_fallback_reserve_before_the_time_limit() {
+        assert_eq!(deadlines(&ILPSolverConfig::new()), (None, None));
+
+        let config = ILPSolverConfig::new()
+            .with_time_limit(Duration::from_millis(500))
+            .with_fallback_reserve(Duration::from_millis(200));
+
+        let (search, solve) = deadlines(&config);
+
+        assert_eq!(
+            search.zip(solve).map(|(search, solve)| solve - search),
+            Some(Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn fallback_applies_promotions_within_its_reserve() -> TestResult {
+        let item_group = item_group_from_items(test_items());
+        let promotions = [promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_all(),
+            SimpleDiscount::PercentageOff(Percentage::from(0.5)),
+            PromotionBudget::unlimited(),
+        ))];
+
+        // Reserving the whole budget leaves the search no time at all.
+        let time_limit = Duration::from_secs(60);
+        let config = ILPSolverConfig::new()
+            .with_time_limit(time_limit)
+            .with_fallback_reserve(time_limit);
+        let mut observer = CountingObserver::default();
+        let result =
+            ILPSolver::solve_with_config(&config, &promotions, &item_group, &mut observer)?;
+
+        assert!(!result.optimal, "a fallback result is never proven optimal");
+        assert_eq!(observer.promotion_variables, 0, "the search never started");
+        assert_eq!(result.total.to_minor_units(), 300);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn expired_time_limit_skips_building_the_formulation() -> TestResult {
+        let item_group = item_group_from_items(test_items());
+        let promotions = [promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_all(),
+            SimpleDiscount::PercentageOff(Percentage::from(0.5)),
+            PromotionBudget::unlimited(),
+        ))];
+
+        let config = ILPSolverConfig::new().with_time_limit(Duration::ZERO);
+        let mut observer = CountingObserver::default();
+        let result =
+            ILPSolver::solve_with_config(&config, &promotions, &item_group, &mut observer)?;
+
+        assert!(!result.optimal);
+        assert_eq!(observer.promotion_variables, 0);
+        assert_eq!(observer.promotion_constraints, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn greedy_fallback_starts_no_sub_problem_past_its_deadline() -> TestResult {
+        let tags = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+        let items: SmallVec<[Item<'_>; 10]> = (0..120_usize)
+            .map(|idx| {
+                let price = 100 + i64::try_from(idx * 37 % 450).unwrap_or_default();
+
+                Item::with_tags(
+                    ProductKey::default(),
+                    Money::from_minor(price, GBP),
+                    StringTagCollection::from_strs(&[tags[idx % 12], tags[(idx * 5 + 3) % 12]]),
+                )
+            })
+            .collect();
+        let item_group = ItemGroup::new(items, GBP);
+
+        // Overlapping deals on every pair of neighbouring tags, each of which the
+        // fallback would otherwise solve a sub-problem for.
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let promotions: Vec<_> = tags
+            .iter()
+            .zip(tags.iter().cycle().skip(1))
+            .flat_map(|(&first, &second)| {
+                let qualification =
+                    Qualification::match_any(StringTagCollection::from_strs(&[first, second]));
+
+                [
+                    promotion(PositionalDiscountPromotion::new(
+                        keys.insert(()),
+                        qualification.clone(),
+                        3,
+                        smallvec![2],
+                        SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+                        PromotionBudget::unlimited(),
+                    )),
+                    promotion(PositionalDiscountPromotion::new(
+                        keys.insert(()),
+                        qualification,
+                        2,
+                        smallvec![1],
+                        SimpleDiscount::PercentageOff(Percentage::from(0.6)),
+                        PromotionBudget::unlimited(),
+                    )),
+                ]
+            })
+            .collect();
+
+        let promotion_refs: Vec<&dyn ILPPromotion> = promotions.iter().map(AsRef::as_ref).collect();
+        let config = ILPSolverConfig::new().with_time_limit(Duration::from_secs(60));
+
+        // Backends only check their time limit between steps of their own search,
+        // so how far a solve overruns is down to them. What the solver controls is
+        // that nothing new starts once its deadline has passed.
+        let result = greedy_fallback(&config, &promotion_refs, &item_group, Some(Instant::now()))?;
+
+        assert!(!result.optimal);
+        assert!(result.promotion_redemptions.is_empty());
+        assert_eq!(result.unaffected_items.len(), item_group.len());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,299 @@
+//! Retailer Objectives
+//!
+//! The customer always gets the lowest basket price. Among the solutions with
+//! that price, a [`RetailerObjective`] chooses the one that suits the retailer
+//! best, such as the one where suppliers fund most of the discount or the one
+//! with the fewest lines to claim back from suppliers.
+//!
+//! Each goal is minimised in its own pass with the basket total, and every
+//! earlier goal, fixed at its optimum, so goals are pursued lexicographically.
+//! Weighted goals are combined into a single pass instead. Promotion-defined
+//! and [`TieBreak`](super::TieBreak) preferences are applied last.
+
+use decimal_percentage::Percentage;
+use good_lp::{Expression, IntoAffineExpression};
+use rustc_hash::FxHashMap;
+use rusty_money::{Money, iso::Currency};
+use smallvec::SmallVec;
+
+use crate::{
+    discounts::percent_of_minor,
+    items::groups::ItemGroup,
+    products::ProductKey,
+    promotions::PromotionKey,
+    solvers::{
+        SolverError, SolverResult,
+        ilp::{ILPSolverConfig, i64_to_f64_exact, promotions::PromotionInstances, tie_break},
+    },
+};
+
+/// Basis points in a whole, used to keep funding coefficients integral.
+const BASIS_POINTS: i64 = 10_000;
+
+/// A goal pursued among solutions with the optimal customer price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RetailerGoal {
+    /// Maximise the retailer's margin: the basket total, less product costs,
+    /// plus the share of discounts funded by suppliers.
+    ///
+    /// The basket total and product costs are the same in every candidate
+    /// solution, so this maximises the supplier-funded share of the discounts.
+    MaximiseMargin,
+
+    /// Minimise the number of items redeemed through supplier-funded promotions,
+    /// each a line on a supplier claim.
+    MinimiseSupplierClaims,
+}
+
+/// Retailer goals, with the supplier funding and product cost data they use.
+#[derive(Debug, Clone, Default)]
+pub struct RetailerObjective {
+    goals: SmallVec<[(RetailerGoal, u32); 2]>,
+    weighted: bool,
+    supplier_funding: FxHashMap<PromotionKey, Percentage>,
+    product_costs: FxHashMap<ProductKey, i64>,
+}
+
+impl RetailerObjective {
+    /// Pursue `goals` in order: each goal only chooses between solutions that are
+    /// optimal for the goals before it.
+    #[must_use]
+    pub fn lexicographic(goals: impl IntoIterator<Item = RetailerGoal>) -> Self {
+        Self {
+            goals: goals.into_iter().map(|goal| (goal, 1)).collect(),
+            weighted: false,
+            ..Self::default()
+        }
+    }
+
+    /// Pursue `goals` together, minimising the sum of each goal's objective
+    /// multiplied by its weight.
+    ///
+    /// A minor unit of margin and an item on a supplier claim count the same
+    /// before weighting.
+    #[must_use]
+    pub fn weighted(goals: impl IntoIterator<Item = (RetailerGoal, u32)>) -> Self {
+        Self {
+            goals: goals.into_iter().collect(),
+            weighted: true,
+            ..Self::default()
+        }
+    }
+
+    /// Set the share of a promotion's discount that its supplier funds.
+    ///
+    /// Promotions without a share are funded by the retailer.
+    #[must_use]
+    pub fn with_supplier_funding(mut self, promotion_key: PromotionKey, share: Percentage) -> Self {
+        self.supplier_funding.insert(promotion_key, share);
+        self
+    }
+
+    /// Set what one unit of a product costs the retailer.
+    ///
+    /// Products without a cost are treated as costing nothing.
+    #[must_use]
+    pub fn with_product_cost(mut self, product_key: ProductKey, cost: Money<'_, Currency>) -> Self {
+        self.product_costs
+            .insert(product_key, cost.to_minor_units());
+        self
+    }
+
+    /// Return the goals, with their weights.
+    pub fn goals(&self) -> &[(RetailerGoal, u32)] {
+        &self.goals
+    }
+
+    /// Return whether the goals are combined by weight rather than in order.
+    pub fn is_weighted(&self) -> bool {
+        self.weighted
+    }
+
+    /// The retailer's margin on a solved basket, in minor units.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if an item in the result is not in `item_group` or
+    /// a funded share cannot be calculated.
+    pub fn margin_minor(
+        &self,
+        item_group: &ItemGroup<'_>,
+        result: &SolverResult<'_>,
+    ) -> Result<i64, SolverError> {
+        let mut margin = result.total.to_minor_units();
+
+        for item in item_group.iter() {
+            let cost = self
+                .product_costs
+                .get(&item.product())
+                .copied()
+                .unwrap_or(0);
+
+            margin = margin.saturating_sub(cost.saturating_mul(i64::from(item.quantity())));
+        }
+
+        let mut discounts: FxHashMap<PromotionKey, i64> = FxHashMap::default();
+
+        for redemption in &result.promotion_redemptions {
+            let discount = redemption
+                .original_price
+                .to_minor_units()
+                .saturating_sub(redemption.final_price.to_minor_units());
+            let entry = discounts.entry(redemption.promotion_key).or_insert(0);
+
+            *entry = entry.saturating_add(discount);
+        }
+
+        for (promotion_key, discount) in discounts {
+            if let Some(share) = self.supplier_funding.get(&promotion_key) {
+                margin = margin.saturating_add(percent_of_minor(share, discount)?);
+            }
+        }
+
+        Ok(margin)
+    }
+
+    /// Supplier-funded share of a promotion's discount, in basis points.
+    fn funding_basis_points(&self, promotion_key: PromotionKey) -> Result<i64, SolverError> {
+        self.supplier_funding
+            .get(&promotion_key)
+            .map_or(Ok(0), |share| Ok(percent_of_minor(share, BASIS_POINTS)?))
+    }
+
+    /// Objective for one goal, to be minimised.
+    ///
+    /// Objectives are scaled by [`BASIS_POINTS`] so funded shares stay integral:
+    /// a minor unit of margin and an item on a claim both weigh one whole.
+    fn goal_objective(
+        &self,
+        goal: RetailerGoal,
+        promotion_instances: &PromotionInstances<'_>,
+        item_group: &ItemGroup<'_>,
+    ) -> Result<Expression, SolverError> {
+        let mut expr = Expression::default();
+
+        for instance in promotion_instances.iter() {
+            let basis_points = self.funding_basis_points(instance.key())?;
+
+            if basis_points == 0 {
+                continue;
+            }
+
+            match goal {
+                RetailerGoal::MaximiseMargin => {
+                    let coeff = i64_to_f64_exact(basis_points)
+                        .ok_or(SolverError::MinorUnitsNotRepresentable(basis_points))?;
+
+                    expr -= instance.discount_expression(item_group)? * coeff;
+                }
+                RetailerGoal::MinimiseSupplierClaims => {
+                    let coeff = i64_to_f64_exact(BASIS_POINTS)
+                        .ok_or(SolverError::MinorUnitsNotRepresentable(BASIS_POINTS))?;
+
+                    for item_idx in 0..item_group.len() {
+                        expr += instance.add_item_presence_term(Expression::default(), item_idx)
+                            * coeff;
+                    }
+                }
+            }
+        }
+
+        Ok(expr)
+    }
+}
+
+/// Objectives minimised, in order, once the basket total is fixed at its
+/// optimum: the retailer's goals, then the tie-break preferences.
+///
+/// Objectives without any terms are left out.
+///
+/// # Errors
+///
+/// Returns [`SolverError`] if an objective cannot be built.
+pub(crate) fn secondary_objectives(
+    config: &ILPSolverConfig,
+    promotion_instances: &PromotionInstances<'_>,
+    item_group: &ItemGroup<'_>,
+) -> Result<SmallVec<[Expression; 3]>, SolverError> {
+    let mut objectives = SmallVec::new();
+
+    if let Some(retailer) = config.retailer_objective() {
+        let mut combined = Expression::default();
+
+        for &(goal, weight) in &retailer.goals {
+            let objective = retailer.goal_objective(goal, promotion_instances, item_group)?;
+
+            if retailer.weighted {
+                combined += objective * f64::from(weight);
+            } else {
+                objectives.push(objective);
+            }
+        }
+
+        if retailer.weighted {
+            objectives.push(combined);
+        }
+    }
+
+    objectives.push(tie_break::secondary_objective(
+        promotion_instances,
+        item_group,
+        config.tie_break(),
+    )?);
+
+    objectives.retain(|objective: &mut Expression| {
+        IntoAffineExpression::linear_coefficients(&*objective).any(|(_, coeff)| coeff != 0.0)
+    });
+
+    Ok(objectives)
+}
+
+#[cfg(test)]
+mod tests {
+    use rusty_money::iso::GBP;
+    use slotmap::SlotMap;
+    use smallvec::smallvec;
+
+    use crate::{items::Item, promotions::redemptions::PromotionRedemption};
+
+    use super::*;
+
+    #[test]
+    fn margin_subtracts_costs_and_adds_supplier_funding() -> testresult::TestResult {
+        let mut products = SlotMap::<ProductKey, ()>::with_key();
+        let mut promotions = SlotMap::<PromotionKey, ()>::with_key();
+        let product = products.insert(());
+        let funded = promotions.insert(());
+
+        let item_group = ItemGroup::new(
+            smallvec![
+                Item::new(product, Money::from_minor(500, GBP)),
+                Item::new(product, Money::from_minor(500, GBP)),
+            ],
+            GBP,
+        );
+        let result = SolverResult {
+            affected_items: smallvec![0],
+            unaffected_items: smallvec![1],
+            total: Money::from_minor(900, GBP),
+            promotion_redemptions: smallvec![PromotionRedemption {
+                promotion_key: funded,
+                item_idx: 0,
+                redemption_idx: 0,
+                original_price: Money::from_minor(500, GBP),
+                final_price: Money::from_minor(400, GBP),
+            }],
+            optimal: true,
+        };
+
+        let objective = RetailerObjective::lexicographic([RetailerGoal::MaximiseMargin])
+            .with_supplier_funding(funded, Percentage::from(0.5))
+            .with_product_cost(product, Money::from_minor(300, GBP));
+
+        // 900 paid, 600 cost, 50 of the 100 discount claimed back.
+        assert_eq!(objective.margin_minor(&item_group, &result)?, 350);
+
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use std::any::Any;
 use good_lp::{Expression, Variable};
 use petgraph::graph::NodeIndex;
 
-use crate::{graph::PromotionLayerKey, promotions::PromotionKey};
+use crate::{graph::PromotionLayerKey, items::groups::ItemGroup, promotions::PromotionKey};
 
 /// Observer trait for capturing ILP formulation as it's built.
 ///
@@ -22,6 +22,14 @@ use crate::{graph::PromotionLayerKey, promotions::PromotionKey};
 /// When no observer is provided (the default case), the solver uses a `NoopObserver`
 /// and the observer calls are optimized away via monomorphization.
 pub trait ILPObserver: Send + Sync + Any {
+    /// Called with the item group a formulation is built over, before any of its
+    /// variables.
+    ///
+    /// Item indexes passed to the other callbacks index into this group. When
+    /// identical units are solved as lines with a quantity, it holds those lines
+    /// rather than the units passed to the solver.
+    fn on_item_group(&mut self, _item_group: &ItemGroup<'_>) {}
+
     /// Called when a presence variable is created for an item.
     ///
     /// Presence variables represent the baseline "buy at full price" option
@@ -76,6 +84,16 @@ pub trait ILPObserver: Send + Sync + Any {
     ) {
     }
 
+    /// Called for every variable once the formulation is built, with its bounds.
+    ///
+    /// # Parameters
+    ///
+    /// - `var`: The decision variable
+    /// - `min`: Lower bound, which may be negative infinity
+    /// - `max`: Upper bound, which may be infinity
+    /// - `integer`: Whether the variable only takes integer values
+    fn on_variable_bounds(&mut self, _var: Variable, _min: f64, _max: f64, _integer: bool) {}
+
     /// Called when a term is added to the objective function.
     ///
     /// # Parameters
@@ -84,6 +102,16 @@ pub trait ILPObserver: Send + Sync + Any {
     /// - `coefficient`: Coefficient in minor units (e.g., pence, cents)
     fn on_objective_term(&mut self, _var: Variable, _coefficient: f64) {}
 
+    /// Called with each variable's value in the solution the result is read from.
+    ///
+    /// Not called when a time-limited solve falls back to the greedy solver.
+    ///
+    /// # Parameters
+    ///
+    /// - `var`: The decision variable
+    /// - `value`: Its solved value
+    fn on_variable_value(&mut self, _var: Variable, _value: f64) {}
+
     /// Called when an exclusivity constraint is added for an item.
     ///
     /// Exclusivity constraints ensure each item is purchased exactly once:
@@ -1,20 +1,27 @@
 //! Direct Discount Promotions ILP
 
-use good_lp::{Expression, Solution, Variable, variable};
+use good_lp::{Expression, Solution, Variable};
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 
 use rusty_money::Money;
 
 use crate::{
-    items::groups::ItemGroup,
-    promotions::{PromotionKey, redemptions::PromotionRedemption, types::DirectDiscountPromotion},
+    items::{Item, groups::ItemGroup},
+    promotions::{
+        PromotionKey,
+        explanations::{MissingRequirement, PromotionProgress},
+        redemptions::PromotionRedemption,
+        types::{DirectDiscountPromotion, PromotionType},
+    },
     solvers::{
         SolverError,
         ilp::{
             BINARY_THRESHOLD, ILPObserver, i64_to_f64_exact,
             promotions::{ILPPromotion, ILPPromotionVars, PromotionVars},
+            solved_count,
             state::ILPState,
+            unit_count_variable,
         },
     },
 };
@@ -180,26 +187,24 @@ impl ILPPromotionVars for DirectDiscountPromotionVars {
         let mut redemptions = SmallVec::new();
         let currency = item_group.currency();
 
-        for item_idx in 0..item_group.len() {
+        for &(item_idx, var) in &self.item_participation {
             let item = item_group.get_item(item_idx)?;
-
-            if !self.is_item_participating(solution, item_idx) {
-                continue;
-            }
-
             let discounted_minor = self.discounted_minor_for_item(item_idx)?;
 
-            // For DirectDiscountPromotion, each item gets its own unique redemption_idx
-            let redemption_idx = *next_redemption_idx;
-            *next_redemption_idx += 1;
-
-            redemptions.push(PromotionRedemption {
-                promotion_key,
-                item_idx,
-                redemption_idx,
-                original_price: *item.price(),
-                final_price: Money::from_minor(discounted_minor, currency),
-            });
+            // A line of identical units is redeemed once per discounted unit, and
+            // each unit gets its own unique redemption_idx.
+            for _ in 0..solved_count(solution, var) {
+                let redemption_idx = *next_redemption_idx;
+                *next_redemption_idx += 1;
+
+                redemptions.push(PromotionRedemption {
+                    promotion_key,
+                    item_idx,
+                    redemption_idx,
+                    original_price: *item.price(),
+                    final_price: Money::from_minor(discounted_minor, currency),
+                });
+            }
         }
 
         Ok(redemptions)
@@ -223,6 +228,36 @@ impl ILPPromotion for DirectDiscountPromotion<'_> {
             .any(|item| qualification.matches(item.tags()))
     }
 
+    fn is_item_eligible(&self, item: &Item<'_>) -> bool {
+        self.qualification().matches(item.tags())
+    }
+
+    fn progress(&self, item_group: &ItemGroup<'_>) -> PromotionProgress {
+        if self.budget().is_exhausted() {
+            return PromotionProgress {
+                missing: Some(MissingRequirement::BudgetExhausted),
+                ..PromotionProgress::default()
+            };
+        }
+
+        let has_qualifying_item = item_group
+            .iter()
+            .any(|item| self.qualification().matches(item.tags()));
+
+        PromotionProgress {
+            missing: (!has_qualifying_item).then_some(MissingRequirement::QualifyingItems(1)),
+            ..PromotionProgress::default()
+        }
+    }
+
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        Some(PromotionType::DirectDiscount(self))
+    }
+
+    fn supports_quantities(&self) -> bool {
+        true
+    }
+
     fn add_variables(
         &self,
         item_group: &ItemGroup<'_>,
@@ -255,7 +290,8 @@ impl ILPPromotion for DirectDiscountPromotion<'_> {
             };
 
             // Create a binary decision variable for this item: should this promotion apply to it?
-            let participation_var = state.problem_variables_mut().add(variable().binary());
+            // A line of identical units instead counts how many of them the promotion applies to.
+            let participation_var = state.problem_variables_mut().add(unit_count_variable(item));
 
             // Persist the variable so we can later mark items as participating from the solved model.
             item_participation.push((item_idx, participation_var));
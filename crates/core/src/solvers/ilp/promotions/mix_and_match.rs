@@ -13,18 +13,21 @@ use rusty_money::Money;
 
 use crate::{
     discounts::percent_of_minor,
-    items::groups::ItemGroup,
+    items::{Item, groups::ItemGroup},
     promotions::{
         PromotionKey,
+        explanations::{MissingRequirement, PromotionProgress},
         redemptions::PromotionRedemption,
-        types::{MixAndMatchDiscount, MixAndMatchPromotion},
+        types::{MixAndMatchDiscount, MixAndMatchPromotion, MixAndMatchSlot, PromotionType},
     },
     solvers::{
         SolverError,
         ilp::{
             BINARY_THRESHOLD, ILPObserver, i64_to_f64_exact,
             promotions::{ILPPromotion, ILPPromotionVars, PromotionVars},
+            solved_count,
             state::ILPState,
+            unit_count_variable,
         },
     },
 };
@@ -55,6 +58,9 @@ pub struct MixAndMatchVars {
     /// Optional bundle-formed indicator (variable-arity bundles).
     bundle_formed: Option<Variable>,
 
+    /// Bundles of an amount-off-total promotion, each with its own discount.
+    amount_off_bundles: Vec<AmountOffBundle>,
+
     /// Target variables for cheapest-item discounts.
     target_vars: Vec<Option<Variable>>,
 
@@ -77,6 +83,19 @@ pub struct MixAndMatchVars {
     monetary_limit_minor: Option<i64>,
 }
 
+/// Solver variables for one bundle of an amount-off-total promotion.
+#[derive(Debug)]
+struct AmountOffBundle {
+    /// Whether the bundle is formed.
+    formed: Variable,
+
+    /// Item selection variables as `(slot, item, variable)`, in slot order.
+    items: SmallVec<[(usize, usize, Variable); 10]>,
+
+    /// Discount taken off the bundle's total.
+    discount: Variable,
+}
+
 impl MixAndMatchVars {
     fn selected_exprs(&self) -> SmallVec<[Expression; 10]> {
         let mut exprs: SmallVec<[Expression; 10]> = SmallVec::with_capacity(self.target_vars.len());
@@ -97,18 +116,21 @@ impl MixAndMatchVars {
     fn add_model_constraints(
         &self,
         promotion_key: PromotionKey,
+        item_group: &ItemGroup<'_>,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
-    ) {
+    ) -> Result<(), SolverError> {
         if self.slot_vars.is_empty() || !self.has_bundle_control_vars() {
-            return;
+            return Ok(());
         }
 
-        self.add_slot_constraints(promotion_key, state, observer);
+        self.add_slot_constraints(promotion_key, item_group, state, observer)?;
 
         if self.needs_target_constraints() {
-            self.add_target_constraints(promotion_key, state, observer);
+            self.add_target_constraints(promotion_key, item_group, state, observer)?;
         }
+
+        Ok(())
     }
 
     fn has_bundle_control_vars(&self) -> bool {
@@ -122,9 +144,10 @@ impl MixAndMatchVars {
     fn add_slot_constraints(
         &self,
         promotion_key: PromotionKey,
+        item_group: &ItemGroup<'_>,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
-    ) {
+    ) -> Result<(), SolverError> {
         for (slot_idx, slot_vars) in self.slot_vars.iter().enumerate() {
             let slot_sum: Expression = slot_vars.iter().map(|(_, var)| *var).sum();
             let (min, max) = self.slot_bounds.get(slot_idx).copied().unwrap_or((0, None));
@@ -140,6 +163,13 @@ impl MixAndMatchVars {
                     y_bundle,
                 );
             } else if let Some(bundle_formed) = self.bundle_formed {
+                // Without a maximum, a slot can still only hold items while the
+                // bundle is formed, so cap it at its eligible unit count.
+                let max = match max {
+                    Some(max) => max,
+                    None => unit_count(item_group, slot_vars.iter().map(|&(idx, _)| idx))?,
+                };
+
                 Self::add_variable_arity_slot_constraints(
                     promotion_key,
                     state,
@@ -151,6 +181,8 @@ impl MixAndMatchVars {
                 );
             }
         }
+
+        Ok(())
     }
 
     fn add_fixed_arity_slot_constraints(
@@ -183,7 +215,7 @@ impl MixAndMatchVars {
         observer: &mut dyn ILPObserver,
         slot_sum: Expression,
         min: usize,
-        max: Option<usize>,
+        max: usize,
         bundle_formed: Variable,
     ) {
         let min_i32 = i32_from_usize(min);
@@ -192,20 +224,12 @@ impl MixAndMatchVars {
         observer.on_promotion_constraint(promotion_key, "Slot min (formed)", &min_expr, ">=", 0.0);
         state.add_geq_constraint(min_expr, 0.0);
 
-        if let Some(max) = max {
-            let max_i32 = i32_from_usize(max);
-            let max_expr = slot_sum.clone() - max_i32 * bundle_formed;
+        let max_i32 = i32_from_usize(max);
+        let max_expr = slot_sum.clone() - max_i32 * bundle_formed;
 
-            observer.on_promotion_constraint(
-                promotion_key,
-                "Slot max (formed)",
-                &max_expr,
-                "<=",
-                0.0,
-            );
+        observer.on_promotion_constraint(promotion_key, "Slot max (formed)", &max_expr, "<=", 0.0);
 
-            state.add_leq_constraint(max_expr, 0.0);
-        }
+        state.add_leq_constraint(max_expr, 0.0);
 
         // If slot has enough items, bundle can be formed.
         let formed_expr = Expression::from(bundle_formed) - slot_sum / min_i32;
@@ -218,9 +242,10 @@ impl MixAndMatchVars {
     fn add_target_constraints(
         &self,
         promotion_key: PromotionKey,
+        item_group: &ItemGroup<'_>,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
-    ) {
+    ) -> Result<(), SolverError> {
         let selected_exprs = self.selected_exprs();
         let mut target_sum = Expression::default();
 
@@ -251,19 +276,22 @@ impl MixAndMatchVars {
                 promotion_key,
                 state,
                 observer,
-                &selected_exprs,
                 target_sum,
                 y_bundle,
             );
         } else if let Some(bundle_formed) = self.bundle_formed {
-            Self::add_variable_arity_target_constraints(
+            self.add_variable_arity_target_constraints(
                 promotion_key,
+                item_group,
                 state,
                 observer,
+                &selected_exprs,
                 target_sum,
                 bundle_formed,
-            );
+            )?;
         }
+
+        Ok(())
     }
 
     fn add_fixed_arity_target_constraints(
@@ -271,27 +299,33 @@ impl MixAndMatchVars {
         promotion_key: PromotionKey,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
-        selected_exprs: &[Expression],
         target_sum: Expression,
         y_bundle: Variable,
     ) {
-        let mut prefix_selected = Expression::default();
-        let mut prefix_targets = Expression::default();
+        // Every bundle's target is its cheapest item, so each slot's selected items
+        // up to any price can fill at most the slot's minimum per target among them.
+        for (slot_idx, &(min, _max)) in self.slot_bounds.iter().enumerate() {
+            let Some(slot_vars) = self.slot_vars.get(slot_idx) else {
+                continue;
+            };
 
-        let k_total = i32_from_usize(self.bundle_size);
+            let min_i32 = i32_from_usize(min);
+            let mut prefix_selected = Expression::default();
+            let mut prefix_targets = Expression::default();
 
-        if k_total > 0 {
             for &(item_idx, _price) in &self.sorted_items {
-                let selected_expr = selected_exprs.get(item_idx).cloned().unwrap_or_default();
-
-                prefix_selected += selected_expr;
-
                 if let Some(target_var) = self.target_vars.get(item_idx).and_then(|v| *v) {
                     prefix_targets += target_var;
                 }
 
-                let expr =
-                    prefix_targets.clone() - (prefix_selected.clone() - (k_total - 1) * y_bundle);
+                let Some(&(_, slot_var)) = slot_vars.iter().find(|(idx, _)| *idx == item_idx)
+                else {
+                    continue;
+                };
+
+                prefix_selected += slot_var;
+
+                let expr = min_i32 * prefix_targets.clone() - prefix_selected.clone();
 
                 observer.on_promotion_constraint(
                     promotion_key,
@@ -301,10 +335,7 @@ impl MixAndMatchVars {
                     0.0,
                 );
 
-                state.add_geq_constraint(
-                    prefix_targets.clone() - (prefix_selected.clone() - (k_total - 1) * y_bundle),
-                    0.0,
-                );
+                state.add_geq_constraint(expr, 0.0);
             }
         }
 
@@ -321,21 +352,250 @@ impl MixAndMatchVars {
         state.add_eq_constraint(target_count_expr, 0.0);
     }
 
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Target constraints need the bundle's selections and its indicator"
+    )]
     fn add_variable_arity_target_constraints(
+        &self,
         promotion_key: PromotionKey,
+        item_group: &ItemGroup<'_>,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
+        selected_exprs: &[Expression],
         target_sum: Expression,
         bundle_formed: Variable,
-    ) {
+    ) -> Result<(), SolverError> {
+        // The target must come no later than any selected item in price order,
+        // so it is the bundle's cheapest item. A line may have all of its units
+        // selected once a target precedes it.
+        let mut prefix_targets = Expression::default();
+
+        for &(item_idx, _price) in &self.sorted_items {
+            if let Some(target_var) = self.target_vars.get(item_idx).and_then(|v| *v) {
+                prefix_targets += target_var;
+            }
+
+            let quantity = f64::from(item_group.get_item(item_idx)?.quantity());
+            let selected_expr = selected_exprs.get(item_idx).cloned().unwrap_or_default();
+            let expr = prefix_targets.clone() * quantity - selected_expr;
+
+            observer.on_promotion_constraint(
+                promotion_key,
+                "cheapest selected (formed)",
+                &expr,
+                ">=",
+                0.0,
+            );
+
+            state.add_geq_constraint(expr, 0.0);
+        }
+
         let expr = target_sum - bundle_formed;
 
         observer.on_promotion_constraint(promotion_key, "target count (formed)", &expr, "=", 0.0);
 
         state.add_eq_constraint(expr, 0.0);
+
+        Ok(())
+    }
+
+    /// Bound each bundle's discount by the amount per bundle and by the bundle's own value.
+    ///
+    /// Bounding the combined discount by the combined value would let a dear bundle make
+    /// up for a cheap one, overstating the discount whenever a bundle is worth less than
+    /// the amount.
+    fn add_amount_off_constraints(
+        &self,
+        promotion_key: PromotionKey,
+        item_group: &ItemGroup<'_>,
+        state: &mut ILPState,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<(), SolverError> {
+        let MixAndMatchRuntimeDiscount::AmountOffTotal(amount) = self.runtime_discount else {
+            return Ok(());
+        };
+
+        let amount_coeff =
+            i64_to_f64_exact(amount).ok_or(SolverError::MinorUnitsNotRepresentable(amount))?;
+
+        if self.amount_off_bundles.len() > 1 {
+            self.add_amount_off_bundle_links(promotion_key, state, observer);
+        }
+
+        for bundle in &self.amount_off_bundles {
+            let per_bundle_expr = Expression::from(bundle.discount) - amount_coeff * bundle.formed;
+
+            observer.on_promotion_constraint(
+                promotion_key,
+                "Amount off per bundle",
+                &per_bundle_expr,
+                "<=",
+                0.0,
+            );
+
+            state.add_leq_constraint(per_bundle_expr, 0.0);
+
+            let mut value_expr = Expression::from(bundle.discount);
+            let mut max_value = 0.0;
+
+            for &(_slot_idx, item_idx, var) in &bundle.items {
+                let item = item_group.get_item(item_idx)?;
+                let price_minor = item.price().to_minor_units();
+                let coeff = i64_to_f64_exact(price_minor)
+                    .ok_or(SolverError::MinorUnitsNotRepresentable(price_minor))?;
+
+                value_expr -= coeff * var;
+                max_value += coeff * f64::from(item.quantity());
+            }
+
+            observer.on_promotion_constraint(
+                promotion_key,
+                "Amount off value",
+                &value_expr,
+                "<=",
+                0.0,
+            );
+
+            state.add_leq_constraint(value_expr.clone(), 0.0);
+
+            // Minimising the cost already takes the full discount. Under a monetary
+            // budget it must not take less, so a binary picks which bound it meets.
+            if self.monetary_limit_minor.is_some() {
+                let clamped = state.problem_variables_mut().add(variable().binary());
+
+                observer.on_auxiliary_variable(
+                    promotion_key,
+                    clamped,
+                    "Amount off clamped",
+                    None,
+                    None,
+                );
+
+                // At least the amount per bundle, unless clamped.
+                let full_expr = Expression::from(bundle.discount) - amount_coeff * bundle.formed
+                    + amount_coeff * clamped;
+
+                observer.on_promotion_constraint(
+                    promotion_key,
+                    "Amount off full",
+                    &full_expr,
+                    ">=",
+                    0.0,
+                );
+
+                state.add_geq_constraint(full_expr, 0.0);
+
+                // At least the bundle's value, when clamped.
+                let clamped_expr = value_expr + max_value - max_value * clamped;
+
+                observer.on_promotion_constraint(
+                    promotion_key,
+                    "Amount off clamped",
+                    &clamped_expr,
+                    ">=",
+                    0.0,
+                );
+
+                state.add_geq_constraint(clamped_expr, 0.0);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tie the per-bundle selections of an amount-off-total promotion to the slots.
+    ///
+    /// Each formed bundle fills every slot, each selected item joins exactly one
+    /// bundle, and bundles are formed in order so equivalent solutions are not
+    /// explored twice.
+    fn add_amount_off_bundle_links(
+        &self,
+        promotion_key: PromotionKey,
+        state: &mut ILPState,
+        observer: &mut dyn ILPObserver,
+    ) {
+        if let Some(y_bundle) = self.y_bundle {
+            let formed_sum: Expression = self
+                .amount_off_bundles
+                .iter()
+                .map(|bundle| bundle.formed)
+                .sum();
+
+            let expr = Expression::from(y_bundle) - formed_sum;
+
+            observer.on_promotion_constraint(promotion_key, "Amount off bundles", &expr, "=", 0.0);
+
+            state.add_eq_constraint(expr, 0.0);
+        }
+
+        for pair in self.amount_off_bundles.windows(2) {
+            let [earlier, later] = pair else {
+                continue;
+            };
+
+            let expr = Expression::from(earlier.formed) - later.formed;
+
+            observer.on_promotion_constraint(
+                promotion_key,
+                "Amount off bundle order",
+                &expr,
+                ">=",
+                0.0,
+            );
+
+            state.add_geq_constraint(expr, 0.0);
+        }
+
+        for bundle in &self.amount_off_bundles {
+            for (slot_idx, &(min, _max)) in self.slot_bounds.iter().enumerate() {
+                let slot_sum: Expression = bundle
+                    .items
+                    .iter()
+                    .filter(|&&(item_slot, _, _)| item_slot == slot_idx)
+                    .map(|&(_, _, var)| var)
+                    .sum();
+
+                let expr = slot_sum - i32_from_usize(min) * bundle.formed;
+
+                observer.on_promotion_constraint(
+                    promotion_key,
+                    "Amount off bundle slot",
+                    &expr,
+                    "=",
+                    0.0,
+                );
+
+                state.add_eq_constraint(expr, 0.0);
+            }
+        }
+
+        let slot_selections = self.slot_vars.iter().flatten().enumerate();
+
+        for (position, &(_item_idx, var)) in slot_selections {
+            let bundle_sum: Expression = self
+                .amount_off_bundles
+                .iter()
+                .filter_map(|bundle| bundle.items.get(position))
+                .map(|&(_, _, bundle_var)| bundle_var)
+                .sum();
+
+            let expr = Expression::from(var) - bundle_sum;
+
+            observer.on_promotion_constraint(
+                promotion_key,
+                "Amount off bundle item",
+                &expr,
+                "=",
+                0.0,
+            );
+
+            state.add_eq_constraint(expr, 0.0);
+        }
     }
 
     /// Add budget constraints for mix-and-match promotions
+    #[expect(clippy::too_many_lines, reason = "Budget terms for each discount type")]
     pub fn add_budget_constraints(
         &self,
         item_group: &ItemGroup<'_>,
@@ -408,9 +668,36 @@ impl MixAndMatchVars {
                         discount_expr += *target_var * coeff;
                     }
                 }
-                _ => {
+                MixAndMatchRuntimeDiscount::AmountOffTotal(_) => {
+                    // The amount taken off each bundle is modelled directly.
+                    for bundle in &self.amount_off_bundles {
+                        discount_expr += bundle.discount;
+                    }
+                }
+                MixAndMatchRuntimeDiscount::FixedTotal(bundle_price) => {
+                    // The selected items' value, less the fixed price of each bundle.
+                    for slot in &self.slot_vars {
+                        for &(item_idx, var) in slot {
+                            let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
+                            let full_minor = item.price().to_minor_units();
+                            let coeff = i64_to_f64_exact(full_minor)
+                                .ok_or(SolverError::MinorUnitsNotRepresentable(full_minor))?;
+
+                            discount_expr += var * coeff;
+                        }
+                    }
+
+                    let coeff = i64_to_f64_exact(bundle_price)
+                        .ok_or(SolverError::MinorUnitsNotRepresentable(bundle_price))?;
+
+                    if let Some(bundles) = self.y_bundle.or(self.bundle_formed) {
+                        discount_expr -= bundles * coeff;
+                    }
+                }
+                MixAndMatchRuntimeDiscount::PercentAllItems(_)
+                | MixAndMatchRuntimeDiscount::AmountOffEachItem(_)
+                | MixAndMatchRuntimeDiscount::FixedPriceEachItem(_) => {
                     // Iterate over all slot variables to compute total discount.
-                    // For bundle-total discounts this remains a conservative estimate.
                     for slot in &self.slot_vars {
                         for &(item_idx, var) in slot {
                             let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
@@ -498,7 +785,8 @@ impl ILPPromotionVars for MixAndMatchVars {
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
     ) -> Result<(), SolverError> {
-        self.add_model_constraints(promotion_key, state, observer);
+        self.add_model_constraints(promotion_key, item_group, state, observer)?;
+        self.add_amount_off_constraints(promotion_key, item_group, state, observer)?;
         self.add_budget_constraints(item_group, state, observer)
     }
 
@@ -523,7 +811,6 @@ impl ILPPromotionVars for MixAndMatchVars {
             return Ok(SmallVec::new());
         }
 
-        let discounts = calculate_discounts_for_vars(solution, self, item_group)?;
         let currency = item_group.currency();
 
         let mut redemptions = SmallVec::new();
@@ -532,13 +819,10 @@ impl ILPPromotionVars for MixAndMatchVars {
             let redemption_idx = *next_redemption_idx;
             *next_redemption_idx += 1;
 
-            for item_idx in bundle {
-                let item = item_group.get_item(item_idx)?;
-                let original_minor = item.price().to_minor_units();
+            let final_prices = bundle_final_prices(self.runtime_discount, item_group, &bundle)?;
 
-                let final_minor = discounts
-                    .get(&item_idx)
-                    .map_or(original_minor, |(_, final_minor)| *final_minor);
+            for (item_idx, final_minor) in bundle.into_iter().zip(final_prices) {
+                let item = item_group.get_item(item_idx)?;
 
                 redemptions.push(PromotionRedemption {
                     promotion_key,
@@ -564,6 +848,31 @@ fn i32_from_usize(value: usize) -> i32 {
     i32::try_from(value).unwrap_or(i32::MAX)
 }
 
+/// Number of units across the item group lines at `item_indices`.
+fn unit_count(
+    item_group: &ItemGroup<'_>,
+    item_indices: impl IntoIterator<Item = usize>,
+) -> Result<usize, SolverError> {
+    let mut units: usize = 0;
+
+    for item_idx in item_indices {
+        let quantity = item_group.get_item(item_idx)?.quantity();
+
+        units = units.saturating_add(usize::try_from(quantity).unwrap_or(usize::MAX));
+    }
+
+    Ok(units)
+}
+
+/// Number of units in `item_group` matching `slot`.
+fn matching_units(item_group: &ItemGroup<'_>, slot: &MixAndMatchSlot) -> usize {
+    item_group
+        .iter()
+        .filter(|item| slot.qualification().matches(item.tags()))
+        .map(|item| usize::try_from(item.quantity()).unwrap_or(usize::MAX))
+        .fold(0, usize::saturating_add)
+}
+
 fn runtime_discount_from_config(discount: &MixAndMatchDiscount<'_>) -> MixAndMatchRuntimeDiscount {
     match discount {
         MixAndMatchDiscount::PercentAllItems(pct) => {
@@ -605,8 +914,7 @@ fn calculate_discounted_minor_for_budget(
         MixAndMatchRuntimeDiscount::AmountOffEachItem(amount_off) => {
             full_minor.saturating_sub(amount_off)
         }
-        // Conservative approximation for budgeting bundle-total discounts:
-        // assume each selected item could be fully discounted.
+        // Bundle-total discounts are budgeted per bundle, not per item.
         MixAndMatchRuntimeDiscount::AmountOffTotal(_)
         | MixAndMatchRuntimeDiscount::FixedTotal(_) => 0,
         MixAndMatchRuntimeDiscount::FixedPriceEachItem(fixed_minor)
@@ -631,6 +939,101 @@ fn proportional_alloc(total: i64, part: i64, denom: i64) -> i64 {
     i64::try_from(value).unwrap_or(0)
 }
 
+/// Create the bundles of an amount-off-total promotion, with the discount of each in
+/// the objective.
+///
+/// When only one bundle can be formed it is the slot selection itself. Otherwise each
+/// bundle that could be formed gets its own selection variables, so its discount can be
+/// bounded by its own value.
+fn add_amount_off_bundles(
+    promotion_key: PromotionKey,
+    item_group: &ItemGroup<'_>,
+    slot_vars: &[SmallVec<[(usize, Variable); 10]>],
+    bundle_control: Option<Variable>,
+    max_bundles: usize,
+    state: &mut ILPState,
+    observer: &mut dyn ILPObserver,
+) -> Result<Vec<AmountOffBundle>, SolverError> {
+    let Some(bundle_control) = bundle_control else {
+        return Ok(Vec::new());
+    };
+
+    if max_bundles <= 1 {
+        let items = slot_vars
+            .iter()
+            .enumerate()
+            .flat_map(|(slot_idx, slot)| {
+                slot.iter()
+                    .map(move |&(item_idx, var)| (slot_idx, item_idx, var))
+            })
+            .collect();
+
+        return Ok(vec![AmountOffBundle {
+            formed: bundle_control,
+            items,
+            discount: add_amount_off_discount(promotion_key, 0, state, observer),
+        }]);
+    }
+
+    let mut bundles = Vec::with_capacity(max_bundles);
+
+    for bundle_idx in 0..max_bundles {
+        let formed = state.problem_variables_mut().add(variable().binary());
+
+        observer.on_auxiliary_variable(
+            promotion_key,
+            formed,
+            "Amount off bundle formed",
+            None,
+            Some(bundle_idx),
+        );
+
+        let mut items = SmallVec::new();
+
+        for (slot_idx, slot) in slot_vars.iter().enumerate() {
+            for &(item_idx, _var) in slot {
+                let item = item_group.get_item(item_idx)?;
+                let var = state.problem_variables_mut().add(unit_count_variable(item));
+
+                observer.on_auxiliary_variable(
+                    promotion_key,
+                    var,
+                    "Amount off bundle item",
+                    Some(item_idx),
+                    Some(bundle_idx),
+                );
+
+                items.push((slot_idx, item_idx, var));
+            }
+        }
+
+        bundles.push(AmountOffBundle {
+            formed,
+            items,
+            discount: add_amount_off_discount(promotion_key, bundle_idx, state, observer),
+        });
+    }
+
+    Ok(bundles)
+}
+
+/// Create the discount taken off one amount-off-total bundle, as a negative objective term.
+fn add_amount_off_discount(
+    promotion_key: PromotionKey,
+    bundle_idx: usize,
+    state: &mut ILPState,
+    observer: &mut dyn ILPObserver,
+) -> Variable {
+    let var = state.problem_variables_mut().add(variable().min(0));
+
+    observer.on_auxiliary_variable(promotion_key, var, "Amount off", None, Some(bundle_idx));
+
+    state.add_to_objective(var, -1.0);
+    observer.on_objective_term(var, -1.0);
+
+    var
+}
+
 fn build_bundles(solution: &dyn Solution, vars: &MixAndMatchVars) -> Vec<Vec<usize>> {
     let bundles_applied = vars.bundle_count(solution);
 
@@ -638,14 +1041,33 @@ fn build_bundles(solution: &dyn Solution, vars: &MixAndMatchVars) -> Vec<Vec<usi
         return Vec::new();
     }
 
-    // Collect selected items per slot
+    // Amount-off-total bundles are priced as they were formed in the solution.
+    if vars.amount_off_bundles.len() > 1 {
+        return vars
+            .amount_off_bundles
+            .iter()
+            .filter(|bundle| solution.value(bundle.formed) > BINARY_THRESHOLD)
+            .map(|bundle| {
+                bundle
+                    .items
+                    .iter()
+                    .flat_map(|&(_, item_idx, var)| {
+                        (0..solved_count(solution, var)).map(move |_| item_idx)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|bundle| !bundle.is_empty())
+            .collect();
+    }
+
+    // Collect selected units per slot, repeating a line's item for each of its units
     let mut slot_items: Vec<Vec<usize>> = Vec::with_capacity(vars.slot_vars.len());
 
     for slot_vars in &vars.slot_vars {
         let mut items = Vec::new();
 
         for &(item_idx, var) in slot_vars {
-            if solution.value(var) > BINARY_THRESHOLD {
+            for _ in 0..solved_count(solution, var) {
                 items.push(item_idx);
             }
         }
@@ -653,6 +1075,13 @@ fn build_bundles(solution: &dyn Solution, vars: &MixAndMatchVars) -> Vec<Vec<usi
         slot_items.push(items);
     }
 
+    if vars.y_bundle.is_some()
+        && vars.needs_target_constraints()
+        && let Some(bundles) = build_target_bundles(solution, vars, &slot_items, bundles_applied)
+    {
+        return bundles;
+    }
+
     let mut bundles = Vec::new();
 
     if vars.y_bundle.is_some() {
@@ -693,7 +1122,162 @@ fn build_bundles(solution: &dyn Solution, vars: &MixAndMatchVars) -> Vec<Vec<usi
     bundles
 }
 
-#[expect(clippy::too_many_lines, reason = "Complex discount calculation logic")]
+/// Group fixed-arity selections into bundles led by their cheapest-item targets.
+///
+/// Going from cheapest to dearest, each target unit starts a bundle and every other
+/// unit joins the first bundle that still needs a unit from its slot. Returns `None`
+/// when the selections cannot be grouped that way.
+fn build_target_bundles(
+    solution: &dyn Solution,
+    vars: &MixAndMatchVars,
+    slot_items: &[Vec<usize>],
+    bundles_applied: usize,
+) -> Option<Vec<Vec<usize>>> {
+    let mut bundles: Vec<(Vec<usize>, SmallVec<[usize; 5]>)> = Vec::with_capacity(bundles_applied);
+
+    for &(item_idx, _price) in &vars.sorted_items {
+        let mut targets = vars
+            .target_vars
+            .get(item_idx)
+            .and_then(|var| *var)
+            .map_or(0, |var| solved_count(solution, var));
+
+        for (slot_idx, items) in slot_items.iter().enumerate() {
+            for _ in items.iter().filter(|&&idx| idx == item_idx) {
+                let (bundle, needed) = if targets > 0 {
+                    targets -= 1;
+
+                    bundles.push((
+                        Vec::with_capacity(vars.bundle_size),
+                        vars.slot_bounds.iter().map(|&(min, _max)| min).collect(),
+                    ));
+
+                    bundles.last_mut()?
+                } else {
+                    bundles
+                        .iter_mut()
+                        .find(|(_, needed)| needed.get(slot_idx).is_some_and(|&count| count > 0))?
+                };
+
+                if needed.get(slot_idx).is_none_or(|&count| count == 0) {
+                    return None;
+                }
+
+                bundle.push(item_idx);
+
+                if let Some(count) = needed.get_mut(slot_idx) {
+                    *count -= 1;
+                }
+            }
+        }
+    }
+
+    let complete = bundles.len() == bundles_applied
+        && bundles
+            .iter()
+            .all(|(_, needed)| needed.iter().all(|&count| count == 0));
+
+    complete.then(|| bundles.into_iter().map(|(bundle, _)| bundle).collect())
+}
+
+/// Final price of each unit in a bundle, in the bundle's order.
+///
+/// Cheapest-item discounts price the bundle's cheapest unit, the first one on a
+/// tie, and bundle-total discounts share the bundle's price across its units.
+fn bundle_final_prices(
+    discount: MixAndMatchRuntimeDiscount,
+    item_group: &ItemGroup<'_>,
+    bundle: &[usize],
+) -> Result<SmallVec<[i64; 10]>, SolverError> {
+    let mut original_prices: SmallVec<[i64; 10]> = SmallVec::with_capacity(bundle.len());
+
+    for &item_idx in bundle {
+        original_prices.push(item_group.get_item(item_idx)?.price().to_minor_units());
+    }
+
+    let cheapest = bundle
+        .iter()
+        .zip(&original_prices)
+        .enumerate()
+        .min_by_key(|&(_, (&item_idx, &price))| (price, item_idx))
+        .map(|(position, _)| position);
+
+    let mut final_prices = SmallVec::with_capacity(bundle.len());
+
+    match discount {
+        MixAndMatchRuntimeDiscount::PercentAllItems(pct) => {
+            for &original_minor in &original_prices {
+                final_prices.push(discounted_minor_percent(&pct, original_minor)?);
+            }
+        }
+        MixAndMatchRuntimeDiscount::AmountOffEachItem(amount_off) => {
+            final_prices.extend(
+                original_prices
+                    .iter()
+                    .map(|&original_minor| original_minor.saturating_sub(amount_off).max(0)),
+            );
+        }
+        MixAndMatchRuntimeDiscount::FixedPriceEachItem(fixed_minor) => {
+            final_prices.extend(original_prices.iter().map(|_| fixed_minor.max(0)));
+        }
+        MixAndMatchRuntimeDiscount::PercentCheapest(pct) => {
+            for (position, &original_minor) in original_prices.iter().enumerate() {
+                final_prices.push(if Some(position) == cheapest {
+                    discounted_minor_percent(&pct, original_minor)?
+                } else {
+                    original_minor
+                });
+            }
+        }
+        MixAndMatchRuntimeDiscount::FixedCheapest(fixed_minor) => {
+            for (position, &original_minor) in original_prices.iter().enumerate() {
+                final_prices.push(if Some(position) == cheapest {
+                    fixed_minor.max(0)
+                } else {
+                    original_minor
+                });
+            }
+        }
+        MixAndMatchRuntimeDiscount::AmountOffTotal(amount_off) => {
+            let original_total: i64 = original_prices.iter().sum();
+
+            final_prices = allocate_bundle_total(
+                original_total.saturating_sub(amount_off).max(0),
+                &original_prices,
+            );
+        }
+        MixAndMatchRuntimeDiscount::FixedTotal(bundle_price) => {
+            final_prices = allocate_bundle_total(bundle_price, &original_prices);
+        }
+    }
+
+    Ok(final_prices)
+}
+
+/// Share `bundle_total` across units in proportion to their original prices, with
+/// the last unit taking the remainder.
+fn allocate_bundle_total(bundle_total: i64, original_prices: &[i64]) -> SmallVec<[i64; 10]> {
+    let original_total: i64 = original_prices.iter().sum();
+    let mut remaining = bundle_total;
+    let mut final_prices = SmallVec::with_capacity(original_prices.len());
+
+    for (i, &original_minor) in original_prices.iter().enumerate() {
+        let final_minor = if i + 1 == original_prices.len() {
+            remaining
+        } else if original_total == 0 {
+            0
+        } else {
+            proportional_alloc(bundle_total, original_minor, original_total)
+        };
+
+        remaining -= final_minor;
+
+        final_prices.push(final_minor);
+    }
+
+    final_prices
+}
+
 fn calculate_discounts_for_vars(
     solution: &dyn Solution,
     vars: &MixAndMatchVars,
@@ -773,74 +1357,14 @@ fn calculate_discounts_for_vars(
                 discounts.insert(item_idx, (original_minor, final_minor));
             }
         }
-        MixAndMatchRuntimeDiscount::AmountOffTotal(amount_off) => {
-            let bundles = build_bundles(solution, vars);
-
-            for bundle_items in bundles {
-                if bundle_items.is_empty() {
-                    continue;
-                }
-
-                let mut original_total = 0_i64;
-
-                for &item_idx in &bundle_items {
-                    let item = item_group.get_item(item_idx)?;
-
-                    original_total += item.price().to_minor_units();
-                }
-
-                let bundle_total = original_total.saturating_sub(amount_off).max(0);
-                let mut remaining = bundle_total;
-
-                for (i, &item_idx) in bundle_items.iter().enumerate() {
-                    let item = item_group.get_item(item_idx)?;
-                    let original_minor = item.price().to_minor_units();
-
-                    let final_minor = if i == bundle_items.len() - 1 {
-                        remaining
-                    } else if original_total == 0 {
-                        0
-                    } else {
-                        proportional_alloc(bundle_total, original_minor, original_total)
-                    };
-
-                    remaining -= final_minor;
-
-                    discounts.insert(item_idx, (original_minor, final_minor));
-                }
-            }
-        }
-        MixAndMatchRuntimeDiscount::FixedTotal(bundle_price) => {
-            let bundles = build_bundles(solution, vars);
-
-            for bundle_items in bundles {
-                if bundle_items.is_empty() {
-                    continue;
-                }
-
-                let mut original_total = 0_i64;
-
-                for &item_idx in &bundle_items {
-                    let item = item_group.get_item(item_idx)?;
-
-                    original_total += item.price().to_minor_units();
-                }
-
-                let mut remaining = bundle_price;
-
-                for (i, &item_idx) in bundle_items.iter().enumerate() {
-                    let item = item_group.get_item(item_idx)?;
-                    let original_minor = item.price().to_minor_units();
-
-                    let final_minor = if i == bundle_items.len() - 1 {
-                        remaining
-                    } else if original_total == 0 {
-                        0
-                    } else {
-                        proportional_alloc(bundle_price, original_minor, original_total)
-                    };
+        MixAndMatchRuntimeDiscount::AmountOffTotal(_)
+        | MixAndMatchRuntimeDiscount::FixedTotal(_) => {
+            for bundle_items in build_bundles(solution, vars) {
+                let final_prices =
+                    bundle_final_prices(vars.runtime_discount, item_group, &bundle_items)?;
 
-                    remaining -= final_minor;
+                for (item_idx, final_minor) in bundle_items.into_iter().zip(final_prices) {
+                    let original_minor = item_group.get_item(item_idx)?.price().to_minor_units();
 
                     discounts.insert(item_idx, (original_minor, final_minor));
                 }
@@ -861,17 +1385,66 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
             return false;
         }
 
+        self.slots()
+            .iter()
+            .all(|slot| matching_units(item_group, slot) >= slot.min())
+    }
+
+    fn is_item_eligible(&self, item: &Item<'_>) -> bool {
+        self.slots()
+            .iter()
+            .any(|slot| slot.qualification().matches(item.tags()))
+    }
+
+    fn progress(&self, item_group: &ItemGroup<'_>) -> PromotionProgress {
+        if self.budget().is_exhausted() {
+            return PromotionProgress {
+                missing: Some(MissingRequirement::BudgetExhausted),
+                ..PromotionProgress::default()
+            };
+        }
+
+        let to_u32 = |count: usize| u32::try_from(count).unwrap_or(u32::MAX);
+
+        // Report the first slot without enough matching items.
         for slot in self.slots() {
-            let matching_items = item_group
-                .iter()
-                .filter(|item| slot.qualification().matches(item.tags()))
-                .count();
+            let matching_items = matching_units(item_group, slot);
 
             if matching_items < slot.min() {
-                return false;
+                return PromotionProgress {
+                    missing: Some(MissingRequirement::SlotItems {
+                        slot: *slot.key(),
+                        items: to_u32(slot.min().saturating_sub(matching_items)),
+                    }),
+                    ..PromotionProgress::default()
+                };
             }
         }
 
+        // Every slot has enough matching items on its own, but an item can only
+        // fill one slot, so slots sharing items may still need more of them.
+        let required_items: usize = self.slots().iter().map(MixAndMatchSlot::min).sum();
+        let eligible_items = item_group
+            .iter()
+            .filter(|item| self.is_item_eligible(item))
+            .map(|item| usize::try_from(item.quantity()).unwrap_or(usize::MAX))
+            .fold(0, usize::saturating_add);
+
+        PromotionProgress {
+            missing: (eligible_items < required_items).then(|| {
+                MissingRequirement::QualifyingItems(to_u32(
+                    required_items.saturating_sub(eligible_items),
+                ))
+            }),
+            ..PromotionProgress::default()
+        }
+    }
+
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        Some(PromotionType::MixAndMatch(self))
+    }
+
+    fn supports_quantities(&self) -> bool {
         true
     }
 
@@ -900,6 +1473,7 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
                 slot_vars: Vec::new(),
                 y_bundle: None,
                 bundle_formed: None,
+                amount_off_bundles: Vec::new(),
                 target_vars: Vec::new(),
                 slot_bounds: Vec::new(),
                 bundle_size: 0,
@@ -910,9 +1484,10 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
             }));
         }
 
-        // Collect eligible items per slot.
+        // Collect eligible items per slot, and the number of units they hold.
         let mut eligible_per_slot: Vec<SmallVec<[(usize, i64); 10]>> =
             Vec::with_capacity(self.slots().len());
+        let mut units_per_slot = Vec::with_capacity(self.slots().len());
         let mut slot_bounds = Vec::with_capacity(self.slots().len());
         let mut feasible = true;
 
@@ -925,11 +1500,14 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
                 }
             }
 
-            if eligible.len() < slot.min() {
+            let units = unit_count(item_group, eligible.iter().map(|&(idx, _)| idx))?;
+
+            if units < slot.min() {
                 feasible = false;
             }
 
             slot_bounds.push((slot.min(), slot.max()));
+            units_per_slot.push(units);
             eligible_per_slot.push(eligible);
         }
 
@@ -939,6 +1517,7 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
                 slot_vars: Vec::new(),
                 y_bundle: None,
                 bundle_formed: None,
+                amount_off_bundles: Vec::new(),
                 target_vars: Vec::new(),
                 slot_bounds: Vec::new(),
                 bundle_size: 0,
@@ -952,10 +1531,10 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
         // Determine whether we can use a bundle counter.
         let can_use_bundle_counter = self.has_fixed_arity();
 
-        let max_bundles = eligible_per_slot
+        let max_bundles = units_per_slot
             .iter()
             .zip(self.slots())
-            .map(|(slot_items, slot)| slot_items.len() / slot.min())
+            .map(|(&units, slot)| units / slot.min())
             .min()
             .unwrap_or(0);
 
@@ -989,7 +1568,8 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
             let mut vars = SmallVec::new();
 
             for &(item_idx, price_minor) in slot_items {
-                let var = state.problem_variables_mut().add(variable().binary());
+                let item = item_group.get_item(item_idx)?;
+                let var = state.problem_variables_mut().add(unit_count_variable(item));
 
                 vars.push((item_idx, var));
 
@@ -1048,7 +1628,8 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
             sorted_items.extend(all_bundle_items.iter().copied());
 
             for &(item_idx, price_minor) in &all_bundle_items {
-                let var = state.problem_variables_mut().add(variable().binary());
+                let item = item_group.get_item(item_idx)?;
+                let var = state.problem_variables_mut().add(unit_count_variable(item));
 
                 if let Some(slot) = target_vars.get_mut(item_idx) {
                     *slot = Some(var);
@@ -1104,26 +1685,32 @@ impl ILPPromotion for MixAndMatchPromotion<'_> {
             }
         }
 
-        // Amount off total objective term (negative per bundle formed)
-        if let MixAndMatchDiscount::AmountOffTotal(amount) = self.discount() {
-            let amount_off = amount.to_minor_units();
-            let coeff = i64_to_f64_exact(amount_off)
-                .ok_or(SolverError::MinorUnitsNotRepresentable(amount_off))?;
+        let amount_off_bundles = if let MixAndMatchDiscount::AmountOffTotal(_) = self.discount() {
+            // A variable-arity promotion forms at most one bundle.
+            let (bundle_control, max_bundles) = match (y_bundle, bundle_formed) {
+                (Some(y_bundle), _) => (Some(y_bundle), max_bundles),
+                (None, bundle_formed) => (bundle_formed, 1),
+            };
 
-            if let Some(y_bundle) = y_bundle {
-                state.add_to_objective(y_bundle, -coeff);
-                observer.on_objective_term(y_bundle, -coeff);
-            } else if let Some(bundle_formed) = bundle_formed {
-                state.add_to_objective(bundle_formed, -coeff);
-                observer.on_objective_term(bundle_formed, -coeff);
-            }
-        }
+            add_amount_off_bundles(
+                promotion_key,
+                item_group,
+                &slot_vars,
+                bundle_control,
+                max_bundles,
+                state,
+                observer,
+            )?
+        } else {
+            Vec::new()
+        };
 
         Ok(Box::new(MixAndMatchVars {
             promotion_key,
             slot_vars,
             y_bundle,
             bundle_formed,
+            amount_off_bundles,
             target_vars,
             slot_bounds,
             bundle_size,
@@ -1154,7 +1741,7 @@ mod tests {
             NoopObserver,
             promotions::test_support::{
                 MapSolution, RecordingObserver, assert_relation_holds,
-                assert_state_constraints_hold, item_group_from_prices,
+                assert_state_constraints_hold, item_group_from_items, item_group_from_prices,
                 observed_lhs_values_for_type, state_lhs_values_for_relation,
             },
             state::{ConstraintRelation, ILPState},
@@ -1188,6 +1775,58 @@ mod tests {
         assert!(!promo.is_applicable(&item_group));
     }
 
+    #[test]
+    fn progress_reports_unfilled_slot_and_shared_items() {
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let main = slot(
+            &mut slot_keys,
+            StringTagCollection::from_strs(&["main"]),
+            1,
+            Some(1),
+        );
+        let side = slot(
+            &mut slot_keys,
+            StringTagCollection::from_strs(&["main", "side"]),
+            2,
+            Some(2),
+        );
+        let side_key = *side.key();
+
+        let promo = MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            vec![main, side],
+            MixAndMatchDiscount::PercentAllItems(Percentage::from(0.1)),
+            PromotionBudget::unlimited(),
+        );
+
+        let tagged = |tags: &[&str]| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(tags),
+            )
+        };
+
+        let one_main = ItemGroup::new(smallvec![tagged(&["main"])], GBP);
+
+        assert_eq!(
+            promo.progress(&one_main).missing,
+            Some(MissingRequirement::SlotItems {
+                slot: side_key,
+                items: 1,
+            })
+        );
+
+        // Each slot sees two matching items, but three are needed in total.
+        let two_mains = ItemGroup::new(smallvec![tagged(&["main"]), tagged(&["main"])], GBP);
+
+        assert_eq!(
+            promo.progress(&two_mains).missing,
+            Some(MissingRequirement::QualifyingItems(1))
+        );
+    }
+
     #[test]
     fn add_constraints_smoke_test() -> TestResult {
         let items: SmallVec<[Item<'_>; 10]> = SmallVec::from_vec(vec![
@@ -1236,7 +1875,7 @@ mod tests {
         let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
             .expect("Expected mix-and-match vars");
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         Ok(())
     }
@@ -1607,6 +2246,66 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn amount_off_total_gets_a_bundle_per_possible_redemption() -> TestResult {
+        let item_group = item_group_from_items([100, 200, 300, 400, 500].map(|price| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            )
+        }));
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let mut amount_off = |max| {
+            MixAndMatchPromotion::new(
+                PromotionKey::default(),
+                vec![slot(
+                    &mut slot_keys,
+                    StringTagCollection::from_strs(&["snack"]),
+                    2,
+                    max,
+                )],
+                MixAndMatchDiscount::AmountOffTotal(Money::from_minor(150, GBP)),
+                PromotionBudget::unlimited(),
+            )
+        };
+
+        // Fixed arity: two bundles of two fit in five items, each with its own items.
+        let fixed = amount_off(Some(2));
+        let mut state = ILPState::with_presence_variables(&item_group)?;
+        let vars = fixed.add_variables(&item_group, &mut state, &mut NoopObserver)?;
+
+        let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
+            .expect("Expected mix-and-match vars");
+
+        assert_eq!(vars.amount_off_bundles.len(), 2);
+        assert!(
+            vars.amount_off_bundles
+                .iter()
+                .all(|bundle| bundle.items.len() == 5 && Some(bundle.formed) != vars.y_bundle)
+        );
+
+        // Variable arity: the single bundle is the slot selection itself.
+        let variable = amount_off(None);
+        let mut state = ILPState::with_presence_variables(&item_group)?;
+        let vars = variable.add_variables(&item_group, &mut state, &mut NoopObserver)?;
+
+        let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
+            .expect("Expected mix-and-match vars");
+
+        let bundle = vars
+            .amount_off_bundles
+            .first()
+            .ok_or("Expected an amount-off bundle")?;
+
+        assert_eq!(vars.amount_off_bundles.len(), 1);
+        assert_eq!(Some(bundle.formed), vars.bundle_formed);
+
+        Ok(())
+    }
+
     #[test]
     fn calculate_item_redemptions_returns_redemption_idxs() -> TestResult {
         let items: SmallVec<[Item<'_>; 10]> = SmallVec::from_vec(vec![
@@ -1912,7 +2611,7 @@ mod tests {
         // Should use bundle_formed for variable arity
         assert!(vars.bundle_formed.is_some());
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         Ok(())
     }
@@ -1975,6 +2674,7 @@ mod tests {
             slot_vars: vec![SmallVec::new()],
             y_bundle: None,
             bundle_formed: None,
+            amount_off_bundles: Vec::new(),
             target_vars: Vec::new(),
             slot_bounds: Vec::new(),
             bundle_size: 0,
@@ -1989,7 +2689,8 @@ mod tests {
     }
 
     #[test]
-    fn add_constraints_returns_early_without_bundle_control_vars() {
+    fn add_constraints_returns_early_without_bundle_control_vars() -> TestResult {
+        let item_group = item_group_from_prices(&[100]);
         let mut pb = ProblemVariables::new();
         let slot_var = pb.add(variable().binary());
         let target_var = pb.add(variable().binary());
@@ -1999,6 +2700,7 @@ mod tests {
             slot_vars: vec![smallvec![(0, slot_var)]],
             y_bundle: None,
             bundle_formed: None,
+            amount_off_bundles: Vec::new(),
             target_vars: vec![Some(target_var)],
             slot_bounds: vec![(1, Some(1))],
             bundle_size: 1,
@@ -2011,12 +2713,18 @@ mod tests {
         let mut state = ILPState::new(pb, Expression::default());
         let mut observer = RecordingObserver::default();
 
-        vars.add_model_constraints(PromotionKey::default(), &mut state, &mut observer);
+        vars.add_model_constraints(
+            PromotionKey::default(),
+            &item_group,
+            &mut state,
+            &mut observer,
+        )?;
 
         let (_pb, _cost, _presence, constraints) = state.into_parts_with_constraints();
 
         assert!(constraints.is_empty());
         assert!(observer.promotion_constraints.is_empty());
+        Ok(())
     }
 
     #[test]
@@ -2064,7 +2772,7 @@ mod tests {
         let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
             .expect("Expected mix-and-match vars");
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         let slot0_var = vars.slot_vars[0][0].1;
         let slot1_var = vars.slot_vars[0][1].1;
@@ -2169,7 +2877,7 @@ mod tests {
         let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
             .expect("Expected mix-and-match vars");
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         let slot0_var = vars.slot_vars[0][0].1;
         let slot1_var = vars.slot_vars[0][1].1;
@@ -2223,6 +2931,7 @@ mod tests {
             slot_vars: Vec::new(),
             y_bundle: None,
             bundle_formed: Some(bundle_formed_zero),
+            amount_off_bundles: Vec::new(),
             target_vars: Vec::new(),
             slot_bounds: Vec::new(),
             bundle_size: 0,
@@ -2258,6 +2967,7 @@ mod tests {
             slot_vars: Vec::new(),
             y_bundle: None,
             bundle_formed: Some(bundle_formed_one),
+            amount_off_bundles: Vec::new(),
             target_vars: Vec::new(),
             slot_bounds: Vec::new(),
             bundle_size: 0,
@@ -2729,6 +3439,7 @@ mod tests {
             slot_vars: vec![smallvec![(0, v0), (1, v1)]],
             y_bundle: None,
             bundle_formed: None,
+            amount_off_bundles: Vec::new(),
             target_vars: vec![None, None],
             slot_bounds: vec![(1, Some(1))],
             bundle_size: 1,
@@ -2789,7 +3500,7 @@ mod tests {
         let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
             .expect("Expected mix-and-match vars");
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         let slot0_var = vars.slot_vars[0][0].1;
         let slot1_var = vars.slot_vars[0][1].1;
@@ -2868,7 +3579,7 @@ mod tests {
         let vars = ((vars.as_ref() as &dyn Any).downcast_ref::<MixAndMatchVars>())
             .expect("Expected mix-and-match vars");
 
-        vars.add_model_constraints(promo.key(), &mut state, &mut observer);
+        vars.add_model_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
 
         let cheapest_prefix_count = observer
             .promotion_constraints
@@ -8,8 +8,11 @@ use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 
 use crate::{
-    items::groups::ItemGroup,
-    promotions::{PromotionKey, redemptions::PromotionRedemption},
+    items::{Item, groups::ItemGroup},
+    promotions::{
+        PromotionKey, explanations::PromotionProgress, redemptions::PromotionRedemption,
+        types::PromotionType,
+    },
     solvers::{
         SolverError,
         ilp::{ILPObserver, state::ILPState},
@@ -104,6 +107,9 @@ pub(crate) struct PromotionInstance<'a> {
 
     /// The solver variables for this promotion instance
     vars: Option<PromotionVars>,
+
+    /// The terms this promotion added to the objective expression (`cost`)
+    cost: Expression,
 }
 
 impl<'a> PromotionInstance<'a> {
@@ -124,6 +130,8 @@ impl<'a> PromotionInstance<'a> {
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
     ) -> Result<Self, SolverError> {
+        let cost_before = state.cost().clone();
+
         let vars = if promotion.is_applicable(item_group) {
             let vars = promotion.add_variables(item_group, state, observer)?;
             vars.add_constraints(promotion.key(), item_group, state, observer)?;
@@ -133,7 +141,13 @@ impl<'a> PromotionInstance<'a> {
             None
         };
 
-        Ok(Self { promotion, vars })
+        let cost = state.cost().clone() - cost_before;
+
+        Ok(Self {
+            promotion,
+            vars,
+            cost,
+        })
     }
 
     /// Contribute this promotion's presence term for `item_idx`.
@@ -148,6 +162,49 @@ impl<'a> PromotionInstance<'a> {
         }
     }
 
+    /// Key of the promotion being solved.
+    pub(crate) fn key(&self) -> PromotionKey {
+        self.promotion.key()
+    }
+
+    /// Whether `item_idx` participates in this promotion in `solution`.
+    pub(crate) fn is_item_participating(&self, solution: &dyn Solution, item_idx: usize) -> bool {
+        self.vars
+            .as_ref()
+            .is_some_and(|vars| vars.is_item_participating(solution, item_idx))
+    }
+
+    /// Discount this promotion gives, as an expression over its variables.
+    ///
+    /// Every item participating in the promotion is priced by the promotion's
+    /// objective terms instead of its presence variable, so the discount is the
+    /// full price of the participating items less those terms.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if an item price cannot be represented exactly as a
+    /// solver coefficient.
+    pub(crate) fn discount_expression(
+        &self,
+        item_group: &ItemGroup<'_>,
+    ) -> Result<Expression, SolverError> {
+        let Some(vars) = &self.vars else {
+            return Ok(Expression::default());
+        };
+
+        let mut expr = Expression::default();
+
+        for (item_idx, item) in item_group.iter().enumerate() {
+            let minor = item.price().to_minor_units();
+            let price =
+                i64_to_f64_exact(minor).ok_or(SolverError::MinorUnitsNotRepresentable(minor))?;
+
+            expr += vars.add_item_participation_term(Expression::default(), item_idx) * price;
+        }
+
+        Ok(expr - self.cost.clone())
+    }
+
     /// Contribute optional lexicographic tie-break terms for this instance.
     ///
     /// # Errors
@@ -329,6 +386,48 @@ pub trait ILPPromotion: Debug + Send + Sync {
     /// Avoid expensive computations that can be deferred until [`ILPPromotion::add_variables`].
     fn is_applicable(&self, item_group: &ItemGroup<'_>) -> bool;
 
+    /// Return whether `item` could ever take part in this promotion.
+    ///
+    /// The solver splits a basket into independent sub-problems, where no promotion is
+    /// eligible for items in more than one of them. The same rule as
+    /// [`ILPPromotion::is_applicable`] applies: returning `true` when unsure is safe (the
+    /// default keeps every item in one sub-problem), while a false negative can hide a
+    /// valid discount.
+    fn is_item_eligible(&self, _item: &Item<'_>) -> bool {
+        true
+    }
+
+    /// Report how close `item_group` is to meeting this promotion's requirements.
+    ///
+    /// This explains promotions to customers and is not used while solving. The default
+    /// reports nothing missing, so a promotion that does not apply is treated as
+    /// outcompeted.
+    fn progress(&self, _item_group: &ItemGroup<'_>) -> PromotionProgress {
+        PromotionProgress::default()
+    }
+
+    /// Return whether this promotion can model a line of identical units as integer counts.
+    ///
+    /// The solver compresses runs of identical units into single item group entries whose
+    /// [`crate::items::Item::quantity`] is the run length, unless an applicable promotion
+    /// that returns `false` is [eligible](ILPPromotion::is_item_eligible) for them. Each
+    /// entry's participation term must then count units, between zero and the quantity,
+    /// and redemptions are reported once per unit against the entry.
+    ///
+    /// Promotions that return `false` (the default) see one entry per unit for every item
+    /// they are eligible for. Every built-in promotion models counts.
+    fn supports_quantities(&self) -> bool {
+        false
+    }
+
+    /// The built-in promotion type behind this promotion, if it is one.
+    ///
+    /// Lets code that only holds the trait object read a built-in promotion's
+    /// definition. The default is `None`, for custom promotions.
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        None
+    }
+
     /// Create per-item binary variables and add them to the objective expression.
     ///
     /// Each eligible item gets a decision variable indicating whether this promotion applies.
@@ -358,6 +457,22 @@ impl ILPPromotion for Arc<dyn ILPPromotion + '_> {
         self.as_ref().is_applicable(item_group)
     }
 
+    fn is_item_eligible(&self, item: &Item<'_>) -> bool {
+        self.as_ref().is_item_eligible(item)
+    }
+
+    fn progress(&self, item_group: &ItemGroup<'_>) -> PromotionProgress {
+        self.as_ref().progress(item_group)
+    }
+
+    fn supports_quantities(&self) -> bool {
+        self.as_ref().supports_quantities()
+    }
+
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        self.as_ref().promotion_type()
+    }
+
     fn add_variables(
         &self,
         item_group: &ItemGroup<'_>,
@@ -11,17 +11,22 @@ use smallvec::SmallVec;
 use rusty_money::Money;
 
 use crate::{
-    discounts::{SimpleDiscount, percent_of_minor},
-    items::groups::ItemGroup,
+    discounts::{SimpleDiscount, amount_off_per_measure_minor, percent_of_minor},
+    items::{Item, groups::ItemGroup, measure::Measure},
     promotions::{
-        PromotionKey, redemptions::PromotionRedemption, types::PositionalDiscountPromotion,
+        PromotionKey,
+        explanations::{MissingRequirement, PromotionProgress},
+        redemptions::PromotionRedemption,
+        types::{PositionalDiscountPromotion, PromotionType},
     },
     solvers::{
         SolverError,
         ilp::{
             BINARY_THRESHOLD, ILPObserver, i64_to_f64_exact,
             promotions::{ILPPromotion, ILPPromotionVars, PromotionVars},
+            solved_count,
             state::ILPState,
+            unit_count_variable,
         },
     },
 };
@@ -31,6 +36,7 @@ enum PositionalRuntimeDiscount {
     PercentageOff(Percentage),
     AmountOverride(i64),
     AmountOff(i64),
+    AmountOffPerMeasure(i64),
 }
 
 /// Solver variables for a positional discount promotion.
@@ -81,6 +87,49 @@ struct PositionalDFAConstraintData {
 
     /// DFA transition variables: `take_vars[pos][r]` = take item at pos when in state r
     take_vars: SmallVec<[SmallVec<[Variable; 8]>; 12]>,
+
+    /// Steps over lines of identical units, by position, in place of their
+    /// transition variables
+    line_steps: SmallVec<[(usize, PositionalLineSteps); 2]>,
+}
+
+/// DFA steps over a line of identical units.
+///
+/// Taking `size * cycles + taken` of the line's units from state `r` moves the
+/// machine to state `(r + taken) mod size`. Each full cycle passes every
+/// position of a bundle once, so only the remaining units depend on the state
+/// the line is entered in.
+#[derive(Debug)]
+struct PositionalLineSteps {
+    /// Number of full cycles through a bundle
+    cycles: Variable,
+
+    /// Moves as `(r, taken, var)`: the line is entered in state `r` and `taken`
+    /// units are taken beyond the full cycles
+    moves: SmallVec<[(u16, u16, Variable); 16]>,
+}
+
+impl PositionalDFAConstraintData {
+    /// Whether the item at eligible position `pos` is a line of identical units.
+    fn is_line(&self, pos: usize) -> bool {
+        self.line_steps.iter().any(|&(line_pos, _)| line_pos == pos)
+    }
+}
+
+/// State the DFA is in after taking `taken` units from state `from`.
+fn next_dfa_state(from: u16, taken: u16, size: u16) -> u16 {
+    let next = (u32::from(from) + u32::from(taken)) % u32::from(size.max(1));
+
+    u16::try_from(next).unwrap_or(0)
+}
+
+/// How many of `taken` units, taken from state `from`, land on a discounted position.
+fn discounted_takes(from: u16, taken: u16, size: u16, positions: &[u16]) -> u16 {
+    let discounted = (0..taken)
+        .filter(|&step| positions.contains(&next_dfa_state(from, step, size)))
+        .count();
+
+    u16::try_from(discounted).unwrap_or(taken)
 }
 
 impl PositionalDiscountVars {
@@ -183,6 +232,8 @@ impl PositionalDiscountVars {
             state,
             observer,
         );
+
+        self.add_dfa_line_constraints(promotion_key, dfa_data, state, observer);
     }
 
     fn add_dfa_state_uniqueness_constraints(
@@ -291,6 +342,10 @@ impl PositionalDiscountVars {
         observer: &mut dyn ILPObserver,
     ) {
         for eligible_idx in 0..num_eligible {
+            if dfa_data.is_line(eligible_idx) {
+                continue;
+            }
+
             let take_sum: Expression = dfa_data
                 .take_vars
                 .get(eligible_idx)
@@ -324,6 +379,10 @@ impl PositionalDiscountVars {
         observer: &mut dyn ILPObserver,
     ) {
         for eligible_idx in 0..num_eligible {
+            if dfa_data.is_line(eligible_idx) {
+                continue;
+            }
+
             let mut discount_sum = Expression::default();
 
             if let Some(takes) = dfa_data.take_vars.get(eligible_idx) {
@@ -387,6 +446,92 @@ impl PositionalDiscountVars {
         }
     }
 
+    /// Add the steps over lines of identical units.
+    ///
+    /// A line is entered in exactly one state, which picks one of the moves from
+    /// it, and the move sets the state after the line. The line's participation
+    /// and discount counts follow from its full cycles and its move.
+    fn add_dfa_line_constraints(
+        &self,
+        promotion_key: PromotionKey,
+        dfa_data: &PositionalDFAConstraintData,
+        state: &mut ILPState,
+        observer: &mut dyn ILPObserver,
+    ) {
+        let size = dfa_data.size;
+        let discounted_per_cycle = discounted_takes(0, size, size, &dfa_data.positions);
+
+        for (pos, steps) in &dfa_data.line_steps {
+            let (Some(states), Some(next_states)) = (
+                dfa_data.state_vars.get(*pos),
+                dfa_data.state_vars.get(pos + 1),
+            ) else {
+                continue;
+            };
+
+            for (r, (&state_var, &next_state)) in (0..size).zip(states.iter().zip(next_states)) {
+                let entered: Expression = steps
+                    .moves
+                    .iter()
+                    .filter(|&&(from, _, _)| from == r)
+                    .map(|&(_, _, var)| var)
+                    .sum();
+                let expr = entered - state_var;
+
+                observer.on_promotion_constraint(promotion_key, "DFA line entry", &expr, "=", 0.0);
+                state.add_eq_constraint(expr, 0.0);
+
+                let left: Expression = steps
+                    .moves
+                    .iter()
+                    .filter(|&&(from, taken, _)| next_dfa_state(from, taken, size) == r)
+                    .map(|&(_, _, var)| var)
+                    .sum();
+                let expr = Expression::from(next_state) - left;
+
+                observer.on_promotion_constraint(promotion_key, "DFA line exit", &expr, "=", 0.0);
+                state.add_eq_constraint(expr, 0.0);
+            }
+
+            let mut taken_sum = Expression::from(steps.cycles) * f64::from(size);
+            let mut discount_sum = Expression::from(steps.cycles) * f64::from(discounted_per_cycle);
+
+            for &(from, taken, var) in &steps.moves {
+                taken_sum += var * f64::from(taken);
+                discount_sum +=
+                    var * f64::from(discounted_takes(from, taken, size, &dfa_data.positions));
+            }
+
+            if let Some(&(_idx, participation_var)) = self.item_participation.get(*pos) {
+                let expr = Expression::from(participation_var) - taken_sum;
+
+                observer.on_promotion_constraint(
+                    promotion_key,
+                    "DFA link line participation",
+                    &expr,
+                    "=",
+                    0.0,
+                );
+
+                state.add_eq_constraint(expr, 0.0);
+            }
+
+            if let Some(&(_idx, discount_var)) = self.item_discounts.get(*pos) {
+                let expr = Expression::from(discount_var) - discount_sum;
+
+                observer.on_promotion_constraint(
+                    promotion_key,
+                    "DFA link line discount",
+                    &expr,
+                    "=",
+                    0.0,
+                );
+
+                state.add_eq_constraint(expr, 0.0);
+            }
+        }
+    }
+
     /// Add budget constraints for positional promotions
     pub fn add_budget_constraints(
         &self,
@@ -436,8 +581,11 @@ impl PositionalDiscountVars {
                 let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
 
                 let full_minor = item.price().to_minor_units();
-                let discounted_minor =
-                    calculate_discounted_minor_for_runtime(full_minor, self.runtime_discount)?;
+                let discounted_minor = calculate_discounted_minor_for_runtime(
+                    full_minor,
+                    item.measure(),
+                    self.runtime_discount,
+                )?;
 
                 let discount_amount = full_minor.saturating_sub(discounted_minor);
                 let coeff = i64_to_f64_exact(discount_amount)
@@ -512,7 +660,11 @@ impl ILPPromotionVars for PositionalDiscountVars {
             let original_minor = item.price().to_minor_units();
 
             let final_minor = if self.is_item_priced_by_promotion(solution, item_idx) {
-                calculate_discounted_minor_for_runtime(original_minor, self.runtime_discount)?
+                calculate_discounted_minor_for_runtime(
+                    original_minor,
+                    item.measure(),
+                    self.runtime_discount,
+                )?
             } else {
                 original_minor
             };
@@ -532,28 +684,46 @@ impl ILPPromotionVars for PositionalDiscountVars {
     ) -> Result<SmallVec<[PromotionRedemption<'b>; 10]>, SolverError> {
         let mut redemptions = SmallVec::new();
         let currency = item_group.currency();
-        let bundle_size = self.bundle_size;
 
-        let mut participating_items: SmallVec<[(usize, i64); 10]> = SmallVec::new();
+        let Some(dfa_data) = &self.dfa_data else {
+            return Ok(redemptions);
+        };
 
-        for (item_idx, item) in item_group.iter().enumerate() {
-            if self.is_item_participating(solution, item_idx) {
-                participating_items.push((item_idx, item.price().to_minor_units()));
+        // Replay the DFA over the taken units in the order it walked them, so each
+        // unit lands in the bundle and position the model put it in.
+        let mut participating_items: SmallVec<[(usize, i64, u32); 10]> = SmallVec::new();
+
+        for &(item_idx, var) in &self.item_participation {
+            let count = solved_count(solution, var);
+
+            if count > 0 {
+                let item = item_group.get_item(item_idx)?;
+
+                participating_items.push((item_idx, item.price().to_minor_units(), count));
             }
         }
 
         participating_items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
-        for chunk in participating_items.chunks(bundle_size) {
-            let redemption_idx = *next_redemption_idx;
-            *next_redemption_idx += 1;
+        let mut dfa_state: u16 = 0;
+        let mut redemption_idx = *next_redemption_idx;
 
-            for &(item_idx, price_minor) in chunk {
-                let item = item_group.get_item(item_idx)?;
+        for (item_idx, price_minor, count) in participating_items {
+            let item = item_group.get_item(item_idx)?;
 
-                let final_price = if self.is_item_priced_by_promotion(solution, item_idx) {
+            for _ in 0..count {
+                if dfa_state == 0 {
+                    redemption_idx = *next_redemption_idx;
+                    *next_redemption_idx += 1;
+                }
+
+                let final_price = if dfa_data.positions.contains(&dfa_state) {
                     Money::from_minor(
-                        calculate_discounted_minor_for_runtime(price_minor, self.runtime_discount)?,
+                        calculate_discounted_minor_for_runtime(
+                            price_minor,
+                            item.measure(),
+                            self.runtime_discount,
+                        )?,
                         currency,
                     )
                 } else {
@@ -567,6 +737,8 @@ impl ILPPromotionVars for PositionalDiscountVars {
                     original_price: *item.price(),
                     final_price,
                 });
+
+                dfa_state = next_dfa_state(dfa_state, 1, dfa_data.size);
             }
         }
 
@@ -585,11 +757,15 @@ fn positional_runtime_discount_from_config(
         SimpleDiscount::AmountOff(amount) => {
             PositionalRuntimeDiscount::AmountOff(amount.to_minor_units())
         }
+        SimpleDiscount::AmountOffPerMeasure(amount) => {
+            PositionalRuntimeDiscount::AmountOffPerMeasure(amount.to_minor_units())
+        }
     }
 }
 
 fn calculate_discounted_minor_for_runtime(
     original_minor: i64,
+    measure: Option<Measure>,
     discount: PositionalRuntimeDiscount,
 ) -> Result<i64, SolverError> {
     let discount_minor = match discount {
@@ -603,11 +779,59 @@ fn calculate_discounted_minor_for_runtime(
         PositionalRuntimeDiscount::AmountOff(amount_minor) => {
             original_minor.saturating_sub(amount_minor)
         }
+        PositionalRuntimeDiscount::AmountOffPerMeasure(amount_minor) => {
+            let amount_off_minor = amount_off_per_measure_minor(amount_minor, measure)
+                .map_err(SolverError::Discount)?;
+
+            original_minor.saturating_sub(amount_off_minor)
+        }
     };
 
     Ok(0.max(discount_minor))
 }
 
+/// Create the DFA steps over a line of `quantity` identical units at `pos`.
+fn add_line_steps(
+    promotion_key: PromotionKey,
+    pos: usize,
+    quantity: u32,
+    size: u16,
+    state: &mut ILPState,
+    observer: &mut dyn ILPObserver,
+) -> PositionalLineSteps {
+    let cycles = state.problem_variables_mut().add(
+        variable()
+            .integer()
+            .min(0)
+            .max(quantity / u32::from(size.max(1))),
+    );
+
+    observer.on_auxiliary_variable(promotion_key, cycles, "DFA line cycles", Some(pos), None);
+
+    let most_taken = u16::try_from(quantity)
+        .unwrap_or(u16::MAX)
+        .min(size.saturating_sub(1));
+    let mut moves = SmallVec::new();
+
+    for from in 0..size {
+        for taken in 0..=most_taken {
+            let var = state.problem_variables_mut().add(variable().binary());
+
+            observer.on_auxiliary_variable(
+                promotion_key,
+                var,
+                &format!("DFA line move {taken}"),
+                Some(pos),
+                Some(usize::from(from)),
+            );
+
+            moves.push((from, taken, var));
+        }
+    }
+
+    PositionalLineSteps { cycles, moves }
+}
+
 impl ILPPromotion for PositionalDiscountPromotion<'_> {
     fn key(&self) -> PromotionKey {
         PositionalDiscountPromotion::key(self)
@@ -625,6 +849,56 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
             .any(|item| qualification.matches(item.tags()))
     }
 
+    fn is_item_eligible(&self, item: &Item<'_>) -> bool {
+        self.qualification().matches(item.tags())
+    }
+
+    fn progress(&self, item_group: &ItemGroup<'_>) -> PromotionProgress {
+        if self.budget().is_exhausted() {
+            return PromotionProgress {
+                missing: Some(MissingRequirement::BudgetExhausted),
+                ..PromotionProgress::default()
+            };
+        }
+
+        // A bundle needs `size` qualifying units.
+        let size = u32::from(self.size());
+        let qualifying_units = item_group
+            .iter()
+            .filter(|item| self.qualification().matches(item.tags()))
+            .map(Item::quantity)
+            .fold(0, u32::saturating_add);
+
+        if qualifying_units < size {
+            return PromotionProgress {
+                missing: Some(MissingRequirement::QualifyingItems(size - qualifying_units)),
+                ..PromotionProgress::default()
+            };
+        }
+
+        // Another bundle needs whatever a partly filled one lacks, unless the
+        // redemption limit stops at the bundles already formed.
+        let bundles = qualifying_units.checked_div(size).unwrap_or_default();
+        let partial = qualifying_units.checked_rem(size).unwrap_or_default();
+        let another_bundle = self
+            .budget()
+            .redemption_limit
+            .is_none_or(|limit| bundles < limit);
+
+        PromotionProgress {
+            next_bundle: another_bundle.then(|| size.saturating_sub(partial)),
+            ..PromotionProgress::default()
+        }
+    }
+
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        Some(PromotionType::PositionalDiscount(self))
+    }
+
+    fn supports_quantities(&self) -> bool {
+        true
+    }
+
     #[expect(
         clippy::too_many_lines,
         reason = "This function is long due to the DFA constraints."
@@ -660,9 +934,18 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
         });
 
         let num_eligible = eligible.len();
-        // Early return if there are insufficient items that are eligible for even
+        let mut eligible_units: usize = 0;
+
+        for &(item_idx, _price_minor) in &eligible {
+            let quantity = item_group.get_item(item_idx)?.quantity();
+
+            eligible_units =
+                eligible_units.saturating_add(usize::try_from(quantity).unwrap_or(usize::MAX));
+        }
+
+        // Early return if there are insufficient units that are eligible for even
         // a single bundle
-        if num_eligible < bundle_size {
+        if eligible_units < bundle_size {
             return Ok(Box::new(PositionalDiscountVars {
                 promotion_key,
                 eligible_items: SmallVec::new(),
@@ -685,8 +968,8 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
 
             let original_minor = item.price().to_minor_units();
 
-            // Create participation variable
-            let participation_var = state.problem_variables_mut().add(variable().binary());
+            // Create participation variable, counting units for a line of identical ones
+            let participation_var = state.problem_variables_mut().add(unit_count_variable(item));
             item_participation.push((item_idx, participation_var));
 
             // Add objective contribution for participation (full price)
@@ -707,11 +990,14 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
             observer.on_objective_term(participation_var, full_price_coeff);
 
             // Calculate discounted price
-            let discounted_minor =
-                calculate_discounted_minor_for_runtime(original_minor, runtime_discount)?;
+            let discounted_minor = calculate_discounted_minor_for_runtime(
+                original_minor,
+                item.measure(),
+                runtime_discount,
+            )?;
 
             // Create discount variable
-            let discount_var = state.problem_variables_mut().add(variable().binary());
+            let discount_var = state.problem_variables_mut().add(unit_count_variable(item));
             item_discounts.push((item_idx, discount_var));
 
             // Subtract discount contribution from the objective
@@ -744,14 +1030,15 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
             SmallVec::<[SmallVec<[Variable; 8]>; 12]>::with_capacity(num_eligible + 1);
 
         let mut take_vars = SmallVec::<[SmallVec<[Variable; 8]>; 12]>::with_capacity(num_eligible);
+        let mut line_steps = SmallVec::new();
 
-        for pos in 0..num_eligible {
+        for (pos, &(item_idx, _price_minor)) in eligible.iter().enumerate() {
+            let quantity = item_group.get_item(item_idx)?.quantity();
             let mut states_at_pos = SmallVec::<[Variable; 8]>::with_capacity(bundle_size);
             let mut takes_at_pos = SmallVec::<[Variable; 8]>::with_capacity(bundle_size);
 
             for r in 0..bundle_size {
                 let state_var = state.problem_variables_mut().add(variable().binary());
-                let take_var = state.problem_variables_mut().add(variable().binary());
 
                 observer.on_auxiliary_variable(
                     promotion_key,
@@ -760,6 +1047,16 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
                     Some(pos),
                     Some(r),
                 );
+
+                states_at_pos.push(state_var);
+
+                // A line of identical units steps through the DFA in one move instead.
+                if quantity > 1 {
+                    continue;
+                }
+
+                let take_var = state.problem_variables_mut().add(variable().binary());
+
                 observer.on_auxiliary_variable(
                     promotion_key,
                     take_var,
@@ -768,10 +1065,16 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
                     Some(r),
                 );
 
-                states_at_pos.push(state_var);
                 takes_at_pos.push(take_var);
             }
 
+            if quantity > 1 {
+                line_steps.push((
+                    pos,
+                    add_line_steps(promotion_key, pos, quantity, self.size(), state, observer),
+                ));
+            }
+
             state_vars.push(states_at_pos);
             take_vars.push(takes_at_pos);
         }
@@ -804,6 +1107,7 @@ impl ILPPromotion for PositionalDiscountPromotion<'_> {
                     state_vars
                 },
                 take_vars,
+                line_steps,
                 size: self.size(),
                 positions: self.positions().iter().copied().collect(),
             }),
@@ -847,6 +1151,7 @@ mod tests {
 
         let pct = calculate_discounted_minor_for_runtime(
             original_minor,
+            None,
             positional_runtime_discount_from_config(&SimpleDiscount::PercentageOff(
                 Percentage::from(0.25),
             )),
@@ -856,6 +1161,7 @@ mod tests {
 
         let override_price = calculate_discounted_minor_for_runtime(
             original_minor,
+            None,
             positional_runtime_discount_from_config(&SimpleDiscount::AmountOverride(
                 Money::from_minor(60, GBP),
             )),
@@ -865,6 +1171,7 @@ mod tests {
 
         let amount_off = calculate_discounted_minor_for_runtime(
             original_minor,
+            None,
             positional_runtime_discount_from_config(&SimpleDiscount::AmountOff(Money::from_minor(
                 30, GBP,
             ))),
@@ -874,6 +1181,7 @@ mod tests {
 
         let clamped = calculate_discounted_minor_for_runtime(
             original_minor,
+            None,
             positional_runtime_discount_from_config(&SimpleDiscount::AmountOff(Money::from_minor(
                 200, GBP,
             ))),
@@ -881,6 +1189,16 @@ mod tests {
 
         assert_eq!(clamped, 0);
 
+        let per_measure = calculate_discounted_minor_for_runtime(
+            original_minor,
+            Some(Measure::grams(500)),
+            positional_runtime_discount_from_config(&SimpleDiscount::AmountOffPerMeasure(
+                Money::from_minor(40, GBP),
+            )),
+        )?;
+
+        assert_eq!(per_measure, 80);
+
         Ok(())
     }
 
@@ -921,6 +1239,94 @@ mod tests {
         assert!(match_all.is_applicable(&item_group));
     }
 
+    #[test]
+    fn progress_counts_missing_qualifying_items() {
+        let promo = PositionalDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["fresh"])),
+            3,
+            SmallVec::from_vec(vec![2u16]),
+            SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+            PromotionBudget::unlimited(),
+        );
+
+        let fresh = || {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["fresh"]),
+            )
+        };
+
+        let one = ItemGroup::new(SmallVec::from_vec(vec![fresh()]), GBP);
+        let three = ItemGroup::new(SmallVec::from_vec(vec![fresh(), fresh(), fresh()]), GBP);
+
+        assert_eq!(
+            promo.progress(&one).missing,
+            Some(MissingRequirement::QualifyingItems(2))
+        );
+        assert_eq!(promo.progress(&one).next_bundle, None);
+        assert_eq!(promo.progress(&three).missing, None);
+        assert_eq!(promo.progress(&three).next_bundle, Some(3));
+    }
+
+    #[test]
+    fn progress_counts_items_to_the_next_bundle() {
+        let promo = |budget| {
+            PositionalDiscountPromotion::new(
+                PromotionKey::default(),
+                Qualification::match_any(StringTagCollection::from_strs(&["fresh"])),
+                3,
+                SmallVec::from_vec(vec![2u16]),
+                SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+                budget,
+            )
+        };
+
+        let fresh = |quantity| {
+            Item::with_quantity(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["fresh"]),
+                quantity,
+            )
+        };
+
+        let four = ItemGroup::new(SmallVec::from_vec(vec![fresh(4)]), GBP);
+        let unlimited = promo(PromotionBudget::unlimited());
+
+        assert_eq!(unlimited.progress(&four).missing, None);
+        assert_eq!(unlimited.progress(&four).next_bundle, Some(2));
+
+        // One bundle is formed, and the limit allows no second one.
+        let limited = promo(PromotionBudget::with_redemption_limit(1));
+
+        assert_eq!(limited.progress(&four).missing, None);
+        assert_eq!(limited.progress(&four).next_bundle, None);
+    }
+
+    #[test]
+    fn progress_reports_an_exhausted_budget() {
+        let promo = PositionalDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_all(),
+            2,
+            SmallVec::from_vec(vec![1u16]),
+            SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+            PromotionBudget::with_monetary_limit(Money::from_minor(0, GBP)),
+        );
+
+        let item_group = item_group_from_prices(&[100, 200]);
+
+        assert_eq!(
+            promo.progress(&item_group),
+            PromotionProgress {
+                missing: Some(MissingRequirement::BudgetExhausted),
+                ..PromotionProgress::default()
+            }
+        );
+    }
+
     #[test]
     fn add_variables_returns_no_dfa_when_insufficient_items() -> TestResult {
         let item_group = item_group_from_prices(&[100, 200]);
@@ -1043,6 +1449,7 @@ mod tests {
                 positions: SmallVec::from_vec(vec![0]),
                 state_vars: SmallVec::from_vec(vec![SmallVec::from_vec(vec![state_var])]),
                 take_vars: SmallVec::from_vec(vec![SmallVec::from_vec(vec![take_var])]),
+                line_steps: SmallVec::new(),
             }),
             runtime_discount: PositionalRuntimeDiscount::PercentageOff(Percentage::from(0.5)),
             bundle_size: 1,
@@ -1088,6 +1495,7 @@ mod tests {
             item_participation: SmallVec::from_vec(vec![(0, participation_var)]),
             item_discounts: SmallVec::from_vec(vec![(0, discount_var)]),
             dfa_data: Some(PositionalDFAConstraintData {
+                line_steps: SmallVec::new(),
                 size: 1,
                 positions: SmallVec::from_vec(vec![0]),
                 state_vars: SmallVec::from_vec(vec![
@@ -1143,6 +1551,7 @@ mod tests {
             item_participation: SmallVec::from_vec(vec![(0, participation_var)]),
             item_discounts: SmallVec::from_vec(vec![(0, discount_var)]),
             dfa_data: Some(PositionalDFAConstraintData {
+                line_steps: SmallVec::new(),
                 size: 1,
                 positions: SmallVec::from_vec(vec![0]),
                 state_vars: SmallVec::from_vec(vec![
@@ -1202,6 +1611,7 @@ mod tests {
             item_participation: SmallVec::from_vec(vec![(0, participation_var)]),
             item_discounts: SmallVec::from_vec(vec![(0, discount_var)]),
             dfa_data: Some(PositionalDFAConstraintData {
+                line_steps: SmallVec::new(),
                 size: 2,
                 positions: SmallVec::from_vec(vec![1]),
                 state_vars: SmallVec::from_vec(vec![
@@ -1438,6 +1848,7 @@ mod tests {
             item_participation: SmallVec::from_vec(vec![(0, p0), (1, p1)]),
             item_discounts: SmallVec::from_vec(vec![(0, d0), (1, d1)]),
             dfa_data: Some(PositionalDFAConstraintData {
+                line_steps: SmallVec::new(),
                 size: 2,
                 positions: SmallVec::from_vec(vec![1]),
                 state_vars: SmallVec::from_vec(vec![
@@ -8,23 +8,39 @@ use smallvec::SmallVec;
 
 use crate::{
     discounts::percent_of_minor,
-    items::groups::ItemGroup,
+    items::{
+        Item,
+        groups::ItemGroup,
+        measure::{Measure, MeasureUnit},
+    },
     products::ProductKey,
     promotions::{
         PromotionKey,
+        explanations::{MissingRequirement, PromotionProgress, TierShortfall},
         redemptions::PromotionRedemption,
-        types::{ThresholdDiscount, TierThreshold, TieredThresholdPromotion},
+        types::{
+            PromotionType, ThresholdDiscount, ThresholdTier, TierThreshold,
+            TieredThresholdPromotion,
+        },
     },
     solvers::{
         SolverError,
         ilp::{
             BINARY_THRESHOLD, ILPObserver, i64_to_f64_exact,
             promotions::{ILPPromotion, ILPPromotionVars, PromotionVars},
+            solved_count,
             state::ILPState,
+            unit_count_variable,
         },
     },
 };
 
+/// Final prices of the units a tier claims, as `(item_idx, original_minor, final_minor)`.
+///
+/// A line of identical units has an entry per unit, since its units need not
+/// all end up at the same price.
+type UnitPrices = SmallVec<[(usize, i64, i64); 10]>;
+
 /// Per-qualifying-tier data captured during variable creation.
 #[derive(Debug)]
 struct QualifyingTier {
@@ -40,6 +56,12 @@ struct QualifyingTier {
     /// Optional upper maximum number of contributing items.
     upper_item_count_threshold: Option<u32>,
 
+    /// Optional lower weight or volume of contributing items required.
+    lower_measure_threshold: Option<Measure>,
+
+    /// Optional upper maximum weight or volume of contributing items.
+    upper_measure_threshold: Option<Measure>,
+
     /// Binary auxiliary variable: is this tier active?
     tier_var: Variable,
 
@@ -64,6 +86,9 @@ struct QualifyingTier {
     /// Bundle-level fixed amount off total discount.
     amount_off_total_minor: Option<i64>,
 
+    /// Discount taken off the total (amount-off-total tiers).
+    amount_off: Option<Variable>,
+
     /// Bundle-level fixed total discount.
     fixed_total_minor: Option<i64>,
 
@@ -78,6 +103,12 @@ struct QualifyingTier {
 }
 
 impl QualifyingTier {
+    fn has_upper_threshold(&self) -> bool {
+        self.upper_monetary_threshold_minor.is_some()
+            || self.upper_item_count_threshold.is_some()
+            || self.upper_measure_threshold.is_some()
+    }
+
     fn has_bundle_total_discount(&self) -> bool {
         self.amount_off_total_minor.is_some() || self.fixed_total_minor.is_some()
     }
@@ -169,9 +200,7 @@ impl ILPPromotionVars for TieredThresholdPromotionVars {
         // This reduces equivalent branch permutations around upper caps while
         // preserving the exact primary optimum enforced by pass 1.
         for qt in &self.qualifying_tiers {
-            if qt.upper_monetary_threshold_minor.is_none()
-                && qt.upper_item_count_threshold.is_none()
-            {
+            if !qt.has_upper_threshold() {
                 continue;
             }
 
@@ -218,7 +247,11 @@ impl ILPPromotionVars for TieredThresholdPromotionVars {
         item_group: &ItemGroup<'_>,
     ) -> Result<FxHashMap<usize, (i64, i64)>, SolverError> {
         if let Some(active_tier) = self.active_tier(solution) {
-            return calculate_discounts_for_tier(active_tier, solution, item_group);
+            return Ok(unit_prices_by_item(calculate_discounts_for_tier(
+                active_tier,
+                solution,
+                item_group,
+            )?));
         }
 
         Ok(FxHashMap::default())
@@ -231,9 +264,13 @@ impl ILPPromotionVars for TieredThresholdPromotionVars {
         item_group: &ItemGroup<'b>,
         next_redemption_idx: &mut usize,
     ) -> Result<SmallVec<[PromotionRedemption<'b>; 10]>, SolverError> {
-        let discounts = self.calculate_item_discounts(solution, item_group)?;
+        let Some(active_tier) = self.active_tier(solution) else {
+            return Ok(SmallVec::new());
+        };
+
+        let mut unit_prices = calculate_discounts_for_tier(active_tier, solution, item_group)?;
 
-        if discounts.is_empty() {
+        if unit_prices.is_empty() {
             return Ok(SmallVec::new());
         }
 
@@ -244,16 +281,9 @@ impl ILPPromotionVars for TieredThresholdPromotionVars {
 
         let mut redemptions = SmallVec::new();
 
-        let mut sorted_discounts: SmallVec<[(usize, (i64, i64)); 10]> = discounts
-            .iter()
-            .map(|(&item_idx, &(original_minor, final_minor))| {
-                (item_idx, (original_minor, final_minor))
-            })
-            .collect();
-
-        sorted_discounts.sort_by_key(|(item_idx, _)| *item_idx);
+        unit_prices.sort_by_key(|&(item_idx, _, _)| item_idx);
 
-        for (item_idx, (original_minor, final_minor)) in sorted_discounts {
+        for (item_idx, original_minor, final_minor) in unit_prices {
             redemptions.push(PromotionRedemption {
                 promotion_key,
                 item_idx,
@@ -297,14 +327,15 @@ impl TieredThresholdPromotionVars {
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
     ) -> Result<(), SolverError> {
-        self.add_tier_item_link_constraints(qt, state, observer);
+        self.add_tier_item_link_constraints(qt, item_group, state, observer)?;
         self.add_lower_threshold_constraints(qt, item_group, state, observer)?;
         self.add_upper_threshold_constraints(qt, item_group, state, observer)?;
         self.add_upper_cap_symmetry_break_constraints(qt, item_group, state, observer)?;
         self.add_tier_activation_constraint(qt, state, observer);
+        self.add_amount_off_constraints(qt, item_group, state, observer)?;
 
         if !qt.target_vars.is_empty() {
-            add_cheapest_constraints(qt, self.promotion_key, state, observer);
+            add_cheapest_constraints(qt, self.promotion_key, item_group, state, observer)?;
         }
 
         Ok(())
@@ -317,7 +348,7 @@ impl TieredThresholdPromotionVars {
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
     ) -> Result<(), SolverError> {
-        if qt.upper_monetary_threshold_minor.is_none() && qt.upper_item_count_threshold.is_none() {
+        if !qt.has_upper_threshold() {
             return Ok(());
         }
 
@@ -328,13 +359,13 @@ impl TieredThresholdPromotionVars {
         // Class key fields:
         // - ProductKey: same underlying SKU
         // - price_minor: same price point in this basket
+        // - measure: same weight or volume, for variable-measure items
         // - contributes: same role in threshold qualification
         // - discountable: same role in discount targeting
         //
-        // If two variables match on all four, swapping them does not change any
+        // If two variables match on all five, swapping them does not change any
         // business outcome, only solver branch shape.
-        let mut classes: FxHashMap<(ProductKey, i64, bool, bool), SmallVec<[Variable; 10]>> =
-            FxHashMap::default();
+        let mut classes: FxHashMap<SymmetryClass, SmallVec<[Variable; 10]>> = FxHashMap::default();
 
         for &(item_idx, item_var) in &qt.item_vars {
             let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
@@ -344,7 +375,13 @@ impl TieredThresholdPromotionVars {
             let discountable = qt.discount_vars.iter().any(|(idx, _)| *idx == item_idx);
 
             classes
-                .entry((item.product(), price_minor, contributes, discountable))
+                .entry((
+                    item.product(),
+                    price_minor,
+                    item.measure(),
+                    contributes,
+                    discountable,
+                ))
                 .or_default()
                 .push(item_var);
         }
@@ -380,12 +417,14 @@ impl TieredThresholdPromotionVars {
     fn add_tier_item_link_constraints(
         &self,
         qt: &QualifyingTier,
+        item_group: &ItemGroup<'_>,
         state: &mut ILPState,
         observer: &mut dyn ILPObserver,
-    ) {
-        // Link items to their tier: d_{t,i} <= tier_t
-        for &(_item_idx, item_var) in &qt.item_vars {
-            let link_expr = Expression::from(item_var) - Expression::from(qt.tier_var);
+    ) -> Result<(), SolverError> {
+        // Link items to their tier: d_{t,i} <= quantity_i * tier_t
+        for &(item_idx, item_var) in &qt.item_vars {
+            let quantity = f64::from(item_group.get_item(item_idx)?.quantity());
+            let link_expr = Expression::from(item_var) - Expression::from(qt.tier_var) * quantity;
 
             observer.on_promotion_constraint(
                 self.promotion_key,
@@ -397,6 +436,8 @@ impl TieredThresholdPromotionVars {
 
             state.add_leq_constraint(link_expr, 0.0);
         }
+
+        Ok(())
     }
 
     fn add_lower_threshold_constraints(
@@ -449,6 +490,30 @@ impl TieredThresholdPromotionVars {
             state.add_geq_constraint(item_count_expr, 0.0);
         }
 
+        if let Some(lower_measure_threshold) = qt.lower_measure_threshold {
+            // Threshold measure: sum(measure_i * c_{t,i}) >= measure_t * tier_t
+            let contribution_measure_expr = weighted_measure_sum_expr(
+                item_group,
+                &qt.contribution_vars,
+                lower_measure_threshold,
+            )?;
+
+            let measure_coeff = u32_to_f64_exact(lower_measure_threshold.amount())?;
+
+            let measure_expr =
+                contribution_measure_expr - Expression::from(qt.tier_var) * measure_coeff;
+
+            observer.on_promotion_constraint(
+                self.promotion_key,
+                "Lower threshold measure",
+                &measure_expr,
+                ">=",
+                0.0,
+            );
+
+            state.add_geq_constraint(measure_expr, 0.0);
+        }
+
         Ok(())
     }
 
@@ -463,6 +528,7 @@ impl TieredThresholdPromotionVars {
         // to this tier instance's qualification and discountable value.
         self.add_upper_monetary_threshold_constraints(qt, item_group, state, observer)?;
         self.add_upper_item_count_threshold_constraints(qt, state, observer)?;
+        self.add_upper_measure_threshold_constraints(qt, item_group, state, observer)?;
 
         Ok(())
     }
@@ -636,6 +702,64 @@ impl TieredThresholdPromotionVars {
         Ok(())
     }
 
+    fn add_upper_measure_threshold_constraints(
+        &self,
+        qt: &QualifyingTier,
+        item_group: &ItemGroup<'_>,
+        state: &mut ILPState,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<(), SolverError> {
+        let Some(upper_measure_threshold) = qt.upper_measure_threshold else {
+            return Ok(());
+        };
+
+        let upper_measure_coeff = u32_to_f64_exact(upper_measure_threshold.amount())?;
+
+        // Measures are never negative, so subset implication is always safe here.
+        let emission = upper_cap_emission_for_sets(&qt.contribution_vars, &qt.discount_vars, true);
+
+        let caps = [
+            (
+                matches!(
+                    emission,
+                    UpperCapEmission::ContributionOnly | UpperCapEmission::Both
+                ),
+                &qt.contribution_vars,
+                "Upper threshold measure (contribution)",
+            ),
+            (
+                matches!(
+                    emission,
+                    UpperCapEmission::DiscountableOnly | UpperCapEmission::Both
+                ),
+                &qt.discount_vars,
+                "Upper threshold measure (discountable)",
+            ),
+        ];
+
+        for (emit, vars, constraint_type) in caps {
+            if !emit {
+                continue;
+            }
+
+            let measure_expr =
+                weighted_measure_sum_expr(item_group, vars, upper_measure_threshold)?;
+            let measure_cap_expr =
+                measure_expr - Expression::from(qt.tier_var) * upper_measure_coeff;
+
+            observer.on_promotion_constraint(
+                self.promotion_key,
+                constraint_type,
+                &measure_cap_expr,
+                "<=",
+                0.0,
+            );
+            state.add_leq_constraint(measure_cap_expr, 0.0);
+        }
+
+        Ok(())
+    }
+
     fn add_tier_activation_constraint(
         &self,
         qt: &QualifyingTier,
@@ -657,6 +781,103 @@ impl TieredThresholdPromotionVars {
         state.add_leq_constraint(expr, 0.0);
     }
 
+    /// Bound the amount taken off by the tier's amount and by the discounted items' value.
+    fn add_amount_off_constraints(
+        &self,
+        qt: &QualifyingTier,
+        item_group: &ItemGroup<'_>,
+        state: &mut ILPState,
+        observer: &mut dyn ILPObserver,
+    ) -> Result<(), SolverError> {
+        let (Some(amount_off), Some(amount)) = (qt.amount_off, qt.amount_off_total_minor) else {
+            return Ok(());
+        };
+
+        let amount_coeff =
+            i64_to_f64_exact(amount).ok_or(SolverError::MinorUnitsNotRepresentable(amount))?;
+
+        // d <= amount * tier_t
+        let amount_expr = Expression::from(amount_off) - amount_coeff * qt.tier_var;
+
+        observer.on_promotion_constraint(
+            self.promotion_key,
+            "Amount off per tier",
+            &amount_expr,
+            "<=",
+            0.0,
+        );
+
+        state.add_leq_constraint(amount_expr, 0.0);
+
+        // d <= sum(price_i * d_{t,i})
+        let value_expr =
+            Expression::from(amount_off) - weighted_price_sum_expr(item_group, &qt.discount_vars)?;
+
+        observer.on_promotion_constraint(
+            self.promotion_key,
+            "Amount off value",
+            &value_expr,
+            "<=",
+            0.0,
+        );
+
+        state.add_leq_constraint(value_expr.clone(), 0.0);
+
+        // Minimising the cost already takes the full discount. Under a monetary
+        // budget it must not take less, so a binary picks which bound it meets.
+        if self.monetary_limit_minor.is_some() {
+            let clamped = state.problem_variables_mut().add(variable().binary());
+
+            observer.on_auxiliary_variable(
+                self.promotion_key,
+                clamped,
+                "Amount off clamped",
+                None,
+                None,
+            );
+
+            // d >= amount * tier_t, unless clamped.
+            let full_expr =
+                Expression::from(amount_off) - amount_coeff * qt.tier_var + amount_coeff * clamped;
+
+            observer.on_promotion_constraint(
+                self.promotion_key,
+                "Amount off full",
+                &full_expr,
+                ">=",
+                0.0,
+            );
+
+            state.add_geq_constraint(full_expr, 0.0);
+
+            // d >= sum(price_i * d_{t,i}), when clamped.
+            let mut max_value = 0.0;
+
+            for &(item_idx, _) in &qt.discount_vars {
+                let item = item_group.get_item(item_idx)?;
+                let minor = item.price().to_minor_units();
+
+                max_value += i64_to_f64_exact(minor)
+                    .ok_or(SolverError::MinorUnitsNotRepresentable(minor))?
+                    * f64::from(item.quantity());
+            }
+
+            let clamped_expr = value_expr + max_value - max_value * clamped;
+
+            observer.on_promotion_constraint(
+                self.promotion_key,
+                "Amount off clamped",
+                &clamped_expr,
+                ">=",
+                0.0,
+            );
+
+            state.add_geq_constraint(clamped_expr, 0.0);
+        }
+
+        Ok(())
+    }
+
     /// Add budget constraints to the ILP state.
     fn add_budget_constraints(
         &self,
@@ -706,6 +927,15 @@ impl TieredThresholdPromotionVars {
 
                         discount_expr += target_var * coeff;
                     }
+                } else if let Some(amount_off) = qt.amount_off {
+                    discount_expr += amount_off;
+                } else if let Some(fixed) = qt.fixed_total_minor {
+                    // The discounted items' value, less the fixed total they sell for.
+                    let fixed_coeff = i64_to_f64_exact(fixed.max(0))
+                        .ok_or(SolverError::MinorUnitsNotRepresentable(fixed))?;
+
+                    discount_expr += weighted_price_sum_expr(item_group, &qt.discount_vars)?
+                        - fixed_coeff * qt.tier_var;
                 } else {
                     for &(item_idx, var) in &qt.item_vars {
                         let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
@@ -740,6 +970,9 @@ impl TieredThresholdPromotionVars {
     }
 }
 
+/// Items in the same class are interchangeable for a capped tier.
+type SymmetryClass = (ProductKey, i64, Option<Measure>, bool, bool);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum UpperCapEmission {
     /// No cap row is needed (no variables on either side).
@@ -836,6 +1069,97 @@ fn weighted_price_sum_expr(
     Ok(expr)
 }
 
+/// Sum the measures of the given item variables, in the unit of `threshold`.
+///
+/// Items sold by count, or measured in a different unit, contribute nothing.
+fn weighted_measure_sum_expr(
+    item_group: &ItemGroup<'_>,
+    vars: &SmallVec<[(usize, Variable); 10]>,
+    threshold: Measure,
+) -> Result<Expression, SolverError> {
+    let mut expr = Expression::default();
+
+    for &(item_idx, var) in vars {
+        let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
+
+        let amount = measure_amount_in_unit(item.measure(), threshold.unit());
+
+        if amount > 0 {
+            expr += var * u32_to_f64_exact(amount)?;
+        }
+    }
+
+    Ok(expr)
+}
+
+/// What `item_group` still needs to reach the lower threshold of `tier`.
+fn tier_shortfall(
+    tier_idx: usize,
+    tier: &ThresholdTier<'_>,
+    item_group: &ItemGroup<'_>,
+) -> TierShortfall {
+    let threshold = tier.lower_threshold();
+    let contribution_qualification = tier.contribution_qualification();
+    let contributing_items = || {
+        item_group
+            .iter()
+            .filter(|item| contribution_qualification.matches(item.tags()))
+    };
+
+    let spend_minor = threshold.monetary_threshold().and_then(|threshold| {
+        let spend: i64 = contributing_items()
+            .map(|item| item.price().to_minor_units() * i64::from(item.quantity()))
+            .sum();
+        let remaining = threshold.to_minor_units().saturating_sub(spend);
+
+        (remaining > 0).then_some(remaining)
+    });
+
+    let items = threshold.item_count_threshold().and_then(|threshold| {
+        let count = contributing_items()
+            .map(Item::quantity)
+            .fold(0, u32::saturating_add);
+        let remaining = threshold.saturating_sub(count);
+
+        (remaining > 0).then_some(remaining)
+    });
+
+    let measure = threshold.measure_threshold().and_then(|threshold| {
+        let total: u64 = contributing_items()
+            .map(|item| {
+                u64::from(measure_amount_in_unit(item.measure(), threshold.unit()))
+                    * u64::from(item.quantity())
+            })
+            .sum();
+        let remaining = u64::from(threshold.amount()).saturating_sub(total);
+        let remaining = u32::try_from(remaining).unwrap_or(u32::MAX);
+
+        (remaining > 0).then(|| match threshold.unit() {
+            MeasureUnit::Gram => Measure::grams(remaining),
+            MeasureUnit::Millilitre => Measure::millilitres(remaining),
+        })
+    });
+
+    let discounted_item = !item_group
+        .iter()
+        .any(|item| tier.discount_qualification().matches(item.tags()));
+
+    TierShortfall {
+        tier_idx,
+        spend_minor,
+        items,
+        measure,
+        discounted_item,
+    }
+}
+
+/// Amount of `measure` if it is expressed in `unit`, otherwise zero.
+fn measure_amount_in_unit(measure: Option<Measure>, unit: MeasureUnit) -> u32 {
+    measure
+        .filter(|measure| measure.unit() == unit)
+        .map_or(0, |measure| measure.amount())
+}
+
 fn u32_to_f64_exact(value: u32) -> Result<f64, SolverError> {
     let as_i64 = i64::from(value);
 
@@ -911,9 +1235,10 @@ fn estimate_target_discounted_minor_for_budget(
 fn add_cheapest_constraints(
     qt: &QualifyingTier,
     promotion_key: PromotionKey,
+    item_group: &ItemGroup<'_>,
     state: &mut ILPState,
     observer: &mut dyn ILPObserver,
-) {
+) -> Result<(), SolverError> {
     // target_i <= d_{t,i} (can only target a claimed item)
     for &(item_idx, target_var) in &qt.target_vars {
         if let Some(&(_, item_var)) = qt.discount_vars.iter().find(|(idx, _)| *idx == item_idx) {
@@ -938,40 +1263,40 @@ fn add_cheapest_constraints(
     observer.on_promotion_constraint(promotion_key, "target count", &expr, "<=", 0.0);
     state.add_leq_constraint(expr, 0.0);
 
-    // Cheapest ordering: target_k + d_{k-1} <= 1
-    // (target_vars are sorted by price ascending)
-    for k in 1..qt.target_vars.len() {
-        let Some(&(prev_idx, _)) = qt.target_vars.get(k - 1) else {
-            continue;
-        };
+    // Cheapest ordering: quantity_k * sum(target_j for j <= k) - d_{t,k} >= 0
+    // (target_vars are sorted by price ascending, so a claimed item is only
+    // ever outranked by a cheaper target, and every unit of a claimed line is)
+    let mut prefix_targets = Expression::default();
 
-        let Some(&(_, curr_target)) = qt.target_vars.get(k) else {
-            continue;
-        };
+    for &(item_idx, target_var) in &qt.target_vars {
+        prefix_targets += target_var;
 
-        let Some(&(_, prev_d)) = qt.discount_vars.iter().find(|(idx, _)| *idx == prev_idx) else {
+        let Some(&(_, item_var)) = qt.discount_vars.iter().find(|(idx, _)| *idx == item_idx) else {
             continue;
         };
 
-        let expr = Expression::from(curr_target) + Expression::from(prev_d);
+        let quantity = f64::from(item_group.get_item(item_idx)?.quantity());
+        let expr = prefix_targets.clone() * quantity - Expression::from(item_var);
 
-        observer.on_promotion_constraint(promotion_key, "cheapest ordering", &expr, "<=", 1.0);
+        observer.on_promotion_constraint(promotion_key, "cheapest ordering", &expr, ">=", 0.0);
 
-        state.add_leq_constraint(expr, 1.0);
+        state.add_geq_constraint(expr, 0.0);
     }
+
+    Ok(())
 }
 
-/// Compute final per-item prices for the active tier.
+/// Compute final per-unit prices for the active tier.
 fn calculate_discounts_for_tier(
     qt: &QualifyingTier,
     solution: &dyn Solution,
     item_group: &ItemGroup<'_>,
-) -> Result<FxHashMap<usize, (i64, i64)>, SolverError> {
+) -> Result<UnitPrices, SolverError> {
     let mut discounts = if qt.has_per_item_discount() {
         calculate_per_item_discounts(qt, solution, item_group)?
     } else if let Some(amount) = qt.amount_off_total_minor {
         calculate_total_discounts(&qt.discount_vars, solution, item_group, &|total| {
-            total.saturating_sub(amount)
+            total.saturating_sub(amount).max(0)
         })?
     } else if let Some(fixed) = qt.fixed_total_minor {
         calculate_total_discounts(&qt.discount_vars, solution, item_group, &|_total| {
@@ -1014,29 +1339,41 @@ fn calculate_discounts_for_tier(
     // Participation is exclusive across promotions even for non-discounted
     // contribution items; include them with full prices.
     for &(item_idx, item_var) in &qt.item_vars {
-        if solution.value(item_var) <= BINARY_THRESHOLD || discounts.contains_key(&item_idx) {
+        if qt.discount_vars.iter().any(|&(_, var)| var == item_var) {
             continue;
         }
 
         let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
         let full_minor = item.price().to_minor_units();
 
-        discounts.insert(item_idx, (full_minor, full_minor));
+        for _ in 0..solved_count(solution, item_var) {
+            discounts.push((item_idx, full_minor, full_minor));
+        }
     }
 
     Ok(discounts)
 }
 
+/// Final price of each claimed item, by item index.
+fn unit_prices_by_item(unit_prices: UnitPrices) -> FxHashMap<usize, (i64, i64)> {
+    unit_prices
+        .into_iter()
+        .map(|(item_idx, original_minor, final_minor)| (item_idx, (original_minor, final_minor)))
+        .collect()
+}
+
 /// Per-item discount: use pre-computed discounted prices.
 fn calculate_per_item_discounts(
     qt: &QualifyingTier,
     solution: &dyn Solution,
     item_group: &ItemGroup<'_>,
-) -> Result<FxHashMap<usize, (i64, i64)>, SolverError> {
-    let mut discounts = FxHashMap::default();
+) -> Result<UnitPrices, SolverError> {
+    let mut discounts = SmallVec::new();
 
     for &(item_idx, item_var) in &qt.discount_vars {
-        if solution.value(item_var) <= BINARY_THRESHOLD {
+        let count = solved_count(solution, item_var);
+
+        if count == 0 {
             continue;
         }
 
@@ -1048,7 +1385,9 @@ fn calculate_per_item_discounts(
             },
         )?;
 
-        discounts.insert(item_idx, (item.price().to_minor_units(), discounted));
+        for _ in 0..count {
+            discounts.push((item_idx, item.price().to_minor_units(), discounted));
+        }
     }
 
     Ok(discounts)
@@ -1060,19 +1399,17 @@ fn calculate_total_discounts(
     solution: &dyn Solution,
     item_group: &ItemGroup<'_>,
     new_total: &dyn Fn(i64) -> i64,
-) -> Result<FxHashMap<usize, (i64, i64)>, SolverError> {
-    let mut discounts = FxHashMap::default();
+) -> Result<UnitPrices, SolverError> {
+    let mut discounts = SmallVec::new();
 
     let mut claimed: SmallVec<[(usize, i64); 10]> = SmallVec::new();
 
     for &(item_idx, item_var) in discount_vars {
-        if solution.value(item_var) <= BINARY_THRESHOLD {
-            continue;
-        }
-
         let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
 
-        claimed.push((item_idx, item.price().to_minor_units()));
+        for _ in 0..solved_count(solution, item_var) {
+            claimed.push((item_idx, item.price().to_minor_units()));
+        }
     }
 
     if claimed.is_empty() {
@@ -1095,7 +1432,7 @@ fn calculate_total_discounts(
 
         remaining -= final_minor;
 
-        discounts.insert(item_idx, (full_minor, final_minor));
+        discounts.push((item_idx, full_minor, final_minor));
     }
 
     Ok(discounts)
@@ -1108,29 +1445,30 @@ fn calculate_cheapest_discounts(
     solution: &dyn Solution,
     item_group: &ItemGroup<'_>,
     target_price: &dyn Fn(i64) -> i64,
-) -> Result<FxHashMap<usize, (i64, i64)>, SolverError> {
-    let mut discounts = FxHashMap::default();
+) -> Result<UnitPrices, SolverError> {
+    let mut discounts = SmallVec::new();
 
-    let target_idx = target_vars
+    let mut target_idx = target_vars
         .iter()
         .find(|(_, var)| solution.value(*var) > BINARY_THRESHOLD)
         .map(|(idx, _)| *idx);
 
     for &(item_idx, item_var) in discount_vars {
-        if solution.value(item_var) <= BINARY_THRESHOLD {
-            continue;
-        }
-
         let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
         let full = item.price().to_minor_units();
 
-        let final_minor = if Some(item_idx) == target_idx {
-            target_price(full)
-        } else {
-            full
-        };
+        // Only one unit of a targeted line is the cheapest item.
+        for _ in 0..solved_count(solution, item_var) {
+            let final_minor = if target_idx == Some(item_idx) {
+                target_idx = None;
+
+                target_price(full)
+            } else {
+                full
+            };
 
-        discounts.insert(item_idx, (full, final_minor));
+            discounts.push((item_idx, full, final_minor));
+        }
     }
 
     Ok(discounts)
@@ -1216,6 +1554,51 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
         })
     }
 
+    fn is_item_eligible(&self, item: &Item<'_>) -> bool {
+        // Contributing items are claimed by the promotion just like discounted ones.
+        self.tiers().iter().any(|tier| {
+            tier.contribution_qualification().matches(item.tags())
+                || tier.discount_qualification().matches(item.tags())
+        })
+    }
+
+    fn progress(&self, item_group: &ItemGroup<'_>) -> PromotionProgress {
+        if self.budget().is_exhausted() {
+            return PromotionProgress {
+                missing: Some(MissingRequirement::BudgetExhausted),
+                ..PromotionProgress::default()
+            };
+        }
+
+        let shortfalls: SmallVec<[TierShortfall; 3]> = self
+            .tiers()
+            .iter()
+            .enumerate()
+            .map(|(tier_idx, tier)| tier_shortfall(tier_idx, tier, item_group))
+            .collect();
+
+        // Tiers are listed in ascending order, each saving more than the one before,
+        // so the next tier is the one after the highest tier reached.
+        match shortfalls.iter().rposition(TierShortfall::is_reached) {
+            Some(reached) => PromotionProgress {
+                next_tier: shortfalls.get(reached.saturating_add(1)).cloned(),
+                ..PromotionProgress::default()
+            },
+            None => PromotionProgress {
+                missing: shortfalls.into_iter().next().map(MissingRequirement::Tier),
+                ..PromotionProgress::default()
+            },
+        }
+    }
+
+    fn promotion_type(&self) -> Option<PromotionType<'_>> {
+        Some(PromotionType::TieredThreshold(self))
+    }
+
+    fn supports_quantities(&self) -> bool {
+        true
+    }
+
     #[expect(
         clippy::too_many_lines,
         reason = "Variable creation for multiple discount types"
@@ -1246,21 +1629,37 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 .upper_threshold()
                 .and_then(TierThreshold::item_count_threshold);
 
+            let lower_measure_threshold = tier.lower_threshold().measure_threshold();
+
+            let upper_measure_threshold = tier
+                .upper_threshold()
+                .and_then(TierThreshold::measure_threshold);
+
             let contribution_qualification = tier.contribution_qualification();
             let discount_qualification = tier.discount_qualification();
 
             let contribution_total: i64 = item_group
                 .iter()
                 .filter(|item| contribution_qualification.matches(item.tags()))
-                .map(|item| item.price().to_minor_units())
+                .map(|item| item.price().to_minor_units() * i64::from(item.quantity()))
                 .sum();
 
-            let contribution_count = item_group
+            let contribution_count_u32 = item_group
                 .iter()
                 .filter(|item| contribution_qualification.matches(item.tags()))
-                .count();
-
-            let contribution_count_u32 = u32::try_from(contribution_count).unwrap_or(u32::MAX);
+                .map(Item::quantity)
+                .fold(0, u32::saturating_add);
+
+            let contribution_measure: u64 = lower_measure_threshold.map_or(0, |threshold| {
+                item_group
+                    .iter()
+                    .filter(|item| contribution_qualification.matches(item.tags()))
+                    .map(|item| {
+                        u64::from(measure_amount_in_unit(item.measure(), threshold.unit()))
+                            * u64::from(item.quantity())
+                    })
+                    .sum()
+            });
 
             // Skip tiers that can never meet their thresholds even if they claim all
             // available contribution items.
@@ -1276,6 +1675,12 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 continue;
             }
 
+            if lower_measure_threshold
+                .is_some_and(|threshold| contribution_measure < u64::from(threshold.amount()))
+            {
+                continue;
+            }
+
             if lower_monetary_threshold_minor
                 .zip(upper_monetary_threshold_minor)
                 .is_some_and(|(lower, upper)| upper < lower)
@@ -1290,6 +1695,16 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 continue;
             }
 
+            // Only a cap in the same unit as the lower threshold can contradict it.
+            if lower_measure_threshold
+                .zip(upper_measure_threshold)
+                .is_some_and(|(lower, upper)| {
+                    lower.unit() == upper.unit() && upper.amount() < lower.amount()
+                })
+            {
+                continue;
+            }
+
             // Create tier auxiliary variable
             let tier_var = state.problem_variables_mut().add(variable().binary());
 
@@ -1309,9 +1724,7 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                     (true, None, None, None, None, false, 0_i64)
                 }
                 ThresholdDiscount::AmountOffTotal(a) => {
-                    let m = a.to_minor_units();
-
-                    (false, Some(m), None, None, None, false, -m)
+                    (false, Some(a.to_minor_units()), None, None, None, false, 0)
                 }
                 ThresholdDiscount::FixedTotal(a) => {
                     let m = a.to_minor_units();
@@ -1345,6 +1758,21 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 observer.on_objective_term(tier_var, coeff);
             }
 
+            // Amount off total objective term (negative discount taken off the total).
+            // It is bounded in the constraints, so the total never goes below zero.
+            let amount_off = if amount_off_total_minor.is_some() {
+                let var = state.problem_variables_mut().add(variable().min(0));
+
+                observer.on_auxiliary_variable(promotion_key, var, "Amount off", None, None);
+
+                state.add_to_objective(var, -1.0);
+                observer.on_objective_term(var, -1.0);
+
+                Some(var)
+            } else {
+                None
+            };
+
             // Create participation variables. Items that contribute to the
             // threshold and/or receive discount are participating and therefore
             // exclusive against other promotions in this layer.
@@ -1380,7 +1808,7 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
             for (item_idx, price, contributes, discountable) in eligible_items {
                 let item = item_group.get_item(item_idx).map_err(SolverError::from)?;
 
-                let item_var = state.problem_variables_mut().add(variable().binary());
+                let item_var = state.problem_variables_mut().add(unit_count_variable(item));
                 item_vars.push((item_idx, item_var));
 
                 if contributes {
@@ -1460,6 +1888,8 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 lower_item_count_threshold,
                 upper_monetary_threshold_minor,
                 upper_item_count_threshold,
+                lower_measure_threshold,
+                upper_measure_threshold,
                 tier_var,
                 item_vars,
                 contribution_vars,
@@ -1468,6 +1898,7 @@ impl ILPPromotion for TieredThresholdPromotion<'_> {
                 target_vars,
                 has_per_item_discount,
                 amount_off_total_minor,
+                amount_off,
                 fixed_total_minor,
                 percent_cheapest,
                 fixed_cheapest_minor,
@@ -1643,6 +2074,74 @@ mod tests {
         assert!(promo.is_applicable(&item_group));
     }
 
+    #[test]
+    fn progress_reports_lowest_tier_shortfall_then_next_tier() {
+        let promo = TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![
+                make_tier_with_tags_and_item_count(
+                    1000,
+                    2,
+                    &["wine"],
+                    &["cheese"],
+                    ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+                ),
+                make_tier_with_tags(
+                    3000,
+                    &["wine"],
+                    &["cheese"],
+                    ThresholdDiscount::PercentEachItem(Percentage::from(0.20)),
+                ),
+            ],
+            PromotionBudget::unlimited(),
+        );
+
+        let tagged = |price: i64, tags: &[&str]| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(tags),
+            )
+        };
+
+        let one_wine = item_group_from_items([tagged(800, &["wine"])]);
+
+        assert_eq!(
+            promo.progress(&one_wine),
+            PromotionProgress {
+                missing: Some(MissingRequirement::Tier(TierShortfall {
+                    tier_idx: 0,
+                    spend_minor: Some(200),
+                    items: Some(1),
+                    measure: None,
+                    discounted_item: true,
+                })),
+                ..PromotionProgress::default()
+            }
+        );
+
+        let first_tier = item_group_from_items([
+            tagged(800, &["wine"]),
+            tagged(700, &["wine"]),
+            tagged(500, &["cheese"]),
+        ]);
+
+        assert_eq!(
+            promo.progress(&first_tier),
+            PromotionProgress {
+                missing: None,
+                next_tier: Some(TierShortfall {
+                    tier_idx: 1,
+                    spend_minor: Some(1500),
+                    items: None,
+                    measure: None,
+                    discounted_item: false,
+                }),
+                ..PromotionProgress::default()
+            }
+        );
+    }
+
     #[test]
     fn add_variables_produces_no_vars_when_no_tiers_qualify() -> TestResult {
         let items = [Item::with_tags(
@@ -2331,6 +2830,86 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn measure_threshold_constraints_are_emitted_when_configured() -> TestResult {
+        let items = [Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(200, GBP),
+            StringTagCollection::from_strs(&["produce"]),
+            Measure::grams(1500),
+        )?];
+
+        let item_group = item_group_from_items(items);
+
+        let tier = ThresholdTier::new(
+            TierThreshold::with_measure_threshold(Measure::kilograms(1)),
+            Some(TierThreshold::with_measure_threshold(Measure::kilograms(3))),
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+        );
+
+        let promo = TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![tier],
+            PromotionBudget::unlimited(),
+        );
+
+        let mut state = ILPState::new(ProblemVariables::new(), Expression::default());
+        let mut observer = RecordingObserver::default();
+
+        let vars = promo.add_variables(&item_group, &mut state, &mut observer)?;
+        vars.add_constraints(promo.key(), &item_group, &mut state, &mut observer)?;
+
+        let constraint_types: Vec<&str> = observer
+            .promotion_constraints
+            .iter()
+            .map(|record| record.constraint_type.as_str())
+            .collect();
+
+        assert!(constraint_types.contains(&"Lower threshold measure"));
+        assert!(constraint_types.contains(&"Upper threshold measure (contribution)"));
+        assert!(!constraint_types.contains(&"Upper threshold measure (discountable)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tier_is_skipped_when_measure_threshold_unreachable() -> TestResult {
+        // 5 litres does not count towards a weight threshold.
+        let items = [Item::with_measure(
+            ProductKey::default(),
+            Money::from_minor(200, GBP),
+            StringTagCollection::from_strs(&["produce"]),
+            Measure::millilitres(5000),
+        )?];
+
+        let item_group = item_group_from_items(items);
+
+        let tier = ThresholdTier::new(
+            TierThreshold::with_measure_threshold(Measure::kilograms(1)),
+            None,
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+        );
+
+        let promo = TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![tier],
+            PromotionBudget::unlimited(),
+        );
+
+        let mut state = ILPState::new(ProblemVariables::new(), Expression::default());
+        let mut observer = CountingObserver::default();
+
+        promo.add_variables(&item_group, &mut state, &mut observer)?;
+
+        assert_eq!(observer.promotion_variables, 0);
+
+        Ok(())
+    }
+
     #[test]
     fn upper_spend_constraints_are_deduplicated_when_sets_match() -> TestResult {
         let items = [Item::with_tags(
@@ -2539,6 +3118,8 @@ mod tests {
             lower_item_count_threshold: None,
             upper_monetary_threshold_minor: None,
             upper_item_count_threshold: None,
+            lower_measure_threshold: None,
+            upper_measure_threshold: None,
             tier_var,
             item_vars: SmallVec::new(),
             contribution_vars: SmallVec::new(),
@@ -2547,6 +3128,7 @@ mod tests {
             target_vars: SmallVec::new(),
             has_per_item_discount: false,
             amount_off_total_minor: Some(50),
+            amount_off: None,
             fixed_total_minor: None,
             percent_cheapest: None,
             fixed_cheapest_minor: None,
@@ -2559,6 +3141,7 @@ mod tests {
         let per_item_tier = QualifyingTier {
             has_per_item_discount: true,
             amount_off_total_minor: None,
+            amount_off: None,
             ..bundle_tier
         };
 
@@ -2585,9 +3168,12 @@ mod tests {
         let discount_vars = SmallVec::from_vec(vec![(0, v0), (1, v1)]);
         let solution = MapSolution::with(&[(v0, 1.0), (v1, 1.0)]);
 
-        let discounts = calculate_total_discounts(&discount_vars, &solution, &item_group, &|t| {
-            t.saturating_sub(60)
-        })?;
+        let discounts = unit_prices_by_item(calculate_total_discounts(
+            &discount_vars,
+            &solution,
+            &item_group,
+            &|t| t.saturating_sub(60),
+        )?);
 
         assert_eq!(discounts.get(&0), Some(&(200, 160)));
         assert_eq!(discounts.get(&1), Some(&(100, 80)));
@@ -2615,8 +3201,12 @@ mod tests {
         let discount_vars = SmallVec::from_vec(vec![(0, v0), (1, v1), (2, v2)]);
         let solution = MapSolution::with(&[(v0, 1.0), (v1, 1.0), (v2, 1.0)]);
 
-        let discounts =
-            calculate_total_discounts(&discount_vars, &solution, &item_group, &|_| 100)?;
+        let discounts = unit_prices_by_item(calculate_total_discounts(
+            &discount_vars,
+            &solution,
+            &item_group,
+            &|_| 100,
+        )?);
 
         assert_eq!(discounts.get(&0), Some(&(100, 33)));
         assert_eq!(discounts.get(&1), Some(&(100, 33)));
@@ -2647,13 +3237,13 @@ mod tests {
         let target_vars = SmallVec::from_vec(vec![(0, t0), (1, t1)]);
         let solution = MapSolution::with(&[(d0, 1.0), (d1, 1.0), (t0, 0.0), (t1, 1.0)]);
 
-        let discounts = calculate_cheapest_discounts(
+        let discounts = unit_prices_by_item(calculate_cheapest_discounts(
             &discount_vars,
             &target_vars,
             &solution,
             &item_group,
             &|price| price.saturating_sub(40),
-        )?;
+        )?);
 
         assert_eq!(discounts.get(&0), Some(&(250, 250)));
         assert_eq!(discounts.get(&1), Some(&(150, 110)));
@@ -2674,6 +3264,8 @@ mod tests {
             lower_item_count_threshold: None,
             upper_monetary_threshold_minor: None,
             upper_item_count_threshold: None,
+            lower_measure_threshold: None,
+            upper_measure_threshold: None,
             tier_var,
             item_vars: SmallVec::new(),
             contribution_vars: SmallVec::new(),
@@ -2682,6 +3274,7 @@ mod tests {
             target_vars: SmallVec::new(),
             has_per_item_discount: true,
             amount_off_total_minor: None,
+            amount_off: None,
             fixed_total_minor: None,
             percent_cheapest: None,
             fixed_cheapest_minor: None,
@@ -2806,7 +3399,7 @@ mod tests {
     }
 
     #[test]
-    fn add_variables_amount_off_total_uses_negative_tier_objective_term() -> TestResult {
+    fn add_variables_amount_off_total_uses_negative_amount_off_objective_term() -> TestResult {
         let items = [Item::with_tags(
             ProductKey::default(),
             Money::from_minor(1000, GBP),
@@ -2833,6 +3426,12 @@ mod tests {
 
         assert!(
             observer
+                .objective_terms
+                .iter()
+                .any(|(_, coeff)| (*coeff - -1.0).abs() < f64::EPSILON)
+        );
+        assert!(
+            !observer
                 .objective_terms
                 .iter()
                 .any(|(_, coeff)| (*coeff - -100.0).abs() < f64::EPSILON)
@@ -2898,6 +3497,8 @@ mod tests {
             lower_item_count_threshold: None,
             upper_monetary_threshold_minor: None,
             upper_item_count_threshold: None,
+            lower_measure_threshold: None,
+            upper_measure_threshold: None,
             tier_var,
             item_vars: SmallVec::from_vec(vec![(0, d0), (1, d1)]),
             contribution_vars: SmallVec::new(),
@@ -2906,6 +3507,7 @@ mod tests {
             target_vars: SmallVec::from_vec(vec![(0, t0), (1, t1)]),
             has_per_item_discount: false,
             amount_off_total_minor: None,
+            amount_off: None,
             fixed_total_minor: None,
             percent_cheapest: Some(Percentage::from(0.25)),
             fixed_cheapest_minor: None,
@@ -2913,7 +3515,8 @@ mod tests {
         };
 
         let solution = MapSolution::with(&[(d0, 1.0), (d1, 1.0), (t0, 1.0), (t1, 0.0)]);
-        let discounts = calculate_discounts_for_tier(&qt, &solution, &item_group)?;
+        let discounts =
+            unit_prices_by_item(calculate_discounts_for_tier(&qt, &solution, &item_group)?);
 
         assert_eq!(discounts.get(&0), Some(&(100, 75)));
         assert_eq!(discounts.get(&1), Some(&(200, 200)));
@@ -3007,7 +3610,7 @@ mod tests {
     }
 
     #[test]
-    fn add_cheapest_constraints_emit_expected_relations() {
+    fn add_cheapest_constraints_emit_expected_relations() -> TestResult {
         let mut state = ILPState::new(ProblemVariables::new(), Expression::default());
 
         let tier_var = state.problem_variables_mut().add(variable().binary());
@@ -3021,6 +3624,8 @@ mod tests {
             lower_item_count_threshold: None,
             upper_monetary_threshold_minor: None,
             upper_item_count_threshold: None,
+            lower_measure_threshold: None,
+            upper_measure_threshold: None,
             tier_var,
             item_vars: SmallVec::new(),
             contribution_vars: SmallVec::new(),
@@ -3029,6 +3634,7 @@ mod tests {
             target_vars: SmallVec::from_vec(vec![(0, t0), (1, t1)]),
             has_per_item_discount: false,
             amount_off_total_minor: None,
+            amount_off: None,
             fixed_total_minor: None,
             percent_cheapest: Some(Percentage::from(0.25)),
             fixed_cheapest_minor: None,
@@ -3037,9 +3643,15 @@ mod tests {
 
         let mut observer = RecordingObserver::default();
 
-        add_cheapest_constraints(&qt, PromotionKey::default(), &mut state, &mut observer);
+        add_cheapest_constraints(
+            &qt,
+            PromotionKey::default(),
+            &item_group_from_prices(&[100, 200]),
+            &mut state,
+            &mut observer,
+        )?;
 
-        assert_eq!(observer.promotion_constraints.len(), 4);
+        assert_eq!(observer.promotion_constraints.len(), 5);
 
         let satisfied =
             MapSolution::with(&[(tier_var, 1.0), (d0, 1.0), (d1, 0.0), (t0, 1.0), (t1, 0.0)]);
@@ -3056,11 +3668,13 @@ mod tests {
             .find(|record| record.constraint_type == "cheapest ordering")
             .map_or(f64::NAN, |record| satisfied.eval(&record.expr));
 
-        assert!((ordering_lhs - 1.0).abs() < f64::EPSILON);
+        assert!((ordering_lhs - 0.0).abs() < f64::EPSILON);
 
         let (_pb, _cost, _presence, constraints) = state.into_parts_with_constraints();
 
         assert_state_constraints_hold(&constraints, &satisfied);
+
+        Ok(())
     }
 
     #[test]
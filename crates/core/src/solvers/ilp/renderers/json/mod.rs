@@ -0,0 +1,587 @@
+//! ILP JSON Trace
+//!
+//! This module provides an observer that records every formulation callback,
+//! in order, into a serialisable trace. Unlike the Typst renderer, the trace is
+//! meant for tools: snapshot tests can diff it, and external visualisers can
+//! read the formulation and its solved values without parsing solver output.
+//!
+//! Variables are identified by their index in the solver's problem, and
+//! promotions and layers by their key's `u64` representation.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use lattice::solvers::ilp::{ILPSolver, renderers::json::TraceObserver};
+//! use std::path::Path;
+//! # use lattice::{fixtures::Fixture, items::groups::ItemGroup};
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let fixture = Fixture::from_set("example_direct_discounts")?;
+//! # let basket = fixture.basket(Some(10))?;
+//! # let item_group = ItemGroup::from(&basket);
+//! # let promotions = fixture.promotions();
+//!
+//! let mut observer = TraceObserver::new();
+//!
+//! let _result = ILPSolver::solve_with_observer(promotions, &item_group, &mut observer)?;
+//!
+//! observer.write(Path::new("trace.json"))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use good_lp::{Expression, IntoAffineExpression, Variable};
+use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use slotmap::{Key, SlotMap};
+
+use crate::{
+    graph::PromotionLayerKey,
+    items::groups::ItemGroup,
+    products::{Product, ProductKey},
+    promotions::{PromotionKey, PromotionMeta},
+    solvers::ilp::{ILPObserver, renderers::var_index},
+};
+
+/// Errors that can occur while writing a trace.
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+    /// Failed to serialise the trace.
+    #[error("Failed to serialise trace: {0}")]
+    Serialise(#[from] serde_json::Error),
+
+    /// Failed to write to the output file.
+    #[error("Failed to write to output file: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A term of a linear expression.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceTerm {
+    /// Variable index.
+    pub var: usize,
+
+    /// Coefficient of the variable.
+    pub coefficient: f64,
+}
+
+/// A recorded observer callback.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// A full-price presence variable was created for an item.
+    PresenceVariable {
+        /// Index of the item in the item group
+        item_idx: usize,
+
+        /// Variable index
+        var: usize,
+
+        /// Full price in minor units
+        price_minor: i64,
+    },
+
+    /// A promotion variable was created for an item.
+    PromotionVariable {
+        /// Promotion key
+        promotion: u64,
+
+        /// Index of the item in the item group
+        item_idx: usize,
+
+        /// Variable index
+        var: usize,
+
+        /// Discounted price in minor units
+        discounted_price_minor: i64,
+
+        /// Optional metadata, such as "participation" or "discount"
+        metadata: Option<String>,
+    },
+
+    /// An auxiliary variable was created for a promotion.
+    AuxiliaryVariable {
+        /// Promotion key
+        promotion: u64,
+
+        /// Variable index
+        var: usize,
+
+        /// Role of the variable, such as "DFA state"
+        role: String,
+
+        /// Optional position index
+        position: Option<usize>,
+
+        /// Optional state index
+        state: Option<usize>,
+    },
+
+    /// A variable's bounds, once the formulation is built.
+    VariableBounds {
+        /// Variable index
+        var: usize,
+
+        /// Lower bound, or `None` if unbounded below
+        min: Option<f64>,
+
+        /// Upper bound, or `None` if unbounded above
+        max: Option<f64>,
+
+        /// Whether the variable only takes integer values
+        integer: bool,
+    },
+
+    /// A term was added to the objective.
+    ObjectiveTerm {
+        /// Variable index
+        var: usize,
+
+        /// Coefficient in minor units
+        coefficient: f64,
+    },
+
+    /// An exclusivity constraint was added for an item.
+    ExclusivityConstraint {
+        /// Index of the item in the item group
+        item_idx: usize,
+
+        /// Left-hand side terms
+        terms: Vec<TraceTerm>,
+
+        /// Left-hand side constant
+        constant: f64,
+    },
+
+    /// A promotion constraint was added.
+    PromotionConstraint {
+        /// Promotion key
+        promotion: u64,
+
+        /// Constraint type, such as `"minimum_quantity"`
+        constraint_type: String,
+
+        /// Left-hand side terms
+        terms: Vec<TraceTerm>,
+
+        /// Left-hand side constant
+        constant: f64,
+
+        /// Relation operator ("=", "<=", ">=")
+        relation: String,
+
+        /// Right-hand side value
+        rhs: f64,
+    },
+
+    /// A variable's value in the solution the result was read from.
+    VariableValue {
+        /// Variable index
+        var: usize,
+
+        /// Solved value
+        value: f64,
+    },
+
+    /// Solving a graph layer began.
+    LayerBegin {
+        /// Layer key
+        layer: u64,
+
+        /// Graph node index
+        node_idx: usize,
+    },
+
+    /// Solving a graph layer ended.
+    LayerEnd,
+}
+
+/// Recorded formulation trace, with optional names for items and promotions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FormulationTrace {
+    /// Item index -> product name
+    pub item_names: Vec<Option<String>>,
+
+    /// Promotion key -> promotion name
+    pub promotion_names: BTreeMap<u64, String>,
+
+    /// Layer key -> layer name
+    pub layer_names: BTreeMap<u64, String>,
+
+    /// Callbacks, in the order they were made
+    pub events: Vec<TraceEvent>,
+}
+
+/// Observer that records every callback into a [`FormulationTrace`].
+#[derive(Debug, Clone, Default)]
+pub struct TraceObserver {
+    trace: FormulationTrace,
+}
+
+impl TraceObserver {
+    /// Create a new trace observer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a trace observer and attach product/promotion metadata for naming.
+    #[must_use]
+    pub fn new_with_metadata<'a>(
+        item_group: &ItemGroup<'a>,
+        product_meta: &SlotMap<ProductKey, Product<'a>>,
+        promotion_meta: &SlotMap<PromotionKey, PromotionMeta>,
+    ) -> Self {
+        let item_names = item_group
+            .iter()
+            .map(|item| {
+                product_meta
+                    .get(item.product())
+                    .map(|product| product.name.clone())
+            })
+            .collect();
+
+        let promotion_names = promotion_meta
+            .iter()
+            .map(|(key, meta)| (key_id(key), meta.name.clone()))
+            .collect();
+
+        let mut layer_names = BTreeMap::new();
+
+        for (_promotion_key, meta) in promotion_meta {
+            for (layer_key, layer_name) in &meta.layer_names {
+                layer_names
+                    .entry(key_id(layer_key))
+                    .or_insert_with(|| layer_name.clone());
+            }
+        }
+
+        Self {
+            trace: FormulationTrace {
+                item_names,
+                promotion_names,
+                layer_names,
+                events: Vec::new(),
+            },
+        }
+    }
+
+    /// Get the recorded trace.
+    #[must_use]
+    pub fn trace(&self) -> &FormulationTrace {
+        &self.trace
+    }
+
+    /// Take the recorded trace, leaving this observer empty.
+    #[must_use]
+    pub fn into_trace(self) -> FormulationTrace {
+        self.trace
+    }
+
+    /// Serialise the trace as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TraceError::Serialise`] if the trace cannot be serialised.
+    pub fn to_json(&self) -> Result<String, TraceError> {
+        Ok(serde_json::to_string_pretty(&self.trace)?)
+    }
+
+    /// Write the trace as JSON to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TraceError`] if the trace cannot be serialised or the file
+    /// cannot be written.
+    pub fn write(&self, path: &Path) -> Result<(), TraceError> {
+        fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        self.trace.events.push(event);
+    }
+}
+
+impl ILPObserver for TraceObserver {
+    fn on_presence_variable(&mut self, item_idx: usize, var: Variable, price_minor: i64) {
+        self.record(TraceEvent::PresenceVariable {
+            item_idx,
+            var: var_id(var),
+            price_minor,
+        });
+    }
+
+    fn on_promotion_variable(
+        &mut self,
+        promotion_key: PromotionKey,
+        item_idx: usize,
+        var: Variable,
+        discounted_price_minor: i64,
+        metadata: Option<&str>,
+    ) {
+        self.record(TraceEvent::PromotionVariable {
+            promotion: key_id(promotion_key),
+            item_idx,
+            var: var_id(var),
+            discounted_price_minor,
+            metadata: metadata.map(String::from),
+        });
+    }
+
+    fn on_auxiliary_variable(
+        &mut self,
+        promotion_key: PromotionKey,
+        var: Variable,
+        role: &str,
+        position: Option<usize>,
+        state: Option<usize>,
+    ) {
+        self.record(TraceEvent::AuxiliaryVariable {
+            promotion: key_id(promotion_key),
+            var: var_id(var),
+            role: role.to_string(),
+            position,
+            state,
+        });
+    }
+
+    fn on_variable_bounds(&mut self, var: Variable, min: f64, max: f64, integer: bool) {
+        self.record(TraceEvent::VariableBounds {
+            var: var_id(var),
+            min: min.is_finite().then_some(min),
+            max: max.is_finite().then_some(max),
+            integer,
+        });
+    }
+
+    fn on_objective_term(&mut self, var: Variable, coefficient: f64) {
+        self.record(TraceEvent::ObjectiveTerm {
+            var: var_id(var),
+            coefficient,
+        });
+    }
+
+    fn on_exclusivity_constraint(&mut self, item_idx: usize, constraint_expr: &Expression) {
+        self.record(TraceEvent::ExclusivityConstraint {
+            item_idx,
+            terms: terms(constraint_expr),
+            constant: constraint_expr.constant(),
+        });
+    }
+
+    fn on_promotion_constraint(
+        &mut self,
+        promotion_key: PromotionKey,
+        constraint_type: &str,
+        constraint_expr: &Expression,
+        relation: &str,
+        rhs: f64,
+    ) {
+        self.record(TraceEvent::PromotionConstraint {
+            promotion: key_id(promotion_key),
+            constraint_type: constraint_type.to_string(),
+            terms: terms(constraint_expr),
+            constant: constraint_expr.constant(),
+            relation: relation.to_string(),
+            rhs,
+        });
+    }
+
+    fn on_variable_value(&mut self, var: Variable, value: f64) {
+        self.record(TraceEvent::VariableValue {
+            var: var_id(var),
+            value,
+        });
+    }
+
+    fn on_layer_begin(&mut self, layer_key: PromotionLayerKey, node_idx: NodeIndex) {
+        self.record(TraceEvent::LayerBegin {
+            layer: key_id(layer_key),
+            node_idx: node_idx.index(),
+        });
+    }
+
+    fn on_layer_end(&mut self) {
+        self.record(TraceEvent::LayerEnd);
+    }
+}
+
+/// Stable identifier for a slotmap key.
+fn key_id<K: Key>(key: K) -> u64 {
+    key.data().as_ffi()
+}
+
+/// Variable index; `good_lp` variables always print their index.
+fn var_id(var: Variable) -> usize {
+    var_index(var).unwrap_or(usize::MAX)
+}
+
+/// Terms of `expr`, ordered by variable index so traces diff cleanly.
+fn terms(expr: &Expression) -> Vec<TraceTerm> {
+    let mut terms: Vec<TraceTerm> = expr
+        .linear_coefficients()
+        .map(|(var, coefficient)| TraceTerm {
+            var: var_id(var),
+            coefficient,
+        })
+        .collect();
+
+    terms.sort_by_key(|term| term.var);
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::{Money, iso::GBP};
+    use smallvec::smallvec;
+    use tempfile::tempdir;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        graph::PromotionGraph,
+        items::Item,
+        promotions::{
+            Promotion, budget::PromotionBudget, promotion, qualification::Qualification,
+            types::DirectDiscountPromotion,
+        },
+        solvers::ilp::ILPSolver,
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    /// A £3 shampoo.
+    fn shampoo() -> ItemGroup<'static> {
+        ItemGroup::new(
+            smallvec![Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["shampoo"]),
+            )],
+            GBP,
+        )
+    }
+
+    /// "20% off" shampoo.
+    fn shampoo_discount() -> Promotion<'static> {
+        promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["shampoo"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.2)),
+            PromotionBudget::unlimited(),
+        ))
+    }
+
+    fn solve_traced(observer: &mut TraceObserver) -> TestResult {
+        ILPSolver::solve_with_observer(&[shampoo_discount()], &shampoo(), observer)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace_records_formulation_and_solution() -> TestResult {
+        let mut observer = TraceObserver::new();
+
+        solve_traced(&mut observer)?;
+
+        let events = &observer.trace().events;
+
+        assert!(matches!(
+            events.first(),
+            Some(TraceEvent::PresenceVariable {
+                item_idx: 0,
+                var: 0,
+                price_minor: 300
+            })
+        ));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TraceEvent::PromotionVariable {
+                discounted_price_minor: 240,
+                ..
+            }
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TraceEvent::ExclusivityConstraint { item_idx: 0, terms, .. } if terms.len() == 2
+        )));
+
+        // The discounted option is taken and the full-price one is not.
+        let values: Vec<(usize, f64)> = events
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::VariableValue { var, value } => Some((*var, *value)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(values, vec![(0, 0.0), (1, 1.0)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_tags_events_by_kind() -> TestResult {
+        let mut observer = TraceObserver::new();
+
+        solve_traced(&mut observer)?;
+
+        let json: serde_json::Value = serde_json::from_str(&observer.to_json()?)?;
+        let first = json
+            .get("events")
+            .and_then(|events| events.get(0))
+            .ok_or("missing events")?;
+
+        assert_eq!(
+            first.get("event").and_then(serde_json::Value::as_str),
+            Some("presence_variable")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn layers_are_bracketed_by_begin_and_end() -> TestResult {
+        let graph = PromotionGraph::single_layer([shampoo_discount()])?;
+        let item_group = shampoo();
+        let mut observer = TraceObserver::new();
+
+        graph.evaluate_with_observer(&item_group, Some(&mut observer))?;
+
+        let events = &observer.trace().events;
+
+        assert!(matches!(
+            events.first(),
+            Some(TraceEvent::LayerBegin { .. })
+        ));
+        assert!(matches!(events.last(), Some(TraceEvent::LayerEnd)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_creates_json_file() -> TestResult {
+        let dir = tempdir()?;
+        let path = dir.path().join("trace.json");
+        let mut observer = TraceObserver::new();
+
+        solve_traced(&mut observer)?;
+        observer.write(&path)?;
+
+        let written = observer.into_trace();
+        let contents = fs::read_to_string(&path)?;
+
+        assert!(contents.contains("\"variable_value\""));
+        assert_eq!(contents, serde_json::to_string_pretty(&written)?);
+
+        Ok(())
+    }
+}
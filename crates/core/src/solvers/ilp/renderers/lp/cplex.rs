@@ -0,0 +1,163 @@
+//! CPLEX LP Format
+
+use super::{LpModel, LpVariable, OBJECTIVE_NAME, push_line, render_number};
+
+/// Terms written on each line of a long expression.
+const TERMS_PER_LINE: usize = 4;
+
+/// Render `model` in CPLEX LP format.
+pub(super) fn render(model: &LpModel) -> String {
+    let mut output = String::new();
+
+    push_line(&mut output, &format!("\\ {}", model.name));
+    output.push_str("Minimize\n");
+
+    push_line(
+        &mut output,
+        &format!(
+            " {OBJECTIVE_NAME}: {}",
+            render_terms(&model.objective_terms())
+        ),
+    );
+
+    output.push_str("Subject To\n");
+
+    for constraint in &model.constraints {
+        push_line(
+            &mut output,
+            &format!(
+                " {}: {} {} {}",
+                constraint.name,
+                render_terms(&model.terms(&constraint.expr)),
+                constraint.relation,
+                render_number(constraint.effective_rhs())
+            ),
+        );
+    }
+
+    output.push_str("Bounds\n");
+
+    for variable in &model.variables {
+        if let Some(bound) = render_bound(variable) {
+            push_line(&mut output, &format!(" {bound}"));
+        }
+    }
+
+    render_names(
+        &mut output,
+        "General",
+        model
+            .variables
+            .iter()
+            .filter(|variable| variable.integer && !variable.is_binary()),
+    );
+    render_names(
+        &mut output,
+        "Binary",
+        model
+            .variables
+            .iter()
+            .filter(|variable| variable.is_binary()),
+    );
+
+    output.push_str("End\n");
+
+    output
+}
+
+/// Render terms as `3 x + 2 y - z`, wrapping long expressions.
+fn render_terms(terms: &[(String, f64)]) -> String {
+    if terms.is_empty() {
+        return String::from("0");
+    }
+
+    let mut output = String::new();
+
+    for (idx, (name, coeff)) in terms.iter().enumerate() {
+        if idx > 0 {
+            output.push_str(if idx % TERMS_PER_LINE == 0 {
+                "\n   "
+            } else {
+                " "
+            });
+            output.push_str(if *coeff < 0.0 { "- " } else { "+ " });
+        } else if *coeff < 0.0 {
+            output.push_str("- ");
+        }
+
+        let abs = coeff.abs();
+
+        #[expect(
+            clippy::float_cmp,
+            reason = "Only an exact unit coefficient can be left implicit"
+        )]
+        let is_unit = abs == 1.0;
+
+        if !is_unit {
+            output.push_str(&render_number(abs));
+            output.push(' ');
+        }
+
+        output.push_str(name);
+    }
+
+    output
+}
+
+/// Bound line for a variable, if it differs from the default `0 <= x <= inf`.
+///
+/// Binary variables are declared in their own section instead.
+fn render_bound(variable: &LpVariable) -> Option<String> {
+    let LpVariable { name, min, max, .. } = variable;
+
+    if variable.is_binary() || (*min == 0.0 && *max == f64::INFINITY) {
+        return None;
+    }
+
+    Some(match (min.is_finite(), max.is_finite()) {
+        (false, false) => format!("{name} free"),
+        (false, true) => format!("-inf <= {name} <= {}", render_number(*max)),
+        (true, false) => format!("{name} >= {}", render_number(*min)),
+        (true, true) => format!(
+            "{} <= {name} <= {}",
+            render_number(*min),
+            render_number(*max)
+        ),
+    })
+}
+
+/// Write a section listing variable names, one per line, if there are any.
+fn render_names<'a>(
+    output: &mut String,
+    section: &str,
+    mut variables: impl Iterator<Item = &'a LpVariable>,
+) {
+    let Some(first) = variables.next() else {
+        return;
+    };
+
+    output.push_str(section);
+    output.push('\n');
+
+    for variable in std::iter::once(first).chain(variables) {
+        push_line(output, &format!(" {}", variable.name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_omit_unit_coefficients_and_wrap() {
+        let terms: Vec<(String, f64)> = (0..5)
+            .map(|idx| (format!("x_{idx}"), if idx == 1 { -1.0 } else { 2.5 }))
+            .collect();
+
+        assert_eq!(
+            render_terms(&terms),
+            "2.5 x_0 - x_1 + 2.5 x_2 + 2.5 x_3\n   + 2.5 x_4"
+        );
+        assert_eq!(render_terms(&[]), "0");
+    }
+}
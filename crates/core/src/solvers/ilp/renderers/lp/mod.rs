@@ -0,0 +1,886 @@
+//! ILP LP and MPS Renderers
+//!
+//! This module provides a renderer that captures ILP formulations and writes
+//! them as standard CPLEX-LP or free-format MPS files, so slow or surprising
+//! solves can be reproduced in external MILP tools.
+//!
+//! Variables and constraints are named after the products and promotions they
+//! belong to. When a promotion graph is evaluated, each layer with promotions
+//! is written to its own file next to the output path.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use lattice::solvers::ilp::renderers::lp::{LpFormat, LpRenderer};
+//! use std::path::PathBuf;
+//! # use lattice::{fixtures::Fixture, items::groups::ItemGroup};
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let fixture = Fixture::from_set("layered")?;
+//! # let basket = fixture.basket(Some(10))?;
+//! # let item_group = ItemGroup::from(&basket);
+//!
+//! let mut renderer = LpRenderer::new_with_metadata(
+//!     PathBuf::from("layered.lp"),
+//!     LpFormat::CplexLp,
+//!     &item_group,
+//!     fixture.product_meta_map(),
+//!     fixture.promotion_meta_map(),
+//! );
+//!
+//! let _result = fixture
+//!     .graph()?
+//!     .evaluate_with_observer(&item_group, Some(&mut renderer))?;
+//!
+//! // Writes `layered-layer_1.lp`, `layered-layer_2.lp`, ...
+//! let _paths = renderer.write()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use good_lp::{Expression, IntoAffineExpression, Variable};
+use petgraph::graph::NodeIndex;
+use rustc_hash::{FxHashMap, FxHashSet};
+use slotmap::SlotMap;
+
+use crate::{
+    graph::PromotionLayerKey,
+    items::groups::ItemGroup,
+    products::{Product, ProductKey},
+    promotions::{PromotionKey, PromotionMeta},
+    solvers::ilp::{ILPObserver, renderers::var_index},
+};
+
+mod cplex;
+mod mps;
+
+/// Longest name segment taken from a product, promotion or layer name.
+const MAX_SEGMENT_LEN: usize = 32;
+
+/// Name of the objective row.
+const OBJECTIVE_NAME: &str = "obj";
+
+/// File format written by [`LpRenderer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LpFormat {
+    /// CPLEX LP format (`.lp`).
+    CplexLp,
+
+    /// Free-format MPS (`.mps`).
+    Mps,
+}
+
+impl LpFormat {
+    /// File extension for the format.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            LpFormat::CplexLp => "lp",
+            LpFormat::Mps => "mps",
+        }
+    }
+}
+
+/// Errors that can occur during LP and MPS rendering.
+#[derive(Debug, thiserror::Error)]
+pub enum LpRenderError {
+    /// Failed to write to an output file.
+    #[error("Failed to write to output file: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A captured decision variable.
+#[derive(Debug, Clone)]
+pub struct LpVariable {
+    /// ILP decision variable.
+    pub var: Variable,
+
+    /// Name written to the file.
+    pub name: String,
+
+    /// Lower bound, which may be negative infinity.
+    pub min: f64,
+
+    /// Upper bound, which may be infinity.
+    pub max: f64,
+
+    /// Whether the variable only takes integer values.
+    pub integer: bool,
+}
+
+impl LpVariable {
+    /// Whether the variable is an integer restricted to 0 or 1.
+    #[must_use]
+    #[expect(clippy::float_cmp, reason = "Bounds are set exactly by the solver")]
+    pub fn is_binary(&self) -> bool {
+        self.integer && self.min == 0.0 && self.max == 1.0
+    }
+}
+
+/// A captured constraint.
+#[derive(Debug, Clone)]
+pub struct LpConstraint {
+    /// Name written to the file.
+    pub name: String,
+
+    /// Left-hand side expression.
+    pub expr: Expression,
+
+    /// Relation operator ("=", "<=", ">=").
+    pub relation: String,
+
+    /// Right-hand side value.
+    pub rhs: f64,
+}
+
+impl LpConstraint {
+    /// Right-hand side once any constant in the expression is moved across.
+    fn effective_rhs(&self) -> f64 {
+        self.rhs - self.expr.constant()
+    }
+}
+
+/// Captured formulation of a single solve.
+#[derive(Debug, Clone, Default)]
+pub struct LpModel {
+    /// Model name, such as `layer_1`.
+    pub name: String,
+
+    /// Variables, in the order the solver created them.
+    pub variables: Vec<LpVariable>,
+
+    /// Objective terms to minimise: `var` -> coefficient (minor units)
+    pub objective: FxHashMap<Variable, f64>,
+
+    /// Constraints, in the order they were added.
+    pub constraints: Vec<LpConstraint>,
+
+    /// Names assigned by the variable callbacks.
+    names: FxHashMap<Variable, String>,
+
+    /// Presence variable for each item, used for exclusivity right-hand sides.
+    presence_vars: FxHashMap<usize, Variable>,
+
+    /// Names already taken by variables or constraints.
+    used_names: FxHashSet<String>,
+
+    /// Whether any promotion added variables.
+    has_promotions: bool,
+}
+
+impl LpModel {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    /// Name of a variable, falling back to its index for unnamed variables.
+    fn var_name(&self, var: Variable) -> String {
+        self.names
+            .get(&var)
+            .cloned()
+            .unwrap_or_else(|| fallback_name(var))
+    }
+
+    /// Reserve `name`, adding a numeric suffix if it is already taken.
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut suffix = 1_usize;
+
+        while !self.used_names.insert(candidate.clone()) {
+            suffix = suffix.saturating_add(1);
+            candidate = format!("{name}_{suffix}");
+        }
+
+        candidate
+    }
+
+    fn name_variable(&mut self, var: Variable, name: &str) {
+        if !self.names.contains_key(&var) {
+            let name = self.unique_name(name);
+
+            self.names.insert(var, name);
+        }
+    }
+
+    /// Non-zero terms of `expr`, in variable order.
+    fn terms(&self, expr: &Expression) -> Vec<(String, f64)> {
+        self.named_terms(expr.linear_coefficients())
+    }
+
+    /// Non-zero objective terms, in variable order.
+    fn objective_terms(&self) -> Vec<(String, f64)> {
+        self.named_terms(self.objective.iter().map(|(&var, &coeff)| (var, coeff)))
+    }
+
+    fn named_terms(&self, terms: impl Iterator<Item = (Variable, f64)>) -> Vec<(String, f64)> {
+        let mut terms: Vec<(Variable, f64)> = terms.filter(|&(_, coeff)| coeff != 0.0).collect();
+
+        terms.sort_by_key(|&(var, _)| var_index(var));
+
+        terms
+            .into_iter()
+            .map(|(var, coeff)| (self.var_name(var), coeff))
+            .collect()
+    }
+
+    /// Render the model in `format`.
+    #[must_use]
+    pub fn render(&self, format: LpFormat) -> String {
+        match format {
+            LpFormat::CplexLp => cplex::render(self),
+            LpFormat::Mps => mps::render(self),
+        }
+    }
+}
+
+/// Renderer that implements `ILPObserver` and writes LP or MPS files.
+///
+/// A formulation observed outside of any graph layer is written to the output
+/// path itself. Formulations observed per layer are written next to it, named
+/// after the layer, and layers without promotions are left out.
+#[derive(Debug, Clone)]
+pub struct LpRenderer {
+    /// Output format
+    format: LpFormat,
+
+    /// Output path for a single formulation, and base name for layers
+    output_path: PathBuf,
+
+    /// Completed layers with promotions
+    layers: Vec<LpModel>,
+
+    /// Number of layers begun, including those without promotions
+    layers_seen: usize,
+
+    /// Formulation being captured
+    current: LpModel,
+
+    /// Product key -> product name
+    product_names: FxHashMap<ProductKey, String>,
+
+    /// Item index -> product name, for the item group being solved
+    item_names: Vec<Option<String>>,
+
+    /// Promotion key -> promotion name
+    promotion_names: FxHashMap<PromotionKey, String>,
+
+    /// Layer key -> layer name
+    layer_names: FxHashMap<PromotionLayerKey, String>,
+}
+
+impl LpRenderer {
+    /// Create a new renderer.
+    #[must_use]
+    pub fn new(output_path: PathBuf, format: LpFormat) -> Self {
+        Self {
+            format,
+            output_path,
+            layers: Vec::new(),
+            layers_seen: 0,
+            current: LpModel::new(String::from("formulation")),
+            product_names: FxHashMap::default(),
+            item_names: Vec::new(),
+            promotion_names: FxHashMap::default(),
+            layer_names: FxHashMap::default(),
+        }
+    }
+
+    /// Create a renderer and attach product/promotion metadata for naming.
+    ///
+    /// Items are named from `item_group` until a solve reports the item group it
+    /// builds over, which may hold lines of identical units instead.
+    #[must_use]
+    pub fn new_with_metadata<'a>(
+        output_path: PathBuf,
+        format: LpFormat,
+        item_group: &ItemGroup<'a>,
+        product_meta: &SlotMap<ProductKey, Product<'a>>,
+        promotion_meta: &SlotMap<PromotionKey, PromotionMeta>,
+    ) -> Self {
+        let product_names = product_meta
+            .iter()
+            .map(|(key, product)| (key, product.name.clone()))
+            .collect();
+
+        let promotion_names = promotion_meta
+            .iter()
+            .map(|(key, meta)| (key, meta.name.clone()))
+            .collect();
+
+        let mut layer_names: FxHashMap<PromotionLayerKey, String> = FxHashMap::default();
+
+        for (_promotion_key, meta) in promotion_meta {
+            for (layer_key, layer_name) in &meta.layer_names {
+                layer_names
+                    .entry(layer_key)
+                    .or_insert_with(|| layer_name.clone());
+            }
+        }
+
+        let mut renderer = Self {
+            product_names,
+            promotion_names,
+            layer_names,
+            ..Self::new(output_path, format)
+        };
+
+        renderer.on_item_group(item_group);
+
+        renderer
+    }
+
+    /// Get the output path.
+    #[must_use]
+    pub fn output_path(&self) -> &Path {
+        &self.output_path
+    }
+
+    /// Get the output format.
+    #[must_use]
+    pub fn format(&self) -> LpFormat {
+        self.format
+    }
+
+    /// Captured formulations: one per layer with promotions, or the single
+    /// formulation observed outside of any layer.
+    #[must_use]
+    pub fn models(&self) -> Vec<&LpModel> {
+        if self.layers_seen == 0 {
+            vec![&self.current]
+        } else {
+            self.layers.iter().collect()
+        }
+    }
+
+    /// Render every captured formulation, with the path it is written to.
+    #[must_use]
+    pub fn render(&self) -> Vec<(PathBuf, String)> {
+        self.models()
+            .into_iter()
+            .map(|model| (self.model_path(model), model.render(self.format)))
+            .collect()
+    }
+
+    /// Write every captured formulation, returning the paths written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LpRenderError::IoError`] if a file cannot be created or written.
+    pub fn write(&self) -> Result<Vec<PathBuf>, LpRenderError> {
+        let mut paths = Vec::new();
+
+        for (path, content) in self.render() {
+            fs::write(&path, content)?;
+
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Path a captured formulation is written to.
+    fn model_path(&self, model: &LpModel) -> PathBuf {
+        if self.layers_seen == 0 {
+            return self.output_path.clone();
+        }
+
+        let stem = self.output_path.file_stem().map_or_else(
+            || String::from("formulation"),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+
+        self.output_path.with_file_name(format!(
+            "{stem}-{}.{}",
+            model.name,
+            self.format.extension()
+        ))
+    }
+
+    fn item_segment(&self, item_idx: usize) -> String {
+        let display_idx = item_idx.saturating_add(1);
+
+        match self.item_names.get(item_idx).and_then(|name| name.as_ref()) {
+            Some(name) => format!("{display_idx}_{}", sanitize(name)),
+            None => display_idx.to_string(),
+        }
+    }
+
+    fn promotion_segment(&self, promotion_key: PromotionKey) -> String {
+        self.promotion_names.get(&promotion_key).map_or_else(
+            || sanitize(&format!("{promotion_key:?}")),
+            |name| sanitize(name),
+        )
+    }
+}
+
+impl ILPObserver for LpRenderer {
+    fn on_item_group(&mut self, item_group: &ItemGroup<'_>) {
+        self.item_names = item_group
+            .iter()
+            .map(|item| self.product_names.get(&item.product()).cloned())
+            .collect();
+    }
+
+    fn on_presence_variable(&mut self, item_idx: usize, var: Variable, _price_minor: i64) {
+        let name = format!("x_{}", self.item_segment(item_idx));
+
+        self.current.name_variable(var, &name);
+        self.current.presence_vars.insert(item_idx, var);
+    }
+
+    fn on_promotion_variable(
+        &mut self,
+        promotion_key: PromotionKey,
+        item_idx: usize,
+        var: Variable,
+        _discounted_price_minor: i64,
+        metadata: Option<&str>,
+    ) {
+        let mut name = format!(
+            "y_{}_{}",
+            self.promotion_segment(promotion_key),
+            self.item_segment(item_idx)
+        );
+
+        if let Some(metadata) = metadata {
+            name.push('_');
+            name.push_str(&sanitize(metadata));
+        }
+
+        self.current.name_variable(var, &name);
+        self.current.has_promotions = true;
+    }
+
+    fn on_auxiliary_variable(
+        &mut self,
+        promotion_key: PromotionKey,
+        var: Variable,
+        role: &str,
+        position: Option<usize>,
+        state: Option<usize>,
+    ) {
+        let mut name = format!(
+            "a_{}_{}",
+            self.promotion_segment(promotion_key),
+            sanitize(role)
+        );
+
+        if let Some(position) = position {
+            name.push_str("_p");
+            name.push_str(&position.to_string());
+        }
+
+        if let Some(state) = state {
+            name.push_str("_s");
+            name.push_str(&state.to_string());
+        }
+
+        self.current.name_variable(var, &name);
+    }
+
+    fn on_variable_bounds(&mut self, var: Variable, min: f64, max: f64, integer: bool) {
+        if !self.current.names.contains_key(&var) {
+            self.current.name_variable(var, &fallback_name(var));
+        }
+
+        let name = self.current.var_name(var);
+
+        self.current.variables.push(LpVariable {
+            var,
+            name,
+            min,
+            max,
+            integer,
+        });
+    }
+
+    fn on_objective_term(&mut self, var: Variable, coefficient: f64) {
+        let entry = self.current.objective.entry(var).or_insert(0.0);
+
+        *entry += coefficient;
+    }
+
+    fn on_exclusivity_constraint(&mut self, item_idx: usize, constraint_expr: &Expression) {
+        // A line of identical units is bought as many times as its quantity,
+        // which is the upper bound of its presence variable.
+        let rhs = self
+            .current
+            .presence_vars
+            .get(&item_idx)
+            .and_then(|presence| {
+                self.current
+                    .variables
+                    .iter()
+                    .find(|variable| variable.var == *presence)
+            })
+            .map_or(1.0, |variable| variable.max);
+
+        let name = self
+            .current
+            .unique_name(&format!("item_{}", self.item_segment(item_idx)));
+
+        self.current.constraints.push(LpConstraint {
+            name,
+            expr: constraint_expr.clone(),
+            relation: String::from("="),
+            rhs,
+        });
+    }
+
+    fn on_promotion_constraint(
+        &mut self,
+        promotion_key: PromotionKey,
+        constraint_type: &str,
+        constraint_expr: &Expression,
+        relation: &str,
+        rhs: f64,
+    ) {
+        let name = self.current.unique_name(&format!(
+            "c_{}_{}",
+            self.promotion_segment(promotion_key),
+            sanitize(constraint_type)
+        ));
+
+        self.current.constraints.push(LpConstraint {
+            name,
+            expr: constraint_expr.clone(),
+            relation: relation.to_string(),
+            rhs,
+        });
+    }
+
+    fn on_layer_begin(&mut self, layer_key: PromotionLayerKey, _node_idx: NodeIndex) {
+        self.layers_seen = self.layers_seen.saturating_add(1);
+
+        let mut name = format!("layer_{}", self.layers_seen);
+
+        if let Some(layer_name) = self.layer_names.get(&layer_key) {
+            name.push('_');
+            name.push_str(&sanitize(layer_name));
+        }
+
+        self.current = LpModel::new(name);
+    }
+
+    fn on_layer_end(&mut self) {
+        let model = std::mem::take(&mut self.current);
+
+        if model.has_promotions {
+            self.layers.push(model);
+        }
+    }
+}
+
+/// Replace characters that LP and MPS names do not allow, and shorten long names.
+fn sanitize(name: &str) -> String {
+    let mut sanitized = String::new();
+
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            sanitized.push(ch);
+        } else if !sanitized.is_empty() && !sanitized.ends_with('_') {
+            sanitized.push('_');
+        }
+    }
+
+    let trimmed: String = sanitized
+        .trim_end_matches('_')
+        .chars()
+        .take(MAX_SEGMENT_LEN)
+        .collect();
+
+    if trimmed.is_empty() {
+        String::from("unnamed")
+    } else {
+        trimmed
+    }
+}
+
+/// Name for a variable no callback named.
+fn fallback_name(var: Variable) -> String {
+    var_index(var).map_or_else(|| String::from("v"), |idx| format!("v_{idx}"))
+}
+
+/// Append `line` and a newline to `output`.
+fn push_line(output: &mut String, line: &str) {
+    output.push_str(line);
+    output.push('\n');
+}
+
+/// Format a coefficient or bound, without a fractional part for integers.
+fn render_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.0}")
+    } else {
+        format!("{value}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use decimal_percentage::Percentage;
+    use good_lp::{ProblemVariables, variable};
+    use rusty_money::{Money, iso::GBP};
+    use smallvec::smallvec;
+    use tempfile::tempdir;
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        graph::PromotionGraph,
+        items::Item,
+        promotions::{
+            budget::PromotionBudget, promotion, qualification::Qualification,
+            types::DirectDiscountPromotion,
+        },
+        solvers::ilp::ILPSolver,
+        tags::string::StringTagCollection,
+    };
+
+    use super::*;
+
+    struct Scenario<'a> {
+        item_group: ItemGroup<'a>,
+        products: SlotMap<ProductKey, Product<'a>>,
+        promotion_meta: SlotMap<PromotionKey, PromotionMeta>,
+        promotions: Vec<crate::promotions::Promotion<'a>>,
+    }
+
+    /// Two shampoos, one a line of three, and "20% off" shampoo.
+    fn scenario<'a>() -> Scenario<'a> {
+        let mut products = SlotMap::<ProductKey, Product<'a>>::with_key();
+        let mut promotion_meta = SlotMap::<PromotionKey, PromotionMeta>::with_key();
+
+        let shampoo = products.insert(Product {
+            name: "Shampoo 250ml".to_string(),
+            tags: StringTagCollection::from_strs(&["shampoo"]),
+            price: Money::from_minor(300, GBP),
+        });
+        let key = promotion_meta.insert(PromotionMeta {
+            name: "20% off shampoo".to_string(),
+            ..PromotionMeta::default()
+        });
+
+        let item = Item::with_tags(
+            shampoo,
+            Money::from_minor(300, GBP),
+            StringTagCollection::from_strs(&["shampoo"]),
+        );
+
+        Scenario {
+            item_group: ItemGroup::new(smallvec![item.clone(), item], GBP),
+            products,
+            promotion_meta,
+            promotions: vec![promotion(DirectDiscountPromotion::new(
+                key,
+                Qualification::match_any(StringTagCollection::from_strs(&["shampoo"])),
+                SimpleDiscount::PercentageOff(Percentage::from(0.2)),
+                PromotionBudget::unlimited(),
+            ))],
+        }
+    }
+
+    fn observed(format: LpFormat) -> Result<LpRenderer, crate::solvers::SolverError> {
+        let scenario = scenario();
+        let mut renderer = LpRenderer::new_with_metadata(
+            PathBuf::from("formulation.lp"),
+            format,
+            &scenario.item_group,
+            &scenario.products,
+            &scenario.promotion_meta,
+        );
+
+        ILPSolver::solve_with_observer(&scenario.promotions, &scenario.item_group, &mut renderer)?;
+
+        Ok(renderer)
+    }
+
+    #[test]
+    fn sanitize_replaces_disallowed_characters() {
+        assert_eq!(sanitize("20% off shampoo!"), "20_off_shampoo");
+        assert_eq!(sanitize("  "), "unnamed");
+        assert_eq!(sanitize(&"a".repeat(40)).len(), MAX_SEGMENT_LEN);
+    }
+
+    #[test]
+    fn render_number_drops_integral_fractions() {
+        assert_eq!(render_number(240.0), "240");
+        assert_eq!(render_number(-1.0), "-1");
+        assert_eq!(render_number(0.5), "0.5");
+    }
+
+    #[test]
+    fn names_come_from_products_and_promotions() -> TestResult {
+        let renderer = observed(LpFormat::CplexLp)?;
+        let models = renderer.models();
+        let model = models.first().ok_or("missing model")?;
+
+        let names: Vec<&str> = model
+            .variables
+            .iter()
+            .map(|variable| variable.name.as_str())
+            .collect();
+
+        assert!(
+            names
+                .iter()
+                .any(|name| name.starts_with("x_1_Shampoo_250ml"))
+        );
+        assert!(
+            names
+                .iter()
+                .any(|name| name.starts_with("y_20_off_shampoo_"))
+        );
+        assert!(
+            model
+                .constraints
+                .iter()
+                .any(|constraint| constraint.name.starts_with("item_1_Shampoo_250ml"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn names_follow_lines_of_identical_units() -> TestResult {
+        let mut scenario = scenario();
+
+        let conditioner = scenario.products.insert(Product {
+            name: "Conditioner".to_string(),
+            tags: StringTagCollection::from_strs(&["shampoo"]),
+            price: Money::from_minor(400, GBP),
+        });
+
+        let shampoo = scenario.item_group.get_item(0).map(Item::product)?;
+
+        // Three shampoos then a conditioner, solved as two lines.
+        let item_group = ItemGroup::new(
+            smallvec![
+                Item::with_quantity(
+                    shampoo,
+                    Money::from_minor(300, GBP),
+                    StringTagCollection::from_strs(&["shampoo"]),
+                    3,
+                ),
+                Item::with_tags(
+                    conditioner,
+                    Money::from_minor(400, GBP),
+                    StringTagCollection::from_strs(&["shampoo"]),
+                ),
+            ],
+            GBP,
+        );
+
+        let mut renderer = LpRenderer::new_with_metadata(
+            PathBuf::from("formulation.lp"),
+            LpFormat::CplexLp,
+            &item_group,
+            &scenario.products,
+            &scenario.promotion_meta,
+        );
+
+        ILPSolver::solve_with_observer(&scenario.promotions, &item_group, &mut renderer)?;
+
+        let models = renderer.models();
+        let model = models.first().ok_or("missing model")?;
+
+        let presence: Vec<&str> = model
+            .variables
+            .iter()
+            .map(|variable| variable.name.as_str())
+            .filter(|name| name.starts_with("x_"))
+            .collect();
+
+        assert_eq!(presence, vec!["x_1_Shampoo_250ml", "x_2_Conditioner"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cplex_lp_lists_sections_in_order() -> TestResult {
+        let renderer = observed(LpFormat::CplexLp)?;
+        let outputs = renderer.render();
+        let (path, content) = outputs.first().ok_or("missing output")?;
+
+        assert_eq!(path, &PathBuf::from("formulation.lp"));
+
+        let sections: Vec<usize> = ["Minimize", "Subject To", "End"]
+            .iter()
+            .map(|section| content.find(section).ok_or("missing section"))
+            .collect::<Result<_, _>>()?;
+
+        assert!(sections.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(content.contains(" obj: "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mps_lists_sections_in_order() -> TestResult {
+        let renderer = observed(LpFormat::Mps)?;
+        let outputs = renderer.render();
+        let (_, content) = outputs.first().ok_or("missing output")?;
+
+        let sections: Vec<usize> = ["NAME", "ROWS", "COLUMNS", "RHS", "BOUNDS", "ENDATA"]
+            .iter()
+            .map(|section| content.find(section).ok_or("missing section"))
+            .collect::<Result<_, _>>()?;
+
+        assert!(sections.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(content.contains(" N  obj"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn exclusivity_rhs_follows_presence_bounds() {
+        let mut renderer = LpRenderer::new(PathBuf::from("model.lp"), LpFormat::CplexLp);
+        let mut pb = ProblemVariables::new();
+        let line = pb.add(variable().integer().min(0).max(3));
+
+        renderer.on_presence_variable(0, line, 300);
+        renderer.on_variable_bounds(line, 0.0, 3.0, true);
+        renderer.on_exclusivity_constraint(0, &Expression::from(line));
+
+        let models = renderer.models();
+
+        assert_eq!(
+            models
+                .first()
+                .and_then(|model| model.constraints.first())
+                .map(|constraint| constraint.rhs),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn layers_are_written_to_separate_files() -> TestResult {
+        let scenario = scenario();
+        let dir = tempdir()?;
+        let graph = PromotionGraph::single_layer(scenario.promotions.iter().cloned())?;
+
+        let mut renderer = LpRenderer::new_with_metadata(
+            dir.path().join("basket.mps"),
+            LpFormat::Mps,
+            &scenario.item_group,
+            &scenario.products,
+            &scenario.promotion_meta,
+        );
+
+        graph.evaluate_with_observer(&scenario.item_group, Some(&mut renderer))?;
+
+        let paths = renderer.write()?;
+
+        assert_eq!(paths, vec![dir.path().join("basket-layer_1.mps")]);
+        assert!(fs::read_to_string(&paths[0])?.ends_with("ENDATA\n"));
+
+        Ok(())
+    }
+}
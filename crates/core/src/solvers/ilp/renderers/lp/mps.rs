@@ -0,0 +1,191 @@
+//! Free-format MPS
+
+use rustc_hash::FxHashMap;
+
+use super::{LpModel, LpVariable, OBJECTIVE_NAME, push_line, render_number};
+
+/// Render `model` in free-format MPS.
+pub(super) fn render(model: &LpModel) -> String {
+    let mut output = String::new();
+
+    push_line(&mut output, &format!("NAME {}", model.name));
+
+    output.push_str("ROWS\n");
+
+    push_line(&mut output, &format!(" N  {OBJECTIVE_NAME}"));
+
+    for constraint in &model.constraints {
+        let row_type = match constraint.relation.as_str() {
+            "<=" => "L",
+            ">=" => "G",
+            _ => "E",
+        };
+
+        push_line(&mut output, &format!(" {row_type}  {}", constraint.name));
+    }
+
+    // MPS lists coefficients by column, so gather each variable's entries.
+    let mut entries: FxHashMap<&str, Vec<(&str, f64)>> = FxHashMap::default();
+    let objective_terms = model.objective_terms();
+    let constraint_terms: Vec<(&str, Vec<(String, f64)>)> = model
+        .constraints
+        .iter()
+        .map(|constraint| (constraint.name.as_str(), model.terms(&constraint.expr)))
+        .collect();
+
+    for (name, coeff) in &objective_terms {
+        entries
+            .entry(name.as_str())
+            .or_default()
+            .push((OBJECTIVE_NAME, *coeff));
+    }
+
+    for (row, terms) in &constraint_terms {
+        for (name, coeff) in terms {
+            entries
+                .entry(name.as_str())
+                .or_default()
+                .push((row, *coeff));
+        }
+    }
+
+    output.push_str("COLUMNS\n");
+
+    let (integers, continuous): (Vec<&LpVariable>, Vec<&LpVariable>) = model
+        .variables
+        .iter()
+        .partition(|variable| variable.integer);
+
+    if !integers.is_empty() {
+        output.push_str("    MARKER  'MARKER'  'INTORG'\n");
+        render_columns(&mut output, &integers, &entries);
+        output.push_str("    MARKER  'MARKER'  'INTEND'\n");
+    }
+
+    render_columns(&mut output, &continuous, &entries);
+
+    output.push_str("RHS\n");
+
+    for constraint in &model.constraints {
+        let rhs = constraint.effective_rhs();
+
+        if rhs != 0.0 {
+            push_line(
+                &mut output,
+                &format!("    RHS  {}  {}", constraint.name, render_number(rhs)),
+            );
+        }
+    }
+
+    output.push_str("BOUNDS\n");
+
+    for variable in &model.variables {
+        render_bounds(&mut output, variable);
+    }
+
+    output.push_str("ENDATA\n");
+
+    output
+}
+
+/// Write each variable's column entries.
+///
+/// A variable in no row is still listed, with a zero objective coefficient, so
+/// that it is declared.
+fn render_columns(
+    output: &mut String,
+    variables: &[&LpVariable],
+    entries: &FxHashMap<&str, Vec<(&str, f64)>>,
+) {
+    for variable in variables {
+        match entries.get(variable.name.as_str()) {
+            Some(column) if !column.is_empty() => {
+                for (row, coeff) in column {
+                    push_line(
+                        output,
+                        &format!("    {}  {row}  {}", variable.name, render_number(*coeff)),
+                    );
+                }
+            }
+            _ => {
+                push_line(
+                    output,
+                    &format!("    {}  {OBJECTIVE_NAME}  0", variable.name),
+                );
+            }
+        }
+    }
+}
+
+/// Write a variable's bounds.
+///
+/// Integer bounds are always written, as some readers treat integer columns
+/// without bounds as binary.
+fn render_bounds(output: &mut String, variable: &LpVariable) {
+    let LpVariable {
+        name,
+        min,
+        max,
+        integer,
+        ..
+    } = variable;
+
+    if variable.is_binary() {
+        push_line(output, &format!(" BV BND  {name}"));
+        return;
+    }
+
+    if !min.is_finite() && !max.is_finite() {
+        push_line(output, &format!(" FR BND  {name}"));
+        return;
+    }
+
+    if !min.is_finite() {
+        push_line(output, &format!(" MI BND  {name}"));
+    } else if *min != 0.0 || *integer {
+        push_line(output, &format!(" LO BND  {name}  {}", render_number(*min)));
+    }
+
+    if max.is_finite() {
+        push_line(output, &format!(" UP BND  {name}  {}", render_number(*max)));
+    } else if *integer {
+        push_line(output, &format!(" PL BND  {name}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use good_lp::{ProblemVariables, variable};
+
+    use super::*;
+
+    fn lp_variable(name: &str, min: f64, max: f64, integer: bool) -> LpVariable {
+        let mut pb = ProblemVariables::new();
+
+        LpVariable {
+            var: pb.add(variable()),
+            name: name.to_string(),
+            min,
+            max,
+            integer,
+        }
+    }
+
+    #[test]
+    fn bounds_cover_binary_integer_and_free_variables() {
+        let mut output = String::new();
+
+        render_bounds(&mut output, &lp_variable("b", 0.0, 1.0, true));
+        render_bounds(&mut output, &lp_variable("n", 0.0, 3.0, true));
+        render_bounds(
+            &mut output,
+            &lp_variable("f", f64::NEG_INFINITY, f64::INFINITY, false),
+        );
+        render_bounds(&mut output, &lp_variable("c", 0.0, f64::INFINITY, false));
+
+        assert_eq!(
+            output,
+            " BV BND  b\n LO BND  n  0\n UP BND  n  3\n FR BND  f\n"
+        );
+    }
+}
@@ -1,3 +1,19 @@
 //! ILP Renderers
 
+use good_lp::Variable;
+
+pub mod json;
+pub mod lp;
 pub mod typst;
+
+/// Index of a variable within its problem, read from its debug output.
+fn var_index(var: Variable) -> Option<usize> {
+    let debug = format!("{var:?}");
+    let digits: String = debug
+        .chars()
+        .skip_while(|ch| !ch.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect();
+
+    digits.parse().ok()
+}
@@ -135,6 +135,11 @@ impl ILPState {
         (self.pb, self.cost, self.item_presence, self.constraints)
     }
 
+    /// Return the objective expression built so far.
+    pub(crate) fn cost(&self) -> &Expression {
+        &self.cost
+    }
+
     /// Add a term to the objective function (cost expression)
     ///
     /// Tells the solver "if you choose this option (set this variable to 1), add this
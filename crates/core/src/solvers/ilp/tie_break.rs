@@ -0,0 +1,199 @@
+//! Tie-break Policies
+//!
+//! Several redemption assignments often give the same basket total. Which one
+//! the solver lands on then depends on the order variables and constraints were
+//! added in, so receipts could differ after a harmless reorder of promotions.
+//!
+//! A [`TieBreak`] policy decides between equally cheap assignments explicitly.
+//! It is encoded as weighted participation terms in the secondary objective,
+//! which is only minimised once the basket total is fixed at its optimum, so the
+//! policy never changes the total. Promotions are also put in key order before
+//! the model is built, so the same basket and promotions always give the same
+//! redemptions, whatever order the promotions are listed in.
+
+use good_lp::{Expression, IntoAffineExpression};
+use smallvec::SmallVec;
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::PromotionKey,
+    solvers::{
+        SolverError,
+        ilp::{i64_to_f64_exact, promotions::PromotionInstances},
+    },
+};
+
+/// How to choose between redemption assignments with the same basket total.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TieBreak {
+    /// Only apply the preferences promotions define themselves, such as tiered
+    /// promotions preferring their most expensive items. Ties beyond those are
+    /// left to the solver.
+    #[default]
+    PromotionDefined,
+
+    /// Prefer redeeming as few items through promotions as possible.
+    FewestRedemptions,
+
+    /// Prefer promotions earlier in the list. Promotions not in the list come
+    /// after every listed promotion.
+    PromotionPriority(Vec<PromotionKey>),
+
+    /// Prefer redeeming the highest-priced items.
+    HigherPricedItems,
+}
+
+impl TieBreak {
+    /// Whether promotions are ordered by key before solving.
+    pub(crate) fn is_explicit(&self) -> bool {
+        !matches!(self, TieBreak::PromotionDefined)
+    }
+
+    /// Weight of redeeming an item through a promotion; lower is preferred, and
+    /// buying the item at full price has a weight of zero.
+    fn weight(&self, promotion_key: PromotionKey, price_rank: usize) -> usize {
+        match self {
+            TieBreak::PromotionDefined => 0,
+            TieBreak::FewestRedemptions => 1,
+            TieBreak::PromotionPriority(keys) => keys
+                .iter()
+                .position(|&key| key == promotion_key)
+                .unwrap_or(keys.len())
+                .saturating_add(1),
+            TieBreak::HigherPricedItems => price_rank,
+        }
+    }
+}
+
+/// Build the secondary objective: the promotions' own tie-break terms, with the
+/// policy's terms weighted to take precedence over them.
+///
+/// # Errors
+///
+/// Returns [`SolverError`] if a promotion fails to build its terms or a weight
+/// cannot be represented exactly as a coefficient.
+pub(crate) fn secondary_objective(
+    promotion_instances: &PromotionInstances<'_>,
+    item_group: &ItemGroup<'_>,
+    tie_break: &TieBreak,
+) -> Result<Expression, SolverError> {
+    let promotion_terms =
+        promotion_instances.add_secondary_objective_terms(Expression::default(), item_group)?;
+
+    if !tie_break.is_explicit() {
+        return Ok(promotion_terms);
+    }
+
+    let scale = policy_scale(&promotion_terms, item_group)?;
+    let price_ranks = price_ranks(item_group);
+    let mut expr = promotion_terms;
+
+    for instance in promotion_instances.iter() {
+        for (item_idx, &price_rank) in price_ranks.iter().enumerate() {
+            let weight = i64::try_from(tie_break.weight(instance.key(), price_rank))
+                .map_err(|_err| SolverError::InvariantViolation {
+                    message: "tie-break weight overflow",
+                })?
+                .saturating_mul(scale);
+
+            if weight == 0 {
+                continue;
+            }
+
+            let coeff =
+                i64_to_f64_exact(weight).ok_or(SolverError::MinorUnitsNotRepresentable(weight))?;
+
+            expr += instance.add_item_presence_term(Expression::default(), item_idx) * coeff;
+        }
+    }
+
+    Ok(expr)
+}
+
+/// Multiplier for policy weights that keeps a one-step policy difference larger
+/// than any difference in the promotions' own terms.
+fn policy_scale(
+    promotion_terms: &Expression,
+    item_group: &ItemGroup<'_>,
+) -> Result<i64, SolverError> {
+    // Every term's variable counts units of one line, so it never exceeds the
+    // largest line quantity.
+    let max_quantity = item_group
+        .iter()
+        .map(|item| f64::from(item.quantity()))
+        .fold(1.0, f64::max);
+
+    let bound = IntoAffineExpression::linear_coefficients(promotion_terms)
+        .map(|(_, coeff)| coeff.abs() * max_quantity)
+        .sum::<f64>()
+        .ceil();
+
+    format!("{bound:.0}")
+        .parse::<i64>()
+        .map(|bound| bound.saturating_add(1))
+        .map_err(|_err| SolverError::InvariantViolation {
+            message: "tie-break scale out of i64 range",
+        })
+}
+
+/// Rank of each item by price, from 1 for the most expensive; equal prices are
+/// ranked in item order.
+fn price_ranks(item_group: &ItemGroup<'_>) -> SmallVec<[usize; 10]> {
+    let mut order: SmallVec<[(usize, i64); 10]> = item_group
+        .iter()
+        .enumerate()
+        .map(|(item_idx, item)| (item_idx, item.price().to_minor_units()))
+        .collect();
+
+    order.sort_by(|(a_idx, a_price), (b_idx, b_price)| b_price.cmp(a_price).then(a_idx.cmp(b_idx)));
+
+    let mut ranks: SmallVec<[usize; 10]> = SmallVec::from_elem(0, item_group.len());
+
+    for (rank, (item_idx, _)) in order.into_iter().enumerate() {
+        if let Some(slot) = ranks.get_mut(item_idx) {
+            *slot = rank.saturating_add(1);
+        }
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use rusty_money::{Money, iso::GBP};
+    use smallvec::smallvec;
+
+    use crate::{items::Item, products::ProductKey};
+
+    use super::*;
+
+    #[test]
+    fn price_ranks_order_items_by_descending_price() {
+        let item_group = ItemGroup::new(
+            smallvec![
+                Item::new(ProductKey::default(), Money::from_minor(200, GBP)),
+                Item::new(ProductKey::default(), Money::from_minor(500, GBP)),
+                Item::new(ProductKey::default(), Money::from_minor(200, GBP)),
+            ],
+            GBP,
+        );
+
+        assert_eq!(price_ranks(&item_group).as_slice(), &[2, 1, 3]);
+    }
+
+    #[test]
+    fn priority_weights_follow_the_list() {
+        let mut keys = slotmap::SlotMap::<PromotionKey, ()>::with_key();
+        let first = keys.insert(());
+        let second = keys.insert(());
+        let unlisted = keys.insert(());
+
+        let tie_break = TieBreak::PromotionPriority(vec![first, second]);
+
+        assert_eq!(tie_break.weight(first, 1), 1);
+        assert_eq!(tie_break.weight(second, 1), 2);
+        assert_eq!(tie_break.weight(unlisted, 1), 3);
+        assert_eq!(TieBreak::PromotionDefined.weight(first, 1), 0);
+    }
+}
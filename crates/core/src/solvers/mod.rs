@@ -11,7 +11,10 @@ use crate::{
     promotions::{Promotion, redemptions::PromotionRedemption},
 };
 
+pub mod brute_force;
+pub mod greedy;
 pub mod ilp;
+pub mod verifier;
 
 /// Solver Errors
 #[derive(Debug, Error)]
@@ -38,6 +41,16 @@ pub enum SolverError {
     #[error(transparent)]
     ResolutionError(#[from] ResolutionError),
 
+    /// Item group is too large for an exhaustive solver.
+    #[error("item group has {items} items, more than the limit of {limit}")]
+    TooManyItems {
+        /// Number of items in the group
+        items: usize,
+
+        /// Largest number of items the solver accepts
+        limit: usize,
+    },
+
     /// Internal solver invariant was violated (this is a bug).
     #[error("solver invariant violated: {message}")]
     InvariantViolation {
@@ -60,6 +73,11 @@ pub struct SolverResult<'a> {
 
     /// Details of each promotion redemptions (item, bundle, original/final price)
     pub promotion_redemptions: SmallVec<[PromotionRedemption<'a>; 10]>,
+
+    /// Whether the solver proved this is the lowest total.
+    ///
+    /// `false` when a time limit stopped the search before optimality was proven.
+    pub optimal: bool,
 }
 
 /// Trait for solving promotion problems on a set of items
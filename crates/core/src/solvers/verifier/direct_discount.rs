@@ -0,0 +1,57 @@
+//! Direct Discount Verification
+//!
+//! Every redeemed item qualifies and is priced by the discount on its own.
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::{redemptions::PromotionRedemption, types::DirectDiscountPromotion},
+    solvers::verifier::{
+        VerificationError, bundles, check_budget, check_final_price, simple_discounted_minor,
+    },
+};
+
+/// Verify a direct discount's redemptions in one layer.
+pub(crate) fn verify(
+    promotion: &DirectDiscountPromotion<'_>,
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+    let bundles = bundles(item_group, redemptions)?;
+
+    for (&redemption_idx, bundle) in &bundles {
+        if bundle.len() != 1 {
+            return Err(VerificationError::IncompleteBundle {
+                promotion_key,
+                redemption_idx,
+                expected: 1,
+                actual: bundle.len(),
+            });
+        }
+
+        for redeemed in bundle {
+            if !promotion.qualification().matches(redeemed.item.tags()) {
+                return Err(VerificationError::IneligibleItem {
+                    promotion_key,
+                    item_idx: redeemed.item_idx,
+                });
+            }
+
+            let expected = simple_discounted_minor(
+                promotion.discount(),
+                redeemed.original_minor,
+                redeemed.item.measure(),
+            )?;
+
+            check_final_price(promotion_key, redeemed, expected)?;
+        }
+    }
+
+    // Each redeemed item counts against the redemption limit.
+    check_budget(
+        promotion_key,
+        promotion.budget(),
+        redemptions.len(),
+        &bundles,
+    )
+}
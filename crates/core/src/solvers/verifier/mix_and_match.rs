@@ -0,0 +1,176 @@
+//! Mix-and-Match Verification
+//!
+//! Each redemption is one bundle whose items can be assigned to the promotion's
+//! slots, with every slot holding between its minimum and maximum number of
+//! items. The bundle is priced by the promotion's discount. Without a fixed
+//! bundle size, the promotion is redeemed at most once.
+
+use smallvec::SmallVec;
+
+use crate::{
+    discounts::percent_of_minor,
+    items::groups::ItemGroup,
+    promotions::{
+        redemptions::PromotionRedemption,
+        types::{MixAndMatchDiscount, MixAndMatchPromotion, MixAndMatchSlot},
+    },
+    solvers::verifier::{
+        RedeemedItem, VerificationError, bundles, check_budget, check_bundle_total,
+        check_cheapest_prices, check_final_price,
+    },
+};
+
+/// Verify a mix-and-match promotion's redemptions in one layer.
+pub(crate) fn verify(
+    promotion: &MixAndMatchPromotion<'_>,
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+    let bundles = bundles(item_group, redemptions)?;
+
+    // Without a fixed bundle size, the promotion is redeemed at most once.
+    if !promotion.has_fixed_arity() && bundles.len() > 1 {
+        return Err(VerificationError::RepeatedRedemption {
+            promotion_key,
+            count: bundles.len(),
+        });
+    }
+
+    for (&redemption_idx, bundle) in &bundles {
+        if promotion.has_fixed_arity() && bundle.len() != promotion.bundle_size() {
+            return Err(VerificationError::IncompleteBundle {
+                promotion_key,
+                redemption_idx,
+                expected: promotion.bundle_size(),
+                actual: bundle.len(),
+            });
+        }
+
+        let mut filled: SmallVec<[usize; 5]> = promotion.slots().iter().map(|_| 0).collect();
+
+        if !fills_slots(promotion.slots(), bundle, &mut filled) {
+            return Err(VerificationError::SlotsUnfilled {
+                promotion_key,
+                redemption_idx,
+            });
+        }
+
+        verify_prices(promotion, redemption_idx, bundle)?;
+    }
+
+    check_budget(promotion_key, promotion.budget(), bundles.len(), &bundles)
+}
+
+/// Whether `items` can be assigned to `slots`, given how many items each slot
+/// already holds in `filled`.
+fn fills_slots(
+    slots: &[MixAndMatchSlot],
+    items: &[RedeemedItem<'_, '_>],
+    filled: &mut [usize],
+) -> bool {
+    let Some((redeemed, rest)) = items.split_first() else {
+        return slots
+            .iter()
+            .zip(filled.iter())
+            .all(|(slot, &count)| count >= slot.min());
+    };
+
+    for (slot_idx, slot) in slots.iter().enumerate() {
+        if !slot.qualification().matches(redeemed.item.tags()) {
+            continue;
+        }
+
+        let Some(count) = filled.get_mut(slot_idx) else {
+            continue;
+        };
+
+        if slot.max().is_some_and(|max| *count >= max) {
+            continue;
+        }
+
+        *count += 1;
+
+        let fits = fills_slots(slots, rest, filled);
+
+        if let Some(count) = filled.get_mut(slot_idx) {
+            *count -= 1;
+        }
+
+        if fits {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Check a bundle's final prices against the promotion's discount.
+fn verify_prices(
+    promotion: &MixAndMatchPromotion<'_>,
+    redemption_idx: usize,
+    bundle: &[RedeemedItem<'_, '_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+
+    match promotion.discount() {
+        MixAndMatchDiscount::PercentAllItems(pct) => {
+            for redeemed in bundle {
+                let original = redeemed.original_minor;
+
+                check_final_price(
+                    promotion_key,
+                    redeemed,
+                    original.saturating_sub(percent_of_minor(pct, original)?),
+                )?;
+            }
+        }
+        MixAndMatchDiscount::AmountOffEachItem(amount) => {
+            for redeemed in bundle {
+                check_final_price(
+                    promotion_key,
+                    redeemed,
+                    redeemed
+                        .original_minor
+                        .saturating_sub(amount.to_minor_units())
+                        .max(0),
+                )?;
+            }
+        }
+        MixAndMatchDiscount::FixedPriceEachItem(amount) => {
+            for redeemed in bundle {
+                check_final_price(promotion_key, redeemed, amount.to_minor_units().max(0))?;
+            }
+        }
+        MixAndMatchDiscount::AmountOffTotal(amount) => {
+            let original: i64 = bundle.iter().map(|redeemed| redeemed.original_minor).sum();
+
+            check_bundle_total(
+                promotion_key,
+                redemption_idx,
+                bundle,
+                original.saturating_sub(amount.to_minor_units()).max(0),
+            )?;
+        }
+        MixAndMatchDiscount::FixedTotal(amount) => {
+            check_bundle_total(
+                promotion_key,
+                redemption_idx,
+                bundle,
+                amount.to_minor_units().max(0),
+            )?;
+        }
+        MixAndMatchDiscount::PercentCheapest(pct) => {
+            check_cheapest_prices(promotion_key, bundle, |original| {
+                Ok(original.saturating_sub(percent_of_minor(pct, original)?))
+            })?;
+        }
+        MixAndMatchDiscount::FixedCheapest(amount) => {
+            check_cheapest_prices(promotion_key, bundle, |_original| {
+                Ok(amount.to_minor_units().max(0))
+            })?;
+        }
+    }
+
+    Ok(())
+}
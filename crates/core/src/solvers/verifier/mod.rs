@@ -0,0 +1,739 @@
+//! Solution Verifier
+//!
+//! Checks a solver's result against the promotion rules, without trusting the
+//! solver that produced it. Each promotion type re-derives its own semantics
+//! from its definition: bundle sizes and discounted positions, mix-and-match
+//! slot fills, tier thresholds and budgets. The verifier also checks that each
+//! item is claimed at most once and that the total matches the final prices.
+//!
+//! Verification is cheap compared to solving, so it can run in debug builds
+//! (for example from a `debug_assert!`) as well as in tests.
+
+use std::collections::BTreeMap;
+
+use rustc_hash::FxHashSet;
+use smallvec::SmallVec;
+use thiserror::Error;
+
+use crate::{
+    discounts::{DiscountError, SimpleDiscount, amount_off_per_measure_minor, percent_of_minor},
+    items::{Item, groups::ItemGroup, measure::Measure},
+    promotions::{
+        Promotion, PromotionKey, budget::PromotionBudget, redemptions::PromotionRedemption,
+        types::PromotionType,
+    },
+    solvers::{SolverResult, ilp::ILPPromotion},
+};
+
+pub(crate) mod direct_discount;
+pub(crate) mod mix_and_match;
+pub(crate) mod positional_discount;
+pub(crate) mod tiered_threshold;
+
+/// A way in which a result breaks the promotion rules.
+///
+/// Prices are in minor units.
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    /// A redemption refers to an item that is not in the item group.
+    #[error("redemption refers to unknown item {item_idx}")]
+    UnknownItem {
+        /// Index of the missing item
+        item_idx: usize,
+    },
+
+    /// A redemption refers to a promotion that was not solved for.
+    #[error("redemption refers to unknown promotion {promotion_key:?}")]
+    UnknownPromotion {
+        /// Key of the unknown promotion
+        promotion_key: PromotionKey,
+    },
+
+    /// An item was claimed by more than one redemption in the same layer.
+    #[error("item {item_idx} is claimed more than once")]
+    ItemClaimedTwice {
+        /// Index of the item
+        item_idx: usize,
+    },
+
+    /// A redemption's original price is not the price the item was solved at.
+    #[error("item {item_idx} has original price {actual}, expected {expected}")]
+    OriginalPriceMismatch {
+        /// Index of the item
+        item_idx: usize,
+
+        /// Price the item was solved at
+        expected: i64,
+
+        /// Original price on the redemption
+        actual: i64,
+    },
+
+    /// A redemption's final price does not follow from the promotion's discount.
+    #[error("promotion {promotion_key:?} priced item {item_idx} at {actual}, expected {expected}")]
+    FinalPriceMismatch {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the item
+        item_idx: usize,
+
+        /// Price the discount gives
+        expected: i64,
+
+        /// Final price on the redemption
+        actual: i64,
+    },
+
+    /// The final prices of a bundle do not add up to the bundle's discounted total.
+    #[error(
+        "promotion {promotion_key:?} priced redemption {redemption_idx} at {actual}, expected {expected}"
+    )]
+    BundleTotalMismatch {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the redemption
+        redemption_idx: usize,
+
+        /// Total the discount gives
+        expected: i64,
+
+        /// Sum of the final prices
+        actual: i64,
+    },
+
+    /// An item does not qualify for the promotion that claimed it.
+    #[error("item {item_idx} does not qualify for promotion {promotion_key:?}")]
+    IneligibleItem {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the item
+        item_idx: usize,
+    },
+
+    /// A redemption has the wrong number of items.
+    #[error(
+        "promotion {promotion_key:?} redemption {redemption_idx} has {actual} items, expected {expected}"
+    )]
+    IncompleteBundle {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the redemption
+        redemption_idx: usize,
+
+        /// Number of items the promotion requires
+        expected: usize,
+
+        /// Number of items in the redemption
+        actual: usize,
+    },
+
+    /// A mix-and-match redemption's items cannot fill the promotion's slots.
+    #[error("promotion {promotion_key:?} redemption {redemption_idx} does not fill its slots")]
+    SlotsUnfilled {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the redemption
+        redemption_idx: usize,
+    },
+
+    /// A tiered promotion's items and prices do not match any of its tiers.
+    #[error("promotion {promotion_key:?} redemption {redemption_idx} matches none of its tiers")]
+    NoMatchingTier {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Index of the redemption
+        redemption_idx: usize,
+    },
+
+    /// A promotion that applies once per basket was redeemed more than once.
+    #[error("promotion {promotion_key:?} was redeemed {count} times, but applies once")]
+    RepeatedRedemption {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Number of redemptions
+        count: usize,
+    },
+
+    /// A promotion was redeemed more times than its budget allows.
+    #[error("promotion {promotion_key:?} was redeemed {actual} times, limit is {limit}")]
+    RedemptionLimitExceeded {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Redemption limit from the budget
+        limit: u32,
+
+        /// Number of redemptions
+        actual: usize,
+    },
+
+    /// A promotion gave away more than its budget allows.
+    #[error("promotion {promotion_key:?} discounted {actual}, limit is {limit}")]
+    MonetaryLimitExceeded {
+        /// Key of the promotion
+        promotion_key: PromotionKey,
+
+        /// Monetary limit from the budget
+        limit: i64,
+
+        /// Total discount given
+        actual: i64,
+    },
+
+    /// An item is missing from, or wrongly listed in, the affected or unaffected items.
+    #[error("item {item_idx} is not listed correctly as affected or unaffected")]
+    AffectedItemsMismatch {
+        /// Index of the item
+        item_idx: usize,
+    },
+
+    /// An item's redemptions across layers do not follow on from each other, or
+    /// do not match the layers it was routed through.
+    #[error("item {item_idx} has redemptions that do not follow its route through the graph")]
+    RedemptionChainBroken {
+        /// Index of the item
+        item_idx: usize,
+    },
+
+    /// The result's total is not the sum of the final prices.
+    #[error("total is {actual}, expected {expected}")]
+    TotalMismatch {
+        /// Sum of the final prices
+        expected: i64,
+
+        /// Total on the result
+        actual: i64,
+    },
+
+    /// An expected price could not be calculated.
+    #[error(transparent)]
+    Discount(#[from] DiscountError),
+}
+
+impl VerificationError {
+    /// Renumber the item this error refers to, if any, with `map`.
+    pub(crate) fn map_item_idx(self, map: impl Fn(usize) -> usize) -> Self {
+        match self {
+            Self::UnknownItem { item_idx } => Self::UnknownItem {
+                item_idx: map(item_idx),
+            },
+            Self::ItemClaimedTwice { item_idx } => Self::ItemClaimedTwice {
+                item_idx: map(item_idx),
+            },
+            Self::OriginalPriceMismatch {
+                item_idx,
+                expected,
+                actual,
+            } => Self::OriginalPriceMismatch {
+                item_idx: map(item_idx),
+                expected,
+                actual,
+            },
+            Self::FinalPriceMismatch {
+                promotion_key,
+                item_idx,
+                expected,
+                actual,
+            } => Self::FinalPriceMismatch {
+                promotion_key,
+                item_idx: map(item_idx),
+                expected,
+                actual,
+            },
+            Self::IneligibleItem {
+                promotion_key,
+                item_idx,
+            } => Self::IneligibleItem {
+                promotion_key,
+                item_idx: map(item_idx),
+            },
+            Self::AffectedItemsMismatch { item_idx } => Self::AffectedItemsMismatch {
+                item_idx: map(item_idx),
+            },
+            Self::RedemptionChainBroken { item_idx } => Self::RedemptionChainBroken {
+                item_idx: map(item_idx),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Verify a single solve's result against the promotions it was solved with.
+///
+/// Returns the first rule the result breaks.
+///
+/// # Errors
+///
+/// Returns a [`VerificationError`] describing the first violation found.
+pub fn verify_result(
+    promotions: &[Promotion<'_>],
+    item_group: &ItemGroup<'_>,
+    result: &SolverResult<'_>,
+) -> Result<(), VerificationError> {
+    let claimed = verify_redemptions(promotions, item_group, &result.promotion_redemptions)?;
+
+    let mut listed = FxHashSet::default();
+
+    for &item_idx in result.affected_items.iter().chain(&result.unaffected_items) {
+        if item_idx >= item_group.len() || !listed.insert(item_idx) {
+            return Err(VerificationError::AffectedItemsMismatch { item_idx });
+        }
+    }
+
+    let mut expected_total = 0_i64;
+
+    for (item_idx, item) in item_group.iter().enumerate() {
+        let final_minor = claimed.get(&item_idx).copied();
+
+        if result.affected_items.contains(&item_idx) != final_minor.is_some()
+            || !listed.contains(&item_idx)
+        {
+            return Err(VerificationError::AffectedItemsMismatch { item_idx });
+        }
+
+        expected_total += final_minor.unwrap_or_else(|| item.price().to_minor_units());
+    }
+
+    let actual_total = result.total.to_minor_units();
+
+    if actual_total != expected_total {
+        return Err(VerificationError::TotalMismatch {
+            expected: expected_total,
+            actual: actual_total,
+        });
+    }
+
+    Ok(())
+}
+
+/// Verify one layer's redemptions against the promotions solved in it.
+///
+/// Returns each claimed item's final price, by item index.
+pub(crate) fn verify_redemptions(
+    promotions: &[Promotion<'_>],
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<BTreeMap<usize, i64>, VerificationError> {
+    let mut claimed = BTreeMap::new();
+
+    for redemption in redemptions {
+        let item = redeemed_item(item_group, redemption)?;
+
+        if claimed
+            .insert(redemption.item_idx, redemption.final_price.to_minor_units())
+            .is_some()
+        {
+            return Err(VerificationError::ItemClaimedTwice {
+                item_idx: redemption.item_idx,
+            });
+        }
+
+        let expected = item.price().to_minor_units();
+        let actual = redemption.original_price.to_minor_units();
+
+        if actual != expected {
+            return Err(VerificationError::OriginalPriceMismatch {
+                item_idx: redemption.item_idx,
+                expected,
+                actual,
+            });
+        }
+
+        if !promotions
+            .iter()
+            .any(|promotion| promotion.key() == redemption.promotion_key)
+        {
+            return Err(VerificationError::UnknownPromotion {
+                promotion_key: redemption.promotion_key,
+            });
+        }
+    }
+
+    for promotion in promotions {
+        let key = promotion.key();
+
+        let own: SmallVec<[PromotionRedemption<'_>; 10]> = redemptions
+            .iter()
+            .filter(|redemption| redemption.promotion_key == key)
+            .cloned()
+            .collect();
+
+        if !own.is_empty() {
+            verify_promotion(promotion, item_group, &own)?;
+        }
+    }
+
+    Ok(claimed)
+}
+
+/// Verify one promotion's redemptions against the rules of its type.
+///
+/// `redemptions` are this promotion's redemptions only. Custom promotions are
+/// only held to the checks shared by every promotion.
+pub(crate) fn verify_promotion(
+    promotion: &Promotion<'_>,
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<(), VerificationError> {
+    match promotion.promotion_type() {
+        Some(PromotionType::DirectDiscount(promotion)) => {
+            direct_discount::verify(promotion, item_group, redemptions)
+        }
+        Some(PromotionType::MixAndMatch(promotion)) => {
+            mix_and_match::verify(promotion, item_group, redemptions)
+        }
+        Some(PromotionType::PositionalDiscount(promotion)) => {
+            positional_discount::verify(promotion, item_group, redemptions)
+        }
+        Some(PromotionType::TieredThreshold(promotion)) => {
+            tiered_threshold::verify(promotion, item_group, redemptions)
+        }
+        None => Ok(()),
+    }
+}
+
+/// A redeemed item with its original and final prices, in minor units.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RedeemedItem<'r, 'a> {
+    pub(crate) item_idx: usize,
+    pub(crate) item: &'r Item<'a>,
+    pub(crate) original_minor: i64,
+    pub(crate) final_minor: i64,
+}
+
+impl RedeemedItem<'_, '_> {
+    /// Amount taken off the item's price.
+    pub(crate) fn discount_minor(&self) -> i64 {
+        self.original_minor.saturating_sub(self.final_minor)
+    }
+}
+
+/// A promotion's redemptions grouped by redemption index, in index order.
+pub(crate) type Bundles<'r, 'a> = BTreeMap<usize, SmallVec<[RedeemedItem<'r, 'a>; 10]>>;
+
+/// Group a promotion's redemptions into bundles that were redeemed together.
+pub(crate) fn bundles<'r, 'a>(
+    item_group: &'r ItemGroup<'a>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<Bundles<'r, 'a>, VerificationError> {
+    let mut bundles: Bundles<'r, 'a> = BTreeMap::new();
+
+    for redemption in redemptions {
+        let item = redeemed_item(item_group, redemption)?;
+
+        bundles
+            .entry(redemption.redemption_idx)
+            .or_default()
+            .push(RedeemedItem {
+                item_idx: redemption.item_idx,
+                item,
+                original_minor: redemption.original_price.to_minor_units(),
+                final_minor: redemption.final_price.to_minor_units(),
+            });
+    }
+
+    Ok(bundles)
+}
+
+/// Look up the item a redemption refers to.
+fn redeemed_item<'r, 'a>(
+    item_group: &'r ItemGroup<'a>,
+    redemption: &PromotionRedemption<'_>,
+) -> Result<&'r Item<'a>, VerificationError> {
+    item_group
+        .get_item(redemption.item_idx)
+        .map_err(|_err| VerificationError::UnknownItem {
+            item_idx: redemption.item_idx,
+        })
+}
+
+/// Check that a redeemed item's final price is `expected`.
+pub(crate) fn check_final_price(
+    promotion_key: PromotionKey,
+    redeemed: &RedeemedItem<'_, '_>,
+    expected: i64,
+) -> Result<(), VerificationError> {
+    if redeemed.final_minor == expected {
+        return Ok(());
+    }
+
+    Err(VerificationError::FinalPriceMismatch {
+        promotion_key,
+        item_idx: redeemed.item_idx,
+        expected,
+        actual: redeemed.final_minor,
+    })
+}
+
+/// Check a bundle's final prices against the `(original, final)` prices its
+/// discount gives.
+///
+/// Items with the same original price are interchangeable, so prices are
+/// matched by original price rather than by item.
+pub(crate) fn check_bundle_prices(
+    promotion_key: PromotionKey,
+    bundle: &[RedeemedItem<'_, '_>],
+    expected: &mut [(i64, i64)],
+) -> Result<(), VerificationError> {
+    let mut actual: SmallVec<[RedeemedItem<'_, '_>; 10]> = bundle.iter().copied().collect();
+
+    actual.sort_by(|a, b| {
+        b.original_minor
+            .cmp(&a.original_minor)
+            .then_with(|| a.final_minor.cmp(&b.final_minor))
+    });
+    expected.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    for (redeemed, &(_, expected_final)) in actual.iter().zip(expected.iter()) {
+        check_final_price(promotion_key, redeemed, expected_final)?;
+    }
+
+    Ok(())
+}
+
+/// Check that a bundle's final prices add up to `expected` and none is negative.
+pub(crate) fn check_bundle_total(
+    promotion_key: PromotionKey,
+    redemption_idx: usize,
+    bundle: &[RedeemedItem<'_, '_>],
+    expected: i64,
+) -> Result<(), VerificationError> {
+    if let Some(redeemed) = bundle.iter().find(|redeemed| redeemed.final_minor < 0) {
+        return check_final_price(promotion_key, redeemed, 0);
+    }
+
+    let actual: i64 = bundle.iter().map(|redeemed| redeemed.final_minor).sum();
+
+    if actual != expected {
+        return Err(VerificationError::BundleTotalMismatch {
+            promotion_key,
+            redemption_idx,
+            expected,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// Check a bundle where only its cheapest item is discounted, to `target_price`.
+pub(crate) fn check_cheapest_prices(
+    promotion_key: PromotionKey,
+    bundle: &[RedeemedItem<'_, '_>],
+    target_price: impl Fn(i64) -> Result<i64, DiscountError>,
+) -> Result<(), VerificationError> {
+    let Some(cheapest) = bundle.iter().map(|redeemed| redeemed.original_minor).min() else {
+        return Ok(());
+    };
+
+    let mut expected: SmallVec<[(i64, i64); 10]> = SmallVec::new();
+    let mut targeted = false;
+
+    for redeemed in bundle {
+        let original = redeemed.original_minor;
+
+        if !targeted && original == cheapest {
+            targeted = true;
+            expected.push((original, target_price(original)?));
+        } else {
+            expected.push((original, original));
+        }
+    }
+
+    check_bundle_prices(promotion_key, bundle, &mut expected)
+}
+
+/// Check a promotion's redemption count and total discount against its budget.
+pub(crate) fn check_budget(
+    promotion_key: PromotionKey,
+    budget: &PromotionBudget<'_>,
+    redemption_count: usize,
+    bundles: &Bundles<'_, '_>,
+) -> Result<(), VerificationError> {
+    if let Some(limit) = budget.redemption_limit
+        && redemption_count > usize::try_from(limit).unwrap_or(usize::MAX)
+    {
+        return Err(VerificationError::RedemptionLimitExceeded {
+            promotion_key,
+            limit,
+            actual: redemption_count,
+        });
+    }
+
+    if let Some(limit) = budget.monetary_limit {
+        let limit = limit.to_minor_units();
+
+        let actual: i64 = bundles
+            .values()
+            .flatten()
+            .map(RedeemedItem::discount_minor)
+            .sum();
+
+        if actual > limit {
+            return Err(VerificationError::MonetaryLimitExceeded {
+                promotion_key,
+                limit,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Price of an item after a simple discount, never below zero.
+pub(crate) fn simple_discounted_minor(
+    discount: &SimpleDiscount<'_>,
+    original_minor: i64,
+    measure: Option<Measure>,
+) -> Result<i64, DiscountError> {
+    let discounted = match discount {
+        SimpleDiscount::PercentageOff(pct) => {
+            original_minor.saturating_sub(percent_of_minor(pct, original_minor)?)
+        }
+        SimpleDiscount::AmountOverride(amount) => amount.to_minor_units(),
+        SimpleDiscount::AmountOff(amount) => original_minor.saturating_sub(amount.to_minor_units()),
+        SimpleDiscount::AmountOffPerMeasure(amount) => original_minor.saturating_sub(
+            amount_off_per_measure_minor(amount.to_minor_units(), measure)?,
+        ),
+    };
+
+    Ok(discounted.max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal_percentage::Percentage;
+    use rusty_money::{Money, iso::GBP};
+    use slotmap::SlotMap;
+    use smallvec::{SmallVec, smallvec};
+    use testresult::TestResult;
+
+    use crate::{
+        discounts::SimpleDiscount,
+        items::{Item, groups::ItemGroup},
+        products::ProductKey,
+        promotions::{
+            Promotion, PromotionKey, budget::PromotionBudget, promotion,
+            qualification::Qualification, types::PositionalDiscountPromotion,
+        },
+        solvers::{Solver, ilp::ILPSolver},
+    };
+
+    use super::*;
+
+    fn items(prices: &[i64]) -> ItemGroup<'static> {
+        let mut products = SlotMap::<ProductKey, ()>::with_key();
+
+        let items: SmallVec<[Item<'static>; 10]> = prices
+            .iter()
+            .map(|&price| Item::new(products.insert(()), Money::from_minor(price, GBP)))
+            .collect();
+
+        ItemGroup::new(items, GBP)
+    }
+
+    fn three_for_two(key: PromotionKey) -> Promotion<'static> {
+        promotion(PositionalDiscountPromotion::new(
+            key,
+            Qualification::match_all(),
+            3,
+            smallvec![2],
+            SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+            PromotionBudget::unlimited(),
+        ))
+    }
+
+    #[test]
+    fn solver_result_passes_verification() -> TestResult {
+        let key = SlotMap::<PromotionKey, ()>::with_key().insert(());
+        let promotions = [three_for_two(key)];
+        let item_group = items(&[300, 200, 100, 50]);
+
+        let result = ILPSolver::solve(&promotions, &item_group)?;
+
+        assert_eq!(result.total.to_minor_units(), 550);
+        verify_result(&promotions, &item_group, &result)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_results_are_rejected() -> TestResult {
+        let key = SlotMap::<PromotionKey, ()>::with_key().insert(());
+        let promotions = [three_for_two(key)];
+        let item_group = items(&[300, 200, 100, 50]);
+
+        let result = ILPSolver::solve(&promotions, &item_group)?;
+
+        // Free item is not the cheapest in its bundle.
+        let mut wrong_position = result.clone();
+
+        for redemption in &mut wrong_position.promotion_redemptions {
+            redemption.final_price = match redemption.original_price.to_minor_units() {
+                300 => Money::from_minor(0, GBP),
+                _ => redemption.original_price,
+            };
+        }
+
+        wrong_position.total = Money::from_minor(350, GBP);
+
+        assert!(matches!(
+            verify_result(&promotions, &item_group, &wrong_position),
+            Err(VerificationError::FinalPriceMismatch { .. })
+        ));
+
+        // A bundle missing an item.
+        let mut short_bundle = result.clone();
+        short_bundle.promotion_redemptions.truncate(2);
+
+        assert!(matches!(
+            verify_result(&promotions, &item_group, &short_bundle),
+            Err(VerificationError::IncompleteBundle { .. })
+        ));
+
+        // The total does not add up.
+        let mut wrong_total = result;
+        wrong_total.total = Money::from_minor(500, GBP);
+
+        assert!(matches!(
+            verify_result(&promotions, &item_group, &wrong_total),
+            Err(VerificationError::TotalMismatch {
+                expected: 550,
+                actual: 500,
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn items_claimed_twice_are_rejected() -> TestResult {
+        let key = SlotMap::<PromotionKey, ()>::with_key().insert(());
+        let promotions = [three_for_two(key)];
+        let item_group = items(&[300, 200, 100, 50]);
+
+        let mut result = ILPSolver::solve(&promotions, &item_group)?;
+        let first = result
+            .promotion_redemptions
+            .first()
+            .cloned()
+            .ok_or("expected a redemption")?;
+
+        result.promotion_redemptions.push(first);
+
+        assert!(matches!(
+            verify_result(&promotions, &item_group, &result),
+            Err(VerificationError::ItemClaimedTwice { .. })
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+//! Positional Discount Verification
+//!
+//! Each redemption is a full bundle of qualifying items. Ordered by price, most
+//! expensive first, only the items at the promotion's positions are discounted.
+
+use smallvec::SmallVec;
+
+use crate::{
+    items::groups::ItemGroup,
+    promotions::{redemptions::PromotionRedemption, types::PositionalDiscountPromotion},
+    solvers::verifier::{
+        RedeemedItem, VerificationError, bundles, check_budget, check_bundle_prices,
+        simple_discounted_minor,
+    },
+};
+
+/// Verify a positional discount's redemptions in one layer.
+pub(crate) fn verify(
+    promotion: &PositionalDiscountPromotion<'_>,
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+    let size = usize::from(promotion.size());
+    let bundles = bundles(item_group, redemptions)?;
+
+    for (&redemption_idx, bundle) in &bundles {
+        if bundle.len() != size {
+            return Err(VerificationError::IncompleteBundle {
+                promotion_key,
+                redemption_idx,
+                expected: size,
+                actual: bundle.len(),
+            });
+        }
+
+        if let Some(redeemed) = bundle
+            .iter()
+            .find(|redeemed| !promotion.qualification().matches(redeemed.item.tags()))
+        {
+            return Err(VerificationError::IneligibleItem {
+                promotion_key,
+                item_idx: redeemed.item_idx,
+            });
+        }
+
+        let mut ordered: SmallVec<[&RedeemedItem<'_, '_>; 10]> = bundle.iter().collect();
+
+        ordered.sort_by(|a, b| {
+            b.original_minor
+                .cmp(&a.original_minor)
+                .then_with(|| a.item_idx.cmp(&b.item_idx))
+        });
+
+        let mut expected: SmallVec<[(i64, i64); 10]> = SmallVec::new();
+
+        for (position, redeemed) in ordered.into_iter().enumerate() {
+            let discounted = u16::try_from(position)
+                .is_ok_and(|position| promotion.positions().contains(&position));
+
+            let final_minor = if discounted {
+                simple_discounted_minor(
+                    promotion.discount(),
+                    redeemed.original_minor,
+                    redeemed.item.measure(),
+                )?
+            } else {
+                redeemed.original_minor
+            };
+
+            expected.push((redeemed.original_minor, final_minor));
+        }
+
+        check_bundle_prices(promotion_key, bundle, &mut expected)?;
+    }
+
+    check_budget(promotion_key, promotion.budget(), bundles.len(), &bundles)
+}
@@ -0,0 +1,223 @@
+//! Tiered Threshold Verification
+//!
+//! A tiered promotion is redeemed at most once, by a single tier. The claimed
+//! items that contribute to that tier must reach its lower threshold and stay
+//! within its upper cap, as must the claimed items it discounts. Items that
+//! only contribute keep their price.
+
+use smallvec::SmallVec;
+
+use crate::{
+    discounts::percent_of_minor,
+    items::{groups::ItemGroup, measure::Measure},
+    promotions::{
+        redemptions::PromotionRedemption,
+        types::{ThresholdDiscount, ThresholdTier, TierThreshold, TieredThresholdPromotion},
+    },
+    solvers::verifier::{
+        RedeemedItem, VerificationError, bundles, check_budget, check_bundle_total,
+        check_cheapest_prices, check_final_price,
+    },
+};
+
+/// Verify a tiered threshold promotion's redemptions in one layer.
+pub(crate) fn verify(
+    promotion: &TieredThresholdPromotion<'_>,
+    item_group: &ItemGroup<'_>,
+    redemptions: &[PromotionRedemption<'_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+    let bundles = bundles(item_group, redemptions)?;
+
+    if bundles.len() > 1 {
+        return Err(VerificationError::RepeatedRedemption {
+            promotion_key,
+            count: bundles.len(),
+        });
+    }
+
+    for (&redemption_idx, bundle) in &bundles {
+        if let Some(redeemed) = bundle.iter().find(|redeemed| {
+            !promotion.tiers().iter().any(|tier| {
+                tier.contribution_qualification()
+                    .matches(redeemed.item.tags())
+                    || tier.discount_qualification().matches(redeemed.item.tags())
+            })
+        }) {
+            return Err(VerificationError::IneligibleItem {
+                promotion_key,
+                item_idx: redeemed.item_idx,
+            });
+        }
+
+        // The tier that was redeemed is not recorded, so any tier the items and
+        // prices are consistent with will do.
+        let mut matched = false;
+
+        for tier in promotion.tiers() {
+            if matches_tier(promotion, tier, redemption_idx, bundle)? {
+                matched = true;
+                break;
+            }
+        }
+
+        if !matched {
+            return Err(VerificationError::NoMatchingTier {
+                promotion_key,
+                redemption_idx,
+            });
+        }
+    }
+
+    check_budget(promotion_key, promotion.budget(), bundles.len(), &bundles)
+}
+
+/// Whether the redemption's items and prices are consistent with `tier`.
+fn matches_tier(
+    promotion: &TieredThresholdPromotion<'_>,
+    tier: &ThresholdTier<'_>,
+    redemption_idx: usize,
+    bundle: &[RedeemedItem<'_, '_>],
+) -> Result<bool, VerificationError> {
+    let mut contributing: SmallVec<[RedeemedItem<'_, '_>; 10]> = SmallVec::new();
+    let mut discounted: SmallVec<[RedeemedItem<'_, '_>; 10]> = SmallVec::new();
+
+    for redeemed in bundle {
+        let contributes = tier
+            .contribution_qualification()
+            .matches(redeemed.item.tags());
+        let discountable = tier.discount_qualification().matches(redeemed.item.tags());
+
+        if !contributes && !discountable {
+            return Ok(false);
+        }
+
+        if contributes {
+            contributing.push(*redeemed);
+        }
+
+        if discountable {
+            discounted.push(*redeemed);
+        } else if redeemed.final_minor != redeemed.original_minor {
+            return Ok(false);
+        }
+    }
+
+    if !reaches(tier.lower_threshold(), &contributing) {
+        return Ok(false);
+    }
+
+    if let Some(upper) = tier.upper_threshold()
+        && (exceeds(upper, &contributing) || exceeds(upper, &discounted))
+    {
+        return Ok(false);
+    }
+
+    let prices = verify_prices(promotion, tier, redemption_idx, &discounted);
+
+    match prices {
+        Ok(()) => Ok(true),
+        Err(VerificationError::Discount(err)) => Err(VerificationError::Discount(err)),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Whether `items` meet every requirement of a lower threshold.
+fn reaches(threshold: &TierThreshold<'_>, items: &[RedeemedItem<'_, '_>]) -> bool {
+    let spend: i64 = items.iter().map(|redeemed| redeemed.original_minor).sum();
+
+    threshold
+        .monetary_threshold()
+        .is_none_or(|threshold| spend >= threshold.to_minor_units())
+        && threshold
+            .item_count_threshold()
+            .is_none_or(|threshold| items.len() >= usize::try_from(threshold).unwrap_or(usize::MAX))
+        && threshold.measure_threshold().is_none_or(|threshold| {
+            measure_in_unit(items, threshold) >= u64::from(threshold.amount())
+        })
+}
+
+/// Whether `items` go over any cap of an upper threshold.
+fn exceeds(threshold: &TierThreshold<'_>, items: &[RedeemedItem<'_, '_>]) -> bool {
+    let spend: i64 = items.iter().map(|redeemed| redeemed.original_minor).sum();
+
+    threshold
+        .monetary_threshold()
+        .is_some_and(|threshold| spend > threshold.to_minor_units())
+        || threshold
+            .item_count_threshold()
+            .is_some_and(|threshold| items.len() > usize::try_from(threshold).unwrap_or(usize::MAX))
+        || threshold.measure_threshold().is_some_and(|threshold| {
+            measure_in_unit(items, threshold) > u64::from(threshold.amount())
+        })
+}
+
+/// Total measure of `items` in the unit of `threshold`; other items count as zero.
+fn measure_in_unit(items: &[RedeemedItem<'_, '_>], threshold: Measure) -> u64 {
+    items
+        .iter()
+        .filter_map(|redeemed| redeemed.item.measure())
+        .filter(|measure| measure.unit() == threshold.unit())
+        .map(|measure| u64::from(measure.amount()))
+        .sum()
+}
+
+/// Check the discounted items' final prices against the tier's discount.
+fn verify_prices(
+    promotion: &TieredThresholdPromotion<'_>,
+    tier: &ThresholdTier<'_>,
+    redemption_idx: usize,
+    discounted: &[RedeemedItem<'_, '_>],
+) -> Result<(), VerificationError> {
+    let promotion_key = promotion.key();
+
+    match tier.discount() {
+        ThresholdDiscount::PercentEachItem(_)
+        | ThresholdDiscount::AmountOffEachItem(_)
+        | ThresholdDiscount::FixedPriceEachItem(_) => {
+            for redeemed in discounted {
+                let expected =
+                    TieredThresholdPromotion::calculate_discounted_price(tier, redeemed.item)?
+                        .to_minor_units();
+
+                check_final_price(promotion_key, redeemed, expected)?;
+            }
+        }
+        ThresholdDiscount::AmountOffTotal(amount) => {
+            let original: i64 = discounted
+                .iter()
+                .map(|redeemed| redeemed.original_minor)
+                .sum();
+
+            check_bundle_total(
+                promotion_key,
+                redemption_idx,
+                discounted,
+                original.saturating_sub(amount.to_minor_units()).max(0),
+            )?;
+        }
+        ThresholdDiscount::FixedTotal(amount) if !discounted.is_empty() => {
+            check_bundle_total(
+                promotion_key,
+                redemption_idx,
+                discounted,
+                amount.to_minor_units().max(0),
+            )?;
+        }
+        ThresholdDiscount::FixedTotal(_) => {}
+        ThresholdDiscount::PercentCheapest(pct) => {
+            check_cheapest_prices(promotion_key, discounted, |original| {
+                Ok(original
+                    .saturating_sub(percent_of_minor(pct, original)?)
+                    .max(0))
+            })?;
+        }
+        ThresholdDiscount::FixedCheapest(amount) => {
+            check_cheapest_prices(promotion_key, discounted, |_original| {
+                Ok(amount.to_minor_units().max(0))
+            })?;
+        }
+    }
+
+    Ok(())
+}
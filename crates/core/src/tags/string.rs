@@ -13,7 +13,7 @@ use smallvec::SmallVec;
 use crate::tags::collection::TagCollection;
 
 /// A string-based tag collection using `SmallVec<[String; 5]>` for simple operations.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct StringTagCollection {
     tags: SmallVec<[String; 5]>,
 }
@@ -0,0 +1,102 @@
+//! Integration tests for enumerating alternative solutions on the fixture sets.
+
+use std::collections::BTreeSet;
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    promotions::PromotionKey,
+    solvers::{
+        Solver, SolverResult,
+        ilp::{ILPSolver, ILPSolverConfig},
+    },
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+/// Which promotion each item was redeemed through.
+fn assignment(result: &SolverResult<'_>) -> BTreeSet<(usize, PromotionKey)> {
+    result
+        .promotion_redemptions
+        .iter()
+        .map(|redemption| (redemption.item_idx, redemption.promotion_key))
+        .collect()
+}
+
+#[test]
+fn alternatives_are_distinct_and_ordered_by_total() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        let alternatives =
+            ILPSolver::solve_alternatives(&ILPSolverConfig::default(), promotions, &item_group, 4)?;
+        let optimal = ILPSolver::solve(promotions, &item_group)?;
+
+        let totals: Vec<i64> = alternatives
+            .iter()
+            .map(|result| result.total.to_minor_units())
+            .collect();
+
+        assert_eq!(
+            totals.first().copied(),
+            Some(optimal.total.to_minor_units()),
+            "{set}"
+        );
+        assert!(totals.windows(2).all(|pair| pair[0] <= pair[1]), "{set}");
+
+        let assignments: BTreeSet<_> = alternatives.iter().map(assignment).collect();
+
+        assert_eq!(assignments.len(), alternatives.len(), "{set}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn runner_up_is_the_next_cheapest_assignment() -> TestResult {
+    let fixture = Fixture::from_set("complex")?;
+    let basket = fixture.basket(Some(4))?;
+    let item_group = ItemGroup::from(&basket);
+
+    let alternatives = ILPSolver::solve_alternatives(
+        &ILPSolverConfig::default(),
+        fixture.promotions(),
+        &item_group,
+        2,
+    )?;
+
+    let totals: Vec<i64> = alternatives
+        .iter()
+        .map(|result| result.total.to_minor_units())
+        .collect();
+
+    // The runner-up keeps the 3-for-2 bundle but leaves the Travel Shower Gel at
+    // full price instead of 15% off.
+    assert_eq!(totals, vec![935, 950]);
+    assert_eq!(
+        alternatives
+            .get(1)
+            .map(|result| result.unaffected_items.to_vec()),
+        Some(vec![2])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Conformance tests that run every fixture set against every enabled MILP backend.
+//!
+//! Backends may pick different optimal solutions when several tie, so results are
+//! compared on the optimum itself (the basket total) rather than on which items
+//! were chosen.
+
+use std::time::Duration;
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    solvers::{
+        Solver,
+        ilp::{ILPBackend, ILPSolver, ILPSolverConfig, NoopObserver},
+    },
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn every_enabled_backend_reaches_the_same_optimum() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        let reference = ILPSolver::solve(promotions, &item_group)?;
+
+        for &backend in ILPBackend::ENABLED {
+            let config = ILPSolverConfig::new().with_backend(backend);
+            let result =
+                ILPSolver::solve_with_config(&config, promotions, &item_group, &mut NoopObserver)?;
+
+            assert_eq!(
+                result.total,
+                reference.total,
+                "{} disagrees with the default backend on fixture set {set}",
+                backend.name()
+            );
+
+            let mut items: Vec<usize> = result
+                .affected_items
+                .iter()
+                .chain(result.unaffected_items.iter())
+                .copied()
+                .collect();
+
+            items.sort_unstable();
+
+            assert_eq!(
+                items,
+                (0..item_group.len()).collect::<Vec<_>>(),
+                "{} must account for every item exactly once on fixture set {set}",
+                backend.name()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn every_enabled_backend_solves_the_comprehensive_fixture() -> TestResult {
+    let fixture = Fixture::from_set("comprehensive")?;
+    let basket = fixture.basket(None)?;
+    let item_group = ItemGroup::from(&basket);
+
+    for &backend in ILPBackend::ENABLED {
+        let config = ILPSolverConfig::new().with_backend(backend);
+        let result = ILPSolver::solve_with_config(
+            &config,
+            fixture.promotions(),
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(
+            result.total.to_minor_units(),
+            2122,
+            "{} returned the wrong total",
+            backend.name()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn every_enabled_backend_is_optimal_within_a_generous_time_limit() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        let reference = ILPSolver::solve(promotions, &item_group)?;
+
+        for &backend in ILPBackend::ENABLED {
+            let config = ILPSolverConfig::new()
+                .with_backend(backend)
+                .with_time_limit(Duration::from_secs(60));
+            let result =
+                ILPSolver::solve_with_config(&config, promotions, &item_group, &mut NoopObserver)?;
+
+            assert!(
+                result.optimal,
+                "{} did not finish fixture set {set} within the limit",
+                backend.name()
+            );
+            assert_eq!(result.total, reference.total);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn every_enabled_backend_prices_every_item_when_out_of_time() -> TestResult {
+    let fixture = Fixture::from_set("comprehensive")?;
+    let basket = fixture.basket(None)?;
+    let item_group = ItemGroup::from(&basket);
+
+    for &backend in ILPBackend::ENABLED {
+        let config = ILPSolverConfig::new()
+            .with_backend(backend)
+            .with_time_limit(Duration::ZERO);
+        let result = ILPSolver::solve_with_config(
+            &config,
+            fixture.promotions(),
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert!(!result.optimal, "{} claimed an optimum", backend.name());
+        assert_eq!(
+            result.affected_items.len() + result.unaffected_items.len(),
+            item_group.len(),
+            "{} must account for every item",
+            backend.name()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Batch evaluation tests: pricing baskets as a batch gives the same results as
+//! pricing each on its own, with totals that add up.
+
+use std::collections::BTreeSet;
+
+use rusty_money::{Money, iso::USD};
+use testresult::TestResult;
+
+use lattice::{
+    basket::Basket,
+    fixtures::Fixture,
+    graph::{BasketSummary, GraphError, LayeredSolverResult},
+    items::{Item, groups::ItemGroup},
+    products::ProductKey,
+    promotions::PromotionKey,
+    solvers::ilp::ILPPromotion,
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+/// Each item's redemptions as `(item, promotion, redemption index, final price)`.
+fn redemptions(result: &LayeredSolverResult<'_>) -> BTreeSet<(usize, PromotionKey, usize, i64)> {
+    result
+        .item_redemptions
+        .iter()
+        .flat_map(|(&item_idx, redemptions)| {
+            redemptions.iter().map(move |redemption| {
+                (
+                    item_idx,
+                    redemption.promotion_key,
+                    redemption.redemption_idx,
+                    redemption.final_price.to_minor_units(),
+                )
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn batch_results_match_individual_evaluation() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let graph = fixture.graph()?;
+
+        let baskets = (1..=fixture.items().len())
+            .map(|n| fixture.basket(Some(n)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut summaries: Vec<BasketSummary<'_>> = Vec::new();
+
+        let totals = graph
+            .batch()
+            .evaluate(baskets.iter().map(ItemGroup::from), |summary| {
+                summaries.push(summary);
+            })?;
+
+        assert_eq!(summaries.len(), baskets.len(), "fixture set {set}");
+        assert_eq!(totals.baskets, baskets.len(), "fixture set {set}");
+
+        for (basket_idx, (summary, basket)) in summaries.iter().zip(&baskets).enumerate() {
+            let expected = graph.evaluate(&ItemGroup::from(basket))?;
+
+            assert_eq!(summary.basket_idx, basket_idx, "fixture set {set}");
+            assert_eq!(
+                summary.result.total.to_minor_units(),
+                expected.total.to_minor_units(),
+                "fixture set {set}, basket {basket_idx}: totals differ"
+            );
+            assert_eq!(
+                redemptions(&summary.result),
+                redemptions(&expected),
+                "fixture set {set}, basket {basket_idx}: redemptions differ"
+            );
+            assert_eq!(
+                summary.result.full_price_items, expected.full_price_items,
+                "fixture set {set}, basket {basket_idx}: full price items differ"
+            );
+        }
+
+        let savings: i64 = summaries.iter().map(BasketSummary::savings_minor).sum();
+        let discounts: i64 = totals
+            .promotions
+            .values()
+            .map(|promotion| promotion.discount_minor)
+            .sum();
+
+        assert_eq!(totals.savings_minor(), savings, "fixture set {set}");
+        assert_eq!(discounts, savings, "fixture set {set}");
+
+        for promotion in totals.promotions.values() {
+            assert!(
+                promotion.redemptions <= promotion.items,
+                "fixture set {set}: more redemptions than items"
+            );
+            assert!(
+                promotion.baskets <= promotion.redemptions,
+                "fixture set {set}: more baskets than redemptions"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn batch_totals_sum_each_promotion_across_baskets() -> TestResult {
+    let fixture = Fixture::from_set("layered")?;
+    let graph = fixture.graph()?;
+    let basket = fixture.basket(None)?;
+
+    let totals = graph
+        .batch()
+        .evaluate((0..3).map(|_| ItemGroup::from(&basket)), |_| {})?;
+
+    let lunch_deal = totals
+        .promotions
+        .get(&fixture.promotion("lunch-deal")?.key())
+        .ok_or("expected the lunch deal to be redeemed")?;
+
+    assert_eq!(totals.baskets, 3);
+    assert_eq!(totals.savings_minor(), 903);
+    assert_eq!(lunch_deal.discount_minor, 489);
+    assert_eq!(lunch_deal.items, 6);
+    assert_eq!(lunch_deal.redemptions, 6);
+    assert_eq!(lunch_deal.baskets, 3);
+
+    Ok(())
+}
+
+#[test]
+fn batch_rejects_baskets_in_another_currency() -> TestResult {
+    let fixture = Fixture::from_set("layered")?;
+    let graph = fixture.graph()?;
+    let basket = fixture.basket(None)?;
+
+    let dollars = Basket::with_items(
+        [Item::new(
+            ProductKey::default(),
+            Money::from_minor(100, USD),
+        )],
+        USD,
+    )?;
+
+    let mut priced = Vec::new();
+
+    let result = graph.batch().evaluate(
+        [ItemGroup::from(&basket), ItemGroup::from(&dollars)],
+        |summary| priced.push(summary.basket_idx),
+    );
+
+    assert!(matches!(
+        result,
+        Err(GraphError::BatchCurrencyMismatch { basket_idx: 1, .. })
+    ));
+    assert_eq!(priced, vec![0]);
+
+    Ok(())
+}
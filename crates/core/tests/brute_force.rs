@@ -0,0 +1,343 @@
+//! Differential tests: on small random baskets, the ILP solver finds the same
+//! optimum as an exhaustive search over the promotion definitions.
+
+use decimal_percentage::Percentage;
+use rusty_money::{Money, iso::GBP};
+use slotmap::SlotMap;
+use smallvec::SmallVec;
+use testresult::TestResult;
+
+use lattice::{
+    basket::Basket,
+    discounts::SimpleDiscount,
+    fixtures::Fixture,
+    items::{
+        Item,
+        groups::ItemGroup,
+        measure::{Measure, MeasureError},
+    },
+    products::ProductKey,
+    promotions::{
+        Promotion, PromotionKey, PromotionSlotKey,
+        budget::PromotionBudget,
+        promotion,
+        qualification::Qualification,
+        types::{
+            DirectDiscountPromotion, MixAndMatchDiscount, MixAndMatchPromotion, MixAndMatchSlot,
+            PositionalDiscountPromotion, ThresholdDiscount, ThresholdTier, TierThreshold,
+            TieredThresholdPromotion,
+        },
+    },
+    solvers::{
+        Solver, SolverError, brute_force::BruteForceSolver, ilp::ILPSolver, verifier::verify_result,
+    },
+    tags::string::StringTagCollection,
+};
+
+const SEEDS: u64 = 1000;
+
+const TAGS: [&str; 3] = ["a", "b", "c"];
+
+/// Most units in a random basket, which keeps the exhaustive search quick.
+const MAX_UNITS: u32 = 8;
+
+/// Small deterministic generator (`SplitMix64`), so failures reproduce from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `low..=high`.
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.range(1, 100) <= percent
+    }
+
+    fn minor(&mut self, low: i64, high: i64, step: i64) -> i64 {
+        let steps = u64::try_from((high - low) / step).unwrap_or_default();
+
+        low + i64::try_from(self.range(0, steps)).unwrap_or_default() * step
+    }
+
+    fn tag(&mut self) -> &'static str {
+        TAGS.get(usize::try_from(self.range(0, 2)).unwrap_or_default())
+            .copied()
+            .unwrap_or("a")
+    }
+
+    fn percentage(&mut self) -> Percentage {
+        Percentage::from(f64::from(u32::try_from(self.range(1, 10)).unwrap_or_default()) / 10.0)
+    }
+
+    fn money(&mut self, low: i64, high: i64) -> Money<'static, rusty_money::iso::Currency> {
+        Money::from_minor(self.minor(low, high, 10), GBP)
+    }
+
+    fn grams(&mut self) -> Measure {
+        Measure::grams(u32::try_from(self.minor(100, 1500, 50)).unwrap_or_default())
+    }
+}
+
+/// Single units, lines of identical units and items sold by weight, with at
+/// most [`MAX_UNITS`] units in all.
+fn random_items(rng: &mut Rng) -> Result<Vec<Item<'static>>, MeasureError> {
+    let mut items = Vec::new();
+    let mut units = 0;
+
+    for _ in 0..rng.range(1, 6) {
+        let mut tags: Vec<&str> = TAGS.iter().copied().filter(|_| rng.chance(40)).collect();
+
+        if tags.is_empty() {
+            tags.push(rng.tag());
+        }
+
+        let tags = StringTagCollection::from_strs(&tags);
+        let price = Money::from_minor(rng.minor(50, 1000, 10), GBP);
+
+        let item = match rng.range(0, 4) {
+            0 => Item::with_quantity(
+                ProductKey::default(),
+                price,
+                tags,
+                u32::try_from(rng.range(2, 3)).unwrap_or_default(),
+            ),
+            1 => Item::with_measure(ProductKey::default(), price, tags, rng.grams())?,
+            _ => Item::with_tags(ProductKey::default(), price, tags),
+        };
+
+        if units + item.quantity() > MAX_UNITS {
+            break;
+        }
+
+        units += item.quantity();
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+fn random_qualification(rng: &mut Rng) -> Qualification {
+    if rng.chance(25) {
+        Qualification::match_all()
+    } else if rng.chance(60) {
+        Qualification::match_any(StringTagCollection::from_strs(&[rng.tag()]))
+    } else {
+        Qualification::match_any(StringTagCollection::from_strs(&[rng.tag(), rng.tag()]))
+    }
+}
+
+fn random_budget(rng: &mut Rng) -> PromotionBudget<'static> {
+    match rng.range(0, 5) {
+        0 => PromotionBudget::with_redemption_limit(
+            u32::try_from(rng.range(1, 2)).unwrap_or_default(),
+        ),
+        1 => PromotionBudget::with_monetary_limit(rng.money(50, 400)),
+        _ => PromotionBudget::unlimited(),
+    }
+}
+
+fn random_simple_discount(rng: &mut Rng) -> SimpleDiscount<'static> {
+    match rng.range(0, 2) {
+        0 => SimpleDiscount::PercentageOff(rng.percentage()),
+        1 => SimpleDiscount::AmountOff(rng.money(10, 300)),
+        _ => SimpleDiscount::AmountOverride(rng.money(10, 300)),
+    }
+}
+
+fn random_mix_and_match_discount(rng: &mut Rng) -> MixAndMatchDiscount<'static> {
+    match rng.range(0, 6) {
+        0 => MixAndMatchDiscount::PercentAllItems(rng.percentage()),
+        1 => MixAndMatchDiscount::AmountOffEachItem(rng.money(10, 300)),
+        2 => MixAndMatchDiscount::FixedPriceEachItem(rng.money(10, 300)),
+        3 => MixAndMatchDiscount::AmountOffTotal(rng.money(10, 600)),
+        4 => MixAndMatchDiscount::FixedTotal(rng.money(100, 1500)),
+        5 => MixAndMatchDiscount::PercentCheapest(rng.percentage()),
+        _ => MixAndMatchDiscount::FixedCheapest(rng.money(0, 200)),
+    }
+}
+
+fn random_threshold_discount(rng: &mut Rng) -> ThresholdDiscount<'static> {
+    match rng.range(0, 6) {
+        0 => ThresholdDiscount::PercentEachItem(rng.percentage()),
+        1 => ThresholdDiscount::AmountOffEachItem(rng.money(10, 300)),
+        2 => ThresholdDiscount::FixedPriceEachItem(rng.money(10, 300)),
+        3 => ThresholdDiscount::AmountOffTotal(rng.money(10, 600)),
+        4 => ThresholdDiscount::FixedTotal(rng.money(100, 1500)),
+        5 => ThresholdDiscount::PercentCheapest(rng.percentage()),
+        _ => ThresholdDiscount::FixedCheapest(rng.money(0, 200)),
+    }
+}
+
+fn random_threshold(rng: &mut Rng, low: i64, high: i64) -> TierThreshold<'static> {
+    let count = u32::try_from(rng.range(1, 3)).unwrap_or_default();
+
+    match rng.range(0, 3) {
+        0 => TierThreshold::with_monetary_threshold(rng.money(low, high)),
+        1 => TierThreshold::with_item_count_threshold(count),
+        2 => TierThreshold::with_measure_threshold(rng.grams()),
+        _ => TierThreshold::with_both_thresholds(rng.money(low, high), count),
+    }
+}
+
+fn random_promotion(
+    rng: &mut Rng,
+    key: PromotionKey,
+    slot_keys: &mut SlotMap<PromotionSlotKey, ()>,
+) -> Promotion<'static> {
+    match rng.range(0, 3) {
+        0 => promotion(DirectDiscountPromotion::new(
+            key,
+            random_qualification(rng),
+            random_simple_discount(rng),
+            random_budget(rng),
+        )),
+        1 => {
+            let size = u16::try_from(rng.range(2, 3)).unwrap_or_default();
+            let mut positions: SmallVec<[u16; 5]> = (0..size).filter(|_| rng.chance(50)).collect();
+
+            if positions.is_empty() {
+                positions.push(size - 1);
+            }
+
+            promotion(PositionalDiscountPromotion::new(
+                key,
+                random_qualification(rng),
+                size,
+                positions,
+                random_simple_discount(rng),
+                random_budget(rng),
+            ))
+        }
+        2 => {
+            let slots = (0..rng.range(1, 2))
+                .map(|_| {
+                    let min = usize::try_from(rng.range(1, 2)).unwrap_or_default();
+                    let max = match rng.range(0, 2) {
+                        0 => Some(min),
+                        1 => Some(min + 1),
+                        _ => None,
+                    };
+
+                    MixAndMatchSlot::new(slot_keys.insert(()), random_qualification(rng), min, max)
+                })
+                .collect();
+
+            promotion(MixAndMatchPromotion::new(
+                key,
+                slots,
+                random_mix_and_match_discount(rng),
+                random_budget(rng),
+            ))
+        }
+        _ => {
+            let tiers = (0..rng.range(1, 2))
+                .map(|_| {
+                    let upper = rng.chance(30).then(|| random_threshold(rng, 1000, 3000));
+
+                    ThresholdTier::new(
+                        random_threshold(rng, 100, 1500),
+                        upper,
+                        random_qualification(rng),
+                        random_qualification(rng),
+                        random_threshold_discount(rng),
+                    )
+                })
+                .collect();
+
+            promotion(TieredThresholdPromotion::new(
+                key,
+                tiers,
+                random_budget(rng),
+            ))
+        }
+    }
+}
+
+#[test]
+fn ilp_matches_exhaustive_optimum_on_random_baskets() -> TestResult {
+    for seed in 0..SEEDS {
+        let mut rng = Rng(seed);
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let basket = Basket::with_items(random_items(&mut rng)?, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotions: Vec<Promotion<'static>> = (0..rng.range(1, 3))
+            .map(|_| random_promotion(&mut rng, keys.insert(()), &mut slot_keys))
+            .collect();
+
+        let ilp = ILPSolver::solve(&promotions, &item_group)?;
+        let exhaustive = BruteForceSolver::solve(&promotions, &item_group)?;
+
+        if let Err(err) = verify_result(&promotions, &item_group, &exhaustive) {
+            panic!("seed {seed}: exhaustive result fails verification: {err}");
+        }
+
+        if let Err(err) = verify_result(&promotions, &item_group, &ilp) {
+            panic!("seed {seed}: ILP result fails verification: {err}");
+        }
+
+        assert_eq!(
+            ilp.total.to_minor_units(),
+            exhaustive.total.to_minor_units(),
+            "seed {seed}: ILP total differs from the exhaustive optimum"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ilp_matches_exhaustive_optimum_on_small_fixtures() -> TestResult {
+    for set in ["direct", "positional", "mix-and-match", "tiered-threshold"] {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        if item_group.len() > BruteForceSolver::MAX_ITEMS {
+            continue;
+        }
+
+        let ilp = ILPSolver::solve(promotions, &item_group)?;
+        let exhaustive = BruteForceSolver::solve(promotions, &item_group)?;
+
+        assert_eq!(
+            ilp.total.to_minor_units(),
+            exhaustive.total.to_minor_units(),
+            "fixture set {set}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn exhaustive_solver_rejects_large_baskets() -> TestResult {
+    let items = (0..=BruteForceSolver::MAX_ITEMS)
+        .map(|_| Item::new(ProductKey::default(), Money::from_minor(100, GBP)))
+        .collect::<Vec<_>>();
+
+    let basket = Basket::with_items(items, GBP)?;
+    let item_group = ItemGroup::from(&basket);
+
+    assert!(matches!(
+        BruteForceSolver::solve(&[], &item_group),
+        Err(SolverError::TooManyItems { .. })
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+//! Helpers shared by the solver integration tests.
+
+use testresult::TestResult;
+
+use lattice::solvers::ilp::{ILPBackend, ILPSolverConfig};
+
+/// Run `test` once against each MILP backend enabled in this build.
+///
+/// The backend is named on standard error before each run, so a failing
+/// test's captured output shows which backend it failed on.
+#[expect(
+    clippy::print_stderr,
+    reason = "names the backend in a failing test's captured output"
+)]
+pub fn for_each_backend(test: impl Fn(&ILPSolverConfig) -> TestResult) -> TestResult {
+    for &backend in ILPBackend::ENABLED {
+        eprintln!("solving with {}", backend.name());
+
+        test(&ILPSolverConfig::new().with_backend(backend))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Integration tests checking that decomposing baskets into independent
+//! components does not change the solver's results on any fixture set.
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    solvers::ilp::{ILPSolver, ILPSolverConfig, NoopObserver},
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn decomposed_totals_match_monolithic_totals() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let decomposed = ILPSolver::solve_with_config(
+            &ILPSolverConfig::new(),
+            fixture.promotions(),
+            &item_group,
+            &mut NoopObserver,
+        )?;
+        let monolithic = ILPSolver::solve_with_config(
+            &ILPSolverConfig::new().with_decomposition(false),
+            fixture.promotions(),
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(
+            decomposed.total.to_minor_units(),
+            monolithic.total.to_minor_units(),
+            "decomposition changed the total on fixture set {set}"
+        );
+        assert!(decomposed.optimal);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn decomposed_results_account_for_every_item_exactly_once() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let result = ILPSolver::solve_with_config(
+            &ILPSolverConfig::new(),
+            fixture.promotions(),
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        let mut items: Vec<usize> = result
+            .affected_items
+            .iter()
+            .chain(result.unaffected_items.iter())
+            .copied()
+            .collect();
+
+        items.sort_unstable();
+
+        assert_eq!(
+            items,
+            (0..item_group.len()).collect::<Vec<_>>(),
+            "items not accounted for exactly once on fixture set {set}"
+        );
+
+        let mut redemption_keys: Vec<(usize, usize)> = result
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| (redemption.redemption_idx, redemption.item_idx))
+            .collect();
+
+        redemption_keys.sort_unstable();
+        redemption_keys.dedup();
+
+        assert_eq!(redemption_keys.len(), result.promotion_redemptions.len());
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 //! Integration tests for direct discount promotions through the ILP solver.
 
+mod common;
+
 use decimal_percentage::Percentage;
 use rusty_money::{Money, iso::GBP};
 use testresult::TestResult;
@@ -7,320 +9,394 @@ use testresult::TestResult;
 use lattice::{
     basket::Basket,
     discounts::SimpleDiscount,
-    items::{Item, groups::ItemGroup},
+    items::{Item, groups::ItemGroup, measure::Measure},
     products::ProductKey,
     promotions::{
         PromotionKey, budget::PromotionBudget, promotion, qualification::Qualification,
         types::DirectDiscountPromotion,
     },
-    solvers::{Solver, ilp::ILPSolver},
+    solvers::ilp::{ILPSolver, NoopObserver},
     tags::string::StringTagCollection,
 };
 
+use common::for_each_backend;
+
 #[test]
 fn solver_handles_percentage_off() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["fruit"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["fruit"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(150, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["fruit"])),
-        SimpleDiscount::PercentageOff(Percentage::from(0.25)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Fruit items: 100 * 0.75 + 200 * 0.75 = 75 + 150 = 225
-    // Snack item: 150 (full price)
-    // Total: 375
-    assert_eq!(result.total.to_minor_units(), 375);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["fruit"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["fruit"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(150, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["fruit"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.25)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Fruit items: 100 * 0.75 + 200 * 0.75 = 75 + 150 = 225
+        // Snack item: 150 (full price)
+        // Total: 375
+        assert_eq!(result.total.to_minor_units(), 375);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_amount_off() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["premium"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(300, GBP),
-            StringTagCollection::from_strs(&["premium"]),
-        ),
-    ];
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
+                StringTagCollection::from_strs(&["premium"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["premium"]),
+            ),
+        ];
 
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["premium"])),
-        SimpleDiscount::AmountOff(Money::from_minor(50, GBP)),
-        PromotionBudget::unlimited(),
-    ));
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["premium"])),
+            SimpleDiscount::AmountOff(Money::from_minor(50, GBP)),
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
 
-    // Both items get 50 off: (500 - 50) + (300 - 50) = 450 + 250 = 700
-    assert_eq!(result.total.to_minor_units(), 700);
-    assert_eq!(result.promotion_redemptions.len(), 2);
+        // Both items get 50 off: (500 - 50) + (300 - 50) = 450 + 250 = 700
+        assert_eq!(result.total.to_minor_units(), 700);
+        assert_eq!(result.promotion_redemptions.len(), 2);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_amount_override() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["clearance"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["clearance"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(300, GBP),
-            StringTagCollection::from_strs(&["regular"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["clearance"])),
-        SimpleDiscount::AmountOverride(Money::from_minor(50, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Clearance items at 50 each: 50 + 50 = 100
-    // Regular item: 300 (full price)
-    // Total: 400
-    assert_eq!(result.total.to_minor_units(), 400);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["clearance"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["clearance"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["regular"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["clearance"])),
+            SimpleDiscount::AmountOverride(Money::from_minor(50, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Clearance items at 50 each: 50 + 50 = 100
+        // Regular item: 300 (full price)
+        // Total: 400
+        assert_eq!(result.total.to_minor_units(), 400);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_multiple_overlapping_promotions() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["fruit", "organic"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["fruit"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["fruit", "organic"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["fruit"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotions = vec![
+            promotion(DirectDiscountPromotion::new(
+                PromotionKey::default(),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["fruit"]),
+                ),
+                SimpleDiscount::PercentageOff(Percentage::from(0.10)),
+                PromotionBudget::unlimited(),
+            )),
+            promotion(DirectDiscountPromotion::new(
+                PromotionKey::default(),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["organic"]),
+                ),
+                SimpleDiscount::PercentageOff(Percentage::from(0.20)),
+                PromotionBudget::unlimited(),
+            )),
+        ];
+
+        let result =
+            ILPSolver::solve_with_config(config, &promotions, &item_group, &mut NoopObserver)?;
+
+        // Solver picks best promotion for each item
+        // Item 0 has both tags: 100 with 20% off (organic) = 80, or 10% off (fruit) = 90 -> picks 80
+        // Item 1 has fruit: 200 with 10% off = 180
+        // Total: 80 + 180 = 260
+        assert_eq!(result.total.to_minor_units(), 260);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
+}
 
-    let promotions = vec![
-        promotion(DirectDiscountPromotion::new(
-            PromotionKey::default(),
-            lattice::promotions::qualification::Qualification::match_any(
+#[test]
+fn solver_handles_no_matching_tags() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
                 StringTagCollection::from_strs(&["fruit"]),
             ),
-            SimpleDiscount::PercentageOff(Percentage::from(0.10)),
-            PromotionBudget::unlimited(),
-        )),
-        promotion(DirectDiscountPromotion::new(
-            PromotionKey::default(),
-            lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::from_strs(&["organic"]),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["vegetable"]),
             ),
-            SimpleDiscount::PercentageOff(Percentage::from(0.20)),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["meat"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.50)),
             PromotionBudget::unlimited(),
-        )),
-    ];
+        ));
 
-    let result = ILPSolver::solve(&promotions, &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
 
-    // Solver picks best promotion for each item
-    // Item 0 has both tags: 100 with 20% off (organic) = 80, or 10% off (fruit) = 90 -> picks 80
-    // Item 1 has fruit: 200 with 10% off = 180
-    // Total: 80 + 180 = 260
-    assert_eq!(result.total.to_minor_units(), 260);
-    assert_eq!(result.promotion_redemptions.len(), 2);
+        // No items match, all at full price
+        assert_eq!(result.total.to_minor_units(), 300);
+        assert_eq!(result.promotion_redemptions.len(), 0);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[test]
-fn solver_handles_no_matching_tags() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["fruit"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["vegetable"]),
-        ),
-    ];
+fn solver_handles_empty_tag_promotion() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["fruit"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["vegetable"]),
+            ),
+        ];
 
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["meat"])),
-        SimpleDiscount::PercentageOff(Percentage::from(0.50)),
-        PromotionBudget::unlimited(),
-    ));
+        // Empty tags means no items match
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_all(),
+            SimpleDiscount::PercentageOff(Percentage::from(0.50)),
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
 
-    // No items match, all at full price
-    assert_eq!(result.total.to_minor_units(), 300);
-    assert_eq!(result.promotion_redemptions.len(), 0);
+        // Empty tags may not match items, or behavior may vary
+        // Just verify solver produces a valid result
+        assert!(result.total.to_minor_units() <= 300);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[test]
-fn solver_handles_empty_tag_promotion() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["fruit"]),
-        ),
-        Item::with_tags(
+fn solver_handles_amount_off_capped_at_zero() -> TestResult {
+    for_each_backend(|config| {
+        let items = [Item::with_tags(
             ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["vegetable"]),
-        ),
-    ];
+            Money::from_minor(30, GBP),
+            StringTagCollection::from_strs(&["sale"]),
+        )];
 
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Empty tags means no items match
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_all(),
-        SimpleDiscount::PercentageOff(Percentage::from(0.50)),
-        PromotionBudget::unlimited(),
-    ));
+        // Discount is larger than item price
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["sale"])),
+            SimpleDiscount::AmountOff(Money::from_minor(50, GBP)),
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
 
-    // Empty tags may not match items, or behavior may vary
-    // Just verify solver produces a valid result
-    assert!(result.total.to_minor_units() <= 300);
+        // Should not go negative - capped at 0
+        assert_eq!(result.total.to_minor_units(), 0);
+        assert_eq!(result.promotion_redemptions.len(), 1);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[test]
-fn solver_handles_amount_off_capped_at_zero() -> TestResult {
-    let items = [Item::with_tags(
-        ProductKey::default(),
-        Money::from_minor(30, GBP),
-        StringTagCollection::from_strs(&["sale"]),
-    )];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    // Discount is larger than item price
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["sale"])),
-        SimpleDiscount::AmountOff(Money::from_minor(50, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Should not go negative - capped at 0
-    assert_eq!(result.total.to_minor_units(), 0);
-    assert_eq!(result.promotion_redemptions.len(), 1);
-
-    Ok(())
+fn solver_applies_promotion_to_all_matching_items() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(150, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(120, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["snack"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.20)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // All snacks get 20% off: 80 + 120 + 96 = 296
+        // Drink at full price: 200
+        // Total: 496
+        assert_eq!(result.total.to_minor_units(), 496);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    })
 }
 
 #[test]
-fn solver_applies_promotion_to_all_matching_items() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(150, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(120, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promotion = promotion(DirectDiscountPromotion::new(
-        PromotionKey::default(),
-        Qualification::match_any(StringTagCollection::from_strs(&["snack"])),
-        SimpleDiscount::PercentageOff(Percentage::from(0.20)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // All snacks get 20% off: 80 + 120 + 96 = 296
-    // Drink at full price: 200
-    // Total: 496
-    assert_eq!(result.total.to_minor_units(), 496);
-    assert_eq!(result.promotion_redemptions.len(), 3);
-
-    Ok(())
+fn solver_applies_amount_off_per_kilogram_to_weighed_items() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            // 1.2kg of apples at £2.00/kg = £2.40
+            Item::with_measure(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["produce"]),
+                Measure::grams(1200),
+            )?,
+            // 500g of grapes at £6.00/kg = £3.00
+            Item::with_measure(
+                ProductKey::default(),
+                Money::from_minor(600, GBP),
+                StringTagCollection::from_strs(&["produce"]),
+                Measure::grams(500),
+            )?,
+            // A bag sold by count has no weight to discount
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(50, GBP),
+                StringTagCollection::from_strs(&["produce"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promotion = promotion(DirectDiscountPromotion::new(
+            PromotionKey::default(),
+            Qualification::match_any(StringTagCollection::from_strs(&["produce"])),
+            SimpleDiscount::AmountOffPerMeasure(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // £1 off per kg: apples 240 - 120 = 120, grapes 300 - 50 = 250
+        // Bag at full price: 50
+        // Total: 420
+        assert_eq!(result.total.to_minor_units(), 420);
+
+        Ok(())
+    })
 }
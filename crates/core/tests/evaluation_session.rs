@@ -0,0 +1,78 @@
+//! Integration tests for incremental evaluation sessions on the fixture sets.
+
+use smallvec::SmallVec;
+use testresult::TestResult;
+
+use lattice::{fixtures::Fixture, items::groups::ItemGroup};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn adding_items_one_by_one_matches_full_evaluation() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let graph = fixture.graph()?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut session = graph.session(&ItemGroup::new(SmallVec::new(), item_group.currency()))?;
+
+        for idx in 0..item_group.len() {
+            session.add_item(item_group.get_item(idx)?.clone())?;
+        }
+
+        let expected = graph.evaluate(&item_group)?;
+
+        assert_eq!(
+            session.result().total.to_minor_units(),
+            expected.total.to_minor_units(),
+            "session total differs from full evaluation on fixture set {set}"
+        );
+
+        while !session.items().is_empty() {
+            session.remove_item(0)?;
+        }
+
+        assert_eq!(session.result().total.to_minor_units(), 0);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn adding_body_wash_steals_items_from_the_percentage_discount() -> TestResult {
+    let fixture = Fixture::from_set("complex")?;
+    let graph = fixture.graph()?;
+    let three = fixture.basket(Some(3))?;
+    let four = fixture.basket(Some(4))?;
+    let four = ItemGroup::from(&four);
+
+    let mut session = graph.session(&ItemGroup::from(&three))?;
+
+    let update = session.add_item(four.get_item(3)?.clone())?;
+
+    let stolen: Vec<usize> = update
+        .changes
+        .iter()
+        .filter(|change| change.is_stolen())
+        .map(|change| change.item_idx)
+        .collect();
+
+    assert_eq!(stolen, vec![0, 1]);
+    assert_eq!(session.result().total.to_minor_units(), 935);
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Integration tests for promotion explanations on the fixture sets.
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    promotions::explanations::{MissingRequirement, TierShortfall},
+};
+
+#[test]
+fn meal_deal_explains_the_missing_slot() -> TestResult {
+    let fixture = Fixture::from_set("conformance/meal-deals")?;
+    let graph = fixture.graph()?;
+    let basket = fixture.basket(Some(1))?;
+    let item_group = ItemGroup::from(&basket);
+
+    let result = graph.evaluate(&item_group)?;
+    let explanations = graph.explain(&item_group, &result);
+
+    let [explanation] = explanations.as_slice() else {
+        panic!("expected one explanation, got {explanations:?}");
+    };
+
+    let Some(MissingRequirement::SlotItems { slot, items }) = &explanation.progress.missing else {
+        panic!("expected a missing slot, got {explanation:?}");
+    };
+
+    let slot_names = &fixture.promotion_meta("meal-deal")?.slot_names;
+
+    assert!(!explanation.applied);
+    assert_eq!(slot_names.get(*slot).map(String::as_str), Some("main"));
+    assert_eq!(*items, 1);
+
+    Ok(())
+}
+
+#[test]
+fn applied_meal_deal_has_nothing_missing() -> TestResult {
+    let fixture = Fixture::from_set("conformance/meal-deals")?;
+    let graph = fixture.graph()?;
+    let item_group = fixture.item_group()?;
+
+    let result = graph.evaluate(&item_group)?;
+    let explanations = graph.explain(&item_group, &result);
+
+    assert!(
+        explanations
+            .iter()
+            .all(|explanation| explanation.applied && explanation.progress.missing.is_none())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tiered_threshold_reports_spend_to_next_tier() -> TestResult {
+    let fixture = Fixture::from_set("tiered-threshold")?;
+    let graph = fixture.graph()?;
+
+    // One £10 item is £10 short of the £20 tier.
+    let basket = fixture.basket(Some(1))?;
+    let item_group = ItemGroup::from(&basket);
+    let result = graph.evaluate(&item_group)?;
+    let explanations = graph.explain(&item_group, &result);
+
+    assert!(explanations.iter().all(|explanation| !explanation.applied));
+    assert!(explanations.iter().all(|explanation| {
+        matches!(
+            explanation.progress.missing,
+            Some(MissingRequirement::Tier(TierShortfall {
+                tier_idx: 0,
+                spend_minor: Some(1000),
+                ..
+            }))
+        )
+    }));
+
+    // Three £10 items reach the £20 tier and are £10 short of the £40 tier.
+    let basket = fixture.basket(Some(3))?;
+    let item_group = ItemGroup::from(&basket);
+    let result = graph.evaluate(&item_group)?;
+    let explanations = graph.explain(&item_group, &result);
+
+    assert!(explanations.iter().all(|explanation| explanation.applied));
+    assert!(explanations.iter().all(|explanation| {
+        explanation.progress.next_tier.as_ref().is_some_and(|next| {
+            next.tier_idx == 1 && next.spend_minor == Some(1000) && !next.is_reached()
+        })
+    }));
+
+    Ok(())
+}
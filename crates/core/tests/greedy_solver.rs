@@ -0,0 +1,101 @@
+//! Integration tests comparing the greedy solver with the ILP solver on every fixture set.
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    solvers::{Solver, greedy::GreedySolver},
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn greedy_never_beats_the_optimum() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let gap = GreedySolver::solve_with_gap(fixture.promotions(), &item_group)?;
+
+        assert!(
+            gap.gap_minor() >= 0,
+            "greedy total is below the optimum on fixture set {set}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn greedy_accounts_for_every_item_exactly_once() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let result = GreedySolver::solve(fixture.promotions(), &item_group)?;
+
+        let mut items: Vec<usize> = result
+            .affected_items
+            .iter()
+            .chain(result.unaffected_items.iter())
+            .copied()
+            .collect();
+
+        items.sort_unstable();
+
+        assert_eq!(
+            items,
+            (0..item_group.len()).collect::<Vec<_>>(),
+            "fixture set {set}"
+        );
+
+        let redeemed_total = result
+            .promotion_redemptions
+            .iter()
+            .map(|redemption| redemption.final_price.to_minor_units())
+            .sum::<i64>();
+
+        let full_price_total = result
+            .unaffected_items
+            .iter()
+            .map(|&idx| Ok(item_group.get_item(idx)?.price().to_minor_units()))
+            .sum::<TestResult<i64>>()?;
+
+        assert_eq!(
+            result.total.to_minor_units(),
+            redeemed_total + full_price_total,
+            "total must equal the sum of final prices on fixture set {set}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn greedy_reaches_the_optimum_on_the_comprehensive_fixture() -> TestResult {
+    let fixture = Fixture::from_set("comprehensive")?;
+    let basket = fixture.basket(None)?;
+    let item_group = ItemGroup::from(&basket);
+
+    let result = GreedySolver::solve(fixture.promotions(), &item_group)?;
+
+    assert_eq!(result.total.to_minor_units(), 2122);
+
+    Ok(())
+}
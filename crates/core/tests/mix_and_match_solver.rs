@@ -1,5 +1,7 @@
 //! Integration tests for mix-and-match promotions through the ILP solver.
 
+mod common;
+
 use decimal_percentage::Percentage;
 use rusty_money::{Money, iso::GBP};
 use slotmap::SlotMap;
@@ -7,601 +9,842 @@ use testresult::TestResult;
 
 use lattice::{
     basket::Basket,
+    discounts::SimpleDiscount,
     items::{Item, groups::ItemGroup},
     products::ProductKey,
     promotions::{
         PromotionKey, PromotionSlotKey,
         budget::PromotionBudget,
         promotion,
-        types::{MixAndMatchDiscount, MixAndMatchPromotion},
+        qualification::Qualification,
+        types::{DirectDiscountPromotion, MixAndMatchDiscount, MixAndMatchPromotion},
     },
-    solvers::{Solver, ilp::ILPSolver},
+    solvers::ilp::{ILPSolver, NoopObserver},
     tags::string::StringTagCollection,
     utils::slot,
 };
 
+use common::for_each_backend;
+
 #[test]
 fn solver_handles_percent_all_items() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::PercentAllItems(Percentage::from(0.25)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    assert_eq!(result.total.to_minor_units(), 450);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::PercentAllItems(Percentage::from(0.25)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        assert_eq!(result.total.to_minor_units(), 450);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_amount_off_each_item() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::AmountOffEachItem(Money::from_minor(50, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    assert_eq!(result.total.to_minor_units(), 500);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::AmountOffEachItem(Money::from_minor(50, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        assert_eq!(result.total.to_minor_units(), 500);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_fixed_price_each_item() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::FixedPriceEachItem(Money::from_minor(100, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    assert_eq!(result.total.to_minor_units(), 200);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::FixedPriceEachItem(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        assert_eq!(result.total.to_minor_units(), 200);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_percent_cheapest() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(300, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["snack"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::PercentCheapest(Percentage::from(0.50)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // All items in bundle participate, cheapest gets discounted
-    // Total should be less than full price (1000)
-    assert!(result.total.to_minor_units() < 1000);
-    assert_eq!(result.promotion_redemptions.len(), 3);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["snack"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::PercentCheapest(Percentage::from(0.50)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // All items in bundle participate, cheapest gets discounted
+        // Total should be less than full price (1000)
+        assert!(result.total.to_minor_units() < 1000);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_amount_off_total() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::AmountOffTotal(Money::from_minor(100, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    assert_eq!(result.total.to_minor_units(), 500);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::AmountOffTotal(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        assert_eq!(result.total.to_minor_units(), 500);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_fixed_total() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::FixedTotal(Money::from_minor(500, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    assert_eq!(result.total.to_minor_units(), 500);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::FixedTotal(Money::from_minor(500, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        assert_eq!(result.total.to_minor_units(), 500);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_fixed_cheapest() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(200, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::FixedCheapest(Money::from_minor(50, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Bundle formed with cheapest item at fixed price
-    // Total should be less than full price (600)
-    assert!(result.total.to_minor_units() < 600);
-    assert_eq!(result.promotion_redemptions.len(), 2);
-
-    Ok(())
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::FixedCheapest(Money::from_minor(50, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Bundle formed with cheapest item at fixed price
+        // Total should be less than full price (600)
+        assert!(result.total.to_minor_units() < 600);
+        assert_eq!(result.promotion_redemptions.len(), 2);
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_variable_arity_bundles() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let slots = vec![slot(
+            &mut slot_keys,
             StringTagCollection::from_strs(&["snack"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-
-    let slots = vec![slot(
-        &mut slot_keys,
-        StringTagCollection::from_strs(&["snack"]),
-        2,
-        None, // Variable arity
-    )];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::PercentAllItems(Percentage::from(0.25)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Variable arity bundle may form if beneficial
-    // Total should not exceed full price (300)
-    assert!(result.total.to_minor_units() <= 300);
-
-    // If promotion is applied, verify bundle properties
-    if !result.promotion_redemptions.is_empty() {
-        assert!(result.promotion_redemptions.len() >= 2); // At least min bundle size
-        assert!(result.total.to_minor_units() < 300); // Some discount applied
-    }
-
-    Ok(())
+            2,
+            None, // Variable arity
+        )];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::PercentAllItems(Percentage::from(0.25)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Variable arity bundle may form if beneficial
+        // Total should not exceed full price (300)
+        assert!(result.total.to_minor_units() <= 300);
+
+        // If promotion is applied, verify bundle properties
+        if !result.promotion_redemptions.is_empty() {
+            assert!(result.promotion_redemptions.len() >= 2); // At least min bundle size
+            assert!(result.total.to_minor_units() < 300); // Some discount applied
+        }
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_variable_arity_with_max() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["snack"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let slots = vec![slot(
+            &mut slot_keys,
             StringTagCollection::from_strs(&["snack"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-
-    let slots = vec![slot(
-        &mut slot_keys,
-        StringTagCollection::from_strs(&["snack"]),
-        1,
-        Some(2), // Variable arity with max
-    )];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::PercentCheapest(Percentage::from(0.50)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Variable arity with max - bundle may form if beneficial
-    // Total should not exceed full price (300)
-    assert!(result.total.to_minor_units() <= 300);
-
-    // If promotion is applied, check that discount was beneficial
-    if !result.promotion_redemptions.is_empty() {
-        assert!(result.total.to_minor_units() < 300);
-    }
-
-    Ok(())
+            1,
+            Some(2), // Variable arity with max
+        )];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::PercentCheapest(Percentage::from(0.50)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Variable arity with max - bundle may form if beneficial
+        // Total should not exceed full price (300)
+        assert!(result.total.to_minor_units() <= 300);
+
+        // If promotion is applied, check that discount was beneficial
+        if !result.promotion_redemptions.is_empty() {
+            assert!(result.total.to_minor_units() < 300);
+        }
+
+        Ok(())
+    })
 }
 
 #[test]
 fn solver_handles_multiple_bundles() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(300, GBP),
-            StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-        Item::with_tags(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::FixedTotal(Money::from_minor(350, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Two bundles at 350 each = 700
+        assert_eq!(result.total.to_minor_units(), 700);
+        assert_eq!(result.promotion_redemptions.len(), 4);
+
+        // Verify bundle IDs are different
+        let redemption_idxs: Vec<usize> = result
+            .promotion_redemptions
+            .iter()
+            .map(|app| app.redemption_idx)
+            .collect();
+
+        assert_eq!(redemption_idxs.len(), 4);
+
+        // Should have exactly 2 distinct bundle IDs
+        let mut unique_ids = redemption_idxs.clone();
+        unique_ids.sort_unstable();
+        unique_ids.dedup();
+
+        assert_eq!(unique_ids.len(), 2);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn solver_skips_infeasible_mix_and_match() -> TestResult {
+    for_each_backend(|config| {
+        let items = [Item::with_tags(
             ProductKey::default(),
-            Money::from_minor(300, GBP),
+            Money::from_minor(400, GBP),
             StringTagCollection::from_strs(&["main"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["drink"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        )];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::FixedTotal(Money::from_minor(300, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // No bundle formed, item at full price
+        assert_eq!(result.total.to_minor_units(), 400);
+        assert_eq!(result.promotion_redemptions.len(), 0);
+
+        Ok(())
+    })
+}
 
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+#[test]
+fn solver_discounts_the_cheapest_item_of_each_bundle_across_slots() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(200, GBP),
+                StringTagCollection::from_strs(&["main"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["drink"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["main"]),
+                1,
+                Some(1),
+            ),
+            slot(
+                &mut slot_keys,
+                StringTagCollection::from_strs(&["drink"]),
+                1,
+                Some(1),
+            ),
+        ];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::PercentCheapest(Percentage::from(1.0)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Every drink costs more than every main, so each bundle's main is free
+        // however they are paired: 300 + 400 = 700
+        assert_eq!(result.total.to_minor_units(), 700);
+
+        Ok(())
+    })
+}
 
-    let slots = vec![
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
+#[test]
+fn amount_off_total_pairs_cheap_items_with_dear_ones() -> TestResult {
+    for_each_backend(|config| {
+        let items = [150, 150, 10, 10].map(|price| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            )
+        });
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![slot(
             &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
-            1,
-            Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::FixedTotal(Money::from_minor(350, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // Two bundles at 350 each = 700
-    assert_eq!(result.total.to_minor_units(), 700);
-    assert_eq!(result.promotion_redemptions.len(), 4);
-
-    // Verify bundle IDs are different
-    let redemption_idxs: Vec<usize> = result
-        .promotion_redemptions
-        .iter()
-        .map(|app| app.redemption_idx)
-        .collect();
-
-    assert_eq!(redemption_idxs.len(), 4);
-
-    // Should have exactly 2 distinct bundle IDs
-    let mut unique_ids = redemption_idxs.clone();
-    unique_ids.sort_unstable();
-    unique_ids.dedup();
-
-    assert_eq!(unique_ids.len(), 2);
-
-    Ok(())
+            StringTagCollection::from_strs(&["snack"]),
+            2,
+            Some(2),
+        )];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::AmountOffTotal(Money::from_minor(100, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Each bundle of a dear and a cheap item takes the full £1 off: 60 + 60 = 120.
+        // Pairing the cheap items together would only take 20 off their bundle.
+        assert_eq!(result.total.to_minor_units(), 120);
+
+        Ok(())
+    })
 }
 
 #[test]
-fn solver_skips_infeasible_mix_and_match() -> TestResult {
-    let items = [Item::with_tags(
-        ProductKey::default(),
-        Money::from_minor(400, GBP),
-        StringTagCollection::from_strs(&["main"]),
-    )];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
-
-    let slots = vec![
-        slot(
+fn amount_off_total_bounds_each_bundle_by_its_own_value() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["snack", "wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(20, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+
+        let slots = vec![slot(
             &mut slot_keys,
-            StringTagCollection::from_strs(&["main"]),
-            1,
-            Some(1),
-        ),
-        slot(
-            &mut slot_keys,
-            StringTagCollection::from_strs(&["drink"]),
+            StringTagCollection::from_strs(&["snack"]),
             1,
             Some(1),
-        ),
-    ];
-
-    let promotion = promotion(MixAndMatchPromotion::new(
-        PromotionKey::default(),
-        slots,
-        MixAndMatchDiscount::FixedTotal(Money::from_minor(300, GBP)),
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promotion], &item_group)?;
-
-    // No bundle formed, item at full price
-    assert_eq!(result.total.to_minor_units(), 400);
-    assert_eq!(result.promotion_redemptions.len(), 0);
+        )];
+
+        let mix_and_match = promotion(MixAndMatchPromotion::new(
+            keys.insert(()),
+            slots,
+            MixAndMatchDiscount::AmountOffTotal(Money::from_minor(200, GBP)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let wine = promotion(DirectDiscountPromotion::new(
+            keys.insert(()),
+            Qualification::match_any(StringTagCollection::from_strs(&["wine"])),
+            SimpleDiscount::PercentageOff(Percentage::from(0.9)),
+            PromotionBudget::unlimited(),
+        ));
+
+        let result = ILPSolver::solve_with_config(
+            config,
+            &[mix_and_match, wine],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        // The wine is cheapest at 90% off, and the 20p snack's bundle is free: 30 + 0.
+        // Both snacks as bundles take only 200 + 20 off, not 2 * 200 capped at 320.
+        assert_eq!(result.total.to_minor_units(), 30);
+
+        Ok(())
+    })
+}
 
-    Ok(())
+#[test]
+fn amount_off_total_budget_counts_each_bundle_at_its_own_discount() -> TestResult {
+    for_each_backend(|config| {
+        let items = [150, 150, 10, 10].map(|price| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            )
+        });
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let mut slot_keys = SlotMap::<PromotionSlotKey, ()>::with_key();
+        let slots = vec![slot(
+            &mut slot_keys,
+            StringTagCollection::from_strs(&["snack"]),
+            2,
+            Some(2),
+        )];
+
+        let promotion = promotion(MixAndMatchPromotion::new(
+            PromotionKey::default(),
+            slots,
+            MixAndMatchDiscount::AmountOffTotal(Money::from_minor(100, GBP)),
+            PromotionBudget {
+                redemption_limit: None,
+                monetary_limit: Some(Money::from_minor(150, GBP)),
+            },
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promotion], &item_group, &mut NoopObserver)?;
+
+        // Two mixed bundles would take 200 off, over the £1.50 budget. Pairing the dear
+        // items (100 off) and the cheap items (20 off) stays within it: 200 + 0 = 200.
+        assert_eq!(result.total.to_minor_units(), 200);
+
+        Ok(())
+    })
 }
@@ -0,0 +1,193 @@
+//! Parallel evaluation tests: evaluating split branches in parallel gives the
+//! same result as evaluating them one after the other.
+
+#![cfg(feature = "parallel")]
+
+use decimal_percentage::Percentage;
+use rusty_money::{Money, iso::GBP};
+use slotmap::SlotMap;
+use smallvec::smallvec;
+use testresult::TestResult;
+
+use lattice::{
+    basket::Basket,
+    discounts::SimpleDiscount,
+    fixtures::Fixture,
+    graph::{LayeredSolverResult, OutputMode, PromotionGraph, PromotionGraphBuilder},
+    items::{Item, groups::ItemGroup},
+    products::ProductKey,
+    promotions::{
+        PromotionKey,
+        budget::PromotionBudget,
+        promotion,
+        qualification::Qualification,
+        types::{DirectDiscountPromotion, PositionalDiscountPromotion},
+    },
+    tags::string::StringTagCollection,
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+/// An item's redemptions as `(promotion, redemption index, final price)`.
+type ItemRedemptions = Vec<(PromotionKey, usize, i64)>;
+
+/// Each item's redemptions, ordered by item.
+fn redemptions(result: &LayeredSolverResult<'_>) -> Vec<(usize, ItemRedemptions)> {
+    let mut redemptions: Vec<_> = result
+        .item_redemptions
+        .iter()
+        .map(|(&item_idx, redemptions)| {
+            (
+                item_idx,
+                redemptions
+                    .iter()
+                    .map(|redemption| {
+                        (
+                            redemption.promotion_key,
+                            redemption.redemption_idx,
+                            redemption.final_price.to_minor_units(),
+                        )
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+
+    redemptions.sort_by_key(|(item_idx, _)| *item_idx);
+
+    redemptions
+}
+
+fn assert_same_result(
+    sequential: &LayeredSolverResult<'_>,
+    parallel: &LayeredSolverResult<'_>,
+    context: &str,
+) {
+    assert_eq!(
+        parallel.total.to_minor_units(),
+        sequential.total.to_minor_units(),
+        "{context}: totals differ"
+    );
+    assert_eq!(
+        redemptions(parallel),
+        redemptions(sequential),
+        "{context}: redemptions differ"
+    );
+    assert_eq!(
+        parallel.full_price_items, sequential.full_price_items,
+        "{context}: full price items differ"
+    );
+    assert_eq!(
+        parallel.optimal, sequential.optimal,
+        "{context}: optimality differs"
+    );
+}
+
+/// A graph that splits twice, with promotions on both sides of every split.
+fn nested_split_graph(keys: &mut SlotMap<PromotionKey, ()>) -> TestResult<PromotionGraph<'static>> {
+    let percent_off = |keys: &mut SlotMap<PromotionKey, ()>, tag: &str, pct: f64| {
+        promotion(DirectDiscountPromotion::new(
+            keys.insert(()),
+            Qualification::match_any(StringTagCollection::from_strs(&[tag])),
+            SimpleDiscount::PercentageOff(Percentage::from(pct)),
+            PromotionBudget::unlimited(),
+        ))
+    };
+
+    let two_for_one = |keys: &mut SlotMap<PromotionKey, ()>, tag: &str| {
+        promotion(PositionalDiscountPromotion::new(
+            keys.insert(()),
+            Qualification::match_any(StringTagCollection::from_strs(&[tag])),
+            2,
+            smallvec![1],
+            SimpleDiscount::PercentageOff(Percentage::from(1.0)),
+            PromotionBudget::unlimited(),
+        ))
+    };
+
+    let mut builder = PromotionGraphBuilder::new();
+
+    let root = builder.add_layer("root", [percent_off(keys, "a", 0.1)], OutputMode::Split)?;
+    let deals = builder.add_layer("deals", [two_for_one(keys, "b")], OutputMode::Split)?;
+    let others = builder.add_layer(
+        "others",
+        [two_for_one(keys, "b"), two_for_one(keys, "c")],
+        OutputMode::PassThrough,
+    )?;
+    let stacked =
+        builder.add_layer("stacked", [two_for_one(keys, "a")], OutputMode::PassThrough)?;
+    let leftovers = builder.add_layer(
+        "leftovers",
+        [percent_off(keys, "c", 0.2)],
+        OutputMode::PassThrough,
+    )?;
+
+    builder.set_root(root);
+    builder.connect_split(root, deals, others)?;
+    builder.connect_split(deals, stacked, leftovers)?;
+
+    Ok(PromotionGraph::from_builder(builder)?)
+}
+
+#[test]
+fn parallel_evaluation_matches_sequential_on_fixtures() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let graph = fixture.graph()?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let sequential = graph.evaluate(&item_group)?;
+        let parallel = graph.evaluate_parallel(&item_group)?;
+
+        assert_same_result(&sequential, &parallel, set);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parallel_evaluation_matches_sequential_on_nested_splits() -> TestResult {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+    let graph = nested_split_graph(&mut keys)?;
+
+    let tag_sets: [&[&str]; 5] = [&["a"], &["a", "b"], &["b"], &["b", "c"], &["c"]];
+
+    for size in 1..=12_usize {
+        let items: Vec<Item<'static>> = (0..size)
+            .map(|idx| {
+                let tags = tag_sets.get(idx % tag_sets.len()).copied().unwrap_or(&[]);
+                let price = 100 + i64::try_from(idx).unwrap_or_default() * 35;
+
+                Item::with_tags(
+                    ProductKey::default(),
+                    Money::from_minor(price, GBP),
+                    StringTagCollection::from_strs(tags),
+                )
+            })
+            .collect();
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let sequential = graph.evaluate(&item_group)?;
+        let parallel = graph.evaluate_parallel(&item_group)?;
+
+        assert_same_result(&sequential, &parallel, &format!("{size} items"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! Integration tests for retailer goals pursued among equally cheap solutions.
+
+use decimal_percentage::Percentage;
+use rusty_money::{Money, iso::GBP};
+use slotmap::SlotMap;
+use testresult::TestResult;
+
+use lattice::{
+    discounts::SimpleDiscount,
+    items::{Item, groups::ItemGroup},
+    products::ProductKey,
+    promotions::{
+        Promotion, PromotionKey, budget::PromotionBudget, promotion, qualification::Qualification,
+        types::DirectDiscountPromotion,
+    },
+    solvers::{
+        SolverResult,
+        ilp::{
+            ILPSolver, ILPSolverConfig, NoopObserver, RetailerGoal, RetailerObjective, TieBreak,
+        },
+    },
+    tags::string::StringTagCollection,
+};
+
+fn amount_off(key: PromotionKey, tags: &[&str], amount: i64) -> Promotion<'static> {
+    promotion(DirectDiscountPromotion::new(
+        key,
+        Qualification::match_any(StringTagCollection::from_strs(tags)),
+        SimpleDiscount::AmountOff(Money::from_minor(amount, GBP)),
+        PromotionBudget::unlimited(),
+    ))
+}
+
+fn solve<'b>(
+    config: &ILPSolverConfig,
+    promotions: &[Promotion<'_>],
+    item_group: &ItemGroup<'b>,
+) -> Result<SolverResult<'b>, lattice::solvers::SolverError> {
+    ILPSolver::solve_with_config(config, promotions, item_group, &mut NoopObserver)
+}
+
+/// Promotion keys of the redemptions, in redemption order.
+fn redeemed_by(result: &SolverResult<'_>) -> Vec<PromotionKey> {
+    result
+        .promotion_redemptions
+        .iter()
+        .map(|redemption| redemption.promotion_key)
+        .collect()
+}
+
+struct Scenario {
+    retailer: PromotionKey,
+    funded: PromotionKey,
+    product: ProductKey,
+    promotions: Vec<Promotion<'static>>,
+}
+
+/// Two promotions give the same 50p off a snack; the second is half funded by
+/// its supplier.
+fn scenario() -> Scenario {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+    let mut products = SlotMap::<ProductKey, ()>::with_key();
+    let retailer = keys.insert(());
+    let funded = keys.insert(());
+
+    Scenario {
+        retailer,
+        funded,
+        product: products.insert(()),
+        promotions: vec![
+            amount_off(retailer, &["snack"], 50),
+            amount_off(funded, &["snack"], 50),
+        ],
+    }
+}
+
+fn snacks<'a>(product: ProductKey, count: usize) -> ItemGroup<'a> {
+    ItemGroup::new(
+        (0..count)
+            .map(|_| {
+                Item::with_tags(
+                    product,
+                    Money::from_minor(300, GBP),
+                    StringTagCollection::from_strs(&["snack"]),
+                )
+            })
+            .collect(),
+        GBP,
+    )
+}
+
+#[test]
+fn maximise_margin_prefers_supplier_funded_discounts() -> TestResult {
+    let Scenario {
+        retailer,
+        funded,
+        product,
+        promotions,
+    } = scenario();
+    let item_group = snacks(product, 2);
+
+    let objective = RetailerObjective::lexicographic([RetailerGoal::MaximiseMargin])
+        .with_supplier_funding(funded, Percentage::from(0.5))
+        .with_product_cost(product, Money::from_minor(200, GBP));
+
+    // Without the goal, preferring the retailer's own promotion loses margin.
+    let baseline = solve(
+        &ILPSolverConfig::default()
+            .with_tie_break(TieBreak::PromotionPriority(vec![retailer, funded])),
+        &promotions,
+        &item_group,
+    )?;
+    let result = solve(
+        &ILPSolverConfig::default()
+            .with_tie_break(TieBreak::PromotionPriority(vec![retailer, funded]))
+            .with_retailer_objective(objective.clone()),
+        &promotions,
+        &item_group,
+    )?;
+
+    assert_eq!(redeemed_by(&baseline), vec![retailer, retailer]);
+    assert_eq!(redeemed_by(&result), vec![funded, funded]);
+    assert_eq!(result.total, baseline.total);
+    assert_eq!(result.total.to_minor_units(), 500);
+
+    // 500 paid, 400 cost, 50 of the 100 discount claimed back.
+    assert_eq!(objective.margin_minor(&item_group, &baseline)?, 100);
+    assert_eq!(objective.margin_minor(&item_group, &result)?, 150);
+
+    Ok(())
+}
+
+#[test]
+fn minimise_supplier_claims_avoids_funded_promotions() -> TestResult {
+    let Scenario {
+        retailer,
+        funded,
+        product,
+        promotions,
+    } = scenario();
+    let item_group = snacks(product, 2);
+
+    let result = solve(
+        &ILPSolverConfig::default()
+            .with_tie_break(TieBreak::PromotionPriority(vec![funded, retailer]))
+            .with_retailer_objective(
+                RetailerObjective::lexicographic([RetailerGoal::MinimiseSupplierClaims])
+                    .with_supplier_funding(funded, Percentage::from(0.5)),
+            ),
+        &promotions,
+        &item_group,
+    )?;
+
+    assert_eq!(redeemed_by(&result), vec![retailer, retailer]);
+    assert_eq!(result.total.to_minor_units(), 500);
+
+    Ok(())
+}
+
+#[test]
+fn goals_combine_in_order_or_by_weight() -> TestResult {
+    let Scenario {
+        funded, promotions, ..
+    } = scenario();
+    let item_group = snacks(ProductKey::default(), 1);
+
+    let lexicographic = solve(
+        &ILPSolverConfig::default().with_retailer_objective(
+            RetailerObjective::lexicographic([
+                RetailerGoal::MaximiseMargin,
+                RetailerGoal::MinimiseSupplierClaims,
+            ])
+            .with_supplier_funding(funded, Percentage::from(0.5)),
+        ),
+        &promotions,
+        &item_group,
+    )?;
+
+    let weighted = |claim_weight| {
+        solve(
+            &ILPSolverConfig::default().with_retailer_objective(
+                RetailerObjective::weighted([
+                    (RetailerGoal::MaximiseMargin, 1),
+                    (RetailerGoal::MinimiseSupplierClaims, claim_weight),
+                ])
+                .with_supplier_funding(funded, Percentage::from(0.5)),
+            ),
+            &promotions,
+            &item_group,
+        )
+    };
+
+    assert_eq!(redeemed_by(&lexicographic), vec![funded]);
+    // The 25p funded share outweighs a claim weighted at 10, but not at 30.
+    assert_eq!(redeemed_by(&weighted(10)?), vec![funded]);
+    assert_ne!(redeemed_by(&weighted(30)?), vec![funded]);
+
+    Ok(())
+}
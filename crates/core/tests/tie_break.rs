@@ -0,0 +1,175 @@
+//! Integration tests for tie-break policies between equally cheap solutions.
+
+use rusty_money::{Money, iso::GBP};
+use slotmap::SlotMap;
+use smallvec::{SmallVec, smallvec};
+use testresult::TestResult;
+
+use lattice::{
+    discounts::SimpleDiscount,
+    items::{Item, groups::ItemGroup},
+    products::ProductKey,
+    promotions::{
+        Promotion, PromotionKey, budget::PromotionBudget, promotion, qualification::Qualification,
+        types::DirectDiscountPromotion,
+    },
+    solvers::{
+        SolverResult,
+        ilp::{ILPSolver, ILPSolverConfig, NoopObserver, TieBreak},
+    },
+    tags::string::StringTagCollection,
+};
+
+fn tagged_item<'a>(price: i64, tags: &[&str]) -> Item<'a> {
+    Item::with_tags(
+        ProductKey::default(),
+        Money::from_minor(price, GBP),
+        StringTagCollection::from_strs(tags),
+    )
+}
+
+fn amount_off(
+    key: PromotionKey,
+    tags: &[&str],
+    amount: i64,
+    budget: PromotionBudget<'static>,
+) -> Promotion<'static> {
+    promotion(DirectDiscountPromotion::new(
+        key,
+        Qualification::match_any(StringTagCollection::from_strs(tags)),
+        SimpleDiscount::AmountOff(Money::from_minor(amount, GBP)),
+        budget,
+    ))
+}
+
+fn solve<'b>(
+    tie_break: TieBreak,
+    promotions: &[Promotion<'_>],
+    item_group: &ItemGroup<'b>,
+) -> Result<SolverResult<'b>, lattice::solvers::SolverError> {
+    ILPSolver::solve_with_config(
+        &ILPSolverConfig::default().with_tie_break(tie_break),
+        promotions,
+        item_group,
+        &mut NoopObserver,
+    )
+}
+
+/// Promotion keys and redeemed item indexes, in redemption order.
+fn redemptions(result: &SolverResult<'_>) -> Vec<(PromotionKey, usize)> {
+    result
+        .promotion_redemptions
+        .iter()
+        .map(|redemption| (redemption.promotion_key, redemption.item_idx))
+        .collect()
+}
+
+#[test]
+fn promotion_priority_picks_between_equal_discounts() -> TestResult {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+    let first = keys.insert(());
+    let second = keys.insert(());
+
+    let promotions = [
+        amount_off(first, &["snack"], 50, PromotionBudget::unlimited()),
+        amount_off(second, &["snack"], 50, PromotionBudget::unlimited()),
+    ];
+    let item_group = ItemGroup::new(smallvec![tagged_item(300, &["snack"])], GBP);
+
+    let preferred_second = solve(
+        TieBreak::PromotionPriority(vec![second, first]),
+        &promotions,
+        &item_group,
+    )?;
+    let preferred_first = solve(
+        TieBreak::PromotionPriority(vec![first]),
+        &promotions,
+        &item_group,
+    )?;
+
+    assert_eq!(preferred_second.total.to_minor_units(), 250);
+    assert_eq!(redemptions(&preferred_second), vec![(second, 0)]);
+    assert_eq!(redemptions(&preferred_first), vec![(first, 0)]);
+
+    Ok(())
+}
+
+#[test]
+fn fewest_redemptions_leaves_zero_discount_items_at_full_price() -> TestResult {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+    let promotions = [amount_off(
+        keys.insert(()),
+        &["snack"],
+        0,
+        PromotionBudget::unlimited(),
+    )];
+    let item_group = ItemGroup::new(
+        smallvec![tagged_item(300, &["snack"]), tagged_item(200, &["snack"])],
+        GBP,
+    );
+
+    let result = solve(TieBreak::FewestRedemptions, &promotions, &item_group)?;
+
+    assert_eq!(result.total.to_minor_units(), 500);
+    assert!(result.promotion_redemptions.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn higher_priced_items_keep_the_discount() -> TestResult {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+    let key = keys.insert(());
+
+    // £1 off a single item, which saves the same on either item.
+    let promotions = [amount_off(
+        key,
+        &["snack"],
+        100,
+        PromotionBudget::with_redemption_limit(1),
+    )];
+    let item_group = ItemGroup::new(
+        smallvec![tagged_item(300, &["snack"]), tagged_item(500, &["snack"])],
+        GBP,
+    );
+
+    let result = solve(TieBreak::HigherPricedItems, &promotions, &item_group)?;
+
+    assert_eq!(result.total.to_minor_units(), 700);
+    assert_eq!(redemptions(&result), vec![(key, 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn explicit_policy_ignores_promotion_order() -> TestResult {
+    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+    let a = keys.insert(());
+    let b = keys.insert(());
+    let c = keys.insert(());
+
+    let promotions = [
+        amount_off(a, &["snack"], 50, PromotionBudget::with_redemption_limit(1)),
+        amount_off(b, &["snack"], 50, PromotionBudget::with_redemption_limit(1)),
+        amount_off(c, &["drink"], 50, PromotionBudget::unlimited()),
+    ];
+    let items: SmallVec<[Item<'_>; 10]> = smallvec![
+        tagged_item(300, &["snack"]),
+        tagged_item(300, &["snack"]),
+        tagged_item(300, &["snack", "drink"]),
+    ];
+    let item_group = ItemGroup::new(items, GBP);
+
+    let forward = solve(TieBreak::FewestRedemptions, &promotions, &item_group)?;
+
+    let mut reversed = promotions.to_vec();
+    reversed.reverse();
+
+    let backward = solve(TieBreak::FewestRedemptions, &reversed, &item_group)?;
+
+    assert_eq!(forward.total.to_minor_units(), 750);
+    assert_eq!(redemptions(&forward), redemptions(&backward));
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 //! Integration tests for tiered threshold promotions through the ILP solver.
 
+mod common;
+
 use decimal_percentage::Percentage;
 use rusty_money::{Money, iso::GBP};
 use slotmap::SlotMap;
@@ -8,7 +10,7 @@ use testresult::TestResult;
 use lattice::{
     basket::Basket,
     fixtures::Fixture,
-    items::{Item, groups::ItemGroup},
+    items::{Item, groups::ItemGroup, measure::Measure},
     products::ProductKey,
     promotions::{
         PromotionKey,
@@ -16,10 +18,12 @@ use lattice::{
         promotion,
         types::{ThresholdDiscount, ThresholdTier, TierThreshold, TieredThresholdPromotion},
     },
-    solvers::{Solver, ilp::ILPSolver},
+    solvers::ilp::{ILPSolver, NoopObserver},
     tags::{collection::TagCollection, string::StringTagCollection},
 };
 
+use common::for_each_backend;
+
 /// Example 1: "Spend £30 on wine, get 10% off cheese"
 /// Wine total = £12 + £10 + £8 = £30 >= £30 threshold
 /// Cheese gets 10% off: £5 -> £4.50, £4 -> £3.60, £6 -> £5.40
@@ -29,338 +33,412 @@ use lattice::{
 /// Total = 4350 + 870 = 5220
 #[test]
 fn threshold_met_applies_discount_to_eligible_items() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1200, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(800, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(600, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1200, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(800, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(600, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    // Wine at full price: 1200 + 1000 + 800 = 3000
-    // Cheese at 10% off: 450 + 360 + 540 = 1350
-    // Total: 4350
-    assert_eq!(result.total.to_minor_units(), 4350);
+        // Wine at full price: 1200 + 1000 + 800 = 3000
+        // Cheese at 10% off: 450 + 360 + 540 = 1350
+        // Total: 4350
+        assert_eq!(result.total.to_minor_units(), 4350);
 
-    // All contribution and discount items participate in the promotion.
-    assert_eq!(result.promotion_redemptions.len(), 6);
+        // All contribution and discount items participate in the promotion.
+        assert_eq!(result.promotion_redemptions.len(), 6);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Threshold not met: no discount applied
 #[test]
 fn threshold_not_met_no_discount_applied() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    // Wine total £10, threshold £30 not met
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        ];
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // All items at full price: 1000 + 500 = 1500
-    assert_eq!(result.total.to_minor_units(), 1500);
-    assert_eq!(result.promotion_redemptions.len(), 0);
+        // Wine total £10, threshold £30 not met
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    Ok(())
+        // All items at full price: 1000 + 500 = 1500
+        assert_eq!(result.total.to_minor_units(), 1500);
+        assert_eq!(result.promotion_redemptions.len(), 0);
+
+        Ok(())
+    })
 }
 
 /// Item-count threshold not met: no discount applied even when spend threshold is met.
 #[test]
 fn item_count_threshold_not_met_no_discount_applied() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1500, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1500, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_both_thresholds(Money::from_minor(3000, GBP), 3),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1500, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1500, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        ];
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Spend threshold met (£30 on wine), but only 2 contributing items (< 3 required).
-    assert_eq!(result.total.to_minor_units(), 3500);
-    assert_eq!(result.promotion_redemptions.len(), 0);
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_both_thresholds(Money::from_minor(3000, GBP), 3),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    Ok(())
+        // Spend threshold met (£30 on wine), but only 2 contributing items (< 3 required).
+        assert_eq!(result.total.to_minor_units(), 3500);
+        assert_eq!(result.promotion_redemptions.len(), 0);
+
+        Ok(())
+    })
 }
 
 /// Item-count threshold met: discount applies when spend and count requirements are both met.
 #[test]
 fn item_count_threshold_met_applies_discount() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_both_thresholds(Money::from_minor(3000, GBP), 3),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_both_thresholds(Money::from_minor(3000, GBP), 3),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    // Cheese gets 10% off: 500 -> 450
-    assert_eq!(result.total.to_minor_units(), 3450);
-    assert_eq!(result.promotion_redemptions.len(), 4);
+        // Cheese gets 10% off: 500 -> 450
+        assert_eq!(result.total.to_minor_units(), 3450);
+        assert_eq!(result.promotion_redemptions.len(), 4);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Item-count-only threshold: discount applies without a monetary threshold.
 #[test]
 fn item_count_only_threshold_met_applies_discount() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(100, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_item_count_threshold(2),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        ];
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Cheese gets 10% off: 500 -> 450
-    assert_eq!(result.total.to_minor_units(), 650);
-    assert_eq!(result.promotion_redemptions.len(), 3);
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_item_count_threshold(2),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
 
-    Ok(())
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
+
+        // Cheese gets 10% off: 500 -> 450
+        assert_eq!(result.total.to_minor_units(), 650);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    })
 }
 
 /// Upper threshold caps contribution/discountable value but does not deactivate the tier.
 #[test]
 fn upper_threshold_caps_discountable_value_without_disabling_tier() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(3000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(3000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(3000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
-            Some(TierThreshold::with_monetary_threshold(Money::from_minor(
-                6000, GBP,
-            ))),
-            lattice::promotions::qualification::Qualification::match_any(
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(3000, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(3000, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(3000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
+                Some(TierThreshold::with_monetary_threshold(Money::from_minor(
+                    6000, GBP,
+                ))),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    // Lower threshold met (>= £30). Upper threshold caps discountable value at £60,
-    // so only two £30 items get discounted: 2700 + 2700 + 3000 = 8400.
-    assert_eq!(result.total.to_minor_units(), 8400);
-    assert_eq!(result.promotion_redemptions.len(), 2);
+        // Lower threshold met (>= £30). Upper threshold caps discountable value at £60,
+        // so only two £30 items get discounted: 2700 + 2700 + 3000 = 8400.
+        assert_eq!(result.total.to_minor_units(), 8400);
+        assert_eq!(result.promotion_redemptions.len(), 2);
 
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Example 2: "Spend £50 get £5 off, spend £80 get £12 off"
 /// Solver picks the best tier that minimises total cost.
 #[test]
 fn multiple_tiers_qualify_solver_picks_optimal() -> TestResult {
-    // Total basket = 5 items at £20 each = £100
-    let items: Vec<Item<'_>> = (0..5)
-        .map(|_| Item::new(ProductKey::default(), Money::from_minor(2000, GBP)))
-        .collect();
+    for_each_backend(|config| {
+        // Total basket = 5 items at £20 each = £100
+        let items: Vec<Item<'_>> = (0..5)
+            .map(|_| Item::new(ProductKey::default(), Money::from_minor(2000, GBP)))
+            .collect();
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        // Empty tags = basket-wide
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![
+                ThresholdTier::new(
+                    TierThreshold::with_monetary_threshold(Money::from_minor(5000, GBP)),
+                    None,
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    ThresholdDiscount::AmountOffEachItem(Money::from_minor(500, GBP)),
+                ),
+                ThresholdTier::new(
+                    TierThreshold::with_monetary_threshold(Money::from_minor(8000, GBP)),
+                    None,
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    ThresholdDiscount::AmountOffEachItem(Money::from_minor(1200, GBP)),
+                ),
+            ],
+            PromotionBudget::unlimited(),
+        ));
 
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    // Empty tags = basket-wide
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![
-            ThresholdTier::new(
-                TierThreshold::with_monetary_threshold(Money::from_minor(5000, GBP)),
+        // With £12 off each of 5 items, total = 5 * (2000 - 1200) = 5 * 800 = 4000
+        // vs £5 off each: 5 * 1500 = 7500
+        // Solver picks £12 off tier
+        assert_eq!(result.total.to_minor_units(), 4000);
+        assert_eq!(result.promotion_redemptions.len(), 5);
+
+        Ok(())
+    })
+}
+
+/// Example 3: Basket-wide threshold with basket-wide discount (empty tag sets)
+#[test]
+fn basket_wide_threshold_and_discount() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::new(ProductKey::default(), Money::from_minor(1500, GBP)),
+            Item::new(ProductKey::default(), Money::from_minor(1000, GBP)),
+            Item::new(ProductKey::default(), Money::from_minor(500, GBP)),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        // Total = £30, threshold = £20
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(2000, GBP)),
                 None,
                 lattice::promotions::qualification::Qualification::match_any(
                     StringTagCollection::empty(),
@@ -368,99 +446,213 @@ fn multiple_tiers_qualify_solver_picks_optimal() -> TestResult {
                 lattice::promotions::qualification::Qualification::match_any(
                     StringTagCollection::empty(),
                 ),
-                ThresholdDiscount::AmountOffEachItem(Money::from_minor(500, GBP)),
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.05)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
+
+        // 5% off all: 1425 + 950 + 475 = 2850
+        assert_eq!(result.total.to_minor_units(), 2850);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    })
+}
+
+/// When only the lower tier qualifies, it is selected
+#[test]
+fn only_lower_tier_qualifies() -> TestResult {
+    for_each_backend(|config| {
+        // Total = 3 * £20 = £60
+        let items: Vec<Item<'_>> = (0..3)
+            .map(|_| Item::new(ProductKey::default(), Money::from_minor(2000, GBP)))
+            .collect();
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![
+                ThresholdTier::new(
+                    TierThreshold::with_monetary_threshold(Money::from_minor(5000, GBP)),
+                    None,
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    ThresholdDiscount::AmountOffEachItem(Money::from_minor(500, GBP)),
+                ),
+                ThresholdTier::new(
+                    TierThreshold::with_monetary_threshold(Money::from_minor(8000, GBP)),
+                    None,
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    lattice::promotions::qualification::Qualification::match_any(
+                        StringTagCollection::empty(),
+                    ),
+                    ThresholdDiscount::AmountOffEachItem(Money::from_minor(1200, GBP)),
+                ),
+            ],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
+
+        // Only £50 tier qualifies (total is £60 < £80)
+        // £5 off each of 3 items: 3 * 1500 = 4500
+        assert_eq!(result.total.to_minor_units(), 4500);
+        assert_eq!(result.promotion_redemptions.len(), 3);
+
+        Ok(())
+    })
+}
+
+/// All items in a tier share the same bundle ID
+#[test]
+fn tier_items_share_redemption_idx() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(3000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(500, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(400, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdTier::new(
-                TierThreshold::with_monetary_threshold(Money::from_minor(8000, GBP)),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(2000, GBP)),
                 None,
                 lattice::promotions::qualification::Qualification::match_any(
-                    StringTagCollection::empty(),
+                    StringTagCollection::from_strs(&["wine"]),
                 ),
                 lattice::promotions::qualification::Qualification::match_any(
-                    StringTagCollection::empty(),
+                    StringTagCollection::from_strs(&["cheese"]),
                 ),
-                ThresholdDiscount::AmountOffEachItem(Money::from_minor(1200, GBP)),
-            ),
-        ],
-        PromotionBudget::unlimited(),
-    ));
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
 
-    // With £12 off each of 5 items, total = 5 * (2000 - 1200) = 5 * 800 = 4000
-    // vs £5 off each: 5 * 1500 = 7500
-    // Solver picks £12 off tier
-    assert_eq!(result.total.to_minor_units(), 4000);
-    assert_eq!(result.promotion_redemptions.len(), 5);
+        assert_eq!(result.promotion_redemptions.len(), 3);
 
-    Ok(())
+        // Both cheese items should share the same redemption_idx
+        let redemption_idxs: Vec<usize> = result
+            .promotion_redemptions
+            .iter()
+            .map(|a| a.redemption_idx)
+            .collect();
+
+        assert_eq!(redemption_idxs.first(), redemption_idxs.get(1));
+        assert_eq!(redemption_idxs.first(), redemption_idxs.get(2));
+
+        Ok(())
+    })
 }
 
-/// Example 3: Basket-wide threshold with basket-wide discount (empty tag sets)
+/// Fixture-based test: load the tiered-threshold fixtures
 #[test]
-fn basket_wide_threshold_and_discount() -> TestResult {
-    let items = [
-        Item::new(ProductKey::default(), Money::from_minor(1500, GBP)),
-        Item::new(ProductKey::default(), Money::from_minor(1000, GBP)),
-        Item::new(ProductKey::default(), Money::from_minor(500, GBP)),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+fn fixture_based_tiered_threshold() -> TestResult {
+    for_each_backend(|config| {
+        let fixture = Fixture::from_set("tiered-threshold")?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Total = £30, threshold = £20
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(2000, GBP)),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::empty(),
-            ),
-            lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::empty(),
-            ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.05)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        // Run the graph evaluation (which exercises all three promotions)
+        let result = fixture
+            .graph()?
+            .evaluate_with_config(config, &item_group, None)?;
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+        // Verify the result accounts for all items
+        let total_items = result.item_redemptions.len() + result.full_price_items.len();
 
-    // 5% off all: 1425 + 950 + 475 = 2850
-    assert_eq!(result.total.to_minor_units(), 2850);
-    assert_eq!(result.promotion_redemptions.len(), 3);
+        assert_eq!(total_items, 10);
 
-    Ok(())
+        Ok(())
+    })
 }
 
-/// When only the lower tier qualifies, it is selected
+/// Contribution items are exclusive participants and cannot be claimed by other
+/// promotions in the same layer.
 #[test]
-fn only_lower_tier_qualifies() -> TestResult {
-    // Total = 3 * £20 = £60
-    let items: Vec<Item<'_>> = (0..3)
-        .map(|_| Item::new(ProductKey::default(), Money::from_minor(2000, GBP)))
-        .collect();
+fn contribution_items_are_exclusive_across_promotions() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1200, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(800, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+        ];
 
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    let promo = promotion(TieredThresholdPromotion::new(
-        PromotionKey::default(),
-        vec![
-            ThresholdTier::new(
-                TierThreshold::with_monetary_threshold(Money::from_minor(5000, GBP)),
+        let mut keys = SlotMap::<PromotionKey, ()>::with_key();
+
+        let wine_cheese_key = keys.insert(());
+        let basket_key = keys.insert(());
+
+        let wine_cheese = promotion(TieredThresholdPromotion::new(
+            wine_cheese_key,
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
                 None,
                 lattice::promotions::qualification::Qualification::match_any(
-                    StringTagCollection::empty(),
+                    StringTagCollection::from_strs(&["wine"]),
                 ),
                 lattice::promotions::qualification::Qualification::match_any(
-                    StringTagCollection::empty(),
+                    StringTagCollection::from_strs(&["cheese"]),
                 ),
-                ThresholdDiscount::AmountOffEachItem(Money::from_minor(500, GBP)),
-            ),
-            ThresholdTier::new(
-                TierThreshold::with_monetary_threshold(Money::from_minor(8000, GBP)),
+                ThresholdDiscount::PercentEachItem(Percentage::from(1.0)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let basket_wide = promotion(TieredThresholdPromotion::new(
+            basket_key,
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(0, GBP)),
                 None,
                 lattice::promotions::qualification::Qualification::match_any(
                     StringTagCollection::empty(),
@@ -468,175 +660,325 @@ fn only_lower_tier_qualifies() -> TestResult {
                 lattice::promotions::qualification::Qualification::match_any(
                     StringTagCollection::empty(),
                 ),
-                ThresholdDiscount::AmountOffEachItem(Money::from_minor(1200, GBP)),
-            ),
-        ],
-        PromotionBudget::unlimited(),
-    ));
-
-    let result = ILPSolver::solve(&[promo], &item_group)?;
-
-    // Only £50 tier qualifies (total is £60 < £80)
-    // £5 off each of 3 items: 3 * 1500 = 4500
-    assert_eq!(result.total.to_minor_units(), 4500);
-    assert_eq!(result.promotion_redemptions.len(), 3);
-
-    Ok(())
+                ThresholdDiscount::PercentEachItem(Percentage::from(0.05)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result = ILPSolver::solve_with_config(
+            config,
+            &[wine_cheese, basket_wide],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        // Wine & cheese wins only if all wine contributors participate in that promotion.
+        assert_eq!(result.total.to_minor_units(), 3000);
+        assert_eq!(result.promotion_redemptions.len(), 4);
+        assert!(
+            result
+                .promotion_redemptions
+                .iter()
+                .all(|app| app.promotion_key == wine_cheese_key)
+        );
+
+        Ok(())
+    })
 }
 
-/// All items in a tier share the same bundle ID
-#[test]
-fn tier_items_share_redemption_idx() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(3000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(500, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(400, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let promo = promotion(TieredThresholdPromotion::new(
+/// "Buy 2kg of loose produce, get 20% off produce"
+/// Only weighed items count towards the 2kg; the bag sold by count does not.
+fn produce_by_weight_promotion() -> lattice::promotions::Promotion<'static> {
+    promotion(TieredThresholdPromotion::new(
         PromotionKey::default(),
         vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(2000, GBP)),
+            TierThreshold::with_measure_threshold(Measure::kilograms(2)),
             None,
             lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::from_strs(&["wine"]),
+                StringTagCollection::from_strs(&["produce"]),
             ),
             lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::from_strs(&["cheese"]),
+                StringTagCollection::from_strs(&["produce"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.10)),
+            ThresholdDiscount::PercentEachItem(Percentage::from(0.20)),
         )],
         PromotionBudget::unlimited(),
-    ));
+    ))
+}
 
-    let result = ILPSolver::solve(&[promo], &item_group)?;
+fn weighed_produce(grams: u32, per_kg_minor: i64) -> TestResult<Item<'static>> {
+    Ok(Item::with_measure(
+        ProductKey::default(),
+        Money::from_minor(per_kg_minor, GBP),
+        StringTagCollection::from_strs(&["produce"]),
+        Measure::grams(grams),
+    )?)
+}
+
+#[test]
+fn measure_threshold_met_applies_discount() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            // 1.2kg at £2.00/kg = £2.40
+            weighed_produce(1200, 200)?,
+            // 900g at £3.00/kg = £2.70
+            weighed_produce(900, 300)?,
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["produce"]),
+            ),
+        ];
 
-    assert_eq!(result.promotion_redemptions.len(), 3);
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Both cheese items should share the same redemption_idx
-    let redemption_idxs: Vec<usize> = result
-        .promotion_redemptions
-        .iter()
-        .map(|a| a.redemption_idx)
-        .collect();
+        let result = ILPSolver::solve_with_config(
+            config,
+            &[produce_by_weight_promotion()],
+            &item_group,
+            &mut NoopObserver,
+        )?;
 
-    assert_eq!(redemption_idxs.first(), redemption_idxs.get(1));
-    assert_eq!(redemption_idxs.first(), redemption_idxs.get(2));
+        // 2.1kg >= 2kg, so all produce gets 20% off: 192 + 216 + 80 = 488
+        assert_eq!(result.total.to_minor_units(), 488);
 
-    Ok(())
+        Ok(())
+    })
 }
 
-/// Fixture-based test: load the tiered-threshold fixtures
 #[test]
-fn fixture_based_tiered_threshold() -> TestResult {
-    let fixture = Fixture::from_set("tiered-threshold")?;
-    let basket = fixture.basket(None)?;
-    let item_group = ItemGroup::from(&basket);
+fn measure_threshold_ignores_items_sold_by_count() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            // 1.2kg at £2.00/kg = £2.40
+            weighed_produce(1200, 200)?,
+            // 700g at £3.00/kg = £2.10
+            weighed_produce(700, 300)?,
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(100, GBP),
+                StringTagCollection::from_strs(&["produce"]),
+            ),
+        ];
 
-    // Run the graph evaluation (which exercises all three promotions)
-    let result = fixture.graph()?.evaluate(&item_group)?;
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Verify the result accounts for all items
-    let total_items = result.item_redemptions.len() + result.full_price_items.len();
+        let result = ILPSolver::solve_with_config(
+            config,
+            &[produce_by_weight_promotion()],
+            &item_group,
+            &mut NoopObserver,
+        )?;
 
-    assert_eq!(total_items, 10);
+        // 1.9kg < 2kg: everything at full price, 240 + 210 + 100 = 550
+        assert_eq!(result.total.to_minor_units(), 550);
+        assert!(result.promotion_redemptions.is_empty());
 
-    Ok(())
+        Ok(())
+    })
 }
 
-/// Contribution items are exclusive participants and cannot be claimed by other
-/// promotions in the same layer.
 #[test]
-fn contribution_items_are_exclusive_across_promotions() -> TestResult {
-    let items = [
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1200, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(800, GBP),
-            StringTagCollection::from_strs(&["wine"]),
-        ),
-        Item::with_tags(
-            ProductKey::default(),
-            Money::from_minor(1000, GBP),
-            StringTagCollection::from_strs(&["cheese"]),
-        ),
-    ];
-
-    let basket = Basket::with_items(items, GBP)?;
-    let item_group = ItemGroup::from(&basket);
-
-    let mut keys = SlotMap::<PromotionKey, ()>::with_key();
-
-    let wine_cheese_key = keys.insert(());
-    let basket_key = keys.insert(());
-
-    let wine_cheese = promotion(TieredThresholdPromotion::new(
-        wine_cheese_key,
-        vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(3000, GBP)),
-            None,
-            lattice::promotions::qualification::Qualification::match_any(
+fn amount_off_total_does_not_take_the_total_below_zero() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
                 StringTagCollection::from_strs(&["wine"]),
             ),
-            lattice::promotions::qualification::Qualification::match_any(
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
                 StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(1.0)),
-        )],
-        PromotionBudget::unlimited(),
-    ));
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_monetary_threshold(Money::from_minor(1000, GBP)),
+                None,
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["wine"]),
+                ),
+                lattice::promotions::qualification::Qualification::match_any(
+                    StringTagCollection::from_strs(&["cheese"]),
+                ),
+                ThresholdDiscount::AmountOffTotal(Money::from_minor(500, GBP)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
+
+        // £5 off £3 of cheese makes it free, not negative: 1000 + 0
+        assert_eq!(result.total.to_minor_units(), 1000);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn percent_cheapest_discounts_the_cheapest_claimed_item() -> TestResult {
+    for_each_backend(|config| {
+        let items = [100, 200, 300].map(|price| {
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(price, GBP),
+                StringTagCollection::from_strs(&["snack"]),
+            )
+        });
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let snacks = || {
+            lattice::promotions::qualification::Qualification::match_any(
+                StringTagCollection::from_strs(&["snack"]),
+            )
+        };
+
+        let promo = promotion(TieredThresholdPromotion::new(
+            PromotionKey::default(),
+            vec![ThresholdTier::new(
+                TierThreshold::with_item_count_threshold(2),
+                Some(TierThreshold::with_item_count_threshold(2)),
+                snacks(),
+                snacks(),
+                ThresholdDiscount::PercentCheapest(Percentage::from(0.50)),
+            )],
+            PromotionBudget::unlimited(),
+        ));
+
+        let result =
+            ILPSolver::solve_with_config(config, &[promo], &item_group, &mut NoopObserver)?;
+
+        // Claiming £2 and £3 halves the £2 item: 100 + 100 + 300 = 500. Halving the
+        // £3 item while the £1 item is also claimed would not be the cheapest.
+        assert_eq!(result.total.to_minor_units(), 500);
+
+        Ok(())
+    })
+}
 
-    let basket_wide = promotion(TieredThresholdPromotion::new(
-        basket_key,
+/// "Spend £10 on wine, get £5 off cheese", for £3 of cheese, under a budget.
+fn wine_and_cheese_deal(
+    discount: ThresholdDiscount<'static>,
+    monetary_limit: i64,
+) -> lattice::promotions::Promotion<'static> {
+    promotion(TieredThresholdPromotion::new(
+        PromotionKey::default(),
         vec![ThresholdTier::new(
-            TierThreshold::with_monetary_threshold(Money::from_minor(0, GBP)),
+            TierThreshold::with_monetary_threshold(Money::from_minor(1000, GBP)),
             None,
             lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::empty(),
+                StringTagCollection::from_strs(&["wine"]),
             ),
             lattice::promotions::qualification::Qualification::match_any(
-                StringTagCollection::empty(),
+                StringTagCollection::from_strs(&["cheese"]),
             ),
-            ThresholdDiscount::PercentEachItem(Percentage::from(0.05)),
+            discount,
         )],
-        PromotionBudget::unlimited(),
-    ));
+        PromotionBudget {
+            redemption_limit: None,
+            monetary_limit: Some(Money::from_minor(monetary_limit, GBP)),
+        },
+    ))
+}
+
+#[test]
+fn amount_off_total_budget_counts_the_capped_discount() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+        ];
 
-    let result = ILPSolver::solve(&[wine_cheese, basket_wide], &item_group)?;
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
 
-    // Wine & cheese wins only if all wine contributors participate in that promotion.
-    assert_eq!(result.total.to_minor_units(), 3000);
-    assert_eq!(result.promotion_redemptions.len(), 4);
-    assert!(
-        result
-            .promotion_redemptions
-            .iter()
-            .all(|app| app.promotion_key == wine_cheese_key)
-    );
+        let discount = || ThresholdDiscount::AmountOffTotal(Money::from_minor(500, GBP));
+
+        // Only £3 comes off, so a £4 budget covers it: 1000 + 0
+        let within = ILPSolver::solve_with_config(
+            config,
+            &[wine_and_cheese_deal(discount(), 400)],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(within.total.to_minor_units(), 1000);
+
+        // A £2 budget does not: 1000 + 300
+        let over = ILPSolver::solve_with_config(
+            config,
+            &[wine_and_cheese_deal(discount(), 200)],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(over.total.to_minor_units(), 1300);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn fixed_total_budget_counts_the_discounted_items_value() -> TestResult {
+    for_each_backend(|config| {
+        let items = [
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(1000, GBP),
+                StringTagCollection::from_strs(&["wine"]),
+            ),
+            Item::with_tags(
+                ProductKey::default(),
+                Money::from_minor(300, GBP),
+                StringTagCollection::from_strs(&["cheese"]),
+            ),
+        ];
+
+        let basket = Basket::with_items(items, GBP)?;
+        let item_group = ItemGroup::from(&basket);
+
+        let discount = || ThresholdDiscount::FixedTotal(Money::from_minor(100, GBP));
+
+        // Cheese for £1 takes £2 off, within a £2.50 budget: 1000 + 100
+        let within = ILPSolver::solve_with_config(
+            config,
+            &[wine_and_cheese_deal(discount(), 250)],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(within.total.to_minor_units(), 1100);
+
+        // But not within £1.50: 1000 + 300
+        let over = ILPSolver::solve_with_config(
+            config,
+            &[wine_and_cheese_deal(discount(), 150)],
+            &item_group,
+            &mut NoopObserver,
+        )?;
+
+        assert_eq!(over.total.to_minor_units(), 1300);
 
-    Ok(())
+        Ok(())
+    })
 }
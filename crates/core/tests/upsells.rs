@@ -0,0 +1,75 @@
+//! Integration tests for upsell suggestions on the fixture sets.
+
+use testresult::TestResult;
+
+use lattice::{fixtures::Fixture, items::Item, items::groups::ItemGroup};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn upsells_match_evaluating_each_addition() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let graph = fixture.graph()?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let products = fixture.product_meta_map();
+
+        let base_total = graph.evaluate(&item_group)?.total.to_minor_units();
+        let upsells = graph.upsells(&item_group, products.iter())?;
+
+        assert_eq!(upsells.len(), products.len(), "{set}");
+
+        for upsell in &upsells {
+            let product = products
+                .get(upsell.product_key)
+                .ok_or("upsell for an unknown product")?;
+
+            let mut items: Vec<Item<'_>> = item_group.iter().cloned().collect();
+
+            items.push(Item::with_tags(
+                upsell.product_key,
+                product.price,
+                product.tags.clone(),
+            ));
+
+            let projected = graph.evaluate(&ItemGroup::new(
+                items.into_iter().collect(),
+                item_group.currency(),
+            ))?;
+
+            assert_eq!(
+                upsell.marginal_minor,
+                projected.total.to_minor_units() - base_total,
+                "{set}"
+            );
+            assert_eq!(
+                upsell.savings_minor,
+                product.price.to_minor_units() - upsell.marginal_minor,
+                "{set}"
+            );
+        }
+
+        assert!(
+            upsells
+                .windows(2)
+                .all(|pair| pair[0].savings_minor >= pair[1].savings_minor),
+            "{set}"
+        );
+    }
+
+    Ok(())
+}
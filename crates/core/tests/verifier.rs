@@ -0,0 +1,119 @@
+//! Verifier tests: every fixture set's results satisfy the promotion rules, as
+//! checked independently of the solver.
+
+use testresult::TestResult;
+
+use lattice::{
+    fixtures::Fixture,
+    items::groups::ItemGroup,
+    solvers::{
+        Solver,
+        greedy::GreedySolver,
+        ilp::ILPSolver,
+        verifier::{VerificationError, verify_result},
+    },
+};
+
+const FIXTURE_SETS: &[&str] = &[
+    "budget-application",
+    "budget-monetary",
+    "complex",
+    "comprehensive",
+    "demo",
+    "direct",
+    "layered",
+    "mix-and-match",
+    "positional",
+    "qualification",
+    "tiered-threshold",
+    "conformance/meal-deals",
+];
+
+#[test]
+fn ilp_results_satisfy_promotion_rules() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        let result = ILPSolver::solve(promotions, &item_group)?;
+
+        if let Err(err) = verify_result(promotions, &item_group, &result) {
+            panic!("ILP result for fixture set {set} fails verification: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn greedy_results_satisfy_promotion_rules() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let promotions = fixture.promotions();
+
+        let result = GreedySolver::solve(promotions, &item_group)?;
+
+        if let Err(err) = verify_result(promotions, &item_group, &result) {
+            panic!("greedy result for fixture set {set} fails verification: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layered_results_satisfy_promotion_rules() -> TestResult {
+    for set in FIXTURE_SETS {
+        let fixture = Fixture::from_set(set)?;
+        let basket = fixture.basket(None)?;
+        let item_group = ItemGroup::from(&basket);
+        let graph = fixture.graph()?;
+
+        let result = graph.evaluate(&item_group)?;
+
+        if let Err(err) = graph.verify(&item_group, &result) {
+            panic!("layered result for fixture set {set} fails verification: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layered_verification_rejects_redemptions_outside_their_layer() -> TestResult {
+    let fixture = Fixture::from_set("layered")?;
+    let basket = fixture.basket(None)?;
+    let item_group = ItemGroup::from(&basket);
+    let graph = fixture.graph()?;
+
+    let mut result = graph.evaluate(&item_group)?;
+
+    // Swap the order of an item's stacked redemptions, so the first layer sees
+    // the second layer's promotion.
+    let stacked = result
+        .item_redemptions
+        .values_mut()
+        .find(|redemptions| redemptions.len() > 1)
+        .ok_or("expected an item discounted in more than one layer")?;
+
+    stacked.swap(0, 1);
+
+    assert!(graph.verify(&item_group, &result).is_err());
+
+    // Drop the discounts entirely, but keep the discounted total.
+    result
+        .full_price_items
+        .extend(result.item_redemptions.keys().copied());
+    result.item_redemptions.clear();
+
+    assert!(matches!(
+        graph.verify(&item_group, &result),
+        Err(VerificationError::TotalMismatch { .. })
+    ));
+
+    Ok(())
+}
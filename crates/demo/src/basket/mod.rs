@@ -17,7 +17,7 @@ use rusty_money::{Money, iso::Currency};
 use slotmap::{SecondaryMap, SlotMap};
 
 #[cfg(target_arch = "wasm32")]
-use lattice::solvers::ilp::renderers::typst::MultiLayerRenderer;
+use lattice::{graph::Upsell, solvers::ilp::renderers::typst::MultiLayerRenderer};
 
 use lattice::{
     basket::Basket,
@@ -152,25 +152,23 @@ fn build_basket(
         .map_err(|error| format!("Failed to build basket: {error}"))
 }
 
-/// Solve only the basket total (minor units) for marginal-price estimation.
+/// Estimate the basket-impact of adding one unit of each catalogue product.
 ///
 /// # Errors
 ///
 /// Returns an error if basket construction or graph solving fails.
 #[cfg(target_arch = "wasm32")]
-pub fn solve_total_minor(
+pub fn estimate_upsells(
     solver_data: &BasketSolverData,
     cart_fixture_keys: &[String],
-) -> Result<i64, String> {
+) -> Result<Vec<Upsell>, String> {
     let basket = build_basket(solver_data, cart_fixture_keys)?;
     let item_group = ItemGroup::from(&basket);
 
-    let solved = solver_data
+    solver_data
         .graph
-        .evaluate(&item_group)
-        .map_err(|error| format!("Failed to solve promotion graph: {error}"))?;
-
-    Ok(solved.total.to_minor_units())
+        .upsells(&item_group, solver_data.product_meta_map.iter())
+        .map_err(|error| format!("Failed to solve promotion graph: {error}"))
 }
 
 fn solve_basket(
@@ -5,9 +5,15 @@ use std::sync::OnceLock;
 
 use leptos::{prelude::*, task};
 
+#[cfg(target_arch = "wasm32")]
+use lattice::products::ProductKey;
+
 #[cfg(target_arch = "wasm32")]
 use leptos_workers::worker;
 
+#[cfg(target_arch = "wasm32")]
+use slotmap::SecondaryMap;
+
 #[cfg(target_arch = "wasm32")]
 use crate::basket;
 
@@ -45,18 +51,11 @@ struct EstimateSignals {
     generation: RwSignal<u64>,
 }
 
-#[cfg(target_arch = "wasm32")]
-#[derive(Debug)]
-struct WorkerProductMeta {
-    fixture_key: String,
-    price_minor: i64,
-}
-
 #[cfg(target_arch = "wasm32")]
 #[derive(Debug)]
 struct WorkerData {
     solver_data: basket::BasketSolverData,
-    products: Vec<WorkerProductMeta>,
+    fixture_keys: SecondaryMap<ProductKey, String>,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -194,13 +193,10 @@ fn load_worker_data() -> Result<WorkerData, String> {
     let loaded_products = crate::products::load_products(PRODUCTS_FIXTURE_YAML)?;
     let loaded_promotions = crate::promotions::load_promotions(PROMOTIONS_FIXTURE_YAML)?;
 
-    let worker_products = loaded_products
-        .products
+    let fixture_keys = loaded_products
+        .product_key_by_fixture_key
         .iter()
-        .map(|product| WorkerProductMeta {
-            fixture_key: product.fixture_key.clone(),
-            price_minor: product.price_minor,
-        })
+        .map(|(fixture_key, &product_key)| (product_key, fixture_key.clone()))
         .collect();
 
     Ok(WorkerData {
@@ -212,7 +208,7 @@ fn load_worker_data() -> Result<WorkerData, String> {
             promotion_meta_map: loaded_promotions.promotion_meta_map,
             currency: loaded_products.currency,
         },
-        products: worker_products,
+        fixture_keys,
     })
 }
 
@@ -235,31 +231,21 @@ async fn estimate_cart_worker(cart_keys: String) -> String {
 
     let cart_snapshot = decode_cart_keys(&cart_keys);
 
-    let Ok(base_total_minor) = basket::solve_total_minor(&worker_data.solver_data, &cart_snapshot)
-    else {
-        return format!("{WORKER_ERROR_PREFIX}Failed to solve base basket");
+    let Ok(upsells) = basket::estimate_upsells(&worker_data.solver_data, &cart_snapshot) else {
+        return format!("{WORKER_ERROR_PREFIX}Failed to solve basket estimates");
     };
 
-    let mut lines: Vec<String> = Vec::with_capacity(worker_data.products.len());
-
-    for product in &worker_data.products {
-        let mut projected_cart = cart_snapshot.clone();
-        projected_cart.push(product.fixture_key.clone());
-
-        let Ok(projected_total_minor) =
-            basket::solve_total_minor(&worker_data.solver_data, &projected_cart)
-        else {
-            continue;
-        };
-
-        let marginal_minor = projected_total_minor - base_total_minor;
-        let savings_minor = product.price_minor - marginal_minor;
+    let lines: Vec<String> = upsells
+        .iter()
+        .filter_map(|upsell| {
+            let fixture_key = worker_data.fixture_keys.get(upsell.product_key)?;
 
-        lines.push(format!(
-            "{}\t{}\t{}",
-            product.fixture_key, marginal_minor, savings_minor
-        ));
-    }
+            Some(format!(
+                "{}\t{}\t{}",
+                fixture_key, upsell.marginal_minor, upsell.savings_minor
+            ))
+        })
+        .collect();
 
     lines.join("\n")
 }
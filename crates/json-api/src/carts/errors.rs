@@ -18,6 +18,11 @@ pub(crate) fn into_status_error(error: CartsServiceError) -> StatusError {
 
             StatusError::internal_server_error()
         }
+        CartsServiceError::Pricing(source) => {
+            error!("failed to price cart: {source}");
+
+            StatusError::internal_server_error()
+        }
         CartsServiceError::NotFound => {
             error!("cart not found");
 
@@ -12,7 +12,10 @@ use salvo::{
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use lattice_app::domain::carts::records::{CartItemRecord, CartRecord};
+use lattice_app::domain::{
+    carts::records::{CartItemRecord, CartRecord},
+    pricing::data::Redemption,
+};
 
 use crate::{carts::errors::into_status_error, extensions::*, state::State};
 
@@ -22,6 +25,12 @@ pub(crate) struct CartResponse {
     /// The unique identifier of the cart
     pub uuid: Uuid,
 
+    /// The sum of the cart item prices before promotions
+    pub subtotal: u64,
+
+    /// The cart total after promotions
+    pub total: u64,
+
     /// The items in the cart
     pub items: Vec<CartItemResponse>,
 
@@ -39,6 +48,8 @@ impl From<CartRecord> for CartResponse {
     fn from(cart: CartRecord) -> Self {
         CartResponse {
             uuid: cart.uuid.into(),
+            subtotal: cart.subtotal,
+            total: cart.total,
             items: cart.items.into_iter().map(CartItemResponse::from).collect(),
             created_at: cart.created_at.to_string(),
             updated_at: cart.updated_at.to_string(),
@@ -59,6 +70,9 @@ pub(crate) struct CartItemResponse {
     /// The unique identifier of the product in the cart item
     pub product_uuid: Uuid,
 
+    /// The promotions redeemed against the cart item
+    pub redemptions: Vec<RedemptionResponse>,
+
     /// The date and time the cart was created
     pub created_at: String,
 
@@ -75,6 +89,11 @@ impl From<CartItemRecord> for CartItemResponse {
             uuid: cart_item.uuid.into(),
             price: cart_item.price,
             product_uuid: cart_item.product_uuid.into(),
+            redemptions: cart_item
+                .redemptions
+                .into_iter()
+                .map(RedemptionResponse::from)
+                .collect(),
             created_at: cart_item.created_at.to_string(),
             updated_at: cart_item.updated_at.to_string(),
             deleted_at: cart_item.deleted_at.as_ref().map(ToString::to_string),
@@ -82,9 +101,36 @@ impl From<CartItemRecord> for CartItemResponse {
     }
 }
 
+/// Redemption Response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct RedemptionResponse {
+    /// The unique identifier of the redeemed promotion
+    pub promotion_uuid: Uuid,
+
+    /// The redemption this item belongs to; items redeemed together share an index
+    pub redemption_idx: usize,
+
+    /// The item price before the promotion was applied
+    pub original_price: u64,
+
+    /// The item price after the promotion was applied
+    pub final_price: u64,
+}
+
+impl From<Redemption> for RedemptionResponse {
+    fn from(redemption: Redemption) -> Self {
+        Self {
+            promotion_uuid: redemption.promotion_uuid.into(),
+            redemption_idx: redemption.redemption_idx,
+            original_price: redemption.original_price,
+            final_price: redemption.final_price,
+        }
+    }
+}
+
 /// Get Cart Handler
 ///
-/// Returns a cart.
+/// Returns a cart, priced against the promotions active at the requested point in time.
 #[endpoint(
     tags("carts"),
     summary = "Get Cart",
@@ -132,10 +178,17 @@ pub(crate) async fn handler(
 #[cfg(test)]
 mod tests {
     use jiff::Timestamp;
-    use salvo::test::TestClient;
+    use salvo::test::{ResponseExt, TestClient};
     use testresult::TestResult;
 
-    use lattice_app::domain::carts::{CartsServiceError, MockCartsService, records::CartUuid};
+    use lattice_app::domain::{
+        carts::{
+            CartsServiceError, MockCartsService,
+            records::{CartItemUuid, CartUuid},
+        },
+        products::records::ProductUuid,
+        promotions::records::PromotionUuid,
+    };
 
     use crate::test_helpers::{TEST_TENANT_UUID, carts_service, make_cart};
 
@@ -169,6 +222,61 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_returns_totals_and_item_redemptions() -> TestResult {
+        let mut repo = MockCartsService::new();
+        let uuid = CartUuid::new();
+        let promotion_uuid = PromotionUuid::new();
+
+        let mut cart = make_cart(uuid);
+
+        cart.subtotal = 10_00;
+        cart.total = 8_00;
+        cart.items.push(CartItemRecord {
+            uuid: CartItemUuid::new(),
+            price: 10_00,
+            product_uuid: ProductUuid::new(),
+            redemptions: vec![Redemption {
+                promotion_uuid,
+                redemption_idx: 0,
+                original_price: 10_00,
+                final_price: 8_00,
+            }],
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        });
+
+        repo.expect_get_cart()
+            .once()
+            .return_once(move |_, _, _| Ok(cart));
+
+        let mut res = TestClient::get(format!("http://example.com/carts/{uuid}"))
+            .send(&make_service(repo))
+            .await;
+
+        let body: CartResponse = res.take_json().await?;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(body.subtotal, 10_00);
+        assert_eq!(body.total, 8_00);
+
+        let Some(item) = body.items.first() else {
+            panic!("expected item, got None");
+        };
+
+        let Some(redemption) = item.redemptions.first() else {
+            panic!("expected redemption, got None");
+        };
+
+        assert_eq!(redemption.promotion_uuid, promotion_uuid.into_uuid());
+        assert_eq!(redemption.redemption_idx, 0);
+        assert_eq!(redemption.original_price, 10_00);
+        assert_eq!(redemption.final_price, 8_00);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_missing_cart_returns_404() -> TestResult {
         let mut repo = MockCartsService::new();
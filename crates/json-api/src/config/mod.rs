@@ -6,12 +6,14 @@ use crate::config::{
     auth::AuthConfig,
     db::DatabaseConfig,
     observability::{LoggingConfig, ObservabilityConfig},
+    pricing::PricingConfig,
     server::ServerRuntimeConfig,
 };
 
 pub(crate) mod auth;
 pub(crate) mod db;
 pub(crate) mod observability;
+pub(crate) mod pricing;
 pub(crate) mod server;
 
 /// Lattice JSON API Server configuration
@@ -37,6 +39,10 @@ pub struct ServerConfig {
     /// `OpenBao` authentication settings.
     #[command(flatten)]
     pub auth: AuthConfig,
+
+    /// Cart and quote pricing settings.
+    #[command(flatten)]
+    pub pricing: PricingConfig,
 }
 
 impl ServerConfig {
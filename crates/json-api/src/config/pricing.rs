@@ -0,0 +1,17 @@
+//! Pricing Config
+
+use clap::Args;
+use rusty_money::iso::{self, Currency};
+
+/// Pricing settings.
+#[derive(Debug, Args)]
+pub struct PricingConfig {
+    /// ISO 4217 code of the currency prices and promotion amounts are in
+    #[arg(long, env = "CURRENCY", default_value = "GBP", value_parser = parse_currency)]
+    pub currency: &'static Currency,
+}
+
+/// Look up an ISO 4217 currency by its alphabetic code.
+fn parse_currency(code: &str) -> Result<&'static Currency, String> {
+    iso::find(&code.to_ascii_uppercase()).ok_or_else(|| format!("unknown currency `{code}`"))
+}
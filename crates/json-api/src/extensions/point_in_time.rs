@@ -18,3 +18,12 @@ impl PointInTimeExt for QueryParam<String, false> {
             .map(|point_in_time| point_in_time.unwrap_or_else(Timestamp::now))
     }
 }
+
+impl PointInTimeExt for Option<String> {
+    fn into_point_in_time(self) -> Result<Timestamp, StatusError> {
+        self.map(|value| value.parse::<Timestamp>())
+            .transpose()
+            .or_400("could not parse \"at\" field")
+            .map(|point_in_time| point_in_time.unwrap_or_else(Timestamp::now))
+    }
+}
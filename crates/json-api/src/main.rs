@@ -36,8 +36,10 @@ mod healthcheck;
 mod observability;
 mod products;
 mod promotions;
+mod quotes;
 mod router;
 mod shutdown;
+mod stacks;
 mod state;
 
 #[cfg(test)]
@@ -88,7 +90,13 @@ pub async fn main() {
         transit_key: config.auth.transit_key,
     });
 
-    let app = match AppContext::from_database_url(&config.database.database_url, openbao).await {
+    let app = match AppContext::from_database_url(
+        &config.database.database_url,
+        openbao,
+        config.pricing.currency,
+    )
+    .await
+    {
         Ok(app) => app,
         Err(init_error) => {
             error!("failed to initialise app context: {init_error}");
@@ -59,6 +59,24 @@ pub(crate) async fn handler(
             span.record("promotion_uuid", tracing::field::display(uuid));
             span.record("promotion_type", tracing::field::display("direct_discount"));
         }
+        CreatePromotionRequest::PositionalDiscount { uuid, .. } => {
+            span.record("promotion_uuid", tracing::field::display(uuid));
+            span.record(
+                "promotion_type",
+                tracing::field::display("positional_discount"),
+            );
+        }
+        CreatePromotionRequest::MixAndMatch { uuid, .. } => {
+            span.record("promotion_uuid", tracing::field::display(uuid));
+            span.record("promotion_type", tracing::field::display("mix_and_match"));
+        }
+        CreatePromotionRequest::TieredThreshold { uuid, .. } => {
+            span.record("promotion_uuid", tracing::field::display(uuid));
+            span.record(
+                "promotion_type",
+                tracing::field::display("tiered_threshold"),
+            );
+        }
     }
 
     let uuid = state
@@ -189,6 +207,49 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_positional_promotion_success() -> TestResult {
+        let promotion_uuid = PromotionUuid::new();
+        let promotion = make_promotion(promotion_uuid);
+
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_create_promotion()
+            .once()
+            .withf(move |tenant, new| {
+                *tenant == TEST_TENANT_UUID
+                    && *new
+                        == NewPromotion::PositionalDiscount {
+                            uuid: promotion_uuid,
+                            budgets: Budgets {
+                                redemptions: None,
+                                monetary: None,
+                            },
+                            size: 3,
+                            positions: smallvec![2],
+                            discount: SimpleDiscount::PercentageOff { percentage: 100 },
+                            qualification: None,
+                        }
+            })
+            .return_once(move |_, _| Ok(promotion));
+
+        let res = TestClient::post("http://example.com/promotions")
+            .json(&json!({
+                "type": "positional_discount",
+                "uuid": promotion_uuid.into_uuid(),
+                "budgets": {},
+                "size": 3,
+                "positions": [2],
+                "discount": { "type": "percentage_off", "percentage": 100 }
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_promotion_conflict_returns_409() -> TestResult {
         let uuid = PromotionUuid::new();
@@ -0,0 +1,128 @@
+//! Delete Promotion Handler
+
+use std::sync::Arc;
+
+use salvo::{oapi::extract::PathParam, prelude::*};
+use uuid::Uuid;
+
+use crate::{extensions::*, promotions::errors::into_status_error, state::State};
+
+/// Delete Promotion Handler
+///
+/// Soft-deletes a promotion. It remains readable at earlier points in time.
+#[endpoint(
+    tags("promotions"),
+    summary = "Delete Promotion",
+    security(("bearer_auth" = [])),
+    responses(
+        (status_code = StatusCode::OK, description = "Promotion deleted"),
+        (status_code = StatusCode::NOT_FOUND, description = "Promotion not found"),
+        (status_code = StatusCode::BAD_REQUEST, description = "Bad Request"),
+        (status_code = StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error"),
+    ),
+)]
+#[tracing::instrument(
+    name = "promotions.delete",
+    skip(uuid, depot),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        promotion_uuid = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    uuid: PathParam<Uuid>,
+    depot: &mut Depot,
+) -> Result<StatusCode, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let uuid = uuid.into_inner();
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("promotion_uuid", tracing::field::display(uuid));
+
+    state
+        .app
+        .promotions
+        .delete_promotion(tenant, uuid.into())
+        .await
+        .map_err(into_status_error)?;
+
+    tracing::info!(promotion_uuid = %uuid, "deleted promotion");
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::test::TestClient;
+    use testresult::TestResult;
+
+    use lattice_app::domain::promotions::{
+        PromotionsServiceError, records::PromotionUuid, service::MockPromotionsService,
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, promotions_service};
+
+    use super::*;
+
+    fn make_service(promotions: MockPromotionsService) -> Service {
+        promotions_service(
+            promotions,
+            Router::with_path("promotions/{uuid}").delete(handler),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_delete_promotion_success() -> TestResult {
+        let uuid = PromotionUuid::new();
+
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_delete_promotion()
+            .once()
+            .withf(move |tenant, u| *tenant == TEST_TENANT_UUID && *u == uuid)
+            .return_once(|_, _| Ok(()));
+
+        let res = TestClient::delete(format!("http://example.com/promotions/{uuid}"))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_promotion_invalid_uuid_returns_400() -> TestResult {
+        let res = TestClient::delete("http://example.com/promotions/123")
+            .send(&make_service(MockPromotionsService::new()))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_promotion_not_found_returns_404() -> TestResult {
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_delete_promotion()
+            .once()
+            .return_once(|_, _| Err(PromotionsServiceError::NotFound));
+
+        let res = TestClient::delete(format!(
+            "http://example.com/promotions/{}",
+            PromotionUuid::new()
+        ))
+        .send(&make_service(mock))
+        .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Get Promotion Handler
+
+use std::sync::Arc;
+
+use salvo::{
+    oapi::extract::{PathParam, QueryParam},
+    prelude::*,
+};
+use uuid::Uuid;
+
+use crate::{
+    extensions::*,
+    promotions::{errors::into_status_error, responses::PromotionResponse},
+    state::State,
+};
+
+/// Get Promotion Handler
+///
+/// Returns a promotion as it was defined at the given point in time.
+#[endpoint(
+    tags("promotions"),
+    summary = "Get Promotion",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(
+    name = "promotions.get",
+    skip(uuid, at, depot),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        promotion_uuid = tracing::field::Empty,
+        point_in_time = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    uuid: PathParam<Uuid>,
+    at: QueryParam<String, false>,
+    depot: &mut Depot,
+) -> Result<Json<PromotionResponse>, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let point_in_time = at.into_point_in_time()?;
+    let uuid = uuid.into_inner();
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("promotion_uuid", tracing::field::display(uuid));
+    span.record("point_in_time", tracing::field::display(point_in_time));
+
+    let promotion = state
+        .app
+        .promotions
+        .get_promotion(tenant, uuid.into(), point_in_time)
+        .await
+        .map_err(into_status_error)?;
+
+    tracing::info!(promotion_uuid = %promotion.uuid, "fetched promotion");
+
+    Ok(Json(promotion.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::{Value, json};
+    use testresult::TestResult;
+
+    use lattice_app::domain::promotions::{
+        PromotionsServiceError, records::PromotionUuid, service::MockPromotionsService,
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, make_promotion, promotions_service};
+
+    use super::*;
+
+    fn make_service(promotions: MockPromotionsService) -> Service {
+        promotions_service(
+            promotions,
+            Router::with_path("promotions/{uuid}").get(handler),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_returns_promotion() -> TestResult {
+        let uuid = PromotionUuid::new();
+        let promotion = make_promotion(uuid);
+
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_get_promotion()
+            .once()
+            .withf(move |tenant, u, _| *tenant == TEST_TENANT_UUID && *u == uuid)
+            .return_once(move |_, _, _| Ok(promotion));
+
+        let mut res = TestClient::get(format!("http://example.com/promotions/{uuid}"))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let body: Value = res.take_json().await?;
+
+        assert_eq!(body["uuid"], json!(uuid.into_uuid()));
+        assert_eq!(body["type"], "direct_discount");
+        assert_eq!(
+            body["discount"],
+            json!({ "type": "percentage_off", "percentage": 10 })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_promotion_returns_404() -> TestResult {
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_get_promotion()
+            .once()
+            .return_once(|_, _, _| Err(PromotionsServiceError::NotFound));
+
+        let res = TestClient::get(format!(
+            "http://example.com/promotions/{}",
+            PromotionUuid::new()
+        ))
+        .send(&make_service(mock))
+        .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_forwards_point_in_time_query_param() -> TestResult {
+        let uuid = PromotionUuid::new();
+        let at: Timestamp = "2026-02-21T12:00:00Z".parse()?;
+        let promotion = make_promotion(uuid);
+
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_get_promotion()
+            .once()
+            .withf(move |tenant, u, point_in_time| {
+                *tenant == TEST_TENANT_UUID && *u == uuid && *point_in_time == at
+            })
+            .return_once(move |_, _, _| Ok(promotion));
+
+        let res = TestClient::get(format!(
+            "http://example.com/promotions/{uuid}?at=2026-02-21T12:00:00Z"
+        ))
+        .send(&make_service(mock))
+        .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        Ok(())
+    }
+}
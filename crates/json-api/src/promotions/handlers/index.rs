@@ -0,0 +1,155 @@
+//! Promotion Index Handler
+
+use std::sync::Arc;
+
+use salvo::{
+    oapi::{ToSchema, extract::QueryParam},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extensions::*,
+    promotions::{errors::into_status_error, responses::PromotionResponse},
+    state::State,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct PromotionsResponse {
+    /// The list of promotions
+    pub promotions: Vec<PromotionResponse>,
+}
+
+/// Promotion Index Handler
+///
+/// Returns the promotions live at the given point in time.
+#[endpoint(
+    tags("promotions"),
+    summary = "List Promotions",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(
+    name = "promotions.list",
+    skip(at, depot),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        point_in_time = tracing::field::Empty,
+        promotion_count = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    at: QueryParam<String, false>,
+    depot: &mut Depot,
+) -> Result<Json<PromotionsResponse>, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let point_in_time = at.into_point_in_time()?;
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("point_in_time", tracing::field::display(point_in_time));
+
+    let promotions = state
+        .app
+        .promotions
+        .list_promotions(tenant, point_in_time)
+        .await
+        .map_err(into_status_error)?;
+
+    let promotion_count = promotions.len();
+
+    span.record("promotion_count", tracing::field::display(promotion_count));
+
+    tracing::info!(promotion_count, "listed promotions");
+
+    Ok(Json(PromotionsResponse {
+        promotions: promotions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use salvo::test::{ResponseExt, TestClient};
+    use testresult::TestResult;
+
+    use lattice_app::domain::promotions::{
+        PromotionsServiceError, records::PromotionUuid, service::MockPromotionsService,
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, make_promotion, promotions_service};
+
+    use super::*;
+
+    fn make_service(promotions: MockPromotionsService) -> Service {
+        promotions_service(promotions, Router::with_path("promotions").get(handler))
+    }
+
+    #[tokio::test]
+    async fn test_index_returns_promotions() -> TestResult {
+        let uuid_a = PromotionUuid::new();
+        let uuid_b = PromotionUuid::new();
+
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_list_promotions()
+            .once()
+            .withf(|tenant, _| *tenant == TEST_TENANT_UUID)
+            .return_once(move |_, _| Ok(vec![make_promotion(uuid_a), make_promotion(uuid_b)]));
+
+        let response: PromotionsResponse = TestClient::get("http://example.com/promotions")
+            .send(&make_service(mock))
+            .await
+            .take_json()
+            .await?;
+
+        assert_eq!(
+            response
+                .promotions
+                .iter()
+                .map(|promotion| promotion.uuid)
+                .collect::<Vec<_>>(),
+            vec![uuid_a.into_uuid(), uuid_b.into_uuid()]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_service_error_returns_400() -> TestResult {
+        let mut mock = MockPromotionsService::new();
+
+        mock.expect_list_promotions()
+            .once()
+            .return_once(|_, _| Err(PromotionsServiceError::InvalidData));
+
+        let res = TestClient::get("http://example.com/promotions")
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_forwards_point_in_time_query_param() -> TestResult {
+        let mut mock = MockPromotionsService::new();
+        let at: Timestamp = "2026-02-21T12:00:00Z".parse()?;
+
+        mock.expect_list_promotions()
+            .once()
+            .withf(move |tenant, point_in_time| *tenant == TEST_TENANT_UUID && *point_in_time == at)
+            .return_once(|_, _| Ok(vec![]));
+
+        let res = TestClient::get("http://example.com/promotions?at=2026-02-21T12:00:00Z")
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 //! Promotion Handlers
 
 pub(crate) mod create;
+pub(crate) mod delete;
+pub(crate) mod get;
+pub(crate) mod index;
 pub(crate) mod update;
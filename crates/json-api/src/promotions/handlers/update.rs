@@ -60,6 +60,18 @@ pub(crate) async fn handler(
         UpdatePromotionRequest::DirectDiscount { .. } => {
             span.record("promotion_type", tracing::field::display("direct"));
         }
+        UpdatePromotionRequest::PositionalDiscount { .. } => {
+            span.record("promotion_type", tracing::field::display("positional"));
+        }
+        UpdatePromotionRequest::MixAndMatch { .. } => {
+            span.record("promotion_type", tracing::field::display("mix_and_match"));
+        }
+        UpdatePromotionRequest::TieredThreshold { .. } => {
+            span.record(
+                "promotion_type",
+                tracing::field::display("tiered_threshold"),
+            );
+        }
     }
 
     state
@@ -3,7 +3,9 @@
 use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 
-use lattice_app::domain::promotions::data::discounts::SimpleDiscount;
+use lattice_app::domain::promotions::data::discounts::{
+    MixAndMatchDiscount, SimpleDiscount, ThresholdDiscount,
+};
 
 /// Simple Discount Request
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -25,3 +27,85 @@ impl From<SimpleDiscountRequest> for SimpleDiscount {
         }
     }
 }
+
+/// Mix and Match Discount Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MixAndMatchDiscountRequest {
+    PercentAllItems { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl From<MixAndMatchDiscountRequest> for MixAndMatchDiscount {
+    fn from(request: MixAndMatchDiscountRequest) -> Self {
+        match request {
+            MixAndMatchDiscountRequest::PercentAllItems { percentage } => {
+                MixAndMatchDiscount::PercentAllItems { percentage }
+            }
+            MixAndMatchDiscountRequest::AmountOffEachItem { amount } => {
+                MixAndMatchDiscount::AmountOffEachItem { amount }
+            }
+            MixAndMatchDiscountRequest::FixedPriceEachItem { amount } => {
+                MixAndMatchDiscount::FixedPriceEachItem { amount }
+            }
+            MixAndMatchDiscountRequest::AmountOffTotal { amount } => {
+                MixAndMatchDiscount::AmountOffTotal { amount }
+            }
+            MixAndMatchDiscountRequest::FixedTotal { amount } => {
+                MixAndMatchDiscount::FixedTotal { amount }
+            }
+            MixAndMatchDiscountRequest::PercentCheapest { percentage } => {
+                MixAndMatchDiscount::PercentCheapest { percentage }
+            }
+            MixAndMatchDiscountRequest::FixedCheapest { amount } => {
+                MixAndMatchDiscount::FixedCheapest { amount }
+            }
+        }
+    }
+}
+
+/// Threshold Discount Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThresholdDiscountRequest {
+    PercentEachItem { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl From<ThresholdDiscountRequest> for ThresholdDiscount {
+    fn from(request: ThresholdDiscountRequest) -> Self {
+        match request {
+            ThresholdDiscountRequest::PercentEachItem { percentage } => {
+                ThresholdDiscount::PercentEachItem { percentage }
+            }
+            ThresholdDiscountRequest::AmountOffEachItem { amount } => {
+                ThresholdDiscount::AmountOffEachItem { amount }
+            }
+            ThresholdDiscountRequest::FixedPriceEachItem { amount } => {
+                ThresholdDiscount::FixedPriceEachItem { amount }
+            }
+            ThresholdDiscountRequest::AmountOffTotal { amount } => {
+                ThresholdDiscount::AmountOffTotal { amount }
+            }
+            ThresholdDiscountRequest::FixedTotal { amount } => {
+                ThresholdDiscount::FixedTotal { amount }
+            }
+            ThresholdDiscountRequest::PercentCheapest { percentage } => {
+                ThresholdDiscount::PercentCheapest { percentage }
+            }
+            ThresholdDiscountRequest::FixedCheapest { amount } => {
+                ThresholdDiscount::FixedCheapest { amount }
+            }
+        }
+    }
+}
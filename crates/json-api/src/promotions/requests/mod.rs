@@ -6,16 +6,22 @@ use lattice_app::domain::promotions::{
 };
 use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 use uuid::Uuid;
 
 use crate::promotions::requests::{
-    budgets::BudgetsRequest, discounts::SimpleDiscountRequest,
+    budgets::BudgetsRequest,
+    discounts::{MixAndMatchDiscountRequest, SimpleDiscountRequest},
     qualification::CreateQualificationRequest,
+    slots::MixAndMatchSlotRequest,
+    tiers::ThresholdTierRequest,
 };
 
 pub(crate) mod budgets;
 pub(crate) mod discounts;
 pub(crate) mod qualification;
+pub(crate) mod slots;
+pub(crate) mod tiers;
 
 /// Create Promotion Request
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -27,6 +33,25 @@ pub enum CreatePromotionRequest {
         discount: SimpleDiscountRequest,
         qualification: Option<CreateQualificationRequest>,
     },
+    PositionalDiscount {
+        uuid: Uuid,
+        budgets: BudgetsRequest,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscountRequest,
+        qualification: Option<CreateQualificationRequest>,
+    },
+    MixAndMatch {
+        uuid: Uuid,
+        budgets: BudgetsRequest,
+        slots: Vec<MixAndMatchSlotRequest>,
+        discount: MixAndMatchDiscountRequest,
+    },
+    TieredThreshold {
+        uuid: Uuid,
+        budgets: BudgetsRequest,
+        tiers: Vec<ThresholdTierRequest>,
+    },
 }
 
 impl From<CreatePromotionRequest> for NewPromotion {
@@ -43,6 +68,41 @@ impl From<CreatePromotionRequest> for NewPromotion {
                 discount: discount.into(),
                 qualification: qualification.map(Into::into),
             },
+            CreatePromotionRequest::PositionalDiscount {
+                uuid,
+                budgets,
+                size,
+                positions,
+                discount,
+                qualification,
+            } => NewPromotion::PositionalDiscount {
+                uuid: PromotionUuid::from_uuid(uuid),
+                budgets: budgets.into(),
+                size,
+                positions,
+                discount: discount.into(),
+                qualification: qualification.map(Into::into),
+            },
+            CreatePromotionRequest::MixAndMatch {
+                uuid,
+                budgets,
+                slots,
+                discount,
+            } => NewPromotion::MixAndMatch {
+                uuid: PromotionUuid::from_uuid(uuid),
+                budgets: budgets.into(),
+                slots: slots.into_iter().map(Into::into).collect(),
+                discount: discount.into(),
+            },
+            CreatePromotionRequest::TieredThreshold {
+                uuid,
+                budgets,
+                tiers,
+            } => NewPromotion::TieredThreshold {
+                uuid: PromotionUuid::from_uuid(uuid),
+                budgets: budgets.into(),
+                tiers: tiers.into_iter().map(Into::into).collect(),
+            },
         }
     }
 }
@@ -56,6 +116,22 @@ pub enum UpdatePromotionRequest {
         discount: SimpleDiscountRequest,
         qualification: Option<CreateQualificationRequest>,
     },
+    PositionalDiscount {
+        budgets: BudgetsRequest,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscountRequest,
+        qualification: Option<CreateQualificationRequest>,
+    },
+    MixAndMatch {
+        budgets: BudgetsRequest,
+        slots: Vec<MixAndMatchSlotRequest>,
+        discount: MixAndMatchDiscountRequest,
+    },
+    TieredThreshold {
+        budgets: BudgetsRequest,
+        tiers: Vec<ThresholdTierRequest>,
+    },
 }
 
 impl From<UpdatePromotionRequest> for PromotionUpdate {
@@ -70,6 +146,34 @@ impl From<UpdatePromotionRequest> for PromotionUpdate {
                 discount: discount.into(),
                 qualification: qualification.map(Into::into),
             },
+            UpdatePromotionRequest::PositionalDiscount {
+                budgets,
+                size,
+                positions,
+                discount,
+                qualification,
+            } => PromotionUpdate::PositionalDiscount {
+                budgets: budgets.into(),
+                size,
+                positions,
+                discount: discount.into(),
+                qualification: qualification.map(Into::into),
+            },
+            UpdatePromotionRequest::MixAndMatch {
+                budgets,
+                slots,
+                discount,
+            } => PromotionUpdate::MixAndMatch {
+                budgets: budgets.into(),
+                slots: slots.into_iter().map(Into::into).collect(),
+                discount: discount.into(),
+            },
+            UpdatePromotionRequest::TieredThreshold { budgets, tiers } => {
+                PromotionUpdate::TieredThreshold {
+                    budgets: budgets.into(),
+                    tiers: tiers.into_iter().map(Into::into).collect(),
+                }
+            }
         }
     }
 }
@@ -82,8 +186,12 @@ mod tests {
     use testresult::TestResult;
     use uuid::Uuid;
 
-    use crate::promotions::requests::qualification::{
-        CreateQualificationRuleRequest, QualificationContextRequest, QualificationOpRequest,
+    use crate::promotions::requests::{
+        discounts::ThresholdDiscountRequest,
+        qualification::{
+            CreateQualificationRuleRequest, QualificationContextRequest, QualificationOpRequest,
+        },
+        tiers::TierThresholdRequest,
     };
 
     use super::*;
@@ -204,4 +312,296 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn create_positional_discount_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "positional_discount",
+                "uuid": "019c8e08-0000-7000-8000-000000000002",
+                "budgets": {},
+                "size": 3,
+                "positions": [2],
+                "discount": {
+                    "type": "percentage_off",
+                    "percentage": 100
+                }
+            }
+        "#;
+
+        let request: CreatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            CreatePromotionRequest::PositionalDiscount {
+                uuid: Uuid::from_str("019c8e08-0000-7000-8000-000000000002")?,
+                budgets: BudgetsRequest {
+                    redemptions: None,
+                    monetary: None,
+                },
+                size: 3,
+                positions: smallvec![2],
+                discount: SimpleDiscountRequest::PercentageOff { percentage: 100 },
+                qualification: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_positional_discount_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "positional_discount",
+                "budgets": {
+                    "redemptions": 10
+                },
+                "size": 2,
+                "positions": [1],
+                "discount": {
+                    "type": "percentage_off",
+                    "percentage": 50
+                }
+            }
+        "#;
+
+        let request: UpdatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            UpdatePromotionRequest::PositionalDiscount {
+                budgets: BudgetsRequest {
+                    redemptions: Some(10),
+                    monetary: None,
+                },
+                size: 2,
+                positions: smallvec![1],
+                discount: SimpleDiscountRequest::PercentageOff { percentage: 50 },
+                qualification: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_mix_and_match_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "mix_and_match",
+                "uuid": "019c8e08-0000-7000-8000-000000000003",
+                "budgets": {},
+                "slots": [
+                    {
+                        "qualification": {
+                            "op": "and",
+                            "rules": [{ "type": "has_any", "tags": ["main"] }]
+                        },
+                        "min": 1,
+                        "max": 1
+                    },
+                    {
+                        "min": 1
+                    }
+                ],
+                "discount": {
+                    "type": "fixed_total",
+                    "amount": 500
+                }
+            }
+        "#;
+
+        let request: CreatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            CreatePromotionRequest::MixAndMatch {
+                uuid: Uuid::from_str("019c8e08-0000-7000-8000-000000000003")?,
+                budgets: BudgetsRequest {
+                    redemptions: None,
+                    monetary: None,
+                },
+                slots: vec![
+                    MixAndMatchSlotRequest {
+                        qualification: Some(CreateQualificationRequest {
+                            context: QualificationContextRequest::Primary,
+                            op: QualificationOpRequest::And,
+                            rules: vec![CreateQualificationRuleRequest::HasAny {
+                                tags: smallvec!["main".to_string()]
+                            }],
+                        }),
+                        min: 1,
+                        max: Some(1),
+                    },
+                    MixAndMatchSlotRequest {
+                        qualification: None,
+                        min: 1,
+                        max: None,
+                    },
+                ],
+                discount: MixAndMatchDiscountRequest::FixedTotal { amount: 500 },
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_mix_and_match_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "mix_and_match",
+                "budgets": {},
+                "slots": [{ "min": 2, "max": 2 }],
+                "discount": {
+                    "type": "percent_cheapest",
+                    "percentage": 100
+                }
+            }
+        "#;
+
+        let request: UpdatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            UpdatePromotionRequest::MixAndMatch {
+                budgets: BudgetsRequest {
+                    redemptions: None,
+                    monetary: None,
+                },
+                slots: vec![MixAndMatchSlotRequest {
+                    qualification: None,
+                    min: 2,
+                    max: Some(2),
+                }],
+                discount: MixAndMatchDiscountRequest::PercentCheapest { percentage: 100 },
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_tiered_threshold_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "tiered_threshold",
+                "uuid": "019c8e08-0000-7000-8000-000000000004",
+                "budgets": {},
+                "tiers": [
+                    {
+                        "lower_threshold": { "monetary": 5000 },
+                        "upper_threshold": { "monetary": 10000 },
+                        "discount": {
+                            "type": "percent_each_item",
+                            "percentage": 10
+                        }
+                    },
+                    {
+                        "lower_threshold": { "monetary": 10000 },
+                        "discount_qualification": {
+                            "op": "and",
+                            "rules": [{ "type": "has_none", "tags": ["gift-card"] }]
+                        },
+                        "discount": {
+                            "type": "percent_each_item",
+                            "percentage": 20
+                        }
+                    }
+                ]
+            }
+        "#;
+
+        let request: CreatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            CreatePromotionRequest::TieredThreshold {
+                uuid: Uuid::from_str("019c8e08-0000-7000-8000-000000000004")?,
+                budgets: BudgetsRequest {
+                    redemptions: None,
+                    monetary: None,
+                },
+                tiers: vec![
+                    ThresholdTierRequest {
+                        lower_threshold: TierThresholdRequest {
+                            monetary: Some(5000),
+                            items: None,
+                        },
+                        upper_threshold: Some(TierThresholdRequest {
+                            monetary: Some(10000),
+                            items: None,
+                        }),
+                        contribution_qualification: None,
+                        discount_qualification: None,
+                        discount: ThresholdDiscountRequest::PercentEachItem { percentage: 10 },
+                    },
+                    ThresholdTierRequest {
+                        lower_threshold: TierThresholdRequest {
+                            monetary: Some(10000),
+                            items: None,
+                        },
+                        upper_threshold: None,
+                        contribution_qualification: None,
+                        discount_qualification: Some(CreateQualificationRequest {
+                            context: QualificationContextRequest::Primary,
+                            op: QualificationOpRequest::And,
+                            rules: vec![CreateQualificationRuleRequest::HasNone {
+                                tags: smallvec!["gift-card".to_string()]
+                            }],
+                        }),
+                        discount: ThresholdDiscountRequest::PercentEachItem { percentage: 20 },
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_tiered_threshold_promotion_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "type": "tiered_threshold",
+                "budgets": {
+                    "monetary": 100000
+                },
+                "tiers": [
+                    {
+                        "lower_threshold": { "items": 3 },
+                        "discount": {
+                            "type": "amount_off_total",
+                            "amount": 500
+                        }
+                    }
+                ]
+            }
+        "#;
+
+        let request: UpdatePromotionRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            UpdatePromotionRequest::TieredThreshold {
+                budgets: BudgetsRequest {
+                    redemptions: None,
+                    monetary: Some(100_000),
+                },
+                tiers: vec![ThresholdTierRequest {
+                    lower_threshold: TierThresholdRequest {
+                        monetary: None,
+                        items: Some(3),
+                    },
+                    upper_threshold: None,
+                    contribution_qualification: None,
+                    discount_qualification: None,
+                    discount: ThresholdDiscountRequest::AmountOffTotal { amount: 500 },
+                }],
+            }
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,29 @@
+//! Promotion Slot Requests
+
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+use lattice_app::domain::promotions::data::slots::MixAndMatchSlot;
+
+use crate::promotions::requests::qualification::CreateQualificationRequest;
+
+/// Mix and Match Slot Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct MixAndMatchSlotRequest {
+    pub qualification: Option<CreateQualificationRequest>,
+
+    #[serde(default)]
+    pub min: u32,
+
+    pub max: Option<u32>,
+}
+
+impl From<MixAndMatchSlotRequest> for MixAndMatchSlot {
+    fn from(request: MixAndMatchSlotRequest) -> Self {
+        MixAndMatchSlot {
+            qualification: request.qualification.map(Into::into),
+            min: request.min,
+            max: request.max,
+        }
+    }
+}
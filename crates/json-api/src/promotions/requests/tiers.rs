@@ -0,0 +1,48 @@
+//! Promotion Tier Requests
+
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+use lattice_app::domain::promotions::data::tiers::{ThresholdTier, TierThreshold};
+
+use crate::promotions::requests::{
+    discounts::ThresholdDiscountRequest, qualification::CreateQualificationRequest,
+};
+
+/// Tier Threshold Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct TierThresholdRequest {
+    pub monetary: Option<u64>,
+    pub items: Option<u32>,
+}
+
+impl From<TierThresholdRequest> for TierThreshold {
+    fn from(request: TierThresholdRequest) -> Self {
+        TierThreshold {
+            monetary: request.monetary,
+            items: request.items,
+        }
+    }
+}
+
+/// Threshold Tier Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct ThresholdTierRequest {
+    pub lower_threshold: TierThresholdRequest,
+    pub upper_threshold: Option<TierThresholdRequest>,
+    pub contribution_qualification: Option<CreateQualificationRequest>,
+    pub discount_qualification: Option<CreateQualificationRequest>,
+    pub discount: ThresholdDiscountRequest,
+}
+
+impl From<ThresholdTierRequest> for ThresholdTier {
+    fn from(request: ThresholdTierRequest) -> Self {
+        ThresholdTier {
+            lower_threshold: request.lower_threshold.into(),
+            upper_threshold: request.upper_threshold.map(Into::into),
+            contribution_qualification: request.contribution_qualification.map(Into::into),
+            discount_qualification: request.discount_qualification.map(Into::into),
+            discount: request.discount.into(),
+        }
+    }
+}
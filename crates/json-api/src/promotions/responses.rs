@@ -0,0 +1,388 @@
+//! Promotions Responses
+
+use std::string::ToString;
+
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use uuid::Uuid;
+
+use lattice_app::domain::promotions::{
+    data::{
+        PromotionDetails,
+        budgets::Budgets,
+        discounts::{MixAndMatchDiscount, SimpleDiscount, ThresholdDiscount},
+        qualification::{Qualification, QualificationContext, QualificationOp, QualificationRule},
+        slots::MixAndMatchSlotDetails,
+        tiers::{ThresholdTierDetails, TierThreshold},
+    },
+    records::PromotionDetailsRecord,
+};
+
+/// Promotion Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct PromotionResponse {
+    /// The unique identifier of the promotion
+    pub uuid: Uuid,
+
+    /// The unique identifier of the promotion's detail version
+    pub detail_uuid: Uuid,
+
+    /// The promotion's type-specific definition
+    #[serde(flatten)]
+    pub details: PromotionDetailsResponse,
+
+    /// The date and time the promotion was created
+    pub created_at: String,
+
+    /// The date and time the promotion was last updated
+    pub updated_at: String,
+
+    /// The date and time the promotion was deleted
+    pub deleted_at: Option<String>,
+}
+
+impl From<PromotionDetailsRecord> for PromotionResponse {
+    fn from(promotion: PromotionDetailsRecord) -> Self {
+        PromotionResponse {
+            uuid: promotion.uuid.into(),
+            detail_uuid: promotion.details.uuid(),
+            details: promotion.details.into(),
+            created_at: promotion.created_at.to_string(),
+            updated_at: promotion.updated_at.to_string(),
+            deleted_at: promotion.deleted_at.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// Promotion Details Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum PromotionDetailsResponse {
+    DirectDiscount {
+        budgets: BudgetsResponse,
+        discount: SimpleDiscountResponse,
+        qualification: Option<QualificationResponse>,
+    },
+    PositionalDiscount {
+        budgets: BudgetsResponse,
+        size: u16,
+        positions: SmallVec<[u16; 5]>,
+        discount: SimpleDiscountResponse,
+        qualification: Option<QualificationResponse>,
+    },
+    MixAndMatch {
+        budgets: BudgetsResponse,
+        slots: Vec<MixAndMatchSlotResponse>,
+        discount: MixAndMatchDiscountResponse,
+    },
+    TieredThreshold {
+        budgets: BudgetsResponse,
+        tiers: Vec<ThresholdTierResponse>,
+    },
+}
+
+impl From<PromotionDetails> for PromotionDetailsResponse {
+    fn from(details: PromotionDetails) -> Self {
+        match details {
+            PromotionDetails::DirectDiscount {
+                budgets,
+                discount,
+                qualification,
+                ..
+            } => PromotionDetailsResponse::DirectDiscount {
+                budgets: budgets.into(),
+                discount: discount.into(),
+                qualification: qualification.map(Into::into),
+            },
+            PromotionDetails::PositionalDiscount {
+                budgets,
+                size,
+                positions,
+                discount,
+                qualification,
+                ..
+            } => PromotionDetailsResponse::PositionalDiscount {
+                budgets: budgets.into(),
+                size,
+                positions,
+                discount: discount.into(),
+                qualification: qualification.map(Into::into),
+            },
+            PromotionDetails::MixAndMatch {
+                budgets,
+                slots,
+                discount,
+                ..
+            } => PromotionDetailsResponse::MixAndMatch {
+                budgets: budgets.into(),
+                slots: slots.into_iter().map(Into::into).collect(),
+                discount: discount.into(),
+            },
+            PromotionDetails::TieredThreshold { budgets, tiers, .. } => {
+                PromotionDetailsResponse::TieredThreshold {
+                    budgets: budgets.into(),
+                    tiers: tiers.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+    }
+}
+
+/// Budgets Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct BudgetsResponse {
+    pub redemptions: Option<u64>,
+    pub monetary: Option<u64>,
+}
+
+impl From<Budgets> for BudgetsResponse {
+    fn from(budgets: Budgets) -> Self {
+        BudgetsResponse {
+            redemptions: budgets.redemptions,
+            monetary: budgets.monetary,
+        }
+    }
+}
+
+/// Simple Discount Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum SimpleDiscountResponse {
+    PercentageOff { percentage: u16 },
+    FixedAmountOff { amount: u64 },
+}
+
+impl From<SimpleDiscount> for SimpleDiscountResponse {
+    fn from(discount: SimpleDiscount) -> Self {
+        match discount {
+            SimpleDiscount::PercentageOff { percentage } => {
+                SimpleDiscountResponse::PercentageOff { percentage }
+            }
+            SimpleDiscount::FixedAmountOff { amount } => {
+                SimpleDiscountResponse::FixedAmountOff { amount }
+            }
+        }
+    }
+}
+
+/// Mix and Match Discount Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum MixAndMatchDiscountResponse {
+    PercentAllItems { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl From<MixAndMatchDiscount> for MixAndMatchDiscountResponse {
+    fn from(discount: MixAndMatchDiscount) -> Self {
+        match discount {
+            MixAndMatchDiscount::PercentAllItems { percentage } => {
+                MixAndMatchDiscountResponse::PercentAllItems { percentage }
+            }
+            MixAndMatchDiscount::AmountOffEachItem { amount } => {
+                MixAndMatchDiscountResponse::AmountOffEachItem { amount }
+            }
+            MixAndMatchDiscount::FixedPriceEachItem { amount } => {
+                MixAndMatchDiscountResponse::FixedPriceEachItem { amount }
+            }
+            MixAndMatchDiscount::AmountOffTotal { amount } => {
+                MixAndMatchDiscountResponse::AmountOffTotal { amount }
+            }
+            MixAndMatchDiscount::FixedTotal { amount } => {
+                MixAndMatchDiscountResponse::FixedTotal { amount }
+            }
+            MixAndMatchDiscount::PercentCheapest { percentage } => {
+                MixAndMatchDiscountResponse::PercentCheapest { percentage }
+            }
+            MixAndMatchDiscount::FixedCheapest { amount } => {
+                MixAndMatchDiscountResponse::FixedCheapest { amount }
+            }
+        }
+    }
+}
+
+/// Threshold Discount Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ThresholdDiscountResponse {
+    PercentEachItem { percentage: u16 },
+    AmountOffEachItem { amount: u64 },
+    FixedPriceEachItem { amount: u64 },
+    AmountOffTotal { amount: u64 },
+    FixedTotal { amount: u64 },
+    PercentCheapest { percentage: u16 },
+    FixedCheapest { amount: u64 },
+}
+
+impl From<ThresholdDiscount> for ThresholdDiscountResponse {
+    fn from(discount: ThresholdDiscount) -> Self {
+        match discount {
+            ThresholdDiscount::PercentEachItem { percentage } => {
+                ThresholdDiscountResponse::PercentEachItem { percentage }
+            }
+            ThresholdDiscount::AmountOffEachItem { amount } => {
+                ThresholdDiscountResponse::AmountOffEachItem { amount }
+            }
+            ThresholdDiscount::FixedPriceEachItem { amount } => {
+                ThresholdDiscountResponse::FixedPriceEachItem { amount }
+            }
+            ThresholdDiscount::AmountOffTotal { amount } => {
+                ThresholdDiscountResponse::AmountOffTotal { amount }
+            }
+            ThresholdDiscount::FixedTotal { amount } => {
+                ThresholdDiscountResponse::FixedTotal { amount }
+            }
+            ThresholdDiscount::PercentCheapest { percentage } => {
+                ThresholdDiscountResponse::PercentCheapest { percentage }
+            }
+            ThresholdDiscount::FixedCheapest { amount } => {
+                ThresholdDiscountResponse::FixedCheapest { amount }
+            }
+        }
+    }
+}
+
+/// Qualification Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct QualificationResponse {
+    pub context: QualificationContextResponse,
+    pub op: QualificationOpResponse,
+    pub rules: Vec<QualificationRuleResponse>,
+}
+
+impl From<Qualification> for QualificationResponse {
+    fn from(qualification: Qualification) -> Self {
+        QualificationResponse {
+            context: qualification.context.into(),
+            op: qualification.op.into(),
+            rules: qualification.rules.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Qualification Context Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum QualificationContextResponse {
+    Primary,
+    Group,
+}
+
+impl From<QualificationContext> for QualificationContextResponse {
+    fn from(context: QualificationContext) -> Self {
+        match context {
+            QualificationContext::Primary => QualificationContextResponse::Primary,
+            QualificationContext::Group => QualificationContextResponse::Group,
+        }
+    }
+}
+
+/// Qualification Operation Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum QualificationOpResponse {
+    And,
+    Or,
+}
+
+impl From<QualificationOp> for QualificationOpResponse {
+    fn from(op: QualificationOp) -> Self {
+        match op {
+            QualificationOp::And => QualificationOpResponse::And,
+            QualificationOp::Or => QualificationOpResponse::Or,
+        }
+    }
+}
+
+/// Qualification Rule Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum QualificationRuleResponse {
+    HasAll {
+        tags: SmallVec<[String; 3]>,
+    },
+    HasAny {
+        tags: SmallVec<[String; 3]>,
+    },
+    HasNone {
+        tags: SmallVec<[String; 3]>,
+    },
+    Group {
+        qualification: QualificationResponse,
+    },
+}
+
+impl From<QualificationRule> for QualificationRuleResponse {
+    fn from(rule: QualificationRule) -> Self {
+        match rule {
+            QualificationRule::HasAll { tags } => QualificationRuleResponse::HasAll { tags },
+            QualificationRule::HasAny { tags } => QualificationRuleResponse::HasAny { tags },
+            QualificationRule::HasNone { tags } => QualificationRuleResponse::HasNone { tags },
+            QualificationRule::Group { qualification } => QualificationRuleResponse::Group {
+                qualification: qualification.into(),
+            },
+        }
+    }
+}
+
+/// Mix and Match Slot Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct MixAndMatchSlotResponse {
+    pub qualification: Option<QualificationResponse>,
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+impl From<MixAndMatchSlotDetails> for MixAndMatchSlotResponse {
+    fn from(details: MixAndMatchSlotDetails) -> Self {
+        MixAndMatchSlotResponse {
+            qualification: details.slot.qualification.map(Into::into),
+            min: details.slot.min,
+            max: details.slot.max,
+        }
+    }
+}
+
+/// Tier Threshold Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct TierThresholdResponse {
+    pub monetary: Option<u64>,
+    pub items: Option<u32>,
+}
+
+impl From<TierThreshold> for TierThresholdResponse {
+    fn from(threshold: TierThreshold) -> Self {
+        TierThresholdResponse {
+            monetary: threshold.monetary,
+            items: threshold.items,
+        }
+    }
+}
+
+/// Threshold Tier Response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub(crate) struct ThresholdTierResponse {
+    pub lower_threshold: TierThresholdResponse,
+    pub upper_threshold: Option<TierThresholdResponse>,
+    pub contribution_qualification: Option<QualificationResponse>,
+    pub discount_qualification: Option<QualificationResponse>,
+    pub discount: ThresholdDiscountResponse,
+}
+
+impl From<ThresholdTierDetails> for ThresholdTierResponse {
+    fn from(details: ThresholdTierDetails) -> Self {
+        ThresholdTierResponse {
+            lower_threshold: details.tier.lower_threshold.into(),
+            upper_threshold: details.tier.upper_threshold.map(Into::into),
+            contribution_qualification: details.tier.contribution_qualification.map(Into::into),
+            discount_qualification: details.tier.discount_qualification.map(Into::into),
+            discount: details.tier.discount.into(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Quote Errors
+
+use salvo::http::StatusError;
+use tracing::error;
+
+use lattice_app::domain::{pricing::PricingError, quotes::QuotesServiceError};
+
+pub(crate) fn into_status_error(error: QuotesServiceError) -> StatusError {
+    match error {
+        QuotesServiceError::InvalidReference => {
+            StatusError::bad_request().brief("Quote references an unknown product")
+        }
+        QuotesServiceError::Pricing(PricingError::AmountOutOfRange) => {
+            StatusError::bad_request().brief("Quote amounts are out of range")
+        }
+        QuotesServiceError::Sql(source) => {
+            error!("failed to fetch quote data: {source}");
+
+            StatusError::internal_server_error()
+        }
+        QuotesServiceError::Pricing(source) => {
+            error!("failed to price quote: {source}");
+
+            StatusError::internal_server_error()
+        }
+    }
+}
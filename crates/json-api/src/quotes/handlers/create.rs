@@ -0,0 +1,225 @@
+//! Create Quote Handler
+
+use std::sync::Arc;
+
+use salvo::{Depot, oapi::extract::JsonBody, prelude::*};
+
+use crate::{
+    extensions::*,
+    quotes::{errors::into_status_error, requests::CreateQuoteRequest, responses::QuoteResponse},
+    state::State,
+};
+
+/// Create Quote Handler
+///
+/// Prices a basket against the tenant's promotion stack without creating a cart.
+#[endpoint(
+    tags("quotes"),
+    summary = "Create Quote",
+    security(("bearer_auth" = [])),
+    responses(
+        (status_code = StatusCode::OK, description = "Quote priced"),
+        (status_code = StatusCode::BAD_REQUEST, description = "Bad Request"),
+        (status_code = StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error"),
+    ),
+)]
+#[tracing::instrument(
+    name = "quotes.create",
+    skip(json, depot),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        item_count = tracing::field::Empty,
+        point_in_time = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    json: JsonBody<CreateQuoteRequest>,
+    depot: &mut Depot,
+) -> Result<Json<QuoteResponse>, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let mut request = json.into_inner();
+    let point_in_time = request.at.take().into_point_in_time()?;
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("item_count", request.items.len());
+    span.record("point_in_time", tracing::field::display(point_in_time));
+
+    let quote = state
+        .app
+        .quotes
+        .create_quote(tenant, request.into(), point_in_time)
+        .await
+        .map_err(into_status_error)?;
+
+    tracing::info!(
+        subtotal = quote.subtotal,
+        total = quote.total,
+        "created quote"
+    );
+
+    Ok(Json(quote.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::json;
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use lattice_app::domain::{
+        pricing::data::Redemption,
+        products::records::ProductUuid,
+        promotions::records::PromotionUuid,
+        quotes::{
+            MockQuotesService, QuotesServiceError,
+            data::{NewQuote, NewQuoteItem},
+            records::{QuoteItemRecord, QuoteRecord},
+        },
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, quotes_service};
+
+    use super::*;
+
+    fn make_service(quotes: MockQuotesService) -> Service {
+        quotes_service(quotes, Router::with_path("quotes").post(handler))
+    }
+
+    #[tokio::test]
+    async fn test_create_quote_success() -> TestResult {
+        let product_uuid = ProductUuid::new();
+        let promotion_uuid = PromotionUuid::new();
+
+        let mut mock = MockQuotesService::new();
+
+        mock.expect_create_quote()
+            .once()
+            .withf(move |tenant, quote, _| {
+                *tenant == TEST_TENANT_UUID
+                    && *quote
+                        == NewQuote {
+                            items: vec![
+                                NewQuoteItem::Product { product_uuid },
+                                NewQuoteItem::Inline {
+                                    price: 300,
+                                    tags: smallvec!["sale".to_string()],
+                                },
+                            ],
+                        }
+            })
+            .return_once(move |_, _, _| {
+                Ok(QuoteRecord {
+                    subtotal: 400,
+                    total: 250,
+                    items: vec![
+                        QuoteItemRecord {
+                            product_uuid: Some(product_uuid),
+                            price: 100,
+                            tags: smallvec![],
+                            redemptions: Vec::new(),
+                        },
+                        QuoteItemRecord {
+                            product_uuid: None,
+                            price: 300,
+                            tags: smallvec!["sale".to_string()],
+                            redemptions: vec![Redemption {
+                                promotion_uuid,
+                                redemption_idx: 0,
+                                original_price: 300,
+                                final_price: 150,
+                            }],
+                        },
+                    ],
+                })
+            });
+
+        let mut res = TestClient::post("http://example.com/quotes")
+            .json(&json!({
+                "items": [
+                    { "type": "product", "product_uuid": product_uuid.into_uuid() },
+                    { "type": "inline", "price": 300, "tags": ["sale"] }
+                ]
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let body: QuoteResponse = res.take_json().await?;
+
+        assert_eq!(body.subtotal, 400);
+        assert_eq!(body.total, 250);
+        assert_eq!(body.savings, 150);
+        assert_eq!(body.full_price_items, vec![0]);
+        assert_eq!(body.bundles.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_quote_forwards_point_in_time() -> TestResult {
+        let at: Timestamp = "2026-02-21T12:00:00Z".parse()?;
+
+        let mut mock = MockQuotesService::new();
+
+        mock.expect_create_quote()
+            .once()
+            .withf(move |tenant, _, point_in_time| {
+                *tenant == TEST_TENANT_UUID && *point_in_time == at
+            })
+            .return_once(|_, _, _| {
+                Ok(QuoteRecord {
+                    subtotal: 0,
+                    total: 0,
+                    items: Vec::new(),
+                })
+            });
+
+        let res = TestClient::post("http://example.com/quotes")
+            .json(&json!({ "items": [], "at": "2026-02-21T12:00:00Z" }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_quote_invalid_point_in_time_returns_400() -> TestResult {
+        let res = TestClient::post("http://example.com/quotes")
+            .json(&json!({ "items": [], "at": "yesterday" }))
+            .send(&make_service(MockQuotesService::new()))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_quote_unknown_product_returns_400() -> TestResult {
+        let mut mock = MockQuotesService::new();
+
+        mock.expect_create_quote()
+            .once()
+            .return_once(|_, _, _| Err(QuotesServiceError::InvalidReference));
+
+        let res = TestClient::post("http://example.com/quotes")
+            .json(&json!({
+                "items": [{ "type": "product", "product_uuid": ProductUuid::new().into_uuid() }]
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+//! Quote Handlers
+
+pub(crate) mod create;
@@ -0,0 +1,8 @@
+//! Quotes
+
+pub(crate) mod errors;
+pub(crate) mod handlers;
+pub(crate) mod requests;
+pub(crate) mod responses;
+
+pub(crate) use handlers::*;
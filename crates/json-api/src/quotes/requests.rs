@@ -0,0 +1,116 @@
+//! Quotes Requests
+
+use lattice_app::domain::{
+    products::records::ProductUuid,
+    quotes::data::{NewQuote, NewQuoteItem},
+};
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use uuid::Uuid;
+
+/// Create Quote Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct CreateQuoteRequest {
+    /// The items to price
+    pub items: Vec<QuoteItemRequest>,
+
+    /// Price against the promotions live at this time, instead of now
+    #[serde(default)]
+    pub at: Option<String>,
+}
+
+impl From<CreateQuoteRequest> for NewQuote {
+    fn from(request: CreateQuoteRequest) -> Self {
+        NewQuote {
+            items: request.items.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Quote Item Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuoteItemRequest {
+    /// A catalogue product
+    Product { product_uuid: Uuid },
+
+    /// An item priced and tagged by the caller
+    Inline {
+        price: u64,
+        #[serde(default)]
+        tags: SmallVec<[String; 3]>,
+    },
+}
+
+impl From<QuoteItemRequest> for NewQuoteItem {
+    fn from(request: QuoteItemRequest) -> Self {
+        match request {
+            QuoteItemRequest::Product { product_uuid } => NewQuoteItem::Product {
+                product_uuid: ProductUuid::from_uuid(product_uuid),
+            },
+            QuoteItemRequest::Inline { price, tags } => NewQuoteItem::Inline { price, tags },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use smallvec::smallvec;
+    use testresult::TestResult;
+
+    use super::*;
+
+    #[test]
+    fn create_quote_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "items": [
+                    {
+                        "type": "product",
+                        "product_uuid": "019c8e08-0000-7000-8000-000000000001"
+                    },
+                    {
+                        "type": "inline",
+                        "price": 250,
+                        "tags": ["sale"]
+                    },
+                    {
+                        "type": "inline",
+                        "price": 100
+                    }
+                ],
+                "at": "2026-02-21T12:00:00Z"
+            }
+        "#;
+
+        let request: CreateQuoteRequest = serde_json::from_str(json)?;
+
+        assert_eq!(request.at.as_deref(), Some("2026-02-21T12:00:00Z"));
+
+        assert_eq!(
+            NewQuote::from(request),
+            NewQuote {
+                items: vec![
+                    NewQuoteItem::Product {
+                        product_uuid: ProductUuid::from_uuid(Uuid::from_str(
+                            "019c8e08-0000-7000-8000-000000000001"
+                        )?),
+                    },
+                    NewQuoteItem::Inline {
+                        price: 250,
+                        tags: smallvec!["sale".to_string()],
+                    },
+                    NewQuoteItem::Inline {
+                        price: 100,
+                        tags: SmallVec::new(),
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+}
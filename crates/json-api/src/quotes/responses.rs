@@ -0,0 +1,229 @@
+//! Quotes Responses
+
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use uuid::Uuid;
+
+use lattice_app::domain::quotes::records::{QuoteItemRecord, QuoteRecord};
+
+use crate::carts::handlers::get::RedemptionResponse;
+
+/// Quote Response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct QuoteResponse {
+    /// The sum of the item prices before promotions
+    pub subtotal: u64,
+
+    /// The total after promotions
+    pub total: u64,
+
+    /// The amount saved by promotions
+    pub savings: u64,
+
+    /// The quoted items, in the order they were given
+    pub items: Vec<QuoteItemResponse>,
+
+    /// Indexes of the items no promotion applied to
+    pub full_price_items: Vec<usize>,
+
+    /// The promotion redemptions, each grouping the items redeemed together
+    pub bundles: Vec<BundleResponse>,
+}
+
+impl From<QuoteRecord> for QuoteResponse {
+    fn from(quote: QuoteRecord) -> Self {
+        let full_price_items = quote
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.redemptions.is_empty())
+            .map(|(item_idx, _)| item_idx)
+            .collect();
+
+        let bundles = bundles(&quote.items);
+
+        QuoteResponse {
+            subtotal: quote.subtotal,
+            total: quote.total,
+            savings: quote.subtotal.saturating_sub(quote.total),
+            items: quote.items.into_iter().map(Into::into).collect(),
+            full_price_items,
+            bundles,
+        }
+    }
+}
+
+/// Quote Item Response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct QuoteItemResponse {
+    /// The unique identifier of the product, for catalogue items
+    pub product_uuid: Option<Uuid>,
+
+    /// The price of the item before promotions
+    pub price: u64,
+
+    /// The tags the item was priced with
+    pub tags: SmallVec<[String; 3]>,
+
+    /// The promotions redeemed against the item, one per stack layer that touched it
+    pub redemptions: Vec<RedemptionResponse>,
+}
+
+impl From<QuoteItemRecord> for QuoteItemResponse {
+    fn from(item: QuoteItemRecord) -> Self {
+        Self {
+            product_uuid: item.product_uuid.map(Into::into),
+            price: item.price,
+            tags: item.tags,
+            redemptions: item
+                .redemptions
+                .into_iter()
+                .map(RedemptionResponse::from)
+                .collect(),
+        }
+    }
+}
+
+/// Bundle Response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct BundleResponse {
+    /// The unique identifier of the redeemed promotion
+    pub promotion_uuid: Uuid,
+
+    /// The redemption index, unique per promotion
+    pub redemption_idx: usize,
+
+    /// Indexes of the items redeemed together
+    pub items: Vec<usize>,
+
+    /// The sum of the bundled item prices before the promotion was applied
+    pub original_price: u64,
+
+    /// The sum of the bundled item prices after the promotion was applied
+    pub final_price: u64,
+
+    /// The amount saved by this redemption
+    pub savings: u64,
+}
+
+/// Group item redemptions into bundles, in order of each bundle's first item.
+fn bundles(items: &[QuoteItemRecord]) -> Vec<BundleResponse> {
+    let mut bundles: Vec<BundleResponse> = Vec::new();
+
+    for (item_idx, item) in items.iter().enumerate() {
+        for redemption in &item.redemptions {
+            let promotion_uuid = redemption.promotion_uuid.into_uuid();
+
+            let existing = bundles.iter_mut().find(|bundle| {
+                bundle.promotion_uuid == promotion_uuid
+                    && bundle.redemption_idx == redemption.redemption_idx
+            });
+
+            match existing {
+                Some(bundle) => {
+                    bundle.items.push(item_idx);
+                    bundle.original_price = bundle
+                        .original_price
+                        .saturating_add(redemption.original_price);
+                    bundle.final_price = bundle.final_price.saturating_add(redemption.final_price);
+                    bundle.savings = bundle.original_price.saturating_sub(bundle.final_price);
+                }
+                None => bundles.push(BundleResponse {
+                    promotion_uuid,
+                    redemption_idx: redemption.redemption_idx,
+                    items: vec![item_idx],
+                    original_price: redemption.original_price,
+                    final_price: redemption.final_price,
+                    savings: redemption
+                        .original_price
+                        .saturating_sub(redemption.final_price),
+                }),
+            }
+        }
+    }
+
+    bundles
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::smallvec;
+
+    use lattice_app::domain::{
+        pricing::data::Redemption, products::records::ProductUuid,
+        promotions::records::PromotionUuid,
+    };
+
+    use super::*;
+
+    fn redemption(
+        promotion_uuid: PromotionUuid,
+        redemption_idx: usize,
+        final_price: u64,
+    ) -> Redemption {
+        Redemption {
+            promotion_uuid,
+            redemption_idx,
+            original_price: 100,
+            final_price,
+        }
+    }
+
+    #[test]
+    fn quote_response_groups_redemptions_into_bundles() {
+        let bundle_promotion = PromotionUuid::new();
+        let item_promotion = PromotionUuid::new();
+
+        let quote = QuoteRecord {
+            subtotal: 400,
+            total: 290,
+            items: vec![
+                QuoteItemRecord {
+                    product_uuid: Some(ProductUuid::new()),
+                    price: 100,
+                    tags: smallvec!["drink".to_string()],
+                    redemptions: vec![redemption(bundle_promotion, 0, 75)],
+                },
+                QuoteItemRecord {
+                    product_uuid: None,
+                    price: 100,
+                    tags: smallvec![],
+                    redemptions: Vec::new(),
+                },
+                QuoteItemRecord {
+                    product_uuid: None,
+                    price: 100,
+                    tags: smallvec!["snack".to_string()],
+                    redemptions: vec![redemption(bundle_promotion, 0, 75)],
+                },
+                QuoteItemRecord {
+                    product_uuid: None,
+                    price: 100,
+                    tags: smallvec!["sale".to_string()],
+                    redemptions: vec![redemption(item_promotion, 0, 40)],
+                },
+            ],
+        };
+
+        let response = QuoteResponse::from(quote);
+
+        assert_eq!(response.savings, 110);
+        assert_eq!(response.full_price_items, vec![1]);
+        assert_eq!(response.bundles.len(), 2);
+
+        let bundle = &response.bundles[0];
+
+        assert_eq!(bundle.promotion_uuid, bundle_promotion.into_uuid());
+        assert_eq!(bundle.items, vec![0, 2]);
+        assert_eq!(bundle.original_price, 200);
+        assert_eq!(bundle.final_price, 150);
+        assert_eq!(bundle.savings, 50);
+
+        let bundle = &response.bundles[1];
+
+        assert_eq!(bundle.promotion_uuid, item_promotion.into_uuid());
+        assert_eq!(bundle.items, vec![3]);
+        assert_eq!(bundle.savings, 60);
+    }
+}
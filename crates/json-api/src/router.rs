@@ -2,7 +2,7 @@
 
 use salvo::Router;
 
-use crate::{carts, products, promotions};
+use crate::{carts, products, promotions, quotes, stacks};
 
 pub fn app_router() -> Router {
     Router::new()
@@ -36,8 +36,24 @@ pub fn app_router() -> Router {
         )
         .push(
             Router::with_path("promotions")
+                .get(promotions::index::handler)
                 .post(promotions::create::handler)
-                .push(Router::with_path("{uuid}").put(promotions::update::handler)),
+                .push(
+                    Router::with_path("{uuid}")
+                        .get(promotions::get::handler)
+                        .put(promotions::update::handler)
+                        .delete(promotions::delete::handler),
+                ),
+        )
+        .push(Router::with_path("quotes").post(quotes::create::handler))
+        .push(
+            Router::with_path("stacks")
+                .post(stacks::create::handler)
+                .push(
+                    Router::with_path("{uuid}")
+                        .get(stacks::get::handler)
+                        .put(stacks::update::handler),
+                ),
         )
 }
 
@@ -55,6 +71,8 @@ mod tests {
             carts::{CartsServiceError, MockCartsService},
             products::{MockProductsService, ProductsServiceError},
             promotions::{PromotionsServiceError, service::MockPromotionsService},
+            quotes::MockQuotesService,
+            stacks::{MockStacksService, StacksServiceError},
         },
     };
 
@@ -66,11 +84,14 @@ mod tests {
         carts: MockCartsService,
         products: MockProductsService,
         promotions: MockPromotionsService,
+        stacks: MockStacksService,
     ) -> Service {
         let state = Arc::new(State::new(AppContext {
             carts: Arc::new(carts),
             products: Arc::new(products),
             promotions: Arc::new(promotions),
+            quotes: Arc::new(MockQuotesService::new()),
+            stacks: Arc::new(stacks),
             auth: Arc::new(MockAuthService::new()),
         }));
 
@@ -88,6 +109,7 @@ mod tests {
             MockCartsService::new(),
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::post("http://example.com/carts")
@@ -113,6 +135,7 @@ mod tests {
             carts,
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::get(format!("http://example.com/carts/{}", Uuid::nil()))
@@ -137,6 +160,7 @@ mod tests {
             carts,
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::delete(format!("http://example.com/carts/{}", Uuid::nil()))
@@ -156,6 +180,7 @@ mod tests {
             MockCartsService::new(),
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::post(format!("http://example.com/carts/{}/items", Uuid::nil()))
@@ -180,6 +205,7 @@ mod tests {
             carts,
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::delete(format!(
@@ -208,6 +234,7 @@ mod tests {
             MockCartsService::new(),
             products,
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::get("http://example.com/products")
@@ -227,6 +254,7 @@ mod tests {
             MockCartsService::new(),
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::post("http://example.com/products")
@@ -240,12 +268,91 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_get_promotions_is_registered() {
+        let mut promotions = MockPromotionsService::new();
+
+        promotions
+            .expect_list_promotions()
+            .return_once(|_, _| Ok(Vec::new()));
+
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            promotions,
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::get("http://example.com/promotions")
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "GET /promotions should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_promotion_is_registered() {
+        let mut promotions = MockPromotionsService::new();
+
+        promotions
+            .expect_get_promotion()
+            .return_once(|_, _, _| Err(PromotionsServiceError::InvalidData));
+
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            promotions,
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::get(format!("http://example.com/promotions/{}", Uuid::nil()))
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "GET /promotions/{{uuid}} should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_promotion_is_registered() {
+        let mut promotions = MockPromotionsService::new();
+
+        promotions
+            .expect_delete_promotion()
+            .return_once(|_, _| Err(PromotionsServiceError::InvalidData));
+
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            promotions,
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::delete(format!("http://example.com/promotions/{}", Uuid::nil()))
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "DELETE /promotions/{{uuid}} should be registered"
+        );
+    }
+
     #[tokio::test]
     async fn test_post_promotions_is_registered() {
         let service = router_service(
             MockCartsService::new(),
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::post("http://example.com/promotions")
@@ -271,6 +378,7 @@ mod tests {
             MockCartsService::new(),
             MockProductsService::new(),
             promotions,
+            MockStacksService::new(),
         );
 
         let res = TestClient::put(format!("http://example.com/promotions/{}", Uuid::nil()))
@@ -296,6 +404,7 @@ mod tests {
             MockCartsService::new(),
             products,
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::get(format!("http://example.com/products/{}", Uuid::nil()))
@@ -315,6 +424,7 @@ mod tests {
             MockCartsService::new(),
             MockProductsService::new(),
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::put(format!("http://example.com/products/{}", Uuid::nil()))
@@ -339,6 +449,7 @@ mod tests {
             MockCartsService::new(),
             products,
             MockPromotionsService::new(),
+            MockStacksService::new(),
         );
 
         let res = TestClient::delete(format!("http://example.com/products/{}", Uuid::nil()))
@@ -351,4 +462,90 @@ mod tests {
             "DELETE /products/{{product}} should be registered"
         );
     }
+
+    #[tokio::test]
+    async fn test_post_quotes_is_registered() {
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            MockPromotionsService::new(),
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::post("http://example.com/quotes")
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "POST /quotes should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_stacks_is_registered() {
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            MockPromotionsService::new(),
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::post("http://example.com/stacks")
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "POST /stacks should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_stack_is_registered() {
+        let mut stacks = MockStacksService::new();
+
+        stacks
+            .expect_get_stack()
+            .return_once(|_, _, _| Err(StacksServiceError::AlreadyExists));
+
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            MockPromotionsService::new(),
+            stacks,
+        );
+
+        let res = TestClient::get(format!("http://example.com/stacks/{}", Uuid::nil()))
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "GET /stacks/{{uuid}} should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_stack_is_registered() {
+        let service = router_service(
+            MockCartsService::new(),
+            MockProductsService::new(),
+            MockPromotionsService::new(),
+            MockStacksService::new(),
+        );
+
+        let res = TestClient::put(format!("http://example.com/stacks/{}", Uuid::nil()))
+            .send(&service)
+            .await;
+
+        assert_ne!(
+            res.status_code,
+            Some(StatusCode::NOT_FOUND),
+            "PUT /stacks/{{uuid}} should be registered"
+        );
+    }
 }
@@ -0,0 +1,30 @@
+//! Stack Errors
+
+use salvo::http::StatusError;
+use tracing::error;
+
+use lattice_app::domain::stacks::StacksServiceError;
+
+pub(crate) fn into_status_error(error: StacksServiceError) -> StatusError {
+    match error {
+        StacksServiceError::AlreadyExists => StatusError::conflict().brief("Stack already exists"),
+        StacksServiceError::InvalidReference
+        | StacksServiceError::MissingRequiredData
+        | StacksServiceError::InvalidData => {
+            StatusError::bad_request().brief("Invalid stack payload")
+        }
+        StacksServiceError::InvalidGraph(source) => {
+            StatusError::bad_request().brief(format!("Invalid stack graph: {source}"))
+        }
+        StacksServiceError::Sql(source) => {
+            error!("failed to process stack: {source}");
+
+            StatusError::internal_server_error()
+        }
+        StacksServiceError::NotFound => {
+            error!("stack not found");
+
+            StatusError::not_found()
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Create Stack Handler
+
+use std::sync::Arc;
+
+use salvo::{Depot, http::header::LOCATION, oapi::extract::JsonBody, prelude::*};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    extensions::*,
+    stacks::{errors::into_status_error, requests::CreateStackRequest},
+    state::State,
+};
+
+/// Stack Created Response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct StackCreatedResponse {
+    /// Created stack UUID
+    pub uuid: Uuid,
+}
+
+/// Create Stack Handler
+///
+/// Creates the tenant's promotion stack. A tenant has at most one stack.
+#[endpoint(
+    tags("stacks"),
+    summary = "Create Stack",
+    security(("bearer_auth" = [])),
+    responses(
+        (status_code = StatusCode::CREATED, description = "Stack created"),
+        (status_code = StatusCode::CONFLICT, description = "Stack already exists"),
+        (status_code = StatusCode::BAD_REQUEST, description = "Bad Request"),
+        (status_code = StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error"),
+    ),
+)]
+#[tracing::instrument(
+    name = "stacks.create",
+    skip(json, depot, res),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        stack_uuid = tracing::field::Empty,
+        layer_count = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    json: JsonBody<CreateStackRequest>,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<Json<StackCreatedResponse>, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let request = json.into_inner();
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("stack_uuid", tracing::field::display(request.uuid));
+    span.record("layer_count", request.layers.len());
+
+    let uuid = state
+        .app
+        .stacks
+        .create_stack(tenant, request.into())
+        .await
+        .map_err(into_status_error)?
+        .uuid;
+
+    res.add_header(LOCATION, format!("/stacks/{uuid}"), true)
+        .or_500("failed to set location header")?
+        .status_code(StatusCode::CREATED);
+
+    tracing::info!(stack_uuid = %uuid, "created stack");
+
+    Ok(Json(StackCreatedResponse { uuid: uuid.into() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::json;
+    use testresult::TestResult;
+
+    use lattice_app::domain::{
+        pricing::PricingError,
+        promotions::records::PromotionUuid,
+        stacks::{
+            MockStacksService, StacksServiceError,
+            data::{LayerOutput, NewStack, StackGraph, StackLayer},
+            records::{StackRecord, StackUuid},
+        },
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, stacks_service};
+
+    use super::*;
+
+    fn make_service(stacks: MockStacksService) -> Service {
+        stacks_service(stacks, Router::with_path("stacks").post(handler))
+    }
+
+    fn make_stack(uuid: StackUuid) -> StackRecord {
+        StackRecord {
+            uuid,
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_stack_success() -> TestResult {
+        let stack_uuid = StackUuid::new();
+        let promotion_uuid = PromotionUuid::new();
+        let stack = make_stack(stack_uuid);
+
+        let mut mock = MockStacksService::new();
+
+        mock.expect_create_stack()
+            .once()
+            .withf(move |tenant, new| {
+                *tenant == TEST_TENANT_UUID
+                    && *new
+                        == NewStack {
+                            uuid: stack_uuid,
+                            graph: StackGraph {
+                                root: "default".to_string(),
+                                layers: vec![StackLayer {
+                                    name: "default".to_string(),
+                                    promotions: vec![promotion_uuid],
+                                    output: LayerOutput::PassThrough { next: None },
+                                }],
+                            },
+                        }
+            })
+            .return_once(move |_, _| Ok(stack));
+
+        let mut res = TestClient::post("http://example.com/stacks")
+            .json(&json!({
+                "uuid": stack_uuid.into_uuid(),
+                "root": "default",
+                "layers": [
+                    {
+                        "name": "default",
+                        "promotions": [promotion_uuid.into_uuid()],
+                        "output": { "type": "pass_through" }
+                    }
+                ]
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        let body: StackCreatedResponse = res.take_json().await?;
+        let location = res.headers().get("location").and_then(|v| v.to_str().ok());
+
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(location, Some(format!("/stacks/{stack_uuid}").as_str()));
+        assert_eq!(body.uuid, stack_uuid.into_uuid());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_stack_conflict_returns_409() -> TestResult {
+        let mut mock = MockStacksService::new();
+
+        mock.expect_create_stack()
+            .once()
+            .return_once(|_, _| Err(StacksServiceError::AlreadyExists));
+
+        let res = TestClient::post("http://example.com/stacks")
+            .json(&json!({
+                "uuid": StackUuid::new().into_uuid(),
+                "root": "default",
+                "layers": []
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::CONFLICT));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_stack_invalid_graph_returns_400() -> TestResult {
+        let mut mock = MockStacksService::new();
+
+        mock.expect_create_stack().once().return_once(|_, _| {
+            Err(StacksServiceError::InvalidGraph(
+                PricingError::UnknownLayer("missing".to_string()),
+            ))
+        });
+
+        let res = TestClient::post("http://example.com/stacks")
+            .json(&json!({
+                "uuid": StackUuid::new().into_uuid(),
+                "root": "missing",
+                "layers": []
+            }))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+//! Get Stack Handler
+
+use std::{string::ToString, sync::Arc};
+
+use salvo::{
+    oapi::{
+        ToSchema,
+        extract::{PathParam, QueryParam},
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use lattice_app::domain::stacks::{
+    data::{LayerOutput, StackLayer},
+    records::StackDetailsRecord,
+};
+
+use crate::{extensions::*, stacks::errors::into_status_error, state::State};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct StackResponse {
+    /// The unique identifier of the stack
+    pub uuid: Uuid,
+
+    /// The unique identifier of this version of the stack
+    pub version_uuid: Uuid,
+
+    /// Name of the layer items enter the stack at
+    pub root: String,
+
+    /// The layers of the stack
+    pub layers: Vec<StackLayerResponse>,
+
+    /// The date and time the stack was created
+    pub created_at: String,
+
+    /// The date and time the stack was last updated
+    pub updated_at: String,
+
+    /// The date and time the stack was deleted
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct StackLayerResponse {
+    /// Name of the layer
+    pub name: String,
+
+    /// The promotions competing in this layer
+    pub promotions: Vec<Uuid>,
+
+    /// Where items go after this layer
+    pub output: LayerOutputResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum LayerOutputResponse {
+    PassThrough {
+        next: Option<String>,
+    },
+    Split {
+        participating: Option<String>,
+        non_participating: Option<String>,
+    },
+}
+
+impl From<StackDetailsRecord> for StackResponse {
+    fn from(stack: StackDetailsRecord) -> Self {
+        StackResponse {
+            uuid: stack.uuid.into(),
+            version_uuid: stack.version_uuid.into(),
+            root: stack.graph.root,
+            layers: stack.graph.layers.into_iter().map(Into::into).collect(),
+            created_at: stack.created_at.to_string(),
+            updated_at: stack.updated_at.to_string(),
+            deleted_at: stack.deleted_at.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+impl From<StackLayer> for StackLayerResponse {
+    fn from(layer: StackLayer) -> Self {
+        StackLayerResponse {
+            name: layer.name,
+            promotions: layer.promotions.into_iter().map(Into::into).collect(),
+            output: match layer.output {
+                LayerOutput::PassThrough { next } => LayerOutputResponse::PassThrough { next },
+                LayerOutput::Split {
+                    participating,
+                    non_participating,
+                } => LayerOutputResponse::Split {
+                    participating,
+                    non_participating,
+                },
+            },
+        }
+    }
+}
+
+/// Get Stack Handler
+///
+/// Returns a stack as it was at the given point in time.
+#[endpoint(
+    tags("stacks"),
+    summary = "Get Stack",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(
+    name = "stacks.get",
+    skip(uuid, at, depot),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        stack_uuid = tracing::field::Empty,
+        point_in_time = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    uuid: PathParam<Uuid>,
+    at: QueryParam<String, false>,
+    depot: &mut Depot,
+) -> Result<Json<StackResponse>, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let point_in_time = at.into_point_in_time()?;
+    let uuid = uuid.into_inner();
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("stack_uuid", tracing::field::display(uuid));
+    span.record("point_in_time", tracing::field::display(point_in_time));
+
+    let stack = state
+        .app
+        .stacks
+        .get_stack(tenant, uuid.into(), point_in_time)
+        .await
+        .map_err(into_status_error)?;
+
+    tracing::info!(stack_uuid = %stack.uuid, "fetched stack");
+
+    Ok(Json(stack.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use salvo::test::{ResponseExt, TestClient};
+    use testresult::TestResult;
+
+    use lattice_app::domain::{
+        promotions::records::PromotionUuid,
+        stacks::{
+            MockStacksService, StacksServiceError,
+            data::StackGraph,
+            records::{StackUuid, StackVersionUuid},
+        },
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, stacks_service};
+
+    use super::*;
+
+    fn make_service(stacks: MockStacksService) -> Service {
+        stacks_service(stacks, Router::with_path("stacks/{uuid}").get(handler))
+    }
+
+    fn make_stack(uuid: StackUuid, promotion: PromotionUuid) -> StackDetailsRecord {
+        StackDetailsRecord {
+            uuid,
+            version_uuid: StackVersionUuid::from_uuid(uuid.into_uuid()),
+            graph: StackGraph {
+                root: "default".to_string(),
+                layers: vec![StackLayer {
+                    name: "default".to_string(),
+                    promotions: vec![promotion],
+                    output: LayerOutput::PassThrough { next: None },
+                }],
+            },
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_returns_200() -> TestResult {
+        let mut mock = MockStacksService::new();
+        let uuid = StackUuid::new();
+        let promotion = PromotionUuid::new();
+
+        let stack = make_stack(uuid, promotion);
+
+        mock.expect_get_stack()
+            .once()
+            .withf(move |tenant, u, _| *tenant == TEST_TENANT_UUID && *u == uuid)
+            .return_once(move |_, _, _| Ok(stack));
+
+        let mut res = TestClient::get(format!("http://example.com/stacks/{uuid}"))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let body: StackResponse = res.take_json().await?;
+
+        assert_eq!(body.uuid, uuid.into_uuid());
+        assert_eq!(body.root, "default");
+
+        let [layer] = body.layers.as_slice() else {
+            panic!("expected one layer, got {:?}", body.layers);
+        };
+
+        assert_eq!(layer.promotions, vec![promotion.into_uuid()]);
+        assert_eq!(
+            layer.output,
+            LayerOutputResponse::PassThrough { next: None }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_stack_returns_404() -> TestResult {
+        let mut mock = MockStacksService::new();
+        let uuid = StackUuid::new();
+
+        mock.expect_get_stack()
+            .once()
+            .return_once(|_, _, _| Err(StacksServiceError::NotFound));
+
+        let res = TestClient::get(format!("http://example.com/stacks/{uuid}"))
+            .send(&make_service(mock))
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_forwards_point_in_time_query_param() -> TestResult {
+        let mut mock = MockStacksService::new();
+        let uuid = StackUuid::new();
+        let at: Timestamp = "2026-02-21T12:00:00Z".parse()?;
+        let stack = make_stack(uuid, PromotionUuid::new());
+
+        mock.expect_get_stack()
+            .once()
+            .withf(move |tenant, u, point_in_time| {
+                *tenant == TEST_TENANT_UUID && *u == uuid && *point_in_time == at
+            })
+            .return_once(move |_, _, _| Ok(stack));
+
+        let res = TestClient::get(format!(
+            "http://example.com/stacks/{uuid}?at=2026-02-21T12:00:00Z"
+        ))
+        .send(&make_service(mock))
+        .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+//! Stack Handlers
+
+pub(crate) mod create;
+pub(crate) mod get;
+pub(crate) mod update;
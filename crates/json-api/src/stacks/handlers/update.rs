@@ -0,0 +1,170 @@
+//! Update Stack Handler
+
+use std::sync::Arc;
+
+use salvo::{
+    Depot,
+    http::header::LOCATION,
+    oapi::extract::{JsonBody, PathParam},
+    prelude::*,
+};
+use uuid::Uuid;
+
+use lattice_app::domain::stacks::records::StackUuid;
+
+use crate::{
+    extensions::*,
+    stacks::{errors::into_status_error, requests::UpdateStackRequest},
+    state::State,
+};
+
+/// Update Stack Handler
+///
+/// Replaces the stack's layers with a new version.
+#[endpoint(
+    tags("stacks"),
+    summary = "Update Stack",
+    security(("bearer_auth" = [])),
+    responses(
+        (status_code = StatusCode::OK, description = "Stack updated"),
+        (status_code = StatusCode::NOT_FOUND, description = "Stack not found"),
+        (status_code = StatusCode::BAD_REQUEST, description = "Bad Request"),
+        (status_code = StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error"),
+    ),
+)]
+#[tracing::instrument(
+    name = "stacks.update",
+    skip(uuid, json, depot, res),
+    fields(
+        tenant_uuid = tracing::field::Empty,
+        stack_uuid = tracing::field::Empty,
+        layer_count = tracing::field::Empty
+    ),
+    err
+)]
+pub(crate) async fn handler(
+    uuid: PathParam<Uuid>,
+    json: JsonBody<UpdateStackRequest>,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<StatusCode, StatusError> {
+    let state = depot.obtain_or_500::<Arc<State>>()?;
+    let tenant = depot.tenant_uuid_or_401()?;
+    let uuid = uuid.into_inner();
+    let request = json.into_inner();
+
+    let span = tracing::Span::current();
+
+    span.record("tenant_uuid", tracing::field::display(tenant));
+    span.record("stack_uuid", tracing::field::display(uuid));
+    span.record("layer_count", request.layers.len());
+
+    state
+        .app
+        .stacks
+        .update_stack(tenant, StackUuid::from_uuid(uuid), request.into())
+        .await
+        .map_err(into_status_error)?;
+
+    res.add_header(LOCATION, format!("/stacks/{uuid}"), true)
+        .or_500("failed to set location header")?
+        .status_code(StatusCode::OK);
+
+    tracing::info!(stack_uuid = %uuid, "updated stack");
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::test::TestClient;
+    use serde_json::json;
+    use testresult::TestResult;
+
+    use lattice_app::domain::stacks::{
+        MockStacksService, StacksServiceError,
+        data::{LayerOutput, StackGraph, StackLayer, StackUpdate},
+    };
+
+    use crate::test_helpers::{TEST_TENANT_UUID, stacks_service};
+
+    use super::*;
+
+    fn make_service(stacks: MockStacksService) -> Service {
+        stacks_service(stacks, Router::with_path("stacks/{uuid}").put(handler))
+    }
+
+    #[tokio::test]
+    async fn test_update_stack_success() -> TestResult {
+        let stack_uuid = StackUuid::new();
+
+        let mut mock = MockStacksService::new();
+
+        mock.expect_update_stack()
+            .once()
+            .withf(move |tenant, uuid, update| {
+                *tenant == TEST_TENANT_UUID
+                    && *uuid == stack_uuid
+                    && *update
+                        == StackUpdate {
+                            graph: StackGraph {
+                                root: "default".to_string(),
+                                layers: vec![StackLayer {
+                                    name: "default".to_string(),
+                                    promotions: Vec::new(),
+                                    output: LayerOutput::Split {
+                                        participating: None,
+                                        non_participating: Some("default".to_string()),
+                                    },
+                                }],
+                            },
+                        }
+            })
+            .return_once(|_, _, _| Ok(()));
+
+        let res = TestClient::put(format!(
+            "http://example.com/stacks/{}",
+            stack_uuid.into_uuid()
+        ))
+        .json(&json!({
+            "root": "default",
+            "layers": [
+                {
+                    "name": "default",
+                    "promotions": [],
+                    "output": { "type": "split", "non_participating": "default" }
+                }
+            ]
+        }))
+        .send(&make_service(mock))
+        .await;
+
+        let location = res.headers().get("location").and_then(|v| v.to_str().ok());
+
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(location, Some(format!("/stacks/{stack_uuid}").as_str()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_stack_not_found_returns_404() -> TestResult {
+        let mut mock = MockStacksService::new();
+
+        mock.expect_update_stack()
+            .once()
+            .return_once(|_, _, _| Err(StacksServiceError::NotFound));
+
+        let res = TestClient::put(format!(
+            "http://example.com/stacks/{}",
+            StackUuid::new().into_uuid()
+        ))
+        .json(&json!({ "root": "default", "layers": [] }))
+        .send(&make_service(mock))
+        .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        Ok(())
+    }
+}
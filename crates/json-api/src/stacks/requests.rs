@@ -0,0 +1,209 @@
+//! Stacks Requests
+
+use lattice_app::domain::{
+    promotions::records::PromotionUuid,
+    stacks::{
+        data::{LayerOutput, NewStack, StackGraph, StackLayer, StackUpdate},
+        records::StackUuid,
+    },
+};
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Create Stack Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct CreateStackRequest {
+    /// The unique identifier of the stack
+    pub uuid: Uuid,
+
+    /// Name of the layer items enter the stack at
+    pub root: String,
+
+    /// The layers of the stack
+    pub layers: Vec<StackLayerRequest>,
+}
+
+impl From<CreateStackRequest> for NewStack {
+    fn from(request: CreateStackRequest) -> Self {
+        NewStack {
+            uuid: StackUuid::from_uuid(request.uuid),
+            graph: stack_graph(request.root, request.layers),
+        }
+    }
+}
+
+/// Update Stack Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct UpdateStackRequest {
+    /// Name of the layer items enter the stack at
+    pub root: String,
+
+    /// The layers of the stack
+    pub layers: Vec<StackLayerRequest>,
+}
+
+impl From<UpdateStackRequest> for StackUpdate {
+    fn from(request: UpdateStackRequest) -> Self {
+        StackUpdate {
+            graph: stack_graph(request.root, request.layers),
+        }
+    }
+}
+
+/// Stack Layer Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct StackLayerRequest {
+    /// Name of the layer, unique within the stack
+    pub name: String,
+
+    /// The promotions competing in this layer
+    pub promotions: Vec<Uuid>,
+
+    /// Where items go after this layer
+    pub output: LayerOutputRequest,
+}
+
+impl From<StackLayerRequest> for StackLayer {
+    fn from(request: StackLayerRequest) -> Self {
+        StackLayer {
+            name: request.name,
+            promotions: request
+                .promotions
+                .into_iter()
+                .map(PromotionUuid::from_uuid)
+                .collect(),
+            output: request.output.into(),
+        }
+    }
+}
+
+/// Layer Output Request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LayerOutputRequest {
+    PassThrough {
+        next: Option<String>,
+    },
+    Split {
+        participating: Option<String>,
+        non_participating: Option<String>,
+    },
+}
+
+impl From<LayerOutputRequest> for LayerOutput {
+    fn from(request: LayerOutputRequest) -> Self {
+        match request {
+            LayerOutputRequest::PassThrough { next } => LayerOutput::PassThrough { next },
+            LayerOutputRequest::Split {
+                participating,
+                non_participating,
+            } => LayerOutput::Split {
+                participating,
+                non_participating,
+            },
+        }
+    }
+}
+
+fn stack_graph(root: String, layers: Vec<StackLayerRequest>) -> StackGraph {
+    StackGraph {
+        root,
+        layers: layers.into_iter().map(Into::into).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use testresult::TestResult;
+
+    use super::*;
+
+    #[test]
+    fn create_stack_request_parse() -> TestResult {
+        let json = r#"
+            {
+                "uuid": "019c8e08-0000-7000-8000-000000000001",
+                "root": "members",
+                "layers": [
+                    {
+                        "name": "members",
+                        "promotions": ["019c8e08-0000-7000-8000-000000000002"],
+                        "output": {
+                            "type": "split",
+                            "participating": "loyalty"
+                        }
+                    },
+                    {
+                        "name": "loyalty",
+                        "promotions": [],
+                        "output": { "type": "pass_through" }
+                    }
+                ]
+            }
+        "#;
+
+        let request: CreateStackRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            request,
+            CreateStackRequest {
+                uuid: Uuid::from_str("019c8e08-0000-7000-8000-000000000001")?,
+                root: "members".to_string(),
+                layers: vec![
+                    StackLayerRequest {
+                        name: "members".to_string(),
+                        promotions: vec![Uuid::from_str("019c8e08-0000-7000-8000-000000000002")?],
+                        output: LayerOutputRequest::Split {
+                            participating: Some("loyalty".to_string()),
+                            non_participating: None,
+                        },
+                    },
+                    StackLayerRequest {
+                        name: "loyalty".to_string(),
+                        promotions: Vec::new(),
+                        output: LayerOutputRequest::PassThrough { next: None },
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_stack_request_converts_to_update() -> TestResult {
+        let json = r#"
+            {
+                "root": "default",
+                "layers": [
+                    {
+                        "name": "default",
+                        "promotions": [],
+                        "output": { "type": "pass_through", "next": null }
+                    }
+                ]
+            }
+        "#;
+
+        let request: UpdateStackRequest = serde_json::from_str(json)?;
+
+        assert_eq!(
+            StackUpdate::from(request),
+            StackUpdate {
+                graph: StackGraph {
+                    root: "default".to_string(),
+                    layers: vec![StackLayer {
+                        name: "default".to_string(),
+                        promotions: Vec::new(),
+                        output: LayerOutput::PassThrough { next: None },
+                    }],
+                },
+            }
+        );
+
+        Ok(())
+    }
+}
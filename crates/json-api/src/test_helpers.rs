@@ -18,7 +18,13 @@ use lattice_app::{
             MockProductsService,
             records::{ProductRecord, ProductUuid},
         },
-        promotions::service::MockPromotionsService,
+        promotions::{
+            data::{PromotionDetails, budgets::Budgets, discounts::SimpleDiscount},
+            records::{DirectDiscountDetailUuid, PromotionDetailsRecord, PromotionUuid},
+            service::MockPromotionsService,
+        },
+        quotes::MockQuotesService,
+        stacks::MockStacksService,
         tenants::records::TenantUuid,
     },
 };
@@ -74,15 +80,38 @@ fn strict_promotions_mock() -> MockPromotionsService {
 
     promotions.expect_create_promotion().never();
     promotions.expect_update_promotion().never();
+    promotions.expect_list_promotions().never();
+    promotions.expect_get_promotion().never();
+    promotions.expect_delete_promotion().never();
 
     promotions
 }
 
+fn strict_quotes_mock() -> MockQuotesService {
+    let mut quotes = MockQuotesService::new();
+
+    quotes.expect_create_quote().never();
+
+    quotes
+}
+
+fn strict_stacks_mock() -> MockStacksService {
+    let mut stacks = MockStacksService::new();
+
+    stacks.expect_get_stack().never();
+    stacks.expect_create_stack().never();
+    stacks.expect_update_stack().never();
+
+    stacks
+}
+
 pub(crate) fn state_with_auth(auth: MockAuthService) -> Arc<State> {
     Arc::new(State::new(AppContext {
         carts: Arc::new(strict_carts_mock()),
         products: Arc::new(strict_products_mock()),
         promotions: Arc::new(strict_promotions_mock()),
+        quotes: Arc::new(strict_quotes_mock()),
+        stacks: Arc::new(strict_stacks_mock()),
         auth: Arc::new(auth),
     }))
 }
@@ -92,6 +121,8 @@ pub(crate) fn state_with_carts(carts: MockCartsService) -> Arc<State> {
         carts: Arc::new(carts),
         products: Arc::new(strict_products_mock()),
         promotions: Arc::new(strict_promotions_mock()),
+        quotes: Arc::new(strict_quotes_mock()),
+        stacks: Arc::new(strict_stacks_mock()),
         auth: Arc::new(strict_auth_mock()),
     }))
 }
@@ -101,6 +132,8 @@ pub(crate) fn state_with_products(products: MockProductsService) -> Arc<State> {
         carts: Arc::new(strict_carts_mock()),
         products: Arc::new(products),
         promotions: Arc::new(strict_promotions_mock()),
+        quotes: Arc::new(strict_quotes_mock()),
+        stacks: Arc::new(strict_stacks_mock()),
         auth: Arc::new(strict_auth_mock()),
     }))
 }
@@ -110,6 +143,30 @@ pub(crate) fn state_with_promotions(promotions: MockPromotionsService) -> Arc<St
         carts: Arc::new(strict_carts_mock()),
         products: Arc::new(strict_products_mock()),
         promotions: Arc::new(promotions),
+        quotes: Arc::new(strict_quotes_mock()),
+        stacks: Arc::new(strict_stacks_mock()),
+        auth: Arc::new(strict_auth_mock()),
+    }))
+}
+
+pub(crate) fn state_with_stacks(stacks: MockStacksService) -> Arc<State> {
+    Arc::new(State::new(AppContext {
+        carts: Arc::new(strict_carts_mock()),
+        products: Arc::new(strict_products_mock()),
+        promotions: Arc::new(strict_promotions_mock()),
+        quotes: Arc::new(strict_quotes_mock()),
+        stacks: Arc::new(stacks),
+        auth: Arc::new(strict_auth_mock()),
+    }))
+}
+
+pub(crate) fn state_with_quotes(quotes: MockQuotesService) -> Arc<State> {
+    Arc::new(State::new(AppContext {
+        carts: Arc::new(strict_carts_mock()),
+        products: Arc::new(strict_products_mock()),
+        promotions: Arc::new(strict_promotions_mock()),
+        quotes: Arc::new(quotes),
+        stacks: Arc::new(strict_stacks_mock()),
         auth: Arc::new(strict_auth_mock()),
     }))
 }
@@ -132,6 +189,24 @@ pub(crate) fn promotions_service(promotions: MockPromotionsService, route: Route
     )
 }
 
+pub(crate) fn quotes_service(quotes: MockQuotesService, route: Router) -> Service {
+    Service::new(
+        Router::new()
+            .hoop(inject(state_with_quotes(quotes)))
+            .hoop(inject_tenant)
+            .push(route),
+    )
+}
+
+pub(crate) fn stacks_service(stacks: MockStacksService, route: Router) -> Service {
+    Service::new(
+        Router::new()
+            .hoop(inject(state_with_stacks(stacks)))
+            .hoop(inject_tenant)
+            .push(route),
+    )
+}
+
 pub(crate) fn carts_service(carts: MockCartsService, route: Router) -> Service {
     Service::new(
         Router::new()
@@ -162,3 +237,21 @@ pub(crate) fn make_product(uuid: ProductUuid) -> ProductRecord {
         deleted_at: None,
     }
 }
+
+pub(crate) fn make_promotion(uuid: PromotionUuid) -> PromotionDetailsRecord {
+    PromotionDetailsRecord {
+        uuid,
+        details: PromotionDetails::DirectDiscount {
+            uuid: DirectDiscountDetailUuid::from_uuid(uuid.into_uuid()),
+            budgets: Budgets {
+                redemptions: None,
+                monetary: None,
+            },
+            discount: SimpleDiscount::PercentageOff { percentage: 10 },
+            qualification: None,
+        },
+        created_at: Timestamp::UNIX_EPOCH,
+        updated_at: Timestamp::UNIX_EPOCH,
+        deleted_at: None,
+    }
+}
@@ -0,0 +1,177 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TYPE MIX_AND_MATCH_DISCOUNT_KIND AS ENUM(
+  'percent_all_items',
+  'amount_off_each_item',
+  'fixed_price_each_item',
+  'amount_off_total',
+  'fixed_total',
+  'percent_cheapest',
+  'fixed_cheapest'
+);
+
+CREATE TABLE mix_and_match_promotions (
+  uuid UUID PRIMARY KEY,
+  promotion_uuid UUID NOT NULL,
+
+  redemption_budget BIGINT CHECK (redemption_budget >= 0),
+  monetary_budget BIGINT CHECK (monetary_budget >= 0),
+
+  discount_kind MIX_AND_MATCH_DISCOUNT_KIND NOT NULL,
+  discount_percentage BIGINT CHECK (discount_percentage > 0),
+  discount_amount BIGINT CHECK (discount_amount >= 0),
+
+  valid_period TSTZRANGE NOT NULL DEFAULT tstzrange (now(), NULL, '[)'),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (NOT isempty(valid_period)),
+
+  CHECK (
+    (
+      discount_kind IN ('percent_all_items', 'percent_cheapest')
+      AND discount_percentage IS NOT NULL
+      AND discount_amount IS NULL
+    )
+    OR (
+      discount_kind IN ('amount_off_each_item', 'amount_off_total')
+      AND discount_amount > 0
+      AND discount_percentage IS NULL
+    )
+    OR (
+      discount_kind IN (
+        'fixed_price_each_item',
+        'fixed_total',
+        'fixed_cheapest'
+      )
+      AND discount_amount IS NOT NULL
+      AND discount_percentage IS NULL
+    )
+  ),
+
+  CONSTRAINT mix_and_match_promotions_promotion_fk FOREIGN KEY (promotion_uuid) REFERENCES promotions (uuid) ON DELETE CASCADE,
+  CONSTRAINT mix_and_match_promotions_no_overlap_exclude EXCLUDE USING GIST (
+    promotion_uuid
+    WITH
+      =,
+      valid_period
+    WITH
+      &&
+  ) DEFERRABLE
+);
+
+CREATE INDEX mix_and_match_promotions_promotion_uuid_idx ON mix_and_match_promotions (promotion_uuid);
+
+CREATE INDEX mix_and_match_promotions_created_at_idx ON mix_and_match_promotions (created_at);
+
+CREATE UNIQUE INDEX mix_and_match_promotions_current_idx ON mix_and_match_promotions (promotion_uuid)
+WHERE
+  upper_inf(valid_period);
+
+CREATE POLICY mix_and_match_promotions_tenant_select_policy ON mix_and_match_promotions FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = mix_and_match_promotions.promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY mix_and_match_promotions_tenant_insert_policy ON mix_and_match_promotions FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = mix_and_match_promotions.promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY mix_and_match_promotions_tenant_update_policy ON mix_and_match_promotions
+FOR UPDATE
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = mix_and_match_promotions.promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  )
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = mix_and_match_promotions.promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY mix_and_match_promotions_tenant_delete_policy ON mix_and_match_promotions FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      promotions p
+    WHERE
+      p.uuid = mix_and_match_promotions.promotion_uuid
+      AND p.promotionable_type = 'mix_and_match'
+      AND p.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
+
+ALTER TABLE mix_and_match_promotions ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE FUNCTION bump_mix_and_match_promotion_updated_at () RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    UPDATE promotions SET updated_at = now()
+    WHERE uuid = NEW.promotion_uuid
+      AND promotionable_type = 'mix_and_match';
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER mix_and_match_promotions_bump_updated_at
+AFTER INSERT
+OR
+UPDATE ON mix_and_match_promotions FOR EACH ROW
+EXECUTE FUNCTION bump_mix_and_match_promotion_updated_at ();
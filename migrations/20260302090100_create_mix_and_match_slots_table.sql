@@ -0,0 +1,81 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TABLE mix_and_match_slots (
+  uuid UUID PRIMARY KEY,
+  mix_and_match_promotion_uuid UUID NOT NULL,
+
+  position INTEGER NOT NULL CHECK (position >= 0),
+
+  min_items BIGINT NOT NULL CHECK (min_items >= 0),
+  max_items BIGINT CHECK (max_items > 0),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (
+    max_items IS NULL
+    OR max_items >= min_items
+  ),
+
+  CONSTRAINT mix_and_match_slots_promotion_fk FOREIGN KEY (mix_and_match_promotion_uuid) REFERENCES mix_and_match_promotions (uuid) ON DELETE CASCADE,
+  CONSTRAINT mix_and_match_slots_position_uniq UNIQUE (mix_and_match_promotion_uuid, position)
+);
+
+ALTER TABLE mix_and_match_slots ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY mix_and_match_slots_tenant_select_policy ON mix_and_match_slots FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        mix_and_match_promotions mm
+        JOIN promotions p ON p.uuid = mm.promotion_uuid
+      WHERE
+        mm.uuid = mix_and_match_slots.mix_and_match_promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY mix_and_match_slots_tenant_insert_policy ON mix_and_match_slots FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        mix_and_match_promotions mm
+        JOIN promotions p ON p.uuid = mm.promotion_uuid
+      WHERE
+        mm.uuid = mix_and_match_slots.mix_and_match_promotion_uuid
+        AND p.promotionable_type = 'mix_and_match'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY mix_and_match_slots_tenant_delete_policy ON mix_and_match_slots FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      mix_and_match_promotions mm
+      JOIN promotions p ON p.uuid = mm.promotion_uuid
+    WHERE
+      mm.uuid = mix_and_match_slots.mix_and_match_promotion_uuid
+      AND p.promotionable_type = 'mix_and_match'
+      AND p.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
@@ -0,0 +1,141 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TABLE tiered_threshold_promotions (
+  uuid UUID PRIMARY KEY,
+  promotion_uuid UUID NOT NULL,
+
+  redemption_budget BIGINT CHECK (redemption_budget >= 0),
+  monetary_budget BIGINT CHECK (monetary_budget >= 0),
+
+  valid_period TSTZRANGE NOT NULL DEFAULT tstzrange (now(), NULL, '[)'),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (NOT isempty(valid_period)),
+
+  CONSTRAINT tiered_threshold_promotions_promotion_fk FOREIGN KEY (promotion_uuid) REFERENCES promotions (uuid) ON DELETE CASCADE,
+  CONSTRAINT tiered_threshold_promotions_no_overlap_exclude EXCLUDE USING GIST (
+    promotion_uuid
+    WITH
+      =,
+      valid_period
+    WITH
+      &&
+  ) DEFERRABLE
+);
+
+CREATE INDEX tiered_threshold_promotions_promotion_uuid_idx ON tiered_threshold_promotions (promotion_uuid);
+
+CREATE INDEX tiered_threshold_promotions_created_at_idx ON tiered_threshold_promotions (created_at);
+
+CREATE UNIQUE INDEX tiered_threshold_promotions_current_idx ON tiered_threshold_promotions (promotion_uuid)
+WHERE
+  upper_inf(valid_period);
+
+CREATE POLICY tiered_threshold_promotions_tenant_select_policy ON tiered_threshold_promotions FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = tiered_threshold_promotions.promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY tiered_threshold_promotions_tenant_insert_policy ON tiered_threshold_promotions FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = tiered_threshold_promotions.promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY tiered_threshold_promotions_tenant_update_policy ON tiered_threshold_promotions
+FOR UPDATE
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = tiered_threshold_promotions.promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  )
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = tiered_threshold_promotions.promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY tiered_threshold_promotions_tenant_delete_policy ON tiered_threshold_promotions FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      promotions p
+    WHERE
+      p.uuid = tiered_threshold_promotions.promotion_uuid
+      AND p.promotionable_type = 'tiered_threshold'
+      AND p.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
+
+ALTER TABLE tiered_threshold_promotions ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE FUNCTION bump_tiered_threshold_promotion_updated_at () RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    UPDATE promotions SET updated_at = now()
+    WHERE uuid = NEW.promotion_uuid
+      AND promotionable_type = 'tiered_threshold';
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER tiered_threshold_promotions_bump_updated_at
+AFTER INSERT
+OR
+UPDATE ON tiered_threshold_promotions FOR EACH ROW
+EXECUTE FUNCTION bump_tiered_threshold_promotion_updated_at ();
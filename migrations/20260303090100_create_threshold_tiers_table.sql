@@ -0,0 +1,137 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TYPE THRESHOLD_DISCOUNT_KIND AS ENUM(
+  'percent_each_item',
+  'amount_off_each_item',
+  'fixed_price_each_item',
+  'amount_off_total',
+  'fixed_total',
+  'percent_cheapest',
+  'fixed_cheapest'
+);
+
+-- Tier qualifications are stored in `qualifications` with their
+-- `promotionable_uuid` set to the tier's contribution or discount UUID.
+CREATE TABLE threshold_tiers (
+  uuid UUID PRIMARY KEY,
+  tiered_threshold_promotion_uuid UUID NOT NULL,
+
+  position INTEGER NOT NULL CHECK (position >= 0),
+
+  contribution_uuid UUID NOT NULL UNIQUE,
+  discount_uuid UUID NOT NULL UNIQUE,
+
+  lower_monetary_threshold BIGINT CHECK (lower_monetary_threshold >= 0),
+  lower_item_count_threshold BIGINT CHECK (
+    lower_item_count_threshold >= 0
+    AND lower_item_count_threshold <= 4294967295
+  ),
+  upper_monetary_threshold BIGINT CHECK (upper_monetary_threshold >= 0),
+  upper_item_count_threshold BIGINT CHECK (
+    upper_item_count_threshold >= 0
+    AND upper_item_count_threshold <= 4294967295
+  ),
+
+  discount_kind THRESHOLD_DISCOUNT_KIND NOT NULL,
+  discount_percentage BIGINT CHECK (discount_percentage > 0),
+  discount_amount BIGINT CHECK (discount_amount >= 0),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (
+    upper_monetary_threshold IS NULL
+    OR lower_monetary_threshold IS NULL
+    OR upper_monetary_threshold >= lower_monetary_threshold
+  ),
+
+  CHECK (
+    upper_item_count_threshold IS NULL
+    OR lower_item_count_threshold IS NULL
+    OR upper_item_count_threshold >= lower_item_count_threshold
+  ),
+
+  CHECK (
+    (
+      discount_kind IN ('percent_each_item', 'percent_cheapest')
+      AND discount_percentage IS NOT NULL
+      AND discount_amount IS NULL
+    )
+    OR (
+      discount_kind IN ('amount_off_each_item', 'amount_off_total')
+      AND discount_amount > 0
+      AND discount_percentage IS NULL
+    )
+    OR (
+      discount_kind IN (
+        'fixed_price_each_item',
+        'fixed_total',
+        'fixed_cheapest'
+      )
+      AND discount_amount IS NOT NULL
+      AND discount_percentage IS NULL
+    )
+  ),
+
+  CONSTRAINT threshold_tiers_promotion_fk FOREIGN KEY (tiered_threshold_promotion_uuid) REFERENCES tiered_threshold_promotions (uuid) ON DELETE CASCADE,
+  CONSTRAINT threshold_tiers_position_uniq UNIQUE (tiered_threshold_promotion_uuid, position)
+);
+
+ALTER TABLE threshold_tiers ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY threshold_tiers_tenant_select_policy ON threshold_tiers FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        tiered_threshold_promotions tt
+        JOIN promotions p ON p.uuid = tt.promotion_uuid
+      WHERE
+        tt.uuid = threshold_tiers.tiered_threshold_promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY threshold_tiers_tenant_insert_policy ON threshold_tiers FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        tiered_threshold_promotions tt
+        JOIN promotions p ON p.uuid = tt.promotion_uuid
+      WHERE
+        tt.uuid = threshold_tiers.tiered_threshold_promotion_uuid
+        AND p.promotionable_type = 'tiered_threshold'
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND p.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY threshold_tiers_tenant_delete_policy ON threshold_tiers FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      tiered_threshold_promotions tt
+      JOIN promotions p ON p.uuid = tt.promotion_uuid
+    WHERE
+      tt.uuid = threshold_tiers.tiered_threshold_promotion_uuid
+      AND p.promotionable_type = 'tiered_threshold'
+      AND p.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
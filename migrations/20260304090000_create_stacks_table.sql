@@ -0,0 +1,71 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TABLE stacks (
+  uuid UUID PRIMARY KEY,
+
+  tenant_uuid UUID NOT NULL DEFAULT NULLIF(
+    current_setting('app.current_tenant_uuid', TRUE),
+    ''
+  )::uuid,
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  deleted_at TIMESTAMPTZ,
+
+  CONSTRAINT stacks_tenant_uuid_uuid_uniq UNIQUE (tenant_uuid, uuid),
+  CONSTRAINT stacks_tenant_fk FOREIGN KEY (tenant_uuid) REFERENCES tenants (uuid) ON DELETE CASCADE
+);
+
+-- A tenant prices carts against a single promotion stack.
+CREATE UNIQUE INDEX stacks_tenant_current_idx ON stacks (tenant_uuid)
+WHERE
+  deleted_at IS NULL;
+
+CREATE POLICY stacks_tenant_select_policy ON stacks FOR
+SELECT
+  USING (
+    tenant_uuid = NULLIF(
+      current_setting('app.current_tenant_uuid', TRUE),
+      ''
+    )::uuid
+  );
+
+CREATE POLICY stacks_tenant_insert_policy ON stacks FOR INSERT
+WITH
+  CHECK (
+    tenant_uuid = NULLIF(
+      current_setting('app.current_tenant_uuid', TRUE),
+      ''
+    )::uuid
+  );
+
+CREATE POLICY stacks_tenant_update_policy ON stacks
+FOR UPDATE
+  USING (
+    tenant_uuid = NULLIF(
+      current_setting('app.current_tenant_uuid', TRUE),
+      ''
+    )::uuid
+  )
+WITH
+  CHECK (
+    tenant_uuid = NULLIF(
+      current_setting('app.current_tenant_uuid', TRUE),
+      ''
+    )::uuid
+  );
+
+CREATE POLICY stacks_tenant_delete_policy ON stacks FOR DELETE USING (
+  tenant_uuid = NULLIF(
+    current_setting('app.current_tenant_uuid', TRUE),
+    ''
+  )::uuid
+);
+
+ALTER TABLE stacks ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE TRIGGER stacks_set_updated_at BEFORE
+UPDATE ON stacks FOR EACH ROW
+EXECUTE FUNCTION set_updated_at ();
@@ -0,0 +1,134 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TABLE stack_versions (
+  uuid UUID PRIMARY KEY,
+  stack_uuid UUID NOT NULL,
+
+  root_layer TEXT NOT NULL,
+
+  valid_period TSTZRANGE NOT NULL DEFAULT tstzrange (now(), NULL, '[)'),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (NOT isempty(valid_period)),
+
+  CONSTRAINT stack_versions_stack_fk FOREIGN KEY (stack_uuid) REFERENCES stacks (uuid) ON DELETE CASCADE,
+  CONSTRAINT stack_versions_no_overlap_exclude EXCLUDE USING GIST (
+    stack_uuid
+    WITH
+      =,
+      valid_period
+    WITH
+      &&
+  ) DEFERRABLE
+);
+
+CREATE INDEX stack_versions_stack_uuid_idx ON stack_versions (stack_uuid);
+
+CREATE INDEX stack_versions_created_at_idx ON stack_versions (created_at);
+
+CREATE UNIQUE INDEX stack_versions_current_idx ON stack_versions (stack_uuid)
+WHERE
+  upper_inf(valid_period);
+
+CREATE POLICY stack_versions_tenant_select_policy ON stack_versions FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stacks s
+      WHERE
+        s.uuid = stack_versions.stack_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY stack_versions_tenant_insert_policy ON stack_versions FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stacks s
+      WHERE
+        s.uuid = stack_versions.stack_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND s.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY stack_versions_tenant_update_policy ON stack_versions
+FOR UPDATE
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stacks s
+      WHERE
+        s.uuid = stack_versions.stack_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND s.deleted_at IS NULL
+    )
+  )
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stacks s
+      WHERE
+        s.uuid = stack_versions.stack_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND s.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY stack_versions_tenant_delete_policy ON stack_versions FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      stacks s
+    WHERE
+      s.uuid = stack_versions.stack_uuid
+      AND s.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
+
+ALTER TABLE stack_versions ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE FUNCTION bump_stack_updated_at () RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    UPDATE stacks SET updated_at = now()
+    WHERE uuid = NEW.stack_uuid;
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER stack_versions_bump_updated_at
+AFTER INSERT
+OR
+UPDATE ON stack_versions FOR EACH ROW
+EXECUTE FUNCTION bump_stack_updated_at ();
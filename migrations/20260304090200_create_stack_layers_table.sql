@@ -0,0 +1,101 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TYPE STACK_LAYER_OUTPUT_MODE AS ENUM('pass_through', 'split');
+
+CREATE TABLE stack_layers (
+  uuid UUID PRIMARY KEY,
+  stack_version_uuid UUID NOT NULL,
+
+  position INTEGER NOT NULL CHECK (position >= 0),
+  name TEXT NOT NULL CHECK (name <> ''),
+
+  output_mode STACK_LAYER_OUTPUT_MODE NOT NULL,
+
+  next_layer TEXT,
+  participating_layer TEXT,
+  non_participating_layer TEXT,
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  CHECK (
+    (
+      output_mode = 'pass_through'
+      AND participating_layer IS NULL
+      AND non_participating_layer IS NULL
+    )
+    OR (
+      output_mode = 'split'
+      AND next_layer IS NULL
+    )
+  ),
+
+  CONSTRAINT stack_layers_stack_version_fk FOREIGN KEY (stack_version_uuid) REFERENCES stack_versions (uuid) ON DELETE CASCADE,
+  CONSTRAINT stack_layers_position_uniq UNIQUE (stack_version_uuid, position),
+  CONSTRAINT stack_layers_name_uniq UNIQUE (stack_version_uuid, name),
+
+  -- Layers route to siblings in the same version, which may be inserted later
+  -- in the same transaction.
+  CONSTRAINT stack_layers_next_layer_fk FOREIGN KEY (stack_version_uuid, next_layer) REFERENCES stack_layers (stack_version_uuid, name) DEFERRABLE INITIALLY DEFERRED,
+  CONSTRAINT stack_layers_participating_layer_fk FOREIGN KEY (stack_version_uuid, participating_layer) REFERENCES stack_layers (stack_version_uuid, name) DEFERRABLE INITIALLY DEFERRED,
+  CONSTRAINT stack_layers_non_participating_layer_fk FOREIGN KEY (stack_version_uuid, non_participating_layer) REFERENCES stack_layers (stack_version_uuid, name) DEFERRABLE INITIALLY DEFERRED
+);
+
+ALTER TABLE stack_versions
+ADD CONSTRAINT stack_versions_root_layer_fk FOREIGN KEY (uuid, root_layer) REFERENCES stack_layers (stack_version_uuid, name) DEFERRABLE INITIALLY DEFERRED;
+
+ALTER TABLE stack_layers ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY stack_layers_tenant_select_policy ON stack_layers FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stack_versions sv
+        JOIN stacks s ON s.uuid = sv.stack_uuid
+      WHERE
+        sv.uuid = stack_layers.stack_version_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY stack_layers_tenant_insert_policy ON stack_layers FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stack_versions sv
+        JOIN stacks s ON s.uuid = sv.stack_uuid
+      WHERE
+        sv.uuid = stack_layers.stack_version_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND s.deleted_at IS NULL
+    )
+  );
+
+CREATE POLICY stack_layers_tenant_delete_policy ON stack_layers FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      stack_versions sv
+      JOIN stacks s ON s.uuid = sv.stack_uuid
+    WHERE
+      sv.uuid = stack_layers.stack_version_uuid
+      AND s.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);
@@ -0,0 +1,90 @@
+SET
+  LOCAL lock_timeout = '5s';
+
+CREATE TABLE stack_layer_promotions (
+  stack_layer_uuid UUID NOT NULL,
+  promotion_uuid UUID NOT NULL,
+
+  position INTEGER NOT NULL CHECK (position >= 0),
+
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  PRIMARY KEY (stack_layer_uuid, promotion_uuid),
+
+  CONSTRAINT stack_layer_promotions_stack_layer_fk FOREIGN KEY (stack_layer_uuid) REFERENCES stack_layers (uuid) ON DELETE CASCADE,
+  CONSTRAINT stack_layer_promotions_promotion_fk FOREIGN KEY (promotion_uuid) REFERENCES promotions (uuid) ON DELETE CASCADE,
+  CONSTRAINT stack_layer_promotions_position_uniq UNIQUE (stack_layer_uuid, position)
+);
+
+CREATE INDEX stack_layer_promotions_promotion_uuid_idx ON stack_layer_promotions (promotion_uuid);
+
+ALTER TABLE stack_layer_promotions ENABLE ROW LEVEL SECURITY,
+FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY stack_layer_promotions_tenant_select_policy ON stack_layer_promotions FOR
+SELECT
+  USING (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stack_layers sl
+        JOIN stack_versions sv ON sv.uuid = sl.stack_version_uuid
+        JOIN stacks s ON s.uuid = sv.stack_uuid
+      WHERE
+        sl.uuid = stack_layer_promotions.stack_layer_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY stack_layer_promotions_tenant_insert_policy ON stack_layer_promotions FOR INSERT
+WITH
+  CHECK (
+    EXISTS (
+      SELECT
+        1
+      FROM
+        stack_layers sl
+        JOIN stack_versions sv ON sv.uuid = sl.stack_version_uuid
+        JOIN stacks s ON s.uuid = sv.stack_uuid
+      WHERE
+        sl.uuid = stack_layer_promotions.stack_layer_uuid
+        AND s.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+        AND s.deleted_at IS NULL
+    )
+    AND EXISTS (
+      SELECT
+        1
+      FROM
+        promotions p
+      WHERE
+        p.uuid = stack_layer_promotions.promotion_uuid
+        AND p.tenant_uuid = NULLIF(
+          current_setting('app.current_tenant_uuid', TRUE),
+          ''
+        )::uuid
+    )
+  );
+
+CREATE POLICY stack_layer_promotions_tenant_delete_policy ON stack_layer_promotions FOR DELETE USING (
+  EXISTS (
+    SELECT
+      1
+    FROM
+      stack_layers sl
+      JOIN stack_versions sv ON sv.uuid = sl.stack_version_uuid
+      JOIN stacks s ON s.uuid = sv.stack_uuid
+    WHERE
+      sl.uuid = stack_layer_promotions.stack_layer_uuid
+      AND s.tenant_uuid = NULLIF(
+        current_setting('app.current_tenant_uuid', TRUE),
+        ''
+      )::uuid
+  )
+);